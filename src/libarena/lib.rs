@@ -165,6 +165,15 @@ impl<T> TypedArena<T> {
         }
     }
 
+    /// Returns the number of bytes reserved by the arena's chunks, whether
+    /// or not they have been allocated into yet.
+    pub fn allocated_bytes(&self) -> usize {
+        let elem_size = mem::size_of::<T>();
+        self.chunks.borrow().iter().fold(0, |bytes, chunk| {
+            bytes + chunk.storage.cap() * elem_size
+        })
+    }
+
     /// Grows the arena.
     #[inline(never)]
     #[cold]
@@ -251,6 +260,7 @@ mod tests {
     use self::test::Bencher;
     use super::TypedArena;
     use std::cell::Cell;
+    use std::mem;
 
     #[allow(dead_code)]
     #[derive(Debug, Eq, PartialEq)]
@@ -349,6 +359,22 @@ mod tests {
         }
     }
 
+    #[test]
+    pub fn test_typed_arena_allocated_bytes() {
+        let arena = TypedArena::with_capacity(4);
+        assert_eq!(arena.allocated_bytes(), 4 * mem::size_of::<Point>());
+        for _ in 0..100 {
+            arena.alloc(Point { x: 1, y: 2, z: 3 });
+        }
+        assert!(arena.allocated_bytes() >= 100 * mem::size_of::<Point>());
+
+        let arena = TypedArena::new();
+        for _ in 0..100 {
+            arena.alloc(());
+        }
+        assert_eq!(arena.allocated_bytes(), 0);
+    }
+
     #[test]
     pub fn test_typed_arena_clear() {
         let mut arena = TypedArena::new();
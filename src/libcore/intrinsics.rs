@@ -305,6 +305,12 @@ extern "rust-intrinsic" {
     /// will trigger a compiler error.
     pub fn return_address() -> *const u8;
 
+    /// Returns the `(file, line, column)` of the call to the enclosing
+    /// `#[track_caller]` function, or of this call itself when the
+    /// enclosing function is not `#[track_caller]`.
+    #[cfg(not(stage0))]
+    pub fn caller_location() -> &'static (&'static str, u32, u32);
+
     /// Returns `true` if the actual type given as `T` requires drop
     /// glue; returns `false` if the actual type provided for `T`
     /// implements `Copy`.
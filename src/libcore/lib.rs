@@ -74,6 +74,7 @@
 #![feature(repr_simd, platform_intrinsics)]
 #![feature(staged_api)]
 #![feature(unboxed_closures)]
+#![cfg_attr(not(stage0), feature(track_caller))]
 
 #[macro_use]
 mod macros;
@@ -152,6 +152,7 @@ use iter::ExactSizeIterator;
 use iter::{Iterator, DoubleEndedIterator, FromIterator, IntoIterator};
 use mem;
 use ops::FnOnce;
+use panicking;
 use result::Result::{Ok, Err};
 use result::Result;
 
@@ -292,6 +293,7 @@ impl<T> Option<T> {
     /// ```
     #[inline]
     #[stable(feature = "rust1", since = "1.0.0")]
+    #[cfg_attr(not(stage0), track_caller)]
     pub fn expect(self, msg: &str) -> T {
         match self {
             Some(val) => val,
@@ -324,10 +326,11 @@ impl<T> Option<T> {
     /// ```
     #[inline]
     #[stable(feature = "rust1", since = "1.0.0")]
+    #[cfg_attr(not(stage0), track_caller)]
     pub fn unwrap(self) -> T {
         match self {
             Some(val) => val,
-            None => panic!("called `Option::unwrap()` on a `None` value"),
+            None => expect_failed("called `Option::unwrap()` on a `None` value"),
         }
     }
 
@@ -698,10 +701,12 @@ impl<T: Default> Option<T> {
 }
 
 // This is a separate function to reduce the code size of .expect() itself.
+// The panic points at whoever called `.expect()` or `.unwrap()`.
 #[inline(never)]
 #[cold]
+#[cfg_attr(not(stage0), track_caller)]
 fn expect_failed(msg: &str) -> ! {
-    panic!("{}", msg)
+    panicking::panic_at_caller(format_args!("{}", msg))
 }
 
 
@@ -899,12 +904,12 @@ impl<A, V: FromIterator<A>> FromIterator<Option<A>> for Option<V> {
         // FIXME(#11084): This could be replaced with Iterator::scan when this
         // performance bug is closed.
 
-        struct Adapter<Iter> {
-            iter: Iter,
+        struct Adapter<It> {
+            iter: It,
             found_none: bool,
         }
 
-        impl<T, Iter: Iterator<Item=Option<T>>> Iterator for Adapter<Iter> {
+        impl<T, It: Iterator<Item=Option<T>>> Iterator for Adapter<It> {
             type Item = T;
 
             #[inline]
@@ -35,6 +35,8 @@
             issue = "0")]
 
 use fmt;
+#[cfg(not(stage0))]
+use intrinsics;
 
 #[cold] #[inline(never)] // this is the slow path, always
 #[lang = "panic"]
@@ -57,6 +59,23 @@ fn panic_bounds_check(file_line: &(&'static str, u32),
                            len, index), file_line)
 }
 
+/// Panics with the location of the call to the enclosing `#[track_caller]`
+/// function, rather than that of the library code giving up.
+#[cfg(not(stage0))]
+#[cold] #[inline(never)]
+#[track_caller]
+pub fn panic_at_caller(fmt: fmt::Arguments) -> ! {
+    let &(file, line, _) = unsafe { intrinsics::caller_location() };
+    panic_fmt(fmt, &(file, line))
+}
+
+#[cfg(stage0)]
+#[cold] #[inline(never)]
+pub fn panic_at_caller(fmt: fmt::Arguments) -> ! {
+    static _FILE_LINE: (&'static str, u32) = (file!(), line!());
+    panic_fmt(fmt, &_FILE_LINE)
+}
+
 #[cold] #[inline(never)]
 pub fn panic_fmt(fmt: fmt::Arguments, file_line: &(&'static str, u32)) -> ! {
     #[allow(improper_ctypes)]
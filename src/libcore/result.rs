@@ -909,12 +909,12 @@ impl<A, E, V: FromIterator<A>> FromIterator<Result<A, E>> for Result<V, E> {
         // FIXME(#11084): This could be replaced with Iterator::scan when this
         // performance bug is closed.
 
-        struct Adapter<Iter, E> {
-            iter: Iter,
+        struct Adapter<It, E> {
+            iter: It,
             err: Option<E>,
         }
 
-        impl<T, E, Iter: Iterator<Item=Result<T, E>>> Iterator for Adapter<Iter, E> {
+        impl<T, E, It: Iterator<Item=Result<T, E>>> Iterator for Adapter<It, E> {
             type Item = T;
 
             #[inline]
@@ -25,7 +25,6 @@
 #![feature(float_extras)]
 #![feature(flt2dec)]
 #![feature(iter_arith)]
-#![feature(libc)]
 #![feature(nonzero)]
 #![feature(peekable_is_empty)]
 #![feature(ptr_as_ref)]
@@ -40,8 +39,6 @@
 
 extern crate core;
 extern crate test;
-extern crate libc;
-extern crate rustc_unicode;
 extern crate rand;
 
 mod any;
@@ -118,6 +118,10 @@ pub enum DepNode {
     TraitItems(DefId),
     ReprHints(DefId),
     TraitSelect(DefId),
+
+    // The predicates for builtin bounds like `T: Send + Sized`. These
+    // depend only on the language items, so a single node suffices.
+    BuiltinBoundPredicates,
 }
 
 #[derive(Clone)]
@@ -1571,6 +1571,12 @@ that the value provided is a positive integer between quotes, like so:
 ```
 #![recursion_limit="1000"]
 ```
+
+The value may also be a constant integer expression:
+
+```
+#![recursion_limit="4 * 256"]
+```
 "##,
 
 E0297: r##"
@@ -1962,6 +1968,28 @@ each method; it is not possible to annotate the entire impl with an `#[inline]`
 attribute.
 "##,
 
+E0531: r##"
+An `#[optimize(..)]` attribute was placed on something other than a function
+or method with a body.
+
+Examples of erroneous code:
+
+```compile_fail
+#![feature(optimize_attribute)]
+
+#[optimize(size)]
+struct Foo;
+
+trait Bar {
+    #[optimize(speed)]
+    fn bar(&self);
+}
+```
+
+`#[optimize]` changes how a single function is optimized, overriding the
+crate's `-C opt-level`, so it only makes sense on the definition of a function.
+"##,
+
 }
 
 
@@ -2007,4 +2035,8 @@ register_diagnostics! {
     E0490, // a value of type `..` is borrowed for too long
     E0491, // in type `..`, reference has a longer lifetime than the data it...
     E0495, // cannot infer an appropriate lifetime due to conflicting requirements
+    E0520, // item with an external symbol name is not marked `#[export]`
+    E0521, // generic functions cannot be marked `#[export]`
+    E0522, // only functions and statics can be marked `#[export]`
+    E0543, // const arguments are not allowed on this type
 }
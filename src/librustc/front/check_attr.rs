@@ -11,7 +11,7 @@
 use session::Session;
 
 use syntax::ast;
-use syntax::attr::AttrMetaMethods;
+use syntax::attr::{self, AttrMetaMethods};
 use syntax::visit;
 use syntax::visit::Visitor;
 
@@ -19,6 +19,7 @@ use syntax::visit::Visitor;
 enum Target {
     Fn,
     Struct,
+    Union,
     Enum,
     Other,
 }
@@ -28,6 +29,7 @@ impl Target {
         match item.node {
             ast::ItemKind::Fn(..) => Target::Fn,
             ast::ItemKind::Struct(..) => Target::Struct,
+            ast::ItemKind::Union(..) => Target::Union,
             ast::ItemKind::Enum(..) => Target::Enum,
             _ => Target::Other,
         }
@@ -45,6 +47,13 @@ impl<'a> CheckAttrVisitor<'a> {
         }
     }
 
+    fn check_optimize(&self, attr: &ast::Attribute, target: Target) {
+        if target != Target::Fn {
+            span_err!(self.sess, attr.span, E0531, "attribute should be applied to function");
+        }
+        attr::find_optimize_attr(Some(self.sess.diagnostic()), &[attr.clone()]);
+    }
+
     fn check_repr(&self, attr: &ast::Attribute, target: Target) {
         let words = match attr.meta_item_list() {
             Some(words) => words,
@@ -56,8 +65,9 @@ impl<'a> CheckAttrVisitor<'a> {
             let word: &str = &word.name();
             let message = match word {
                 "C" => {
-                    if target != Target::Struct && target != Target::Enum {
-                            "attribute should be applied to struct or enum"
+                    if target != Target::Struct && target != Target::Union &&
+                       target != Target::Enum {
+                            "attribute should be applied to struct, enum or union"
                     } else {
                         continue
                     }
@@ -89,6 +99,7 @@ impl<'a> CheckAttrVisitor<'a> {
         let name: &str = &attr.name();
         match name {
             "inline" => self.check_inline(attr, target),
+            "optimize" => self.check_optimize(attr, target),
             "repr" => self.check_repr(attr, target),
             _ => (),
         }
@@ -103,6 +114,30 @@ impl<'a, 'v> Visitor<'v> for CheckAttrVisitor<'a> {
         }
         visit::walk_item(self, item);
     }
+
+    // Only `#[optimize]` is checked on associated items, whose other
+    // attributes were never restricted.
+    fn visit_trait_item(&mut self, item: &ast::TraitItem) {
+        let target = match item.node {
+            ast::TraitItemKind::Method(_, Some(_)) => Target::Fn,
+            _ => Target::Other,
+        };
+        for attr in item.attrs.iter().filter(|a| a.check_name("optimize")) {
+            self.check_optimize(attr, target);
+        }
+        visit::walk_trait_item(self, item);
+    }
+
+    fn visit_impl_item(&mut self, item: &ast::ImplItem) {
+        let target = match item.node {
+            ast::ImplItemKind::Method(..) => Target::Fn,
+            _ => Target::Other,
+        };
+        for attr in item.attrs.iter().filter(|a| a.check_name("optimize")) {
+            self.check_optimize(attr, target);
+        }
+        visit::walk_impl_item(self, item);
+    }
 }
 
 pub fn check_crate(sess: &Session, krate: &ast::Crate) {
@@ -127,7 +127,8 @@ impl<'ast> Visitor<'ast> for NodeCollector<'ast> {
         // information we encapsulate into
         let def_data = match i.node {
             ItemDefaultImpl(..) | ItemImpl(..) => DefPathData::Impl(i.name),
-            ItemEnum(..) | ItemStruct(..) | ItemTrait(..) => DefPathData::Type(i.name),
+            ItemEnum(..) | ItemStruct(..) | ItemUnion(..) | ItemTrait(..) |
+            ItemTraitAlias(..) => DefPathData::Type(i.name),
             ItemExternCrate(..) | ItemMod(..) => DefPathData::Mod(i.name),
             ItemStatic(..) | ItemConst(..) | ItemFn(..) => DefPathData::Value(i.name),
             _ => DefPathData::Misc,
@@ -169,7 +170,13 @@ impl<'ast> Visitor<'ast> for NodeCollector<'ast> {
                     self.create_def(field.id, DefPathData::Field(field.name));
                 }
             }
-            ItemTrait(_, _, ref bounds, _) => {
+            ItemUnion(ref struct_def, _) => {
+                for field in struct_def.fields() {
+                    self.create_def(field.id, DefPathData::Field(field.name));
+                }
+            }
+            ItemTrait(_, _, ref bounds, _) |
+            ItemTraitAlias(_, ref bounds) => {
                 for b in bounds.iter() {
                     if let TraitTyParamBound(ref t, TraitBoundModifier::None) = *b {
                         self.insert(t.trait_ref.ref_id, NodeItem(i));
@@ -209,6 +216,9 @@ impl<'ast> Visitor<'ast> for NodeCollector<'ast> {
                             NodeTyParam(ty_param),
                             DefPathData::TypeParam(ty_param.name));
         }
+        for const_param in generics.const_params.iter() {
+            self.create_def(const_param.id, DefPathData::ConstParam(const_param.name));
+        }
 
         intravisit::walk_generics(self, generics);
     }
@@ -292,6 +302,21 @@ impl<'ast> Visitor<'ast> for NodeCollector<'ast> {
         self.parent_node = parent_node;
     }
 
+    fn visit_ty(&mut self, ty: &'ast Ty) {
+        if let TyImplTrait(..) = ty.node {
+            self.create_def(ty.id, DefPathData::ImplTrait);
+        }
+
+        intravisit::walk_ty(self, ty);
+    }
+
+    fn visit_path_parameters(&mut self, path_span: Span, parameters: &'ast PathParameters) {
+        if let AngleBracketedParameters(ref data) = *parameters {
+            walk_list!(self, visit_expr, &data.consts);
+        }
+        intravisit::walk_path_parameters(self, path_span, parameters);
+    }
+
     fn visit_stmt(&mut self, stmt: &'ast Stmt) {
         let id = util::stmt_id(stmt);
         self.insert(id, NodeStmt(stmt));
@@ -13,13 +13,23 @@ use middle::def_id::{DefId, DefIndex};
 use rustc_data_structures::fnv::FnvHashMap;
 use syntax::ast;
 use syntax::parse::token::InternedString;
-use util::nodemap::NodeMap;
 
 #[derive(Clone)]
 pub struct Definitions {
     data: Vec<DefData>,
     key_map: FnvHashMap<DefKey, DefIndex>,
-    node_map: NodeMap<DefIndex>,
+
+    // Dense `NodeId -> DefIndex` table, indexed by node-id. Node-ids are
+    // handed out sequentially, so this is much cheaper to query than a hash
+    // map in the inner loops of collect and typeck.
+    node_to_def_index: Vec<Option<DefIndex>>,
+
+    // `DUMMY_NODE_ID` is far outside the dense range, so it gets its own slot.
+    dummy_node_def_index: Option<DefIndex>,
+
+    // Dense `DefIndex -> NodeId` table, parallel to `data`, so the reverse
+    // translation doesn't have to touch the (much larger) `DefData`.
+    def_index_to_node: Vec<ast::NodeId>,
 }
 
 /// A unique identifier that we can use to lookup a definition
@@ -78,9 +88,11 @@ pub enum DefPathData {
     Value(ast::Name),
     MacroDef(ast::Name),
     ClosureExpr,
+    ImplTrait, // the hidden type behind an `impl Trait`
 
     // Subportions of items
     TypeParam(ast::Name),
+    ConstParam(ast::Name),
     LifetimeDef(ast::Name),
     EnumVariant(ast::Name),
     Field(ast::Name),
@@ -98,7 +110,9 @@ impl Definitions {
         Definitions {
             data: vec![],
             key_map: FnvHashMap(),
-            node_map: NodeMap(),
+            node_to_def_index: vec![],
+            dummy_node_def_index: None,
+            def_index_to_node: vec![],
         }
     }
 
@@ -120,7 +134,10 @@ impl Definitions {
     }
 
     pub fn opt_def_index(&self, node: ast::NodeId) -> Option<DefIndex> {
-        self.node_map.get(&node).cloned()
+        if node == ast::DUMMY_NODE_ID {
+            return self.dummy_node_def_index;
+        }
+        self.node_to_def_index.get(node as usize).and_then(|&index| index)
     }
 
     pub fn opt_local_def_id(&self, node: ast::NodeId) -> Option<DefId> {
@@ -130,7 +147,7 @@ impl Definitions {
     pub fn as_local_node_id(&self, def_id: DefId) -> Option<ast::NodeId> {
         if def_id.krate == LOCAL_CRATE {
             assert!(def_id.index.as_usize() < self.data.len());
-            Some(self.data[def_id.index.as_usize()].node_id)
+            Some(self.def_index_to_node[def_id.index.as_usize()])
         } else {
             None
         }
@@ -141,11 +158,13 @@ impl Definitions {
                                   node_id: ast::NodeId,
                                   data: DefPathData)
                                   -> DefIndex {
-        assert!(!self.node_map.contains_key(&node_id),
-                "adding a def'n for node-id {:?} and data {:?} but a previous def'n exists: {:?}",
-                node_id,
-                data,
-                self.data[self.node_map[&node_id].as_usize()]);
+        if let Some(prev) = self.opt_def_index(node_id) {
+            panic!("adding a def'n for node-id {:?} and data {:?} \
+                    but a previous def'n exists: {:?}",
+                   node_id,
+                   data,
+                   self.data[prev.as_usize()]);
+        }
 
         // Find a unique DefKey. This basically means incrementing the disambiguator
         // until we get no match.
@@ -164,7 +183,16 @@ impl Definitions {
         // Create the definition.
         let index = DefIndex::new(self.data.len());
         self.data.push(DefData { key: key.clone(), node_id: node_id });
-        self.node_map.insert(node_id, index);
+        self.def_index_to_node.push(node_id);
+        if node_id == ast::DUMMY_NODE_ID {
+            self.dummy_node_def_index = Some(index);
+        } else {
+            let node = node_id as usize;
+            if node >= self.node_to_def_index.len() {
+                self.node_to_def_index.resize(node + 1, None);
+            }
+            self.node_to_def_index[node] = Some(index);
+        }
         self.key_map.insert(key, index);
 
         index
@@ -181,6 +209,7 @@ impl DefPathData {
             Value(name) |
             MacroDef(name) |
             TypeParam(name) |
+            ConstParam(name) |
             LifetimeDef(name) |
             EnumVariant(name) |
             DetachedCrate(name) |
@@ -207,6 +236,10 @@ impl DefPathData {
                 InternedString::new("{{closure}}")
             }
 
+            ImplTrait => {
+                InternedString::new("{{impl-Trait}}")
+            }
+
             StructCtor => {
                 InternedString::new("{{constructor}}")
             }
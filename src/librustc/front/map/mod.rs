@@ -373,6 +373,29 @@ impl<'ast> Map<'ast> {
         self.definitions.borrow().as_local_node_id(def_id)
     }
 
+    /// Like `local_def_id`, but translates a whole batch of nodes while
+    /// borrowing the definitions table only once. Use this in loops over
+    /// fields, variants, generic parameters and the like.
+    pub fn local_def_ids<I>(&self, nodes: I) -> Vec<DefId>
+        where I: IntoIterator<Item=NodeId>
+    {
+        let definitions = self.definitions.borrow();
+        nodes.into_iter().map(|node| {
+            definitions.opt_local_def_id(node).unwrap_or_else(|| {
+                panic!("local_def_ids: no entry for `{}`, which has a map of `{:?}`",
+                       node, self.find_entry(node))
+            })
+        }).collect()
+    }
+
+    /// Batch version of `as_local_node_id`.
+    pub fn as_local_node_ids<I>(&self, def_ids: I) -> Vec<Option<NodeId>>
+        where I: IntoIterator<Item=DefId>
+    {
+        let definitions = self.definitions.borrow();
+        def_ids.into_iter().map(|def_id| definitions.as_local_node_id(def_id)).collect()
+    }
+
     fn entry_count(&self) -> usize {
         self.map.borrow().len()
     }
@@ -385,6 +408,12 @@ impl<'ast> Map<'ast> {
         self.forest.krate()
     }
 
+    /// If `id` was generated while lowering a desugared construct (e.g. a
+    /// `for` loop), the kind of that construct.
+    pub fn desugaring_kind(&self, id: NodeId) -> Option<DesugaringKind> {
+        self.forest.krate.desugaring_kind(id)
+    }
+
     /// Retrieve the Node corresponding to `id`, panicking if it cannot
     /// be found.
     pub fn get(&self, id: NodeId) -> Node<'ast> {
@@ -595,7 +624,8 @@ impl<'ast> Map<'ast> {
         match self.find(id) {
             Some(NodeItem(i)) => {
                 match i.node {
-                    ItemStruct(ref struct_def, _) => struct_def,
+                    ItemStruct(ref struct_def, _) |
+                    ItemUnion(ref struct_def, _) => struct_def,
                     _ => panic!("struct ID bound to non-struct")
                 }
             }
@@ -759,6 +789,7 @@ impl<'ast> Map<'ast> {
             Some(NodeBlock(block)) => block.span,
             Some(NodeStructCtor(_)) => self.expect_item(self.get_parent(id)).span,
             Some(NodeTyParam(ty_param)) => ty_param.span,
+            Some(NodeLifetime(lifetime)) => lifetime.span,
             _ => return None,
         };
         Some(sp)
@@ -1039,7 +1070,9 @@ fn node_id_to_string(map: &Map, id: NodeId, include_id: bool) -> String {
                 ItemTy(..) => "ty",
                 ItemEnum(..) => "enum",
                 ItemStruct(..) => "struct",
+                ItemUnion(..) => "union",
                 ItemTrait(..) => "trait",
+                ItemTraitAlias(..) => "trait alias",
                 ItemImpl(..) => "impl",
                 ItemDefaultImpl(..) => "default impl",
             };
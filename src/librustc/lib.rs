@@ -43,7 +43,6 @@
 
 extern crate arena;
 extern crate core;
-extern crate flate;
 extern crate fmt_macros;
 extern crate getopts;
 extern crate graphviz;
@@ -85,6 +84,7 @@ pub mod front {
 }
 
 pub mod middle {
+    pub mod analysis_pass;
     pub mod astconv_util;
     pub mod expr_use_visitor; // STAGE0: increase glitch immunity
     pub mod cfg;
@@ -111,6 +111,7 @@ pub mod middle {
     pub mod region;
     pub mod recursion_limit;
     pub mod resolve_lifetime;
+    pub mod rust_intrinsics;
     pub mod stability;
     pub mod subst;
     pub mod traits;
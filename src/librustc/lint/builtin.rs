@@ -30,10 +30,28 @@ declare_lint! {
 
 declare_lint! {
     pub UNUSED_EXTERN_CRATES,
-    Allow,
+    Warn,
     "extern crates that are never used"
 }
 
+declare_lint! {
+    pub UNUSED_MACRO_IMPORTS,
+    Warn,
+    "`#[macro_use]` imports from which no macro is ever used"
+}
+
+declare_lint! {
+    pub TYPE_PARAM_SHADOWING,
+    Warn,
+    "type parameters that shadow a type of the same name in scope"
+}
+
+declare_lint! {
+    pub UNUSED_LIFETIMES,
+    Warn,
+    "lifetime parameters that are never used"
+}
+
 declare_lint! {
     pub UNUSED_QUALIFICATIONS,
     Allow,
@@ -154,6 +172,18 @@ declare_lint! {
     "transmute from function item type to pointer-sized type erroneously allowed"
 }
 
+declare_lint! {
+    pub SHADOWED_ASSOCIATED_TYPES,
+    Warn,
+    "associated types that reuse the name of one declared in a supertrait"
+}
+
+declare_lint! {
+    pub MUT_BINDING_RESETS_DEFAULT_MODE,
+    Warn,
+    "`mut` bindings that move out of a value matched by reference instead of borrowing it"
+}
+
 /// Does nothing as a lint pass, but registers some `Lint`s
 /// which are used by other parts of the compiler.
 #[derive(Copy, Clone)]
@@ -164,6 +194,9 @@ impl LintPass for HardwiredLints {
         lint_array!(
             UNUSED_IMPORTS,
             UNUSED_EXTERN_CRATES,
+            UNUSED_MACRO_IMPORTS,
+            UNUSED_LIFETIMES,
+            TYPE_PARAM_SHADOWING,
             UNUSED_QUALIFICATIONS,
             UNKNOWN_LINTS,
             UNUSED_VARIABLES,
@@ -184,7 +217,9 @@ impl LintPass for HardwiredLints {
             MATCH_OF_UNIT_VARIANT_VIA_PAREN_DOTDOT,
             CONST_ERR,
             RAW_POINTER_DERIVE,
-            TRANSMUTE_FROM_FN_ITEM_TYPES
+            TRANSMUTE_FROM_FN_ITEM_TYPES,
+            SHADOWED_ASSOCIATED_TYPES,
+            MUT_BINDING_RESETS_DEFAULT_MODE
         )
     }
 }
@@ -257,7 +257,25 @@ impl LintStore {
         }
     }
 
+    /// Applies the default levels of the local crate's edition. Must run
+    /// before any other levels are set, as it overwrites them.
+    fn apply_edition_defaults(&mut self, sess: &Session) {
+        let edition = sess.edition();
+        for &(lint, _) in &self.lints {
+            if lint.edition_level.is_none() {
+                continue;
+            }
+            let id = LintId::of(lint);
+            match lint.default_level_for(edition) {
+                Allow => { self.levels.remove(&id); }
+                level => { self.levels.insert(id, (level, Default)); }
+            }
+        }
+    }
+
     pub fn process_command_line(&mut self, sess: &Session) {
+        self.apply_edition_defaults(sess);
+
         for &(ref lint_name, level) in &sess.opts.lint_opts {
             check_lint_name_cmdline(sess, self,
                                     &lint_name[..], level);
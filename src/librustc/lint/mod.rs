@@ -37,6 +37,7 @@ use syntax::codemap::Span;
 use rustc_front::intravisit::FnKind;
 use syntax::visit as ast_visit;
 use syntax::ast;
+use syntax::edition::Edition;
 use rustc_front::hir;
 
 pub use lint::context::{LateContext, EarlyContext, LintContext, LintStore,
@@ -65,6 +66,10 @@ pub struct Lint {
     ///
     /// e.g. "imports that are never used"
     pub desc: &'static str,
+
+    /// A stricter default level that applies to crates using the given
+    /// edition or a later one.
+    pub edition_level: Option<(Edition, Level)>,
 }
 
 impl Lint {
@@ -72,6 +77,14 @@ impl Lint {
     pub fn name_lower(&self) -> String {
         self.name.to_ascii_lowercase()
     }
+
+    /// The default level of the lint for a crate using `edition`.
+    pub fn default_level_for(&self, edition: Edition) -> Level {
+        match self.edition_level {
+            Some((since, level)) if edition >= since => level,
+            _ => self.default_level,
+        }
+    }
 }
 
 /// Build a `Lint` initializer.
@@ -82,20 +95,33 @@ macro_rules! lint_initializer {
             name: stringify!($name),
             default_level: ::rustc::lint::$level,
             desc: $desc,
+            edition_level: None,
         }
-    )
+    );
+    ($name:ident, $level:ident, $desc:expr, $edition:ident => $edition_level:ident) => (
+        ::rustc::lint::Lint {
+            name: stringify!($name),
+            default_level: ::rustc::lint::$level,
+            desc: $desc,
+            edition_level: Some((::syntax::edition::Edition::$edition,
+                                 ::rustc::lint::$edition_level)),
+        }
+    );
 }
 
 /// Declare a static item of type `&'static Lint`.
+///
+/// A lint may be made stricter for newer editions by adding e.g.
+/// `Edition2018 => Deny` after the description.
 #[macro_export]
 macro_rules! declare_lint {
-    (pub $name:ident, $level:ident, $desc:expr) => (
+    (pub $name:ident, $level:ident, $desc:expr $(, $edition:ident => $elevel:ident)*) => (
         pub static $name: &'static ::rustc::lint::Lint
-            = &lint_initializer!($name, $level, $desc);
+            = &lint_initializer!($name, $level, $desc $(, $edition => $elevel)*);
     );
-    ($name:ident, $level:ident, $desc:expr) => (
+    ($name:ident, $level:ident, $desc:expr $(, $edition:ident => $elevel:ident)*) => (
         static $name: &'static ::rustc::lint::Lint
-            = &lint_initializer!($name, $level, $desc);
+            = &lint_initializer!($name, $level, $desc $(, $edition => $elevel)*);
     );
 }
 
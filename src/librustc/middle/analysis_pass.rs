@@ -0,0 +1,58 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Custom analysis passes registered by a driver embedding rustc.
+//!
+//! These run at the end of `phase_3_run_analysis_passes`, once the crate has
+//! been type checked, borrow checked and linted, and before translation.
+//! They are run in the order they were registered, and any errors they
+//! report fail the compilation like those of the built-in passes.
+
+use middle::ty::{self, TyCtxt};
+use mir::mir_map::MirMap;
+
+/// A pass with access to the type context and the results of analysis.
+pub trait AnalysisPass<'tcx> {
+    /// A short name for the pass, used by `-Z time-passes`.
+    fn name(&self) -> &'static str {
+        "<unnamed analysis pass>"
+    }
+
+    fn run_pass(&mut self,
+                tcx: &TyCtxt<'tcx>,
+                mir_map: &MirMap<'tcx>,
+                analysis: &ty::CrateAnalysis);
+}
+
+/// The analysis passes registered with a session, in registration order.
+pub struct AnalysisPasses {
+    passes: Vec<Box<for<'tcx> AnalysisPass<'tcx>>>,
+}
+
+impl AnalysisPasses {
+    pub fn new() -> AnalysisPasses {
+        AnalysisPasses {
+            passes: Vec::new(),
+        }
+    }
+
+    /// Appends a pass; it will run after all those pushed before it.
+    pub fn push_pass(&mut self, pass: Box<for<'tcx> AnalysisPass<'tcx>>) {
+        self.passes.push(pass);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.passes.is_empty()
+    }
+
+    pub fn iter_mut(&mut self) -> ::std::slice::IterMut<Box<for<'tcx> AnalysisPass<'tcx>>> {
+        self.passes.iter_mut()
+    }
+}
@@ -36,6 +36,13 @@ pub fn prohibit_type_params(tcx: &TyCtxt, segments: &[ast::PathSegment]) {
             prohibit_projection(tcx, binding.span);
             break;
         }
+        if let ast::AngleBracketedParameters(ref data) = segment.parameters {
+            for c in data.consts.iter() {
+                span_err!(tcx.sess, c.span, E0543,
+                          "const arguments are not allowed on this type");
+                break;
+            }
+        }
     }
 }
 
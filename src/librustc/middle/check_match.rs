@@ -469,7 +469,8 @@ impl<'map> ast_util::IdVisitingOperation for RenamingRecorder<'map> {
 
 impl<'a, 'tcx> Folder for StaticInliner<'a, 'tcx> {
     fn fold_pat(&mut self, pat: P<Pat>) -> P<Pat> {
-        return match pat.node {
+        let pat_id = pat.id;
+        let pat = match pat.node {
             PatKind::Ident(..) | PatKind::Path(..) | PatKind::QPath(..) => {
                 let def = self.tcx.def_map.borrow().get(&pat.id).map(|d| d.full_def());
                 match def {
@@ -499,6 +500,7 @@ impl<'a, 'tcx> Folder for StaticInliner<'a, 'tcx> {
             }
             _ => noop_fold_pat(pat, self)
         };
+        return make_binding_modes_explicit(self.tcx, pat_id, pat);
 
         fn record_renamings(const_expr: &hir::Expr,
                             substituted_pat: &hir::Pat,
@@ -516,6 +518,45 @@ impl<'a, 'tcx> Folder for StaticInliner<'a, 'tcx> {
     }
 }
 
+/// Spells out what default binding modes left implicit in the pattern
+/// `pat_id`, now folded into `pat`: a binding gets the `ref` or `ref mut`
+/// it binds with, and every reference typeck dereferenced implicitly
+/// becomes an explicit `&` pattern. The new `&` patterns get fresh ids,
+/// with their types recorded, so that the rest of match checking and
+/// translation can treat them like any other pattern.
+fn make_binding_modes_explicit(tcx: &TyCtxt, pat_id: NodeId, pat: P<Pat>) -> P<Pat> {
+    let (mode, adjustments) = {
+        let tables = tcx.tables.borrow();
+        (tables.pat_binding_modes.get(&pat_id).cloned(),
+         tables.pat_adjustments(pat_id).to_vec())
+    };
+
+    let pat = match mode {
+        Some(mode) => pat.map(|Pat { id, node, span }| {
+            let node = match node {
+                PatKind::Ident(_, ident, sub) => PatKind::Ident(mode, ident, sub),
+                node => node,
+            };
+            Pat { id: id, node: node, span: span }
+        }),
+        None => pat,
+    };
+
+    adjustments.into_iter().rev().fold(pat, |inner, ref_ty| {
+        let mutbl = match ref_ty.sty {
+            ty::TyRef(_, mt) => mt.mutbl,
+            _ => tcx.sess.span_bug(inner.span, "implicitly dereferenced a non-reference"),
+        };
+        let id = tcx.sess.reserve_node_ids(1);
+        tcx.node_type_insert(id, ref_ty);
+        P(Pat {
+            id: id,
+            span: inner.span,
+            node: PatKind::Ref(inner, mutbl),
+        })
+    })
+}
+
 /// Constructs a partial witness for a pattern given a list of
 /// patterns expanded by the specialization step.
 ///
@@ -799,7 +840,7 @@ fn pat_constructors(cx: &MatchCheckCtxt, p: &Pat,
             vec!(ConstantRange(eval_const_expr(cx.tcx, &lo), eval_const_expr(cx.tcx, &hi))),
         PatKind::Vec(ref before, ref slice, ref after) =>
             match left_ty.sty {
-                ty::TyArray(_, _) => vec!(Single),
+                ty::TyArray(_, _) | ty::TyParamArray(_, _) => vec!(Single),
                 _                      => if slice.is_some() {
                     (before.len() + after.len()..max_slice_length+1)
                         .map(|length| Slice(length))
@@ -1065,8 +1106,8 @@ fn check_legality_of_move_bindings(cx: &MatchCheckCtxt,
     let def_map = &tcx.def_map;
     let mut by_ref_span = None;
     for pat in pats {
-        pat_bindings(def_map, &pat, |bm, _, span, _path| {
-            match bm {
+        pat_bindings(def_map, &pat, |bm, p_id, span, _path| {
+            match tcx.tables.borrow().binding_mode(p_id, bm) {
                 hir::BindByRef(_) => {
                     by_ref_span = Some(span);
                 }
@@ -1096,18 +1137,19 @@ fn check_legality_of_move_bindings(cx: &MatchCheckCtxt,
         front_util::walk_pat(&pat, |p| {
             if pat_is_binding(&def_map.borrow(), &p) {
                 match p.node {
-                    PatKind::Ident(hir::BindByValue(_), _, ref sub) => {
-                        let pat_ty = tcx.node_id_to_type(p.id);
-                        //FIXME: (@jroesch) this code should be floated up as well
-                        let infcx = infer::new_infer_ctxt(cx.tcx,
-                                                          &cx.tcx.tables,
-                                                          Some(cx.param_env.clone()));
-                        if infcx.type_moves_by_default(pat_ty, pat.span) {
-                            check_move(p, sub.as_ref().map(|p| &**p));
+                    PatKind::Ident(bm, _, ref sub) => {
+                        let bm = tcx.tables.borrow().binding_mode(p.id, bm);
+                        if let hir::BindByValue(_) = bm {
+                            let pat_ty = tcx.node_id_to_type(p.id);
+                            //FIXME: (@jroesch) this code should be floated up as well
+                            let infcx = infer::new_infer_ctxt(cx.tcx,
+                                                              &cx.tcx.tables,
+                                                              Some(cx.param_env.clone()));
+                            if infcx.type_moves_by_default(pat_ty, pat.span) {
+                                check_move(p, sub.as_ref().map(|p| &**p));
+                            }
                         }
                     }
-                    PatKind::Ident(hir::BindByRef(_), _, _) => {
-                    }
                     _ => {
                         cx.tcx.sess.span_bug(
                             p.span,
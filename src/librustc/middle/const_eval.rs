@@ -18,14 +18,14 @@ use front::map as ast_map;
 use front::map::blocks::FnLikeNode;
 use middle::cstore::{self, CrateStore, InlinedItem};
 use middle::{infer, subst, traits};
-use middle::def::Def;
+use middle::def::{Def, DefMap, PathResolution};
 use middle::subst::Subst;
 use middle::def_id::DefId;
 use middle::pat_util::def_to_path;
-use middle::ty::{self, Ty, TyCtxt};
+use middle::ty::{self, TraitMap, Ty, TyCtxt};
 use middle::astconv_util::ast_ty_to_prim_ty;
 use util::num::ToPrimitive;
-use util::nodemap::NodeMap;
+use util::nodemap::{DefIdMap, NodeMap};
 use session::Session;
 
 use graphviz::IntoCow;
@@ -33,6 +33,8 @@ use syntax::ast;
 use rustc_front::hir::{Expr, PatKind};
 use rustc_front::hir;
 use rustc_front::intravisit::FnKind;
+use rustc_front::print::pprust;
+use rustc_front::util as hir_util;
 use syntax::codemap::Span;
 use syntax::parse::token::InternedString;
 use syntax::ptr::P;
@@ -245,6 +247,154 @@ pub fn lookup_const_fn_by_id<'tcx>(tcx: &TyCtxt<'tcx>, def_id: DefId)
     }
 }
 
+/// Resolves paths like `Foo::BAR`, which name resolution only resolves up
+/// to `Foo`, to an associated const or const method of a local struct or
+/// enum `Foo`, for use before type checking has resolved them for good.
+/// The impls of each such type and the traits in scope at each such path
+/// are gathered once, after name resolution.
+pub struct TypeRelativeItems {
+    impls: DefIdMap<Vec<ast::NodeId>>,
+    traits_in_scope: TraitMap,
+}
+
+impl TypeRelativeItems {
+    pub fn new(ast_map: &ast_map::Map, def_map: &DefMap, trait_map: &TraitMap)
+               -> TypeRelativeItems {
+        let mut impls = DefIdMap();
+        for item in ast_map.krate().items.values() {
+            if let hir::ItemImpl(_, _, _, _, ref self_ty, _) = item.node {
+                match def_map.get(&self_ty.id) {
+                    Some(&PathResolution { base_def: Def::Struct(def_id), depth: 0 }) |
+                    Some(&PathResolution { base_def: Def::Enum(def_id), depth: 0 }) => {
+                        impls.entry(def_id).or_insert(vec![]).push(item.id);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        let traits_in_scope = trait_map.iter().filter(|&(id, _)| {
+            def_map.get(id).map_or(false, |resolution| resolution.depth != 0)
+        }).map(|(&id, traits)| (id, traits.clone())).collect();
+        TypeRelativeItems {
+            impls: impls,
+            traits_in_scope: traits_in_scope,
+        }
+    }
+
+    /// Resolves the path of expression `expr_id`. The items of inherent
+    /// impls are preferred; otherwise the item must be the only one of that
+    /// name among the impls of traits in scope, where a trait's default
+    /// const stands in for an impl that does not define it. Impls whose self
+    /// type does not match type arguments given in the path are skipped.
+    pub fn resolve(&self,
+                   ast_map: &ast_map::Map,
+                   def_map: &DefMap,
+                   expr_id: ast::NodeId,
+                   path: &hir::Path,
+                   resolution: PathResolution)
+                   -> Option<Def> {
+        let self_def_id = match resolution.base_def {
+            Def::Struct(def_id) | Def::Enum(def_id) if resolution.depth == 1 => def_id,
+            _ => return None,
+        };
+        let impl_ids = match self.impls.get(&self_def_id) {
+            Some(impl_ids) => impl_ids,
+            None => return None,
+        };
+        let name = path.segments.last().unwrap().identifier.name;
+        let self_args = path.segments[path.segments.len() - 2].parameters.types();
+        let traits_in_scope = self.traits_in_scope.get(&expr_id).map_or(&[][..], |t| &t[..]);
+
+        let mut inherent = vec![];
+        let mut from_traits = vec![];
+        for &impl_id in impl_ids {
+            let (opt_trait_ref, self_ty, impl_items) = match ast_map.expect_item(impl_id).node {
+                hir::ItemImpl(_, _, _, ref opt_trait_ref, ref self_ty, ref impl_items) => {
+                    (opt_trait_ref, self_ty, impl_items)
+                }
+                _ => continue,
+            };
+            if !self_args_match(def_map, self_ty, &self_args) {
+                continue;
+            }
+            let defined = impl_items.iter().filter(|ii| ii.name == name).filter_map(|ii| {
+                let def_id = ast_map.local_def_id(ii.id);
+                match ii.node {
+                    hir::ImplItemKind::Const(..) => Some(Def::AssociatedConst(def_id)),
+                    hir::ImplItemKind::Method(ref sig, _)
+                        if sig.constness == hir::Constness::Const => Some(Def::Method(def_id)),
+                    _ => None,
+                }
+            }).next();
+            let trait_ref = match *opt_trait_ref {
+                None => {
+                    inherent.extend(defined);
+                    continue;
+                }
+                Some(ref trait_ref) => trait_ref,
+            };
+            let trait_def_id = match def_map.get(&trait_ref.ref_id) {
+                Some(resolution) => resolution.full_def().def_id(),
+                None => continue,
+            };
+            if !traits_in_scope.contains(&trait_def_id) {
+                continue;
+            }
+            match defined {
+                Some(def) => from_traits.push(def),
+                None => from_traits.extend(trait_default_const(ast_map, trait_def_id, name)),
+            }
+        }
+        match (&inherent[..], &from_traits[..]) {
+            (&[def], _) | (&[], &[def]) => Some(def),
+            _ => None,
+        }
+    }
+}
+
+/// Whether an impl for `self_ty` may apply to a path giving `self_args` as
+/// the type arguments of its self type. Arguments the impl leaves generic
+/// match anything, as does a path that gives none.
+fn self_args_match(def_map: &DefMap, self_ty: &hir::Ty, self_args: &[&P<hir::Ty>]) -> bool {
+    if self_args.is_empty() {
+        return true;
+    }
+    let impl_args = match self_ty.node {
+        hir::TyPath(None, ref path) => path.segments.last().unwrap().parameters.types(),
+        _ => return true,
+    };
+    impl_args.len() != self_args.len() ||
+        impl_args.iter().zip(self_args).all(|(impl_arg, self_arg)| {
+            let impl_def = def_map.get(&impl_arg.id).map(|resolution| resolution.full_def());
+            let self_def = def_map.get(&self_arg.id).map(|resolution| resolution.full_def());
+            match (impl_def, self_def) {
+                (Some(Def::TyParam(..)), _) | (None, _) | (_, None) => true,
+                (Some(impl_def), Some(self_def)) => impl_def == self_def,
+            }
+        })
+}
+
+/// The associated const `name` of the local trait `trait_def_id`, if it has
+/// a default value.
+fn trait_default_const(ast_map: &ast_map::Map, trait_def_id: DefId, name: ast::Name)
+                       -> Option<Def> {
+    let trait_id = match ast_map.as_local_node_id(trait_def_id) {
+        Some(trait_id) => trait_id,
+        None => return None,
+    };
+    match ast_map.expect_item(trait_id).node {
+        hir::ItemTrait(_, _, _, ref trait_items) => {
+            trait_items.iter().filter(|ti| ti.name == name).filter_map(|ti| match ti.node {
+                hir::ConstTraitItem(_, Some(_)) => {
+                    Some(Def::AssociatedConst(ast_map.local_def_id(ti.id)))
+                }
+                _ => None,
+            }).next()
+        }
+        _ => None,
+    }
+}
+
 #[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
 pub enum ConstVal {
     Float(f64),
@@ -395,6 +545,71 @@ pub type FnArgMap<'a> = Option<&'a NodeMap<ConstVal>>;
 pub struct ConstEvalErr {
     pub span: Span,
     pub kind: ErrKind,
+    /// The evaluation steps that led to the error, innermost first.
+    pub trace: Vec<ConstEvalStep>,
+}
+
+/// One step on the way from the expression being evaluated to the
+/// sub-expression that failed.
+#[derive(Clone)]
+pub struct ConstEvalStep {
+    pub span: Span,
+    pub kind: StepKind,
+}
+
+#[derive(Clone)]
+pub enum StepKind {
+    /// The left operand of a binary operator.
+    LeftOperand(hir::BinOp_),
+    /// The right operand of a binary operator.
+    RightOperand(hir::BinOp_),
+    /// The operand of `-` or `!`.
+    UnaryOperand(hir::UnOp),
+    /// The operand of a cast.
+    CastOperand,
+    /// The value of the constant with the given path.
+    Constant(String),
+    /// An argument to the const fn with the given path.
+    Argument(String, usize),
+    /// The body of the const fn with the given path.
+    ConstFnCall(String),
+}
+
+impl ConstEvalStep {
+    pub fn description(&self) -> String {
+        match self.kind {
+            StepKind::LeftOperand(op) => {
+                format!("while evaluating the left operand of `{}`",
+                        hir_util::binop_to_string(op))
+            }
+            StepKind::RightOperand(op) => {
+                format!("while evaluating the right operand of `{}`",
+                        hir_util::binop_to_string(op))
+            }
+            StepKind::UnaryOperand(op) => {
+                format!("while evaluating the operand of `{}`", hir_util::unop_to_string(op))
+            }
+            StepKind::CastOperand => "while evaluating the operand of this cast".to_string(),
+            StepKind::Constant(ref path) => {
+                format!("while evaluating the value of the constant `{}`", path)
+            }
+            StepKind::Argument(ref path, i) => {
+                format!("while evaluating argument {} in this call to `{}`", i + 1, path)
+            }
+            StepKind::ConstFnCall(ref path) => {
+                format!("while evaluating this call to const fn `{}`", path)
+            }
+        }
+    }
+}
+
+/// Records on the error returned by `result` that it came out of `step`.
+fn in_step<T>(result: Result<T, ConstEvalErr>, span: Span, kind: StepKind)
+              -> Result<T, ConstEvalErr> {
+    result.map_err(|mut err| {
+        err.trace.push(ConstEvalStep { span: span, kind: kind });
+        err
+    })
 }
 
 #[derive(Clone)]
@@ -577,7 +792,13 @@ impl UintTy {
 
 macro_rules! signal {
     ($e:expr, $exn:expr) => {
-        return Err(ConstEvalErr { span: $e.span, kind: $exn })
+        signal_at!($e.span, $exn)
+    }
+}
+
+macro_rules! signal_at {
+    ($span:expr, $exn:expr) => {
+        return Err(ConstEvalErr { span: $span, kind: $exn, trace: vec![] })
     }
 }
 
@@ -586,8 +807,8 @@ macro_rules! signal {
 // evaluation.
 //
 // They all take the operator's arguments (`a` and `b` if binary), the
-// overall expression (`e`) and, if available, whole expression's
-// concrete type (`opt_ety`).
+// span of the overall expression (`span`) and, if available, whole
+// expression's concrete type (`opt_ety`).
 //
 // If the whole expression's concrete type is None, then this is a
 // constant evaluation happening before type check (e.g. in the check
@@ -597,8 +818,8 @@ macro_rules! signal {
 // passes will do it again with the type information, and thus do the
 // overflow checks then.
 
-pub fn const_int_checked_neg<'a>(
-    a: i64, e: &'a Expr, opt_ety: Option<IntTy>) -> EvalResult {
+pub fn const_int_checked_neg(
+    a: i64, span: Span, opt_ety: Option<IntTy>) -> EvalResult {
 
     let (min,max) = match opt_ety {
         // (-i8::MIN is itself not an i8, etc, but this is an easy way
@@ -612,19 +833,19 @@ pub fn const_int_checked_neg<'a>(
 
     let oflo = a < min || a > max;
     if oflo {
-        signal!(e, NegateWithOverflow(a));
+        signal_at!(span, NegateWithOverflow(a));
     } else {
         Ok(Int(-a))
     }
 }
 
-pub fn const_uint_checked_neg<'a>(
-    a: u64, _e: &'a Expr, _opt_ety: Option<UintTy>) -> EvalResult {
+pub fn const_uint_checked_neg(
+    a: u64, _span: Span, _opt_ety: Option<UintTy>) -> EvalResult {
     // This always succeeds, and by definition, returns `(!a)+1`.
     Ok(Uint((!a).wrapping_add(1)))
 }
 
-fn const_uint_not(a: u64, opt_ety: Option<UintTy>) -> ConstVal {
+pub fn const_uint_not(a: u64, opt_ety: Option<UintTy>) -> ConstVal {
     let mask = match opt_ety {
         Some(UintTy::U8) => u8::MAX as u64,
         Some(UintTy::U16) => u16::MAX as u64,
@@ -712,12 +933,12 @@ macro_rules! pub_fn_checked_op {
         $ret_oflo_body:ident $overflowing_op:ident
             $const_ty:ident $signal_exn:expr
     }} => {
-        pub fn $fn_name<'a>($a: $a_ty,
-                            $b: $b_ty,
-                            e: &'a Expr,
-                            opt_ety: Option<$WhichTy>) -> EvalResult {
+        pub fn $fn_name($a: $a_ty,
+                        $b: $b_ty,
+                        span: Span,
+                        opt_ety: Option<$WhichTy>) -> EvalResult {
             let (ret, oflo) = $ret_oflo_body!($a, $b, opt_ety, $overflowing_op);
-            if !oflo { Ok($const_ty(ret)) } else { signal!(e, $signal_exn) }
+            if !oflo { Ok($const_ty(ret)) } else { signal_at!(span, $signal_exn) }
         }
     }
 }
@@ -734,18 +955,18 @@ pub_fn_checked_op!{ const_int_checked_mul(a: i64, b: i64,.. IntTy) {
            int_arith_body overflowing_mul Int MuliWithOverflow(a, b)
 }}
 
-pub fn const_int_checked_div<'a>(
-    a: i64, b: i64, e: &'a Expr, opt_ety: Option<IntTy>) -> EvalResult {
-    if b == 0 { signal!(e, DivideByZero); }
+pub fn const_int_checked_div(
+    a: i64, b: i64, span: Span, opt_ety: Option<IntTy>) -> EvalResult {
+    if b == 0 { signal_at!(span, DivideByZero); }
     let (ret, oflo) = int_arith_body!(a, b, opt_ety, overflowing_div);
-    if !oflo { Ok(Int(ret)) } else { signal!(e, DivideWithOverflow) }
+    if !oflo { Ok(Int(ret)) } else { signal_at!(span, DivideWithOverflow) }
 }
 
-pub fn const_int_checked_rem<'a>(
-    a: i64, b: i64, e: &'a Expr, opt_ety: Option<IntTy>) -> EvalResult {
-    if b == 0 { signal!(e, ModuloByZero); }
+pub fn const_int_checked_rem(
+    a: i64, b: i64, span: Span, opt_ety: Option<IntTy>) -> EvalResult {
+    if b == 0 { signal_at!(span, ModuloByZero); }
     let (ret, oflo) = int_arith_body!(a, b, opt_ety, overflowing_rem);
-    if !oflo { Ok(Int(ret)) } else { signal!(e, ModuloWithOverflow) }
+    if !oflo { Ok(Int(ret)) } else { signal_at!(span, ModuloWithOverflow) }
 }
 
 pub_fn_checked_op!{ const_int_checked_shl(a: i64, b: i64,.. IntTy) {
@@ -776,18 +997,18 @@ pub_fn_checked_op!{ const_uint_checked_mul(a: u64, b: u64,.. UintTy) {
            uint_arith_body overflowing_mul Uint MuluWithOverflow(a, b)
 }}
 
-pub fn const_uint_checked_div<'a>(
-    a: u64, b: u64, e: &'a Expr, opt_ety: Option<UintTy>) -> EvalResult {
-    if b == 0 { signal!(e, DivideByZero); }
+pub fn const_uint_checked_div(
+    a: u64, b: u64, span: Span, opt_ety: Option<UintTy>) -> EvalResult {
+    if b == 0 { signal_at!(span, DivideByZero); }
     let (ret, oflo) = uint_arith_body!(a, b, opt_ety, overflowing_div);
-    if !oflo { Ok(Uint(ret)) } else { signal!(e, DivideWithOverflow) }
+    if !oflo { Ok(Uint(ret)) } else { signal_at!(span, DivideWithOverflow) }
 }
 
-pub fn const_uint_checked_rem<'a>(
-    a: u64, b: u64, e: &'a Expr, opt_ety: Option<UintTy>) -> EvalResult {
-    if b == 0 { signal!(e, ModuloByZero); }
+pub fn const_uint_checked_rem(
+    a: u64, b: u64, span: Span, opt_ety: Option<UintTy>) -> EvalResult {
+    if b == 0 { signal_at!(span, ModuloByZero); }
     let (ret, oflo) = uint_arith_body!(a, b, opt_ety, overflowing_rem);
-    if !oflo { Ok(Uint(ret)) } else { signal!(e, ModuloWithOverflow) }
+    if !oflo { Ok(Uint(ret)) } else { signal_at!(span, ModuloWithOverflow) }
 }
 
 pub_fn_checked_op!{ const_uint_checked_shl(a: u64, b: u64,.. UintTy) {
@@ -848,17 +1069,19 @@ pub fn eval_const_expr_partial<'tcx>(tcx: &TyCtxt<'tcx>,
 
     let result = match e.node {
       hir::ExprUnary(hir::UnNeg, ref inner) => {
-        match try!(eval_const_expr_partial(tcx, &inner, ty_hint, fn_args)) {
+        match try!(in_step(eval_const_expr_partial(tcx, &inner, ty_hint, fn_args),
+                           e.span, StepKind::UnaryOperand(hir::UnNeg))) {
           Float(f) => Float(-f),
-          Int(n) =>  try!(const_int_checked_neg(n, e, expr_int_type)),
+          Int(n) =>  try!(const_int_checked_neg(n, e.span, expr_int_type)),
           Uint(i) => {
-              try!(const_uint_checked_neg(i, e, expr_uint_type))
+              try!(const_uint_checked_neg(i, e.span, expr_uint_type))
           }
           const_val => signal!(e, NegateOn(const_val)),
         }
       }
       hir::ExprUnary(hir::UnNot, ref inner) => {
-        match try!(eval_const_expr_partial(tcx, &inner, ty_hint, fn_args)) {
+        match try!(in_step(eval_const_expr_partial(tcx, &inner, ty_hint, fn_args),
+                           e.span, StepKind::UnaryOperand(hir::UnNot))) {
           Int(i) => Int(!i),
           Uint(i) => const_uint_not(i, expr_uint_type),
           Bool(b) => Bool(!b),
@@ -870,8 +1093,10 @@ pub fn eval_const_expr_partial<'tcx>(tcx: &TyCtxt<'tcx>,
             hir::BiShl | hir::BiShr => ty_hint.checked_or(tcx.types.usize),
             _ => ty_hint
         };
-        match (try!(eval_const_expr_partial(tcx, &a, ty_hint, fn_args)),
-               try!(eval_const_expr_partial(tcx, &b, b_ty, fn_args))) {
+        match (try!(in_step(eval_const_expr_partial(tcx, &a, ty_hint, fn_args),
+                            e.span, StepKind::LeftOperand(op.node))),
+               try!(in_step(eval_const_expr_partial(tcx, &b, b_ty, fn_args),
+                            e.span, StepKind::RightOperand(op.node)))) {
           (Float(a), Float(b)) => {
             match op.node {
               hir::BiAdd => Float(a + b),
@@ -890,16 +1115,16 @@ pub fn eval_const_expr_partial<'tcx>(tcx: &TyCtxt<'tcx>,
           }
           (Int(a), Int(b)) => {
             match op.node {
-              hir::BiAdd => try!(const_int_checked_add(a,b,e,expr_int_type)),
-              hir::BiSub => try!(const_int_checked_sub(a,b,e,expr_int_type)),
-              hir::BiMul => try!(const_int_checked_mul(a,b,e,expr_int_type)),
-              hir::BiDiv => try!(const_int_checked_div(a,b,e,expr_int_type)),
-              hir::BiRem => try!(const_int_checked_rem(a,b,e,expr_int_type)),
+              hir::BiAdd => try!(const_int_checked_add(a,b,e.span,expr_int_type)),
+              hir::BiSub => try!(const_int_checked_sub(a,b,e.span,expr_int_type)),
+              hir::BiMul => try!(const_int_checked_mul(a,b,e.span,expr_int_type)),
+              hir::BiDiv => try!(const_int_checked_div(a,b,e.span,expr_int_type)),
+              hir::BiRem => try!(const_int_checked_rem(a,b,e.span,expr_int_type)),
               hir::BiBitAnd => Int(a & b),
               hir::BiBitOr => Int(a | b),
               hir::BiBitXor => Int(a ^ b),
-              hir::BiShl => try!(const_int_checked_shl(a,b,e,expr_int_type)),
-              hir::BiShr => try!(const_int_checked_shr(a,b,e,expr_int_type)),
+              hir::BiShl => try!(const_int_checked_shl(a,b,e.span,expr_int_type)),
+              hir::BiShr => try!(const_int_checked_shr(a,b,e.span,expr_int_type)),
               hir::BiEq => Bool(a == b),
               hir::BiLt => Bool(a < b),
               hir::BiLe => Bool(a <= b),
@@ -911,16 +1136,16 @@ pub fn eval_const_expr_partial<'tcx>(tcx: &TyCtxt<'tcx>,
           }
           (Uint(a), Uint(b)) => {
             match op.node {
-              hir::BiAdd => try!(const_uint_checked_add(a,b,e,expr_uint_type)),
-              hir::BiSub => try!(const_uint_checked_sub(a,b,e,expr_uint_type)),
-              hir::BiMul => try!(const_uint_checked_mul(a,b,e,expr_uint_type)),
-              hir::BiDiv => try!(const_uint_checked_div(a,b,e,expr_uint_type)),
-              hir::BiRem => try!(const_uint_checked_rem(a,b,e,expr_uint_type)),
+              hir::BiAdd => try!(const_uint_checked_add(a,b,e.span,expr_uint_type)),
+              hir::BiSub => try!(const_uint_checked_sub(a,b,e.span,expr_uint_type)),
+              hir::BiMul => try!(const_uint_checked_mul(a,b,e.span,expr_uint_type)),
+              hir::BiDiv => try!(const_uint_checked_div(a,b,e.span,expr_uint_type)),
+              hir::BiRem => try!(const_uint_checked_rem(a,b,e.span,expr_uint_type)),
               hir::BiBitAnd => Uint(a & b),
               hir::BiBitOr => Uint(a | b),
               hir::BiBitXor => Uint(a ^ b),
-              hir::BiShl => try!(const_uint_checked_shl(a,b,e,expr_uint_type)),
-              hir::BiShr => try!(const_uint_checked_shr(a,b,e,expr_uint_type)),
+              hir::BiShl => try!(const_uint_checked_shl(a,b,e.span,expr_uint_type)),
+              hir::BiShr => try!(const_uint_checked_shr(a,b,e.span,expr_uint_type)),
               hir::BiEq => Bool(a == b),
               hir::BiLt => Bool(a < b),
               hir::BiLe => Bool(a <= b),
@@ -933,15 +1158,15 @@ pub fn eval_const_expr_partial<'tcx>(tcx: &TyCtxt<'tcx>,
           // shifts can have any integral type as their rhs
           (Int(a), Uint(b)) => {
             match op.node {
-              hir::BiShl => try!(const_int_checked_shl_via_uint(a,b,e,expr_int_type)),
-              hir::BiShr => try!(const_int_checked_shr_via_uint(a,b,e,expr_int_type)),
+              hir::BiShl => try!(const_int_checked_shl_via_uint(a,b,e.span,expr_int_type)),
+              hir::BiShr => try!(const_int_checked_shr_via_uint(a,b,e.span,expr_int_type)),
               _ => signal!(e, InvalidOpForIntUint(op.node)),
             }
           }
           (Uint(a), Int(b)) => {
             match op.node {
-              hir::BiShl => try!(const_uint_checked_shl_via_int(a,b,e,expr_uint_type)),
-              hir::BiShr => try!(const_uint_checked_shr_via_int(a,b,e,expr_uint_type)),
+              hir::BiShl => try!(const_uint_checked_shl_via_int(a,b,e.span,expr_uint_type)),
+              hir::BiShr => try!(const_uint_checked_shr_via_int(a,b,e.span,expr_uint_type)),
               _ => signal!(e, InvalidOpForUintInt(op.node)),
             }
           }
@@ -980,24 +1205,32 @@ pub fn eval_const_expr_partial<'tcx>(tcx: &TyCtxt<'tcx>,
             }
         };
 
-        let val = try!(eval_const_expr_partial(tcx, &base, base_hint, fn_args));
+        let val = try!(in_step(eval_const_expr_partial(tcx, &base, base_hint, fn_args),
+                               e.span, StepKind::CastOperand));
         match cast_const(tcx, val, ety) {
             Ok(val) => val,
-            Err(kind) => return Err(ConstEvalErr { span: e.span, kind: kind }),
+            Err(kind) => return Err(ConstEvalErr { span: e.span, kind: kind, trace: vec![] }),
         }
       }
-      hir::ExprPath(..) => {
-          let opt_def = if let Some(def) = tcx.def_map.borrow().get(&e.id) {
+      hir::ExprPath(_, ref path) => {
+          let resolution = tcx.def_map.borrow().get(&e.id).cloned();
+          let type_relative = resolution.map_or(false, |resolution| resolution.depth != 0);
+          let opt_def = if let Some(resolution) = resolution {
               // After type-checking, def_map contains definition of the
               // item referred to by the path. During type-checking, it
               // can contain the raw output of path resolution, which
-              // might be a partially resolved path.
-              // FIXME: There's probably a better way to make sure we don't
-              // panic here.
-              if def.depth != 0 {
-                  signal!(e, UnresolvedPath);
+              // might be a partially resolved path, in which case we try
+              // to find the associated const or const fn it refers to.
+              if resolution.depth != 0 {
+                  let def = tcx.type_relative_items.resolve(&tcx.map, &tcx.def_map.borrow(),
+                                                            e.id, path, resolution);
+                  match def {
+                      Some(def) => Some(def),
+                      None => signal!(e, UnresolvedPath),
+                  }
+              } else {
+                  Some(resolution.full_def())
               }
-              Some(def.full_def())
           } else {
               None
           };
@@ -1022,7 +1255,7 @@ pub fn eval_const_expr_partial<'tcx>(tcx: &TyCtxt<'tcx>,
                       match impl_or_trait_container(tcx, def_id) {
                           ty::TraitContainer(trait_id) => match tcx.map.find(node_id) {
                               Some(ast_map::NodeTraitItem(ti)) => match ti.node {
-                                  hir::ConstTraitItem(ref ty, _) => {
+                                  hir::ConstTraitItem(ref ty, ref default) => {
                                       if let ExprTypeChecked = ty_hint {
                                           let substs = tcx.node_id_item_substs(e.id).substs;
                                           (resolve_trait_associated_const(tcx,
@@ -1030,6 +1263,10 @@ pub fn eval_const_expr_partial<'tcx>(tcx: &TyCtxt<'tcx>,
                                                                           trait_id,
                                                                           substs),
                                            Some(&**ty))
+                                       } else if type_relative {
+                                           // `Foo::BAR` resolved to the default of a
+                                           // trait whose impl for `Foo` inherits it.
+                                           (default.as_ref().map(|d| &**d), Some(&**ty))
                                        } else {
                                            (None, None)
                                        }
@@ -1084,7 +1321,8 @@ pub fn eval_const_expr_partial<'tcx>(tcx: &TyCtxt<'tcx>,
           } else {
               ty_hint
           };
-          try!(eval_const_expr_partial(tcx, const_expr, item_hint, fn_args))
+          try!(in_step(eval_const_expr_partial(tcx, const_expr, item_hint, fn_args),
+                       e.span, StepKind::Constant(pprust::path_to_string(path))))
       }
       hir::ExprCall(ref callee, ref args) => {
           let sub_ty_hint = ty_hint.erase_hint();
@@ -1102,19 +1340,20 @@ pub fn eval_const_expr_partial<'tcx>(tcx: &TyCtxt<'tcx>,
           assert_eq!(decl.inputs.len(), args.len());
 
           let mut call_args = NodeMap();
-          for (arg, arg_expr) in decl.inputs.iter().zip(args.iter()) {
-              let arg_val = try!(eval_const_expr_partial(
+          for (i, (arg, arg_expr)) in decl.inputs.iter().zip(args.iter()).enumerate() {
+              let arg_val = try!(in_step(eval_const_expr_partial(
                   tcx,
                   arg_expr,
                   sub_ty_hint,
                   fn_args
-              ));
+              ), e.span, StepKind::Argument(tcx.item_path_str(did), i)));
               debug!("const call arg: {:?}", arg);
               let old = call_args.insert(arg.pat.id, arg_val);
               assert!(old.is_none());
           }
           debug!("const call({:?})", call_args);
-          try!(eval_const_expr_partial(tcx, &result, ty_hint, Some(&call_args)))
+          try!(in_step(eval_const_expr_partial(tcx, &result, ty_hint, Some(&call_args)),
+                       e.span, StepKind::ConstFnCall(tcx.item_path_str(did))))
       },
       hir::ExprLit(ref lit) => lit_to_const(tcx.sess, e.span, &lit, ety),
       hir::ExprBlock(ref block) => {
@@ -1272,10 +1511,22 @@ fn resolve_trait_associated_const<'a, 'tcx: 'a>(tcx: &'a TyCtxt<'tcx>,
 
     match selection {
         traits::VtableImpl(ref impl_data) => {
-            match tcx.associated_consts(impl_data.impl_def_id)
-                     .iter().find(|ic| ic.name == ti.name) {
-                Some(ic) => lookup_const_by_id(tcx, ic.def_id, None, None),
-                None => match ti.node {
+            // Look in the impl, then in the impls it specializes.
+            let found = traits::find_impl_item(tcx, impl_data.impl_def_id, |item| {
+                match *item {
+                    ty::ConstTraitItem(ref ic) => ic.name == ti.name,
+                    _ => false,
+                }
+            });
+            match found {
+                Some((item_impl, ty::ConstTraitItem(ic))) => {
+                    let substs = traits::translate_substs(&infcx,
+                                                          impl_data.impl_def_id,
+                                                          impl_data.substs,
+                                                          item_impl);
+                    lookup_const_by_id(tcx, ic.def_id, None, Some(tcx.mk_substs(substs)))
+                }
+                _ => match ti.node {
                     hir::ConstTraitItem(_, Some(ref expr)) => Some(&*expr),
                     _ => None,
                 },
@@ -1289,7 +1540,7 @@ fn resolve_trait_associated_const<'a, 'tcx: 'a>(tcx: &'a TyCtxt<'tcx>,
     }
 }
 
-fn cast_const<'tcx>(tcx: &TyCtxt<'tcx>, val: ConstVal, ty: Ty) -> CastResult {
+pub fn cast_const<'tcx>(tcx: &TyCtxt<'tcx>, val: ConstVal, ty: Ty) -> CastResult {
     macro_rules! convert_val {
         ($intermediate_ty:ty, $const_type:ident, $target_ty:ty) => {
             match val {
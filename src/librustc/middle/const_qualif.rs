@@ -8,6 +8,8 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use syntax::codemap::Span;
+
 // Const qualification, from partial to completely promotable.
 bitflags! {
     #[derive(RustcEncodable, RustcDecodable)]
@@ -42,3 +44,78 @@ bitflags! {
                                    ConstQualif::NOT_CONST.bits
     }
 }
+
+/// Why an expression is `NOT_CONST`: the subexpression (or statement) that
+/// made it so, and what is wrong with it. Recorded by const qualification
+/// for every expression it qualifies as `NOT_CONST`, in
+/// `TyCtxt::not_const_causes`, so that lints and tools can explain why a
+/// value can't be promoted to a constant.
+#[derive(Copy, Clone, Debug)]
+pub struct NotConstCause {
+    pub span: Span,
+    pub reason: NotConstReason,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum NotConstReason {
+    /// A `let` or expression statement in a block.
+    Statement,
+    /// A use of an overloaded operator.
+    OverloadedOperator,
+    /// A use of an overloaded dereference, through autoderef.
+    OverloadedDeref,
+    /// A `box` expression.
+    Allocation,
+    RawPtrDeref,
+    RawPtrComparison,
+    RawPtrToIntCast,
+    /// A use of a static outside of a static initializer.
+    StaticRef,
+    /// A use of a local variable (or argument) in a `const fn`.
+    LocalVariable,
+    /// A path that refers to something other than a constant, function or
+    /// constructor, e.g. a local variable.
+    NonConstPath,
+    /// A call to something other than a `const fn` or constructor.
+    NonConstFnCall,
+    /// A call to a method other than a `const fn` inherent method.
+    NonConstMethodCall,
+    /// A closure that captures variables.
+    CapturingClosure,
+    /// A `&` borrow of a value with interior mutability.
+    InteriorMutableBorrow,
+    /// A `&mut` borrow of a value that isn't zero-sized.
+    MutableBorrow,
+    /// Control flow, assignment or inline assembly, which constants can't
+    /// contain yet.
+    Unsupported,
+}
+
+impl NotConstReason {
+    pub fn description(&self) -> &'static str {
+        match *self {
+            NotConstReason::Statement => "blocks in constants may only contain items",
+            NotConstReason::OverloadedOperator => "user-defined operators are not constant",
+            NotConstReason::OverloadedDeref =>
+                "user-defined dereference operators are not constant",
+            NotConstReason::Allocation => "allocations are not constant",
+            NotConstReason::RawPtrDeref => "raw pointer dereferences are not constant",
+            NotConstReason::RawPtrComparison => "raw pointer comparisons are not constant",
+            NotConstReason::RawPtrToIntCast => "casts of raw pointers to integers are not constant",
+            NotConstReason::StaticRef => "statics are not constant",
+            NotConstReason::LocalVariable => "local variables are not constant",
+            NotConstReason::NonConstPath =>
+                "paths may only refer to constants, functions and constructors",
+            NotConstReason::NonConstFnCall =>
+                "only constant functions and constructors can be called",
+            NotConstReason::NonConstMethodCall =>
+                "only constant inherent methods can be called",
+            NotConstReason::CapturingClosure => "closures that capture variables are not constant",
+            NotConstReason::InteriorMutableBorrow =>
+                "values with interior mutability cannot be borrowed",
+            NotConstReason::MutableBorrow => "values cannot be borrowed mutably",
+            NotConstReason::Unsupported =>
+                "control flow, assignments and inline assembly are not supported",
+        }
+    }
+}
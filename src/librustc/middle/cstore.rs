@@ -64,6 +64,7 @@ pub struct LinkMeta {
 pub struct CrateSource {
     pub dylib: Option<(PathBuf, PathKind)>,
     pub rlib: Option<(PathBuf, PathKind)>,
+    pub rmeta: Option<(PathBuf, PathKind)>,
     pub cnum: ast::CrateNum,
 }
 
@@ -149,6 +150,7 @@ pub trait CrateStore<'tcx> : Any {
     fn item_path(&self, def: DefId) -> Vec<hir_map::PathElem>;
     fn extern_item_path(&self, def: DefId) -> Vec<hir_map::PathElem>;
     fn item_name(&self, def: DefId) -> ast::Name;
+    fn item_span(&self, tcx: &TyCtxt<'tcx>, def: DefId) -> Option<Span>;
     fn item_predicates(&self, tcx: &TyCtxt<'tcx>, def: DefId)
                        -> ty::GenericPredicates<'tcx>;
     fn item_super_predicates(&self, tcx: &TyCtxt<'tcx>, def: DefId)
@@ -174,6 +176,7 @@ pub trait CrateStore<'tcx> : Any {
     fn impl_polarity(&self, def: DefId) -> Option<hir::ImplPolarity>;
     fn custom_coerce_unsized_kind(&self, def: DefId)
                                   -> Option<ty::adjustment::CustomCoerceUnsized>;
+    fn impl_parent(&self, impl_def_id: DefId) -> Option<DefId>;
     fn associated_consts(&self, tcx: &TyCtxt<'tcx>, def: DefId)
                          -> Vec<Rc<ty::AssociatedConst<'tcx>>>;
 
@@ -196,6 +199,9 @@ pub trait CrateStore<'tcx> : Any {
     // crate metadata
     fn dylib_dependency_formats(&self, cnum: ast::CrateNum)
                                     -> Vec<(ast::CrateNum, LinkagePreference)>;
+    /// The crates that `cnum` was compiled against, or for `LOCAL_CRATE`
+    /// the crates named by its `extern crate` items.
+    fn crate_dependencies(&self, cnum: ast::CrateNum) -> Vec<ast::CrateNum>;
     fn lang_items(&self, cnum: ast::CrateNum) -> Vec<(DefIndex, usize)>;
     fn missing_lang_items(&self, cnum: ast::CrateNum) -> Vec<lang_items::LangItem>;
     fn is_staged_api(&self, cnum: ast::CrateNum) -> bool;
@@ -209,8 +215,10 @@ pub trait CrateStore<'tcx> : Any {
     fn plugin_registrar_fn(&self, cnum: ast::CrateNum) -> Option<DefId>;
     fn native_libraries(&self, cnum: ast::CrateNum) -> Vec<(NativeLibraryKind, String)>;
     fn reachable_ids(&self, cnum: ast::CrateNum) -> Vec<DefId>;
+    fn default_bound_traits(&self, cnum: ast::CrateNum) -> Vec<DefId>;
 
     // resolve
+    fn def_key(&self, def: DefId) -> hir_map::DefKey;
     fn def_path(&self, def: DefId) -> hir_map::DefPath;
     fn variant_kind(&self, def_id: DefId) -> Option<VariantKind>;
     fn struct_ctor_def_id(&self, struct_def_id: DefId) -> Option<DefId>;
@@ -315,6 +323,7 @@ impl<'tcx> CrateStore<'tcx> for DummyCrateStore {
     fn item_path(&self, def: DefId) -> Vec<hir_map::PathElem> { unimplemented!() }
     fn extern_item_path(&self, def: DefId) -> Vec<hir_map::PathElem> { unimplemented!() }
     fn item_name(&self, def: DefId) -> ast::Name { unimplemented!() }
+    fn item_span(&self, tcx: &TyCtxt<'tcx>, def: DefId) -> Option<Span> { None }
     fn item_predicates(&self, tcx: &TyCtxt<'tcx>, def: DefId)
                        -> ty::GenericPredicates<'tcx> { unimplemented!() }
     fn item_super_predicates(&self, tcx: &TyCtxt<'tcx>, def: DefId)
@@ -344,6 +353,7 @@ impl<'tcx> CrateStore<'tcx> for DummyCrateStore {
     fn custom_coerce_unsized_kind(&self, def: DefId)
                                   -> Option<ty::adjustment::CustomCoerceUnsized>
         { unimplemented!() }
+    fn impl_parent(&self, def: DefId) -> Option<DefId> { unimplemented!() }
     fn associated_consts(&self, tcx: &TyCtxt<'tcx>, def: DefId)
                          -> Vec<Rc<ty::AssociatedConst<'tcx>>> { unimplemented!() }
 
@@ -367,6 +377,8 @@ impl<'tcx> CrateStore<'tcx> for DummyCrateStore {
     fn dylib_dependency_formats(&self, cnum: ast::CrateNum)
                                     -> Vec<(ast::CrateNum, LinkagePreference)>
         { unimplemented!() }
+    fn crate_dependencies(&self, cnum: ast::CrateNum) -> Vec<ast::CrateNum>
+        { unimplemented!() }
     fn lang_items(&self, cnum: ast::CrateNum) -> Vec<(DefIndex, usize)>
         { unimplemented!() }
     fn missing_lang_items(&self, cnum: ast::CrateNum) -> Vec<lang_items::LangItem>
@@ -386,8 +398,10 @@ impl<'tcx> CrateStore<'tcx> for DummyCrateStore {
     fn native_libraries(&self, cnum: ast::CrateNum) -> Vec<(NativeLibraryKind, String)>
         { unimplemented!() }
     fn reachable_ids(&self, cnum: ast::CrateNum) -> Vec<DefId> { unimplemented!() }
+    fn default_bound_traits(&self, cnum: ast::CrateNum) -> Vec<DefId> { unimplemented!() }
 
     // resolve
+    fn def_key(&self, def: DefId) -> hir_map::DefKey { unimplemented!() }
     fn def_path(&self, def: DefId) -> hir_map::DefPath { unimplemented!() }
     fn variant_kind(&self, def_id: DefId) -> Option<VariantKind> { unimplemented!() }
     fn struct_ctor_def_id(&self, struct_def_id: DefId) -> Option<DefId>
@@ -178,7 +178,7 @@ impl<'a, 'tcx> MarkSymbolVisitor<'a, 'tcx> {
         match *node {
             ast_map::NodeItem(item) => {
                 match item.node {
-                    hir::ItemStruct(..) => {
+                    hir::ItemStruct(..) | hir::ItemUnion(..) => {
                         self.struct_has_extern_repr = item.attrs.iter().any(|attr| {
                             attr::find_repr_attrs(self.tcx.sess.diagnostic(), attr)
                                 .contains(&attr::ReprExtern)
@@ -407,6 +407,7 @@ fn get_struct_ctor_id(item: &hir::Item) -> Option<ast::NodeId> {
 struct DeadVisitor<'a, 'tcx: 'a> {
     tcx: &'a TyCtxt<'tcx>,
     live_symbols: Box<HashSet<ast::NodeId>>,
+    access_levels: &'a privacy::AccessLevels,
 }
 
 impl<'a, 'tcx> DeadVisitor<'a, 'tcx> {
@@ -416,7 +417,8 @@ impl<'a, 'tcx> DeadVisitor<'a, 'tcx> {
             | hir::ItemConst(..)
             | hir::ItemFn(..)
             | hir::ItemEnum(..)
-            | hir::ItemStruct(..) => true,
+            | hir::ItemStruct(..)
+            | hir::ItemUnion(..) => true,
             _ => false
         };
         let ctor_id = get_struct_ctor_id(item);
@@ -481,11 +483,53 @@ impl<'a, 'tcx> DeadVisitor<'a, 'tcx> {
         false
     }
 
+    /// For a dead `pub` item, explains why it isn't visible outside the
+    /// crate (and so not kept alive as part of its interface): names the
+    /// outermost enclosing item that got no access level.
+    fn unexported_pub_item_note(&self, item: &hir::Item) -> Option<String> {
+        if item.vis != hir::Public || self.access_levels.is_reachable(item.id) {
+            return None;
+        }
+        let mut hidden_by = None;
+        let mut id = item.id;
+        while id != ast::CRATE_NODE_ID {
+            id = self.tcx.map.get_parent(id);
+            if self.access_levels.is_reachable(id) {
+                break;
+            }
+            hidden_by = Some(id);
+        }
+        hidden_by.map(|id| {
+            format!("; it is `pub`, but not visible outside the crate as {} is not",
+                    privacy::access_subject(self.tcx, id))
+        })
+    }
+
     fn warn_dead_code(&mut self,
                       id: ast::NodeId,
                       span: codemap::Span,
                       name: ast::Name,
                       node_type: &str) {
+        self.warn_dead_code_with_note(id, span, name, node_type, None)
+    }
+
+    fn warn_dead_code_with_note(&mut self,
+                                id: ast::NodeId,
+                                span: codemap::Span,
+                                name: ast::Name,
+                                node_type: &str,
+                                note: Option<String>) {
+        // Helpers that are only used by `#[cfg(test)]` code look dead in
+        // non-test builds (and vice versa for `#[cfg(not(test))]`). Resolve
+        // found the items the stripped code refers to by path; methods are
+        // only known by name, as the stripped code was never type-checked.
+        if self.tcx.sess.test_only_items.borrow().contains(&id) {
+            return;
+        }
+        if (node_type == "method" || node_type == "associated const") &&
+           self.tcx.sess.test_only_uses.borrow().method_names.contains(&name) {
+            return;
+        }
         let name = name.as_str();
         if !name.starts_with("_") {
             self.tcx
@@ -493,7 +537,8 @@ impl<'a, 'tcx> DeadVisitor<'a, 'tcx> {
                 .add_lint(lint::builtin::DEAD_CODE,
                           id,
                           span,
-                          format!("{} is never used: `{}`", node_type, name));
+                          format!("{} is never used: `{}`{}",
+                                  node_type, name, note.unwrap_or(String::new())));
         }
     }
 }
@@ -509,11 +554,13 @@ impl<'a, 'tcx, 'v> Visitor<'v> for DeadVisitor<'a, 'tcx> {
 
     fn visit_item(&mut self, item: &hir::Item) {
         if self.should_warn_about_item(item) {
-            self.warn_dead_code(
+            let note = self.unexported_pub_item_note(item);
+            self.warn_dead_code_with_note(
                 item.id,
                 item.span,
                 item.name,
-                item.node.descriptive_variant()
+                item.node.descriptive_variant(),
+                note
             );
         } else {
             // Only continue if we didn't warn
@@ -586,6 +633,10 @@ pub fn check_crate(tcx: &TyCtxt, access_levels: &privacy::AccessLevels) {
     let _task = tcx.dep_graph.in_task(DepNode::DeadCheck);
     let krate = tcx.map.krate();
     let live_symbols = find_live(tcx, access_levels, krate);
-    let mut visitor = DeadVisitor { tcx: tcx, live_symbols: live_symbols };
+    let mut visitor = DeadVisitor {
+        tcx: tcx,
+        live_symbols: live_symbols,
+        access_levels: access_levels,
+    };
     intravisit::walk_crate(&mut visitor, krate);
 }
@@ -18,7 +18,9 @@ use rustc_front::hir;
 pub enum Def {
     Fn(DefId),
     SelfTy(Option<DefId>,                    // trait id
-              Option<(ast::NodeId, ast::NodeId)>),   // (impl id, self type id)
+              Option<(ast::NodeId, ast::NodeId)>),   // (impl id, self type id), or
+                                                     // the item id twice for `Self`
+                                                     // in a struct or enum
     Mod(DefId),
     ForeignMod(DefId),
     Static(DefId, bool /* is_mutbl */),
@@ -33,6 +35,7 @@ pub enum Def {
     Trait(DefId),
     PrimTy(hir::PrimTy),
     TyParam(ParamSpace, u32, DefId, ast::Name),
+    ConstParam(ParamSpace, u32, DefId, ast::Name),
     Upvar(DefId,        // def id of closed over local
              ast::NodeId,  // node id of closed over local
              usize,        // index in the freevars list of the closure
@@ -113,7 +116,7 @@ impl Def {
 
             Def::Fn(..) | Def::Mod(..) | Def::ForeignMod(..) | Def::Static(..) |
             Def::Variant(..) | Def::Enum(..) | Def::TyAlias(..) | Def::AssociatedTy(..) |
-            Def::TyParam(..) | Def::Struct(..) | Def::Trait(..) |
+            Def::TyParam(..) | Def::ConstParam(..) | Def::Struct(..) | Def::Trait(..) |
             Def::Method(..) | Def::Const(..) | Def::AssociatedConst(..) |
             Def::PrimTy(..) | Def::Label(..) | Def::SelfTy(..) | Def::Err => {
                 panic!("attempted .var_id() on invalid {:?}", self)
@@ -125,8 +128,9 @@ impl Def {
         match *self {
             Def::Fn(id) | Def::Mod(id) | Def::ForeignMod(id) | Def::Static(id, _) |
             Def::Variant(_, id) | Def::Enum(id) | Def::TyAlias(id) | Def::AssociatedTy(_, id) |
-            Def::TyParam(_, _, id, _) | Def::Struct(id) | Def::Trait(id) |
-            Def::Method(id) | Def::Const(id) | Def::AssociatedConst(id) |
+            Def::TyParam(_, _, id, _) | Def::ConstParam(_, _, id, _) |
+            Def::Struct(id) | Def::Trait(id) | Def::Method(id) |
+            Def::Const(id) | Def::AssociatedConst(id) |
             Def::Local(id, _) | Def::Upvar(id, _, _, _) => {
                 id
             }
@@ -165,6 +169,7 @@ impl Def {
             Def::Const(..) => "const",
             Def::AssociatedConst(..) => "associated const",
             Def::TyParam(..) => "type parameter",
+            Def::ConstParam(..) => "const parameter",
             Def::PrimTy(..) => "builtin type",
             Def::Local(..) => "local variable",
             Def::Upvar(..) => "closure capture",
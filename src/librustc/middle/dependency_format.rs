@@ -62,13 +62,16 @@
 //! than finding a number of solutions (there are normally quite a few).
 
 use syntax::ast;
+use syntax::errors::DiagnosticBuilder;
 
 use session;
 use session::config;
-use middle::cstore::CrateStore;
+use middle::cstore::{CrateStore, LOCAL_CRATE};
 use middle::cstore::LinkagePreference::{self, RequireStatic, RequireDynamic};
 use util::nodemap::FnvHashMap;
 
+use std::collections::VecDeque;
+
 /// A list of dependencies for a certain crate type.
 ///
 /// The length of this vector is the same as the number of external crates used.
@@ -91,6 +94,11 @@ pub enum Linkage {
 }
 
 pub fn calculate(sess: &session::Session) {
+    // Nothing is linked with `--emit=metadata`, so dependencies that are only
+    // available as `.rmeta` files are fine.
+    if sess.opts.only_metadata_requested() {
+        return
+    }
     let mut fmts = sess.dependency_formats.borrow_mut();
     for &ty in sess.crate_types.borrow().iter() {
         let linkage = calculate_type(sess, ty);
@@ -186,7 +194,8 @@ fn calculate_type(sess: &session::Session,
         if src.dylib.is_none() &&
            !formats.contains_key(&cnum) &&
            sess.cstore.is_explicitly_linked(cnum) {
-            assert!(src.rlib.is_some());
+            // A crate only found as an `.rmeta` is reported as missing below.
+            assert!(src.rlib.is_some() || src.rmeta.is_some());
             info!("adding staticlib: {}", sess.cstore.crate_name(cnum));
             add_library(sess, cnum, RequireStatic, &mut formats);
             ret[cnum as usize - 1] = Linkage::Static;
@@ -326,10 +335,81 @@ fn verify_ok(sess: &session::Session, list: &[Linkage]) {
         if let Some(prev_alloc) = allocator {
             let prev_name = sess.cstore.crate_name(prev_alloc);
             let cur_name = sess.cstore.crate_name(cnum);
-            sess.err(&format!("cannot link together two \
-                               allocators: {} and {}",
-                              prev_name, cur_name));
+            let mut err = sess.struct_err(&format!("cannot link together two \
+                                                    allocators: {} and {}",
+                                                   prev_name, cur_name));
+            note_linked_through(sess, &mut err, prev_alloc, list);
+            note_linked_through(sess, &mut err, cnum, list);
+            err.help("a program may only use one allocator, so at most one of them \
+                      may be linked");
+            err.emit();
         }
         allocator = Some(cnum);
     }
 }
+
+// Explains how `cnum` ended up in the output: how it's linked, and the chain
+// of dependencies that pulled it in.
+fn note_linked_through(sess: &session::Session,
+                       err: &mut DiagnosticBuilder,
+                       cnum: ast::CrateNum,
+                       list: &[Linkage]) {
+    let name = sess.cstore.crate_name(cnum);
+    let how = match list[cnum as usize - 1] {
+        Linkage::Static => "statically",
+        Linkage::Dynamic => "dynamically",
+        Linkage::IncludedFromDylib | Linkage::NotLinked => "as part of a dylib",
+    };
+    match dependency_path(sess, cnum) {
+        Some(ref path) if path.len() == 1 => {
+            err.note(&format!("`{}` is linked {}, as a direct dependency of this crate",
+                              name, how));
+        }
+        Some(path) => {
+            err.note(&format!("`{}` is linked {}, pulled in by `{}`",
+                              name, how, path.join(" -> ")));
+        }
+        None if sess.injected_allocator.get() == Some(cnum) => {
+            err.note(&format!("`{}` is linked {}, as the default allocator", name, how));
+        }
+        None => {
+            err.note(&format!("`{}` is linked {}", name, how));
+        }
+    }
+}
+
+// The names of the crates along a shortest chain of dependencies from the
+// local crate to `target`, ending with `target` itself. Dependencies of a
+// dylib on crates it includes statically count as well, as those are what
+// bring in copies of crates that only exist inside dylibs.
+fn dependency_path(sess: &session::Session, target: ast::CrateNum) -> Option<Vec<String>> {
+    let mut parents = FnvHashMap();
+    let mut queue = VecDeque::new();
+    queue.push_back(LOCAL_CRATE);
+    while let Some(cnum) = queue.pop_front() {
+        if cnum == target {
+            let mut path = vec![];
+            let mut cur = cnum;
+            while cur != LOCAL_CRATE {
+                path.push(sess.cstore.crate_name(cur));
+                cur = parents[&cur];
+            }
+            path.reverse();
+            return Some(path);
+        }
+        let mut deps = sess.cstore.crate_dependencies(cnum);
+        if cnum != LOCAL_CRATE {
+            deps.extend(sess.cstore.dylib_dependency_formats(cnum).into_iter()
+                            .map(|(dep, _)| dep));
+        }
+        // Visit dependencies in a fixed order so the path is deterministic.
+        deps.sort();
+        for dep in deps {
+            if dep != LOCAL_CRATE && !parents.contains_key(&dep) {
+                parents.insert(dep, cnum);
+                queue.push_back(dep);
+            }
+        }
+    }
+    None
+}
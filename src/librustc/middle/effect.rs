@@ -171,6 +171,13 @@ impl<'a, 'tcx, 'v> Visitor<'v> for EffectCheckVisitor<'a, 'tcx> {
             hir::ExprInlineAsm(..) => {
                 self.require_unsafe(expr.span, "use of inline assembly");
             }
+            hir::ExprField(ref base, _) => {
+                if let ty::TyStruct(adt, _) = self.tcx.expr_ty_adjusted(base).sty {
+                    if adt.is_union() {
+                        self.require_unsafe(expr.span, "access to union field");
+                    }
+                }
+            }
             hir::ExprPath(..) => {
                 if let Def::Static(_, true) = self.tcx.resolve_expr(expr) {
                     self.require_unsafe(expr.span, "use of mutable static");
@@ -941,12 +941,15 @@ impl<'d,'t,'a,'tcx> ExprUseVisitor<'d,'t,'a,'tcx> {
             let def_map = &self.tcx().def_map;
             if pat_util::pat_is_binding(&def_map.borrow(), pat) {
                 match pat.node {
-                    PatKind::Ident(hir::BindByRef(_), _, _) =>
-                        mode.lub(BorrowingMatch),
-                    PatKind::Ident(hir::BindByValue(_), _, _) => {
-                        match copy_or_move(self.typer, &cmt_pat, PatBindingMove) {
-                            Copy => mode.lub(CopyingMatch),
-                            Move(_) => mode.lub(MovingMatch),
+                    PatKind::Ident(bm, _, _) => {
+                        match self.typer.tables.borrow().binding_mode(pat.id, bm) {
+                            hir::BindByRef(_) => mode.lub(BorrowingMatch),
+                            hir::BindByValue(_) => {
+                                match copy_or_move(self.typer, &cmt_pat, PatBindingMove) {
+                                    Copy => mode.lub(CopyingMatch),
+                                    Move(_) => mode.lub(MovingMatch),
+                                }
+                            }
                         }
                     }
                     _ => {
@@ -997,18 +1000,22 @@ impl<'d,'t,'a,'tcx> ExprUseVisitor<'d,'t,'a,'tcx> {
 
                 // It is also a borrow or copy/move of the value being matched.
                 match pat.node {
-                    PatKind::Ident(hir::BindByRef(m), _, _) => {
-                        if let ty::TyRef(&r, _) = pat_ty.sty {
-                            let bk = ty::BorrowKind::from_mutbl(m);
-                            delegate.borrow(pat.id, pat.span, cmt_pat,
-                                            r, bk, RefBinding);
+                    PatKind::Ident(bm, _, _) => {
+                        match typer.tables.borrow().binding_mode(pat.id, bm) {
+                            hir::BindByRef(m) => {
+                                if let ty::TyRef(&r, _) = pat_ty.sty {
+                                    let bk = ty::BorrowKind::from_mutbl(m);
+                                    delegate.borrow(pat.id, pat.span, cmt_pat,
+                                                    r, bk, RefBinding);
+                                }
+                            }
+                            hir::BindByValue(_) => {
+                                let mode = copy_or_move(typer, &cmt_pat, PatBindingMove);
+                                debug!("walk_pat binding consuming pat");
+                                delegate.consume_pat(pat, cmt_pat, mode);
+                            }
                         }
                     }
-                    PatKind::Ident(hir::BindByValue(_), _, _) => {
-                        let mode = copy_or_move(typer, &cmt_pat, PatBindingMove);
-                        debug!("walk_pat binding consuming pat");
-                        delegate.consume_pat(pat, cmt_pat, mode);
-                    }
                     _ => {
                         tcx.sess.span_bug(
                             pat.span,
@@ -105,8 +105,10 @@ impl<'tcx> TyCtxt<'tcx> {
             match item.node {
                 hir::ItemImpl(..) => "impl",
                 hir::ItemStruct(..) => "struct",
+                hir::ItemUnion(..) => "union",
                 hir::ItemEnum(..) => "enum",
                 hir::ItemTrait(..) => "trait",
+                hir::ItemTraitAlias(..) => "trait alias",
                 hir::ItemFn(..) => "function body",
                 _ => "item"
             }
@@ -133,22 +135,29 @@ impl<'tcx> TyCtxt<'tcx> {
                         return;
                     }
                 };
-                let tag = match self.map.find(scope.node_id(&self.region_maps)) {
-                    Some(ast_map::NodeBlock(_)) => "block",
-                    Some(ast_map::NodeExpr(expr)) => match expr.node {
-                        hir::ExprCall(..) => "call",
-                        hir::ExprMethodCall(..) => "method call",
-                        hir::ExprMatch(_, _, hir::MatchSource::IfLetDesugar { .. }) => "if let",
-                        hir::ExprMatch(_, _, hir::MatchSource::WhileLetDesugar) =>  "while let",
-                        hir::ExprMatch(_, _, hir::MatchSource::ForLoopDesugar) =>  "for",
-                        hir::ExprMatch(..) => "match",
-                        _ => "expression",
-                    },
-                    Some(ast_map::NodeStmt(_)) => "statement",
-                    Some(ast_map::NodeItem(it)) => item_scope_tag(&it),
-                    Some(_) | None => {
-                        err.span_note(span, &unknown_scope());
-                        return;
+                let scope_id = scope.node_id(&self.region_maps);
+                let desugaring_tag;
+                let tag = if let Some(kind) = self.map.desugaring_kind(scope_id) {
+                    desugaring_tag = format!("{} desugaring", kind.as_str());
+                    &desugaring_tag[..]
+                } else {
+                    match self.map.find(scope_id) {
+                        Some(ast_map::NodeBlock(_)) => "block",
+                        Some(ast_map::NodeExpr(expr)) => match expr.node {
+                            hir::ExprCall(..) => "call",
+                            hir::ExprMethodCall(..) => "method call",
+                            hir::ExprMatch(_, _, hir::MatchSource::IfLetDesugar { .. }) => "if let",
+                            hir::ExprMatch(_, _, hir::MatchSource::WhileLetDesugar) =>  "while let",
+                            hir::ExprMatch(_, _, hir::MatchSource::ForLoopDesugar) =>  "for",
+                            hir::ExprMatch(..) => "match",
+                            _ => "expression",
+                        },
+                        Some(ast_map::NodeStmt(_)) => "statement",
+                        Some(ast_map::NodeItem(it)) => item_scope_tag(&it),
+                        Some(_) | None => {
+                            err.span_note(span, &unknown_scope());
+                            return;
+                        }
                     }
                 };
                 let scope_decorated_tag = match self.region_maps.code_extent_data(scope) {
@@ -224,6 +233,31 @@ impl<'tcx> TyCtxt<'tcx> {
         } else {
             err.note(&message);
         }
+
+        if let ty::ReFree(ty::FreeRegion { scope, bound_region: ty::BrAnon(idx) }) = region {
+            self.note_anon_region_origin(err, scope, idx);
+        }
+    }
+
+    /// Points at the elided lifetime in a function signature that
+    /// introduced the anonymous region `BrAnon(idx)` of the function
+    /// whose call-site scope is `scope`, if collect recorded it.
+    fn note_anon_region_origin(&self,
+                               err: &mut DiagnosticBuilder,
+                               scope: region::CodeExtent,
+                               idx: u32) {
+        let fn_id = match self.region_maps.code_extent_data(scope) {
+            region::CodeExtentData::CallSiteScope { fn_id, .. } |
+            region::CodeExtentData::ParameterScope { fn_id, .. } => fn_id,
+            _ => return
+        };
+        let origins = self.anon_region_origins.borrow();
+        if let Some(origin) = origins.get(&fn_id).and_then(|o| o.get(idx as usize)) {
+            err.span_note(origin.span,
+                          &format!("the anonymous lifetime #{} is the lifetime elided \
+                                    in the type of {}",
+                                   idx + 1, origin.position));
+        }
     }
 }
 
@@ -1321,6 +1355,7 @@ impl<'a, 'tcx> Rebuilder<'a, 'tcx> {
         hir::Generics {
             lifetimes: lifetimes.into(),
             ty_params: ty_params,
+            const_params: generics.const_params.clone(),
             where_clause: where_clause,
         }
     }
@@ -1570,6 +1605,7 @@ impl<'a, 'tcx> Rebuilder<'a, 'tcx> {
                 hir::AngleBracketedParameters(hir::AngleBracketedParameterData {
                     lifetimes: new_lts.into(),
                     types: new_types,
+                    consts: data.consts.clone(),
                     bindings: new_bindings,
                })
             }
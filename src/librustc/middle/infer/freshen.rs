@@ -158,6 +158,7 @@ impl<'a, 'tcx> TypeFolder<'tcx> for TypeFreshener<'a, 'tcx> {
             ty::TyStr |
             ty::TyError |
             ty::TyArray(..) |
+            ty::TyParamArray(..) |
             ty::TySlice(..) |
             ty::TyRawPtr(..) |
             ty::TyRef(..) |
@@ -168,6 +169,7 @@ impl<'a, 'tcx> TypeFolder<'tcx> for TypeFreshener<'a, 'tcx> {
             ty::TyClosure(..) |
             ty::TyTuple(..) |
             ty::TyProjection(..) |
+            ty::TyAnon(..) |
             ty::TyParam(..) => {
                 t.super_fold_with(self)
             }
@@ -525,6 +525,10 @@ pub fn normalize_associated_type<'tcx,T>(tcx: &TyCtxt<'tcx>, value: &T) -> T
         return value;
     }
 
+    // Trans only deals in concrete types, so look behind any `impl Trait`
+    // before normalizing the projections that may be applied to it.
+    let value = tcx.reveal_anon_types(&value);
+
     let infcx = new_infer_ctxt(tcx, &tcx.tables, None);
     let mut selcx = traits::SelectionContext::new(&infcx);
     let cause = traits::ObligationCause::dummy();
@@ -1598,6 +1598,13 @@ impl<'a, 'tcx> Liveness<'a, 'tcx> {
                          ln: LiveNode,
                          var: Variable)
                          -> bool {
+        // Bindings introduced by lowering (e.g. the iterator of a `for`
+        // loop) are not the user's to clean up; claim we warned so that
+        // no dead-assignment lint is reported for them either.
+        if self.ir.tcx.map.desugaring_kind(id).is_some() {
+            return true;
+        }
+
         if !self.used_on_entry(ln, var) {
             let r = self.should_warn(var);
             if let Some(name) = r {
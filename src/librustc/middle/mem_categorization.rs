@@ -227,7 +227,7 @@ fn deref_kind(t: Ty, context: DerefKindContext) -> McResult<deref_kind> {
             Ok(deref_interior(InteriorField(PositionalField(0))))
         }
 
-        ty::TyArray(_, _) | ty::TySlice(_) | ty::TyStr => {
+        ty::TyArray(_, _) | ty::TyParamArray(..) | ty::TySlice(_) | ty::TyStr => {
             // no deref of indexed content without supplying InteriorOffsetKind
             if let Some(context) = context {
                 Ok(deref_interior(InteriorElement(context, element_kind(t))))
@@ -395,8 +395,12 @@ impl<'t, 'a,'tcx> MemCategorizationContext<'t, 'a, 'tcx> {
         // looking at a `ref x`, and if so, figures out what the type
         // *being borrowed* is.  But ideally we would put in a more
         // fundamental fix to this conflated use of the node id.
-        let ret_ty = match pat.node {
-            PatKind::Ident(hir::BindByRef(_), _, _) => {
+        let binding_mode = match pat.node {
+            PatKind::Ident(bm, _, _) => Some(self.typer.tables.borrow().binding_mode(pat.id, bm)),
+            _ => None,
+        };
+        let ret_ty = match binding_mode {
+            Some(hir::BindByRef(_)) => {
                 // a bind-by-ref means that the base_ty will be the type of the ident itself,
                 // but what we want here is the type of the underlying value being borrowed.
                 // So peel off one-level, turning the &T into T.
@@ -550,7 +554,7 @@ impl<'t, 'a,'tcx> MemCategorizationContext<'t, 'a, 'tcx> {
 
         match def {
           Def::Struct(..) | Def::Variant(..) | Def::Const(..) |
-          Def::AssociatedConst(..) | Def::Fn(..) | Def::Method(..) => {
+          Def::AssociatedConst(..) | Def::ConstParam(..) | Def::Fn(..) | Def::Method(..) => {
                 Ok(self.cat_rvalue_node(id, span, expr_ty))
           }
 
@@ -1195,6 +1199,14 @@ impl<'t, 'a,'tcx> MemCategorizationContext<'t, 'a, 'tcx> {
                pat,
                cmt);
 
+        // If typeck dereferenced the value implicitly before matching it
+        // against `pat` (default binding modes), so do we.
+        let mut cmt = cmt;
+        let implicit_derefs = self.typer.tables.borrow().pat_adjustments(pat.id).len();
+        for _ in 0..implicit_derefs {
+            cmt = try!(self.cat_deref(pat, cmt, 0, None));
+        }
+
         (*op)(self, cmt.clone(), pat);
 
         let opt_def = if let Some(path_res) = self.tcx().def_map.borrow().get(&pat.id) {
@@ -1616,7 +1628,7 @@ fn element_kind(t: Ty) -> ElementKind {
             ty::TySlice(_) => VecElement,
             _ => OtherElement
         },
-        ty::TyArray(..) | ty::TySlice(_) => VecElement,
+        ty::TyArray(..) | ty::TyParamArray(..) | ty::TySlice(_) => VecElement,
         _ => OtherElement
     }
 }
@@ -12,10 +12,11 @@
 //! outside their scopes. This pass will also generate a set of exported items
 //! which are available for use externally when compiled as a library.
 
+use middle::ty::TyCtxt;
 use util::nodemap::{DefIdSet, FnvHashMap};
 
 use std::hash::Hash;
-use syntax::ast::NodeId;
+use syntax::ast::{self, NodeId};
 
 // Accessibility levels, sorted in ascending order
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -32,10 +33,59 @@ pub enum AccessLevel {
     Public,
 }
 
+// Why a node was given its accessibility level. The node named by a reason
+// has a reason of its own, so following them leads back to the crate root.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AccessReason<Id = NodeId> {
+    // Nameable through a path of `pub` items starting at the crate root
+    PublicPath,
+    // Reexported by a `pub use` in the given module
+    Reexport(Id),
+    // Inherits the level of the given enclosing item
+    Parent(Id),
+    // Appears in the interface of the given reachable item
+    Interface(Id),
+    // An exported macro
+    Macro,
+}
+
 // Accessibility levels for reachable HIR nodes
 #[derive(Clone)]
 pub struct AccessLevels<Id = NodeId> {
-    pub map: FnvHashMap<Id, AccessLevel>
+    pub map: FnvHashMap<Id, AccessLevel>,
+    // Why each node in `map` got its current level
+    pub reasons: FnvHashMap<Id, AccessReason<Id>>,
+}
+
+impl<Id: Hash + Eq + Copy> AccessLevels<Id> {
+    pub fn reason(&self, id: Id) -> Option<AccessReason<Id>> {
+        self.reasons.get(&id).cloned()
+    }
+
+    // The reasons leading from `id` back to a public path or macro, starting
+    // with the reason for `id` itself
+    pub fn reason_chain(&self, id: Id) -> Vec<(Id, AccessReason<Id>)> {
+        let mut chain = vec![];
+        let mut next = Some(id);
+        while let Some(id) = next {
+            let reason = match self.reason(id) {
+                Some(reason) => reason,
+                None => break,
+            };
+            chain.push((id, reason));
+            next = match reason {
+                AccessReason::Reexport(id) |
+                AccessReason::Parent(id) |
+                AccessReason::Interface(id) => Some(id),
+                AccessReason::PublicPath | AccessReason::Macro => None,
+            };
+            // Levels only grow, so a chain can't loop, but be defensive
+            if chain.len() > self.reasons.len() {
+                break;
+            }
+        }
+        chain
+    }
 }
 
 impl<Id: Hash + Eq> AccessLevels<Id> {
@@ -52,7 +102,47 @@ impl<Id: Hash + Eq> AccessLevels<Id> {
 
 impl<Id: Hash + Eq> Default for AccessLevels<Id> {
     fn default() -> Self {
-        AccessLevels { map: Default::default() }
+        AccessLevels { map: Default::default(), reasons: Default::default() }
+    }
+}
+
+impl AccessLevels {
+    // Explains why `id` is visible outside the crate, e.g. "`a::f` is
+    // inside `a`, `a` is reexported from the crate root"
+    pub fn describe(&self, tcx: &TyCtxt, id: NodeId) -> Option<String> {
+        let chain = self.reason_chain(id);
+        if chain.is_empty() {
+            return None;
+        }
+        let steps: Vec<_> = chain.into_iter().map(|(id, reason)| {
+            let subject = access_subject(tcx, id);
+            match reason {
+                AccessReason::PublicPath => format!("{} is public", subject),
+                AccessReason::Macro => format!("{} is an exported macro", subject),
+                AccessReason::Reexport(module) => {
+                    format!("{} is reexported from {}", subject, access_subject(tcx, module))
+                }
+                AccessReason::Parent(parent) => {
+                    format!("{} is inside {}", subject, access_subject(tcx, parent))
+                }
+                AccessReason::Interface(user) => {
+                    format!("{} is used in the interface of {}",
+                            subject, access_subject(tcx, user))
+                }
+            }
+        }).collect();
+        Some(steps.join(", "))
+    }
+}
+
+// How a node is named when describing access reasons
+pub fn access_subject(tcx: &TyCtxt, id: NodeId) -> String {
+    if id == ast::CRATE_NODE_ID {
+        return "the crate root".to_string();
+    }
+    match tcx.map.opt_local_def_id(id) {
+        Some(def_id) => format!("`{}`", tcx.def_path_str(def_id)),
+        None => tcx.map.node_to_user_string(id),
     }
 }
 
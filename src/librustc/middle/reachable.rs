@@ -21,6 +21,7 @@ use middle::def::Def;
 use middle::def_id::DefId;
 use middle::ty::{self, TyCtxt};
 use middle::privacy;
+use session::Session;
 use session::config;
 use util::nodemap::NodeSet;
 
@@ -28,6 +29,7 @@ use std::collections::HashSet;
 use syntax::abi::Abi;
 use syntax::ast;
 use syntax::attr;
+use syntax::codemap::Span;
 use rustc_front::hir;
 use rustc_front::intravisit::Visitor;
 use rustc_front::intravisit;
@@ -275,6 +277,7 @@ impl<'a, 'tcx> ReachableContext<'a, 'tcx> {
                     hir::ItemMod(..) | hir::ItemForeignMod(..) |
                     hir::ItemImpl(..) | hir::ItemTrait(..) |
                     hir::ItemStruct(..) | hir::ItemEnum(..) |
+                    hir::ItemUnion(..) | hir::ItemTraitAlias(..) |
                     hir::ItemDefaultImpl(..) => {}
                 }
             }
@@ -349,6 +352,94 @@ impl<'a, 'v> Visitor<'v> for CollectPrivateImplItemsVisitor<'a> {
     }
 }
 
+// Collects the items marked `#[export]`, which are always part of the
+// export surface. When the export list is exclusive (`-Z explicit-exports`),
+// `#[no_mangle]` and `#[export_name]` items outside of it are reported, since
+// they would silently stop being exported.
+struct CollectExportsVisitor<'a, 'tcx: 'a> {
+    tcx: &'a TyCtxt<'tcx>,
+    exclusive: bool,
+    worklist: &'a mut Vec<ast::NodeId>,
+}
+
+impl<'a, 'tcx> CollectExportsVisitor<'a, 'tcx> {
+    fn check(&mut self, id: ast::NodeId, span: Span, attrs: &[ast::Attribute],
+             kind: ExportKind) {
+        let sess = &self.tcx.sess;
+        if !attr::contains_name(attrs, "export") {
+            if self.exclusive && attr::contains_extern_indicator(sess.diagnostic(), attrs) {
+                struct_span_err!(sess, span, E0520,
+                                 "this item has an external symbol name but is not \
+                                  marked `#[export]`")
+                    .fileline_help(span, "with `-Z explicit-exports`, only `#[export]` items \
+                                          are exported; add `#[export]` or remove \
+                                          `#[no_mangle]`/`#[export_name]`")
+                    .emit();
+            }
+            return;
+        }
+
+        match kind {
+            ExportKind::Symbol => self.worklist.push(id),
+            ExportKind::Generic => {
+                span_err!(sess, span, E0521,
+                          "generic functions cannot be marked `#[export]`");
+            }
+            ExportKind::Other => {
+                span_err!(sess, span, E0522,
+                          "only functions and statics can be marked `#[export]`");
+            }
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+enum ExportKind {
+    // Items with a symbol of their own
+    Symbol,
+    // Functions that only get symbols when monomorphized
+    Generic,
+    Other,
+}
+
+impl<'a, 'tcx, 'v> Visitor<'v> for CollectExportsVisitor<'a, 'tcx> {
+    fn visit_item(&mut self, item: &hir::Item) {
+        let kind = match item.node {
+            hir::ItemFn(_, _, _, _, ref generics, _) if generics.is_type_parameterized() => {
+                ExportKind::Generic
+            }
+            hir::ItemFn(..) | hir::ItemStatic(..) => ExportKind::Symbol,
+            _ => ExportKind::Other,
+        };
+        self.check(item.id, item.span, &item.attrs, kind);
+
+        if let hir::ItemImpl(_, _, ref generics, _, _, ref impl_items) = item.node {
+            for impl_item in impl_items {
+                let kind = match impl_item.node {
+                    hir::ImplItemKind::Method(ref sig, _) => {
+                        if generics.is_type_parameterized() ||
+                           sig.generics.is_type_parameterized() {
+                            ExportKind::Generic
+                        } else {
+                            ExportKind::Symbol
+                        }
+                    }
+                    _ => ExportKind::Other,
+                };
+                self.check(impl_item.id, impl_item.span, &impl_item.attrs, kind);
+            }
+        }
+    }
+}
+
+/// Whether only the items marked `#[export]` should be exported. Rlibs
+/// always export everything reachable, as downstream Rust crates may
+/// refer to any of it.
+pub fn has_explicit_exports(sess: &Session) -> bool {
+    sess.opts.debugging_opts.explicit_exports &&
+        !sess.crate_types.borrow().contains(&config::CrateTypeRlib)
+}
+
 pub fn find_reachable(tcx: &TyCtxt,
                       access_levels: &privacy::AccessLevels)
                       -> NodeSet {
@@ -360,9 +451,24 @@ pub fn find_reachable(tcx: &TyCtxt,
     //         a result of the privacy pass along with all local lang items and impl items.
     //         If other crates link to us, they're going to expect to be able to
     //         use the lang items, so we need to be sure to mark them as
-    //         exported.
-    for (id, _) in &access_levels.map {
-        reachable_context.worklist.push(*id);
+    //         exported. With an explicit export list, the `#[export]` items
+    //         take the place of the public ones.
+    let explicit = has_explicit_exports(&tcx.sess);
+    if tcx.sess.opts.debugging_opts.explicit_exports && !explicit {
+        tcx.sess.warn("`-Z explicit-exports` has no effect when building an rlib");
+    }
+    {
+        let mut collect_exports = CollectExportsVisitor {
+            tcx: tcx,
+            exclusive: explicit,
+            worklist: &mut reachable_context.worklist,
+        };
+        tcx.map.krate().visit_all_items(&mut collect_exports);
+    }
+    if !explicit {
+        for (id, _) in &access_levels.map {
+            reachable_context.worklist.push(*id);
+        }
     }
     for item in tcx.lang_items.items().iter() {
         if let Some(did) = *item {
@@ -371,7 +477,7 @@ pub fn find_reachable(tcx: &TyCtxt,
             }
         }
     }
-    {
+    if !explicit {
         let mut collect_private_impl_items = CollectPrivateImplItemsVisitor {
             access_levels: access_levels,
             worklist: &mut reachable_context.worklist,
@@ -12,12 +12,16 @@
 //
 // There are various parts of the compiler that must impose arbitrary limits
 // on how deeply they recurse to prevent stack overflow. Users can override
-// this via an attribute on the crate like `#![recursion_limit="22"]`. This pass
-// just peeks and looks for that attribute.
+// this via an attribute on the crate like `#![recursion_limit="22"]`, whose
+// value may also be a constant expression such as `"2 * 64"`. This pass just
+// peeks and looks for that attribute.
 
 use session::Session;
 use syntax::ast;
-use syntax::attr::AttrMetaMethods;
+use syntax::attr::{AttrMetaMethods, AttributeMethods};
+use syntax::attr_const::{self, AttrConst};
+
+use std::usize;
 
 pub fn update_recursion_limit(sess: &Session, krate: &ast::Crate) {
     for attr in &krate.attrs {
@@ -25,14 +29,18 @@ pub fn update_recursion_limit(sess: &Session, krate: &ast::Crate) {
             continue;
         }
 
-        if let Some(s) = attr.value_str() {
-            if let Some(n) = s.parse().ok() {
-                sess.recursion_limit.set(n);
+        let msg = match attr_const::eval_meta_item(attr.meta()) {
+            Ok(AttrConst::Int(n)) if n <= usize::MAX as u64 => {
+                sess.recursion_limit.set(n as usize);
                 return;
             }
-        }
+            Ok(value) => format!("expected an integer, found {}", value),
+            Err(msg) => msg,
+        };
 
-        span_err!(sess, attr.span, E0296, "malformed recursion limit attribute, \
-                                  expected #![recursion_limit=\"N\"]");
+        struct_span_err!(sess, attr.span, E0296, "malformed recursion limit attribute, \
+                                          expected #![recursion_limit=\"N\"]")
+            .note(&msg)
+            .emit();
     }
 }
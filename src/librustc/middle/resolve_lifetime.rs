@@ -139,7 +139,9 @@ impl<'a, 'v> Visitor<'v> for LifetimeContext<'a> {
                 hir::ItemTy(_, ref generics) |
                 hir::ItemEnum(_, ref generics) |
                 hir::ItemStruct(_, ref generics) |
+                hir::ItemUnion(_, ref generics) |
                 hir::ItemTrait(_, ref generics, _, _) |
+                hir::ItemTraitAlias(ref generics, _) |
                 hir::ItemImpl(_, _, ref generics, _, _, _) => {
                     // These kinds of items have only early bound lifetime parameters.
                     let lifetimes = &generics.lifetimes;
@@ -233,12 +235,22 @@ impl<'a, 'v> Visitor<'v> for LifetimeContext<'a> {
         // methods in an impl can reuse label names.
         let saved = replace(&mut self.labels_in_fn, vec![]);
 
-        if let hir::MethodTraitItem(ref sig, None) = trait_item.node {
-            self.visit_early_late(
-                subst::FnSpace, &sig.generics,
-                |this| intravisit::walk_trait_item(this, trait_item))
-        } else {
-            intravisit::walk_trait_item(self, trait_item);
+        match trait_item.node {
+            hir::MethodTraitItem(ref sig, None) => {
+                self.visit_early_late(
+                    subst::FnSpace, &sig.generics,
+                    |this| intravisit::walk_trait_item(this, trait_item))
+            }
+            hir::TypeTraitItem(ref generics, _, _) => {
+                // The lifetime parameters of an associated type are all early bound.
+                let lifetimes = &generics.lifetimes;
+                self.with(EarlyScope(subst::FnSpace, lifetimes, self.scope),
+                          |old_scope, this| {
+                    this.check_lifetime_defs(old_scope, lifetimes);
+                    intravisit::walk_trait_item(this, trait_item);
+                });
+            }
+            _ => intravisit::walk_trait_item(self, trait_item),
         }
 
         replace(&mut self.labels_in_fn, saved);
@@ -290,12 +302,11 @@ impl<'a, 'v> Visitor<'v> for LifetimeContext<'a> {
                         self.visit_lifetime(bound);
                     }
                 }
-                &hir::WherePredicate::EqPredicate(hir::WhereEqPredicate{ id,
-                                                                         ref path,
-                                                                         ref ty,
+                &hir::WherePredicate::EqPredicate(hir::WhereEqPredicate{ ref lhs_ty,
+                                                                         ref rhs_ty,
                                                                          .. }) => {
-                    self.visit_path(path, id);
-                    self.visit_ty(&ty);
+                    self.visit_ty(lhs_ty);
+                    self.visit_ty(rhs_ty);
                 }
             }
         }
@@ -822,7 +833,12 @@ fn early_bound_lifetime_names(generics: &hir::Generics) -> Vec<ast::Name> {
                         collector.visit_lifetime(bound);
                     }
                 }
-                &hir::WherePredicate::EqPredicate(_) => unimplemented!()
+                &hir::WherePredicate::EqPredicate(hir::WhereEqPredicate{ref lhs_ty,
+                                                                        ref rhs_ty,
+                                                                        ..}) => {
+                    collector.visit_ty(lhs_ty);
+                    collector.visit_ty(rhs_ty);
+                }
             }
         }
     }
@@ -0,0 +1,305 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The table of `extern "rust-intrinsic"` functions known to the compiler.
+//!
+//! Type checking validates each declaration against the signature recorded
+//! here, and translation uses the `llvm` mapping for the intrinsics that
+//! lower to a single call of an LLVM intrinsic. Atomic intrinsics are named
+//! `atomic_<op>` or `atomic_<op>_<ordering>` and are described once per
+//! `<op>` in `ATOMIC_OPS`; the ordering is only of interest to translation.
+//!
+//! Adding an intrinsic means adding it here, to `libcore/intrinsics.rs` and,
+//! unless it has an `llvm` mapping, to `librustc_trans/trans/intrinsic.rs`.
+//! The tests at the bottom of this file check the first two agree.
+
+pub use self::IntrinsicType::*;
+pub use self::IntrinsicOutput::*;
+
+/// A type in the signature of an intrinsic.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum IntrinsicType {
+    Nil,
+    Bool,
+    U8,
+    I32,
+    U64,
+    Isize,
+    Usize,
+    F32,
+    F64,
+    /// `&'static str`
+    StaticStr,
+    /// The intrinsic's type parameter with the given index.
+    Param(u8),
+    /// `&P`, for the type parameter `P` with the given index, with an
+    /// anonymous late-bound region.
+    RefParam(u8),
+    /// `*const T`
+    ConstPtr(&'static IntrinsicType),
+    /// `*mut T`
+    MutPtr(&'static IntrinsicType),
+    /// `(T, bool)`, as returned by the checked arithmetic intrinsics.
+    WithOverflow(&'static IntrinsicType),
+    /// `fn(*mut u8)`, the function pointer taken by `try`.
+    TryFn,
+    /// `&'static (&'static str, u32, u32)`, the location passed to
+    /// `#[track_caller]` functions.
+    CallerLocation,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum IntrinsicOutput {
+    Returns(IntrinsicType),
+    Diverges,
+}
+
+#[derive(Debug)]
+pub struct RustIntrinsic {
+    pub name: &'static str,
+    /// The number of type parameters the declaration must have.
+    pub n_tps: usize,
+    pub inputs: &'static [IntrinsicType],
+    pub output: IntrinsicOutput,
+    /// Whether the result only depends on the type parameters, so that a
+    /// call can be folded by constant evaluation.
+    pub const_eval: bool,
+    /// The LLVM intrinsic a call lowers to, for intrinsics that need no
+    /// special handling in translation.
+    pub llvm: Option<&'static str>,
+}
+
+macro_rules! intrinsic {
+    ($name:expr, $n_tps:expr, ($($input:expr),*) -> $output:expr) => {
+        intrinsic!($name, $n_tps, ($($input),*) -> $output, const_eval: false, llvm: None)
+    };
+    ($name:expr, $n_tps:expr, ($($input:expr),*) -> $output:expr, const_eval) => {
+        intrinsic!($name, $n_tps, ($($input),*) -> $output, const_eval: true, llvm: None)
+    };
+    ($name:expr, $n_tps:expr, ($($input:expr),*) -> $output:expr, llvm: $llvm:expr) => {
+        intrinsic!($name, $n_tps, ($($input),*) -> $output, const_eval: false, llvm: Some($llvm))
+    };
+    ($name:expr, $n_tps:expr, ($($input:expr),*) -> $output:expr,
+     const_eval: $const_eval:expr, llvm: $llvm:expr) => {
+        RustIntrinsic {
+            name: $name,
+            n_tps: $n_tps,
+            inputs: &[$($input),*],
+            output: $output,
+            const_eval: $const_eval,
+            llvm: $llvm,
+        }
+    };
+}
+
+pub static RUST_INTRINSICS: &'static [RustIntrinsic] = &[
+    intrinsic!("abort", 0, () -> Diverges),
+    intrinsic!("unreachable", 0, () -> Diverges),
+    intrinsic!("assume", 0, (Bool) -> Returns(Nil), llvm: "llvm.assume"),
+    intrinsic!("breakpoint", 0, () -> Returns(Nil)),
+
+    intrinsic!("size_of", 1, () -> Returns(Usize), const_eval),
+    intrinsic!("min_align_of", 1, () -> Returns(Usize), const_eval),
+    intrinsic!("pref_align_of", 1, () -> Returns(Usize), const_eval),
+    intrinsic!("size_of_val", 1, (RefParam(0)) -> Returns(Usize)),
+    intrinsic!("min_align_of_val", 1, (RefParam(0)) -> Returns(Usize)),
+    intrinsic!("needs_drop", 1, () -> Returns(Bool), const_eval),
+    intrinsic!("type_name", 1, () -> Returns(StaticStr)),
+    intrinsic!("type_id", 1, () -> Returns(U64), const_eval),
+
+    intrinsic!("init", 1, () -> Returns(Param(0))),
+    intrinsic!("init_dropped", 1, () -> Returns(Param(0))),
+    intrinsic!("uninit", 1, () -> Returns(Param(0))),
+    intrinsic!("forget", 1, (Param(0)) -> Returns(Nil)),
+    intrinsic!("transmute", 2, (Param(0)) -> Returns(Param(1))),
+    intrinsic!("move_val_init", 1, (MutPtr(&Param(0)), Param(0)) -> Returns(Nil)),
+    intrinsic!("drop_in_place", 1, (MutPtr(&Param(0))) -> Returns(Nil)),
+    intrinsic!("return_address", 0, () -> Returns(ConstPtr(&U8))),
+    intrinsic!("caller_location", 0, () -> Returns(CallerLocation)),
+    intrinsic!("discriminant_value", 1, (RefParam(0)) -> Returns(U64)),
+    intrinsic!("try", 0, (TryFn, MutPtr(&U8), MutPtr(&U8)) -> Returns(I32)),
+
+    intrinsic!("offset", 1, (ConstPtr(&Param(0)), Isize) -> Returns(ConstPtr(&Param(0)))),
+    intrinsic!("arith_offset", 1,
+               (ConstPtr(&Param(0)), Isize) -> Returns(ConstPtr(&Param(0)))),
+    intrinsic!("copy", 1,
+               (ConstPtr(&Param(0)), MutPtr(&Param(0)), Usize) -> Returns(Nil)),
+    intrinsic!("copy_nonoverlapping", 1,
+               (ConstPtr(&Param(0)), MutPtr(&Param(0)), Usize) -> Returns(Nil)),
+    intrinsic!("volatile_copy_memory", 1,
+               (MutPtr(&Param(0)), ConstPtr(&Param(0)), Usize) -> Returns(Nil)),
+    intrinsic!("volatile_copy_nonoverlapping_memory", 1,
+               (MutPtr(&Param(0)), ConstPtr(&Param(0)), Usize) -> Returns(Nil)),
+    intrinsic!("write_bytes", 1, (MutPtr(&Param(0)), U8, Usize) -> Returns(Nil)),
+    intrinsic!("volatile_set_memory", 1, (MutPtr(&Param(0)), U8, Usize) -> Returns(Nil)),
+    intrinsic!("volatile_load", 1, (ConstPtr(&Param(0))) -> Returns(Param(0))),
+    intrinsic!("volatile_store", 1, (MutPtr(&Param(0)), Param(0)) -> Returns(Nil)),
+
+    intrinsic!("sqrtf32", 0, (F32) -> Returns(F32), llvm: "llvm.sqrt.f32"),
+    intrinsic!("sqrtf64", 0, (F64) -> Returns(F64), llvm: "llvm.sqrt.f64"),
+    intrinsic!("powif32", 0, (F32, I32) -> Returns(F32), llvm: "llvm.powi.f32"),
+    intrinsic!("powif64", 0, (F64, I32) -> Returns(F64), llvm: "llvm.powi.f64"),
+    intrinsic!("sinf32", 0, (F32) -> Returns(F32), llvm: "llvm.sin.f32"),
+    intrinsic!("sinf64", 0, (F64) -> Returns(F64), llvm: "llvm.sin.f64"),
+    intrinsic!("cosf32", 0, (F32) -> Returns(F32), llvm: "llvm.cos.f32"),
+    intrinsic!("cosf64", 0, (F64) -> Returns(F64), llvm: "llvm.cos.f64"),
+    intrinsic!("powf32", 0, (F32, F32) -> Returns(F32), llvm: "llvm.pow.f32"),
+    intrinsic!("powf64", 0, (F64, F64) -> Returns(F64), llvm: "llvm.pow.f64"),
+    intrinsic!("expf32", 0, (F32) -> Returns(F32), llvm: "llvm.exp.f32"),
+    intrinsic!("expf64", 0, (F64) -> Returns(F64), llvm: "llvm.exp.f64"),
+    intrinsic!("exp2f32", 0, (F32) -> Returns(F32), llvm: "llvm.exp2.f32"),
+    intrinsic!("exp2f64", 0, (F64) -> Returns(F64), llvm: "llvm.exp2.f64"),
+    intrinsic!("logf32", 0, (F32) -> Returns(F32), llvm: "llvm.log.f32"),
+    intrinsic!("logf64", 0, (F64) -> Returns(F64), llvm: "llvm.log.f64"),
+    intrinsic!("log10f32", 0, (F32) -> Returns(F32), llvm: "llvm.log10.f32"),
+    intrinsic!("log10f64", 0, (F64) -> Returns(F64), llvm: "llvm.log10.f64"),
+    intrinsic!("log2f32", 0, (F32) -> Returns(F32), llvm: "llvm.log2.f32"),
+    intrinsic!("log2f64", 0, (F64) -> Returns(F64), llvm: "llvm.log2.f64"),
+    intrinsic!("fmaf32", 0, (F32, F32, F32) -> Returns(F32), llvm: "llvm.fma.f32"),
+    intrinsic!("fmaf64", 0, (F64, F64, F64) -> Returns(F64), llvm: "llvm.fma.f64"),
+    intrinsic!("fabsf32", 0, (F32) -> Returns(F32), llvm: "llvm.fabs.f32"),
+    intrinsic!("fabsf64", 0, (F64) -> Returns(F64), llvm: "llvm.fabs.f64"),
+    intrinsic!("copysignf32", 0, (F32, F32) -> Returns(F32), llvm: "llvm.copysign.f32"),
+    intrinsic!("copysignf64", 0, (F64, F64) -> Returns(F64), llvm: "llvm.copysign.f64"),
+    intrinsic!("floorf32", 0, (F32) -> Returns(F32), llvm: "llvm.floor.f32"),
+    intrinsic!("floorf64", 0, (F64) -> Returns(F64), llvm: "llvm.floor.f64"),
+    intrinsic!("ceilf32", 0, (F32) -> Returns(F32), llvm: "llvm.ceil.f32"),
+    intrinsic!("ceilf64", 0, (F64) -> Returns(F64), llvm: "llvm.ceil.f64"),
+    intrinsic!("truncf32", 0, (F32) -> Returns(F32), llvm: "llvm.trunc.f32"),
+    intrinsic!("truncf64", 0, (F64) -> Returns(F64), llvm: "llvm.trunc.f64"),
+    intrinsic!("rintf32", 0, (F32) -> Returns(F32), llvm: "llvm.rint.f32"),
+    intrinsic!("rintf64", 0, (F64) -> Returns(F64), llvm: "llvm.rint.f64"),
+    intrinsic!("nearbyintf32", 0, (F32) -> Returns(F32), llvm: "llvm.nearbyint.f32"),
+    intrinsic!("nearbyintf64", 0, (F64) -> Returns(F64), llvm: "llvm.nearbyint.f64"),
+    intrinsic!("roundf32", 0, (F32) -> Returns(F32), llvm: "llvm.round.f32"),
+    intrinsic!("roundf64", 0, (F64) -> Returns(F64), llvm: "llvm.round.f64"),
+
+    intrinsic!("ctpop", 1, (Param(0)) -> Returns(Param(0))),
+    intrinsic!("ctlz", 1, (Param(0)) -> Returns(Param(0))),
+    intrinsic!("cttz", 1, (Param(0)) -> Returns(Param(0))),
+    intrinsic!("bswap", 1, (Param(0)) -> Returns(Param(0))),
+    intrinsic!("add_with_overflow", 1, (Param(0), Param(0)) -> Returns(WithOverflow(&Param(0)))),
+    intrinsic!("sub_with_overflow", 1, (Param(0), Param(0)) -> Returns(WithOverflow(&Param(0)))),
+    intrinsic!("mul_with_overflow", 1, (Param(0), Param(0)) -> Returns(WithOverflow(&Param(0)))),
+    intrinsic!("unchecked_div", 1, (Param(0), Param(0)) -> Returns(Param(0))),
+    intrinsic!("unchecked_rem", 1, (Param(0), Param(0)) -> Returns(Param(0))),
+    intrinsic!("overflowing_add", 1, (Param(0), Param(0)) -> Returns(Param(0))),
+    intrinsic!("overflowing_sub", 1, (Param(0), Param(0)) -> Returns(Param(0))),
+    intrinsic!("overflowing_mul", 1, (Param(0), Param(0)) -> Returns(Param(0))),
+];
+
+/// The operations of the `atomic_*` intrinsics, keyed by the `<op>` part of
+/// the intrinsic's name.
+pub static ATOMIC_OPS: &'static [RustIntrinsic] = &[
+    intrinsic!("cxchg", 1, (MutPtr(&Param(0)), Param(0), Param(0)) -> Returns(Param(0))),
+    intrinsic!("cxchgweak", 1,
+               (MutPtr(&Param(0)), Param(0), Param(0)) -> Returns(WithOverflow(&Param(0)))),
+    intrinsic!("load", 1, (ConstPtr(&Param(0))) -> Returns(Param(0))),
+    intrinsic!("store", 1, (MutPtr(&Param(0)), Param(0)) -> Returns(Nil)),
+    intrinsic!("xchg", 1, (MutPtr(&Param(0)), Param(0)) -> Returns(Param(0))),
+    intrinsic!("xadd", 1, (MutPtr(&Param(0)), Param(0)) -> Returns(Param(0))),
+    intrinsic!("xsub", 1, (MutPtr(&Param(0)), Param(0)) -> Returns(Param(0))),
+    intrinsic!("and", 1, (MutPtr(&Param(0)), Param(0)) -> Returns(Param(0))),
+    intrinsic!("nand", 1, (MutPtr(&Param(0)), Param(0)) -> Returns(Param(0))),
+    intrinsic!("or", 1, (MutPtr(&Param(0)), Param(0)) -> Returns(Param(0))),
+    intrinsic!("xor", 1, (MutPtr(&Param(0)), Param(0)) -> Returns(Param(0))),
+    intrinsic!("max", 1, (MutPtr(&Param(0)), Param(0)) -> Returns(Param(0))),
+    intrinsic!("min", 1, (MutPtr(&Param(0)), Param(0)) -> Returns(Param(0))),
+    intrinsic!("umax", 1, (MutPtr(&Param(0)), Param(0)) -> Returns(Param(0))),
+    intrinsic!("umin", 1, (MutPtr(&Param(0)), Param(0)) -> Returns(Param(0))),
+    intrinsic!("fence", 0, () -> Returns(Nil)),
+    intrinsic!("singlethreadfence", 0, () -> Returns(Nil)),
+];
+
+/// Looks up a non-atomic intrinsic by name.
+pub fn find(name: &str) -> Option<&'static RustIntrinsic> {
+    RUST_INTRINSICS.iter().find(|intrinsic| intrinsic.name == name)
+}
+
+/// Looks up the operation of an `atomic_<op>[_<ordering>]` intrinsic by its
+/// `<op>`.
+pub fn find_atomic_op(op: &str) -> Option<&'static RustIntrinsic> {
+    ATOMIC_OPS.iter().find(|intrinsic| intrinsic.name == op)
+}
+
+#[cfg(test)]
+fn declared_intrinsics() -> Vec<&'static str> {
+    let source = include_str!("../../libcore/intrinsics.rs");
+    let mut in_block = false;
+    let mut names = vec![];
+    for line in source.lines() {
+        let line = line.trim();
+        if line.starts_with("extern \"rust-intrinsic\"") {
+            in_block = true;
+        } else if line == "}" {
+            in_block = false;
+        } else if in_block && line.starts_with("pub fn ") {
+            let rest = &line["pub fn ".len()..];
+            let end = rest.find(|c| c == '<' || c == '(').unwrap();
+            names.push(&rest[..end]);
+        }
+    }
+    names
+}
+
+#[test]
+fn every_declared_intrinsic_is_described() {
+    for name in declared_intrinsics() {
+        if name.starts_with("atomic_") {
+            let op = name.split('_').nth(1).unwrap();
+            assert!(find_atomic_op(op).is_some(), "no entry for atomic op of `{}`", name);
+        } else {
+            assert!(find(name).is_some(), "no entry for intrinsic `{}`", name);
+        }
+    }
+}
+
+#[test]
+fn every_described_intrinsic_is_declared() {
+    let declared = declared_intrinsics();
+    for intrinsic in RUST_INTRINSICS {
+        assert!(declared.contains(&intrinsic.name),
+                "`{}` is not declared in libcore", intrinsic.name);
+    }
+    for op in ATOMIC_OPS {
+        assert!(declared.iter().any(|name| name.split('_').nth(1) == Some(op.name)),
+                "no `atomic_{}` intrinsic is declared in libcore", op.name);
+    }
+}
+
+#[test]
+fn intrinsic_signatures_are_well_formed() {
+    fn max_param(ty: &IntrinsicType) -> Option<u8> {
+        match *ty {
+            Param(n) | RefParam(n) => Some(n),
+            ConstPtr(ty) | MutPtr(ty) | WithOverflow(ty) => max_param(ty),
+            _ => None,
+        }
+    }
+
+    for intrinsic in RUST_INTRINSICS.iter().chain(ATOMIC_OPS) {
+        assert_eq!(RUST_INTRINSICS.iter().chain(ATOMIC_OPS)
+                                  .filter(|i| i.name == intrinsic.name).count(), 1);
+        let output = match intrinsic.output {
+            Returns(ty) => Some(ty),
+            Diverges => None,
+        };
+        for ty in intrinsic.inputs.iter().chain(output.iter()) {
+            if let Some(n) = max_param(ty) {
+                assert!((n as usize) < intrinsic.n_tps,
+                        "`{}` uses undeclared type parameter {}", intrinsic.name, n);
+            }
+        }
+        if intrinsic.llvm.is_some() {
+            assert!(intrinsic.n_tps == 0 && !intrinsic.const_eval,
+                    "`{}` cannot be lowered to a single LLVM intrinsic", intrinsic.name);
+        }
+    }
+}
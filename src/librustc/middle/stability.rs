@@ -16,6 +16,7 @@ pub use self::StabilityLevel::*;
 use dep_graph::DepNode;
 use front::map as hir_map;
 use session::Session;
+use session::config::PrintRequest;
 use lint;
 use middle::cstore::{CrateStore, LOCAL_CRATE};
 use middle::def::Def;
@@ -26,7 +27,7 @@ use syntax::parse::token::InternedString;
 use syntax::codemap::{Span, DUMMY_SP};
 use syntax::ast;
 use syntax::ast::{NodeId, Attribute};
-use syntax::feature_gate::{GateIssue, emit_feature_err};
+use syntax::feature_gate::{GateIssue, GateKind, GateUse, emit_feature_err};
 use syntax::attr::{self, Stability, Deprecation, AttrMetaMethods};
 use util::nodemap::{DefIdMap, FnvHashSet, FnvHashMap};
 
@@ -322,16 +323,17 @@ impl<'tcx> Index<'tcx> {
 pub fn check_unstable_api_usage(tcx: &TyCtxt)
                                 -> FnvHashMap<InternedString, StabilityLevel> {
     let _task = tcx.dep_graph.in_task(DepNode::StabilityCheck);
-    let ref active_lib_features = tcx.sess.features.borrow().declared_lib_features;
-
-    // Put the active features into a map for quick lookup
-    let active_features = active_lib_features.iter().map(|&(ref s, _)| s.clone()).collect();
+    // Put the active features into a map for quick lookup. The session's
+    // features are borrowed mutably again while checking, to record gates.
+    let active_features = tcx.sess.features.borrow().declared_lib_features
+                             .iter().map(|&(ref s, _)| s.clone()).collect();
 
     let mut checker = Checker {
         tcx: tcx,
         active_features: active_features,
         used_features: FnvHashMap(),
         in_skip_block: 0,
+        in_trait_impl: false,
     };
     intravisit::walk_crate(&mut checker, tcx.map.krate());
 
@@ -344,6 +346,8 @@ struct Checker<'a, 'tcx: 'a> {
     used_features: FnvHashMap<InternedString, StabilityLevel>,
     // Within a block where feature gate checking can be skipped.
     in_skip_block: u32,
+    // Checking the items of a trait impl against the trait's items.
+    in_trait_impl: bool,
 }
 
 impl<'a, 'tcx> Checker<'a, 'tcx> {
@@ -367,7 +371,20 @@ impl<'a, 'tcx> Checker<'a, 'tcx> {
             Some(&Stability { level: attr::Unstable {ref reason, issue}, ref feature, .. }) => {
                 self.used_features.insert(feature.clone(), Unstable);
 
-                if !self.active_features.contains(feature) {
+                let enabled = self.active_features.contains(feature);
+                if self.tcx.sess.opts.prints.contains(&PrintRequest::Features) {
+                    let gate = GateUse {
+                        feature: feature.clone(),
+                        kind: GateKind::Library,
+                        span: span,
+                        enabled: enabled,
+                    };
+                    self.tcx.sess.features.borrow_mut().gate_uses.insert(gate);
+                }
+
+                if !enabled && self.in_trait_impl {
+                    self.report_unstable_impl_item(id, span, feature, reason, issue);
+                } else if !enabled {
                     let msg = match *reason {
                         Some(ref r) => format!("use of unstable library feature '{}': {}",
                                                &feature, &r),
@@ -404,6 +421,47 @@ impl<'a, 'tcx> Checker<'a, 'tcx> {
             }
         }
     }
+
+    /// Implementing an unstable trait item is a use of it just like calling
+    /// it, but the usual "use of unstable library feature" message is
+    /// confusing when pointing at an item definition. Explain whether the
+    /// item could simply be left out to fall back on the trait's default.
+    fn report_unstable_impl_item(&self, id: DefId, span: Span, feature: &InternedString,
+                                 reason: &Option<InternedString>, issue: u32) {
+        let item_path = self.tcx.item_path_str(id);
+        let mut msg = format!("implementation of unstable trait item `{}`", item_path);
+        if let Some(ref r) = *reason {
+            msg.push_str(&format!(": {}", r));
+        }
+        msg.push_str(&format!(" (see issue #{})", issue));
+
+        let mut err = self.tcx.sess.struct_span_err(span, &msg);
+        if trait_item_has_default(self.tcx, id) {
+            err.span_note(span, "this item has a default in the trait; \
+                                 remove it from the impl to use the default");
+        } else {
+            err.span_note(span, "this item has no default in the trait, so the trait \
+                                 cannot be implemented without the unstable feature");
+        }
+        // #23973: do not suggest `#![feature(...)]` if we are in beta/stable
+        if option_env!("CFG_DISABLE_UNSTABLE_FEATURES").is_none() {
+            err.fileline_help(span, &format!("add #![feature({})] to the \
+                                              crate attributes to enable",
+                                             feature));
+        }
+        err.emit();
+    }
+}
+
+/// Whether a trait item comes with a default that impls may rely on.
+fn trait_item_has_default(tcx: &TyCtxt, id: DefId) -> bool {
+    match tcx.impl_or_trait_item(id) {
+        ty::ConstTraitItem(ref associated_const) => associated_const.has_value,
+        ty::TypeTraitItem(ref associated_type) => associated_type.ty.is_some(),
+        ty::MethodTraitItem(ref method) => {
+            tcx.provided_trait_methods(method.container.id()).iter().any(|m| m.def_id == id)
+        }
+    }
 }
 
 impl<'a, 'v, 'tcx> Visitor<'v> for Checker<'a, 'tcx> {
@@ -416,12 +474,18 @@ impl<'a, 'v, 'tcx> Visitor<'v> for Checker<'a, 'tcx> {
 
     fn visit_item(&mut self, item: &hir::Item) {
         // When compiling with --test we don't enforce stability on the
-        // compiler-generated test module, demarcated with `DUMMY_SP` plus the
-        // name `__test`
-        if item.span == DUMMY_SP && item.name.as_str() == "__test" { return }
-
+        // compiler-generated test module, demarcated with an injected span
+        // plus the name `__test`
+        if self.tcx.sess.codemap().span_is_injected(item.span) &&
+           item.name.as_str() == "__test" { return }
+
+        self.in_trait_impl = match item.node {
+            hir::ItemImpl(_, _, _, Some(_), _, _) => true,
+            _ => false,
+        };
         check_item(self.tcx, item, true,
                    &mut |id, sp, stab, depr| self.check(id, sp, stab, depr));
+        self.in_trait_impl = false;
         intravisit::walk_item(self, item);
     }
 
@@ -470,8 +534,11 @@ pub fn check_item(tcx: &TyCtxt, item: &hir::Item, warn_about_defns: bool,
                   cb: &mut FnMut(DefId, Span, &Option<&Stability>, &Option<Deprecation>)) {
     match item.node {
         hir::ItemExternCrate(_) => {
-            // compiler-generated `extern crate` items have a dummy span.
-            if item.span == DUMMY_SP { return }
+            // compiler-generated `extern crate` items have a dummy or an
+            // injected span.
+            if item.span == DUMMY_SP || tcx.sess.codemap().span_is_injected(item.span) {
+                return
+            }
 
             let cnum = match tcx.sess.cstore.extern_mod_stmt_cnum(item.id) {
                 Some(cnum) => cnum,
@@ -35,6 +35,17 @@ use syntax::codemap::{Span, DUMMY_SP};
 pub struct Substs<'tcx> {
     pub types: VecPerParamSpace<Ty<'tcx>>,
     pub regions: RegionSubsts,
+    /// The values of const parameters. Only items whose generics declare
+    /// const parameters have them; everywhere else this is empty.
+    pub consts: VecPerParamSpace<ConstArg>,
+}
+
+/// The value given to a const parameter: the bits of an integer or `bool`,
+/// or a const parameter of the item being checked.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ConstArg {
+    Value(u64),
+    Param(ty::ParamConst),
 }
 
 /// Represents the values to use when substituting lifetime parameters.
@@ -51,7 +62,7 @@ impl<'tcx> Substs<'tcx> {
                r: VecPerParamSpace<ty::Region>)
                -> Substs<'tcx>
     {
-        Substs { types: t, regions: NonerasedRegions(r) }
+        Substs { types: t, regions: NonerasedRegions(r), consts: VecPerParamSpace::empty() }
     }
 
     pub fn new_type(t: Vec<Ty<'tcx>>,
@@ -73,20 +84,22 @@ impl<'tcx> Substs<'tcx> {
 
     pub fn erased(t: VecPerParamSpace<Ty<'tcx>>) -> Substs<'tcx>
     {
-        Substs { types: t, regions: ErasedRegions }
+        Substs { types: t, regions: ErasedRegions, consts: VecPerParamSpace::empty() }
     }
 
     pub fn empty() -> Substs<'tcx> {
         Substs {
             types: VecPerParamSpace::empty(),
             regions: NonerasedRegions(VecPerParamSpace::empty()),
+            consts: VecPerParamSpace::empty(),
         }
     }
 
     pub fn trans_empty() -> Substs<'tcx> {
         Substs {
             types: VecPerParamSpace::empty(),
-            regions: ErasedRegions
+            regions: ErasedRegions,
+            consts: VecPerParamSpace::empty(),
         }
     }
 
@@ -96,7 +109,12 @@ impl<'tcx> Substs<'tcx> {
             NonerasedRegions(ref regions) => regions.is_empty(),
         };
 
-        regions_is_noop && self.types.is_empty()
+        regions_is_noop && self.types.is_empty() && self.consts.is_empty()
+    }
+
+    /// These substs with `consts` as the values of const parameters.
+    pub fn with_consts(self, consts: VecPerParamSpace<ConstArg>) -> Substs<'tcx> {
+        Substs { consts: consts, ..self }
     }
 
     pub fn type_for_def(&self, ty_param_def: &ty::TypeParameterDef) -> Ty<'tcx> {
@@ -115,8 +133,7 @@ impl<'tcx> Substs<'tcx> {
     }
 
     pub fn erase_regions(self) -> Substs<'tcx> {
-        let Substs { types, regions: _ } = self;
-        Substs { types: types, regions: ErasedRegions }
+        Substs { regions: ErasedRegions, ..self }
     }
 
     /// Since ErasedRegions are only to be used in trans, most of the compiler can use this method
@@ -142,34 +159,36 @@ impl<'tcx> Substs<'tcx> {
                        m_regions: Vec<ty::Region>)
                        -> Substs<'tcx>
     {
-        let Substs { types, regions } = self;
+        let Substs { types, regions, consts } = self;
         let types = types.with_slice(FnSpace, &m_types);
         let regions = regions.map(|r| r.with_slice(FnSpace, &m_regions));
-        Substs { types: types, regions: regions }
+        Substs { types: types, regions: regions, consts: consts }
     }
 
     pub fn with_method_from(&self,
                             meth_substs: &Substs<'tcx>)
                             -> Substs<'tcx>
     {
-        let Substs { types, regions } = self.clone();
+        let Substs { types, regions, consts } = self.clone();
         let types = types.with_slice(FnSpace, meth_substs.types.get_slice(FnSpace));
         let regions = regions.map(|r| {
             r.with_slice(FnSpace, meth_substs.regions().get_slice(FnSpace))
         });
-        Substs { types: types, regions: regions }
+        let consts = consts.with_slice(FnSpace, meth_substs.consts.get_slice(FnSpace));
+        Substs { types: types, regions: regions, consts: consts }
     }
 
     /// Creates a trait-ref out of this substs, ignoring the FnSpace substs
     pub fn to_trait_ref(&self, tcx: &TyCtxt<'tcx>, trait_id: DefId)
                         -> ty::TraitRef<'tcx> {
-        let Substs { mut types, regions } = self.clone();
+        let Substs { mut types, regions, mut consts } = self.clone();
         types.truncate(FnSpace, 0);
         let regions = regions.map(|mut r| { r.truncate(FnSpace, 0); r });
+        consts.truncate(FnSpace, 0);
 
         ty::TraitRef {
             def_id: trait_id,
-            substs: tcx.mk_substs(Substs { types: types, regions: regions })
+            substs: tcx.mk_substs(Substs { types: types, regions: regions, consts: consts })
         }
     }
 }
@@ -697,6 +716,13 @@ impl<'a, 'tcx> TypeFolder<'tcx> for SubstFolder<'a, 'tcx> {
             ty::TyParam(p) => {
                 self.ty_for_param(p, t)
             }
+            ty::TyParamArray(elem, p) => {
+                let elem = self.fold_ty(elem);
+                match self.fold_const_arg(ConstArg::Param(p)) {
+                    ConstArg::Value(len) => self.tcx().mk_array(elem, len as usize),
+                    ConstArg::Param(p) => self.tcx().mk_param_array(elem, p),
+                }
+            }
             _ => {
                 t.super_fold_with(self)
             }
@@ -710,6 +736,31 @@ impl<'a, 'tcx> TypeFolder<'tcx> for SubstFolder<'a, 'tcx> {
 
         return t1;
     }
+
+    fn fold_const_arg(&mut self, c: ConstArg) -> ConstArg {
+        let p = match c {
+            ConstArg::Param(p) => p,
+            ConstArg::Value(_) => return c,
+        };
+        match self.substs.consts.opt_get(p.space, p.idx as usize) {
+            Some(&arg) => arg,
+            // Wrong const arguments are reported and then left out of the
+            // substs, leaving the parameter for later errors to skip over.
+            None if self.tcx().sess.has_errors() => c,
+            None => {
+                let span = self.span.unwrap_or(DUMMY_SP);
+                self.tcx().sess.span_bug(
+                    span,
+                    &format!("Const parameter `{:?}` ({:?}/{}) out of range \
+                              when substituting (root type={:?}) substs={:?}",
+                             p,
+                             p.space,
+                             p.idx,
+                             self.root_ty,
+                             self.substs));
+            }
+        }
+    }
 }
 
 impl<'a,'tcx> SubstFolder<'a,'tcx> {
@@ -260,12 +260,14 @@ fn ty_is_local_constructor<'tcx>(tcx: &TyCtxt<'tcx>,
         ty::TyFnDef(..) |
         ty::TyFnPtr(_) |
         ty::TyArray(..) |
+        ty::TyParamArray(..) |
         ty::TySlice(..) |
         ty::TyRawPtr(..) |
         ty::TyRef(..) |
         ty::TyTuple(..) |
         ty::TyParam(..) |
-        ty::TyProjection(..) => {
+        ty::TyProjection(..) |
+        ty::TyAnon(..) => {
             false
         }
 
@@ -21,17 +21,25 @@ use super::{
     ObjectSafetyViolation,
     MethodViolationCode,
     object_safety_violations,
+    SelectionContext,
+    VtableImpl,
 };
 
 use fmt_macros::{Parser, Piece, Position};
+use front::map as hir_map;
+use middle::cstore::{CrateStore, DlDef};
+use middle::def::Def;
 use middle::def_id::DefId;
 use middle::infer::InferCtxt;
+use middle::subst;
 use middle::ty::{self, ToPredicate, ToPolyTraitRef, TraitRef, Ty, TyCtxt, TypeFoldable};
 use middle::ty::fast_reject;
 use util::nodemap::{FnvHashMap, FnvHashSet};
 
 use std::cmp;
 use std::fmt;
+use rustc_front::hir;
+use syntax::ast;
 use syntax::attr::{AttributeMethods, AttrMetaMethods};
 use syntax::codemap::Span;
 use syntax::errors::DiagnosticBuilder;
@@ -414,6 +422,7 @@ pub fn report_selection_error<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
                                     }
                                 }
                             }
+                            note_near_miss_impls(infcx, &mut err, obligation, &trait_ref);
                             note_obligation_cause(infcx, &mut err, obligation);
                             err.emit();
                         }
@@ -507,6 +516,158 @@ pub fn report_selection_error<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
     }
 }
 
+/// Looks for impls that "almost" satisfy the failed obligation `trait_ref`:
+/// the trait being implemented for a reference to the self type, or for the
+/// referent when the self type is itself a reference, or a sibling trait
+/// (see `sibling_traits`) being implemented for the self type. Each hit is
+/// reported together with the location of the matching impl.
+fn note_near_miss_impls<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                  err: &mut DiagnosticBuilder,
+                                  obligation: &PredicateObligation<'tcx>,
+                                  trait_ref: &ty::PolyTraitRef<'tcx>)
+{
+    let tcx = infcx.tcx;
+    let self_ty = trait_ref.self_ty();
+    if self_ty.references_error() || self_ty.has_infer_types() {
+        return;
+    }
+
+    let candidates = match self_ty.sty {
+        ty::TyRef(_, ty::TypeAndMut { ty, .. }) => vec![ty],
+        _ => {
+            let region = tcx.mk_region(ty::ReStatic);
+            vec![tcx.mk_imm_ref(region, self_ty), tcx.mk_mut_ref(region, self_ty)]
+        }
+    };
+
+    for candidate in candidates {
+        let mut substs = trait_ref.0.substs.clone();
+        substs.types.replace(subst::SelfSpace, vec![candidate]);
+        let candidate_ref = ty::TraitRef {
+            def_id: trait_ref.def_id(),
+            substs: tcx.mk_substs(substs),
+        };
+        let msg = format!("the trait `{}` is implemented for `{}`, but not for `{}`",
+                          tcx.item_path_str(trait_ref.def_id()),
+                          candidate,
+                          self_ty);
+        note_applicable_impl(infcx, err, obligation, candidate_ref, &msg);
+    }
+
+    for sibling in sibling_traits(tcx, trait_ref.def_id()) {
+        let sibling_ref = ty::TraitRef {
+            def_id: sibling,
+            substs: trait_ref.0.substs,
+        };
+        let msg = format!("the similar trait `{}` is implemented for `{}`",
+                          tcx.item_path_str(sibling),
+                          self_ty);
+        note_applicable_impl(infcx, err, obligation, sibling_ref, &msg);
+    }
+}
+
+/// Notes `msg` at the impl that satisfies `trait_ref`, if there is one and
+/// its where-clauses hold.
+fn note_applicable_impl<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                  err: &mut DiagnosticBuilder,
+                                  obligation: &PredicateObligation<'tcx>,
+                                  trait_ref: ty::TraitRef<'tcx>,
+                                  msg: &str)
+{
+    let tcx = infcx.tcx;
+    let trait_obligation = obligation.with(ty::Binder(ty::TraitPredicate {
+        trait_ref: trait_ref
+    }));
+
+    let impl_def_id = infcx.probe(|_| {
+        let mut selcx = SelectionContext::new(infcx);
+        // Selection only matches the impl header; the impl's own predicates
+        // come back as nested obligations.
+        match selcx.select(&trait_obligation) {
+            Ok(Some(VtableImpl(data))) => {
+                if data.nested.iter().all(|nested| selcx.evaluate_obligation(nested)) {
+                    Some(data.impl_def_id)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    });
+
+    if let Some(impl_def_id) = impl_def_id {
+        match tcx.map.span_if_local(impl_def_id) {
+            Some(impl_span) => {
+                err.span_note(impl_span, msg);
+            }
+            None => {
+                err.fileline_note(obligation.cause.span,
+                                  &format!("{} (see the impl at `{}`)",
+                                           msg,
+                                           tcx.def_path_str(impl_def_id)));
+            }
+        }
+    }
+}
+
+/// The traits defined in the same module as `trait_def_id` that take the
+/// same parameters and have items of the same names, such as `fmt::Debug`
+/// for `fmt::Display`. A type that implements one of them was probably
+/// meant to be used with it instead.
+fn sibling_traits(tcx: &TyCtxt, trait_def_id: DefId) -> Vec<DefId> {
+    let parent = match tcx.def_key(trait_def_id).parent {
+        Some(index) => DefId { krate: trait_def_id.krate, index: index },
+        None => return vec![],
+    };
+
+    let neighbours: Vec<DefId> = match tcx.map.as_local_node_id(parent) {
+        Some(node_id) => {
+            let module = if node_id == ast::CRATE_NODE_ID {
+                &tcx.map.krate().module
+            } else {
+                match tcx.map.find(node_id) {
+                    Some(hir_map::NodeItem(item)) => match item.node {
+                        hir::ItemMod(ref m) => m,
+                        _ => return vec![],
+                    },
+                    _ => return vec![],
+                }
+            };
+            module.item_ids.iter().filter_map(|item_id| {
+                match tcx.map.expect_item(item_id.id).node {
+                    hir::ItemTrait(..) => Some(tcx.map.local_def_id(item_id.id)),
+                    _ => None,
+                }
+            }).collect()
+        }
+        None => {
+            tcx.sess.cstore.item_children(parent).into_iter().filter_map(|child| {
+                match child.def {
+                    DlDef(Def::Trait(def_id)) => Some(def_id),
+                    _ => None,
+                }
+            }).collect()
+        }
+    };
+
+    let shape = |def_id: DefId| {
+        let generics = &tcx.lookup_trait_def(def_id).generics;
+        let mut names: Vec<_> = tcx.trait_item_def_ids(def_id).iter()
+                                   .map(|item| tcx.item_name(item.def_id()))
+                                   .collect();
+        names.sort();
+        (generics.types.len(subst::TypeSpace), generics.regions.len(subst::TypeSpace), names)
+    };
+
+    let trait_shape = shape(trait_def_id);
+    if trait_shape.2.is_empty() {
+        return vec![];
+    }
+    neighbours.into_iter()
+              .filter(|&def_id| def_id != trait_def_id && shape(def_id) == trait_shape)
+              .collect()
+}
+
 pub fn report_object_safety_error<'tcx>(tcx: &TyCtxt<'tcx>,
                                         span: Span,
                                         trait_def_id: DefId,
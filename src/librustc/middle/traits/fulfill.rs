@@ -24,6 +24,7 @@ use super::is_object_safe;
 use super::FulfillmentError;
 use super::FulfillmentErrorCode;
 use super::ObligationCause;
+use super::observer::notify_observers;
 use super::PredicateObligation;
 use super::project;
 use super::report_overflow_error_cycle;
@@ -166,10 +167,10 @@ impl<'tcx> FulfillmentContext<'tcx> {
         }
     }
 
-    pub fn register_region_obligation<'a>(&mut self,
-                                          t_a: Ty<'tcx>,
-                                          r_b: ty::Region,
-                                          cause: ObligationCause<'tcx>)
+    pub fn register_region_obligation(&mut self,
+                                      t_a: Ty<'tcx>,
+                                      r_b: ty::Region,
+                                      cause: ObligationCause<'tcx>)
     {
         register_region_obligation(t_a, r_b, cause, &mut self.region_obligations);
     }
@@ -190,6 +191,7 @@ impl<'tcx> FulfillmentContext<'tcx> {
         }
 
         debug!("register_predicate_obligation({:?})", obligation);
+        notify_observers(infcx.tcx, |o| o.registered(infcx.tcx, &obligation));
         let obligation = PendingPredicateObligation {
             obligation: obligation,
             stalled_on: vec![]
@@ -217,6 +219,9 @@ impl<'tcx> FulfillmentContext<'tcx> {
                            .into_iter()
                            .map(|e| to_fulfillment_error(e))
                            .collect();
+        for error in &errors {
+            notify_observers(infcx.tcx, |o| o.failed(infcx.tcx, error));
+        }
         if errors.is_empty() {
             Ok(())
         } else {
@@ -290,14 +295,18 @@ impl<'tcx> FulfillmentContext<'tcx> {
             debug!("select: outcome={:?}", outcome);
 
             // these are obligations that were proven to be true.
+            let tcx = selcx.tcx();
             for pending_obligation in outcome.completed {
-                let predicate = &pending_obligation.obligation.predicate;
-                selcx.tcx().fulfilled_predicates.borrow_mut().add_if_global(predicate);
+                let obligation = &pending_obligation.obligation;
+                tcx.fulfilled_predicates.borrow_mut().add_if_global(&obligation.predicate);
+                notify_observers(tcx, |o| o.fulfilled(tcx, obligation));
             }
 
-            errors.extend(
-                outcome.errors.into_iter()
-                              .map(|e| to_fulfillment_error(e)));
+            for error in outcome.errors {
+                let error = to_fulfillment_error(error);
+                notify_observers(tcx, |o| o.failed(tcx, &error));
+                errors.push(error);
+            }
 
             // If nothing new was added, no need to keep looping.
             if outcome.stalled {
@@ -647,9 +656,9 @@ fn coinductive_match<'a,'tcx>(selcx: &mut SelectionContext<'a,'tcx>,
     false
 }
 
-fn scan_for_cycle<'a,'tcx>(top_obligation: &PredicateObligation<'tcx>,
-                           backtrace: &Backtrace<PendingPredicateObligation<'tcx>>)
-                           -> Option<Vec<PredicateObligation<'tcx>>>
+fn scan_for_cycle<'tcx>(top_obligation: &PredicateObligation<'tcx>,
+                        backtrace: &Backtrace<PendingPredicateObligation<'tcx>>)
+                        -> Option<Vec<PredicateObligation<'tcx>>>
 {
     let mut map = FnvHashMap();
     let all_obligations =
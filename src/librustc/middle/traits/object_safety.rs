@@ -24,6 +24,7 @@ use middle::def_id::DefId;
 use middle::subst::{self, SelfSpace, TypeSpace};
 use middle::traits;
 use middle::ty::{self, ToPolyTraitRef, Ty, TyCtxt, TypeFoldable};
+use util::nodemap::FnvHashSet;
 use std::rc::Rc;
 use syntax::ast;
 
@@ -265,17 +266,19 @@ fn virtual_call_violation_for_method<'tcx>(tcx: &TyCtxt<'tcx>,
     }
 
     // The `Self` type is erased, so it should not appear in list of
-    // arguments or return type apart from the receiver.
-    let ref sig = method.fty.sig;
-    for &input_ty in &sig.0.inputs[1..] {
-        if contains_illegal_self_type_reference(tcx, trait_def_id, input_ty) {
-            return Some(MethodViolationCode::ReferencesSelf);
-        }
-    }
-    if let ty::FnConverging(result_type) = sig.0.output {
-        if contains_illegal_self_type_reference(tcx, trait_def_id, result_type) {
-            return Some(MethodViolationCode::ReferencesSelf);
+    // arguments or return type apart from the receiver. This is usually
+    // recorded on the trait when it is collected.
+    let recorded = match method.container {
+        ty::TraitContainer(def_id) => {
+            tcx.lookup_trait_def(def_id).method_references_self(method.name)
         }
+        ty::ImplContainer(_) => None,
+    };
+    let references_self = recorded.unwrap_or_else(|| {
+        method_references_self(tcx, trait_def_id, method)
+    });
+    if references_self {
+        return Some(MethodViolationCode::ReferencesSelf);
     }
 
     // We can't monomorphize things like `fn foo<A>(...)`.
@@ -286,6 +289,52 @@ fn virtual_call_violation_for_method<'tcx>(tcx: &TyCtxt<'tcx>,
     None
 }
 
+/// Records on the trait definition which of its methods mention `Self`
+/// outside of their receiver. This is done while the trait is collected,
+/// once its supertraits are known, so that later object safety checks (in
+/// this crate or, via metadata, any other) need not walk the method
+/// signatures again.
+pub fn record_methods_referencing_self<'tcx>(tcx: &TyCtxt<'tcx>, trait_def_id: DefId) {
+    let names: FnvHashSet<_> = tcx.trait_items(trait_def_id).iter().filter_map(|item| {
+        match *item {
+            ty::MethodTraitItem(ref m) if method_references_self(tcx, trait_def_id, m) => {
+                Some(m.name)
+            }
+            _ => None,
+        }
+    }).collect();
+
+    debug!("record_methods_referencing_self(trait_def_id={:?}) = {:?}",
+           trait_def_id,
+           names);
+
+    tcx.lookup_trait_def(trait_def_id).set_methods_referencing_self(names);
+}
+
+/// Returns true if `Self` appears in the arguments or return type of
+/// `method`, other than in its receiver or in a projection out of one of
+/// the trait's supertraits.
+fn method_references_self<'tcx>(tcx: &TyCtxt<'tcx>,
+                                trait_def_id: DefId,
+                                method: &ty::Method<'tcx>)
+                                -> bool
+{
+    let ref sig = method.fty.sig;
+    let skip = match method.explicit_self {
+        ty::ExplicitSelfCategory::Static => 0,
+        _ => 1,
+    };
+    let inputs_reference_self = sig.0.inputs.iter().skip(skip).any(|&input_ty| {
+        contains_illegal_self_type_reference(tcx, trait_def_id, input_ty)
+    });
+    inputs_reference_self || match sig.0.output {
+        ty::FnConverging(result_type) => {
+            contains_illegal_self_type_reference(tcx, trait_def_id, result_type)
+        }
+        ty::FnDiverging => false,
+    }
+}
+
 fn contains_illegal_self_type_reference<'tcx>(tcx: &TyCtxt<'tcx>,
                                               trait_def_id: DefId,
                                               ty: Ty<'tcx>)
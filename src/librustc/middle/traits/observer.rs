@@ -0,0 +1,47 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Hooks for watching trait obligations being resolved.
+
+use middle::ty::TyCtxt;
+
+use super::{FulfillmentError, PredicateObligation};
+
+/// Something told about the obligations of every `FulfillmentContext`:
+/// when each is registered, and then when it is proven to hold or found
+/// not to. An obligation that duplicates one already registered in the same
+/// context, or already proven globally, is dropped without being reported.
+///
+/// The obligation's `cause` says where it came from (e.g. which where clause
+/// of which item), so an observer can build up explanations of why some bound
+/// had to hold, or rank the errors differently than the compiler does.
+///
+/// Observers live in `Session::obligation_observers`; plugins add theirs
+/// with `Registry::register_obligation_observer`. They are called in the
+/// middle of trait selection, so they must not start any themselves.
+pub trait ObligationObserver<'tcx> {
+    fn registered(&mut self, _tcx: &TyCtxt<'tcx>, _obligation: &PredicateObligation<'tcx>) { }
+
+    fn fulfilled(&mut self, _tcx: &TyCtxt<'tcx>, _obligation: &PredicateObligation<'tcx>) { }
+
+    fn failed(&mut self, _tcx: &TyCtxt<'tcx>, _error: &FulfillmentError<'tcx>) { }
+}
+
+pub type ObligationObserverObject = Box<for<'tcx> ObligationObserver<'tcx>>;
+
+/// Calls `f` on each of the session's observers.
+pub fn notify_observers<'tcx, F>(tcx: &TyCtxt<'tcx>, mut f: F)
+    where F: FnMut(&mut ObligationObserver<'tcx>)
+{
+    let mut observers = tcx.sess.obligation_observers.borrow_mut();
+    for observer in observers.iter_mut() {
+        f(&mut **observer);
+    }
+}
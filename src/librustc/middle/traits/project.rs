@@ -21,10 +21,13 @@ use super::VtableClosureData;
 use super::VtableImplData;
 use super::util;
 
+use middle::def_id::DefId;
 use middle::infer::{self, TypeOrigin};
 use middle::subst::Subst;
 use middle::ty::{self, ToPredicate, ToPolyTraitRef, Ty, TyCtxt};
 use middle::ty::fold::{TypeFoldable, TypeFolder};
+use std::rc::Rc;
+use syntax::ast;
 use syntax::parse::token;
 use util::common::FN_OUTPUT_NAME;
 
@@ -616,9 +619,11 @@ fn assemble_candidates_from_trait_def<'cx,'tcx>(
 {
     debug!("assemble_candidates_from_trait_def(..)");
 
-    // Check whether the self-type is itself a projection.
-    let trait_ref = match obligation_trait_ref.self_ty().sty {
-        ty::TyProjection(ref data) => data.trait_ref.clone(),
+    // Check whether the self-type is itself a projection, or an
+    // anonymized type whose bounds may mention the projection.
+    let (def_id, substs) = match obligation_trait_ref.self_ty().sty {
+        ty::TyProjection(ref data) => (data.trait_ref.def_id, data.trait_ref.substs),
+        ty::TyAnon(def_id, substs) => (def_id, substs),
         ty::TyInfer(ty::TyVar(_)) => {
             // If the self-type is an inference variable, then it MAY wind up
             // being a projected type, so induce an ambiguity.
@@ -629,8 +634,8 @@ fn assemble_candidates_from_trait_def<'cx,'tcx>(
     };
 
     // If so, extract what we know from the trait and try to come up with a good answer.
-    let trait_predicates = selcx.tcx().lookup_predicates(trait_ref.def_id);
-    let bounds = trait_predicates.instantiate(selcx.tcx(), trait_ref.substs);
+    let trait_predicates = selcx.tcx().lookup_predicates(def_id);
+    let bounds = trait_predicates.instantiate(selcx.tcx(), substs);
     let bounds = elaborate_predicates(selcx.tcx(), bounds.predicates.into_vec());
     assemble_candidates_from_predicates(selcx,
                                         obligation,
@@ -745,6 +750,24 @@ fn assemble_candidates_from_impls<'cx,'tcx>(
             debug!("assemble_candidates_from_impls: impl candidate {:?}",
                    data);
 
+            // A `default` associated type may still be overridden by a more
+            // specialized impl, so we can only use it once we know the
+            // types involved exactly.
+            if impl_assoc_ty_is_default(selcx.tcx(), data.impl_def_id,
+                                        obligation.predicate.item_name) {
+                if obligation_trait_ref.has_infer_types() {
+                    candidate_set.ambiguous = true;
+                    return Ok(());
+                }
+                if obligation_trait_ref.has_param_types() ||
+                   obligation_trait_ref.has_self_ty() ||
+                   obligation_trait_ref.has_projection_types() {
+                    debug!("assemble_candidates_from_impls: not revealing default \
+                            associated type of {:?}", data.impl_def_id);
+                    return Ok(());
+                }
+            }
+
             candidate_set.vec.push(
                 ProjectionTyCandidate::Impl(data));
         }
@@ -935,23 +958,46 @@ fn confirm_param_env_candidate<'cx,'tcx>(
     (projection.ty, vec!())
 }
 
+/// Finds the definition of the associated type `item_name` for an impl,
+/// looking through the impls it specializes if it doesn't define one itself.
+fn find_impl_assoc_ty<'tcx>(tcx: &TyCtxt<'tcx>, impl_def_id: DefId, item_name: ast::Name)
+                            -> Option<(DefId, Rc<ty::AssociatedType<'tcx>>)> {
+    let found = super::find_impl_item(tcx, impl_def_id, |item| {
+        match *item {
+            ty::TypeTraitItem(ref assoc_ty) => assoc_ty.name == item_name,
+            _ => false,
+        }
+    });
+    match found {
+        Some((item_impl, ty::TypeTraitItem(assoc_ty))) => Some((item_impl, assoc_ty)),
+        _ => None,
+    }
+}
+
+fn impl_assoc_ty_is_default(tcx: &TyCtxt, impl_def_id: DefId, item_name: ast::Name) -> bool {
+    match find_impl_assoc_ty(tcx, impl_def_id, item_name) {
+        Some((_, assoc_ty)) => assoc_ty.defaultness.is_default(),
+        None => false,
+    }
+}
+
 fn confirm_impl_candidate<'cx,'tcx>(
     selcx: &mut SelectionContext<'cx,'tcx>,
     obligation: &ProjectionTyObligation<'tcx>,
     impl_vtable: VtableImplData<'tcx, PredicateObligation<'tcx>>)
     -> (Ty<'tcx>, Vec<PredicateObligation<'tcx>>)
 {
-    // there don't seem to be nicer accessors to these:
-    let impl_or_trait_items_map = selcx.tcx().impl_or_trait_items.borrow();
-
-    // Look for the associated type in the impl
-    for impl_item in &selcx.tcx().impl_items.borrow()[&impl_vtable.impl_def_id] {
-        if let ty::TypeTraitItem(ref assoc_ty) = impl_or_trait_items_map[&impl_item.def_id()] {
-            if assoc_ty.name == obligation.predicate.item_name {
-                return (assoc_ty.ty.unwrap().subst(selcx.tcx(), impl_vtable.substs),
-                        impl_vtable.nested);
-            }
-        }
+    // Look for the associated type in the impl, or in the impls it
+    // specializes.
+    let tcx = selcx.tcx();
+    let impl_def_id = impl_vtable.impl_def_id;
+    if let Some((item_impl, assoc_ty)) =
+            find_impl_assoc_ty(tcx, impl_def_id, obligation.predicate.item_name) {
+        let substs = super::translate_substs(selcx.infcx(),
+                                             impl_def_id,
+                                             impl_vtable.substs,
+                                             item_impl);
+        return (assoc_ty.ty.unwrap().subst(tcx, &substs), impl_vtable.nested);
     }
 
     // It is not in the impl - get the default from the trait.
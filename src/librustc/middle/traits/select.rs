@@ -210,6 +210,10 @@ enum SelectionCandidate<'tcx> {
     BuiltinObjectCandidate,
 
     BuiltinUnsizeCandidate,
+
+    /// The trait is an alias (`trait Foo = Bar + Send;`), which holds
+    /// exactly when all of its bounds hold.
+    TraitAliasCandidate(DefId),
 }
 
 struct SelectionCandidateSet<'tcx> {
@@ -1044,6 +1048,9 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
 
         self.assemble_candidates_from_projected_tys(obligation, &mut candidates);
         try!(self.assemble_candidates_from_caller_bounds(stack, &mut candidates));
+        if candidates.vec.is_empty() {
+            self.assemble_candidates_for_trait_alias(obligation, &mut candidates);
+        }
         // Default implementations have lower priority, so we only
         // consider triggering a default if there is no other impl that can apply.
         if candidates.vec.is_empty() {
@@ -1053,6 +1060,17 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
         Ok(candidates)
     }
 
+    fn assemble_candidates_for_trait_alias(&mut self,
+                                           obligation: &TraitObligation<'tcx>,
+                                           candidates: &mut SelectionCandidateSet<'tcx>)
+    {
+        let def_id = obligation.predicate.def_id();
+        if self.tcx().lookup_trait_def(def_id).is_alias() {
+            debug!("assemble_candidates_for_trait_alias({:?})", obligation);
+            candidates.vec.push(TraitAliasCandidate(def_id));
+        }
+    }
+
     fn assemble_candidates_from_projected_tys(&mut self,
                                               obligation: &TraitObligation<'tcx>,
                                               candidates: &mut SelectionCandidateSet<'tcx>)
@@ -1062,9 +1080,11 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
         // FIXME(#20297) -- just examining the self-type is very simplistic
 
         // before we go into the whole skolemization thing, just
-        // quickly check if the self-type is a projection at all.
-        let trait_def_id = match obligation.predicate.0.trait_ref.self_ty().sty {
+        // quickly check if the self-type is a projection (or an
+        // anonymized type, whose bounds act the same way) at all.
+        let def_id = match obligation.predicate.0.trait_ref.self_ty().sty {
             ty::TyProjection(ref data) => data.trait_ref.def_id,
+            ty::TyAnon(def_id, _) => def_id,
             ty::TyInfer(ty::TyVar(_)) => {
                 self.tcx().sess.span_bug(obligation.cause.span,
                     "Self=_ should have been handled by assemble_candidates");
@@ -1072,8 +1092,8 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
             _ => { return; }
         };
 
-        debug!("assemble_candidates_for_projected_tys: trait_def_id={:?}",
-               trait_def_id);
+        debug!("assemble_candidates_for_projected_tys: def_id={:?}",
+               def_id);
 
         let result = self.infcx.probe(|snapshot| {
             self.match_projection_obligation_against_bounds_from_trait(obligation,
@@ -1100,8 +1120,9 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
                skol_trait_predicate,
                skol_map);
 
-        let projection_trait_ref = match skol_trait_predicate.trait_ref.self_ty().sty {
-            ty::TyProjection(ref data) => &data.trait_ref,
+        let (def_id, substs) = match skol_trait_predicate.trait_ref.self_ty().sty {
+            ty::TyProjection(ref data) => (data.trait_ref.def_id, data.trait_ref.substs),
+            ty::TyAnon(def_id, substs) => (def_id, substs),
             _ => {
                 self.tcx().sess.span_bug(
                     obligation.cause.span,
@@ -1111,11 +1132,11 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
             }
         };
         debug!("match_projection_obligation_against_bounds_from_trait: \
-                projection_trait_ref={:?}",
-               projection_trait_ref);
+                def_id={:?}, substs={:?}",
+               def_id, substs);
 
-        let trait_predicates = self.tcx().lookup_predicates(projection_trait_ref.def_id);
-        let bounds = trait_predicates.instantiate(self.tcx(), projection_trait_ref.substs);
+        let predicates = self.tcx().lookup_predicates(def_id);
+        let bounds = predicates.instantiate(self.tcx(), substs);
         debug!("match_projection_obligation_against_bounds_from_trait: \
                 bounds={:?}",
                bounds);
@@ -1365,7 +1386,8 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
                     }
                 }
                 ty::TyParam(..) |
-                ty::TyProjection(..) => {
+                ty::TyProjection(..) |
+                ty::TyAnon(..) => {
                     // In these cases, we don't know what the actual
                     // type is.  Therefore, we cannot break it down
                     // into its constituent types. So we don't
@@ -1533,7 +1555,8 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
             }
 
             // [T; n] -> [T].
-            (&ty::TyArray(_, _), &ty::TySlice(_)) => true,
+            (&ty::TyArray(_, _), &ty::TySlice(_)) |
+            (&ty::TyParamArray(_, _), &ty::TySlice(_)) => true,
 
             // Struct<T> -> Struct<U>.
             (&ty::TyStruct(def_id_a, _), &ty::TyStruct(def_id_b, _)) => {
@@ -1565,10 +1588,10 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
     /// candidates and prefer where-clause candidates.
     ///
     /// See the comment for "SelectionCandidate" for more details.
-    fn candidate_should_be_dropped_in_favor_of<'o>(&mut self,
-                                                   victim: &SelectionCandidate<'tcx>,
-                                                   other: &SelectionCandidate<'tcx>)
-                                                   -> bool
+    fn candidate_should_be_dropped_in_favor_of(&mut self,
+                                               victim: &SelectionCandidate<'tcx>,
+                                               other: &SelectionCandidate<'tcx>)
+                                               -> bool
     {
         if victim == other {
             return true;
@@ -1588,6 +1611,7 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
                 &BuiltinObjectCandidate |
                 &BuiltinUnsizeCandidate |
                 &DefaultImplObjectCandidate(..) |
+                &TraitAliasCandidate(..) |
                 &BuiltinCandidate(..) => {
                     // We have a where-clause so don't go around looking
                     // for impls.
@@ -1601,6 +1625,13 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
                 },
                 &ParamCandidate(..) => false,
             },
+            &ImplCandidate(other_def) => match victim {
+                // Prefer an impl over the impls it specializes.
+                &ImplCandidate(victim_def) => {
+                    super::is_specialization_ancestor(self.tcx(), other_def, victim_def)
+                }
+                _ => false
+            },
             _ => false
         }
     }
@@ -1614,11 +1645,11 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
     // those will hopefully change to library-defined traits in the
     // future.
 
-    fn assemble_builtin_bound_candidates<'o>(&mut self,
-                                             bound: ty::BuiltinBound,
-                                             obligation: &TraitObligation<'tcx>,
-                                             candidates: &mut SelectionCandidateSet<'tcx>)
-                                             -> Result<(),SelectionError<'tcx>>
+    fn assemble_builtin_bound_candidates(&mut self,
+                                         bound: ty::BuiltinBound,
+                                         obligation: &TraitObligation<'tcx>,
+                                         candidates: &mut SelectionCandidateSet<'tcx>)
+                                         -> Result<(),SelectionError<'tcx>>
     {
         match self.builtin_bound(bound, obligation) {
             Ok(If(..)) => {
@@ -1732,7 +1763,7 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
                 }
             }
 
-            ty::TyArray(element_ty, _) => {
+            ty::TyArray(element_ty, _) | ty::TyParamArray(element_ty, _) => {
                 // [T; n]
                 match bound {
                     ty::BoundCopy => ok_if(vec![element_ty]),
@@ -1791,7 +1822,7 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
                 nominal(bound, types)
             }
 
-            ty::TyProjection(_) | ty::TyParam(_) => {
+            ty::TyProjection(_) | ty::TyAnon(..) | ty::TyParam(_) => {
                 // Note: A type parameter is only considered to meet a
                 // particular bound if there is a where clause telling
                 // us that it does, and that case is handled by
@@ -1824,9 +1855,9 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
             Ok(If(ty::Binder(v)))
         }
 
-        fn nominal<'cx, 'tcx>(bound: ty::BuiltinBound,
-                              types: Vec<Ty<'tcx>>)
-                              -> Result<BuiltinBoundConditions<'tcx>, SelectionError<'tcx>>
+        fn nominal<'tcx>(bound: ty::BuiltinBound,
+                         types: Vec<Ty<'tcx>>)
+                         -> Result<BuiltinBoundConditions<'tcx>, SelectionError<'tcx>>
         {
             // First check for markers and other nonsense.
             match bound {
@@ -1872,6 +1903,7 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
             ty::TyTrait(..) |
             ty::TyParam(..) |
             ty::TyProjection(..) |
+            ty::TyAnon(..) |
             ty::TyInfer(ty::TyVar(_)) |
             ty::TyInfer(ty::FreshTy(_)) |
             ty::TyInfer(ty::FreshIntTy(_)) |
@@ -1891,7 +1923,9 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
                 vec![element_ty]
             },
 
-            ty::TyArray(element_ty, _) | ty::TySlice(element_ty) => {
+            ty::TyArray(element_ty, _) |
+            ty::TyParamArray(element_ty, _) |
+            ty::TySlice(element_ty) => {
                 vec![element_ty]
             }
 
@@ -2068,9 +2102,39 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
                 let data = try!(self.confirm_builtin_unsize_candidate(obligation));
                 Ok(VtableBuiltin(data))
             }
+
+            TraitAliasCandidate(alias_def_id) => {
+                let data = self.confirm_trait_alias_candidate(obligation, alias_def_id);
+                Ok(VtableBuiltin(data))
+            }
         }
     }
 
+    /// An alias holds when each of its bounds, substituted with the
+    /// alias's own type parameters, holds.
+    fn confirm_trait_alias_candidate(&mut self,
+                                     obligation: &TraitObligation<'tcx>,
+                                     alias_def_id: DefId)
+                                     -> VtableBuiltinData<PredicateObligation<'tcx>>
+    {
+        debug!("confirm_trait_alias_candidate({:?}, {:?})",
+               obligation,
+               alias_def_id);
+
+        let tcx = self.tcx();
+        let trait_ref = obligation.predicate.to_poly_trait_ref();
+        let predicates = tcx.lookup_super_predicates(alias_def_id);
+        let nested = predicates.predicates.iter().map(|predicate| {
+            Obligation {
+                cause: obligation.cause.clone(),
+                recursion_depth: obligation.recursion_depth + 1,
+                predicate: predicate.subst_supertrait(tcx, &trait_ref),
+            }
+        }).collect();
+
+        VtableBuiltinData { nested: nested }
+    }
+
     fn confirm_projection_candidate(&mut self,
                                     obligation: &TraitObligation<'tcx>)
     {
@@ -2559,7 +2623,8 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
             }
 
             // [T; n] -> [T].
-            (&ty::TyArray(a, _), &ty::TySlice(b)) => {
+            (&ty::TyArray(a, _), &ty::TySlice(b)) |
+            (&ty::TyParamArray(a, _), &ty::TySlice(b)) => {
                 let origin = TypeOrigin::Misc(obligation.cause.span);
                 if self.infcx.sub_types(false, origin, a, b).is_err() {
                     return Err(Unimplemented);
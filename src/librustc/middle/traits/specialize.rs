@@ -0,0 +1,179 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Impl specialization: deciding when one impl is more specific than
+//! another, and finding the items a specializing impl inherits from the
+//! impls it specializes. See RFC 1210 for the overall design.
+
+use super::{FulfillmentContext, Normalized, Obligation, ObligationCause};
+use super::{SelectionContext, normalize};
+
+use middle::def_id::DefId;
+use middle::infer::{self, InferCtxt, TypeOrigin};
+use middle::region;
+use middle::subst::{Subst, Substs};
+use middle::ty::{self, TyCtxt};
+use syntax::codemap::DUMMY_SP;
+
+/// Is `impl1` a specialization of `impl2`?
+///
+/// That is the case when, taking `impl1`'s type parameters as given and its
+/// where clauses as assumptions, `impl2` applies to the trait reference that
+/// `impl1` implements. Every type covered by `impl1` is then covered by
+/// `impl2` as well.
+pub fn specializes<'tcx>(tcx: &TyCtxt<'tcx>, impl1_def_id: DefId, impl2_def_id: DefId) -> bool {
+    debug!("specializes({:?}, {:?})", impl1_def_id, impl2_def_id);
+
+    if impl1_def_id == impl2_def_id {
+        return false;
+    }
+
+    let impl1_trait_ref = match tcx.impl_trait_ref(impl1_def_id) {
+        Some(trait_ref) => trait_ref,
+        None => return false,
+    };
+
+    let scheme = tcx.lookup_item_type(impl1_def_id);
+    let predicates = tcx.lookup_predicates(impl1_def_id);
+    let penv = tcx.construct_parameter_environment(DUMMY_SP,
+                                                   &scheme.generics,
+                                                   &predicates,
+                                                   region::DUMMY_CODE_EXTENT);
+    let impl1_trait_ref = impl1_trait_ref.subst(tcx, &penv.free_substs);
+
+    let infcx = infer::new_infer_ctxt(tcx, &tcx.tables, Some(penv));
+    let selcx = &mut SelectionContext::new(&infcx);
+    let Normalized { value: impl1_trait_ref, obligations: normalization_obligations } =
+        normalize(selcx, ObligationCause::dummy(), &impl1_trait_ref);
+
+    let impl2_generics = tcx.lookup_item_type(impl2_def_id).generics;
+    let impl2_substs = infcx.fresh_substs_for_generics(DUMMY_SP, &impl2_generics);
+    let impl2_trait_ref = tcx.impl_trait_ref(impl2_def_id).unwrap().subst(tcx, &impl2_substs);
+    let Normalized { value: impl2_trait_ref, obligations: impl2_obligations } =
+        normalize(selcx, ObligationCause::dummy(), &impl2_trait_ref);
+
+    if let Err(_) = infer::mk_eq_trait_refs(&infcx,
+                                            true,
+                                            TypeOrigin::Misc(DUMMY_SP),
+                                            impl1_trait_ref,
+                                            impl2_trait_ref) {
+        debug!("specializes: trait references do not unify");
+        return false;
+    }
+
+    let impl2_predicates = tcx.lookup_predicates(impl2_def_id)
+                              .instantiate(tcx, &impl2_substs)
+                              .predicates
+                              .into_vec();
+    let Normalized { value: impl2_predicates, obligations: predicate_obligations } =
+        normalize(selcx, ObligationCause::dummy(), &impl2_predicates);
+
+    let mut fulfill_cx = FulfillmentContext::new();
+    for predicate in impl2_predicates {
+        fulfill_cx.register_predicate_obligation(
+            &infcx, Obligation::new(ObligationCause::dummy(), predicate));
+    }
+    for obligation in normalization_obligations.into_iter()
+                                               .chain(impl2_obligations)
+                                               .chain(predicate_obligations) {
+        fulfill_cx.register_predicate_obligation(&infcx, obligation);
+    }
+
+    match fulfill_cx.select_all_or_error(&infcx) {
+        Ok(()) => true,
+        Err(errors) => {
+            debug!("specializes: unsatisfied obligations {:?}", errors);
+            false
+        }
+    }
+}
+
+/// Picks the impl that `impl_def_id` directly specializes out of all the
+/// impls of its trait: the most specific of the ones it specializes.
+pub fn find_specialization_parent<'tcx>(tcx: &TyCtxt<'tcx>,
+                                        impl_def_id: DefId,
+                                        trait_impls: &[DefId])
+                                        -> Option<DefId> {
+    let parents: Vec<DefId> = trait_impls.iter()
+                                         .cloned()
+                                         .filter(|&other| specializes(tcx, impl_def_id, other))
+                                         .collect();
+    parents.iter().cloned().find(|&parent| {
+        parents.iter().all(|&other| other == parent || specializes(tcx, parent, other))
+    })
+}
+
+/// Is `ancestor` one of the impls that `impl_def_id` (transitively)
+/// specializes?
+pub fn is_specialization_ancestor(tcx: &TyCtxt, impl_def_id: DefId, ancestor: DefId) -> bool {
+    let mut current = tcx.impl_parent(impl_def_id);
+    while let Some(parent) = current {
+        if parent == ancestor {
+            return true;
+        }
+        current = tcx.impl_parent(parent);
+    }
+    false
+}
+
+/// Finds the associated item of `impl_def_id` for which `is_wanted` holds:
+/// either one of its own, or one it inherits from the nearest impl it
+/// specializes that defines such an item. Returns the defining impl along
+/// with the item. Defaults from the trait itself are left to the caller.
+pub fn find_impl_item<'tcx, F>(tcx: &TyCtxt<'tcx>,
+                               impl_def_id: DefId,
+                               mut is_wanted: F)
+                               -> Option<(DefId, ty::ImplOrTraitItem<'tcx>)>
+    where F: FnMut(&ty::ImplOrTraitItem<'tcx>) -> bool
+{
+    let mut current = Some(impl_def_id);
+    while let Some(impl_def_id) = current {
+        let item_ids = tcx.impl_items.borrow()[&impl_def_id].clone();
+        for item_id in item_ids {
+            let item = tcx.impl_or_trait_item(item_id.def_id());
+            if is_wanted(&item) {
+                return Some((impl_def_id, item));
+            }
+        }
+        current = tcx.impl_parent(impl_def_id);
+    }
+    None
+}
+
+/// Given the substitutions `source_substs` of `source_impl`, finds those of
+/// `target_impl`, an impl that `source_impl` specializes, under which both
+/// implement the same trait reference. Method-level substitutions are
+/// carried over unchanged.
+pub fn translate_substs<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                  source_impl: DefId,
+                                  source_substs: &Substs<'tcx>,
+                                  target_impl: DefId)
+                                  -> Substs<'tcx> {
+    if source_impl == target_impl {
+        return source_substs.clone();
+    }
+
+    let tcx = infcx.tcx;
+    let source_trait_ref = tcx.impl_trait_ref(source_impl).unwrap().subst(tcx, source_substs);
+    let target_generics = tcx.lookup_item_type(target_impl).generics;
+    let target_substs = infcx.fresh_substs_for_generics(DUMMY_SP, &target_generics);
+    let target_trait_ref = tcx.impl_trait_ref(target_impl).unwrap().subst(tcx, &target_substs);
+
+    if let Err(_) = infer::mk_eq_trait_refs(infcx,
+                                            true,
+                                            TypeOrigin::Misc(DUMMY_SP),
+                                            source_trait_ref,
+                                            target_trait_ref) {
+        tcx.sess.bug(&format!("translate_substs: impl {:?} does not specialize {:?}",
+                              source_impl, target_impl));
+    }
+
+    infcx.resolve_type_vars_if_possible(&target_substs).with_method_from(source_substs)
+}
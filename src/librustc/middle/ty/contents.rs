@@ -207,7 +207,7 @@ impl<'tcx> ty::TyS<'tcx> {
                     TC::None
                 }
 
-                ty::TyArray(ty, _) => {
+                ty::TyArray(ty, _) | ty::TyParamArray(ty, _) => {
                     tc_ty(cx, ty, cache)
                 }
 
@@ -241,6 +241,7 @@ impl<'tcx> ty::TyS<'tcx> {
                 }
 
                 ty::TyProjection(..) |
+                ty::TyAnon(..) |
                 ty::TyParam(_) => {
                     TC::All
                 }
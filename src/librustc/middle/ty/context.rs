@@ -10,11 +10,12 @@
 
 //! type context book-keeping
 
-use dep_graph::{DepGraph, DepTrackingMap};
+use dep_graph::{DepGraph, DepNode, DepTrackingMap};
 use front::map as ast_map;
 use session::Session;
 use lint;
 use middle;
+use middle::const_eval;
 use middle::cstore::CrateStore;
 use middle::def::DefMap;
 use middle::def_id::DefId;
@@ -24,7 +25,7 @@ use middle::resolve_lifetime;
 use middle::stability;
 use middle::subst::{self, Subst, Substs};
 use middle::traits;
-use middle::ty::{self, TraitRef, Ty, TypeAndMut};
+use middle::ty::{self, ToPredicate, TraitRef, Ty, TypeAndMut};
 use middle::ty::{TyS, TypeVariants};
 use middle::ty::{AdtDef, ClosureSubsts, ExistentialBounds, Region};
 use middle::ty::{FreevarMap};
@@ -74,6 +75,17 @@ impl<'tcx> CtxtArenas<'tcx> {
             adt_defs: TypedArena::new()
         }
     }
+
+    /// The number of bytes reserved by each arena.
+    pub fn allocated_bytes(&self) -> Vec<(&'static str, usize)> {
+        vec![("types", self.type_.allocated_bytes()),
+             ("substs", self.substs.allocated_bytes()),
+             ("fn types", self.bare_fn.allocated_bytes()),
+             ("regions", self.region.allocated_bytes()),
+             ("stability", self.stability.allocated_bytes()),
+             ("trait defs", self.trait_defs.allocated_bytes()),
+             ("adt defs", self.adt_defs.allocated_bytes())]
+    }
 }
 
 pub struct CommonTypes<'tcx> {
@@ -132,7 +144,26 @@ pub struct Tables<'tcx> {
     /// of the struct - this is needed because it is non-trivial to
     /// normalize while preserving regions. This table is used only in
     /// MIR construction and hence is not serialized to metadata.
-    pub fru_field_types: NodeMap<Vec<Ty<'tcx>>>
+    pub fru_field_types: NodeMap<Vec<Ty<'tcx>>>,
+
+    /// For each pattern that was matched against a reference under a
+    /// default binding mode, the reference types that were implicitly
+    /// dereferenced before the pattern itself applied, outermost first.
+    /// The type recorded in `node_types` for the pattern is the type
+    /// left after all of those dereferences.
+    pub pat_adjustments: NodeMap<Vec<Ty<'tcx>>>,
+
+    /// For each binding that got its binding mode from a default binding
+    /// mode, the mode it binds with. Bindings that are not in this table
+    /// bind as written.
+    pub pat_binding_modes: NodeMap<hir::BindingMode>,
+
+    /// For each expression checked against a known type, that type, as far
+    /// as inference had worked it out by the end of the enclosing fn. Only
+    /// filled in under `-Z expected-type`, for tools asking what kind of
+    /// expression would fit at some position; see
+    /// `rustc_typeck::expected_type`.
+    pub expected_types: NodeMap<Ty<'tcx>>,
 }
 
 impl<'tcx> Tables<'tcx> {
@@ -146,10 +177,25 @@ impl<'tcx> Tables<'tcx> {
             closure_tys: DefIdMap(),
             closure_kinds: DefIdMap(),
             liberated_fn_sigs: NodeMap(),
-            fru_field_types: NodeMap()
+            fru_field_types: NodeMap(),
+            pat_adjustments: NodeMap(),
+            pat_binding_modes: NodeMap(),
+            expected_types: NodeMap(),
         }
     }
 
+    /// The references implicitly dereferenced before matching the pattern
+    /// `pat_id`; see `pat_adjustments`.
+    pub fn pat_adjustments(&self, pat_id: NodeId) -> &[Ty<'tcx>] {
+        self.pat_adjustments.get(&pat_id).map_or(&[], |tys| &tys[..])
+    }
+
+    /// The binding mode of the binding `pat_id`, which was written with
+    /// the binding mode `written`.
+    pub fn binding_mode(&self, pat_id: NodeId, written: hir::BindingMode) -> hir::BindingMode {
+        self.pat_binding_modes.get(&pat_id).cloned().unwrap_or(written)
+    }
+
     pub fn closure_kind(this: &RefCell<Self>,
                         tcx: &TyCtxt<'tcx>,
                         def_id: DefId)
@@ -238,6 +284,10 @@ pub struct TyCtxt<'tcx> {
     pub sess: &'tcx Session,
     pub def_map: RefCell<DefMap>,
 
+    /// Resolves paths like `Foo::BAR` in constants evaluated before type
+    /// checking has resolved them.
+    pub type_relative_items: const_eval::TypeRelativeItems,
+
     pub named_region_map: resolve_lifetime::NamedRegionMap,
 
     pub region_maps: RegionMaps,
@@ -262,6 +312,11 @@ pub struct TyCtxt<'tcx> {
     /// itself pushes the `TraitItems` dependency node.
     trait_items_cache: RefCell<DepTrackingMap<maps::TraitItems<'tcx>>>,
 
+    /// A cache for the builtin_bound_predicates() routine. Items that
+    /// are stamped out by macros tend to repeat the same bounds on the
+    /// same type parameters, so this is hit often.
+    builtin_bound_predicates_cache: RefCell<DepTrackingMap<maps::BuiltinBoundPredicates<'tcx>>>,
+
     pub impl_trait_refs: RefCell<DepTrackingMap<maps::ImplTraitRefs<'tcx>>>,
     pub trait_defs: RefCell<DepTrackingMap<maps::TraitDefs<'tcx>>>,
     pub adt_defs: RefCell<DepTrackingMap<maps::AdtDefs<'tcx>>>,
@@ -301,6 +356,7 @@ pub struct TyCtxt<'tcx> {
 
     // FIXME no dep tracking, but we should be able to remove this
     pub ty_param_defs: RefCell<NodeMap<ty::TypeParameterDef<'tcx>>>,
+    pub const_param_defs: RefCell<NodeMap<ty::ConstParameterDef<'tcx>>>,
 
     // FIXME dep tracking -- should be harmless enough
     pub normalized_cache: RefCell<FnvHashMap<Ty<'tcx>, Ty<'tcx>>>,
@@ -314,6 +370,11 @@ pub struct TyCtxt<'tcx> {
     /// True if the variance has been computed yet; false otherwise.
     pub variance_computed: Cell<bool>,
 
+    /// Runs variance inference for the local crate. Registered by
+    /// typeck once item types are collected, so that `item_variances`
+    /// can be answered for local items on demand.
+    pub variance_provider: Cell<Option<fn(&TyCtxt<'tcx>)>>,
+
     /// Maps a DefId of a type to a list of its inherent impls.
     /// Contains implementations of methods that are inherent to a type.
     /// Methods in these implementations don't need to be exported.
@@ -325,6 +386,14 @@ pub struct TyCtxt<'tcx> {
     /// way to do it.
     pub impl_items: RefCell<DepTrackingMap<maps::ImplItems<'tcx>>>,
 
+    /// Pairs of trait impls that coherence found to overlap, local impl
+    /// first. Kept around for `--emit=impl-graph`.
+    pub overlapping_impls: RefCell<Vec<(DefId, DefId)>>,
+
+    /// Maps each local trait impl that specializes another impl to the
+    /// most specific impl it specializes. Filled in by coherence.
+    pub impl_parents: RefCell<DefIdMap<DefId>>,
+
     /// Set of used unsafe nodes (functions or blocks). Unsafe nodes not
     /// present in this set can be warned about.
     pub used_unsafe: RefCell<NodeSet>,
@@ -378,6 +447,14 @@ pub struct TyCtxt<'tcx> {
     /// Maps Expr NodeId's to their constant qualification.
     pub const_qualif_map: RefCell<NodeMap<middle::const_qualif::ConstQualif>>,
 
+    /// Maps the NodeId of every expression qualified `NOT_CONST` to what
+    /// made it so.
+    pub not_const_causes: RefCell<NodeMap<middle::const_qualif::NotConstCause>>,
+
+    /// The traits of this and upstream crates marked `#[default_bound]`, which every
+    /// type parameter is bound by unless it opts out with `?Trait`.
+    pub default_bound_traits: RefCell<Vec<DefId>>,
+
     /// Caches CoerceUnsized kinds for impls on custom types.
     pub custom_coerce_unsized_kinds: RefCell<DefIdMap<ty::adjustment::CustomCoerceUnsized>>,
 
@@ -411,6 +488,20 @@ pub struct TyCtxt<'tcx> {
     /// fragmented data to the set of unfragmented pieces that
     /// constitute it.
     pub fragment_infos: RefCell<DefIdMap<Vec<ty::FragmentInfo>>>,
+
+    /// Local functions marked `#[track_caller]`, recorded by collect.
+    /// Calls to these implicitly pass the location of the call site as
+    /// a trailing argument; see `caller_location_ty`.
+    pub track_caller_fns: RefCell<DefIdSet>,
+
+    /// For each local function, the positions in its signature that
+    /// introduced its anonymous lifetimes, indexed by `BrAnon` number.
+    /// Recorded by collect and used when reporting region errors.
+    pub anon_region_origins: RefCell<NodeMap<Vec<ty::AnonRegionOrigin>>>,
+
+    /// Where each node typeck gave an error type got it from, when
+    /// `tracks_err_types` is set.
+    pub err_type_origins: RefCell<NodeMap<ty::ErrTypeOrigin>>,
 }
 
 impl<'tcx> TyCtxt<'tcx> {
@@ -421,6 +512,13 @@ impl<'tcx> TyCtxt<'tcx> {
         self.ty_param_defs.borrow().get(&node_id).unwrap().clone()
     }
 
+    pub fn const_parameter_def(&self,
+                               node_id: NodeId)
+                               -> ty::ConstParameterDef<'tcx>
+    {
+        self.const_param_defs.borrow().get(&node_id).unwrap().clone()
+    }
+
     pub fn node_types(&self) -> Ref<NodeMap<Ty<'tcx>>> {
         fn projection<'a, 'tcx>(tables: &'a Tables<'tcx>) -> &'a NodeMap<Ty<'tcx>> {
             &tables.node_types
@@ -501,6 +599,7 @@ impl<'tcx> TyCtxt<'tcx> {
     pub fn create_and_enter<F, R>(s: &'tcx Session,
                                  arenas: &'tcx CtxtArenas<'tcx>,
                                  def_map: RefCell<DefMap>,
+                                 type_relative_items: const_eval::TypeRelativeItems,
                                  named_region_map: resolve_lifetime::NamedRegionMap,
                                  map: ast_map::Map<'tcx>,
                                  freevars: FreevarMap,
@@ -528,8 +627,10 @@ impl<'tcx> TyCtxt<'tcx> {
             free_region_maps: RefCell::new(FnvHashMap()),
             item_variance_map: RefCell::new(DepTrackingMap::new(dep_graph.clone())),
             variance_computed: Cell::new(false),
+            variance_provider: Cell::new(None),
             sess: s,
             def_map: def_map,
+            type_relative_items: type_relative_items,
             tables: RefCell::new(Tables::empty()),
             impl_trait_refs: RefCell::new(DepTrackingMap::new(dep_graph.clone())),
             trait_defs: RefCell::new(DepTrackingMap::new(dep_graph.clone())),
@@ -546,11 +647,15 @@ impl<'tcx> TyCtxt<'tcx> {
             impl_or_trait_items: RefCell::new(DepTrackingMap::new(dep_graph.clone())),
             trait_item_def_ids: RefCell::new(DepTrackingMap::new(dep_graph.clone())),
             trait_items_cache: RefCell::new(DepTrackingMap::new(dep_graph.clone())),
+            builtin_bound_predicates_cache: RefCell::new(DepTrackingMap::new(dep_graph.clone())),
             ty_param_defs: RefCell::new(NodeMap()),
+            const_param_defs: RefCell::new(NodeMap()),
             normalized_cache: RefCell::new(FnvHashMap()),
             lang_items: lang_items,
             inherent_impls: RefCell::new(DepTrackingMap::new(dep_graph.clone())),
             impl_items: RefCell::new(DepTrackingMap::new(dep_graph.clone())),
+            overlapping_impls: RefCell::new(Vec::new()),
+            impl_parents: RefCell::new(DefIdMap()),
             used_unsafe: RefCell::new(NodeSet()),
             used_mut_nodes: RefCell::new(NodeSet()),
             populated_external_types: RefCell::new(DefIdSet()),
@@ -564,9 +669,14 @@ impl<'tcx> TyCtxt<'tcx> {
             evaluation_cache: traits::EvaluationCache::new(),
             repr_hint_cache: RefCell::new(DepTrackingMap::new(dep_graph.clone())),
             const_qualif_map: RefCell::new(NodeMap()),
+            not_const_causes: RefCell::new(NodeMap()),
+            default_bound_traits: RefCell::new(Vec::new()),
             custom_coerce_unsized_kinds: RefCell::new(DefIdMap()),
             cast_kinds: RefCell::new(NodeMap()),
-            fragment_infos: RefCell::new(DefIdMap())
+            fragment_infos: RefCell::new(DefIdMap()),
+            track_caller_fns: RefCell::new(DefIdSet()),
+            anon_region_origins: RefCell::new(NodeMap()),
+            err_type_origins: RefCell::new(NodeMap()),
        }, f)
     }
 }
@@ -733,14 +843,30 @@ impl<'tcx> TyCtxt<'tcx> {
     pub fn print_debug_stats(&self) {
         sty_debug_print!(
             self,
-            TyEnum, TyBox, TyArray, TySlice, TyRawPtr, TyRef, TyFnDef, TyFnPtr,
-            TyTrait, TyStruct, TyClosure, TyTuple, TyParam, TyInfer, TyProjection);
+            TyEnum, TyBox, TyArray, TyParamArray, TySlice, TyRawPtr, TyRef, TyFnDef, TyFnPtr,
+            TyTrait, TyStruct, TyClosure, TyTuple, TyParam, TyInfer, TyProjection, TyAnon);
 
         println!("Substs interner: #{}", self.substs_interner.borrow().len());
         println!("BareFnTy interner: #{}", self.bare_fn_interner.borrow().len());
         println!("Region interner: #{}", self.region_interner.borrow().len());
         println!("Stability interner: #{}", self.stability_interner.borrow().len());
     }
+
+    /// Prints the memory reserved by the arenas of this context, and how
+    /// much has been interned into them, for `-Z memory-stats`.
+    pub fn print_arena_stats(&self) {
+        let arenas = self.arenas.allocated_bytes();
+        let total = arenas.iter().fold(0, |total, &(_, bytes)| total + bytes);
+        println!("    arenas: {}KB", total / 1024);
+        for &(name, bytes) in &arenas {
+            println!("      {}: {}KB", name, bytes / 1024);
+        }
+        println!("    interned: {} types, {} substs, {} fn types, {} regions",
+                 self.interner.borrow().len(),
+                 self.substs_interner.borrow().len(),
+                 self.bare_fn_interner.borrow().len(),
+                 self.region_interner.borrow().len());
+    }
 }
 
 
@@ -887,6 +1013,16 @@ impl<'tcx> TyCtxt<'tcx> {
         self.mk_imm_ref(self.mk_region(ty::ReStatic), self.mk_str())
     }
 
+    /// The type of the location passed to `#[track_caller]` functions and
+    /// returned by the `caller_location` intrinsic: `&'static (file, line,
+    /// column)`.
+    pub fn caller_location_ty(&self) -> Ty<'tcx> {
+        let location = self.mk_tup(vec![self.mk_static_str(),
+                                        self.types.u32,
+                                        self.types.u32]);
+        self.mk_imm_ref(self.mk_region(ty::ReStatic), location)
+    }
+
     pub fn mk_enum(&self, def: AdtDef<'tcx>, substs: &'tcx Substs<'tcx>) -> Ty<'tcx> {
         // take a copy of substs so that we own the vectors inside
         self.mk_ty(TyEnum(def, substs))
@@ -928,6 +1064,10 @@ impl<'tcx> TyCtxt<'tcx> {
         self.mk_ty(TyArray(ty, n))
     }
 
+    pub fn mk_param_array(&self, ty: Ty<'tcx>, len: ty::ParamConst) -> Ty<'tcx> {
+        self.mk_ty(TyParamArray(ty, len))
+    }
+
     pub fn mk_slice(&self, ty: Ty<'tcx>) -> Ty<'tcx> {
         self.mk_ty(TySlice(ty))
     }
@@ -977,6 +1117,10 @@ impl<'tcx> TyCtxt<'tcx> {
         self.mk_ty(TyProjection(inner))
     }
 
+    pub fn mk_anon(&self, def_id: DefId, substs: &'tcx Substs<'tcx>) -> Ty<'tcx> {
+        self.mk_ty(TyAnon(def_id, substs))
+    }
+
     pub fn mk_struct(&self, def: AdtDef<'tcx>, substs: &'tcx Substs<'tcx>) -> Ty<'tcx> {
         // take a copy of substs so that we own the vectors inside
         self.mk_ty(TyStruct(def, substs))
@@ -1040,6 +1184,33 @@ impl<'tcx> TyCtxt<'tcx> {
         })
     }
 
+    /// The predicates `param_ty: B` for each builtin bound `B` in
+    /// `builtin_bounds`. A bound whose language item is missing is
+    /// reported and left out.
+    pub fn builtin_bound_predicates(&self,
+                                    param_ty: Ty<'tcx>,
+                                    builtin_bounds: ty::BuiltinBounds)
+                                    -> Rc<Vec<ty::Predicate<'tcx>>> {
+        let predicates = || {
+            Rc::new(builtin_bounds.iter().filter_map(|builtin_bound| {
+                traits::trait_ref_for_builtin_bound(self, builtin_bound, param_ty)
+                    .ok()
+                    .map(|trait_ref| trait_ref.to_predicate())
+            }).collect())
+        };
+
+        // Missing language items are reported for every set of bounds
+        // that needs them, so such sets are never cached.
+        if builtin_bounds.iter().any(|bound| self.lang_items.from_builtin_kind(bound).is_err()) {
+            return predicates();
+        }
+
+        self.builtin_bound_predicates_cache.memoize((param_ty, builtin_bounds), || {
+            self.dep_graph.read(DepNode::CollectLanguageItems);
+            predicates()
+        })
+    }
+
     /// Obtain the representation annotation for a struct definition.
     pub fn lookup_repr_hints(&self, did: DefId) -> Rc<Vec<attr::ReprAttr>> {
         self.repr_hint_cache.memoize(did, || {
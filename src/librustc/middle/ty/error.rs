@@ -40,6 +40,7 @@ pub enum TypeError<'tcx> {
     VecMutability,
     TupleSize(ExpectedFound<usize>),
     FixedArraySize(ExpectedFound<usize>),
+    ConstArg(ExpectedFound<subst::ConstArg>),
     TyParamSize(ExpectedFound<usize>),
     ArgCount,
     RegionsDoesNotOutlive(Region, Region),
@@ -118,6 +119,11 @@ impl<'tcx> fmt::Display for TypeError<'tcx> {
                        values.expected,
                        values.found)
             }
+            ConstArg(values) => {
+                write!(f, "expected const argument `{}`, found `{}`",
+                       values.expected,
+                       values.found)
+            }
             TupleSize(values) => {
                 write!(f, "expected a tuple with {} elements, \
                            found one with {} elements",
@@ -220,6 +226,7 @@ impl<'tcx> ty::TyS<'tcx> {
             ty::TyEnum(def, _) => format!("enum `{}`", cx.item_path_str(def.did)),
             ty::TyBox(_) => "box".to_string(),
             ty::TyArray(_, n) => format!("array of {} elements", n),
+            ty::TyParamArray(_, ref n) => format!("array of `{}` elements", n),
             ty::TySlice(_) => "slice".to_string(),
             ty::TyRawPtr(_) => "*-ptr".to_string(),
             ty::TyRef(_, _) => "&-ptr".to_string(),
@@ -240,6 +247,7 @@ impl<'tcx> ty::TyS<'tcx> {
             ty::TyInfer(ty::FreshIntTy(_)) => "skolemized integral type".to_string(),
             ty::TyInfer(ty::FreshFloatTy(_)) => "skolemized floating-point type".to_string(),
             ty::TyProjection(_) => "associated type".to_string(),
+            ty::TyAnon(..) => "anonymized type".to_string(),
             ty::TyParam(ref p) => {
                 if p.space == subst::SelfSpace {
                     "Self".to_string()
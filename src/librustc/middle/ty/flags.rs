@@ -104,6 +104,13 @@ impl FlagComputation {
                 self.add_projection_ty(data);
             }
 
+            &ty::TyAnon(_, substs) => {
+                // Anonymized types are "normalized" away to their
+                // concrete type in trans, just like projections.
+                self.add_flags(TypeFlags::HAS_PROJECTION);
+                self.add_substs(substs);
+            }
+
             &ty::TyTrait(box ty::TraitTy { ref principal, ref bounds }) => {
                 let mut computation = FlagComputation::new();
                 computation.add_substs(principal.0.substs);
@@ -121,6 +128,12 @@ impl FlagComputation {
                 self.add_ty(tt)
             }
 
+            &ty::TyParamArray(tt, _) => {
+                self.add_flags(TypeFlags::HAS_LOCAL_NAMES);
+                self.add_flags(TypeFlags::HAS_PARAMS);
+                self.add_ty(tt)
+            }
+
             &ty::TyRawPtr(ref m) => {
                 self.add_ty(m.ty);
             }
@@ -194,6 +207,12 @@ impl FlagComputation {
 
     fn add_substs(&mut self, substs: &subst::Substs) {
         self.add_tys(substs.types.as_slice());
+        for c in substs.consts.as_slice() {
+            if let subst::ConstArg::Param(_) = *c {
+                self.add_flags(TypeFlags::HAS_LOCAL_NAMES);
+                self.add_flags(TypeFlags::HAS_PARAMS);
+            }
+        }
         match substs.regions {
             subst::ErasedRegions => {}
             subst::NonerasedRegions(ref regions) => {
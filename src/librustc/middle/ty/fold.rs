@@ -156,6 +156,10 @@ pub trait TypeFolder<'tcx> : Sized {
         substs.super_fold_with(self)
     }
 
+    fn fold_const_arg(&mut self, c: subst::ConstArg) -> subst::ConstArg {
+        c
+    }
+
     fn fold_fn_sig(&mut self,
                    sig: &ty::FnSig<'tcx>)
                    -> ty::FnSig<'tcx> {
@@ -533,7 +537,8 @@ impl<'tcx> TyCtxt<'tcx> {
                            substs: &subst::Substs<'tcx>)
                            -> subst::Substs<'tcx> {
                 subst::Substs { regions: subst::ErasedRegions,
-                                types: substs.types.fold_with(self) }
+                                types: substs.types.fold_with(self),
+                                consts: substs.consts.clone() }
             }
         }
     }
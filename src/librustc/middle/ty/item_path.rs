@@ -0,0 +1,111 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Printing the path of a definition for use in diagnostics.
+//!
+//! `item_path_str` prints the names along the HIR path of an item, which is
+//! fine for items in modules but loses the interesting part of anything
+//! nested in an impl or a closure. `def_path_str` walks the def-path instead
+//! and prints impls as `<Type as Trait>` (or `<Type>`) and closures as
+//! `{{closure}}` under the item they appear in.
+
+use front::map::{self as ast_map, DefPathData};
+use middle::def_id::DefId;
+use middle::ty::TyCtxt;
+use rustc_front::hir;
+use rustc_front::print::pprust;
+
+impl<'tcx> TyCtxt<'tcx> {
+    /// Returns a readable path for `def_id`, e.g. `<foo::Bar as Clone>::clone`
+    /// for a method in an impl or `foo::{{closure}}` for a closure in `foo`.
+    /// Items from other crates are prefixed with the crate's name.
+    pub fn def_path_str(&self, def_id: DefId) -> String {
+        let mut segments = vec![];
+        let mut current = def_id;
+        loop {
+            let key = self.def_key(current);
+            match key.disambiguated_data.data {
+                DefPathData::CrateRoot => {
+                    if !def_id.is_local() {
+                        segments.push(self.sess.cstore.crate_name(def_id.krate));
+                    }
+                    break;
+                }
+                DefPathData::InlinedRoot(..) => {
+                    // Inlined items only know their path in the crate they
+                    // came from; fall back to the HIR path.
+                    return self.item_path_str(def_id);
+                }
+                DefPathData::Impl(..) => {
+                    // The impl header names its self type and trait by their
+                    // own paths, so the module the impl is in adds nothing.
+                    segments.push(self.impl_header_str(current));
+                    break;
+                }
+                DefPathData::ClosureExpr => {
+                    segments.push("{{closure}}".to_string());
+                }
+                DefPathData::ImplTrait => {
+                    segments.push("{{impl-Trait}}".to_string());
+                }
+                DefPathData::Type(name) |
+                DefPathData::Mod(name) |
+                DefPathData::Value(name) |
+                DefPathData::MacroDef(name) |
+                DefPathData::TypeParam(name) |
+                DefPathData::ConstParam(name) |
+                DefPathData::LifetimeDef(name) |
+                DefPathData::EnumVariant(name) |
+                DefPathData::Field(name) |
+                DefPathData::Binding(name) |
+                DefPathData::DetachedCrate(name) => {
+                    segments.push(name.to_string());
+                }
+                DefPathData::Misc |
+                DefPathData::StructCtor |
+                DefPathData::Initializer => {}
+            }
+
+            match key.parent {
+                Some(index) => current = DefId { krate: def_id.krate, index: index },
+                None => break,
+            }
+        }
+
+        segments.reverse();
+        segments.join("::")
+    }
+
+    /// Renders the header of an impl as `<Type as Trait>`, or `<Type>` for an
+    /// inherent impl. Local impls are printed from their HIR, since they may
+    /// be asked about (e.g. in a cycle error) before they have been collected.
+    fn impl_header_str(&self, impl_def_id: DefId) -> String {
+        if let Some(node_id) = self.map.as_local_node_id(impl_def_id) {
+            if let Some(ast_map::NodeItem(item)) = self.map.find(node_id) {
+                if let hir::ItemImpl(_, _, _, ref opt_trait_ref, ref self_ty, _) = item.node {
+                    let self_ty = pprust::ty_to_string(self_ty);
+                    return match *opt_trait_ref {
+                        Some(ref trait_ref) => {
+                            format!("<{} as {}>", self_ty, pprust::path_to_string(&trait_ref.path))
+                        }
+                        None => format!("<{}>", self_ty),
+                    };
+                }
+            }
+            return self.item_path_str(impl_def_id);
+        }
+
+        let self_ty = self.lookup_item_type(impl_def_id).ty;
+        match self.impl_trait_ref(impl_def_id) {
+            Some(trait_ref) => format!("<{} as {}>", self_ty, trait_ref),
+            None => format!("<{}>", self_ty),
+        }
+    }
+}
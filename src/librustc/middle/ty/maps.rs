@@ -43,3 +43,13 @@ dep_map_ty! { ImplItems: ImplItems(DefId) -> Vec<ty::ImplOrTraitItemId> }
 dep_map_ty! { TraitItems: TraitItems(DefId) -> Rc<Vec<ty::ImplOrTraitItem<'tcx>>> }
 dep_map_ty! { ReprHints: ReprHints(DefId) -> Rc<Vec<attr::ReprAttr>> }
 dep_map_ty! { InlinedClosures: Hir(DefId) -> ast::NodeId }
+
+pub struct BuiltinBoundPredicates<'tcx> {
+    data: PhantomData<&'tcx ()>
+}
+
+impl<'tcx> DepTrackingMapConfig for BuiltinBoundPredicates<'tcx> {
+    type Key = (ty::Ty<'tcx>, ty::BuiltinBounds);
+    type Value = Rc<Vec<ty::Predicate<'tcx>>>;
+    fn to_dep_node(_: &Self::Key) -> DepNode { DepNode::BuiltinBoundPredicates }
+}
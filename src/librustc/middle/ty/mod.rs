@@ -38,13 +38,12 @@ use util::nodemap::FnvHashMap;
 
 use serialize::{Encodable, Encoder, Decodable, Decoder};
 use std::borrow::{Borrow, Cow};
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
 use std::hash::{Hash, Hasher};
 use std::iter;
 use std::rc::Rc;
 use std::slice;
 use std::vec::IntoIter;
-use std::collections::{HashMap, HashSet};
 use syntax::ast::{self, CrateNum, Name, NodeId};
 use syntax::attr::{self, AttrMetaMethods};
 use syntax::codemap::{DUMMY_SP, Span};
@@ -57,7 +56,7 @@ use rustc_front::intravisit::Visitor;
 pub use self::sty::{Binder, DebruijnIndex};
 pub use self::sty::{BuiltinBound, BuiltinBounds, ExistentialBounds};
 pub use self::sty::{BareFnTy, FnSig, PolyFnSig, FnOutput, PolyFnOutput};
-pub use self::sty::{ClosureTy, InferTy, ParamTy, ProjectionTy, TraitTy};
+pub use self::sty::{ClosureTy, InferTy, ParamConst, ParamTy, ProjectionTy, TraitTy};
 pub use self::sty::{ClosureSubsts, TypeAndMut};
 pub use self::sty::{TraitRef, TypeVariants, PolyTraitRef};
 pub use self::sty::{BoundRegion, EarlyBoundRegion, FreeRegion, Region};
@@ -84,6 +83,7 @@ pub mod cast;
 pub mod error;
 pub mod fast_reject;
 pub mod fold;
+pub mod item_path;
 pub mod _match;
 pub mod maps;
 pub mod outlives;
@@ -239,6 +239,14 @@ impl<'tcx> ImplOrTraitItem<'tcx> {
         }
     }
 
+    pub fn defaultness(&self) -> hir::Defaultness {
+        match *self {
+            ConstTraitItem(ref associated_const) => associated_const.defaultness,
+            MethodTraitItem(ref method) => method.defaultness,
+            TypeTraitItem(ref associated_type) => associated_type.defaultness,
+        }
+    }
+
     pub fn container(&self) -> ImplOrTraitItemContainer {
         match *self {
             ConstTraitItem(ref associated_const) => associated_const.container,
@@ -280,6 +288,7 @@ pub struct Method<'tcx> {
     pub fty: BareFnTy<'tcx>,
     pub explicit_self: ExplicitSelfCategory,
     pub vis: hir::Visibility,
+    pub defaultness: hir::Defaultness,
     pub def_id: DefId,
     pub container: ImplOrTraitItemContainer,
 }
@@ -291,6 +300,7 @@ impl<'tcx> Method<'tcx> {
                fty: BareFnTy<'tcx>,
                explicit_self: ExplicitSelfCategory,
                vis: hir::Visibility,
+               defaultness: hir::Defaultness,
                def_id: DefId,
                container: ImplOrTraitItemContainer)
                -> Method<'tcx> {
@@ -301,6 +311,7 @@ impl<'tcx> Method<'tcx> {
             fty: fty,
             explicit_self: explicit_self,
             vis: vis,
+            defaultness: defaultness,
             def_id: def_id,
             container: container,
         }
@@ -333,20 +344,62 @@ pub struct AssociatedConst<'tcx> {
     pub name: Name,
     pub ty: Ty<'tcx>,
     pub vis: hir::Visibility,
+    pub defaultness: hir::Defaultness,
     pub def_id: DefId,
     pub container: ImplOrTraitItemContainer,
     pub has_value: bool
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 pub struct AssociatedType<'tcx> {
     pub name: Name,
+    /// The generics of the container, plus the associated type's own
+    /// parameters in `FnSpace` (see `is_generic`).
+    pub generics: Generics<'tcx>,
+    pub predicates: GenericPredicates<'tcx>,
     pub ty: Option<Ty<'tcx>>,
     pub vis: hir::Visibility,
+    pub defaultness: hir::Defaultness,
     pub def_id: DefId,
     pub container: ImplOrTraitItemContainer,
 }
 
+impl<'tcx> AssociatedType<'tcx> {
+    /// Whether this is a generic associated type, i.e. one declared with
+    /// parameters of its own like `type Item<T>;`.
+    pub fn is_generic(&self) -> bool {
+        self.generics.has_type_params(subst::FnSpace) ||
+            self.generics.has_region_params(subst::FnSpace)
+    }
+}
+
+/// Where an anonymous lifetime in a function signature was introduced by
+/// elision. The `n`th origin recorded for a function describes the
+/// region `BrAnon(n)`; these are only used to improve diagnostics.
+#[derive(Clone, Debug)]
+pub struct AnonRegionOrigin {
+    /// The type in which the lifetime was elided.
+    pub span: Span,
+    /// The argument containing that type, e.g. "`self`" or "argument `x`".
+    pub position: String,
+}
+
+/// Where a node that typeck gave an error type got it from, recorded in
+/// `err_type_origins`.
+#[derive(Clone, Copy, Debug)]
+pub struct ErrTypeOrigin {
+    pub span: Span,
+    /// The node whose error type this one was derived from, e.g. an operand
+    /// or the variable a path refers to. `None` where it was introduced.
+    pub parent: Option<NodeId>,
+    /// How many errors had been reported when the node got its type; an
+    /// error type is normally preceded by the error that explains it.
+    pub diagnostic: usize,
+    /// Whether the error type only showed up when writeback resolved the
+    /// node's inference variables.
+    pub at_writeback: bool,
+}
+
 #[derive(Clone, PartialEq, RustcDecodable, RustcEncodable)]
 pub struct ItemVariances {
     pub types: VecPerParamSpace<Variance>,
@@ -647,8 +700,9 @@ pub enum IntVarValue {
 /// surrounding context. See RFC #599 for details.
 #[derive(Copy, Clone)]
 pub enum ObjectLifetimeDefault {
-    /// Require an explicit annotation. Occurs when multiple
-    /// `T:'a` constraints are found.
+    /// Require an explicit annotation, except in fn bodies where the
+    /// region is inferred. Occurs when multiple `T:'a` constraints are
+    /// found and none of them is `'static`.
     Ambiguous,
 
     /// Use the base default, typically 'static, but in a fn body it is a fresh variable
@@ -669,6 +723,18 @@ pub struct TypeParameterDef<'tcx> {
     pub object_lifetime_default: ObjectLifetimeDefault,
 }
 
+/// A const parameter, `const N: usize` under `#![feature(const_generics)]`.
+/// Its argument is carried in `Substs::consts`, and it can be used as the
+/// length of an array type, `[T; N]`.
+#[derive(Clone)]
+pub struct ConstParameterDef<'tcx> {
+    pub name: Name,
+    pub def_id: DefId,
+    pub space: subst::ParamSpace,
+    pub index: u32,
+    pub ty: Ty<'tcx>,
+}
+
 #[derive(Clone)]
 pub struct RegionParameterDef {
     pub name: Name,
@@ -697,6 +763,7 @@ impl RegionParameterDef {
 pub struct Generics<'tcx> {
     pub types: VecPerParamSpace<TypeParameterDef<'tcx>>,
     pub regions: VecPerParamSpace<RegionParameterDef>,
+    pub consts: VecPerParamSpace<ConstParameterDef<'tcx>>,
 }
 
 impl<'tcx> Generics<'tcx> {
@@ -704,11 +771,12 @@ impl<'tcx> Generics<'tcx> {
         Generics {
             types: VecPerParamSpace::empty(),
             regions: VecPerParamSpace::empty(),
+            consts: VecPerParamSpace::empty(),
         }
     }
 
     pub fn is_empty(&self) -> bool {
-        self.types.is_empty() && self.regions.is_empty()
+        self.types.is_empty() && self.regions.is_empty() && self.consts.is_empty()
     }
 
     pub fn has_type_params(&self, space: subst::ParamSpace) -> bool {
@@ -1311,6 +1379,7 @@ impl<'a, 'tcx> ParameterEnvironment<'a, 'tcx> {
                     }
                     hir::ItemEnum(..) |
                     hir::ItemStruct(..) |
+                    hir::ItemUnion(..) |
                     hir::ItemImpl(..) |
                     hir::ItemConst(..) |
                     hir::ItemStatic(..) => {
@@ -1322,7 +1391,8 @@ impl<'a, 'tcx> ParameterEnvironment<'a, 'tcx> {
                                                            &predicates,
                                                            cx.region_maps.item_extent(id))
                     }
-                    hir::ItemTrait(..) => {
+                    hir::ItemTrait(..) |
+                    hir::ItemTraitAlias(..) => {
                         let def_id = cx.map.local_def_id(id);
                         let trait_def = cx.lookup_trait_def(def_id);
                         let predicates = cx.lookup_predicates(def_id);
@@ -1387,6 +1457,7 @@ bitflags! {
         const IS_SIMD             = 1 << 4,
         const IS_FUNDAMENTAL      = 1 << 5,
         const IS_NO_DROP_FLAG     = 1 << 6,
+        const IS_UNION            = 1 << 7,
     }
 }
 
@@ -1422,7 +1493,7 @@ pub struct FieldDefData<'tcx, 'container: 'tcx> {
     ty: ivar::TyIVar<'tcx, 'container>
 }
 
-/// The definition of an abstract data type - a struct or enum.
+/// The definition of an abstract data type - a struct, enum or union.
 ///
 /// These are all interned (by intern_adt_def) into the adt_defs
 /// table.
@@ -1478,7 +1549,7 @@ impl<'tcx> Decodable for AdtDef<'tcx> {
 
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
-pub enum AdtKind { Struct, Enum }
+pub enum AdtKind { Struct, Enum, Union }
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, RustcEncodable, RustcDecodable)]
 pub enum VariantKind { Struct, Tuple, Unit }
@@ -1512,8 +1583,10 @@ impl<'tcx, 'container> AdtDefData<'tcx, 'container> {
         if Some(did) == tcx.lang_items.phantom_data() {
             flags = flags | AdtFlags::IS_PHANTOM_DATA;
         }
-        if let AdtKind::Enum = kind {
-            flags = flags | AdtFlags::IS_ENUM;
+        match kind {
+            AdtKind::Enum => flags = flags | AdtFlags::IS_ENUM,
+            AdtKind::Union => flags = flags | AdtFlags::IS_UNION,
+            AdtKind::Struct => {}
         }
         AdtDefData {
             did: did,
@@ -1530,16 +1603,25 @@ impl<'tcx, 'container> AdtDefData<'tcx, 'container> {
         self.flags.set(self.flags.get() | AdtFlags::IS_DTORCK_VALID)
     }
 
-    /// Returns the kind of the ADT - Struct or Enum.
+    /// Returns the kind of the ADT - Struct, Enum or Union.
     #[inline]
     pub fn adt_kind(&self) -> AdtKind {
         if self.flags.get().intersects(AdtFlags::IS_ENUM) {
             AdtKind::Enum
+        } else if self.flags.get().intersects(AdtFlags::IS_UNION) {
+            AdtKind::Union
         } else {
             AdtKind::Struct
         }
     }
 
+    /// Returns true if this is an untagged union, whose fields all
+    /// share the same storage.
+    #[inline]
+    pub fn is_union(&self) -> bool {
+        self.flags.get().intersects(AdtFlags::IS_UNION)
+    }
+
     /// Returns whether this is a dtorck type. If this returns
     /// true, this type being safe for destruction requires it to be
     /// alive; Otherwise, only the contents are required to be.
@@ -1577,10 +1659,10 @@ impl<'tcx, 'container> AdtDefData<'tcx, 'container> {
         }
     }
 
-    /// Asserts this is a struct and returns the struct's unique
+    /// Asserts this is a struct or union and returns its unique
     /// variant.
     pub fn struct_variant(&self) -> &VariantDefData<'tcx, 'container> {
-        assert!(self.adt_kind() == AdtKind::Struct);
+        assert!(self.adt_kind() != AdtKind::Enum);
         &self.variants[0]
     }
 
@@ -1655,7 +1737,9 @@ impl<'tcx, 'container> AdtDefData<'tcx, 'container> {
     pub fn dtor_kind(&self) -> DtorKind {
         match self.destructor.get() {
             Some(_) => {
-                TraitDtor(!self.flags.get().intersects(AdtFlags::IS_NO_DROP_FLAG))
+                // Unions have no field of their own to keep a drop flag in.
+                let no_flag = AdtFlags::IS_NO_DROP_FLAG | AdtFlags::IS_UNION;
+                TraitDtor(!self.flags.get().intersects(no_flag))
             }
             None => NoDtor,
         }
@@ -1899,6 +1983,64 @@ impl<'tcx> TyCtxt<'tcx> {
         }
     }
 
+    /// Whether typeck records where error types come from
+    /// (`-Z track-err-types`).
+    pub fn tracks_err_types(&self) -> bool {
+        self.sess.opts.debugging_opts.track_err_types
+    }
+
+    /// Records that `node` was given a type referencing the error type. Only
+    /// the first origin recorded for a node is kept.
+    pub fn record_err_type(&self, node: NodeId, origin: ErrTypeOrigin) {
+        if !self.tracks_err_types() {
+            return;
+        }
+        self.err_type_origins.borrow_mut().entry(node).or_insert(origin);
+    }
+
+    /// Reports, for each node still typed with an error type that no other
+    /// recorded node got its error type from, how it got there: `msg` at the
+    /// node, then a note for each step back to where it was introduced.
+    pub fn report_err_types(&self, msg: &str) {
+        let origins = self.err_type_origins.borrow();
+        let parents: NodeSet = origins.values().filter_map(|origin| origin.parent).collect();
+        let mut leaves: Vec<_> = origins.iter().filter(|&(node, _)| {
+            !parents.contains(node) &&
+            self.node_id_to_type_opt(*node).map_or(false, |ty| ty.references_error())
+        }).collect();
+        leaves.sort_by(|a, b| a.1.span.lo.cmp(&b.1.span.lo));
+
+        for (&leaf, leaf_origin) in leaves {
+            let mut err = self.sess.struct_span_warn(leaf_origin.span, msg);
+            let mut seen = NodeSet();
+            let mut node = leaf;
+            let mut origin = leaf_origin;
+            while let Some(parent) = origin.parent {
+                match origins.get(&parent) {
+                    Some(parent_origin) if seen.insert(parent) => {
+                        err.span_note(parent_origin.span, "which it got from here");
+                        node = parent;
+                        origin = parent_origin;
+                    }
+                    _ => break,
+                }
+            }
+            let mut note = match origin.diagnostic {
+                0 => "introduced here, before any error was reported".to_string(),
+                n => format!("introduced here, after {} error(s) had been reported", n),
+            };
+            if origin.at_writeback {
+                note.push_str(", when writeback resolved its type");
+            }
+            if node == leaf {
+                err.note(&note);
+            } else {
+                err.span_note(origin.span, &note);
+            }
+            err.emit();
+        }
+    }
+
     pub fn node_id_to_type_opt(&self, id: NodeId) -> Option<Ty<'tcx>> {
         self.tables.borrow().node_types.get(&id).cloned()
     }
@@ -2154,6 +2296,15 @@ impl<'tcx> TyCtxt<'tcx> {
         }
     }
 
+    /// The impl that the trait impl `impl_def_id` specializes, if any.
+    pub fn impl_parent(&self, impl_def_id: DefId) -> Option<DefId> {
+        if impl_def_id.is_local() {
+            self.impl_parents.borrow().get(&impl_def_id).cloned()
+        } else {
+            self.sess.cstore.impl_parent(impl_def_id)
+        }
+    }
+
     pub fn custom_coerce_unsized_kind(&self, did: DefId) -> adjustment::CustomCoerceUnsized {
         self.custom_coerce_unsized_kinds.memoize(did, || {
             let (kind, src) = if did.krate != LOCAL_CRATE {
@@ -2215,6 +2366,14 @@ impl<'tcx> TyCtxt<'tcx> {
         self.with_path(id, |path| ast_map::path_to_string(path))
     }
 
+    pub fn def_key(&self, id: DefId) -> ast_map::DefKey {
+        if id.is_local() {
+            self.map.def_key(id)
+        } else {
+            self.sess.cstore.def_key(id)
+        }
+    }
+
     pub fn def_path(&self, id: DefId) -> ast_map::DefPath {
         if id.is_local() {
             self.map.def_path(id)
@@ -2340,6 +2499,16 @@ impl<'tcx> TyCtxt<'tcx> {
         self.get_attrs(did).iter().any(|item| item.check_name(attr))
     }
 
+    /// Whether calls to the function `did` pass the caller's location as
+    /// an implicit trailing argument.
+    pub fn is_track_caller(&self, did: DefId) -> bool {
+        if did.is_local() {
+            self.track_caller_fns.borrow().contains(&did)
+        } else {
+            self.has_attr(did, "track_caller")
+        }
+    }
+
     /// Determine whether an item is annotated with `#[repr(packed)]`
     pub fn lookup_packed(&self, did: DefId) -> bool {
         self.lookup_repr_hints(did).contains(&attr::ReprPacked)
@@ -2351,7 +2520,18 @@ impl<'tcx> TyCtxt<'tcx> {
             || self.lookup_repr_hints(did).contains(&attr::ReprSimd)
     }
 
+    /// The variances of the type and lifetime parameters of `item_id`.
+    /// For local items this runs variance inference if it has not run
+    /// yet, which is only possible once item types have been collected.
     pub fn item_variances(&self, item_id: DefId) -> Rc<ItemVariances> {
+        if item_id.is_local() && !self.variance_computed.get() {
+            match self.variance_provider.get() {
+                Some(infer_variance) => infer_variance(self),
+                None => self.sess.bug(&format!("variances of {:?} requested before \
+                                                 item types were collected",
+                                                item_id)),
+            }
+        }
         lookup_locally_or_in_crate_store(
             "item_variance_map", item_id, &self.item_variance_map,
             || Rc::new(self.sess.cstore.item_variances(item_id)))
@@ -2589,9 +2769,13 @@ impl<'tcx> TyCtxt<'tcx> {
             regions.push(def.space, region);
         }
 
+        // map N => N
+        let consts = generics.consts.map(|def| subst::ConstArg::Param(ParamConst::for_def(def)));
+
         Substs {
             types: types,
-            regions: subst::NonerasedRegions(regions)
+            regions: subst::NonerasedRegions(regions),
+            consts: consts
         }
     }
 
@@ -2695,9 +2879,59 @@ pub type CaptureModeMap = NodeMap<hir::CaptureClause>;
 // Trait method resolution
 pub type TraitMap = NodeMap<Vec<DefId>>;
 
-// Map from the NodeId of a glob import to a list of items which are actually
-// imported.
-pub type GlobMap = HashMap<NodeId, HashSet<Name>>;
+/// Map from the NodeId of a glob import to a list of items which are actually
+/// imported.
+///
+/// Resolution only appends the raw `(import, name)` pairs it sees, which is
+/// cheap even for crates with many globs. The per-import name lists are
+/// grouped, sorted and deduplicated the first time the map is queried, so
+/// consumers that never look at the map (or only look at a few imports) do
+/// not pay for building it.
+pub struct GlobMap {
+    pending: RefCell<Vec<(NodeId, Name)>>,
+    names: RefCell<NodeMap<Vec<Name>>>,
+}
+
+impl GlobMap {
+    pub fn new() -> GlobMap {
+        GlobMap {
+            pending: RefCell::new(Vec::new()),
+            names: RefCell::new(NodeMap()),
+        }
+    }
+
+    /// Records that `name` was resolved through the import `import_id`.
+    pub fn record(&self, import_id: NodeId, name: Name) {
+        self.pending.borrow_mut().push((import_id, name));
+    }
+
+    /// Returns the names actually imported through `import_id`, sorted by
+    /// their string value, or `None` if nothing was used through it.
+    pub fn names_imported_by(&self, import_id: NodeId) -> Option<Vec<Name>> {
+        self.populate();
+        self.names.borrow().get(&import_id).cloned()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.borrow().is_empty() && self.names.borrow().is_empty()
+    }
+
+    fn populate(&self) {
+        let mut pending = self.pending.borrow_mut();
+        if pending.is_empty() {
+            return;
+        }
+
+        let mut names = self.names.borrow_mut();
+        for (import_id, name) in pending.drain(..) {
+            names.entry(import_id).or_insert_with(Vec::new).push(name);
+        }
+        for list in names.values_mut() {
+            list.sort_by(|a, b| a.as_str().cmp(&b.as_str()));
+            list.dedup();
+        }
+    }
+}
 
 impl<'tcx> TyCtxt<'tcx> {
     pub fn with_freevars<T, F>(&self, fid: NodeId, f: F) -> T where
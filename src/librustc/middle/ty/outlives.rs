@@ -175,9 +175,11 @@ fn compute_components<'a,'tcx>(infcx: &InferCtxt<'a,'tcx>,
         ty::TyFloat(..) |       // OutlivesScalar
         ty::TyEnum(..) |        // OutlivesNominalType
         ty::TyStruct(..) |      // OutlivesNominalType
+        ty::TyAnon(..) |        // OutlivesNominalType (ish)
         ty::TyBox(..) |         // OutlivesNominalType (ish)
         ty::TyStr |             // OutlivesScalar (ish)
         ty::TyArray(..) |       // ...
+        ty::TyParamArray(..) |  // ...
         ty::TySlice(..) |       // ...
         ty::TyRawPtr(..) |      // ...
         ty::TyRef(..) |         // OutlivesReference
@@ -129,9 +129,12 @@ fn relate_item_substs<'a,'tcx:'a,R>(relation: &mut R,
            a_subst,
            b_subst);
 
+    // Once typeck has registered its provider, local variances are
+    // computed on demand, so they are as good as known.
+    let tcx = relation.tcx();
     let variances;
-    let opt_variances = if relation.tcx().variance_computed.get() {
-        variances = relation.tcx().item_variances(item_def_id);
+    let opt_variances = if tcx.variance_computed.get() || tcx.variance_provider.get().is_some() {
+        variances = tcx.item_variances(item_def_id);
         Some(&*variances)
     } else {
         None
@@ -156,6 +159,14 @@ pub fn relate_substs<'a,'tcx:'a,R>(relation: &mut R,
         substs.types.replace(space, tps);
     }
 
+    // Const arguments are never inferred, so they only relate when equal.
+    for (&a, &b) in a_subst.consts.iter().zip(b_subst.consts.iter()) {
+        if a != b {
+            return Err(TypeError::ConstArg(expected_found(relation, &a, &b)));
+        }
+    }
+    substs.consts = a_subst.consts.clone();
+
     match (&a_subst.regions, &b_subst.regions) {
         (&ErasedRegions, _) | (_, &ErasedRegions) => {
             substs.regions = ErasedRegions;
@@ -547,6 +558,12 @@ pub fn super_relate_tys<'a,'tcx:'a,R>(relation: &mut R,
             }
         }
 
+        (&ty::TyParamArray(a_t, len_a), &ty::TyParamArray(b_t, len_b)) if len_a == len_b =>
+        {
+            let t = try!(relation.relate(&a_t, &b_t));
+            Ok(tcx.mk_param_array(t, len_a))
+        }
+
         (&ty::TySlice(a_t), &ty::TySlice(b_t)) =>
         {
             let t = try!(relation.relate(&a_t, &b_t));
@@ -589,6 +606,13 @@ pub fn super_relate_tys<'a,'tcx:'a,R>(relation: &mut R,
             Ok(tcx.mk_projection(projection_ty.trait_ref, projection_ty.item_name))
         }
 
+        (&ty::TyAnon(a_def_id, a_substs), &ty::TyAnon(b_def_id, b_substs))
+            if a_def_id == b_def_id =>
+        {
+            let substs = try!(relate_substs(relation, None, a_substs, b_substs));
+            Ok(tcx.mk_anon(a_def_id, tcx.mk_substs(substs)))
+        }
+
         _ =>
         {
             Err(TypeError::Sorts(expected_found(relation, &a, &b)))
@@ -117,6 +117,10 @@ pub enum TypeVariants<'tcx> {
     /// An array with the given length. Written as `[T; n]`.
     TyArray(Ty<'tcx>, usize),
 
+    /// An array whose length is a const parameter, `[T; N]`. Substituting a
+    /// value for the parameter turns it into a `TyArray`.
+    TyParamArray(Ty<'tcx>, ParamConst),
+
     /// The pointee of an array slice.  Written as `[T]`.
     TySlice(Ty<'tcx>),
 
@@ -150,6 +154,14 @@ pub enum TypeVariants<'tcx> {
     /// `<T as Trait<..>>::N`.
     TyProjection(ProjectionTy<'tcx>),
 
+    /// An anonymized type, standing in for the concrete type of an
+    /// `impl Trait` in a function's return type. The `DefId` is that
+    /// of the `impl Trait` node and the `Substs` are those of the
+    /// enclosing function. Outside of the function, only the bounds
+    /// of the anonymized type are known; trans reveals the concrete
+    /// type recorded by typeck.
+    TyAnon(DefId, &'tcx Substs<'tcx>),
+
     /// A type parameter; for example, `T` in `fn f<T>(x: T) {}
     TyParam(ParamTy),
 
@@ -549,6 +561,24 @@ impl ParamTy {
     }
 }
 
+/// A const parameter; for example, `N` in `fn f<const N: usize>() {}`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ParamConst {
+    pub space: subst::ParamSpace,
+    pub idx: u32,
+    pub name: Name,
+}
+
+impl ParamConst {
+    pub fn new(space: subst::ParamSpace, index: u32, name: Name) -> ParamConst {
+        ParamConst { space: space, idx: index, name: name }
+    }
+
+    pub fn for_def(def: &ty::ConstParameterDef) -> ParamConst {
+        ParamConst::new(def.space, def.index, def.name)
+    }
+}
+
 /// A [De Bruijn index][dbi] is a standard means of representing
 /// regions (and perhaps later types) in a higher-ranked setting. In
 /// particular, imagine a type like this:
@@ -961,7 +991,7 @@ impl<'tcx> TyS<'tcx> {
     pub fn is_structural(&self) -> bool {
         match self.sty {
             TyStruct(..) | TyTuple(_) | TyEnum(..) |
-            TyArray(..) | TyClosure(..) => true,
+            TyArray(..) | TyParamArray(..) | TyClosure(..) => true,
             _ => self.is_slice() | self.is_trait()
         }
     }
@@ -976,7 +1006,7 @@ impl<'tcx> TyS<'tcx> {
 
     pub fn sequence_element_type(&self, cx: &TyCtxt<'tcx>) -> Ty<'tcx> {
         match self.sty {
-            TyArray(ty, _) | TySlice(ty) => ty,
+            TyArray(ty, _) | TyParamArray(ty, _) | TySlice(ty) => ty,
             TyStr => cx.mk_mach_uint(ast::UintTy::U8),
             _ => cx.sess.bug(&format!("sequence_element_type called on non-sequence value: {}",
                                       self)),
@@ -1133,7 +1163,7 @@ impl<'tcx> TyS<'tcx> {
     // Returns the type of ty[i]
     pub fn builtin_index(&self) -> Option<Ty<'tcx>> {
         match self.sty {
-            TyArray(ty, _) | TySlice(ty) => Some(ty),
+            TyArray(ty, _) | TyParamArray(ty, _) | TySlice(ty) => Some(ty),
             _ => None
         }
     }
@@ -1210,6 +1240,9 @@ impl<'tcx> TyS<'tcx> {
             TyProjection(ref data) => {
                 data.trait_ref.substs.regions().as_slice().to_vec()
             }
+            TyAnon(_, substs) => {
+                substs.regions().as_slice().to_vec()
+            }
             TyFnDef(..) |
             TyFnPtr(_) |
             TyBool |
@@ -1220,6 +1253,7 @@ impl<'tcx> TyS<'tcx> {
             TyBox(_) |
             TyStr |
             TyArray(_, _) |
+            TyParamArray(..) |
             TySlice(_) |
             TyRawPtr(_) |
             TyTuple(_) |
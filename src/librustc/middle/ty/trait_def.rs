@@ -17,7 +17,7 @@ use std::borrow::{Borrow};
 use std::cell::{Cell, Ref, RefCell};
 use syntax::ast::Name;
 use rustc_front::hir;
-use util::nodemap::FnvHashMap;
+use util::nodemap::{FnvHashMap, FnvHashSet};
 
 /// As `TypeScheme` but for a trait ref.
 pub struct TraitDef<'tcx> {
@@ -59,6 +59,13 @@ pub struct TraitDef<'tcx> {
     /// Blanket impls associated with the trait.
     blanket_impls: RefCell<Vec<DefId>>,
 
+    /// The methods of this trait that mention `Self` outside of their
+    /// receiver, and so cannot be called on a trait object. This is
+    /// recorded during collection (or read from metadata) so that object
+    /// safety checks need not walk the signatures again; `None` if that
+    /// has not happened.
+    methods_referencing_self: RefCell<Option<FnvHashSet<Name>>>,
+
     /// Various flags
     pub flags: Cell<TraitFlags>
 }
@@ -78,6 +85,7 @@ impl<'tcx> TraitDef<'tcx> {
             associated_type_names: associated_type_names,
             nonblanket_impls: RefCell::new(FnvHashMap()),
             blanket_impls: RefCell::new(vec![]),
+            methods_referencing_self: RefCell::new(None),
             flags: Cell::new(ty::TraitFlags::NO_TRAIT_FLAGS)
         }
     }
@@ -95,6 +103,16 @@ impl<'tcx> TraitDef<'tcx> {
         }
     }
 
+    /// Whether this is a trait alias (`trait Foo = Bar + Baz;`), whose
+    /// super-predicates are exactly the bounds it stands for.
+    pub fn is_alias(&self) -> bool {
+        self.flags.get().intersects(TraitFlags::IS_ALIAS)
+    }
+
+    pub fn set_is_alias(&self) {
+        self.flags.set(self.flags.get() | TraitFlags::IS_ALIAS)
+    }
+
     pub fn set_object_safety(&self, is_safe: bool) {
         assert!(self.object_safety().map(|cs| cs == is_safe).unwrap_or(true));
         self.flags.set(
@@ -106,6 +124,24 @@ impl<'tcx> TraitDef<'tcx> {
         );
     }
 
+    /// Whether the method `name` mentions `Self` outside of its receiver.
+    /// Returns `None` if this has not been recorded for this trait.
+    pub fn method_references_self(&self, name: Name) -> Option<bool> {
+        self.methods_referencing_self.borrow().as_ref().map(|names| names.contains(&name))
+    }
+
+    /// The methods recorded as mentioning `Self` outside of their receiver,
+    /// if any have been recorded, in no particular order.
+    pub fn methods_referencing_self(&self) -> Option<Vec<Name>> {
+        self.methods_referencing_self.borrow().as_ref().map(|names| {
+            names.iter().cloned().collect()
+        })
+    }
+
+    pub fn set_methods_referencing_self(&self, names: FnvHashSet<Name>) {
+        *self.methods_referencing_self.borrow_mut() = Some(names);
+    }
+
     fn write_trait_impls(&self, tcx: &TyCtxt<'tcx>) {
         tcx.dep_graph.write(DepNode::TraitImpls(self.trait_ref.def_id));
     }
@@ -221,6 +257,7 @@ bitflags! {
         const IS_OBJECT_SAFE        = 1 << 1,
         const OBJECT_SAFETY_VALID   = 1 << 2,
         const IMPLS_VALID           = 1 << 3,
+        const IS_ALIAS              = 1 << 4,
     }
 }
 
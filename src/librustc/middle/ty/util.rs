@@ -266,6 +266,20 @@ impl<'tcx> TyCtxt<'tcx> {
         }
     }
 
+    /// Replaces every anonymized type (`impl Trait`) in `value` with the
+    /// concrete type that typeck inferred for it. Only trans should need
+    /// to look behind an `impl Trait`.
+    pub fn reveal_anon_types<T: TypeFoldable<'tcx>>(&self, value: &T) -> T {
+        value.fold_with(&mut ty::fold::BottomUpFolder { tcx: self, fldop: |ty| {
+            if let TyAnon(def_id, substs) = ty.sty {
+                let concrete_ty = self.lookup_item_type(def_id).ty.subst(self, substs);
+                self.reveal_anon_types(&concrete_ty)
+            } else {
+                ty
+            }
+        }})
+    }
+
     /// Returns `(normalized_type, ty)`, where `normalized_type` is the
     /// IntType representation of one of {i64,i32,i16,i8,u64,u32,u16,u8},
     /// and `ty` is the original type (i.e. may include `isize` or
@@ -465,6 +479,18 @@ impl<'tcx> TyCtxt<'tcx> {
             let mt = |state: &mut SipHasher, mt: TypeAndMut| {
                 mt.mutbl.hash(state);
             };
+            let consts = |state: &mut SipHasher, substs: &subst::Substs| {
+                for c in substs.consts.as_slice() {
+                    match *c {
+                        subst::ConstArg::Value(v) => v.hash(state),
+                        subst::ConstArg::Param(p) => {
+                            p.space.hash(state);
+                            p.idx.hash(state);
+                            p.name.as_str().hash(state);
+                        }
+                    }
+                }
+            };
             let fn_sig = |state: &mut SipHasher, sig: &ty::Binder<ty::FnSig<'tcx>>| {
                 let sig = tcx.anonymize_late_bound_regions(sig).0;
                 for a in &sig.inputs { helper(tcx, *a, svh, state); }
@@ -491,9 +517,10 @@ impl<'tcx> TyCtxt<'tcx> {
                     TyStr => {
                         byte!(7);
                     }
-                    TyEnum(d, _) => {
+                    TyEnum(d, substs) => {
                         byte!(8);
                         did(state, d.did);
+                        consts(state, substs);
                     }
                     TyBox(_) => {
                         byte!(9);
@@ -537,9 +564,10 @@ impl<'tcx> TyCtxt<'tcx> {
 
                         return false;
                     }
-                    TyStruct(d, _) => {
+                    TyStruct(d, substs) => {
                         byte!(18);
                         did(state, d.did);
+                        consts(state, substs);
                     }
                     TyTuple(ref inner) => {
                         byte!(19);
@@ -562,6 +590,16 @@ impl<'tcx> TyCtxt<'tcx> {
                         did(state, data.trait_ref.def_id);
                         hash!(data.item_name.as_str());
                     }
+                    TyParamArray(_, p) => {
+                        byte!(24);
+                        hash!(p.space);
+                        hash!(p.idx);
+                        hash!(p.name.as_str());
+                    }
+                    TyAnon(def_id, _) => {
+                        byte!(25);
+                        did(state, def_id);
+                    }
                 }
                 true
             });
@@ -613,19 +651,25 @@ impl<'tcx> TyCtxt<'tcx> {
                            name: Name)
                            -> ImplMethod<'tcx>
     {
-        // there don't seem to be nicer accessors to these:
-        let impl_or_trait_items_map = self.impl_or_trait_items.borrow();
-
-        for impl_item in &self.impl_items.borrow()[&impl_def_id] {
-            if let ty::MethodTraitItem(ref meth) =
-                impl_or_trait_items_map[&impl_item.def_id()] {
-                if meth.name == name {
-                    return ImplMethod {
-                        method: meth.clone(),
-                        substs: substs,
-                        is_provided: false
-                    }
-                }
+        // Look in the impl, then in the impls it specializes.
+        let found = traits::find_impl_item(self, impl_def_id, |item| {
+            match *item {
+                ty::MethodTraitItem(ref meth) => meth.name == name,
+                _ => false,
+            }
+        });
+        if let Some((item_impl, ty::MethodTraitItem(meth))) = found {
+            let substs = if item_impl == impl_def_id {
+                substs
+            } else {
+                let infcx = infer::normalizing_infer_ctxt(self, &self.tables);
+                let substs = traits::translate_substs(&infcx, impl_def_id, substs, item_impl);
+                self.mk_substs(self.erase_regions(&substs))
+            };
+            return ImplMethod {
+                method: meth,
+                substs: substs,
+                is_provided: false
             }
         }
 
@@ -689,9 +733,9 @@ impl<'tcx> ty::TyS<'tcx> {
                 mutbl: hir::MutMutable, ..
             }) => Some(true),
 
-            TyArray(..) | TySlice(_) | TyTrait(..) | TyTuple(..) |
+            TyArray(..) | TyParamArray(..) | TySlice(_) | TyTrait(..) | TyTuple(..) |
             TyClosure(..) | TyEnum(..) | TyStruct(..) |
-            TyProjection(..) | TyParam(..) | TyInfer(..) | TyError => None
+            TyProjection(..) | TyAnon(..) | TyParam(..) | TyInfer(..) | TyError => None
         }.unwrap_or_else(|| !self.impls_bound(param_env, ty::BoundCopy, span));
 
         if !self.has_param_types() && !self.has_self_ty() {
@@ -724,11 +768,11 @@ impl<'tcx> ty::TyS<'tcx> {
         let result = match self.sty {
             TyBool | TyChar | TyInt(..) | TyUint(..) | TyFloat(..) |
             TyBox(..) | TyRawPtr(..) | TyRef(..) | TyFnDef(..) | TyFnPtr(_) |
-            TyArray(..) | TyTuple(..) | TyClosure(..) => Some(true),
+            TyArray(..) | TyParamArray(..) | TyTuple(..) | TyClosure(..) => Some(true),
 
             TyStr | TyTrait(..) | TySlice(_) => Some(false),
 
-            TyEnum(..) | TyStruct(..) | TyProjection(..) | TyParam(..) |
+            TyEnum(..) | TyStruct(..) | TyProjection(..) | TyAnon(..) | TyParam(..) |
             TyInfer(..) | TyError => None
         }.unwrap_or_else(|| self.impls_bound(param_env, ty::BoundSized, span));
 
@@ -767,7 +811,7 @@ impl<'tcx> ty::TyS<'tcx> {
                 }
                 // Fixed-length vectors.
                 // FIXME(#11924) Behavior undecided for zero-length vectors.
-                TyArray(ty, _) => {
+                TyArray(ty, _) | TyParamArray(ty, _) => {
                     is_type_structurally_recursive(cx, sp, seen, ty)
                 }
                 TyStruct(def, substs) | TyEnum(def, substs) => {
@@ -297,7 +297,8 @@ impl<'a,'tcx> WfPredicates<'a,'tcx> {
                 }
 
                 ty::TySlice(subty) |
-                ty::TyArray(subty, _) => {
+                ty::TyArray(subty, _) |
+                ty::TyParamArray(subty, _) => {
                     if !subty.has_escaping_regions() {
                         let cause = self.cause(traits::SliceOrArrayElem);
                         match traits::trait_ref_for_builtin_bound(self.infcx.tcx,
@@ -359,6 +360,12 @@ impl<'a,'tcx> WfPredicates<'a,'tcx> {
                     // types appearing in the fn signature
                 }
 
+                ty::TyAnon(..) => {
+                    // all of the requirements on the substs were
+                    // already checked when the function returning
+                    // this `impl Trait` was called.
+                }
+
                 ty::TyTrait(ref data) => {
                     // WfObject
                     //
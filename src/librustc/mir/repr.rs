@@ -699,7 +699,10 @@ pub enum CastKind {
 pub enum AggregateKind<'tcx> {
     Vec,
     Tuple,
-    Adt(AdtDef<'tcx>, usize, &'tcx Substs<'tcx>),
+    /// The ADT, the index of the variant being built and its substs. For a
+    /// union, the last field is the index of the one field being set, and
+    /// the aggregate has a single operand; otherwise it is `None`.
+    Adt(AdtDef<'tcx>, usize, &'tcx Substs<'tcx>, Option<usize>),
     Closure(DefId, &'tcx ClosureSubsts<'tcx>),
 }
 
@@ -793,7 +796,7 @@ impl<'tcx> Debug for Rvalue<'tcx> {
                         }
                     }
 
-                    Adt(adt_def, variant, _) => {
+                    Adt(adt_def, variant, _, active_field) => {
                         let variant_def = &adt_def.variants[variant];
                         let name = ty::tls::with(|tcx| tcx.item_path_str(variant_def.did));
 
@@ -802,8 +805,13 @@ impl<'tcx> Debug for Rvalue<'tcx> {
                             ty::VariantKind::Tuple => fmt_tuple(fmt, &name, lvs),
                             ty::VariantKind::Struct => {
                                 let mut struct_fmt = fmt.debug_struct(&name);
-                                for (field, lv) in variant_def.fields.iter().zip(lvs) {
-                                    struct_fmt.field(&field.name.as_str(), lv);
+                                if let Some(i) = active_field {
+                                    struct_fmt.field(&variant_def.fields[i].name.as_str(),
+                                                     &lvs[0]);
+                                } else {
+                                    for (field, lv) in variant_def.fields.iter().zip(lvs) {
+                                        struct_fmt.field(&field.name.as_str(), lv);
+                                    }
                                 }
                                 struct_fmt.finish()
                             }
@@ -870,6 +878,10 @@ pub enum Literal<'tcx> {
     Value {
         value: ConstVal,
     },
+    /// The location of the call this constant is an argument of, passed
+    /// implicitly to `#[track_caller]` functions. Within such a function,
+    /// this is the location the function itself was called from.
+    CallerLocation,
 }
 
 impl<'tcx> Debug for Constant<'tcx> {
@@ -888,6 +900,7 @@ impl<'tcx> Debug for Literal<'tcx> {
                 try!(write!(fmt, "const "));
                 fmt_const_val(fmt, value)
             }
+            CallerLocation => write!(fmt, "caller_location"),
         }
     }
 }
@@ -189,7 +189,7 @@ impl<'tcx> Mir<'tcx> {
                             ops.iter().map(|op| self.operand_ty(tcx, op)).collect()
                         ))
                     }
-                    AggregateKind::Adt(def, _, substs) => {
+                    AggregateKind::Adt(def, _, substs, _) => {
                         Some(def.type_scheme(tcx).ty.subst(tcx, substs))
                     }
                     AggregateKind::Closure(did, substs) => {
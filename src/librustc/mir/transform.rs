@@ -15,7 +15,12 @@ use syntax::ast::NodeId;
 
 /// Various information about pass.
 pub trait Pass {
-    // fn name() for printouts of various sorts?
+    /// A short name for the pass, used when reporting which pass a
+    /// given MIR came out of.
+    fn name(&self) -> &'static str {
+        "<unnamed pass>"
+    }
+
     // fn should_run(Session) to check if pass should run?
 }
 
@@ -53,11 +58,20 @@ impl Passes {
     }
 
     pub fn run_passes<'tcx>(&mut self, pcx: &TyCtxt<'tcx>, map: &mut MirMap<'tcx>) {
-        for pass in &mut self.plugin_passes {
-            pass.run_pass(pcx, map);
-        }
-        for pass in &mut self.passes {
+        self.run_passes_with(pcx, map, |_, _| {});
+    }
+
+    /// Like `run_passes`, but calls `after_pass` with the name of each pass
+    /// and the MIR it produced, once that pass has finished.
+    pub fn run_passes_with<'tcx, F>(&mut self,
+                                    pcx: &TyCtxt<'tcx>,
+                                    map: &mut MirMap<'tcx>,
+                                    mut after_pass: F)
+        where F: FnMut(&str, &MirMap<'tcx>)
+    {
+        for pass in self.plugin_passes.iter_mut().chain(&mut self.passes) {
             pass.run_pass(pcx, map);
+            after_pass(pass.name(), map);
         }
     }
 
@@ -314,7 +314,8 @@ macro_rules! make_mir_visitor {
                     Literal::Item { ref $($mutability)* def_id, .. } => {
                         self.visit_def_id(def_id);
                     },
-                    Literal::Value { .. } => {
+                    Literal::Value { .. } |
+                    Literal::CallerLocation => {
                         // Nothing to do
                     }
                 }
@@ -25,6 +25,7 @@ use middle::cstore;
 
 use syntax::ast::{self, IntTy, UintTy};
 use syntax::attr;
+use syntax::edition::{Edition, ALL_EDITIONS};
 use syntax::attr::AttrMetaMethods;
 use syntax::errors::{ColorConfig, Handler};
 use syntax::parse;
@@ -69,12 +70,18 @@ pub enum OutputType {
     Object,
     Exe,
     DepInfo,
+    Metadata,
+    Mir,
+    ImplGraph,
+    AstJson,
+    AccessLevels,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ErrorOutputType {
     HumanReadable(ColorConfig),
     Json,
+    Short(ColorConfig),
 }
 
 impl Default for ErrorOutputType {
@@ -87,7 +94,12 @@ impl OutputType {
     fn is_compatible_with_codegen_units_and_single_output_file(&self) -> bool {
         match *self {
             OutputType::Exe |
-            OutputType::DepInfo => true,
+            OutputType::DepInfo |
+            OutputType::Metadata |
+            OutputType::Mir |
+            OutputType::ImplGraph |
+            OutputType::AstJson |
+            OutputType::AccessLevels => true,
             OutputType::Bitcode |
             OutputType::Assembly |
             OutputType::LlvmAssembly |
@@ -103,6 +115,11 @@ impl OutputType {
             OutputType::Object => "obj",
             OutputType::Exe => "link",
             OutputType::DepInfo => "dep-info",
+            OutputType::Metadata => "metadata",
+            OutputType::Mir => "mir",
+            OutputType::ImplGraph => "impl-graph",
+            OutputType::AstJson => "ast-json",
+            OutputType::AccessLevels => "access-levels",
         }
     }
 }
@@ -127,6 +144,9 @@ pub struct Options {
     pub search_paths: SearchPaths,
     pub libs: Vec<(String, cstore::NativeLibraryKind)>,
     pub maybe_sysroot: Option<PathBuf>,
+    /// Sysroots given by further `--sysroot` flags, whose libraries are
+    /// searched in order after those of the first.
+    pub extra_sysroots: Vec<PathBuf>,
     pub target_triple: String,
     // User-specified cfg meta items. The compiler itself will add additional
     // items to the crate config, and during parsing the entire crate config
@@ -134,6 +154,8 @@ pub struct Options {
     // anything except building the full crate config prior to parsing.
     pub cfg: ast::CrateConfig,
     pub test: bool,
+    /// `--tests-list`: print the tests found and stop before analysis.
+    pub tests_list: bool,
     pub parse_only: bool,
     pub no_trans: bool,
     pub error_format: ErrorOutputType,
@@ -157,7 +179,36 @@ pub struct Options {
     /// out-of-tree drivers.
     pub alt_std_name: Option<String>,
     /// Indicates how the compiler should treat unstable features
-    pub unstable_features: UnstableFeatures
+    pub unstable_features: UnstableFeatures,
+    /// The edition requested with `--edition`, if any. Crates may also
+    /// select an edition with `#![edition = "..."]`.
+    pub edition: Option<Edition>,
+}
+
+impl Options {
+    /// True if crate metadata is the only output requested (dep-info, MIR,
+    /// the impl graph, the AST and access levels aside), in which case translation, LLVM
+    /// passes and linking are all skipped.
+    pub fn only_metadata_requested(&self) -> bool {
+        self.output_types.contains_key(&OutputType::Metadata) &&
+        self.output_types.keys().all(|&ot| {
+            ot == OutputType::Metadata || ot == OutputType::DepInfo ||
+            ot == OutputType::Mir || ot == OutputType::ImplGraph ||
+            ot == OutputType::AstJson || ot == OutputType::AccessLevels
+        })
+    }
+
+    /// True if the only outputs requested (dep-info and the AST) are ready
+    /// after expansion and nothing else needs the crate analysed, in which
+    /// case compilation stops once they have been written.
+    pub fn only_expansion_outputs_requested(&self) -> bool {
+        !self.output_types.is_empty() &&
+        self.output_types.keys().all(|&ot| {
+            ot == OutputType::DepInfo || ot == OutputType::AstJson
+        }) &&
+        !self.debugging_opts.save_analysis &&
+        !self.prints.contains(&PrintRequest::Features)
+    }
 }
 
 #[derive(Clone, PartialEq, Eq)]
@@ -167,13 +218,20 @@ pub enum PrintRequest {
     CrateName,
     Cfg,
     TargetList,
+    TargetSpec,
+    Features,
 }
 
 pub enum Input {
     /// Load source from file
     File(PathBuf),
     /// The string is the source
-    Str(String)
+    Str(String),
+    /// A crate that has already been configured and expanded, e.g. one
+    /// captured by an `after_expand` callback, to be compiled from node-id
+    /// assignment on. It must have been parsed with the session compiling
+    /// it, so its spans are valid, and its node ids must not be assigned.
+    ExpandedCrate(ast::Crate),
 }
 
 impl Input {
@@ -181,7 +239,7 @@ impl Input {
         match *self {
             Input::File(ref ifile) => ifile.file_stem().unwrap()
                                            .to_str().unwrap().to_string(),
-            Input::Str(_) => "rust_out".to_string(),
+            Input::Str(_) | Input::ExpandedCrate(_) => "rust_out".to_string(),
         }
     }
 }
@@ -210,7 +268,14 @@ impl OutputFilenames {
             OutputType::LlvmAssembly => base.with_extension("ll"),
             OutputType::Object => base.with_extension("o"),
             OutputType::DepInfo => base.with_extension("d"),
+            OutputType::Mir => base.with_extension("mir"),
+            OutputType::ImplGraph => base.with_extension("impl-graph.json"),
+            OutputType::AstJson => base.with_extension("ast.json"),
+            OutputType::AccessLevels => base.with_extension("access-levels.json"),
             OutputType::Exe => base,
+            OutputType::Metadata => {
+                self.out_directory.join(&format!("lib{}.rmeta", self.filestem()))
+            }
         }
     }
 
@@ -249,9 +314,11 @@ pub fn basic_options() -> Options {
         output_types: HashMap::new(),
         search_paths: SearchPaths::new(),
         maybe_sysroot: None,
+        extra_sysroots: Vec::new(),
         target_triple: host_triple().to_string(),
         cfg: Vec::new(),
         test: false,
+        tests_list: false,
         parse_only: false,
         no_trans: false,
         treat_err_as_bug: false,
@@ -269,6 +336,7 @@ pub fn basic_options() -> Options {
         libs: Vec::new(),
         unstable_features: UnstableFeatures::Disallow,
         debug_assertions: true,
+        edition: None,
     }
 }
 
@@ -388,7 +456,7 @@ macro_rules! options {
         pub const parse_opt_list: Option<&'static str> = Some("a space-separated list of strings");
         pub const parse_uint: Option<&'static str> = Some("a number");
         pub const parse_passes: Option<&'static str> =
-            Some("a space-separated list of passes, or `all`");
+            Some("a comma or space separated list of passes, or `all`");
         pub const parse_opt_uint: Option<&'static str> =
             Some("a number");
     }
@@ -488,15 +556,15 @@ macro_rules! options {
                     *slot = AllPasses;
                     true
                 }
-                v => {
-                    let mut passes = vec!();
-                    if parse_list(&mut passes, v) {
-                        *slot = SomePasses(passes);
-                        true
-                    } else {
-                        false
-                    }
+                Some(s) => {
+                    let passes = s.split(|c: char| c == ',' || c.is_whitespace())
+                                  .filter(|pass| !pass.is_empty())
+                                  .map(|pass| pass.to_string())
+                                  .collect();
+                    *slot = SomePasses(passes);
+                    true
                 }
+                None => false,
             }
         }
     }
@@ -552,7 +620,8 @@ options! {CodegenOptions, CodegenSetter, basic_codegen_options,
     codegen_units: usize = (1, parse_uint,
         "divide crate into N units to optimize in parallel"),
     remark: Passes = (SomePasses(Vec::new()), parse_passes,
-        "print remarks for these optimization passes (space separated, or \"all\")"),
+        "print remarks for these optimization passes (comma or space separated, \
+         `inline` or `vectorize` for groups of passes, or \"all\")"),
     no_stack_check: bool = (false, parse_bool,
         "disable checks for stack exhaustion (a memory-safety hazard!)"),
     debuginfo: Option<usize> = (None, parse_opt_uint,
@@ -574,10 +643,36 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "in general, enable more debug printouts"),
     time_passes: bool = (false, parse_bool,
         "measure time of each rustc pass"),
+    time_passes_json: bool = (false, parse_bool,
+        "measure time of each rustc pass and write the results as JSON next to the outputs"),
+    memory_stats: bool = (false, parse_bool,
+        "print memory usage and type context arena sizes after each major pass"),
+    dep_info_json: bool = (false, parse_bool,
+        "write dep-info as JSON (input files, environment variables read and outputs) \
+         instead of Makefile rules"),
+    randomize_layout_of_hashmaps: bool = (false, parse_bool,
+        "perturb the hashing of the compiler's FNV hash maps with a random seed, which \
+         is printed, to catch output that depends on their iteration order"),
+    hashmap_seed: Option<usize> = (None, parse_opt_uint,
+        "the seed to use for -Z randomize-layout-of-hashmaps, to reproduce a run"),
+    server: bool = (false, parse_bool,
+        "stay resident and run the compile jobs read from stdin, one JSON array of \
+         arguments per line, reporting each one's exit status on stdout"),
+    sysroot_manifest: Option<String> = (None, parse_opt_string,
+        "a file listing where the sysroot crates live, as `name = path` lines; these \
+         crates are loaded from there instead of being searched for"),
+    deterministic_output: bool = (false, parse_bool,
+        "sort everything that reaches the outputs in hash map order, so that identical \
+         inputs give bitwise identical artifacts"),
     count_llvm_insns: bool = (false, parse_bool,
         "count where LLVM instrs originate"),
     time_llvm_passes: bool = (false, parse_bool,
         "measure time of each LLVM pass"),
+    time_items: bool = (false, parse_bool,
+        "measure the time type checking and translation spend on each item and \
+         print the slowest ones"),
+    time_items_count: usize = (10, parse_uint,
+        "how many items -Z time-items prints for each phase"),
     input_stats: bool = (false, parse_bool,
         "gather statistics about the input"),
     trans_stats: bool = (false, parse_bool,
@@ -603,9 +698,9 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
     print_llvm_passes: bool = (false, parse_bool,
         "prints the llvm optimization passes being run"),
     ast_json: bool = (false, parse_bool,
-        "print the AST as JSON and halt"),
+        "print the AST as JSON and halt; the same as `--unpretty expanded,ast-json`"),
     ast_json_noexpand: bool = (false, parse_bool,
-        "print the pre-expansion AST as JSON and halt"),
+        "print the pre-expansion AST as JSON and halt; the same as `--unpretty ast-json`"),
     ls: bool = (false, parse_bool,
         "list the symbols defined by a library crate"),
     save_analysis: bool = (false, parse_bool,
@@ -627,6 +722,9 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
           "parse only; do not compile, assemble, or link"),
     no_trans: bool = (false, parse_bool,
           "run all passes except translation; no output"),
+    check_only: bool = (false, parse_bool,
+          "check the crate, including borrowck, without translating it; \
+           only metadata and dep-info are written, if requested"),
     treat_err_as_bug: bool = (false, parse_bool,
           "treat all errors that occur as bugs"),
     incr_comp: bool = (false, parse_bool,
@@ -637,16 +735,41 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
           "parse and expand the source, but run no analysis"),
     extra_plugins: Vec<String> = (Vec::new(), parse_list,
         "load extra plugins"),
+    abi_plugins: Vec<String> = (Vec::new(), parse_list,
+        "load the plugins built against the stable `rustc_plugin_abi` facade at these paths"),
     unstable_options: bool = (false, parse_bool,
           "adds unstable command line options to rustc interface"),
     print_enum_sizes: bool = (false, parse_bool,
           "print the size of enums and their variants"),
+    sandbox_io: bool = (false, parse_bool,
+          "don't let `include!`, `env!` and plugins read anything besides the crate's \
+           sources, except what the `-Z sandbox-allow-*` options allow"),
+    sandbox_allow_path: Vec<String> = (Vec::new(), parse_list,
+          "with `-Z sandbox-io`, let `include!` and friends read files under these directories"),
+    sandbox_allow_env: Vec<String> = (Vec::new(), parse_list,
+          "with `-Z sandbox-io`, let `env!` and `option_env!` read these environment variables"),
+    sandbox_allow_plugins: bool = (false, parse_bool,
+          "with `-Z sandbox-io`, allow compiler plugins to be loaded"),
+    verify_parallel_determinism: bool = (false, parse_bool,
+          "also run the codegen units one at a time and check that the parallel build \
+           produced the same outputs and diagnostics"),
+    explain_crate_loading: bool = (false, parse_bool,
+          "when a crate can't be loaded, list every directory searched and every \
+           candidate rejected, with the reason"),
+    track_err_types: bool = (false, parse_bool,
+          "record where expressions got the error type, and print how it spread when \
+           typeck fails or if it reaches translation"),
+    explicit_exports: bool = (false, parse_bool,
+          "only export the items marked `#[export]` from dylibs and staticlibs"),
     force_overflow_checks: Option<bool> = (None, parse_opt_bool,
           "force overflow checks on or off"),
     force_dropflag_checks: Option<bool> = (None, parse_opt_bool,
           "force drop flag checks on or off"),
     trace_macros: bool = (false, parse_bool,
           "for every macro invocation, print its name and arguments"),
+    trace_attrs: bool = (false, parse_bool,
+          "print which attributes `cfg_attr`, derives and plugins add to or remove from \
+           each item during expansion, and why"),
     enable_nonzeroing_move_hints: bool = (false, parse_bool,
           "force nonzeroing move optimization on"),
     keep_mtwt_tables: bool = (false, parse_bool,
@@ -659,6 +782,10 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
           "print the result of the translation item collection pass"),
     mir_opt_level: Option<usize> = (None, parse_opt_uint,
           "set the MIR optimization level (0-3)"),
+    interpret: Option<String> = (None, parse_opt_string,
+          "run the named function on the MIR interpreter after analysis"),
+    expected_type: Option<String> = (None, parse_opt_string,
+          "print the type expected at `file:line:col` as JSON after analysis"),
 }
 
 pub fn default_lib_output() -> CrateType {
@@ -779,7 +906,6 @@ impl RustcOptGroup {
         RustcOptGroup { opt_group: g, stability: OptionStability::Stable }
     }
 
-    #[allow(dead_code)] // currently we have no "truly unstable" options
     fn unstable(g: getopts::OptGroup) -> RustcOptGroup {
         RustcOptGroup { opt_group: g, stability: OptionStability::Unstable }
     }
@@ -884,10 +1010,12 @@ pub fn rustc_short_optgroups() -> Vec<RustcOptGroup> {
                "NAME"),
         opt::multi_s("", "emit", "Comma separated list of types of output for \
                               the compiler to emit",
-                 "[asm|llvm-bc|llvm-ir|obj|link|dep-info]"),
+                 "[asm|llvm-bc|llvm-ir|obj|link|dep-info|metadata|mir|impl-graph|ast-json|\
+                   access-levels|none]"),
         opt::multi_s("", "print", "Comma separated list of compiler information to \
                                print on stdout",
-                 "[crate-name|file-names|sysroot|cfg|target-list]"),
+                 "[crate-name|file-names|sysroot|cfg|target-list|target-spec-json|\
+                   features]"),
         opt::flagmulti_s("g",  "",  "Equivalent to -C debuginfo=2"),
         opt::flagmulti_s("O", "", "Equivalent to -C opt-level=2"),
         opt::opt_s("o", "", "Write output to <filename>", "FILENAME"),
@@ -919,11 +1047,15 @@ pub fn rustc_optgroups() -> Vec<RustcOptGroup> {
         opt::multi_s("", "extern", "Specify where an external rust library is \
                                 located",
                  "NAME=PATH"),
-        opt::opt_s("", "sysroot", "Override the system root", "PATH"),
+        opt::multi_s("", "sysroot", "Override the system root. May be given more \
+                                   than once to search several sysroots in order", "PATH"),
         opt::multi_ubnr("Z", "", "Set internal debugging options", "FLAG"),
         opt::opt_ubnr("", "error-format",
                       "How errors and other messages are produced",
-                      "human|json"),
+                      "human|json|short"),
+        opt::opt("", "edition",
+                 "Specify which edition of the language the crate is written in",
+                 "2015|2018"),
         opt::opt_s("", "color", "Configure coloring of output:
             auto   = colorize, if output goes to a tty (default);
             always = always colorize output;
@@ -940,9 +1072,13 @@ pub fn rustc_optgroups() -> Vec<RustcOptGroup> {
                       valid types are any of the types for `--pretty`, as well as:
                       `flowgraph=<nodeid>` (graphviz formatted flowgraph for node),
                       `everybody_loops` (all function bodies replaced with `loop {}`),
+                      `ast-json` or `expanded,ast-json` (the AST as JSON),
                       `hir` (the HIR), `hir,identified`, or
                       `hir,typed` (HIR with types for each node).",
                      "TYPE"),
+        opt::flag("", "tests-list",
+                  "Build the test harness, print the tests it would run as JSON and stop \
+                   before analysis"),
 
         // new options here should **not** use the `_ubnr` functions, all new
         // unstable options should use the short variants to indicate that they
@@ -997,13 +1133,14 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
         match matches.opt_str("error-format").as_ref().map(|s| &s[..]) {
             Some("human")   => ErrorOutputType::HumanReadable(color),
             Some("json") => ErrorOutputType::Json,
+            Some("short") => ErrorOutputType::Short(color),
 
             None => ErrorOutputType::HumanReadable(color),
 
             Some(arg) => {
                 early_error(ErrorOutputType::HumanReadable(color),
-                            &format!("argument for --error-format must be human or json (instead \
-                                      was `{}`)",
+                            &format!("argument for --error-format must be human, json or short \
+                                      (instead was `{}`)",
                                      arg))
             }
         }
@@ -1011,6 +1148,20 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
         ErrorOutputType::HumanReadable(color)
     };
 
+    // Like error-format, edition is unstable and may not be in `matches`.
+    let edition = if matches.opts_present(&["edition".to_owned()]) {
+        matches.opt_str("edition").map(|s| {
+            s.parse::<Edition>().unwrap_or_else(|()| {
+                let known = ALL_EDITIONS.iter().map(|e| e.as_str()).collect::<Vec<_>>();
+                early_error(error_format, &format!("argument for --edition must be one of: \
+                                                    {} (instead was `{}`)",
+                                                   known.join(", "), s))
+            })
+        })
+    } else {
+        None
+    };
+
     let unparsed_crate_types = matches.opt_strs("crate-type");
     let crate_types = parse_crate_types_from_list(unparsed_crate_types)
         .unwrap_or_else(|e| early_error(error_format, &e[..]));
@@ -1037,7 +1188,7 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
     let debugging_opts = build_debugging_options(matches, error_format);
 
     let parse_only = debugging_opts.parse_only;
-    let no_trans = debugging_opts.no_trans;
+    let mut no_trans = debugging_opts.no_trans;
     let treat_err_as_bug = debugging_opts.treat_err_as_bug;
     let mir_opt_level = debugging_opts.mir_opt_level.unwrap_or(1);
     let incremental_compilation = debugging_opts.incr_comp;
@@ -1049,17 +1200,27 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
     }
 
     let mut output_types = HashMap::new();
+    let mut emit_none = false;
     if !debugging_opts.parse_only && !no_trans {
         for list in matches.opt_strs("emit") {
             for output_type in list.split(',') {
                 let mut parts = output_type.splitn(2, '=');
                 let output_type = match parts.next().unwrap() {
+                    "none" => {
+                        emit_none = true;
+                        continue;
+                    }
                     "asm" => OutputType::Assembly,
                     "llvm-ir" => OutputType::LlvmAssembly,
                     "llvm-bc" => OutputType::Bitcode,
                     "obj" => OutputType::Object,
                     "link" => OutputType::Exe,
                     "dep-info" => OutputType::DepInfo,
+                    "metadata" => OutputType::Metadata,
+                    "mir" => OutputType::Mir,
+                    "impl-graph" => OutputType::ImplGraph,
+                    "ast-json" => OutputType::AstJson,
+                    "access-levels" => OutputType::AccessLevels,
                     part => {
                         early_error(error_format, &format!("unknown emission type: `{}`",
                                                     part))
@@ -1071,7 +1232,12 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
         }
     };
     if output_types.is_empty() {
-        output_types.insert(OutputType::Exe, None);
+        if emit_none {
+            // Nothing to write means nothing to translate either.
+            no_trans = true;
+        } else {
+            output_types.insert(OutputType::Exe, None);
+        }
     }
 
     let mut cg = build_codegen_options(matches, error_format);
@@ -1101,7 +1267,9 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
 
     let cg = cg;
 
-    let sysroot_opt = matches.opt_str("sysroot").map(|m| PathBuf::from(&m));
+    let mut sysroots = matches.opt_strs("sysroot").into_iter().map(PathBuf::from);
+    let sysroot_opt = sysroots.next();
+    let extra_sysroots = sysroots.collect();
     let target = matches.opt_str("target").unwrap_or(
         host_triple().to_string());
     let opt_level = {
@@ -1168,7 +1336,8 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
     }).collect();
 
     let cfg = parse_cfgspecs(matches.opt_strs("cfg"));
-    let test = matches.opt_present("test");
+    let tests_list = matches.opt_present("tests-list");
+    let test = matches.opt_present("test") || tests_list;
 
     let prints = matches.opt_strs("print").into_iter().map(|s| {
         match &*s {
@@ -1177,6 +1346,8 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
             "sysroot" => PrintRequest::Sysroot,
             "cfg" => PrintRequest::Cfg,
             "target-list" => PrintRequest::TargetList,
+            "target-spec-json" => PrintRequest::TargetSpec,
+            "features" => PrintRequest::Features,
             req => {
                 early_error(error_format, &format!("unknown print request `{}`", req))
             }
@@ -1216,9 +1387,11 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
         output_types: output_types,
         search_paths: search_paths,
         maybe_sysroot: sysroot_opt,
+        extra_sysroots: extra_sysroots,
         target_triple: target,
         cfg: cfg,
         test: test,
+        tests_list: tests_list,
         parse_only: parse_only,
         no_trans: no_trans,
         treat_err_as_bug: treat_err_as_bug,
@@ -1236,6 +1409,7 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
         libs: libs,
         unstable_features: get_unstable_features_setting(),
         debug_assertions: debug_assertions,
+        edition: edition,
     }
 }
 
@@ -33,6 +33,7 @@ pub enum FileMatch {
 
 pub struct FileSearch<'a> {
     pub sysroot: &'a Path,
+    pub extra_sysroots: &'a [PathBuf],
     pub search_paths: &'a SearchPaths,
     pub triple: &'a str,
     pub kind: PathKind,
@@ -57,6 +58,14 @@ impl<'a> FileSearch<'a> {
         }
 
         visited_dirs.insert(tlib_path);
+
+        for sysroot in self.extra_sysroots {
+            let tlib_path = make_target_lib_path(sysroot, self.triple);
+            if !visited_dirs.contains(&tlib_path) {
+                f(&tlib_path, PathKind::All);
+            }
+            visited_dirs.insert(tlib_path);
+        }
     }
 
     pub fn get_lib_path(&self) -> PathBuf {
@@ -100,12 +109,14 @@ impl<'a> FileSearch<'a> {
     }
 
     pub fn new(sysroot: &'a Path,
+               extra_sysroots: &'a [PathBuf],
                triple: &'a str,
                search_paths: &'a SearchPaths,
                kind: PathKind) -> FileSearch<'a> {
         debug!("using sysroot = {}, triple = {}", sysroot.display(), triple);
         FileSearch {
             sysroot: sysroot,
+            extra_sysroots: extra_sysroots,
             search_paths: search_paths,
             triple: triple,
             kind: kind,
@@ -132,6 +143,58 @@ impl<'a> FileSearch<'a> {
     }
 }
 
+/// A sysroot manifest (`-Z sysroot-manifest`) names the file each sysroot
+/// crate should be loaded from, for setups where std and friends don't live
+/// in a sysroot's usual layout. It has one `name = path` entry per line;
+/// blank lines and lines starting with `#` are ignored, and relative paths
+/// are relative to the manifest itself.
+///
+/// The entries are only checked when the crate is loaded, see
+/// `rustc_metadata::loader`.
+pub struct SysrootManifest {
+    pub path: PathBuf,
+    crates: Vec<(String, PathBuf)>,
+}
+
+impl SysrootManifest {
+    pub fn load(path: &Path) -> Result<SysrootManifest, String> {
+        let mut contents = String::new();
+        if let Err(e) = fs::File::open(path).and_then(|mut f| f.read_to_string(&mut contents)) {
+            return Err(format!("couldn't read sysroot manifest `{}`: {}", path.display(), e));
+        }
+        let dir = path.parent().unwrap_or(Path::new(""));
+
+        let mut crates: Vec<(String, PathBuf)> = vec![];
+        for (i, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with("#") {
+                continue
+            }
+            let mut parts = line.splitn(2, '=');
+            let name = parts.next().unwrap().trim();
+            let lib = match parts.next() {
+                Some(lib) if !name.is_empty() && !lib.trim().is_empty() => lib.trim(),
+                _ => {
+                    return Err(format!("{}:{}: expected `name = path`, found `{}`",
+                                       path.display(), i + 1, line));
+                }
+            };
+            if crates.iter().any(|&(ref n, _)| n == name) {
+                return Err(format!("{}:{}: crate `{}` is listed more than once",
+                                   path.display(), i + 1, name));
+            }
+            crates.push((name.to_string(), dir.join(lib)));
+        }
+
+        Ok(SysrootManifest { path: path.to_path_buf(), crates: crates })
+    }
+
+    /// The file the manifest gives for crate `name`, if it lists it.
+    pub fn get(&self, name: &str) -> Option<&Path> {
+        self.crates.iter().find(|&&(ref n, _)| n == name).map(|&(_, ref p)| &**p)
+    }
+}
+
 pub fn relative_target_lib_path(sysroot: &Path, target_triple: &str) -> PathBuf {
     let mut p = PathBuf::from(&find_libdir(sysroot));
     assert!(p.is_relative());
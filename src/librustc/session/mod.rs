@@ -9,31 +9,37 @@
 // except according to those terms.
 
 use lint;
+use middle::analysis_pass::AnalysisPasses;
 use middle::cstore::CrateStore;
 use middle::dependency_format;
+use middle::traits::ObligationObserverObject;
 use session::search_paths::PathKind;
-use util::nodemap::{NodeMap, FnvHashMap};
+use util::nodemap::{NodeMap, NodeSet, FnvHashMap};
 use mir::transform as mir_pass;
 
 use syntax::ast::{NodeId, NodeIdAssigner, Name};
 use syntax::codemap::{Span, MultiSpan};
 use syntax::errors::{self, DiagnosticBuilder};
-use syntax::errors::emitter::{Emitter, BasicEmitter, EmitterWriter};
+use syntax::errors::emitter::{Emitter, BasicEmitter, EmitterWriter, ShortEmitter};
 use syntax::errors::json::JsonEmitter;
 use syntax::diagnostics;
+use syntax::edition::Edition;
 use syntax::feature_gate;
 use syntax::parse;
 use syntax::parse::ParseSess;
-use syntax::{ast, codemap};
+use syntax::{self, ast, codemap};
 use syntax::feature_gate::AttributeType;
+use syntax::session_io::SessionIo;
 
 use rustc_back::target::Target;
+use rustc_data_structures::fnv;
 
 use std::path::{Path, PathBuf};
 use std::cell::{Cell, RefCell};
 use std::collections::{HashMap, HashSet};
 use std::env;
 use std::rc::Rc;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 pub mod config;
 pub mod filesearch;
@@ -52,6 +58,8 @@ pub struct Session {
     pub entry_type: Cell<Option<config::EntryFnType>>,
     pub plugin_registrar_fn: Cell<Option<ast::NodeId>>,
     pub default_sysroot: Option<PathBuf>,
+    /// The manifest given with `-Z sysroot-manifest`, if any.
+    pub sysroot_manifest: Option<filesearch::SysrootManifest>,
     // The name of the root source file of the crate, in the local file system.
     // The path is always expected to be absolute. `None` means that there is no
     // source file.
@@ -61,11 +69,17 @@ pub struct Session {
     pub lints: RefCell<NodeMap<Vec<(lint::LintId, Span, String)>>>,
     pub plugin_llvm_passes: RefCell<Vec<String>>,
     pub mir_passes: RefCell<mir_pass::Passes>,
+    /// Told about every trait obligation; see `traits::ObligationObserver`.
+    pub obligation_observers: RefCell<Vec<ObligationObserverObject>>,
+    // Custom passes run at the end of analysis, registered by the driver.
+    pub analysis_passes: RefCell<AnalysisPasses>,
     pub plugin_attributes: RefCell<Vec<(String, AttributeType)>>,
     pub crate_types: RefCell<Vec<config::CrateType>>,
     pub dependency_formats: RefCell<dependency_format::Dependencies>,
     pub crate_metadata: RefCell<Vec<String>>,
     pub features: RefCell<feature_gate::Features>,
+    /// The tests found while building the test harness, if any.
+    pub tests: RefCell<Vec<syntax::test::Test>>,
 
     /// The maximum recursion limit for potentially infinitely recursive
     /// operations such as auto-dereference and monomorphization.
@@ -79,6 +93,19 @@ pub struct Session {
     /// available in this crate
     pub available_macros: RefCell<HashSet<Name>>,
 
+    /// Names of the `extern crate` items whose macros were used
+    /// during expansion, for the unused extern crate lints.
+    pub used_macro_crates: RefCell<HashSet<Name>>,
+
+    /// What the code that was configured away by a `cfg` depending on
+    /// `test` or `doctest` refers to, such as `#[cfg(test)]` modules in
+    /// normal builds.
+    pub test_only_uses: RefCell<syntax::config::TestOnlyUses>,
+
+    /// The items that `test_only_uses` resolve to. They are not reported as
+    /// dead code, as they are used in the other configuration.
+    pub test_only_items: RefCell<NodeSet>,
+
     /// Map from imported macro spans (which consist of
     /// the localized span for the macro body) to the
     /// macro name and defintion span in the source crate.
@@ -273,7 +300,18 @@ impl Session {
         self.span_bug(sp, &format!("impossible case reached: {}", msg));
     }
     pub fn verbose(&self) -> bool { self.opts.debugging_opts.verbose }
-    pub fn time_passes(&self) -> bool { self.opts.debugging_opts.time_passes }
+    pub fn time_passes(&self) -> bool {
+        self.opts.debugging_opts.time_passes || self.opts.debugging_opts.time_passes_json
+    }
+    /// The edition of the local crate. Only final once the crate root has
+    /// been parsed, as it may be changed by `#![edition]`.
+    pub fn edition(&self) -> Edition { self.parse_sess.edition.get() }
+    /// Whether hash-ordered collections must be sorted before they reach
+    /// an output (metadata, the export list, dep-info), for reproducible
+    /// builds.
+    pub fn deterministic_output(&self) -> bool {
+        self.opts.debugging_opts.deterministic_output
+    }
     pub fn count_llvm_insns(&self) -> bool {
         self.opts.debugging_opts.count_llvm_insns
     }
@@ -283,6 +321,7 @@ impl Session {
     pub fn time_llvm_passes(&self) -> bool {
         self.opts.debugging_opts.time_llvm_passes
     }
+    pub fn time_items(&self) -> bool { self.opts.debugging_opts.time_items }
     pub fn trans_stats(&self) -> bool { self.opts.debugging_opts.trans_stats }
     pub fn meta_stats(&self) -> bool { self.opts.debugging_opts.meta_stats }
     pub fn asm_comments(&self) -> bool { self.opts.debugging_opts.asm_comments }
@@ -315,6 +354,7 @@ impl Session {
     }
     pub fn target_filesearch(&self, kind: PathKind) -> filesearch::FileSearch {
         filesearch::FileSearch::new(self.sysroot(),
+                                    &self.opts.extra_sysroots,
                                     &self.opts.target_triple,
                                     &self.opts.search_paths,
                                     kind)
@@ -322,6 +362,7 @@ impl Session {
     pub fn host_filesearch(&self, kind: PathKind) -> filesearch::FileSearch {
         filesearch::FileSearch::new(
             self.sysroot(),
+            &self.opts.extra_sysroots,
             config::host_triple(),
             &self.opts.search_paths,
             kind)
@@ -404,6 +445,22 @@ pub fn build_session(sopts: config::Options,
                      registry: diagnostics::registry::Registry,
                      cstore: Rc<for<'a> CrateStore<'a>>)
                      -> Session {
+    build_session_with_codemap(sopts,
+                               local_crate_source_file,
+                               registry,
+                               cstore,
+                               Rc::new(codemap::CodeMap::new()))
+}
+
+/// Like `build_session`, but uses the given codemap, which decides through
+/// its `FileLoader` how source files (including out-of-line modules) are
+/// read. Drivers that compile from in-memory sources supply their own.
+pub fn build_session_with_codemap(sopts: config::Options,
+                                  local_crate_source_file: Option<PathBuf>,
+                                  registry: diagnostics::registry::Registry,
+                                  cstore: Rc<for<'a> CrateStore<'a>>,
+                                  codemap: Rc<codemap::CodeMap>)
+                                  -> Session {
     // FIXME: This is not general enough to make the warning lint completely override
     // normal diagnostic warnings, since the warning lint can also be denied and changed
     // later via the source code.
@@ -415,7 +472,6 @@ pub fn build_session(sopts: config::Options,
         .unwrap_or(true);
     let treat_err_as_bug = sopts.treat_err_as_bug;
 
-    let codemap = Rc::new(codemap::CodeMap::new());
     let emitter: Box<Emitter> = match sopts.error_format {
         config::ErrorOutputType::HumanReadable(color_config) => {
             Box::new(EmitterWriter::stderr(color_config, Some(registry), codemap.clone()))
@@ -423,6 +479,9 @@ pub fn build_session(sopts: config::Options,
         config::ErrorOutputType::Json => {
             Box::new(JsonEmitter::stderr(Some(registry), codemap.clone()))
         }
+        config::ErrorOutputType::Short(color_config) => {
+            Box::new(ShortEmitter::stderr(color_config, codemap.clone()))
+        }
     };
 
     let diagnostic_handler =
@@ -447,10 +506,19 @@ pub fn build_session_(sopts: config::Options,
     };
     let target_cfg = config::build_target_config(&sopts, &span_diagnostic);
     let p_s = parse::ParseSess::with_span_handler(span_diagnostic, codemap);
+    if let Some(edition) = sopts.edition {
+        p_s.edition.set(edition);
+    }
     let default_sysroot = match sopts.maybe_sysroot {
         Some(_) => None,
         None => Some(filesearch::get_or_default_sysroot())
     };
+    let sysroot_manifest = sopts.debugging_opts.sysroot_manifest.as_ref().map(|path| {
+        match filesearch::SysrootManifest::load(Path::new(path)) {
+            Ok(manifest) => manifest,
+            Err(e) => early_error(sopts.error_format, &e),
+        }
+    });
 
     // Make the path absolute, if necessary
     let local_crate_source_file = local_crate_source_file.map(|path|
@@ -461,6 +529,19 @@ pub fn build_session_(sopts: config::Options,
         }
     );
 
+    if sopts.debugging_opts.sandbox_io {
+        // The crate's own source tree is part of its input.
+        let mut io = SessionIo::sandboxed();
+        let mut dirs: Vec<PathBuf> = local_crate_source_file.iter().filter_map(|file| {
+            file.parent().map(|dir| dir.to_path_buf())
+        }).collect();
+        dirs.extend(sopts.debugging_opts.sandbox_allow_path.iter().map(PathBuf::from));
+        io.readable_dirs = Some(dirs);
+        io.readable_env = Some(sopts.debugging_opts.sandbox_allow_env.clone());
+        io.allow_plugins = sopts.debugging_opts.sandbox_allow_plugins;
+        *p_s.io.borrow_mut() = io;
+    }
+
     let sess = Session {
         target: target_cfg,
         host: host,
@@ -472,30 +553,57 @@ pub fn build_session_(sopts: config::Options,
         entry_type: Cell::new(None),
         plugin_registrar_fn: Cell::new(None),
         default_sysroot: default_sysroot,
+        sysroot_manifest: sysroot_manifest,
         local_crate_source_file: local_crate_source_file,
         working_dir: env::current_dir().unwrap(),
         lint_store: RefCell::new(lint::LintStore::new()),
         lints: RefCell::new(NodeMap()),
         plugin_llvm_passes: RefCell::new(Vec::new()),
         mir_passes: RefCell::new(mir_pass::Passes::new()),
+        obligation_observers: RefCell::new(Vec::new()),
+        analysis_passes: RefCell::new(AnalysisPasses::new()),
         plugin_attributes: RefCell::new(Vec::new()),
         crate_types: RefCell::new(Vec::new()),
         dependency_formats: RefCell::new(FnvHashMap()),
         crate_metadata: RefCell::new(Vec::new()),
         features: RefCell::new(feature_gate::Features::new()),
+        tests: RefCell::new(Vec::new()),
         recursion_limit: Cell::new(64),
         next_node_id: Cell::new(1),
         injected_allocator: Cell::new(None),
         available_macros: RefCell::new(HashSet::new()),
+        used_macro_crates: RefCell::new(HashSet::new()),
+        test_only_uses: RefCell::new(syntax::config::TestOnlyUses::new()),
+        test_only_items: RefCell::new(NodeSet()),
         imported_macro_spans: RefCell::new(HashMap::new()),
     };
 
+    if sess.opts.debugging_opts.randomize_layout_of_hashmaps {
+        randomize_hashmaps(&sess);
+    }
+
     sess
 }
 
+fn randomize_hashmaps(sess: &Session) {
+    let seed = sess.opts.debugging_opts.hashmap_seed.unwrap_or_else(|| {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        ((now.as_secs() as usize) ^ (now.subsec_nanos() as usize)) | 1
+    });
+    if fnv::set_seed(seed) {
+        sess.note_without_error(&format!("hash maps randomized with seed {}; pass \
+                                          `-Z hashmap-seed={}` to reproduce",
+                                         seed, seed));
+    } else {
+        sess.warn("-Z randomize-layout-of-hashmaps ignored: the hash map seed has \
+                   already been set in this process");
+    }
+}
+
 pub fn early_error(output: config::ErrorOutputType, msg: &str) -> ! {
     let mut emitter: Box<Emitter> = match output {
-        config::ErrorOutputType::HumanReadable(color_config) => {
+        config::ErrorOutputType::HumanReadable(color_config) |
+        config::ErrorOutputType::Short(color_config) => {
             Box::new(BasicEmitter::stderr(color_config))
         }
         config::ErrorOutputType::Json => Box::new(JsonEmitter::basic()),
@@ -506,7 +614,8 @@ pub fn early_error(output: config::ErrorOutputType, msg: &str) -> ! {
 
 pub fn early_warn(output: config::ErrorOutputType, msg: &str) {
     let mut emitter: Box<Emitter> = match output {
-        config::ErrorOutputType::HumanReadable(color_config) => {
+        config::ErrorOutputType::HumanReadable(color_config) |
+        config::ErrorOutputType::Short(color_config) => {
             Box::new(BasicEmitter::stderr(color_config))
         }
         config::ErrorOutputType::Json => Box::new(JsonEmitter::basic()),
@@ -514,8 +623,9 @@ pub fn early_warn(output: config::ErrorOutputType, msg: &str) {
     emitter.emit(None, msg, None, errors::Level::Warning);
 }
 
-// Err(0) means compilation was stopped, but no errors were found.
-// This would be better as a dedicated enum, but using try! is so convenient.
+// The result of an individual pass, carrying the number of errors it
+// reported. Err(0) means compilation was stopped, but no errors were found.
+// The driver reports failures to its callers as a `driver::CompileError`.
 pub type CompileResult = Result<(), usize>;
 
 pub fn compile_result_from_err_count(err_count: usize) -> CompileResult {
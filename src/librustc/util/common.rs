@@ -11,13 +11,15 @@
 #![allow(non_camel_case_types)]
 
 use std::cell::{RefCell, Cell};
+use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::ffi::CString;
 use std::fmt::Debug;
 use std::hash::{Hash, BuildHasher};
 use std::iter::repeat;
+use std::mem;
 use std::path::Path;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use rustc_front::hir;
 use rustc_front::intravisit;
@@ -31,6 +33,42 @@ pub const FN_OUTPUT_NAME: &'static str = "Output";
 #[derive(Clone, Copy, Debug)]
 pub struct ErrorReported;
 
+/// The timing of a single pass, as recorded by `time` while
+/// `start_recording_pass_timings` is in effect on the current thread.
+#[derive(Clone, Debug, RustcEncodable)]
+pub struct PassTiming {
+    pub name: String,
+    /// How many enclosing passes were running when this one started.
+    pub depth: usize,
+    /// Wall-clock time spent in the pass, including nested passes.
+    pub secs: f64,
+    /// Resident set size once the pass finished, if it could be measured.
+    pub rss: Option<usize>,
+}
+
+struct PassTimings {
+    echo: bool,
+    timings: Vec<PassTiming>,
+}
+
+thread_local!(static PASS_TIMINGS: RefCell<Option<PassTimings>> = RefCell::new(None));
+
+/// Makes `time` record the passes run on this thread instead of printing
+/// them. If `echo` is set they are printed as well.
+pub fn start_recording_pass_timings(echo: bool) {
+    PASS_TIMINGS.with(|slot| {
+        *slot.borrow_mut() = Some(PassTimings { echo: echo, timings: vec![] });
+    });
+}
+
+/// Stops recording and returns the passes recorded so far on this thread,
+/// in the order in which they started.
+pub fn take_pass_timings() -> Vec<PassTiming> {
+    PASS_TIMINGS.with(|slot| {
+        slot.borrow_mut().take().map_or(vec![], |p| p.timings)
+    })
+}
+
 pub fn time<T, F>(do_it: bool, what: &str, f: F) -> T where
     F: FnOnce() -> T,
 {
@@ -43,31 +81,134 @@ pub fn time<T, F>(do_it: bool, what: &str, f: F) -> T where
         r
     });
 
+    // Reserve our slot up front so that nested passes end up after us.
+    let record = PASS_TIMINGS.with(|slot| {
+        slot.borrow_mut().as_mut().map(|p| {
+            p.timings.push(PassTiming {
+                name: what.to_string(),
+                depth: old,
+                secs: 0.0,
+                rss: None,
+            });
+            (p.timings.len() - 1, p.echo)
+        })
+    });
+
     let start = Instant::now();
     let rv = f();
     let dur = start.elapsed();
 
     // Hack up our own formatting for the duration to make it easier for scripts
     // to parse (always use the same number of decimal places and the same unit).
-    const NANOS_PER_SEC: f64 = 1_000_000_000.0;
-    let secs = dur.as_secs() as f64;
-    let secs = secs + dur.subsec_nanos() as f64 / NANOS_PER_SEC;
+    let secs = duration_to_secs(dur);
+
+    let rss = get_resident();
 
-    let mem_string = match get_resident() {
-        Some(n) => {
-            let mb = n as f64 / 1_000_000.0;
-            format!("; rss: {}MB", mb.round() as usize)
+    let echo = match record {
+        Some((idx, echo)) => {
+            PASS_TIMINGS.with(|slot| {
+                if let Some(ref mut p) = *slot.borrow_mut() {
+                    p.timings[idx].secs = secs;
+                    p.timings[idx].rss = rss;
+                }
+            });
+            echo
         }
-        None => "".to_owned(),
+        None => true,
     };
-    println!("{}time: {:.3}{}\t{}", repeat("  ").take(old).collect::<String>(),
-             secs, mem_string, what);
+
+    if echo {
+        let mem_string = match rss {
+            Some(n) => {
+                let mb = n as f64 / 1_000_000.0;
+                format!("; rss: {}MB", mb.round() as usize)
+            }
+            None => "".to_owned(),
+        };
+        println!("{}time: {:.3}{}\t{}", repeat("  ").take(old).collect::<String>(),
+                 secs, mem_string, what);
+    }
 
     DEPTH.with(|slot| slot.set(old));
 
     rv
 }
 
+/// The time attributed to one item by `time_item`, summed over every time
+/// the item was processed in that phase (e.g. once per instantiation of a
+/// generic function in trans).
+#[derive(Clone, Debug)]
+pub struct ItemTiming {
+    pub phase: &'static str,
+    pub path: String,
+    /// Time spent on the item itself, excluding nested items.
+    pub secs: f64,
+    /// How many times the item was processed.
+    pub count: usize,
+}
+
+#[derive(Default)]
+struct ItemTimings {
+    /// For each `time_item` call in progress, the time spent so far in
+    /// items nested inside it.
+    nested: Vec<f64>,
+    totals: HashMap<(&'static str, String), (f64, usize)>,
+}
+
+thread_local!(static ITEM_TIMINGS: RefCell<ItemTimings> = RefCell::new(Default::default()));
+
+/// Runs `f`, attributing the time it takes to the item named by `path` in
+/// `phase`. Time spent in nested `time_item` calls (e.g. a generic function
+/// instantiated while translating its caller) is attributed to the nested
+/// item only. `path` is only evaluated if `do_it` is set.
+pub fn time_item<T, P, F>(do_it: bool, phase: &'static str, path: P, f: F) -> T where
+    P: FnOnce() -> String,
+    F: FnOnce() -> T,
+{
+    if !do_it { return f(); }
+
+    ITEM_TIMINGS.with(|slot| slot.borrow_mut().nested.push(0.0));
+    let start = Instant::now();
+    let rv = f();
+    let secs = duration_to_secs(start.elapsed());
+    let path = path();
+
+    ITEM_TIMINGS.with(|slot| {
+        let mut timings = slot.borrow_mut();
+        let nested = timings.nested.pop().unwrap();
+        if let Some(parent) = timings.nested.last_mut() {
+            *parent += secs;
+        }
+        let total = timings.totals.entry((phase, path)).or_insert((0.0, 0));
+        total.0 += secs - nested;
+        total.1 += 1;
+    });
+
+    rv
+}
+
+/// Returns the timings recorded by `time_item` on this thread so far,
+/// slowest first, and forgets them.
+pub fn take_item_timings() -> Vec<ItemTiming> {
+    let totals = ITEM_TIMINGS.with(|slot| mem::replace(&mut slot.borrow_mut().totals,
+                                                       HashMap::new()));
+    let mut timings = totals.into_iter().map(|((phase, path), (secs, count))| {
+        ItemTiming { phase: phase, path: path, secs: secs, count: count }
+    }).collect::<Vec<_>>();
+    timings.sort_by(|a, b| {
+        match b.secs.partial_cmp(&a.secs) {
+            Some(Ordering::Equal) | None => a.path.cmp(&b.path),
+            Some(ord) => ord,
+        }
+    });
+    timings
+}
+
+fn duration_to_secs(dur: Duration) -> f64 {
+    const NANOS_PER_SEC: f64 = 1_000_000_000.0;
+    dur.as_secs() as f64 + dur.subsec_nanos() as f64 / NANOS_PER_SEC
+}
+
 // Like std::macros::try!, but for Option<>.
 macro_rules! option_try(
     ($e:expr) => (match $e { Some(e) => e, None => return None })
@@ -88,8 +229,34 @@ fn get_resident() -> Option<usize> {
     Some(npages * 4096)
 }
 
+// The high-water mark of the resident set size over the life of the process.
+#[cfg(unix)]
+fn get_peak_resident() -> Option<usize> {
+    use std::fs::File;
+    use std::io::Read;
+
+    let mut f = option_try!(File::open("/proc/self/status").ok());
+    let mut contents = String::new();
+    option_try!(f.read_to_string(&mut contents).ok());
+    let line = option_try!(contents.lines().find(|l| l.starts_with("VmHWM:")));
+    let s = option_try!(line.split_whitespace().nth(1));
+    let kb = option_try!(s.parse::<usize>().ok());
+    Some(kb * 1024)
+}
+
 #[cfg(windows)]
 fn get_resident() -> Option<usize> {
+    get_process_memory_counters().map(|(current, _)| current)
+}
+
+#[cfg(windows)]
+fn get_peak_resident() -> Option<usize> {
+    get_process_memory_counters().map(|(_, peak)| peak)
+}
+
+// Returns the current and peak working set sizes.
+#[cfg(windows)]
+fn get_process_memory_counters() -> Option<(usize, usize)> {
     type BOOL = i32;
     type DWORD = u32;
     type HANDLE = *mut u8;
@@ -120,8 +287,21 @@ fn get_resident() -> Option<usize> {
     pmc.cb = mem::size_of_val(&pmc) as DWORD;
     match unsafe { GetProcessMemoryInfo(GetCurrentProcess(), &mut pmc, pmc.cb) } {
         0 => None,
-        _ => Some(pmc.WorkingSetSize as usize),
+        _ => Some((pmc.WorkingSetSize as usize, pmc.PeakWorkingSetSize as usize)),
+    }
+}
+
+/// Prints the current and peak resident set sizes, labelled with the pass
+/// that just finished. Used by `-Z memory-stats`.
+pub fn print_memory_usage(what: &str) {
+    fn mb(bytes: Option<usize>) -> String {
+        match bytes {
+            Some(n) => format!("{}MB", (n as f64 / 1_000_000.0).round() as usize),
+            None => "?".to_owned(),
+        }
     }
+    println!("memory: rss: {}; peak: {}\t{}",
+             mb(get_resident()), mb(get_peak_resident()), what);
 }
 
 pub fn indent<R, F>(op: F) -> R where
@@ -100,6 +100,10 @@ fn parameterized<GG>(f: &mut fmt::Formatter,
             try!(start_or_continue(f, "<", ", "));
             try!(write!(f, "{}", ty));
         }
+        for c in &substs.consts {
+            try!(start_or_continue(f, "<", ", "));
+            try!(write!(f, "{}", c));
+        }
         for projection in projections {
             try!(start_or_continue(f, "<", ", "));
             try!(write!(f, "{}={}",
@@ -178,6 +182,11 @@ fn parameterized<GG>(f: &mut fmt::Formatter,
         try!(write!(f, "{}", ty));
     }
 
+    for c in substs.consts.get_slice(subst::TypeSpace) {
+        try!(start_or_continue(f, "<", ", "));
+        try!(write!(f, "{}", c));
+    }
+
     for projection in projections {
         try!(start_or_continue(f, "<", ", "));
         try!(write!(f, "{}={}",
@@ -314,6 +323,16 @@ impl<'tcx> fmt::Debug for ty::TypeParameterDef<'tcx> {
     }
 }
 
+impl<'tcx> fmt::Debug for ty::ConstParameterDef<'tcx> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ConstParameterDef({}, {:?}, {:?}/{}, {:?})",
+               self.name,
+               self.def_id,
+               self.space, self.index,
+               self.ty)
+    }
+}
+
 impl fmt::Debug for ty::RegionParameterDef {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "RegionParameterDef({}, {:?}, {:?}/{}, {:?})",
@@ -340,8 +359,8 @@ impl<'tcx> fmt::Display for ty::TypeAndMut<'tcx> {
 
 impl<'tcx> fmt::Debug for subst::Substs<'tcx> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "Substs[types={:?}, regions={:?}]",
-               self.types, self.regions)
+        write!(f, "Substs[types={:?}, regions={:?}, consts={:?}]",
+               self.types, self.regions, self.consts)
     }
 }
 
@@ -529,7 +548,7 @@ impl<'a, 'tcx> fmt::Debug for ty::ParameterEnvironment<'a, 'tcx> {
     }
 }
 
-impl<'tcx> fmt::Debug for ty::ObjectLifetimeDefault {
+impl fmt::Debug for ty::ObjectLifetimeDefault {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             ty::ObjectLifetimeDefault::Ambiguous => write!(f, "Ambiguous"),
@@ -863,6 +882,35 @@ impl<'tcx> fmt::Display for ty::TypeVariants<'tcx> {
             }
             TyTrait(ref data) => write!(f, "{}", data),
             ty::TyProjection(ref data) => write!(f, "{}", data),
+            ty::TyAnon(def_id, substs) => ty::tls::with(|tcx| {
+                // Grab the "TraitA + TraitB" from `impl TraitA + TraitB`,
+                // by looking up the bounds recorded for the anonymized type.
+                let predicates = match tcx.predicates.borrow().get(&def_id) {
+                    Some(predicates) => predicates.clone(),
+                    None => return write!(f, "impl .."),
+                };
+                let substs = tcx.lift(&substs).expect("could not lift Substs for printing");
+                let bounds = predicates.instantiate(tcx, substs);
+
+                try!(write!(f, "impl"));
+                let mut sep = " ";
+                let mut is_sized = false;
+                for predicate in bounds.predicates.into_vec() {
+                    if let Some(trait_ref) = predicate.to_opt_poly_trait_ref() {
+                        // Don't print `+Sized`, but rather `+?Sized` if absent.
+                        if Some(trait_ref.def_id()) == tcx.lang_items.sized_trait() {
+                            is_sized = true;
+                            continue;
+                        }
+                        try!(write!(f, "{}{}", sep, trait_ref));
+                        sep = "+";
+                    }
+                }
+                if !is_sized {
+                    try!(write!(f, "{}?Sized", sep));
+                }
+                Ok(())
+            }),
             TyStr => write!(f, "str"),
             TyClosure(did, ref substs) => ty::tls::with(|tcx| {
                 try!(write!(f, "[closure"));
@@ -896,6 +944,7 @@ impl<'tcx> fmt::Display for ty::TypeVariants<'tcx> {
                 write!(f, "]")
             }),
             TyArray(ty, sz) => write!(f, "[{}; {}]",  ty, sz),
+            ty::TyParamArray(ty, ref p) => write!(f, "[{}; {}]", ty, p),
             TySlice(ty) => write!(f, "[{}]",  ty)
         }
     }
@@ -964,7 +1013,28 @@ impl fmt::Debug for ty::ParamTy {
     }
 }
 
-impl<'tcx, T, U> fmt::Display for ty::OutlivesPredicate<T,U>
+impl fmt::Display for ty::ParamConst {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+impl fmt::Debug for ty::ParamConst {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{:?}.{}", self, self.space, self.idx)
+    }
+}
+
+impl fmt::Display for subst::ConstArg {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            subst::ConstArg::Value(bits) => write!(f, "{}", bits),
+            subst::ConstArg::Param(ref p) => write!(f, "{}", p),
+        }
+    }
+}
+
+impl<T, U> fmt::Display for ty::OutlivesPredicate<T,U>
     where T: fmt::Display, U: fmt::Display
 {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
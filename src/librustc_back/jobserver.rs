@@ -0,0 +1,183 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A client for the GNU make jobserver.
+//!
+//! A `make -jN` shares its job slots with the commands it runs through a
+//! pipe holding N - 1 tokens (one byte each), whose ends it names in
+//! `MAKEFLAGS` as `--jobserver-auth=R,W` (or `--jobserver-fds=R,W` before
+//! make 4.2). Every process implicitly owns one slot; it must read a token
+//! from the pipe before doing more than one job's worth of work at a time,
+//! and write it back once done.
+//!
+//! Only the pipe-based protocol is supported, so there is no jobserver on
+//! Windows.
+
+use std::env;
+use std::io;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// A connection to the jobserver of the build that started this process.
+pub struct Client {
+    imp: imp::Client,
+}
+
+/// A token taken from the jobserver. It goes back to the pool when dropped.
+pub struct Acquired {
+    client: Arc<Client>,
+    byte: u8,
+}
+
+impl Client {
+    /// Connects to the jobserver named in `MAKEFLAGS` (or `MFLAGS`), if
+    /// there is one. Make only passes the pipe on to commands it knows to be
+    /// sub-makes (those marked with `+`), so a jobserver whose descriptors
+    /// aren't open is ignored.
+    pub fn from_env() -> Option<Client> {
+        let flags = match env::var("MAKEFLAGS").or_else(|_| env::var("MFLAGS")) {
+            Ok(flags) => flags,
+            Err(_) => return None,
+        };
+        let auth = flags.split_whitespace().filter_map(|flag| {
+            if flag.starts_with("--jobserver-auth=") {
+                Some(&flag["--jobserver-auth=".len()..])
+            } else if flag.starts_with("--jobserver-fds=") {
+                Some(&flag["--jobserver-fds=".len()..])
+            } else {
+                None
+            }
+        }).last();
+        auth.and_then(imp::Client::open).map(|imp| Client { imp: imp })
+    }
+
+    /// Blocks until a token is available and takes it, or until `cancel`
+    /// is set, in which case `None` is returned.
+    pub fn acquire(client: &Arc<Client>, cancel: &AtomicBool)
+                   -> io::Result<Option<Acquired>> {
+        loop {
+            if cancel.load(Ordering::SeqCst) {
+                return Ok(None);
+            }
+            if let Some(byte) = try!(client.imp.try_acquire()) {
+                return Ok(Some(Acquired { client: client.clone(), byte: byte }));
+            }
+        }
+    }
+}
+
+impl Drop for Acquired {
+    fn drop(&mut self) {
+        // If the token can't be returned there is nothing sensible left to
+        // do with it; make will complain about the lost slot at exit.
+        let _ = self.client.imp.release(self.byte);
+    }
+}
+
+#[cfg(unix)]
+mod imp {
+    use libc;
+    use std::io;
+
+    pub struct Client {
+        read: libc::c_int,
+        write: libc::c_int,
+    }
+
+    /// How long `try_acquire` waits for a token before giving the caller a
+    /// chance to cancel.
+    const POLL_TIMEOUT_MS: libc::c_int = 50;
+
+    impl Client {
+        pub fn open(auth: &str) -> Option<Client> {
+            let mut fds = auth.splitn(2, ',');
+            let read = fds.next().and_then(|fd| fd.parse().ok());
+            let write = fds.next().and_then(|fd| fd.parse().ok());
+            match (read, write) {
+                (Some(read), Some(write)) if is_open(read) && is_open(write) => {
+                    Some(Client { read: read, write: write })
+                }
+                _ => None,
+            }
+        }
+
+        /// Waits a little while for a token, returning `None` if none came.
+        ///
+        /// The pipe is shared with the rest of the build and must stay in
+        /// blocking mode, so another process may take the token between the
+        /// `poll` and the `read`. The `read` then blocks until someone gives
+        /// a token back, which the running jobs always eventually do.
+        pub fn try_acquire(&self) -> io::Result<Option<u8>> {
+            let mut fd = libc::pollfd { fd: self.read, events: libc::POLLIN, revents: 0 };
+            let ready = unsafe { libc::poll(&mut fd, 1, POLL_TIMEOUT_MS) };
+            if ready < 0 {
+                let err = io::Error::last_os_error();
+                return if err.kind() == io::ErrorKind::Interrupted { Ok(None) } else { Err(err) };
+            }
+            if ready == 0 {
+                return Ok(None);
+            }
+
+            let mut byte = 0u8;
+            let n = unsafe {
+                libc::read(self.read, &mut byte as *mut u8 as *mut libc::c_void, 1)
+            };
+            match n {
+                1 => Ok(Some(byte)),
+                0 => Err(io::Error::new(io::ErrorKind::UnexpectedEof,
+                                        "the jobserver pipe was closed")),
+                _ => {
+                    let err = io::Error::last_os_error();
+                    if err.kind() == io::ErrorKind::Interrupted { Ok(None) } else { Err(err) }
+                }
+            }
+        }
+
+        pub fn release(&self, byte: u8) -> io::Result<()> {
+            loop {
+                let n = unsafe {
+                    libc::write(self.write, &byte as *const u8 as *const libc::c_void, 1)
+                };
+                if n == 1 {
+                    return Ok(());
+                }
+                let err = io::Error::last_os_error();
+                if err.kind() != io::ErrorKind::Interrupted {
+                    return Err(err);
+                }
+            }
+        }
+    }
+
+    fn is_open(fd: libc::c_int) -> bool {
+        unsafe { libc::fcntl(fd, libc::F_GETFD) != -1 }
+    }
+}
+
+#[cfg(not(unix))]
+mod imp {
+    use std::io;
+
+    pub struct Client;
+
+    impl Client {
+        pub fn open(_auth: &str) -> Option<Client> {
+            None
+        }
+
+        pub fn try_acquire(&self) -> io::Result<Option<u8>> {
+            Ok(None)
+        }
+
+        pub fn release(&self, _byte: u8) -> io::Result<()> {
+            Ok(())
+        }
+    }
+}
@@ -44,7 +44,6 @@
 extern crate syntax;
 extern crate libc;
 extern crate serialize;
-extern crate rustc_llvm;
 extern crate rustc_front;
 #[macro_use] extern crate log;
 
@@ -54,5 +53,7 @@ pub mod rpath;
 pub mod sha2;
 pub mod svh;
 pub mod target;
+mod target_json;
 pub mod slice;
 pub mod dynamic_lib;
+pub mod jobserver;
@@ -90,6 +90,12 @@ impl<'a, 'tcx> RestrictionsContext<'a, 'tcx> {
             }
 
             Categorization::Interior(cmt_base, i) => {
+                if is_union(cmt_base.ty) {
+                    // The fields of a union overlap, so borrowing one of
+                    // them restricts the whole union.
+                    return self.restrict(cmt_base);
+                }
+
                 // R-Field
                 //
                 // Overwriting the base would not change the type of
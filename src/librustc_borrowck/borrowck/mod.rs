@@ -489,6 +489,16 @@ impl<'tcx> LoanPath<'tcx> {
     }
 }
 
+/// The fields of a union all share its storage, so a loan path never
+/// extends into a union: borrowing, moving or assigning any field is
+/// borrowing, moving or assigning the whole union.
+pub fn is_union(ty: ty::Ty) -> bool {
+    match ty.sty {
+        ty::TyStruct(def, _) => def.is_union(),
+        _ => false,
+    }
+}
+
 pub fn opt_loan_path<'tcx>(cmt: &mc::cmt<'tcx>) -> Option<Rc<LoanPath<'tcx>>> {
     //! Computes the `LoanPath` (if any) for a `cmt`.
     //! Note that this logic is somewhat duplicated in
@@ -518,6 +528,10 @@ pub fn opt_loan_path<'tcx>(cmt: &mc::cmt<'tcx>) -> Option<Rc<LoanPath<'tcx>>> {
             })
         }
 
+        Categorization::Interior(ref cmt_base, _) if is_union(cmt_base.ty) => {
+            opt_loan_path(cmt_base)
+        }
+
         Categorization::Interior(ref cmt_base, ik) => {
             opt_loan_path(cmt_base).map(|lp| {
                 let opt_variant_id = match cmt_base.cat {
@@ -1183,7 +1197,7 @@ impl DataFlowOperator for LoanDataFlowOperator {
     }
 }
 
-impl<'tcx> fmt::Debug for InteriorKind {
+impl fmt::Debug for InteriorKind {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             InteriorField(mc::NamedField(fld)) => write!(f, "{}", fld),
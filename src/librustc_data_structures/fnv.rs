@@ -11,6 +11,7 @@
 use std::collections::{HashMap, HashSet};
 use std::default::Default;
 use std::hash::{Hasher, Hash, BuildHasherDefault};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 pub type FnvHashMap<K, V> = HashMap<K, V, BuildHasherDefault<FnvHasher>>;
 pub type FnvHashSet<V> = HashSet<V, BuildHasherDefault<FnvHasher>>;
@@ -37,10 +38,23 @@ pub struct FnvHasher(u64);
 impl Default for FnvHasher {
     #[inline]
     fn default() -> FnvHasher {
-        FnvHasher(0xcbf29ce484222325)
+        // The seed is 0 unless `set_seed` was called, leaving the standard
+        // FNV offset basis; a relaxed load is cheap next to the hashing.
+        FnvHasher(0xcbf29ce484222325 ^ (SEED.load(Ordering::Relaxed) as u64))
     }
 }
 
+static SEED: AtomicUsize = AtomicUsize::new(0);
+
+/// Perturbs the hash of every `FnvHasher` with `seed`, so that the iteration
+/// order of `FnvHashMap`s and `FnvHashSet`s changes with it; used to flush
+/// out code that depends on that order. Returns false, and does nothing, if
+/// a seed has already been set, since changing it would break any map
+/// already holding entries.
+pub fn set_seed(seed: usize) -> bool {
+    SEED.compare_and_swap(0, seed, Ordering::SeqCst) == 0
+}
+
 impl Hasher for FnvHasher {
     #[inline]
     fn write(&mut self, bytes: &[u8]) {
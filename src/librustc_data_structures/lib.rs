@@ -34,7 +34,6 @@
 extern crate core;
 #[macro_use]
 extern crate log;
-extern crate serialize as rustc_serialize; // used by deriving
 
 pub mod bitvec;
 pub mod graph;
@@ -253,7 +253,7 @@ impl<K: UnifyKey> sv::SnapshotVecDelegate for Delegate<K> {
 
 // # Base union-find algorithm, where we are just making sets
 
-impl<'tcx, K: UnifyKey> UnificationTable<K>
+impl<K: UnifyKey> UnificationTable<K>
     where K::Value: Combine
 {
     pub fn union(&mut self, a_id: K, b_id: K) {
@@ -286,7 +286,7 @@ impl<'tcx, K: UnifyKey> UnificationTable<K>
 // floats---anything that doesn't have a subtyping relationship we
 // need to worry about.
 
-impl<'tcx, K, V> UnificationTable<K>
+impl<K, V> UnificationTable<K>
     where K: UnifyKey<Value = Option<V>>,
           V: Clone + PartialEq + Debug
 {
@@ -0,0 +1,120 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! `--emit=access-levels`: a JSON dump of every item privacy found to be
+//! visible outside the crate, with its access level and the reason it was
+//! given that level.
+//!
+//! The output looks like
+//!
+//! ```text
+//! {
+//!   "items": [
+//!     { "id": "0:4", "name": "inner::f", "level": "exported",
+//!       "span": "foo.rs:2:5: 2:16",
+//!       "reason": { "kind": "parent", "via": "0:3" } },
+//!     { "id": "0:3", "name": "inner", "level": "exported",
+//!       "span": "foo.rs:1:1: 3:2",
+//!       "reason": { "kind": "reexport", "via": "0:0" } }
+//!   ]
+//! }
+//! ```
+//!
+//! `via` names the module holding the reexport, the enclosing item, or the
+//! item whose interface mentions this one; its own entry gives the next
+//! step of the chain. Ids are `crate:index` pairs (or `node:N` for nodes
+//! without a definition) and are only meaningful within one dump.
+
+use rustc::middle::privacy::{AccessLevel, AccessLevels, AccessReason};
+use rustc::middle::ty::TyCtxt;
+use rustc::session::config::{OutputFilenames, OutputType};
+use serialize::json::{self, Json};
+use syntax::ast;
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+
+pub fn write_access_levels(tcx: &TyCtxt,
+                           access_levels: &AccessLevels,
+                           outputs: &OutputFilenames) {
+    let path = outputs.path(OutputType::AccessLevels);
+    let dump = access_levels_json(tcx, access_levels);
+    let result = fs::File::create(&path).and_then(|mut file| {
+        write!(file, "{}", json::as_pretty_json(&dump))
+    });
+    if let Err(e) = result {
+        tcx.sess.fatal(&format!("error writing access levels to `{}`: {}",
+                                path.display(), e));
+    }
+}
+
+fn access_levels_json(tcx: &TyCtxt, access_levels: &AccessLevels) -> Json {
+    // The level map is a hash map; sort so that the output doesn't depend
+    // on its ordering.
+    let mut ids: Vec<_> = access_levels.map.keys().cloned().collect();
+    ids.sort();
+
+    let items = ids.into_iter().map(|id| {
+        let level = match access_levels.map[&id] {
+            AccessLevel::Public => "public",
+            AccessLevel::Exported => "exported",
+            AccessLevel::Reachable => "reachable",
+        };
+
+        let mut node = BTreeMap::new();
+        node.insert("id".to_string(), Json::String(node_id_str(tcx, id)));
+        node.insert("name".to_string(), Json::String(node_name(tcx, id)));
+        node.insert("level".to_string(), Json::String(level.to_string()));
+        if let Some(span) = tcx.map.opt_span(id) {
+            let span = tcx.sess.codemap().span_to_string(span);
+            node.insert("span".to_string(), Json::String(span));
+        }
+        if let Some(reason) = access_levels.reason(id) {
+            node.insert("reason".to_string(), reason_json(tcx, reason));
+        }
+        Json::Object(node)
+    }).collect();
+
+    let mut dump = BTreeMap::new();
+    dump.insert("items".to_string(), Json::Array(items));
+    Json::Object(dump)
+}
+
+fn reason_json(tcx: &TyCtxt, reason: AccessReason) -> Json {
+    let (kind, via) = match reason {
+        AccessReason::PublicPath => ("public-path", None),
+        AccessReason::Macro => ("macro", None),
+        AccessReason::Reexport(id) => ("reexport", Some(id)),
+        AccessReason::Parent(id) => ("parent", Some(id)),
+        AccessReason::Interface(id) => ("interface", Some(id)),
+    };
+
+    let mut node = BTreeMap::new();
+    node.insert("kind".to_string(), Json::String(kind.to_string()));
+    if let Some(id) = via {
+        node.insert("via".to_string(), Json::String(node_id_str(tcx, id)));
+    }
+    Json::Object(node)
+}
+
+fn node_id_str(tcx: &TyCtxt, id: ast::NodeId) -> String {
+    match tcx.map.opt_local_def_id(id) {
+        Some(def_id) => format!("{}:{}", def_id.krate, def_id.index.as_usize()),
+        None => format!("node:{}", id),
+    }
+}
+
+fn node_name(tcx: &TyCtxt, id: ast::NodeId) -> String {
+    match tcx.map.opt_local_def_id(id) {
+        Some(def_id) => tcx.def_path_str(def_id),
+        None => tcx.map.node_to_user_string(id),
+    }
+}
@@ -0,0 +1,122 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Response files: an argument of the form `@path` is replaced by the
+//! arguments listed in the file at `path`, so that build systems can pass
+//! more `-L` and `--extern` flags than the command line length limit on
+//! Windows allows.
+//!
+//! A response file holds one argument per line. Blank lines are skipped and
+//! a trailing `\r` is dropped. A line wrapped in double quotes is taken
+//! literally, apart from `\"` and `\\`, which stand for `"` and `\`; this is
+//! how an argument that is empty or has leading or trailing whitespace is
+//! written. Response files can't refer to other response files.
+
+use std::fs::File;
+use std::io::Read;
+
+/// Expands every `@path` argument in `args` in place. The first argument is
+/// the name of the program and is left alone.
+pub fn expand_response_files(args: Vec<String>) -> Result<Vec<String>, String> {
+    let mut expanded = Vec::with_capacity(args.len());
+    for (i, arg) in args.into_iter().enumerate() {
+        if i == 0 || !arg.starts_with("@") {
+            expanded.push(arg);
+            continue;
+        }
+
+        let path = &arg[1..];
+        let mut contents = String::new();
+        try!(File::open(path).and_then(|mut f| f.read_to_string(&mut contents)).map_err(|e| {
+            format!("failed to read response file `{}`: {}", path, e)
+        }));
+        let args = try!(parse_response_file(&contents).map_err(|(line, msg)| {
+            format!("{}:{}: {}", path, line, msg)
+        }));
+        expanded.extend(args);
+    }
+    Ok(expanded)
+}
+
+/// Splits the contents of a response file into arguments. Errors carry the
+/// (1-based) line they're on.
+fn parse_response_file(contents: &str) -> Result<Vec<String>, (usize, String)> {
+    let mut args = vec![];
+    for (i, line) in contents.lines().enumerate() {
+        let line = line.trim_right_matches('\r');
+        if line.trim().is_empty() {
+            continue;
+        }
+        if !line.starts_with("\"") {
+            args.push(line.to_string());
+            continue;
+        }
+
+        let mut arg = String::new();
+        let mut chars = line[1..].chars();
+        let mut closed = false;
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => {
+                    match chars.next() {
+                        Some(c @ '"') | Some(c @ '\\') => arg.push(c),
+                        Some(c) => {
+                            return Err((i + 1, format!("unknown escape `\\{}` in quoted \
+                                                        argument", c)));
+                        }
+                        None => return Err((i + 1, "unterminated quoted argument".to_string())),
+                    }
+                }
+                '"' => {
+                    closed = true;
+                    break;
+                }
+                c => arg.push(c),
+            }
+        }
+        if !closed {
+            return Err((i + 1, "unterminated quoted argument".to_string()));
+        }
+        if !chars.as_str().trim().is_empty() {
+            return Err((i + 1, "unexpected text after quoted argument".to_string()));
+        }
+        args.push(arg);
+    }
+    Ok(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_response_file;
+
+    #[test]
+    fn one_arg_per_line() {
+        let args = parse_response_file("-L\r\nfoo bar\n\n--extern\nstd=/x/libstd.rlib\n");
+        assert_eq!(args, Ok(vec!["-L".to_string(),
+                                 "foo bar".to_string(),
+                                 "--extern".to_string(),
+                                 "std=/x/libstd.rlib".to_string()]));
+    }
+
+    #[test]
+    fn quoted_args() {
+        let args = parse_response_file("\"\"\n\" padded \"\n\"a \\\"b\\\" \\\\c\"\n");
+        assert_eq!(args, Ok(vec!["".to_string(),
+                                 " padded ".to_string(),
+                                 "a \"b\" \\c".to_string()]));
+    }
+
+    #[test]
+    fn bad_quoting() {
+        assert!(parse_response_file("ok\n\"open").unwrap_err().0 == 2);
+        assert!(parse_response_file("\"a\" b").is_err());
+        assert!(parse_response_file("\"\\n\"").is_err());
+    }
+}
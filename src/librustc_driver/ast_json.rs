@@ -0,0 +1,324 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! `--emit=ast-json`: the items of the expanded crate as JSON, in a format
+//! that tools can rely on across compiler versions. Unlike the dump from
+//! `--unpretty ast-json`, which serializes the compiler's data structures as
+//! they happen to be, this format only changes along with `version`.
+//!
+//! Version 1 looks like
+//!
+//! ```text
+//! {
+//!   "version": 1,
+//!   "crate": { "name": "foo", "attrs": [Attribute], "items": [Item] }
+//! }
+//! ```
+//!
+//! where
+//!
+//! * a `Span` is `null` for code the compiler made up, or
+//!   `{ "file_name", "line_start", "line_end", "column_start", "column_end" }`
+//!   as in JSON diagnostics: lines and columns are 1-based, columns count
+//!   characters, and `column_end` is one past the last character;
+//! * an `Attribute` is `{ "style": "outer" | "inner", "doc_comment": bool,
+//!   "meta": Meta, "span": Span }`, a doc comment being the attribute
+//!   `doc = "/// ..."`;
+//! * a `Meta` has a `name` and a `kind`, which is `"word"`, `"name_value"`
+//!   (with a `value`: the contents of a string literal, or the source text
+//!   of any other literal) or `"list"` (with a list of `items`);
+//! * every `Item` has a `kind`, a `name` (`null` for impls and `extern`
+//!   blocks), a `vis` (`"pub"` or `"inherited"`), `attrs` and a `span`, and
+//!   depending on its kind:
+//!   - `"extern_crate"`: `crate`, the name of the crate linked to;
+//!   - `"use"`: `path`, as written;
+//!   - `"static"`: `ty` and `mutable`;
+//!   - `"const"` and `"type"`: `ty`;
+//!   - `"fn"`: nothing more;
+//!   - `"mod"`: `items`;
+//!   - `"foreign_mod"`: `abi`, and `items` of kind `"fn"` or `"static"`;
+//!   - `"struct"`: `fields`;
+//!   - `"enum"`: `variants`, each with a `name`, `attrs`, `span` and
+//!     `fields`;
+//!   - `"trait"`: `items` of kind `"const"`, `"method"` or `"type"`;
+//!   - `"impl"`: `trait` (`null` for inherent impls), `self_ty` and `items`
+//!     of kind `"const"`, `"method"`, `"type"` or `"macro"`;
+//!   - `"default_impl"`: `trait`;
+//!   - `"macro"`: `path`, the macro invoked;
+//! * a field has a `name` (`null` in tuple structs), `vis`, `ty`, `attrs`
+//!   and `span`.
+//!
+//! Types and paths are given as source text. Node ids, expressions and
+//! function signatures aren't part of the format.
+
+use rustc::session::Session;
+use rustc::session::config::{OutputFilenames, OutputType};
+use serialize::json::{self, Json};
+use syntax::ast;
+use syntax::codemap::{CodeMap, Span, DUMMY_SP};
+use syntax::print::pprust;
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+
+/// The version of the format written by `write_ast_json`. It goes up with
+/// every change to the format that could break a reader.
+pub const VERSION: u64 = 1;
+
+pub fn write_ast_json(sess: &Session,
+                      krate: &ast::Crate,
+                      crate_name: &str,
+                      outputs: &OutputFilenames) {
+    let path = outputs.path(OutputType::AstJson);
+    let encoder = Encoder { codemap: sess.codemap() };
+    let json = object(vec![
+        ("version", Json::U64(VERSION)),
+        ("crate", object(vec![
+            ("name", Json::String(crate_name.to_string())),
+            ("attrs", encoder.attrs(&krate.attrs)),
+            ("items", Json::Array(krate.module.items.iter().map(|i| encoder.item(i)).collect())),
+        ])),
+    ]);
+    let result = fs::File::create(&path).and_then(|mut file| {
+        writeln!(file, "{}", json::as_pretty_json(&json))
+    });
+    if let Err(e) = result {
+        sess.fatal(&format!("error writing AST to `{}`: {}", path.display(), e));
+    }
+}
+
+/// A `Span` in the format described above.
+pub fn span(codemap: &CodeMap, span: Span) -> Json {
+    if span == DUMMY_SP {
+        return Json::Null;
+    }
+    let start = codemap.lookup_char_pos(span.lo);
+    let end = codemap.lookup_char_pos(span.hi);
+    object(vec![
+        ("file_name", string(&start.file.name)),
+        ("line_start", Json::U64(start.line as u64)),
+        ("line_end", Json::U64(end.line as u64)),
+        ("column_start", Json::U64(start.col.0 as u64 + 1)),
+        ("column_end", Json::U64(end.col.0 as u64 + 1)),
+    ])
+}
+
+pub fn object(fields: Vec<(&str, Json)>) -> Json {
+    Json::Object(fields.into_iter().map(|(k, v)| (k.to_string(), v)).collect::<BTreeMap<_, _>>())
+}
+
+pub fn string<S: ToString>(s: S) -> Json {
+    Json::String(s.to_string())
+}
+
+fn name(ident: ast::Ident) -> Json {
+    let name = ident.name.as_str();
+    if name.is_empty() { Json::Null } else { string(name) }
+}
+
+fn vis(vis: ast::Visibility) -> Json {
+    string(match vis {
+        ast::Visibility::Public => "pub",
+        ast::Visibility::Inherited => "inherited",
+    })
+}
+
+struct Encoder<'a> {
+    codemap: &'a CodeMap,
+}
+
+impl<'a> Encoder<'a> {
+    fn span(&self, sp: Span) -> Json {
+        span(self.codemap, sp)
+    }
+
+    fn attrs(&self, attrs: &[ast::Attribute]) -> Json {
+        Json::Array(attrs.iter().map(|attr| {
+            object(vec![
+                ("style", string(match attr.node.style {
+                    ast::AttrStyle::Outer => "outer",
+                    ast::AttrStyle::Inner => "inner",
+                })),
+                ("doc_comment", Json::Boolean(attr.node.is_sugared_doc)),
+                ("meta", meta(&attr.node.value)),
+                ("span", self.span(attr.span)),
+            ])
+        }).collect())
+    }
+
+    fn item(&self, item: &ast::Item) -> Json {
+        let mut fields = vec![
+            ("name", name(item.ident)),
+            ("vis", vis(item.vis)),
+            ("attrs", self.attrs(&item.attrs)),
+            ("span", self.span(item.span)),
+        ];
+        let kind = match item.node {
+            ast::ItemKind::ExternCrate(orig_name) => {
+                fields.push(("crate", string(orig_name.unwrap_or(item.ident.name))));
+                "extern_crate"
+            }
+            ast::ItemKind::Use(ref view_path) => {
+                fields.push(("path", string(pprust::to_string(|s| {
+                    s.print_view_path(view_path)
+                }))));
+                "use"
+            }
+            ast::ItemKind::Static(ref ty, mutbl, _) => {
+                fields.push(("ty", string(pprust::ty_to_string(ty))));
+                fields.push(("mutable", Json::Boolean(mutbl == ast::Mutability::Mutable)));
+                "static"
+            }
+            ast::ItemKind::Const(ref ty, _) => {
+                fields.push(("ty", string(pprust::ty_to_string(ty))));
+                "const"
+            }
+            ast::ItemKind::Fn(..) => "fn",
+            ast::ItemKind::Mod(ref module) => {
+                fields.push(("items",
+                             Json::Array(module.items.iter().map(|i| self.item(i)).collect())));
+                "mod"
+            }
+            ast::ItemKind::ForeignMod(ref foreign_mod) => {
+                fields.push(("abi", string(foreign_mod.abi)));
+                fields.push(("items", Json::Array(foreign_mod.items.iter().map(|item| {
+                    object(vec![
+                        ("kind", string(match item.node {
+                            ast::ForeignItemKind::Fn(..) => "fn",
+                            ast::ForeignItemKind::Static(..) => "static",
+                        })),
+                        ("name", name(item.ident)),
+                        ("vis", vis(item.vis)),
+                        ("attrs", self.attrs(&item.attrs)),
+                        ("span", self.span(item.span)),
+                    ])
+                }).collect())));
+                "foreign_mod"
+            }
+            ast::ItemKind::Ty(ref ty, _) => {
+                fields.push(("ty", string(pprust::ty_to_string(ty))));
+                "type"
+            }
+            ast::ItemKind::Enum(ref enum_def, _) => {
+                fields.push(("variants", Json::Array(enum_def.variants.iter().map(|v| {
+                    object(vec![
+                        ("name", name(v.node.name)),
+                        ("attrs", self.attrs(&v.node.attrs)),
+                        ("span", self.span(v.span)),
+                        ("fields", self.fields(&v.node.data)),
+                    ])
+                }).collect())));
+                "enum"
+            }
+            ast::ItemKind::Struct(ref data, _) => {
+                fields.push(("fields", self.fields(data)));
+                "struct"
+            }
+            ast::ItemKind::Union(ref data, _) => {
+                fields.push(("fields", self.fields(data)));
+                "union"
+            }
+            ast::ItemKind::Trait(_, _, _, ref items) => {
+                fields.push(("items", Json::Array(items.iter().map(|item| {
+                    object(vec![
+                        ("kind", string(match item.node {
+                            ast::TraitItemKind::Const(..) => "const",
+                            ast::TraitItemKind::Method(..) => "method",
+                            ast::TraitItemKind::Type(..) => "type",
+                        })),
+                        ("name", name(item.ident)),
+                        ("attrs", self.attrs(&item.attrs)),
+                        ("span", self.span(item.span)),
+                    ])
+                }).collect())));
+                "trait"
+            }
+            ast::ItemKind::TraitAlias(_, ref bounds) => {
+                fields.push(("bounds", string(pprust::bounds_to_string(bounds))));
+                "trait_alias"
+            }
+            ast::ItemKind::DefaultImpl(_, ref trait_ref) => {
+                fields.push(("trait", string(pprust::path_to_string(&trait_ref.path))));
+                "default_impl"
+            }
+            ast::ItemKind::Impl(_, _, _, ref trait_ref, ref self_ty, ref items) => {
+                fields.push(("trait", match *trait_ref {
+                    Some(ref trait_ref) => string(pprust::path_to_string(&trait_ref.path)),
+                    None => Json::Null,
+                }));
+                fields.push(("self_ty", string(pprust::ty_to_string(self_ty))));
+                fields.push(("items", Json::Array(items.iter().map(|item| {
+                    object(vec![
+                        ("kind", string(match item.node {
+                            ast::ImplItemKind::Const(..) => "const",
+                            ast::ImplItemKind::Method(..) => "method",
+                            ast::ImplItemKind::Type(..) => "type",
+                            ast::ImplItemKind::Macro(..) => "macro",
+                        })),
+                        ("name", name(item.ident)),
+                        ("vis", vis(item.vis)),
+                        ("attrs", self.attrs(&item.attrs)),
+                        ("span", self.span(item.span)),
+                    ])
+                }).collect())));
+                "impl"
+            }
+            ast::ItemKind::Mac(ref mac) => {
+                fields.push(("path", string(pprust::path_to_string(&mac.node.path))));
+                "macro"
+            }
+        };
+        fields.push(("kind", string(kind)));
+        object(fields)
+    }
+
+    fn fields(&self, data: &ast::VariantData) -> Json {
+        Json::Array(data.fields().iter().map(|field| {
+            let (field_name, field_vis) = match field.node.kind {
+                ast::NamedField(ident, vis) => (name(ident), vis),
+                ast::UnnamedField(vis) => (Json::Null, vis),
+            };
+            object(vec![
+                ("name", field_name),
+                ("vis", vis(field_vis)),
+                ("ty", string(pprust::ty_to_string(&field.node.ty))),
+                ("attrs", self.attrs(&field.node.attrs)),
+                ("span", self.span(field.span)),
+            ])
+        }).collect())
+    }
+}
+
+fn meta(item: &ast::MetaItem) -> Json {
+    match item.node {
+        ast::MetaItemKind::Word(ref name) => {
+            object(vec![("name", string(name)), ("kind", string("word"))])
+        }
+        ast::MetaItemKind::NameValue(ref name, ref lit) => {
+            let value = match lit.node {
+                ast::LitKind::Str(ref s, _) => s.to_string(),
+                _ => pprust::lit_to_string(lit),
+            };
+            object(vec![
+                ("name", string(name)),
+                ("kind", string("name_value")),
+                ("value", string(value)),
+            ])
+        }
+        ast::MetaItemKind::List(ref name, ref items) => {
+            object(vec![
+                ("name", string(name)),
+                ("kind", string("list")),
+                ("items", Json::Array(items.iter().map(|i| meta(i)).collect())),
+            ])
+        }
+    }
+}
@@ -0,0 +1,395 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! `-Z trace-attrs`: track how the attributes of every item change during
+//! phase 2, and print where each change came from.
+//!
+//! Rather than hooking into `cfg_attr` processing, every syntax extension and
+//! every plugin, the crate is snapshotted after each step of phase 2 and the
+//! attributes of each item are compared with the previous snapshot. The
+//! origin of a change is then worked out from what is left behind:
+//!
+//! * an attribute that replaced a `#[cfg_attr(cond, attr)]` came from it
+//!   because `cond` holds, and a `cfg_attr` that vanished without a
+//!   replacement was dropped because `cond` does not;
+//! * an attribute (or item) that was added with an expansion backtrace was
+//!   generated by the macro, derive or injection its span points to;
+//! * an attribute that disappeared during expansion was consumed by the
+//!   syntax extension it names.
+//!
+//! Items are identified by their path, with impls printed as
+//! `impl Trait for Type`. The report is printed to stdout, one item at a
+//! time:
+//!
+//! ```text
+//! Foo (foo.rs:4:1: 4:16)
+//!     configuration: added `#[derive(Clone)]`, from `#[cfg_attr(a, derive(Clone))]`: `a` holds
+//!     expansion: removed `#[derive(Clone)]`, consumed by `#[derive]`
+//! impl ::std::clone::Clone for Foo (foo.rs:3:20: 3:25)
+//!     expansion: added item, generated by `#[derive(Clone)]`
+//! ```
+
+use syntax::ast;
+use syntax::attr::AttrMetaMethods;
+use syntax::codemap::{self, CodeMap, Span};
+use syntax::print::pprust;
+use syntax::visit::{self, Visitor};
+
+use std::collections::{HashMap, HashSet};
+
+pub struct AttrTrace<'a> {
+    codemap: &'a CodeMap,
+    items: Vec<ItemAttrs>,
+    changes: Vec<Change>,
+}
+
+/// The attributes of one item in a snapshot.
+struct ItemAttrs {
+    /// `path`, made unique within the snapshot by a `#n` suffix.
+    key: String,
+    path: String,
+    span: Span,
+    attrs: Vec<Attr>,
+}
+
+#[derive(Clone)]
+struct Attr {
+    /// The whole attribute, e.g. `#[inline]`.
+    text: String,
+    /// Its name, e.g. `inline` for `#[inline(always)]`.
+    name: String,
+    /// Its meta item, e.g. `inline`, to match it up with a `cfg_attr`.
+    meta: String,
+    /// For a `cfg_attr`, its condition and the meta item it expands to.
+    cfg_attr: Option<(String, String)>,
+    /// For a `cfg`, its condition.
+    cfg: Option<String>,
+    span: Span,
+}
+
+struct Change {
+    key: String,
+    path: String,
+    span: Span,
+    phase: &'static str,
+    what: String,
+    origin: String,
+}
+
+impl<'a> AttrTrace<'a> {
+    /// Starts a trace at the crate as parsed.
+    pub fn new(codemap: &'a CodeMap, krate: &ast::Crate) -> AttrTrace<'a> {
+        AttrTrace {
+            codemap: codemap,
+            items: snapshot(krate),
+            changes: vec![],
+        }
+    }
+
+    /// Takes a new snapshot of `krate` and attributes every difference to
+    /// the previous one to `phase`.
+    pub fn record(&mut self, phase: &'static str, krate: &ast::Crate) {
+        let items = snapshot(krate);
+
+        let old_keys: HashSet<_> = self.items.iter().map(|item| item.key.clone()).collect();
+        let new_keys: HashSet<_> = items.iter().map(|item| item.key.clone()).collect();
+
+        // Items that went away. Only the outermost one of a removed module
+        // or impl is reported.
+        let mut removed: Vec<String> = vec![];
+        for item in &self.items {
+            if new_keys.contains(&item.key) {
+                continue;
+            }
+            let inside_removed = removed.iter().any(|path| {
+                item.path.starts_with(&format!("{}::", path))
+            });
+            if !inside_removed {
+                let cfgs: Vec<_> = item.attrs.iter().filter_map(|a| a.cfg.clone()).collect();
+                let origin = if cfgs.is_empty() {
+                    format!("removed during {}", phase)
+                } else {
+                    format!("`#[cfg({})]` does not hold", cfgs.join(", "))
+                };
+                self.changes.push(Change {
+                    key: item.key.clone(),
+                    path: item.path.clone(),
+                    span: item.span,
+                    phase: phase,
+                    what: "removed item".to_string(),
+                    origin: origin,
+                });
+            }
+            removed.push(item.path.clone());
+        }
+
+        for item in &items {
+            if old_keys.contains(&item.key) {
+                continue;
+            }
+            let origin = self.expansion_origin(item.span)
+                             .unwrap_or_else(|| format!("added during {}", phase));
+            self.changes.push(Change {
+                key: item.key.clone(),
+                path: item.path.clone(),
+                span: item.span,
+                phase: phase,
+                what: "added item".to_string(),
+                origin: origin,
+            });
+        }
+
+        let old_items: HashMap<_, _> = self.items.iter().map(|item| (&item.key[..], item))
+                                                 .collect();
+        for item in &items {
+            if let Some(old) = old_items.get(&item.key[..]) {
+                let changes = self.diff_attrs(phase, old, item);
+                self.changes.extend(changes);
+            }
+        }
+
+        self.items = items;
+    }
+
+    fn diff_attrs(&self, phase: &'static str, old: &ItemAttrs, new: &ItemAttrs) -> Vec<Change> {
+        // Attributes are compared as a multiset of their printed form, since
+        // their ids are reassigned when they are rebuilt.
+        let mut removed = old.attrs.clone();
+        let mut added = vec![];
+        for attr in &new.attrs {
+            match removed.iter().position(|a| a.text == attr.text) {
+                Some(i) => { removed.remove(i); }
+                None => added.push(attr.clone()),
+            }
+        }
+
+        let change = |what: String, origin: String| Change {
+            key: new.key.clone(),
+            path: new.path.clone(),
+            span: new.span,
+            phase: phase,
+            what: what,
+            origin: origin,
+        };
+
+        let mut changes = vec![];
+        let mut expanded_cfg_attrs = HashSet::new();
+        for attr in &added {
+            let from_cfg_attr = removed.iter().enumerate().find(|&(i, a)| {
+                !expanded_cfg_attrs.contains(&i) &&
+                a.cfg_attr.as_ref().map_or(false, |&(_, ref meta)| *meta == attr.meta)
+            });
+            let origin = match from_cfg_attr {
+                Some((i, a)) => {
+                    expanded_cfg_attrs.insert(i);
+                    let cond = &a.cfg_attr.as_ref().unwrap().0;
+                    format!("from `{}`: `{}` holds", a.text, cond)
+                }
+                None => {
+                    self.expansion_origin(attr.span)
+                        .unwrap_or_else(|| format!("added during {}", phase))
+                }
+            };
+            changes.push(change(format!("added `{}`", attr.text), origin));
+        }
+        for (i, attr) in removed.iter().enumerate() {
+            if expanded_cfg_attrs.contains(&i) {
+                continue;
+            }
+            let origin = match attr.cfg_attr {
+                Some((ref cond, _)) => format!("`{}` does not hold", cond),
+                None if phase == "expansion" => format!("consumed by `#[{}]`", attr.name),
+                None => format!("removed during {}", phase),
+            };
+            changes.push(change(format!("removed `{}`", attr.text), origin));
+        }
+        changes
+    }
+
+    /// Describes the macro, derive or compiler injection that produced
+    /// `span`, if any.
+    fn expansion_origin(&self, span: Span) -> Option<String> {
+        self.codemap.with_expn_info(span.expn_id, |info| {
+            info.map(|info| {
+                match info.callee.format {
+                    codemap::MacroAttribute(name) => {
+                        format!("generated by `#[{}]`", name)
+                    }
+                    codemap::MacroBang(name) => format!("generated by `{}!`", name),
+                    codemap::MacroDerive(name) => {
+                        format!("generated by `#[derive({})]`", name)
+                    }
+                    codemap::CompilerInjection(name) => format!("injected by `{}`", name),
+                }
+            })
+        })
+    }
+
+    /// Prints the report, grouping the changes by item in the order the
+    /// items first changed.
+    pub fn print(&self, crate_name: &str) {
+        println!("attribute trace for crate `{}`:", crate_name);
+
+        let mut order = vec![];
+        let mut by_item: HashMap<&str, Vec<&Change>> = HashMap::new();
+        for change in &self.changes {
+            by_item.entry(&change.key[..]).or_insert_with(|| {
+                order.push(change);
+                vec![]
+            }).push(change);
+        }
+
+        for first in order {
+            println!("{} ({})", first.path, self.codemap.span_to_string(first.span));
+            for change in &by_item[&first.key[..]] {
+                println!("    {}: {}, {}", change.phase, change.what, change.origin);
+            }
+        }
+    }
+}
+
+fn snapshot(krate: &ast::Crate) -> Vec<ItemAttrs> {
+    let mut snapshot = Snapshot {
+        path: vec![],
+        items: vec![],
+        seen: HashMap::new(),
+    };
+    snapshot.push("{{crate}}".to_string(), krate.span, &krate.attrs);
+    visit::walk_crate(&mut snapshot, krate);
+    snapshot.items
+}
+
+struct Snapshot {
+    path: Vec<String>,
+    items: Vec<ItemAttrs>,
+    seen: HashMap<String, usize>,
+}
+
+impl Snapshot {
+    fn push(&mut self, path: String, span: Span, attrs: &[ast::Attribute]) {
+        let count = self.seen.entry(path.clone()).or_insert(0);
+        let key = if *count == 0 { path.clone() } else { format!("{}#{}", path, count) };
+        *count += 1;
+
+        self.items.push(ItemAttrs {
+            key: key,
+            path: path,
+            span: span,
+            attrs: attrs.iter().map(snapshot_attr).collect(),
+        });
+    }
+
+    fn with_item<F: FnOnce(&mut Snapshot)>(&mut self,
+                                           name: String,
+                                           span: Span,
+                                           attrs: &[ast::Attribute],
+                                           f: F) {
+        self.path.push(name);
+        let path = self.path.join("::");
+        self.push(path, span, attrs);
+        f(self);
+        self.path.pop();
+    }
+}
+
+fn snapshot_attr(attr: &ast::Attribute) -> Attr {
+    // `check_name` would mark the attributes as used, so compare the names
+    // directly.
+    let name = attr.name();
+    let cfg_attr = if name == "cfg_attr" {
+        attr.meta_item_list().and_then(|items| {
+            if items.len() == 2 {
+                Some((pprust::meta_item_to_string(&items[0]),
+                      pprust::meta_item_to_string(&items[1])))
+            } else {
+                None
+            }
+        })
+    } else {
+        None
+    };
+    let cfg = if name == "cfg" {
+        attr.meta_item_list().map(|items| {
+            items.iter().map(|mi| pprust::meta_item_to_string(mi)).collect::<Vec<_>>().join(", ")
+        })
+    } else {
+        None
+    };
+    Attr {
+        text: pprust::attr_to_string(attr),
+        name: name.to_string(),
+        meta: pprust::meta_item_to_string(&attr.node.value),
+        cfg_attr: cfg_attr,
+        cfg: cfg,
+        span: attr.span,
+    }
+}
+
+impl<'v> Visitor<'v> for Snapshot {
+    fn visit_item(&mut self, item: &'v ast::Item) {
+        let name = match item.node {
+            // Macro invocations have no attributes of their own to track;
+            // what they expand to shows up as added items.
+            ast::ItemKind::Mac(..) => return,
+            ast::ItemKind::Impl(_, _, _, ref trait_ref, ref self_ty, _) => {
+                match *trait_ref {
+                    Some(ref trait_ref) => {
+                        format!("impl {} for {}",
+                                pprust::path_to_string(&trait_ref.path),
+                                pprust::ty_to_string(self_ty))
+                    }
+                    None => format!("impl {}", pprust::ty_to_string(self_ty)),
+                }
+            }
+            ast::ItemKind::DefaultImpl(_, ref trait_ref) => {
+                format!("impl {} for ..", pprust::path_to_string(&trait_ref.path))
+            }
+            ast::ItemKind::Use(..) => "{{use}}".to_string(),
+            ast::ItemKind::ForeignMod(..) => "{{extern}}".to_string(),
+            ast::ItemKind::ExternCrate(..) => format!("extern crate {}", item.ident),
+            _ => item.ident.to_string(),
+        };
+        self.with_item(name, item.span, &item.attrs, |this| visit::walk_item(this, item));
+    }
+
+    fn visit_trait_item(&mut self, item: &'v ast::TraitItem) {
+        self.with_item(item.ident.to_string(), item.span, &item.attrs,
+                       |this| visit::walk_trait_item(this, item));
+    }
+
+    fn visit_impl_item(&mut self, item: &'v ast::ImplItem) {
+        self.with_item(item.ident.to_string(), item.span, &item.attrs,
+                       |this| visit::walk_impl_item(this, item));
+    }
+
+    fn visit_foreign_item(&mut self, item: &'v ast::ForeignItem) {
+        self.with_item(item.ident.to_string(), item.span, &item.attrs,
+                       |this| visit::walk_foreign_item(this, item));
+    }
+
+    fn visit_variant(&mut self, v: &'v ast::Variant, g: &'v ast::Generics, item_id: ast::NodeId) {
+        self.with_item(v.node.name.to_string(), v.span, &v.node.attrs,
+                       |this| visit::walk_variant(this, v, g, item_id));
+    }
+
+    fn visit_struct_field(&mut self, field: &'v ast::StructField) {
+        match field.node.ident() {
+            Some(ident) => {
+                self.with_item(ident.to_string(), field.span, &field.node.attrs,
+                               |this| visit::walk_struct_field(this, field));
+            }
+            None => visit::walk_struct_field(self, field),
+        }
+    }
+
+    fn visit_mac(&mut self, _mac: &'v ast::Mac) {
+        // Unexpanded macros are left alone; see `visit_item`.
+    }
+}
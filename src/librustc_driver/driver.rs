@@ -13,14 +13,14 @@ use rustc::front;
 use rustc::front::map as hir_map;
 use rustc_mir as mir;
 use rustc::mir::mir_map::MirMap;
-use rustc::session::{Session, CompileResult, compile_result_from_err_count};
+use rustc::session::{Session, CompileResult};
 use rustc::session::config::{self, Input, OutputFilenames, OutputType};
 use rustc::session::search_paths::PathKind;
 use rustc::lint;
-use rustc::middle::{self, dependency_format, stability, ty, reachable};
+use rustc::middle::{self, const_eval, dependency_format, stability, ty, reachable};
 use rustc::middle::privacy::AccessLevels;
 use rustc::middle::ty::TyCtxt;
-use rustc::util::common::time;
+use rustc::util::common::{self, time};
 use rustc::util::nodemap::NodeSet;
 use rustc_borrowck as borrowck;
 use rustc_resolve as resolve;
@@ -38,25 +38,57 @@ use rustc_front::hir;
 use rustc_front::lowering::{lower_crate, LoweringContext};
 use rustc_passes::{no_asm, loops, consts, const_fn, rvalues, static_recursion};
 use super::Compilation;
+use super::attr_trace::AttrTrace;
+use super::ast_json;
+use super::access_levels;
+use super::impl_graph;
 
-use serialize::json;
+use serialize::json::{self, ToJson};
 
 use std::collections::HashMap;
 use std::env;
 use std::ffi::{OsString, OsStr};
 use std::fs;
 use std::io::{self, Write};
+use std::panic::{self, AssertRecoverSafe};
 use std::path::{Path, PathBuf};
 use syntax::ast::{self, NodeIdAssigner};
 use syntax::attr::{self, AttrMetaMethods};
 use syntax::diagnostics;
+use syntax::errors;
 use syntax::fold::Folder;
 use syntax::parse::{self, PResult, token};
-use syntax::util::node_count::NodeCounter;
+use syntax::util::node_count::{NodeCounter, SpanCounter};
 use syntax::visit;
 use syntax;
 use syntax_ext;
 
+/// The reason `compile_input` stopped before producing all of its outputs.
+///
+/// Stopping early at the request of a `CompileController` is not an error;
+/// it is only reported as one if errors had been emitted by then.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CompileError {
+    /// The crate could not be parsed.
+    Parse,
+    /// Errors were reported during expansion or analysis. Carries the number
+    /// of errors emitted.
+    Analysis(usize),
+    /// LLVM failed to optimize the crate or to emit one of its outputs.
+    Llvm,
+    /// The linker could not produce the final artifact.
+    Link,
+}
+
+impl CompileError {
+    fn from_err_count(sess: &Session) -> Result<(), CompileError> {
+        match sess.err_count() {
+            0 => Ok(()),
+            n => Err(CompileError::Analysis(n)),
+        }
+    }
+}
+
 pub fn compile_input(sess: &Session,
                      cstore: &CStore,
                      cfg: ast::CrateConfig,
@@ -64,31 +96,35 @@ pub fn compile_input(sess: &Session,
                      outdir: &Option<PathBuf>,
                      output: &Option<PathBuf>,
                      addl_plugins: Option<Vec<String>>,
-                     control: &CompileController) -> CompileResult {
+                     control: &CompileController) -> Result<(), CompileError> {
     macro_rules! controller_entry_point {
         ($point: ident, $tsess: expr, $make_state: expr, $phase_result: expr) => {{
             let state = $make_state;
-            let phase_result: &CompileResult = &$phase_result;
+            let phase_result: Result<(), CompileError> = $phase_result;
             if phase_result.is_ok() || control.$point.run_callback_on_error {
                 (control.$point.callback)(state);
             }
 
             if control.$point.stop == Compilation::Stop {
-                return compile_result_from_err_count($tsess.err_count());
+                return phase_result.and_then(|()| CompileError::from_err_count($tsess));
             }
         }}
     }
 
+    // Written out when we return, however far compilation got.
+    let mut pass_timings = PassTimingsWriter::new(sess);
+    let _item_timings = ItemTimingsReporter { sess: sess };
+
     // We need nested scopes here, because the intermediate results can keep
     // large chunks of memory alive and we want to free them as soon as
     // possible to keep the peak memory usage low
     let (outputs, trans) = {
         let (outputs, expanded_crate, id) = {
-            let krate = match phase_1_parse_input(sess, cfg, input) {
+            let mut krate = match phase_1_parse_input(sess, cfg, input) {
                 Ok(krate) => krate,
                 Err(mut parse_error) => {
                     parse_error.emit();
-                    return Err(1);
+                    return Err(CompileError::Parse);
                 }
             };
 
@@ -97,13 +133,28 @@ pub fn compile_input(sess: &Session,
                                     CompileState::state_after_parse(input, sess, outdir, &krate),
                                     Ok(()));
 
+            (control.before_expand)(sess, &mut krate);
+
+            report_memory_usage(sess, "parsing", None);
+
             let outputs = build_output_filenames(input, outdir, output, &krate.attrs, sess);
+            let outputs = (control.output_filenames)(sess, input, outputs);
+            pass_timings.set_path(outputs.with_extension("time-passes.json"));
             let id = link::find_crate_name(Some(sess), &krate.attrs, input);
-            let expanded_crate = try!(phase_2_configure_and_expand(sess,
-                                                                   &cstore,
-                                                                   krate,
-                                                                   &id[..],
-                                                                   addl_plugins));
+            let expanded_crate = match *input {
+                Input::ExpandedCrate(_) => phase_2_adopt_expanded_crate(sess, krate),
+                Input::File(_) | Input::Str(_) => {
+                    phase_2_configure_and_expand(sess, &cstore, krate, &id[..], addl_plugins)
+                }
+            };
+            let expanded_crate = match expanded_crate {
+                Ok(krate) => krate,
+                // `-W help` stops here once the lints have been described.
+                Err(0) => return Ok(()),
+                Err(err_count) => return Err(CompileError::Analysis(err_count)),
+            };
+
+            report_memory_usage(sess, "expansion", None);
 
             (outputs, expanded_crate, id)
         };
@@ -125,6 +176,7 @@ pub fn compile_input(sess: &Session,
                                   "lowering ast -> hir",
                                   || hir_map::Forest::new(lower_crate(&lcx, &expanded_crate),
                                                           dep_graph));
+        report_memory_usage(sess, "lowering ast -> hir", None);
 
         // Discard MTWT tables that aren't required past lowering to HIR.
         if !sess.opts.debugging_opts.keep_mtwt_tables &&
@@ -135,8 +187,27 @@ pub fn compile_input(sess: &Session,
         let arenas = ty::CtxtArenas::new();
         let hir_map = make_map(sess, &mut hir_forest);
 
+        {
+            let _ignore = hir_map.dep_graph.in_ignore();
+            controller_entry_point!(after_hir_lowering,
+                                    sess,
+                                    CompileState::state_after_hir_lowering(input,
+                                                                           sess,
+                                                                           outdir,
+                                                                           &hir_map,
+                                                                           &expanded_crate,
+                                                                           &hir_map.krate(),
+                                                                           &id[..],
+                                                                           &lcx),
+                                    Ok(()));
+        }
+
         write_out_deps(sess, &outputs, &id);
 
+        if sess.opts.output_types.contains_key(&OutputType::AstJson) {
+            ast_json::write_ast_json(sess, &expanded_crate, &id, &outputs);
+        }
+
         {
             let _ignore = hir_map.dep_graph.in_ignore();
             controller_entry_point!(after_write_deps,
@@ -152,6 +223,13 @@ pub fn compile_input(sess: &Session,
                                     Ok(()));
         }
 
+        // The source files dep-info lists and the AST are both final after
+        // expansion, so there's no need to analyse the crate if nothing else
+        // was asked for.
+        if sess.opts.only_expansion_outputs_requested() {
+            return CompileError::from_err_count(sess);
+        }
+
         time(sess.time_passes(), "attribute checking", || {
             front::check_attr::check_crate(sess, &expanded_crate);
         });
@@ -168,13 +246,14 @@ pub fn compile_input(sess: &Session,
             None
         };
 
-        try!(try!(phase_3_run_analysis_passes(sess,
-                                              &cstore,
-                                              hir_map,
-                                              &arenas,
-                                              &id,
-                                              control.make_glob_map,
-                                              |tcx, mir_map, analysis, result| {
+        let analysis_result = phase_3_run_analysis_passes(sess,
+                                                          &cstore,
+                                                          hir_map,
+                                                          &arenas,
+                                                          &id,
+                                                          control.make_glob_map,
+                                                          &*control.after_mir_pass,
+                                                          |tcx, mir_map, analysis, result| {
             {
                 // Eventually, we will want to track plugins.
                 let _ignore = tcx.dep_graph.in_ignore();
@@ -188,16 +267,42 @@ pub fn compile_input(sess: &Session,
                                                                mir_map.as_ref(),
                                                                tcx,
                                                                &lcx,
-                                                               &id);
+                                                               &id,
+                                                               cstore);
                 (control.after_analysis.callback)(state);
 
                 if control.after_analysis.stop == Compilation::Stop {
-                    return Err(0usize);
+                    return Ok(None);
                 }
             }
 
+            // The impl graph is also written when coherence reported
+            // conflicting impls, which it is meant to help debug.
+            if tcx.sess.opts.output_types.contains_key(&OutputType::ImplGraph) &&
+               (result.is_ok() || !tcx.overlapping_impls.borrow().is_empty()) {
+                impl_graph::write_impl_graph(tcx, &outputs);
+            }
+
             try!(result);
 
+            if tcx.sess.opts.output_types.contains_key(&OutputType::AccessLevels) {
+                access_levels::write_access_levels(tcx, &analysis.access_levels, &outputs);
+            }
+
+            if tcx.sess.opts.output_types.contains_key(&OutputType::Mir) {
+                if let Some(ref mir_map) = mir_map {
+                    write_mir_file(tcx, mir_map, &outputs);
+                }
+            }
+
+            if control.skip_trans || tcx.sess.opts.only_metadata_requested() {
+                if tcx.sess.opts.output_types.contains_key(&OutputType::Metadata) {
+                    phase_4_write_metadata(tcx, mir_map.unwrap(), analysis, &outputs);
+                }
+                // There is nothing left to translate or link.
+                return Ok(None);
+            }
+
             if log_enabled!(::log::INFO) {
                 println!("Pre-trans");
                 tcx.print_debug_stats();
@@ -205,6 +310,11 @@ pub fn compile_input(sess: &Session,
             let trans = phase_4_translate_to_llvm(tcx,
                                                   mir_map.unwrap(),
                                                   analysis);
+            report_memory_usage(&tcx.sess, "translation", Some(tcx));
+
+            if tcx.sess.opts.output_types.contains_key(&OutputType::Metadata) {
+                write_metadata_file(&tcx.sess, &trans.metadata, &outputs);
+            }
 
             if log_enabled!(::log::INFO) {
                 println!("Post-trans");
@@ -214,23 +324,131 @@ pub fn compile_input(sess: &Session,
             // Discard interned strings as they are no longer required.
             token::get_ident_interner().clear();
 
-            Ok((outputs, trans))
-        })))
+            Ok(Some((outputs, trans)))
+        });
+
+        match try!(analysis_result.and_then(|r| r).map_err(CompileError::Analysis)) {
+            Some(result) => result,
+            None => return CompileError::from_err_count(sess),
+        }
     };
 
-    let phase5_result = phase_5_run_llvm_passes(sess, &trans, &outputs);
+    let phase5_result = recover_fatal(CompileError::Llvm, || {
+        phase_5_run_llvm_passes(sess, &trans, &outputs)
+    }).and_then(|result| result.map_err(|_| CompileError::Llvm));
 
     controller_entry_point!(after_llvm,
                             sess,
-                            CompileState::state_after_llvm(input, sess, outdir, &trans),
+                            CompileState::state_after_llvm(input, sess, outdir, &trans, cstore),
                             phase5_result);
     try!(phase5_result);
 
-    phase_6_link_output(sess, &trans, &outputs);
+    try!(recover_fatal(CompileError::Link, || phase_6_link_output(sess, &trans, &outputs)));
+    if sess.err_count() > 0 {
+        return Err(CompileError::Link);
+    }
 
     Ok(())
 }
 
+/// Runs `f`, turning the fatal error it unwinds with into `err`. LLVM and
+/// the linker report their failures through `Session::fatal`, which unwinds
+/// past `compile_input` unless it is caught here. Any other panic is a bug
+/// and keeps unwinding.
+fn recover_fatal<F, R>(err: CompileError, f: F) -> Result<R, CompileError>
+    where F: FnOnce() -> R
+{
+    match panic::recover(AssertRecoverSafe::new(f)) {
+        Ok(result) => Ok(result),
+        Err(ref value) if value.is::<errors::FatalError>() => Err(err),
+        Err(value) => panic::propagate(value),
+    }
+}
+
+/// Prints the current and peak resident set size under `-Z memory-stats`,
+/// along with the type context's arena sizes once there is a `tcx`.
+fn report_memory_usage(sess: &Session, what: &str, tcx: Option<&TyCtxt>) {
+    if !sess.opts.debugging_opts.memory_stats {
+        return;
+    }
+    common::print_memory_usage(what);
+    if let Some(tcx) = tcx {
+        tcx.print_arena_stats();
+    }
+}
+
+/// Records pass timings for `-Z time-passes-json` and writes them out as a
+/// JSON list once dropped.
+struct PassTimingsWriter<'a> {
+    sess: &'a Session,
+    path: Option<PathBuf>,
+}
+
+impl<'a> PassTimingsWriter<'a> {
+    fn new(sess: &'a Session) -> PassTimingsWriter<'a> {
+        if sess.opts.debugging_opts.time_passes_json {
+            common::start_recording_pass_timings(sess.opts.debugging_opts.time_passes);
+        }
+        PassTimingsWriter { sess: sess, path: None }
+    }
+
+    fn set_path(&mut self, path: PathBuf) {
+        if self.sess.opts.debugging_opts.time_passes_json {
+            self.path = Some(path);
+        }
+    }
+}
+
+impl<'a> Drop for PassTimingsWriter<'a> {
+    fn drop(&mut self) {
+        if !self.sess.opts.debugging_opts.time_passes_json {
+            return;
+        }
+        let timings = common::take_pass_timings();
+        let path = match self.path {
+            Some(ref path) => path,
+            // We never got far enough to know where the outputs go.
+            None => return,
+        };
+        let result = fs::File::create(path).and_then(|mut file| {
+            write!(file, "{}", json::as_pretty_json(&timings))
+        });
+        if let Err(e) = result {
+            self.sess.warn(&format!("error writing pass timings to `{}`: {}",
+                                    path.display(), e));
+        }
+    }
+}
+
+/// Prints the items type checking and translation spent the most time on
+/// under `-Z time-items`, once dropped.
+struct ItemTimingsReporter<'a> {
+    sess: &'a Session,
+}
+
+impl<'a> Drop for ItemTimingsReporter<'a> {
+    fn drop(&mut self) {
+        if !self.sess.time_items() {
+            return;
+        }
+        let timings = common::take_item_timings();
+        for &phase in &["type checking", "translation"] {
+            let slowest = timings.iter()
+                                 .filter(|t| t.phase == phase)
+                                 .take(self.sess.opts.debugging_opts.time_items_count)
+                                 .collect::<Vec<_>>();
+            if slowest.is_empty() {
+                continue;
+            }
+            println!("time-items: slowest items in {}:", phase);
+            for t in slowest {
+                println!("  time: {:.3}\t{}\t({} {})",
+                         t.secs, t.path, t.count,
+                         if t.count == 1 { "time" } else { "times" });
+            }
+        }
+    }
+}
 
 /// The name used for source code that doesn't originate in a file
 /// (e.g. source from stdin or a string)
@@ -242,7 +460,7 @@ pub fn source_name(input: &Input) -> String {
     match *input {
         // FIXME (#9639): This needs to handle non-utf8 paths
         Input::File(ref ifile) => ifile.to_str().unwrap().to_string(),
-        Input::Str(_) => anon_src(),
+        Input::Str(_) | Input::ExpandedCrate(_) => anon_src(),
     }
 }
 
@@ -263,11 +481,37 @@ pub fn source_name(input: &Input) -> String {
 pub struct CompileController<'a> {
     pub after_parse: PhaseController<'a>,
     pub after_expand: PhaseController<'a>,
+    pub after_hir_lowering: PhaseController<'a>,
     pub after_write_deps: PhaseController<'a>,
     pub after_analysis: PhaseController<'a>,
     pub after_llvm: PhaseController<'a>,
 
+    // Whether resolution should record which names are used through each
+    // import. Recording is cheap; the map itself is only grouped when it is
+    // first queried through `CrateAnalysis::glob_map`.
     pub make_glob_map: resolve::MakeGlobMap,
+
+    // Called after each MIR pass with the name of the pass and the MIR as
+    // it left that pass, e.g. to compare the MIR across transformations.
+    pub after_mir_pass: Box<Fn(&str, &TyCtxt, &MirMap) + 'a>,
+
+    // Called with the parsed crate before it is configured and expanded, so
+    // that tools can add crate attributes, `extern crate`s or whole modules
+    // of their own. Anything added is treated as if it had been written in
+    // the source; node ids are assigned later, so `DUMMY_NODE_ID` is fine.
+    pub before_expand: Box<Fn(&Session, &mut ast::Crate) + 'a>,
+
+    // Given the filenames computed from the command line and the crate's
+    // attributes, returns the ones to actually use. Build systems embedding
+    // the compiler can use this to choose where artifacts go, e.g. to place
+    // them in content-hashed directories.
+    pub output_filenames: Box<Fn(&Session, &Input, OutputFilenames) -> OutputFilenames + 'a>,
+
+    // If true, compilation ends once analysis (borrowck included) has
+    // finished and any requested metadata has been written; the crate is
+    // never translated. Unlike stopping `after_analysis`, errors from the
+    // later analysis passes are still reported.
+    pub skip_trans: bool,
 }
 
 impl<'a> CompileController<'a> {
@@ -275,10 +519,24 @@ impl<'a> CompileController<'a> {
         CompileController {
             after_parse: PhaseController::basic(),
             after_expand: PhaseController::basic(),
+            after_hir_lowering: PhaseController::basic(),
             after_write_deps: PhaseController::basic(),
             after_analysis: PhaseController::basic(),
             after_llvm: PhaseController::basic(),
             make_glob_map: resolve::MakeGlobMap::No,
+            after_mir_pass: box |_, _, _| {},
+            before_expand: box |_, _| {},
+            output_filenames: box |_, _, outputs| outputs,
+            skip_trans: false,
+        }
+    }
+
+    /// A controller which checks the crate without translating it, as for
+    /// `-Z check-only`.
+    pub fn check_only() -> CompileController<'a> {
+        CompileController {
+            skip_trans: true,
+            ..CompileController::basic()
         }
     }
 }
@@ -320,6 +578,9 @@ pub struct CompileState<'a, 'ast: 'a, 'tcx: 'a> {
     pub tcx: Option<&'a TyCtxt<'tcx>>,
     pub lcx: Option<&'a LoweringContext<'a>>,
     pub trans: Option<&'a trans::CrateTranslation>,
+    // The crates the local crate depends on, with their metadata. Only set
+    // once they have all been loaded, i.e. from `after_analysis` on.
+    pub cstore: Option<&'a CStore>,
 }
 
 impl<'a, 'ast, 'tcx> CompileState<'a, 'ast, 'tcx> {
@@ -343,6 +604,7 @@ impl<'a, 'ast, 'tcx> CompileState<'a, 'ast, 'tcx> {
             tcx: None,
             lcx: None,
             trans: None,
+            cstore: None,
         }
     }
 
@@ -367,6 +629,25 @@ impl<'a, 'ast, 'tcx> CompileState<'a, 'ast, 'tcx> {
         }
     }
 
+    fn state_after_hir_lowering(input: &'a Input,
+                                session: &'a Session,
+                                out_dir: &'a Option<PathBuf>,
+                                hir_map: &'a hir_map::Map<'ast>,
+                                krate: &'a ast::Crate,
+                                hir_crate: &'a hir::Crate,
+                                crate_name: &'a str,
+                                lcx: &'a LoweringContext<'a>)
+                                -> CompileState<'a, 'ast, 'tcx> {
+        CompileState {
+            crate_name: Some(crate_name),
+            ast_map: Some(hir_map),
+            krate: Some(krate),
+            hir_crate: Some(hir_crate),
+            lcx: Some(lcx),
+            ..CompileState::empty(input, session, out_dir)
+        }
+    }
+
     fn state_after_write_deps(input: &'a Input,
                               session: &'a Session,
                               out_dir: &'a Option<PathBuf>,
@@ -395,10 +676,12 @@ impl<'a, 'ast, 'tcx> CompileState<'a, 'ast, 'tcx> {
                             mir_map: Option<&'a MirMap<'tcx>>,
                             tcx: &'a TyCtxt<'tcx>,
                             lcx: &'a LoweringContext<'a>,
-                            crate_name: &'a str)
+                            crate_name: &'a str,
+                            cstore: &'a CStore)
                             -> CompileState<'a, 'ast, 'tcx> {
         CompileState {
             analysis: Some(analysis),
+            cstore: Some(cstore),
             mir_map: mir_map,
             tcx: Some(tcx),
             krate: krate,
@@ -413,9 +696,14 @@ impl<'a, 'ast, 'tcx> CompileState<'a, 'ast, 'tcx> {
     fn state_after_llvm(input: &'a Input,
                         session: &'a Session,
                         out_dir: &'a Option<PathBuf>,
-                        trans: &'a trans::CrateTranslation)
+                        trans: &'a trans::CrateTranslation,
+                        cstore: &'a CStore)
                         -> CompileState<'a, 'ast, 'tcx> {
-        CompileState { trans: Some(trans), ..CompileState::empty(input, session, out_dir) }
+        CompileState {
+            trans: Some(trans),
+            cstore: Some(cstore),
+            ..CompileState::empty(input, session, out_dir)
+        }
     }
 }
 
@@ -423,11 +711,16 @@ pub fn phase_1_parse_input<'a>(sess: &'a Session,
                                cfg: ast::CrateConfig,
                                input: &Input)
                                -> PResult<'a, ast::Crate> {
-    // These may be left in an incoherent state after a previous compile.
-    // `clear_tables` and `get_ident_interner().clear()` can be used to free
-    // memory, but they do not restore the initial state.
-    syntax::ext::mtwt::reset_tables();
-    token::reset_ident_interner();
+    // The syntax state libsyntax keeps in thread-local storage may be left
+    // over from an earlier compilation on this thread, so every crate that is
+    // parsed starts from a clean slate. Nothing from that compilation (names,
+    // ASTs, crate stores holding crate metadata) may be used afterwards. An
+    // already-expanded crate was built against the current state, which has
+    // to be kept for its names to stay valid.
+    match *input {
+        Input::ExpandedCrate(..) => {}
+        Input::File(..) | Input::Str(..) => syntax::reset_thread_state(),
+    }
 
     let krate = try!(time(sess.time_passes(), "parsing", || {
         match *input {
@@ -440,11 +733,18 @@ pub fn phase_1_parse_input<'a>(sess: &'a Session,
                                                    cfg.clone(),
                                                    &sess.parse_sess)
             }
+            Input::ExpandedCrate(ref krate) => Ok(krate.clone()),
         }
     }));
 
-    if sess.opts.debugging_opts.ast_json_noexpand {
-        println!("{}", json::as_json(&krate));
+    // The parser has already switched to the edition named by the crate's
+    // `#![edition]` attribute; it has to agree with the command line.
+    if let Some(edition) = sess.opts.edition {
+        if sess.edition() != edition {
+            sess.err(&format!("the crate is written for the {} edition, but `--edition {}` \
+                               was passed",
+                              sess.edition(), edition));
+        }
     }
 
     if sess.opts.debugging_opts.input_stats {
@@ -459,12 +759,30 @@ pub fn phase_1_parse_input<'a>(sess: &'a Session,
     Ok(krate)
 }
 
+/// Feature gate uses are only worth recording when `--print features` is
+/// going to report them.
+fn records_gate_uses(sess: &Session) -> bool {
+    sess.opts.prints.contains(&config::PrintRequest::Features)
+}
+
 fn count_nodes(krate: &ast::Crate) -> usize {
     let mut counter = NodeCounter::new();
     visit::walk_crate(&mut counter, krate);
     counter.count
 }
 
+fn print_span_stats(krate: &ast::Crate) {
+    let mut counter = SpanCounter::new();
+    visit::walk_crate(&mut counter, krate);
+    let stats = counter.table.stats();
+    println!("Spans:                     {} ({} inline, {} in side table)",
+             stats.interned,
+             stats.inline,
+             stats.overflow);
+    println!("Span bytes (full):         {}", stats.uncompressed_bytes());
+    println!("Span bytes (compact):      {}", stats.compressed_bytes());
+}
+
 // For continuing compilation after a parsed crate has been
 // modified
 
@@ -491,15 +809,26 @@ pub fn phase_2_configure_and_expand(sess: &Session,
     //
     // baz! should not use this definition unless foo is enabled.
 
+    let mut attr_trace = if sess.opts.debugging_opts.trace_attrs {
+        Some(AttrTrace::new(sess.codemap(), &krate))
+    } else {
+        None
+    };
+
     let mut feature_gated_cfgs = vec![];
     krate = try!(time(time_passes, "configuration 1", || {
         sess.track_errors(|| {
             syntax::config::strip_unconfigured_items(sess.diagnostic(),
                                                      krate,
-                                                     &mut feature_gated_cfgs)
+                                                     &mut feature_gated_cfgs,
+                                                     &mut sess.test_only_uses.borrow_mut())
         })
     }));
 
+    if let Some(ref mut trace) = attr_trace {
+        trace.record("configuration", &krate);
+    }
+
     *sess.crate_types.borrow_mut() = collect_crate_types(sess, &krate.attrs);
     *sess.crate_metadata.borrow_mut() = collect_crate_metadata(sess, &krate.attrs);
 
@@ -509,10 +838,12 @@ pub fn phase_2_configure_and_expand(sess: &Session,
 
     try!(time(time_passes, "gated macro checking", || {
         sess.track_errors(|| {
-            let features =
+            let mut features =
               syntax::feature_gate::check_crate_macros(sess.codemap(),
                                                        &sess.parse_sess.span_diagnostic,
-                                                       &krate);
+                                                       &krate,
+                                                       records_gate_uses(sess));
+            features.inherit_gate_uses(&sess.features.borrow());
 
             // these need to be set "early" so that expansion sees `quote` if enabled.
             *sess.features.borrow_mut() = features;
@@ -521,7 +852,9 @@ pub fn phase_2_configure_and_expand(sess: &Session,
 
 
     krate = time(time_passes, "crate injection", || {
-        syntax::std_inject::maybe_inject_crates_ref(krate, sess.opts.alt_std_name.clone())
+        syntax::std_inject::maybe_inject_crates_ref(&sess.parse_sess,
+                                                    krate,
+                                                    sess.opts.alt_std_name.clone())
     });
 
     let macros = time(time_passes,
@@ -532,6 +865,9 @@ pub fn phase_2_configure_and_expand(sess: &Session,
     let registrars = time(time_passes, "plugin loading", || {
         plugin::load::load_plugins(sess, &cstore, &krate, addl_plugins.take().unwrap())
     });
+    let abi_registrars = time(time_passes, "ABI plugin loading", || {
+        plugin::load::load_abi_plugins(sess)
+    });
 
     let mut registry = Registry::new(sess, &krate);
 
@@ -549,10 +885,14 @@ pub fn phase_2_configure_and_expand(sess: &Session,
             registry.args_hidden = Some(registrar.args);
             (registrar.fun)(&mut registry);
         }
+        for registrar in abi_registrars {
+            plugin::abi::register(&mut registry, registrar.fun);
+        }
     });
 
     let Registry { syntax_exts, early_lint_passes, late_lint_passes, lint_groups,
-                   llvm_passes, attributes, mir_passes, .. } = registry;
+                   llvm_passes, attributes, mir_passes, obligation_observers,
+                   .. } = registry;
 
     try!(sess.track_errors(|| {
         let mut ls = sess.lint_store.borrow_mut();
@@ -569,6 +909,7 @@ pub fn phase_2_configure_and_expand(sess: &Session,
 
         *sess.plugin_llvm_passes.borrow_mut() = llvm_passes;
         sess.mir_passes.borrow_mut().extend(mir_passes);
+        sess.obligation_observers.borrow_mut().extend(obligation_observers);
         *sess.plugin_attributes.borrow_mut() = attributes.clone();
     }));
 
@@ -605,28 +946,33 @@ pub fn phase_2_configure_and_expand(sess: &Session,
                                                       cfg,
                                                       &mut feature_gated_cfgs);
         syntax_ext::register_builtins(&mut ecx.syntax_env);
-        let (ret, macro_names) = syntax::ext::expand::expand_crate(ecx,
-                                                                   macros,
-                                                                   syntax_exts,
-                                                                   krate);
+        let (ret, macro_names, used_macro_crates) =
+            syntax::ext::expand::expand_crate(ecx, macros, syntax_exts, krate);
         if cfg!(windows) {
             env::set_var("PATH", &_old_path);
         }
         *sess.available_macros.borrow_mut() = macro_names;
+        *sess.used_macro_crates.borrow_mut() = used_macro_crates;
         ret
     });
 
+    if let Some(ref mut trace) = attr_trace {
+        trace.record("expansion", &krate);
+    }
+
     // Needs to go *after* expansion to be able to check the results
     // of macro expansion.  This runs before #[cfg] to try to catch as
     // much as possible (e.g. help the programmer avoid platform
     // specific differences)
     try!(time(time_passes, "complete gated feature checking 1", || {
         sess.track_errors(|| {
-            let features = syntax::feature_gate::check_crate(sess.codemap(),
-                                                             &sess.parse_sess.span_diagnostic,
-                                                             &krate,
-                                                             &attributes,
-                                                             sess.opts.unstable_features);
+            let mut features = syntax::feature_gate::check_crate(sess.codemap(),
+                                                                 &sess.parse_sess.span_diagnostic,
+                                                                 &krate,
+                                                                 &attributes,
+                                                                 sess.opts.unstable_features,
+                                                                 records_gate_uses(sess));
+            features.inherit_gate_uses(&sess.features.borrow());
             *sess.features.borrow_mut() = features;
         })
     }));
@@ -638,7 +984,8 @@ pub fn phase_2_configure_and_expand(sess: &Session,
         let krate = time(time_passes, "configuration 2", || {
             syntax::config::strip_unconfigured_items(sess.diagnostic(),
                                                      krate,
-                                                     &mut feature_gated_cfgs)
+                                                     &mut feature_gated_cfgs,
+                                                     &mut sess.test_only_uses.borrow_mut())
         });
 
         time(time_passes, "gated configuration checking", || {
@@ -653,14 +1000,25 @@ pub fn phase_2_configure_and_expand(sess: &Session,
         krate
     }));
 
-    krate = time(time_passes, "maybe building test harness", || {
+    if let Some(ref mut trace) = attr_trace {
+        trace.record("configuration", &krate);
+    }
+
+    let (new_krate, tests) = time(time_passes, "maybe building test harness", || {
         syntax::test::modify_for_testing(&sess.parse_sess, &sess.opts.cfg, krate, sess.diagnostic())
     });
+    krate = new_krate;
+    *sess.tests.borrow_mut() = tests;
 
     krate = time(time_passes,
                  "prelude injection",
                  || syntax::std_inject::maybe_inject_prelude(&sess.parse_sess, krate));
 
+    if let Some(ref mut trace) = attr_trace {
+        trace.record("injection", &krate);
+        trace.print(crate_name);
+    }
+
     time(time_passes,
          "checking that all macro invocations are gone",
          || syntax::ext::expand::check_for_macros(&sess.parse_sess, &krate));
@@ -674,11 +1032,13 @@ pub fn phase_2_configure_and_expand(sess: &Session,
     // can insert new attributes via `cfg_attr`)
     try!(time(time_passes, "complete gated feature checking 2", || {
         sess.track_errors(|| {
-            let features = syntax::feature_gate::check_crate(sess.codemap(),
-                                                             &sess.parse_sess.span_diagnostic,
-                                                             &krate,
-                                                             &attributes,
-                                                             sess.opts.unstable_features);
+            let mut features = syntax::feature_gate::check_crate(sess.codemap(),
+                                                                 &sess.parse_sess.span_diagnostic,
+                                                                 &krate,
+                                                                 &attributes,
+                                                                 sess.opts.unstable_features,
+                                                                 records_gate_uses(sess));
+            features.inherit_gate_uses(&sess.features.borrow());
             *sess.features.borrow_mut() = features;
         })
     }));
@@ -689,11 +1049,62 @@ pub fn phase_2_configure_and_expand(sess: &Session,
 
     if sess.opts.debugging_opts.input_stats {
         println!("Post-expansion node count: {}", count_nodes(&krate));
+        print_span_stats(&krate);
     }
 
     Ok(krate)
 }
 
+/// Takes a crate the caller has already configured and expanded (passed as
+/// `Input::ExpandedCrate`) and does the bookkeeping that
+/// `phase_2_configure_and_expand` would otherwise have done along the way:
+/// recording the crate types and recursion limit, checking feature gates and
+/// setting up the lint levels. No plugins are loaded for such a crate.
+pub fn phase_2_adopt_expanded_crate(sess: &Session,
+                                    krate: ast::Crate)
+                                    -> Result<ast::Crate, usize> {
+    let time_passes = sess.time_passes();
+
+    *sess.crate_types.borrow_mut() = collect_crate_types(sess, &krate.attrs);
+    *sess.crate_metadata.borrow_mut() = collect_crate_metadata(sess, &krate.attrs);
+
+    time(time_passes, "recursion limit", || {
+        middle::recursion_limit::update_recursion_limit(sess, &krate);
+    });
+
+    try!(time(time_passes, "complete gated feature checking", || {
+        sess.track_errors(|| {
+            let features = syntax::feature_gate::check_crate(sess.codemap(),
+                                                             &sess.parse_sess.span_diagnostic,
+                                                             &krate,
+                                                             &[],
+                                                             sess.opts.unstable_features,
+                                                             records_gate_uses(sess));
+            *sess.features.borrow_mut() = features;
+        })
+    }));
+
+    if sess.opts.describe_lints {
+        super::describe_lints(&sess.lint_store.borrow(), false);
+        return Err(0);
+    }
+    try!(sess.track_errors(|| sess.lint_store.borrow_mut().process_command_line(sess)));
+
+    time(time_passes,
+         "checking that all macro invocations are gone",
+         || syntax::ext::expand::check_for_macros(&sess.parse_sess, &krate));
+
+    time(time_passes,
+         "checking for inline asm in case the target doesn't support it",
+         || no_asm::check_crate(sess, &krate));
+
+    try!(time(time_passes,
+              "const fn bodies and arguments",
+              || const_fn::check_crate(sess, &krate)));
+
+    Ok(krate)
+}
+
 pub fn assign_node_ids(sess: &Session, krate: ast::Crate) -> ast::Crate {
     struct NodeIdAssigner<'a> {
         sess: &'a Session,
@@ -710,10 +1121,6 @@ pub fn assign_node_ids(sess: &Session, krate: ast::Crate) -> ast::Crate {
                      "assigning node ids",
                      || NodeIdAssigner { sess: sess }.fold_crate(krate));
 
-    if sess.opts.debugging_opts.ast_json {
-        println!("{}", json::as_json(&krate));
-    }
-
     krate
 }
 
@@ -728,13 +1135,15 @@ pub fn make_map<'ast>(sess: &Session,
 
 /// Run the resolution, typechecking, region checking and other
 /// miscellaneous analysis passes on the crate. Return various
-/// structures carrying the results of the analysis.
+/// structures carrying the results of the analysis. `after_mir_pass` is
+/// called after each MIR pass, as `CompileController::after_mir_pass`.
 pub fn phase_3_run_analysis_passes<'tcx, F, R>(sess: &'tcx Session,
                                                cstore: &CStore,
                                                hir_map: hir_map::Map<'tcx>,
                                                arenas: &'tcx ty::CtxtArenas<'tcx>,
                                                name: &str,
                                                make_glob_map: resolve::MakeGlobMap,
+                                               after_mir_pass: &Fn(&str, &TyCtxt, &MirMap),
                                                f: F)
                                                -> Result<R, usize>
     where F: FnOnce(&TyCtxt<'tcx>, Option<MirMap<'tcx>>, ty::CrateAnalysis, CompileResult) -> R
@@ -803,15 +1212,21 @@ pub fn phase_3_run_analysis_passes<'tcx, F, R>(sess: &'tcx Session,
          "loop checking",
          || loops::check_crate(sess, &hir_map));
 
+    let type_relative_items = const_eval::TypeRelativeItems::new(&hir_map,
+                                                                 &def_map.borrow(),
+                                                                 &trait_map);
+
     try!(time(time_passes,
               "static item recursion checking",
-              || static_recursion::check_crate(sess, &def_map.borrow(), &hir_map)));
+              || static_recursion::check_crate(sess, &def_map.borrow(),
+                                               &type_relative_items, &hir_map)));
 
     let index = stability::Index::new(&hir_map);
 
     TyCtxt::create_and_enter(sess,
                                arenas,
                                def_map,
+                               type_relative_items,
                                named_region_map,
                                hir_map,
                                freevars,
@@ -821,6 +1236,7 @@ pub fn phase_3_run_analysis_passes<'tcx, F, R>(sess: &'tcx Session,
                                |tcx| {
         // passes are timed inside typeck
         try_with_f!(typeck::check_crate(tcx, trait_map), (tcx, None, analysis));
+        report_memory_usage(sess, "type checking", Some(tcx));
 
         time(time_passes,
              "const checking",
@@ -876,8 +1292,11 @@ pub fn phase_3_run_analysis_passes<'tcx, F, R>(sess: &'tcx Session,
             passes.push_pass(box mir::transform::remove_dead_blocks::RemoveDeadBlocks);
             passes.push_pass(box mir::transform::erase_regions::EraseRegions);
             // And run everything.
-            passes.run_passes(tcx, &mut mir_map);
+            passes.run_passes_with(tcx, &mut mir_map, |name, mir_map| {
+                after_mir_pass(name, tcx, mir_map)
+            });
         });
+        report_memory_usage(sess, "MIR construction", Some(tcx));
 
         time(time_passes,
              "borrow checking",
@@ -916,7 +1335,12 @@ pub fn phase_3_run_analysis_passes<'tcx, F, R>(sess: &'tcx Session,
              "lint checking",
              || lint::check_crate(tcx, &analysis.access_levels));
 
-        // The above three passes generate errors w/o aborting
+        // Passes registered by the driver run last, in registration order.
+        for pass in sess.analysis_passes.borrow_mut().iter_mut() {
+            time(time_passes, pass.name(), || pass.run_pass(tcx, &mir_map, &analysis));
+        }
+
+        // The above passes generate errors w/o aborting
         if sess.err_count() > 0 {
             return Ok(f(tcx, Some(mir_map), analysis, Err(sess.err_count())));
         }
@@ -942,6 +1366,42 @@ pub fn phase_4_translate_to_llvm<'tcx>(tcx: &TyCtxt<'tcx>,
          move || trans::trans_crate(tcx, &mir_map, analysis))
 }
 
+/// Encode the crate metadata and write it to the `.rmeta` output without
+/// translating the crate. This replaces phases 4-6 when metadata is the
+/// only output requested.
+pub fn phase_4_write_metadata<'tcx>(tcx: &TyCtxt<'tcx>,
+                                    mir_map: MirMap<'tcx>,
+                                    analysis: ty::CrateAnalysis,
+                                    outputs: &OutputFilenames) {
+    let metadata = time(tcx.sess.time_passes(),
+                        "write metadata",
+                        move || trans::encode_crate_metadata(tcx, &mir_map, analysis));
+    write_metadata_file(&tcx.sess, &metadata, outputs);
+}
+
+fn write_metadata_file(sess: &Session, metadata: &[u8], outputs: &OutputFilenames) {
+    let path = outputs.path(OutputType::Metadata);
+    let result = fs::File::create(&path).and_then(|mut file| file.write_all(metadata));
+    if let Err(e) = result {
+        sess.fatal(&format!("error writing metadata to `{}`: {}", path.display(), e));
+    }
+}
+
+/// Pretty-print the MIR of every item, as it stands after the pass pipeline,
+/// to the `--emit=mir` output.
+fn write_mir_file<'tcx>(tcx: &TyCtxt<'tcx>, mir_map: &MirMap<'tcx>, outputs: &OutputFilenames) {
+    let path = outputs.path(OutputType::Mir);
+    // Sort by node id so the output doesn't depend on hash map ordering.
+    let mut mirs: Vec<_> = mir_map.map.iter().collect();
+    mirs.sort_by(|a, b| a.0.cmp(b.0));
+    let result = fs::File::create(&path).and_then(|mut file| {
+        mir::pretty::write_mir_pretty(tcx, mirs.into_iter(), &mut file)
+    });
+    if let Err(e) = result {
+        tcx.sess.fatal(&format!("error writing MIR to `{}`: {}", path.display(), e));
+    }
+}
+
 /// Run LLVM itself, producing a bitcode file, assembly file or object file
 /// as a side effect.
 pub fn phase_5_run_llvm_passes(sess: &Session,
@@ -1005,6 +1465,10 @@ fn write_out_deps(sess: &Session, outputs: &OutputFilenames, id: &str) {
             }
         }
     }
+    // `output_types` is a hash map, so the targets come out in random order.
+    if sess.deterministic_output() {
+        out_filenames.sort();
+    }
 
     // Write out dependency rules to the dep-info file if requested
     if !sess.opts.output_types.contains_key(&OutputType::DepInfo) {
@@ -1012,31 +1476,21 @@ fn write_out_deps(sess: &Session, outputs: &OutputFilenames, id: &str) {
     }
     let deps_filename = outputs.path(OutputType::DepInfo);
 
-    let result =
-        (|| -> io::Result<()> {
-            // Build a list of files used to compile the output and
-            // write Makefile-compatible dependency rules
-            let files: Vec<String> = sess.codemap()
-                                         .files
-                                         .borrow()
-                                         .iter()
-                                         .filter(|fmap| fmap.is_real_file())
-                                         .filter(|fmap| !fmap.is_imported())
-                                         .map(|fmap| escape_dep_filename(&fmap.name))
-                                         .collect();
-            let mut file = try!(fs::File::create(&deps_filename));
-            for path in &out_filenames {
-                try!(write!(file, "{}: {}\n\n", path.display(), files.join(" ")));
-            }
-
-            // Emit a fake target for each input file to the compilation. This
-            // prevents `make` from spitting out an error if a file is later
-            // deleted. For more info see #28735
-            for path in files {
-                try!(writeln!(file, "{}:", path));
-            }
-            Ok(())
-        })();
+    // Build a list of files used to compile the output.
+    let files: Vec<String> = sess.codemap()
+                                 .files
+                                 .borrow()
+                                 .iter()
+                                 .filter(|fmap| fmap.is_real_file())
+                                 .filter(|fmap| !fmap.is_imported())
+                                 .map(|fmap| fmap.name.clone())
+                                 .collect();
+
+    let result = if sess.opts.debugging_opts.dep_info_json {
+        write_deps_json(sess, &deps_filename, &files, &out_filenames)
+    } else {
+        write_deps_makefile(&deps_filename, &files, &out_filenames)
+    };
 
     match result {
         Ok(()) => {}
@@ -1048,6 +1502,47 @@ fn write_out_deps(sess: &Session, outputs: &OutputFilenames, id: &str) {
     }
 }
 
+/// Writes Makefile-compatible dependency rules.
+fn write_deps_makefile(deps_filename: &Path,
+                       files: &[String],
+                       out_filenames: &[PathBuf])
+                       -> io::Result<()> {
+    let files: Vec<String> = files.iter().map(|f| escape_dep_filename(f)).collect();
+    let mut file = try!(fs::File::create(deps_filename));
+    for path in out_filenames {
+        try!(write!(file, "{}: {}\n\n", path.display(), files.join(" ")));
+    }
+
+    // Emit a fake target for each input file to the compilation. This
+    // prevents `make` from spitting out an error if a file is later
+    // deleted. For more info see #28735
+    for path in files {
+        try!(writeln!(file, "{}:", path));
+    }
+    Ok(())
+}
+
+/// Writes the dependencies as a JSON object for `-Z dep-info-json`, for
+/// build systems that would rather not parse Makefile rules. `env` maps each
+/// environment variable read by `env!` or `option_env!` to its value, or to
+/// `null` if it was unset.
+fn write_deps_json(sess: &Session,
+                   deps_filename: &Path,
+                   files: &[String],
+                   out_filenames: &[PathBuf])
+                   -> io::Result<()> {
+    let outputs: Vec<String> = out_filenames.iter()
+                                            .map(|p| p.display().to_string())
+                                            .collect();
+    let mut deps = json::Object::new();
+    deps.insert("files".to_string(), files.to_json());
+    deps.insert("env".to_string(), sess.parse_sess.env_depinfo.borrow().to_json());
+    deps.insert("outputs".to_string(), outputs.to_json());
+
+    let mut file = try!(fs::File::create(deps_filename));
+    writeln!(file, "{}", json::Json::Object(deps).pretty())
+}
+
 pub fn collect_crate_types(session: &Session, attrs: &[ast::Attribute]) -> Vec<config::CrateType> {
     // Unconditionally collect crate types from attributes to make them used
     let attr_types: Vec<config::CrateType> =
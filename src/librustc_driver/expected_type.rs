@@ -0,0 +1,76 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! `-Z expected-type=FILE:LINE:COL`: prints the type expected at a position
+//! as `{ "span": Span, "expected": "u32" }`, with `Span` as in `ast_json`, or
+//! as `null` if no expected type is known there. Lines and columns are
+//! 1-based, and columns count characters.
+
+use ast_json::{object, span, string};
+use rustc::middle::ty::TyCtxt;
+use rustc_typeck::expected_type::expected_type_at;
+use serialize::json::Json;
+use syntax::codemap::{BytePos, Pos};
+
+pub fn print_expected_type(tcx: &TyCtxt, query: &str) {
+    let pos = match find_pos(tcx, query) {
+        Ok(pos) => pos,
+        Err(msg) => {
+            tcx.sess.err(&format!("invalid `-Z expected-type` position `{}`: {}", query, msg));
+            return;
+        }
+    };
+    let json = match expected_type_at(tcx, pos) {
+        Some((sp, ty)) => object(vec![
+            ("span", span(tcx.sess.codemap(), sp)),
+            ("expected", string(ty)),
+        ]),
+        None => Json::Null,
+    };
+    println!("{}", json);
+}
+
+fn find_pos(tcx: &TyCtxt, query: &str) -> Result<BytePos, String> {
+    // Split from the right, as file names may contain colons themselves.
+    let mut parts = query.rsplitn(3, ':');
+    let (col, line, file) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(col), Some(line), Some(file)) => (col, line, file),
+        _ => return Err("expected `file:line:col`".to_string()),
+    };
+    let line = match line.parse::<usize>() {
+        Ok(line) if line > 0 => line - 1,
+        _ => return Err(format!("`{}` is not a line number", line)),
+    };
+    let col = match col.parse::<usize>() {
+        Ok(col) if col > 0 => col - 1,
+        _ => return Err(format!("`{}` is not a column number", col)),
+    };
+
+    let files = tcx.sess.codemap().files.borrow();
+    let filemap = match files.iter().find(|fm| fm.name == file) {
+        Some(filemap) => filemap,
+        None => return Err(format!("`{}` is not part of this crate", file)),
+    };
+    let text = match filemap.get_line(line) {
+        Some(text) => text,
+        None => return Err(format!("`{}` has no line {}", file, line + 1)),
+    };
+    // The column may be one past the last character, at the end of the line.
+    let offset = if col == text.chars().count() {
+        text.len()
+    } else {
+        match text.char_indices().nth(col) {
+            Some((offset, _)) => offset,
+            None => return Err(format!("line {} has no column {}", line + 1, col + 1)),
+        }
+    };
+    let line_start = filemap.lines.borrow()[line];
+    Ok(line_start + BytePos::from_usize(offset))
+}
@@ -0,0 +1,141 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! `--emit=impl-graph`: a JSON dump of the traits this crate defines or
+//! implements, every impl of them that coherence knows about (from this
+//! crate and from upstream crates), and the pairs of impls coherence found
+//! to overlap.
+//!
+//! The output looks like
+//!
+//! ```text
+//! {
+//!   "traits": [
+//!     {
+//!       "id": "0:5",
+//!       "name": "Foo",
+//!       "impls": [
+//!         { "id": "0:9", "name": "<Bar as Foo>", "local": true,
+//!           "blanket": false, "span": "foo.rs:3:1: 3:20" }
+//!       ]
+//!     }
+//!   ],
+//!   "overlaps": [ { "trait": "0:5", "impls": ["0:9", "2:41"] } ]
+//! }
+//! ```
+//!
+//! Ids are `crate:index` pairs and are only meaningful within one dump.
+
+use rustc::middle::def_id::DefId;
+use rustc::middle::ty::TyCtxt;
+use rustc::session::config::{OutputFilenames, OutputType};
+use rustc_front::hir;
+use serialize::json::{self, Json};
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io::Write;
+
+pub fn write_impl_graph(tcx: &TyCtxt, outputs: &OutputFilenames) {
+    let path = outputs.path(OutputType::ImplGraph);
+    let graph = impl_graph(tcx);
+    let result = fs::File::create(&path).and_then(|mut file| {
+        write!(file, "{}", json::as_pretty_json(&graph))
+    });
+    if let Err(e) = result {
+        tcx.sess.fatal(&format!("error writing impl graph to `{}`: {}", path.display(), e));
+    }
+}
+
+fn impl_graph(tcx: &TyCtxt) -> Json {
+    // Traits defined in this crate, plus every trait it implements.
+    let mut traits = BTreeSet::new();
+    for item in tcx.map.krate().items.values() {
+        match item.node {
+            hir::ItemTrait(..) => {
+                traits.insert(tcx.map.local_def_id(item.id));
+            }
+            hir::ItemImpl(_, _, _, Some(_), _, _) => {
+                let impl_def_id = tcx.map.local_def_id(item.id);
+                if let Some(trait_ref) = tcx.impl_trait_ref(impl_def_id) {
+                    traits.insert(trait_ref.def_id);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut trait_nodes: Vec<_> = traits.into_iter().map(|trait_def_id| {
+        (tcx.def_path_str(trait_def_id), trait_node(tcx, trait_def_id))
+    }).collect();
+    trait_nodes.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let overlaps = tcx.overlapping_impls.borrow().iter().map(|&(impl1, impl2)| {
+        let mut overlap = BTreeMap::new();
+        if let Some(trait_ref) = tcx.impl_trait_ref(impl1) {
+            overlap.insert("trait".to_string(), Json::String(def_id_str(trait_ref.def_id)));
+        }
+        overlap.insert("impls".to_string(), Json::Array(vec![
+            Json::String(def_id_str(impl1)),
+            Json::String(def_id_str(impl2)),
+        ]));
+        Json::Object(overlap)
+    }).collect();
+
+    let mut graph = BTreeMap::new();
+    graph.insert("traits".to_string(),
+                 Json::Array(trait_nodes.into_iter().map(|(_, node)| node).collect()));
+    graph.insert("overlaps".to_string(), Json::Array(overlaps));
+    Json::Object(graph)
+}
+
+fn trait_node(tcx: &TyCtxt, trait_def_id: DefId) -> Json {
+    let trait_def = tcx.lookup_trait_def(trait_def_id);
+    tcx.populate_implementations_for_trait_if_necessary(trait_def_id);
+
+    let mut impls = vec![];
+    {
+        let (blanket_impls, nonblanket_impls) = trait_def.borrow_impl_lists(tcx);
+        for &impl_def_id in blanket_impls.iter() {
+            impls.push((impl_def_id, true));
+        }
+        for group in nonblanket_impls.values() {
+            for &impl_def_id in group {
+                impls.push((impl_def_id, false));
+            }
+        }
+    }
+    // The non-blanket impls are grouped in a hash map; sort so that the
+    // output doesn't depend on its ordering.
+    impls.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let impls = impls.into_iter().map(|(impl_def_id, blanket)| {
+        let mut node = BTreeMap::new();
+        node.insert("id".to_string(), Json::String(def_id_str(impl_def_id)));
+        node.insert("name".to_string(), Json::String(tcx.def_path_str(impl_def_id)));
+        node.insert("local".to_string(), Json::Boolean(impl_def_id.is_local()));
+        node.insert("blanket".to_string(), Json::Boolean(blanket));
+        if let Some(node_id) = tcx.map.as_local_node_id(impl_def_id) {
+            let span = tcx.sess.codemap().span_to_string(tcx.map.span(node_id));
+            node.insert("span".to_string(), Json::String(span));
+        }
+        Json::Object(node)
+    }).collect();
+
+    let mut node = BTreeMap::new();
+    node.insert("id".to_string(), Json::String(def_id_str(trait_def_id)));
+    node.insert("name".to_string(), Json::String(tcx.def_path_str(trait_def_id)));
+    node.insert("impls".to_string(), Json::Array(impls));
+    Json::Object(node)
+}
+
+fn def_id_str(def_id: DefId) -> String {
+    format!("{}:{}", def_id.krate, def_id.index.as_usize())
+}
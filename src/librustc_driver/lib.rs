@@ -24,18 +24,17 @@
 #![cfg_attr(not(stage0), deny(warnings))]
 
 #![feature(box_syntax)]
-#![feature(libc)]
 #![feature(quote)]
+#![feature(panic_propagate)]
+#![feature(recover)]
 #![feature(rustc_diagnostic_macros)]
 #![feature(rustc_private)]
 #![feature(set_stdio)]
 #![feature(staged_api)]
+#![feature(std_panic)]
 
-extern crate arena;
-extern crate flate;
 extern crate getopts;
 extern crate graphviz;
-extern crate libc;
 extern crate rustc;
 extern crate rustc_back;
 extern crate rustc_borrowck;
@@ -57,13 +56,13 @@ extern crate log;
 extern crate syntax;
 extern crate syntax_ext;
 
-use driver::CompileController;
+use driver::{CompileController, CompileError};
 use pretty::{PpMode, UserIdentifiedItem};
 
 use rustc_resolve as resolve;
 use rustc_trans::back::link;
 use rustc_trans::save;
-use rustc::session::{config, Session, build_session, CompileResult};
+use rustc::session::{self, config, Session, build_session};
 use rustc::session::config::{Input, PrintRequest, OutputType, ErrorOutputType};
 use rustc::session::config::{get_unstable_features_setting, OptionStability};
 use rustc::middle::cstore::CrateStore;
@@ -87,21 +86,30 @@ use std::sync::{Arc, Mutex};
 use std::thread;
 
 use rustc::session::{early_error, early_warn};
+use serialize::json::ToJson;
 
 use syntax::ast;
+use syntax::codemap::{CodeMap, FileLoader, RealFileLoader};
 use syntax::parse::{self, PResult};
 use syntax::errors;
 use syntax::errors::emitter::Emitter;
 use syntax::diagnostics;
 use syntax::parse::token;
-use syntax::feature_gate::{GatedCfg, UnstableFeatures};
+use syntax::feature_gate::{self, GatedCfg, UnstableFeatures};
 
 #[cfg(test)]
 pub mod test;
 
+pub mod args;
 pub mod driver;
 pub mod pretty;
 pub mod target_features;
+mod access_levels;
+mod ast_json;
+mod attr_trace;
+mod expected_type;
+mod impl_graph;
+pub mod server;
 
 
 const BUG_REPORT_URL: &'static str = "https://github.com/rust-lang/rust/blob/master/CONTRIBUTING.\
@@ -128,16 +136,16 @@ pub fn abort_on_err<T>(result: Result<T, usize>, sess: &Session) -> T {
 pub fn run(args: Vec<String>) -> isize {
     monitor(move || {
         let (result, session) = run_compiler(&args, &mut RustcDefaultCalls);
-        if let Err(err_count) = result {
-            if err_count > 0 {
-                match session {
-                    Some(sess) => sess.fatal(&abort_msg(err_count)),
-                    None => {
-                        let mut emitter =
-                            errors::emitter::BasicEmitter::stderr(errors::ColorConfig::Auto);
-                        emitter.emit(None, &abort_msg(err_count), None, errors::Level::Fatal);
-                        exit_on_err();
-                    }
+        if result.is_err() {
+            match session {
+                Some(sess) => sess.fatal(&abort_msg(sess.err_count())),
+                None => {
+                    // We only fail once an error has been emitted, even if
+                    // there is no session left to count it.
+                    let mut emitter =
+                        errors::emitter::BasicEmitter::stderr(errors::ColorConfig::Auto);
+                    emitter.emit(None, &abort_msg(1), None, errors::Level::Fatal);
+                    exit_on_err();
                 }
             }
         }
@@ -149,7 +157,19 @@ pub fn run(args: Vec<String>) -> isize {
 // See comments on CompilerCalls below for details about the callbacks argument.
 pub fn run_compiler<'a>(args: &[String],
                         callbacks: &mut CompilerCalls<'a>)
-                        -> (CompileResult, Option<Session>) {
+                        -> (Result<(), CompileError>, Option<Session>) {
+    run_compiler_with_file_loader(args, callbacks, box RealFileLoader)
+}
+
+// Like run_compiler, but all source files are read through `loader`
+// instead of straight from the filesystem. This lets a driver compile a
+// tree of sources that only exists in memory, e.g. unsaved editor buffers.
+pub fn run_compiler_with_file_loader<'a, L>(args: &[String],
+                                            callbacks: &mut CompilerCalls<'a>,
+                                            loader: Box<L>)
+                                            -> (Result<(), CompileError>, Option<Session>)
+    where L: FileLoader + 'static
+{
     macro_rules! do_or_return {($expr: expr, $sess: expr) => {
         match $expr {
             Compilation::Stop => return (Ok(()), $sess),
@@ -182,7 +202,12 @@ pub fn run_compiler<'a>(args: &[String],
     };
 
     let cstore = Rc::new(CStore::new(token::get_ident_interner()));
-    let sess = build_session(sopts, input_file_path, descriptions, cstore.clone());
+    let codemap = Rc::new(CodeMap::with_file_loader(loader));
+    let sess = session::build_session_with_codemap(sopts,
+                                                   input_file_path,
+                                                   descriptions,
+                                                   cstore.clone(),
+                                                   codemap);
     rustc_lint::register_builtins(&mut sess.lint_store.borrow_mut(), Some(&sess));
     let mut cfg = config::build_configuration(&sess);
     target_features::add_configuration(&mut cfg, &sess);
@@ -353,7 +378,8 @@ fn handle_explain(code: &str,
 fn check_cfg(sopts: &config::Options,
              output: ErrorOutputType) {
     let mut emitter: Box<Emitter> = match output {
-        config::ErrorOutputType::HumanReadable(color_config) => {
+        config::ErrorOutputType::HumanReadable(color_config) |
+        config::ErrorOutputType::Short(color_config) => {
             Box::new(errors::emitter::BasicEmitter::stderr(color_config))
         }
         config::ErrorOutputType::Json => Box::new(errors::json::JsonEmitter::basic()),
@@ -404,6 +430,11 @@ impl<'a> CompilerCalls<'a> for RustcDefaultCalls {
                 -> Option<(Input, Option<PathBuf>)> {
         match matches.free.len() {
             0 => {
+                if sopts.debugging_opts.server {
+                    let stdin = io::stdin();
+                    server::serve(stdin.lock(), io::stdout());
+                    return None;
+                }
                 if sopts.describe_lints {
                     let mut ls = lint::LintStore::new();
                     rustc_lint::register_builtins(&mut ls, None);
@@ -439,14 +470,25 @@ impl<'a> CompilerCalls<'a> for RustcDefaultCalls {
         } else {
             None
         };
-        if pretty.is_none() && sess.unstable_options() {
+        let pretty = if pretty.is_none() && sess.unstable_options() {
             matches.opt_str("unpretty").map(|a| {
                 // extended with unstable pretty-print variants
                 pretty::parse_pretty(sess, &a, true)
             })
         } else {
             pretty
-        }
+        };
+        // `-Z ast-json` and `-Z ast-json-noexpand` are shorthands for the
+        // corresponding `--unpretty` modes.
+        pretty.or_else(|| {
+            if sess.opts.debugging_opts.ast_json {
+                Some((PpMode::PpmAstJson(pretty::PpmExpanded), None))
+            } else if sess.opts.debugging_opts.ast_json_noexpand {
+                Some((PpMode::PpmAstJson(pretty::PpmNormal), None))
+            } else {
+                None
+            }
+        })
     }
 
     fn late_callback(&mut self,
@@ -461,14 +503,17 @@ impl<'a> CompilerCalls<'a> for RustcDefaultCalls {
     }
 
     fn build_controller(&mut self, sess: &Session) -> CompileController<'a> {
-        let mut control = CompileController::basic();
+        let mut control = if sess.opts.debugging_opts.check_only {
+            CompileController::check_only()
+        } else {
+            CompileController::basic()
+        };
 
-        if sess.opts.parse_only || sess.opts.debugging_opts.show_span.is_some() ||
-           sess.opts.debugging_opts.ast_json_noexpand {
+        if sess.opts.parse_only || sess.opts.debugging_opts.show_span.is_some() {
             control.after_parse.stop = Compilation::Stop;
         }
 
-        if sess.opts.no_analysis || sess.opts.debugging_opts.ast_json {
+        if sess.opts.no_analysis {
             control.after_write_deps.stop = Compilation::Stop;
         }
 
@@ -495,6 +540,51 @@ impl<'a> CompilerCalls<'a> for RustcDefaultCalls {
             control.make_glob_map = resolve::MakeGlobMap::Yes;
         }
 
+        if sess.opts.tests_list {
+            control.after_expand.stop = Compilation::Stop;
+            control.after_expand.callback = box |state| {
+                let sess = state.session;
+                print!("{}", syntax::test::tests_to_json(sess.codemap(), &sess.tests.borrow()));
+            };
+        }
+
+        if let Some(ref name) = sess.opts.debugging_opts.interpret {
+            let name = name.clone();
+            control.after_analysis.stop = Compilation::Stop;
+            control.after_analysis.callback = box move |state| {
+                // This callback runs even when analysis failed.
+                if state.session.err_count() > 0 {
+                    return;
+                }
+                rustc_mir::interpret::run_named(state.tcx.unwrap(),
+                                                state.mir_map.unwrap(),
+                                                &name);
+            };
+        }
+
+        if let Some(ref query) = sess.opts.debugging_opts.expected_type {
+            let query = query.clone();
+            control.after_analysis.stop = Compilation::Stop;
+            control.after_analysis.callback = box move |state| {
+                // Tools ask about code that is still being edited, which
+                // often doesn't type check yet, so answer as best we can.
+                if let Some(tcx) = state.tcx {
+                    expected_type::print_expected_type(tcx, &query);
+                }
+            };
+            control.after_analysis.run_callback_on_error = true;
+        }
+
+        if sess.opts.prints.contains(&PrintRequest::Features) {
+            control.after_analysis.stop = Compilation::Stop;
+            control.after_analysis.callback = box |state| {
+                let sess = state.session;
+                print!("{}", feature_gate::gate_uses_to_json(sess.codemap(),
+                                                             &sess.features.borrow().gate_uses));
+            };
+            control.after_analysis.run_callback_on_error = true;
+        }
+
         control
     }
 }
@@ -511,7 +601,7 @@ impl RustcDefaultCalls {
                         .unwrap();
                     println!("{}", String::from_utf8(v).unwrap());
                 }
-                &Input::Str(_) => {
+                &Input::Str(_) | &Input::ExpandedCrate(_) => {
                     early_error(ErrorOutputType::default(), "cannot list metadata for stdin");
                 }
             }
@@ -551,7 +641,15 @@ impl RustcDefaultCalls {
                     targets.sort();
                     println!("{}", targets.join("\n"));
                 },
+                PrintRequest::TargetSpec => println!("{}", sess.target.target.to_json().pretty()),
                 PrintRequest::Sysroot => println!("{}", sess.sysroot().display()),
+                PrintRequest::Features => {
+                    // Gates are only known once the crate has been analysed,
+                    // so this is printed from `build_controller` instead.
+                    if input.is_none() {
+                        early_error(ErrorOutputType::default(), "no input file provided");
+                    }
+                }
                 PrintRequest::FileNames |
                 PrintRequest::CrateName => {
                     let input = match input {
@@ -608,6 +706,9 @@ impl RustcDefaultCalls {
                 }
             }
         }
+        if sess.opts.prints.contains(&PrintRequest::Features) {
+            return Compilation::Continue;
+        }
         return Compilation::Stop;
     }
 }
@@ -869,7 +970,12 @@ fn print_flag_list<T>(cmdline_opt: &str,
 ///
 /// So with all that in mind, the comments below have some more detail about the
 /// contortions done here to get things to work out correctly.
-pub fn handle_options(mut args: Vec<String>) -> Option<getopts::Matches> {
+pub fn handle_options(args: Vec<String>) -> Option<getopts::Matches> {
+    let mut args = match args::expand_response_files(args) {
+        Ok(args) => args,
+        Err(e) => early_error(ErrorOutputType::default(), &e),
+    };
+
     // Throw away the first argument, the name of the binary
     let _binary = args.remove(0);
 
@@ -1000,6 +1106,7 @@ fn parse_crate_attrs<'a>(sess: &'a Session, input: &Input) -> PResult<'a, Vec<as
                                                      Vec::new(),
                                                      &sess.parse_sess)
         }
+        Input::ExpandedCrate(ref krate) => Ok(krate.attrs.clone()),
     }
 }
 
@@ -40,6 +40,7 @@ use syntax::print::{pp, pprust};
 use syntax::print::pprust::PrintState;
 use syntax::ptr::P;
 use syntax::util::small_vector::SmallVector;
+use serialize::json;
 
 use graphviz as dot;
 
@@ -81,6 +82,9 @@ pub enum PpMode {
     PpmSource(PpSourceMode),
     PpmHir(PpSourceMode),
     PpmFlowGraph(PpFlowGraphMode),
+    /// The AST as JSON, either as parsed (`PpmNormal`) or after expansion
+    /// and node id assignment (`PpmExpanded`).
+    PpmAstJson(PpSourceMode),
     PpmMir,
     PpmMirCFG,
 }
@@ -99,6 +103,8 @@ pub fn parse_pretty(sess: &Session,
         ("expanded", _) => PpmSource(PpmExpanded),
         ("expanded,identified", _) => PpmSource(PpmExpandedIdentified),
         ("expanded,hygiene", _) => PpmSource(PpmExpandedHygiene),
+        ("ast-json", true) => PpmAstJson(PpmNormal),
+        ("expanded,ast-json", true) => PpmAstJson(PpmExpanded),
         ("hir", true) => PpmHir(PpmNormal),
         ("hir,identified", true) => PpmHir(PpmIdentified),
         ("hir,typed", true) => PpmHir(PpmTyped),
@@ -111,7 +117,8 @@ pub fn parse_pretty(sess: &Session,
                 sess.fatal(&format!("argument to `unpretty` must be one of `normal`, \
                                      `expanded`, `flowgraph[,unlabelled]=<nodeid>`, \
                                      `identified`, `expanded,identified`, `everybody_loops`, \
-                                     `hir`, `hir,identified`, `hir,typed`, or `mir`; got {}",
+                                     `ast-json`, `expanded,ast-json`, `hir`, `hir,identified`, \
+                                     `hir,typed`, or `mir`; got {}",
                                     name));
             } else {
                 sess.fatal(&format!("argument to `pretty` must be one of `normal`, `expanded`, \
@@ -209,6 +216,7 @@ impl PpSourceMode {
                                                                  arenas,
                                                                  id,
                                                                  resolve::MakeGlobMap::No,
+                                                                 &|_, _, _| {},
                                                                  |tcx, _, _, _| {
                     let annotation = TypedAnnotation {
                         tcx: tcx,
@@ -573,14 +581,18 @@ fn needs_ast_map(ppm: &PpMode, opt_uii: &Option<UserIdentifiedItem>) -> bool {
         PpmSource(PpmEveryBodyLoops) |
         PpmSource(PpmIdentified) => opt_uii.is_some(),
 
+        PpmAstJson(PpmNormal) => false,
+
         PpmSource(PpmExpanded) |
         PpmSource(PpmExpandedIdentified) |
         PpmSource(PpmExpandedHygiene) |
+        PpmAstJson(PpmExpanded) |
         PpmHir(_) |
         PpmMir |
         PpmMirCFG |
         PpmFlowGraph(_) => true,
-        PpmSource(PpmTyped) => panic!("invalid state"),
+        PpmSource(PpmTyped) |
+        PpmAstJson(_) => panic!("invalid state"),
     }
 }
 
@@ -588,16 +600,19 @@ fn needs_expansion(ppm: &PpMode) -> bool {
     match *ppm {
         PpmSource(PpmNormal) |
         PpmSource(PpmEveryBodyLoops) |
-        PpmSource(PpmIdentified) => false,
+        PpmSource(PpmIdentified) |
+        PpmAstJson(PpmNormal) => false,
 
         PpmSource(PpmExpanded) |
         PpmSource(PpmExpandedIdentified) |
         PpmSource(PpmExpandedHygiene) |
+        PpmAstJson(PpmExpanded) |
         PpmHir(_) |
         PpmMir |
         PpmMirCFG |
         PpmFlowGraph(_) => true,
-        PpmSource(PpmTyped) => panic!("invalid state"),
+        PpmSource(PpmTyped) |
+        PpmAstJson(_) => panic!("invalid state"),
     }
 }
 
@@ -759,6 +774,11 @@ pub fn pretty_print_input(sess: Session,
             })
         }
 
+        (PpmAstJson(_), _) => {
+            // Silently ignores an identified node.
+            writeln!(out, "{}", json::as_json(&krate))
+        }
+
         (PpmHir(s), None) => {
             let out: &mut Write = &mut out;
             s.call_with_pp_support_hir(&sess,
@@ -828,6 +848,7 @@ pub fn pretty_print_input(sess: Session,
                                                              &arenas,
                                                              &id,
                                                              resolve::MakeGlobMap::No,
+                                                             &|_, _, _| {},
                                                              |tcx, mir_map, _, _| {
                 if let Some(mir_map) = mir_map {
                     if let Some(nodeid) = nodeid {
@@ -875,6 +896,7 @@ pub fn pretty_print_input(sess: Session,
                                                                      &arenas,
                                                                      &id,
                                                                      resolve::MakeGlobMap::No,
+                                                                     &|_, _, _| {},
                                                                      |tcx, _, _, _| {
                         print_flowgraph(variants,
                                         tcx,
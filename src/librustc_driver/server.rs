@@ -0,0 +1,121 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! `rustc -Z server`: stay resident and run compile jobs one after the
+//! other, so that a build system only pays for starting the compiler once.
+//!
+//! Jobs are read from stdin, one per line, each a JSON array holding the
+//! arguments of a normal `rustc` invocation (without the program name):
+//!
+//! ```text
+//! ["foo.rs", "--crate-type", "lib", "--out-dir", "out"]
+//! ```
+//!
+//! Diagnostics go to stderr as usual. Once a job is done, a line of the form
+//! `{"status":0}` is written to stdout, with the exit code `rustc` would have
+//! returned. The server exits at the end of its input.
+//!
+//! All jobs run on the same thread, which keeps the metadata of upstream
+//! crates between jobs (see `loader::enable_metadata_cache`); a library is
+//! only read again if its file changed. Each job still gets its own session
+//! and crate store.
+
+use rustc_metadata::loader;
+use serialize::json::Json;
+use syntax::errors;
+use syntax::errors::emitter::Emitter;
+
+use std::io::{BufRead, Write};
+use std::panic;
+
+use super::{abort_msg, run_compiler, RustcDefaultCalls};
+
+pub fn serve<R: BufRead, W: Write>(input: R, mut output: W) {
+    loader::enable_metadata_cache();
+
+    for line in input.lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(_) => break,
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let status = match parse_job(&line) {
+            Ok(args) => run_job(args),
+            Err(msg) => {
+                let mut emitter =
+                    errors::emitter::BasicEmitter::stderr(errors::ColorConfig::Auto);
+                emitter.emit(None, &format!("invalid compile job: {}", msg), None,
+                             errors::Level::Error);
+                1
+            }
+        };
+
+        let result = writeln!(output, "{{\"status\":{}}}", status)
+            .and_then(|()| output.flush());
+        if result.is_err() {
+            // Nobody is listening any more.
+            break;
+        }
+    }
+}
+
+fn parse_job(line: &str) -> Result<Vec<String>, String> {
+    let json = try!(Json::from_str(line).map_err(|e| e.to_string()));
+    let args = match json {
+        Json::Array(args) => args,
+        _ => return Err("expected an array of arguments".to_string()),
+    };
+    let mut job = vec!["rustc".to_string()];
+    for arg in args {
+        match arg {
+            Json::String(arg) => job.push(arg),
+            _ => return Err("arguments must be strings".to_string()),
+        }
+    }
+    Ok(job)
+}
+
+/// Runs one compilation, returning its exit code. Compiler errors (which
+/// unwind with `FatalError`) and bugs are caught here, so that they only end
+/// the job and not the server.
+fn run_job(args: Vec<String>) -> i32 {
+    let result = panic::recover(move || {
+        let (result, session) = run_compiler(&args, &mut RustcDefaultCalls);
+        if result.is_err() {
+            match session {
+                Some(sess) => sess.fatal(&abort_msg(sess.err_count())),
+                None => {
+                    let mut emitter =
+                        errors::emitter::BasicEmitter::stderr(errors::ColorConfig::Auto);
+                    emitter.emit(None, &abort_msg(1), None, errors::Level::Fatal);
+                }
+            }
+        }
+        result.is_ok()
+    });
+
+    match result {
+        Ok(true) => 0,
+        Ok(false) => 1,
+        Err(ref value) if value.is::<errors::FatalError>() => 1,
+        Err(value) => {
+            let mut emitter = errors::emitter::BasicEmitter::stderr(errors::ColorConfig::Auto);
+            if !value.is::<errors::ExplicitBug>() {
+                emitter.emit(None, "unexpected panic", None, errors::Level::Bug);
+            }
+            emitter.emit(None, "the compiler unexpectedly panicked. this is a bug.", None,
+                         errors::Level::Note);
+            101
+        }
+    }
+}
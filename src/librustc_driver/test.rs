@@ -14,6 +14,7 @@ use driver;
 use rustc::dep_graph::DepGraph;
 use rustc_lint;
 use rustc_resolve as resolve;
+use rustc_typeck::middle::const_eval;
 use rustc_typeck::middle::lang_items;
 use rustc_typeck::middle::free_region::FreeRegionMap;
 use rustc_typeck::middle::region::{self, CodeExtent};
@@ -128,14 +129,18 @@ fn test_env<F>(source_string: &str,
 
     // run just enough stuff to build a tcx:
     let lang_items = lang_items::collect_language_items(&sess, &ast_map);
-    let resolve::CrateMap { def_map, freevars, .. } =
+    let resolve::CrateMap { def_map, freevars, trait_map, .. } =
         resolve::resolve_crate(&sess, &ast_map, resolve::MakeGlobMap::No);
+    let type_relative_items = const_eval::TypeRelativeItems::new(&ast_map,
+                                                                 &def_map.borrow(),
+                                                                 &trait_map);
     let named_region_map = resolve_lifetime::krate(&sess, &ast_map, &def_map.borrow());
     let region_map = region::resolve_crate(&sess, &ast_map);
     let index = stability::Index::new(&ast_map);
     TyCtxt::create_and_enter(&sess,
                                &arenas,
                                def_map,
+                               type_relative_items,
                                named_region_map.unwrap(),
                                ast_map,
                                freevars,
@@ -221,7 +226,9 @@ impl<'a, 'tcx> Env<'a, 'tcx> {
 
                 hir::ItemEnum(..) |
                 hir::ItemStruct(..) |
+                hir::ItemUnion(..) |
                 hir::ItemTrait(..) |
+                hir::ItemTraitAlias(..) |
                 hir::ItemImpl(..) |
                 hir::ItemDefaultImpl(..) => {
                     None
@@ -384,6 +384,9 @@ pub fn noop_fold_ty<T: Folder>(t: P<Ty>, fld: &mut T) -> P<Ty> {
                 TyPolyTraitRef(bounds) => {
                     TyPolyTraitRef(bounds.move_map(|b| fld.fold_ty_param_bound(b)))
                 }
+                TyImplTrait(bounds) => {
+                    TyImplTrait(bounds.move_map(|b| fld.fold_ty_param_bound(b)))
+                }
             },
             span: fld.new_span(span),
         }
@@ -406,6 +409,7 @@ pub fn noop_fold_variant<T: Folder>(v: Variant, fld: &mut T) -> Variant {
             attrs: fold_attrs(v.node.attrs, fld),
             data: fld.fold_variant_data(v.node.data),
             disr_expr: v.node.disr_expr.map(|e| fld.fold_expr(e)),
+            where_clause: fld.fold_where_clause(v.node.where_clause),
         },
         span: fld.new_span(v.span),
     }
@@ -450,10 +454,11 @@ pub fn noop_fold_path_parameters<T: Folder>(path_parameters: PathParameters,
 pub fn noop_fold_angle_bracketed_parameter_data<T: Folder>(data: AngleBracketedParameterData,
                                                            fld: &mut T)
                                                            -> AngleBracketedParameterData {
-    let AngleBracketedParameterData { lifetimes, types, bindings } = data;
+    let AngleBracketedParameterData { lifetimes, types, consts, bindings } = data;
     AngleBracketedParameterData {
         lifetimes: fld.fold_lifetimes(lifetimes),
         types: types.move_map(|ty| fld.fold_ty(ty)),
+        consts: consts.move_map(|e| fld.fold_expr(e)),
         bindings: bindings.move_map(|b| fld.fold_ty_binding(b)),
     }
 }
@@ -610,11 +615,18 @@ pub fn noop_fold_opt_lifetime<T: Folder>(o_lt: Option<Lifetime>, fld: &mut T) ->
     o_lt.map(|lt| fld.fold_lifetime(lt))
 }
 
-pub fn noop_fold_generics<T: Folder>(Generics { ty_params, lifetimes, where_clause }: Generics,
-                                     fld: &mut T)
-                                     -> Generics {
+pub fn noop_fold_generics<T: Folder>(generics: Generics, fld: &mut T) -> Generics {
+    let Generics { ty_params, const_params, lifetimes, where_clause } = generics;
     Generics {
         ty_params: fld.fold_ty_params(ty_params),
+        const_params: const_params.move_map(|ConstParam { name, id, ty, span }| {
+            ConstParam {
+                name: name,
+                id: fld.new_id(id),
+                ty: fld.fold_ty(ty),
+                span: fld.new_span(span),
+            }
+        }),
         lifetimes: fld.fold_lifetime_defs(lifetimes),
         where_clause: fld.fold_where_clause(where_clause),
     }
@@ -651,14 +663,12 @@ pub fn noop_fold_where_predicate<T: Folder>(pred: WherePredicate, fld: &mut T) -
                 bounds: bounds.move_map(|bound| fld.fold_lifetime(bound)),
             })
         }
-        hir::WherePredicate::EqPredicate(hir::WhereEqPredicate{id,
-                                                               path,
-                                                               ty,
+        hir::WherePredicate::EqPredicate(hir::WhereEqPredicate{lhs_ty,
+                                                               rhs_ty,
                                                                span}) => {
             hir::WherePredicate::EqPredicate(hir::WhereEqPredicate {
-                id: fld.new_id(id),
-                path: fld.fold_path(path),
-                ty: fld.fold_ty(ty),
+                lhs_ty: fld.fold_ty(lhs_ty),
+                rhs_ty: fld.fold_ty(rhs_ty),
                 span: fld.new_span(span),
             })
         }
@@ -782,6 +792,10 @@ pub fn noop_fold_item_underscore<T: Folder>(i: Item_, folder: &mut T) -> Item_ {
             let struct_def = folder.fold_variant_data(struct_def);
             ItemStruct(struct_def, folder.fold_generics(generics))
         }
+        ItemUnion(struct_def, generics) => {
+            let struct_def = folder.fold_variant_data(struct_def);
+            ItemUnion(struct_def, folder.fold_generics(generics))
+        }
         ItemDefaultImpl(unsafety, ref trait_ref) => {
             ItemDefaultImpl(unsafety, folder.fold_trait_ref((*trait_ref).clone()))
         }
@@ -806,6 +820,9 @@ pub fn noop_fold_item_underscore<T: Folder>(i: Item_, folder: &mut T) -> Item_ {
             let items = items.move_map(|item| folder.fold_trait_item(item));
             ItemTrait(unsafety, folder.fold_generics(generics), bounds, items)
         }
+        ItemTraitAlias(generics, bounds) => {
+            ItemTraitAlias(folder.fold_generics(generics), folder.fold_bounds(bounds))
+        }
     }
 }
 
@@ -824,8 +841,9 @@ pub fn noop_fold_trait_item<T: Folder>(i: TraitItem,
                 MethodTraitItem(noop_fold_method_sig(sig, folder),
                                 body.map(|x| folder.fold_block(x)))
             }
-            TypeTraitItem(bounds, default) => {
-                TypeTraitItem(folder.fold_bounds(bounds),
+            TypeTraitItem(generics, bounds, default) => {
+                TypeTraitItem(folder.fold_generics(generics),
+                              folder.fold_bounds(bounds),
                               default.map(|x| folder.fold_ty(x)))
             }
         },
@@ -839,6 +857,7 @@ pub fn noop_fold_impl_item<T: Folder>(i: ImplItem, folder: &mut T) -> ImplItem {
         name: folder.fold_name(i.name),
         attrs: fold_attrs(i.attrs, folder),
         vis: i.vis,
+        defaultness: i.defaultness,
         node: match i.node {
             ImplItemKind::Const(ty, expr) => {
                 ImplItemKind::Const(folder.fold_ty(ty), folder.fold_expr(expr))
@@ -860,7 +879,7 @@ pub fn noop_fold_mod<T: Folder>(Mod { inner, item_ids }: Mod, folder: &mut T) ->
 }
 
 pub fn noop_fold_crate<T: Folder>(Crate { module, attrs, config, span,
-                                          exported_macros, items }: Crate,
+                                          exported_macros, items, desugarings }: Crate,
                                   folder: &mut T)
                                   -> Crate {
     let config = folder.fold_meta_items(config);
@@ -887,6 +906,10 @@ pub fn noop_fold_crate<T: Folder>(Crate { module, attrs, config, span,
                      .map(|(id, item)| (id, folder.fold_item(item)))
                      .collect();
 
+    let desugarings = desugarings.into_iter()
+                                 .map(|(id, kind)| (folder.map_id(id), kind))
+                                 .collect();
+
     Crate {
         module: module,
         attrs: attrs,
@@ -894,6 +917,7 @@ pub fn noop_fold_crate<T: Folder>(Crate { module, attrs, config, span,
         span: span,
         exported_macros: exported_macros,
         items: items,
+        desugarings: desugarings,
     }
 }
 
@@ -207,6 +207,7 @@ impl PathParameters {
         AngleBracketedParameters(AngleBracketedParameterData {
             lifetimes: HirVec::new(),
             types: HirVec::new(),
+            consts: HirVec::new(),
             bindings: HirVec::new(),
         })
     }
@@ -281,6 +282,9 @@ pub struct AngleBracketedParameterData {
     pub lifetimes: HirVec<Lifetime>,
     /// The type parameters for this path segment, if present.
     pub types: HirVec<P<Ty>>,
+    /// The values of const parameters for this path segment, if present.
+    /// E.g., the `3` and `{N}` of `Grid<3, {N}>`.
+    pub consts: HirVec<P<Expr>>,
     /// Bindings (equality constraints) on associated types, if present.
     /// E.g., `Foo<A=Bar>`.
     pub bindings: HirVec<TypeBinding>,
@@ -288,7 +292,8 @@ pub struct AngleBracketedParameterData {
 
 impl AngleBracketedParameterData {
     fn is_empty(&self) -> bool {
-        self.lifetimes.is_empty() && self.types.is_empty() && self.bindings.is_empty()
+        self.lifetimes.is_empty() && self.types.is_empty() && self.consts.is_empty() &&
+            self.bindings.is_empty()
     }
 }
 
@@ -334,12 +339,22 @@ pub struct TyParam {
     pub span: Span,
 }
 
-/// Represents lifetimes and type parameters attached to a declaration
-/// of a function, enum, trait, etc.
+/// A const parameter, `const N: usize`
+#[derive(Clone, PartialEq, Eq, RustcEncodable, RustcDecodable, Hash, Debug)]
+pub struct ConstParam {
+    pub name: Name,
+    pub id: NodeId,
+    pub ty: P<Ty>,
+    pub span: Span,
+}
+
+/// Represents lifetimes, type parameters and const parameters attached to a
+/// declaration of a function, enum, trait, etc.
 #[derive(Clone, PartialEq, Eq, RustcEncodable, RustcDecodable, Hash, Debug)]
 pub struct Generics {
     pub lifetimes: HirVec<LifetimeDef>,
     pub ty_params: HirVec<TyParam>,
+    pub const_params: HirVec<ConstParam>,
     pub where_clause: WhereClause,
 }
 
@@ -347,8 +362,10 @@ impl Generics {
     pub fn is_lt_parameterized(&self) -> bool {
         !self.lifetimes.is_empty()
     }
+    /// Const parameters count as well: like type parameters, they have to
+    /// be instantiated before the item can be translated.
     pub fn is_type_parameterized(&self) -> bool {
-        !self.ty_params.is_empty()
+        !self.ty_params.is_empty() || !self.const_params.is_empty()
     }
     pub fn is_parameterized(&self) -> bool {
         self.is_lt_parameterized() || self.is_type_parameterized()
@@ -393,13 +410,12 @@ pub struct WhereRegionPredicate {
     pub bounds: HirVec<Lifetime>,
 }
 
-/// An equality predicate (unsupported), e.g. `T=int`
+/// An equality predicate, e.g. `T::Item == u32`
 #[derive(Clone, PartialEq, Eq, RustcEncodable, RustcDecodable, Hash, Debug)]
 pub struct WhereEqPredicate {
-    pub id: NodeId,
     pub span: Span,
-    pub path: Path,
-    pub ty: P<Ty>,
+    pub lhs_ty: P<Ty>,
+    pub rhs_ty: P<Ty>,
 }
 
 pub type CrateConfig = HirVec<P<MetaItem>>;
@@ -419,6 +435,11 @@ pub struct Crate {
     // detected, which in turn can make compile-fail tests yield
     // slightly different results.
     pub items: BTreeMap<NodeId, Item>,
+
+    /// The nodes that lowering made up while desugaring some construct,
+    /// e.g. the `match` and `loop` a `for` loop turns into. Nodes lowered
+    /// from the user's own code inside the construct aren't included.
+    pub desugarings: BTreeMap<NodeId, DesugaringKind>,
 }
 
 impl Crate {
@@ -426,6 +447,12 @@ impl Crate {
         &self.items[&id]
     }
 
+    /// If `id` is a node that lowering generated while desugaring some
+    /// construct, the kind of that construct.
+    pub fn desugaring_kind(&self, id: NodeId) -> Option<DesugaringKind> {
+        self.desugarings.get(&id).cloned()
+    }
+
     /// Visits all items in the crate in some determinstic (but
     /// unspecified) order. If you just need to process every item,
     /// but don't care about nesting, this method is the best choice.
@@ -838,6 +865,38 @@ pub enum MatchSource {
     TryDesugar,
 }
 
+/// A construct that lowering replaces with more basic HIR.
+#[derive(Clone, PartialEq, Eq, RustcEncodable, RustcDecodable, Hash, Debug, Copy)]
+pub enum DesugaringKind {
+    /// `if let PAT = EXPR { .. }`
+    IfLet,
+    /// `while let PAT = EXPR { .. }`
+    WhileLet,
+    /// `for PAT in EXPR { .. }`
+    ForLoop,
+    /// `EXPR?`
+    Try,
+    /// `in PLACE { .. }`
+    InPlace,
+    /// `a..b`, `a...b` and friends
+    Range,
+}
+
+impl DesugaringKind {
+    /// Names the construct for diagnostics, e.g. "in this `for` loop
+    /// desugaring".
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            DesugaringKind::IfLet => "`if let`",
+            DesugaringKind::WhileLet => "`while let`",
+            DesugaringKind::ForLoop => "`for` loop",
+            DesugaringKind::Try => "`?`",
+            DesugaringKind::InPlace => "`in` place",
+            DesugaringKind::Range => "range",
+        }
+    }
+}
+
 #[derive(Clone, PartialEq, Eq, RustcEncodable, RustcDecodable, Hash, Debug, Copy)]
 pub enum CaptureClause {
     CaptureByValue,
@@ -881,7 +940,7 @@ pub struct TraitItem {
 pub enum TraitItem_ {
     ConstTraitItem(P<Ty>, Option<P<Expr>>),
     MethodTraitItem(MethodSig, Option<P<Block>>),
-    TypeTraitItem(TyParamBounds, Option<P<Ty>>),
+    TypeTraitItem(Generics, TyParamBounds, Option<P<Ty>>),
 }
 
 #[derive(Clone, PartialEq, Eq, RustcEncodable, RustcDecodable, Hash, Debug)]
@@ -889,6 +948,7 @@ pub struct ImplItem {
     pub id: NodeId,
     pub name: Name,
     pub vis: Visibility,
+    pub defaultness: Defaultness,
     pub attrs: HirVec<Attribute>,
     pub node: ImplItemKind,
     pub span: Span,
@@ -967,6 +1027,9 @@ pub enum Ty_ {
     TyObjectSum(P<Ty>, TyParamBounds),
     /// A type like `for<'a> Foo<&'a Bar>`
     TyPolyTraitRef(TyParamBounds),
+    /// An `impl Bound1 + Bound2 + Bound3` type
+    /// where `Bound` is a trait or a lifetime.
+    TyImplTrait(TyParamBounds),
     /// Unused for now
     TyTypeof(P<Expr>),
     /// TyInfer means the type should be inferred instead of it having been
@@ -1046,6 +1109,19 @@ pub enum Constness {
     NotConst,
 }
 
+/// Whether an impl item may be overridden by a more specialized impl.
+#[derive(Copy, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable, Hash, Debug)]
+pub enum Defaultness {
+    Default,
+    Final,
+}
+
+impl Defaultness {
+    pub fn is_default(&self) -> bool {
+        *self == Defaultness::Default
+    }
+}
+
 impl fmt::Display for Unsafety {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         fmt::Display::fmt(match *self {
@@ -1141,6 +1217,8 @@ pub struct Variant_ {
     pub data: VariantData,
     /// Explicit discriminant, eg `Foo = 1`
     pub disr_expr: Option<P<Expr>>,
+    /// Bounds that only hold for this variant, eg `Foo(T) where T: Copy`
+    pub where_clause: WhereClause,
 }
 
 pub type Variant = Spanned<Variant_>;
@@ -1358,8 +1436,12 @@ pub enum Item_ {
     ItemEnum(EnumDef, Generics),
     /// A struct definition, e.g. `struct Foo<A> {x: A}`
     ItemStruct(VariantData, Generics),
+    /// A union definition, e.g. `union Foo<A, B> {x: A, y: B}`
+    ItemUnion(VariantData, Generics),
     /// Represents a Trait Declaration
     ItemTrait(Unsafety, Generics, TyParamBounds, HirVec<TraitItem>),
+    /// A trait alias, e.g. `trait Foo = Bar + Send;`
+    ItemTraitAlias(Generics, TyParamBounds),
 
     // Default trait implementations
     ///
@@ -1387,7 +1469,9 @@ impl Item_ {
             ItemTy(..) => "type alias",
             ItemEnum(..) => "enum",
             ItemStruct(..) => "struct",
+            ItemUnion(..) => "union",
             ItemTrait(..) => "trait",
+            ItemTraitAlias(..) => "trait alias",
             ItemImpl(..) |
             ItemDefaultImpl(..) => "item",
         }
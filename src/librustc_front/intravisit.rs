@@ -339,7 +339,8 @@ pub fn walk_item<'v, V: Visitor<'v>>(visitor: &mut V, item: &'v Item) {
             visitor.visit_ty(typ);
             walk_list!(visitor, visit_impl_item, impl_items);
         }
-        ItemStruct(ref struct_definition, ref generics) => {
+        ItemStruct(ref struct_definition, ref generics) |
+        ItemUnion(ref struct_definition, ref generics) => {
             visitor.visit_generics(generics);
             visitor.visit_variant_data(struct_definition, item.name, generics, item.id, item.span);
         }
@@ -348,6 +349,10 @@ pub fn walk_item<'v, V: Visitor<'v>>(visitor: &mut V, item: &'v Item) {
             walk_list!(visitor, visit_ty_param_bound, bounds);
             walk_list!(visitor, visit_trait_item, methods);
         }
+        ItemTraitAlias(ref generics, ref bounds) => {
+            visitor.visit_generics(generics);
+            walk_list!(visitor, visit_ty_param_bound, bounds);
+        }
     }
     walk_list!(visitor, visit_attribute, &item.attrs);
 }
@@ -374,6 +379,7 @@ pub fn walk_variant<'v, V: Visitor<'v>>(visitor: &mut V,
                                item_id,
                                variant.span);
     walk_list!(visitor, visit_expr, &variant.node.disr_expr);
+    walk_where_clause(visitor, &variant.node.where_clause);
     walk_list!(visitor, visit_attribute, &variant.node.attrs);
 }
 
@@ -413,6 +419,9 @@ pub fn walk_ty<'v, V: Visitor<'v>>(visitor: &mut V, typ: &'v Ty) {
         TyPolyTraitRef(ref bounds) => {
             walk_list!(visitor, visit_ty_param_bound, bounds);
         }
+        TyImplTrait(ref bounds) => {
+            walk_list!(visitor, visit_ty_param_bound, bounds);
+        }
         TyTypeof(ref expression) => {
             visitor.visit_expr(expression)
         }
@@ -444,6 +453,9 @@ pub fn walk_path_segment<'v, V: Visitor<'v>>(visitor: &mut V,
     visitor.visit_path_parameters(path_span, &segment.parameters);
 }
 
+/// Const arguments are not walked: they are evaluated when the path is
+/// converted to a type and are not expressions of the enclosing body, so
+/// visitors that need them (resolve, the HIR map) walk them explicitly.
 pub fn walk_path_parameters<'v, V: Visitor<'v>>(visitor: &mut V,
                                                 _path_span: Span,
                                                 path_parameters: &'v PathParameters) {
@@ -544,8 +556,16 @@ pub fn walk_generics<'v, V: Visitor<'v>>(visitor: &mut V, generics: &'v Generics
         walk_list!(visitor, visit_ty_param_bound, &param.bounds);
         walk_list!(visitor, visit_ty, &param.default);
     }
+    for param in &generics.const_params {
+        visitor.visit_name(param.span, param.name);
+        visitor.visit_ty(&param.ty);
+    }
     walk_list!(visitor, visit_lifetime_def, &generics.lifetimes);
-    for predicate in &generics.where_clause.predicates {
+    walk_where_clause(visitor, &generics.where_clause);
+}
+
+pub fn walk_where_clause<'v, V: Visitor<'v>>(visitor: &mut V, where_clause: &'v WhereClause) {
+    for predicate in &where_clause.predicates {
         match predicate {
             &WherePredicate::BoundPredicate(WhereBoundPredicate{ref bounded_ty,
                                                                           ref bounds,
@@ -561,12 +581,11 @@ pub fn walk_generics<'v, V: Visitor<'v>>(visitor: &mut V, generics: &'v Generics
                 visitor.visit_lifetime(lifetime);
                 walk_list!(visitor, visit_lifetime, bounds);
             }
-            &WherePredicate::EqPredicate(WhereEqPredicate{id,
-                                                                    ref path,
-                                                                    ref ty,
-                                                                    ..}) => {
-                visitor.visit_path(path, id);
-                visitor.visit_ty(ty);
+            &WherePredicate::EqPredicate(WhereEqPredicate{ref lhs_ty,
+                                                          ref rhs_ty,
+                                                          ..}) => {
+                visitor.visit_ty(lhs_ty);
+                visitor.visit_ty(rhs_ty);
             }
         }
     }
@@ -636,7 +655,8 @@ pub fn walk_trait_item<'v, V: Visitor<'v>>(visitor: &mut V, trait_item: &'v Trai
                              trait_item.span,
                              trait_item.id);
         }
-        TypeTraitItem(ref bounds, ref default) => {
+        TypeTraitItem(ref generics, ref bounds, ref default) => {
+            visitor.visit_generics(generics);
             walk_list!(visitor, visit_ty_param_bound, bounds);
             walk_list!(visitor, visit_ty, default);
         }
@@ -36,12 +36,7 @@
 
 extern crate serialize;
 #[macro_use]
-extern crate log;
-#[macro_use]
 extern crate syntax;
-#[macro_use]
-#[no_link]
-extern crate rustc_bitflags;
 
 extern crate serialize as rustc_serialize; // used by deriving
 
@@ -91,9 +91,13 @@ pub struct LoweringContext<'a> {
     // A copy of cached_id, but is also set to an id while a node is lowered for
     // the first time.
     gensym_key: Cell<u32>,
+    // The construct being desugared, if any; ids handed out for the nodes it
+    // is replaced with are recorded in `desugarings`.
+    desugaring: Cell<Option<hir::DesugaringKind>>,
+    desugarings: RefCell<BTreeMap<NodeId, hir::DesugaringKind>>,
 }
 
-impl<'a, 'hir> LoweringContext<'a> {
+impl<'a> LoweringContext<'a> {
     pub fn new(id_assigner: &'a NodeIdAssigner, c: Option<&Crate>) -> LoweringContext<'a> {
         let crate_root = c.and_then(|c| {
             if std_inject::no_core(c) {
@@ -112,17 +116,24 @@ impl<'a, 'hir> LoweringContext<'a> {
             cached_id: Cell::new(0),
             gensym_cache: RefCell::new(HashMap::new()),
             gensym_key: Cell::new(0),
+            desugaring: Cell::new(None),
+            desugarings: RefCell::new(BTreeMap::new()),
         }
     }
 
     fn next_id(&self) -> NodeId {
         let cached_id = self.cached_id.get();
-        if cached_id == 0 {
-            return self.id_assigner.next_node_id();
-        }
+        let id = if cached_id == 0 {
+            self.id_assigner.next_node_id()
+        } else {
+            self.cached_id.set(cached_id + 1);
+            cached_id
+        };
 
-        self.cached_id.set(cached_id + 1);
-        cached_id
+        if let Some(kind) = self.desugaring.get() {
+            self.desugarings.borrow_mut().insert(id, kind);
+        }
+        id
     }
 
     fn str_to_ident(&self, s: &'static str) -> hir::Ident {
@@ -142,8 +153,14 @@ impl<'a, 'hir> LoweringContext<'a> {
     }
 }
 
-// Utility fn for setting and unsetting the cached id.
-fn cache_ids<'a, OP, R>(lctx: &LoweringContext, expr_id: NodeId, op: OP) -> R
+// Utility fn for setting and unsetting the cached id. Every desugaring goes
+// through here, so this is also where we note which construct the nodes
+// created by `op` come from.
+fn cache_ids<OP, R>(lctx: &LoweringContext,
+                    expr_id: NodeId,
+                    kind: hir::DesugaringKind,
+                    op: OP)
+                    -> R
     where OP: FnOnce(&LoweringContext) -> R
 {
     // Only reset the id if it was previously 0, i.e., was not cached.
@@ -153,6 +170,8 @@ fn cache_ids<'a, OP, R>(lctx: &LoweringContext, expr_id: NodeId, op: OP) -> R
     // We always reset gensym_key so that if we use the same name in a nested
     // node and after that node, they get different values.
     let old_gensym_key = lctx.gensym_key.get();
+    let old_desugaring = lctx.desugaring.get();
+    lctx.desugaring.set(Some(kind));
 
     {
         let id_cache: &mut HashMap<_, _> = &mut lctx.id_cache.borrow_mut();
@@ -185,6 +204,7 @@ fn cache_ids<'a, OP, R>(lctx: &LoweringContext, expr_id: NodeId, op: OP) -> R
         lctx.cached_id.set(0);
     }
     lctx.gensym_key.set(old_gensym_key);
+    lctx.desugaring.set(old_desugaring);
 
     result
 }
@@ -312,6 +332,9 @@ pub fn lower_ty(lctx: &LoweringContext, t: &Ty) -> P<hir::Ty> {
             PolyTraitRef(ref bounds) => {
                 hir::TyPolyTraitRef(bounds.iter().map(|b| lower_ty_param_bound(lctx, b)).collect())
             }
+            ImplTrait(ref bounds) => {
+                hir::TyImplTrait(bounds.iter().map(|b| lower_ty_param_bound(lctx, b)).collect())
+            }
             Mac(_) => panic!("TyMac should have been expanded by now."),
         },
         span: t.span,
@@ -332,6 +355,7 @@ pub fn lower_variant(lctx: &LoweringContext, v: &Variant) -> hir::Variant {
             attrs: lower_attrs(lctx, &v.node.attrs),
             data: lower_variant_data(lctx, &v.node.data),
             disr_expr: v.node.disr_expr.as_ref().map(|e| lower_expr(lctx, e)),
+            where_clause: lower_where_clause(lctx, &v.node.where_clause),
         },
         span: v.span,
     }
@@ -379,10 +403,11 @@ pub fn lower_path_parameters(lctx: &LoweringContext,
 pub fn lower_angle_bracketed_parameter_data(lctx: &LoweringContext,
                                             data: &AngleBracketedParameterData)
                                             -> hir::AngleBracketedParameterData {
-    let &AngleBracketedParameterData { ref lifetimes, ref types, ref bindings } = data;
+    let &AngleBracketedParameterData { ref lifetimes, ref types, ref consts, ref bindings } = data;
     hir::AngleBracketedParameterData {
         lifetimes: lower_lifetimes(lctx, lifetimes),
         types: types.iter().map(|ty| lower_ty(lctx, ty)).collect(),
+        consts: consts.iter().map(|e| lower_expr(lctx, e)).collect(),
         bindings: bindings.iter().map(|b| lower_ty_binding(lctx, b)).collect(),
     }
 }
@@ -522,6 +547,14 @@ pub fn lower_opt_lifetime(lctx: &LoweringContext,
 pub fn lower_generics(lctx: &LoweringContext, g: &Generics) -> hir::Generics {
     hir::Generics {
         ty_params: lower_ty_params(lctx, &g.ty_params),
+        const_params: g.const_params.iter().map(|p| {
+            hir::ConstParam {
+                name: p.ident.name,
+                id: p.id,
+                ty: lower_ty(lctx, &p.ty),
+                span: p.span,
+            }
+        }).collect(),
         lifetimes: lower_lifetime_defs(lctx, &g.lifetimes),
         where_clause: lower_where_clause(lctx, &g.where_clause),
     }
@@ -561,14 +594,12 @@ pub fn lower_where_predicate(lctx: &LoweringContext,
                 bounds: bounds.iter().map(|bound| lower_lifetime(lctx, bound)).collect(),
             })
         }
-        WherePredicate::EqPredicate(WhereEqPredicate{ id,
-                                                      ref path,
-                                                      ref ty,
+        WherePredicate::EqPredicate(WhereEqPredicate{ ref lhs_ty,
+                                                      ref rhs_ty,
                                                       span}) => {
             hir::WherePredicate::EqPredicate(hir::WhereEqPredicate {
-                id: id,
-                path: lower_path(lctx, path),
-                ty: lower_ty(lctx, ty),
+                lhs_ty: lower_ty(lctx, lhs_ty),
+                rhs_ty: lower_ty(lctx, rhs_ty),
                 span: span,
             })
         }
@@ -699,6 +730,10 @@ pub fn lower_item_kind(lctx: &LoweringContext, i: &ItemKind) -> hir::Item_ {
             let struct_def = lower_variant_data(lctx, struct_def);
             hir::ItemStruct(struct_def, lower_generics(lctx, generics))
         }
+        ItemKind::Union(ref struct_def, ref generics) => {
+            let struct_def = lower_variant_data(lctx, struct_def);
+            hir::ItemUnion(struct_def, lower_generics(lctx, generics))
+        }
         ItemKind::DefaultImpl(unsafety, ref trait_ref) => {
             hir::ItemDefaultImpl(lower_unsafety(lctx, unsafety),
                                  lower_trait_ref(lctx, trait_ref))
@@ -723,6 +758,9 @@ pub fn lower_item_kind(lctx: &LoweringContext, i: &ItemKind) -> hir::Item_ {
                            bounds,
                            items)
         }
+        ItemKind::TraitAlias(ref generics, ref bounds) => {
+            hir::ItemTraitAlias(lower_generics(lctx, generics), lower_bounds(lctx, bounds))
+        }
         ItemKind::Mac(_) => panic!("Shouldn't still be around"),
     }
 }
@@ -741,8 +779,9 @@ pub fn lower_trait_item(lctx: &LoweringContext, i: &TraitItem) -> hir::TraitItem
                 hir::MethodTraitItem(lower_method_sig(lctx, sig),
                                      body.as_ref().map(|x| lower_block(lctx, x)))
             }
-            TraitItemKind::Type(ref bounds, ref default) => {
-                hir::TypeTraitItem(lower_bounds(lctx, bounds),
+            TraitItemKind::Type(ref generics, ref bounds, ref default) => {
+                hir::TypeTraitItem(lower_generics(lctx, generics),
+                                   lower_bounds(lctx, bounds),
                                    default.as_ref().map(|x| lower_ty(lctx, x)))
             }
         },
@@ -756,6 +795,7 @@ pub fn lower_impl_item(lctx: &LoweringContext, i: &ImplItem) -> hir::ImplItem {
         name: i.ident.name,
         attrs: lower_attrs(lctx, &i.attrs),
         vis: lower_visibility(lctx, i.vis),
+        defaultness: lower_defaultness(lctx, i.defaultness),
         node: match i.node {
             ImplItemKind::Const(ref ty, ref expr) => {
                 hir::ImplItemKind::Const(lower_ty(lctx, ty), lower_expr(lctx, expr))
@@ -803,6 +843,7 @@ pub fn lower_crate(lctx: &LoweringContext, c: &Crate) -> hir::Crate {
         span: c.span,
         exported_macros: c.exported_macros.iter().map(|m| lower_macro_def(lctx, m)).collect(),
         items: items,
+        desugarings: lctx.desugarings.borrow().clone(),
     }
 }
 
@@ -880,6 +921,13 @@ pub fn lower_constness(_lctx: &LoweringContext, c: Constness) -> hir::Constness
     }
 }
 
+pub fn lower_defaultness(_lctx: &LoweringContext, d: Defaultness) -> hir::Defaultness {
+    match d {
+        Defaultness::Default => hir::Defaultness::Default,
+        Defaultness::Final => hir::Defaultness::Final,
+    }
+}
+
 pub fn lower_unop(_lctx: &LoweringContext, u: UnOp) -> hir::UnOp {
     match u {
         UnOp::Deref => hir::UnDeref,
@@ -1012,7 +1060,7 @@ pub fn lower_expr(lctx: &LoweringContext, e: &Expr) -> P<hir::Expr> {
                 //     std::intrinsics::move_val_init(raw_place, pop_unsafe!( EXPR ));
                 //     InPlace::finalize(place)
                 // })
-                return cache_ids(lctx, e.id, |lctx| {
+                return cache_ids(lctx, e.id, hir::DesugaringKind::InPlace, |lctx| {
                     let placer_expr = lower_expr(lctx, placer);
                     let value_expr = lower_expr(lctx, value_expr);
 
@@ -1161,7 +1209,7 @@ pub fn lower_expr(lctx: &LoweringContext, e: &Expr) -> P<hir::Expr> {
                 let else_opt = else_opt.as_ref().map(|els| {
                     match els.node {
                         ExprKind::IfLet(..) => {
-                            cache_ids(lctx, e.id, |lctx| {
+                            cache_ids(lctx, e.id, hir::DesugaringKind::IfLet, |lctx| {
                                 // wrap the if-let expr in a block
                                 let span = els.span;
                                 let els = lower_expr(lctx, els);
@@ -1260,7 +1308,7 @@ pub fn lower_expr(lctx: &LoweringContext, e: &Expr) -> P<hir::Expr> {
                                       None)
                 }
 
-                return cache_ids(lctx, e.id, |lctx| {
+                return cache_ids(lctx, e.id, hir::DesugaringKind::Range, |lctx| {
                     use syntax::ast::RangeLimits::*;
 
                     match (e1, e2, lims) {
@@ -1366,7 +1414,7 @@ pub fn lower_expr(lctx: &LoweringContext, e: &Expr) -> P<hir::Expr> {
                 //     _ => [<else_opt> | ()]
                 //   }
 
-                return cache_ids(lctx, e.id, |lctx| {
+                return cache_ids(lctx, e.id, hir::DesugaringKind::IfLet, |lctx| {
                     // `<pat> => <body>`
                     let pat_arm = {
                         let body = lower_block(lctx, body);
@@ -1455,7 +1503,7 @@ pub fn lower_expr(lctx: &LoweringContext, e: &Expr) -> P<hir::Expr> {
                 //     }
                 //   }
 
-                return cache_ids(lctx, e.id, |lctx| {
+                return cache_ids(lctx, e.id, hir::DesugaringKind::WhileLet, |lctx| {
                     // `<pat> => <body>`
                     let pat_arm = {
                         let body = lower_block(lctx, body);
@@ -1508,7 +1556,7 @@ pub fn lower_expr(lctx: &LoweringContext, e: &Expr) -> P<hir::Expr> {
                 //     result
                 //   }
 
-                return cache_ids(lctx, e.id, |lctx| {
+                return cache_ids(lctx, e.id, hir::DesugaringKind::ForLoop, |lctx| {
                     // expand <head>
                     let head = lower_expr(lctx, head);
 
@@ -1594,9 +1642,10 @@ pub fn lower_expr(lctx: &LoweringContext, e: &Expr) -> P<hir::Expr> {
                                                 hir::MatchSource::ForLoopDesugar,
                                                 None);
 
-                    // `{ let _result = ...; _result }`
-                    // underscore prevents an unused_variables lint if the head diverges
-                    let result_ident = lctx.str_to_ident("_result");
+                    // `{ let result = ...; result }`
+                    // no unused_variables lint if the head diverges, as the
+                    // binding is recorded as part of the desugaring
+                    let result_ident = lctx.str_to_ident("result");
                     let let_stmt = stmt_let(lctx, e.span, false, result_ident, match_expr, None);
                     let result = expr_ident(lctx, e.span, result_ident, None);
                     let block = block_all(lctx, e.span, hir_vec![let_stmt], Some(result));
@@ -1619,7 +1668,7 @@ pub fn lower_expr(lctx: &LoweringContext, e: &Expr) -> P<hir::Expr> {
                 //     }
                 // }
 
-                return cache_ids(lctx, e.id, |lctx| {
+                return cache_ids(lctx, e.id, hir::DesugaringKind::Try, |lctx| {
                     // expand <expr>
                     let sub_expr = lower_expr(lctx, sub_expr);
 
@@ -1976,6 +2025,7 @@ fn path_all(sp: Span,
         parameters: hir::AngleBracketedParameters(hir::AngleBracketedParameterData {
             lifetimes: lifetimes,
             types: types,
+            consts: hir::HirVec::new(),
             bindings: bindings,
         }),
     });
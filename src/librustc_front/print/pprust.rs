@@ -520,6 +520,7 @@ impl<'a> State<'a> {
                 let generics = hir::Generics {
                     lifetimes: f.lifetimes.clone(),
                     ty_params: hir::HirVec::new(),
+                    const_params: hir::HirVec::new(),
                     where_clause: hir::WhereClause {
                         id: ast::DUMMY_NODE_ID,
                         predicates: hir::HirVec::new(),
@@ -540,6 +541,9 @@ impl<'a> State<'a> {
             hir::TyPolyTraitRef(ref bounds) => {
                 try!(self.print_bounds("", &bounds[..]));
             }
+            hir::TyImplTrait(ref bounds) => {
+                try!(self.print_bounds("impl", &bounds[..]));
+            }
             hir::TyFixedLengthVec(ref ty, ref v) => {
                 try!(word(&mut self.s, "["));
                 try!(self.print_type(&ty));
@@ -614,14 +618,21 @@ impl<'a> State<'a> {
 
     fn print_associated_type(&mut self,
                              name: ast::Name,
+                             generics: Option<&hir::Generics>,
                              bounds: Option<&hir::TyParamBounds>,
                              ty: Option<&hir::Ty>)
                              -> io::Result<()> {
         try!(self.word_space("type"));
         try!(self.print_name(name));
+        if let Some(generics) = generics {
+            try!(self.print_generics(generics));
+        }
         if let Some(bounds) = bounds {
             try!(self.print_bounds(":", bounds));
         }
+        if let Some(generics) = generics {
+            try!(self.print_where_clause(&generics.where_clause));
+        }
         if let Some(ty) = ty {
             try!(space(&mut self.s));
             try!(self.word_space("="));
@@ -751,6 +762,10 @@ impl<'a> State<'a> {
                 try!(self.head(&visibility_qualified(item.vis, "struct")));
                 try!(self.print_struct(struct_def, generics, item.name, item.span, true));
             }
+            hir::ItemUnion(ref struct_def, ref generics) => {
+                try!(self.head(&visibility_qualified(item.vis, "union")));
+                try!(self.print_struct(struct_def, generics, item.name, item.span, true));
+            }
 
             hir::ItemDefaultImpl(unsafety, ref trait_ref) => {
                 try!(self.head(""));
@@ -833,6 +848,16 @@ impl<'a> State<'a> {
                 }
                 try!(self.bclose(item.span));
             }
+            hir::ItemTraitAlias(ref generics, ref bounds) => {
+                try!(self.head(&visibility_qualified(item.vis, "trait")));
+                try!(self.print_name(item.name));
+                try!(self.print_generics(generics));
+                try!(self.print_bounds(" =", &bounds[..]));
+                try!(self.print_where_clause(&generics.where_clause));
+                try!(word(&mut self.s, ";"));
+                try!(self.end()); // end inner head-block
+                try!(self.end()); // end outer head-block
+            }
         }
         self.ann.post(self, NodeItem(item))
     }
@@ -952,6 +977,7 @@ impl<'a> State<'a> {
         try!(self.head(""));
         let generics = ::util::empty_generics();
         try!(self.print_struct(&v.node.data, &generics, v.node.name, v.span, false));
+        try!(self.print_where_clause(&v.node.where_clause));
         match v.node.disr_expr {
             Some(ref d) => {
                 try!(space(&mut self.s));
@@ -1000,8 +1026,9 @@ impl<'a> State<'a> {
                     try!(word(&mut self.s, ";"));
                 }
             }
-            hir::TypeTraitItem(ref bounds, ref default) => {
+            hir::TypeTraitItem(ref generics, ref bounds, ref default) => {
                 try!(self.print_associated_type(ti.name,
+                                                Some(generics),
                                                 Some(bounds),
                                                 default.as_ref().map(|ty| &**ty)));
             }
@@ -1014,18 +1041,26 @@ impl<'a> State<'a> {
         try!(self.hardbreak_if_not_bol());
         try!(self.maybe_print_comment(ii.span.lo));
         try!(self.print_outer_attributes(&ii.attrs));
+        // `default` goes between the visibility and the rest of the item.
+        let vis = if ii.defaultness.is_default() {
+            try!(word(&mut self.s, &visibility_qualified(ii.vis, "default")));
+            try!(self.nbsp());
+            hir::Visibility::Inherited
+        } else {
+            ii.vis
+        };
         match ii.node {
             hir::ImplItemKind::Const(ref ty, ref expr) => {
-                try!(self.print_associated_const(ii.name, &ty, Some(&expr), ii.vis));
+                try!(self.print_associated_const(ii.name, &ty, Some(&expr), vis));
             }
             hir::ImplItemKind::Method(ref sig, ref body) => {
                 try!(self.head(""));
-                try!(self.print_method_sig(ii.name, sig, ii.vis));
+                try!(self.print_method_sig(ii.name, sig, vis));
                 try!(self.nbsp());
                 try!(self.print_block_with_attrs(body, &ii.attrs));
             }
             hir::ImplItemKind::Type(ref ty) => {
-                try!(self.print_associated_type(ii.name, None, Some(ty)));
+                try!(self.print_associated_type(ii.name, None, None, Some(ty)));
             }
         }
         self.ann.post(self, NodeSubItem(ii.id))
@@ -1669,6 +1704,14 @@ impl<'a> State<'a> {
                     comma = true;
                 }
 
+                if !data.consts.is_empty() {
+                    if comma {
+                        try!(self.word_space(","))
+                    }
+                    try!(self.commasep_exprs(Inconsistent, &data.consts));
+                    comma = true;
+                }
+
                 for binding in data.bindings.iter() {
                     if comma {
                         try!(self.word_space(","))
@@ -2045,7 +2088,8 @@ impl<'a> State<'a> {
     }
 
     pub fn print_generics(&mut self, generics: &hir::Generics) -> io::Result<()> {
-        let total = generics.lifetimes.len() + generics.ty_params.len();
+        let total = generics.lifetimes.len() + generics.ty_params.len() +
+                    generics.const_params.len();
         if total == 0 {
             return Ok(());
         }
@@ -2061,10 +2105,17 @@ impl<'a> State<'a> {
             if idx < generics.lifetimes.len() {
                 let lifetime = &generics.lifetimes[idx];
                 s.print_lifetime_def(lifetime)
-            } else {
+            } else if idx < generics.lifetimes.len() + generics.ty_params.len() {
                 let idx = idx - generics.lifetimes.len();
                 let param = &generics.ty_params[idx];
                 s.print_ty_param(param)
+            } else {
+                let idx = idx - generics.lifetimes.len() - generics.ty_params.len();
+                let param = &generics.const_params[idx];
+                try!(s.word_nbsp("const"));
+                try!(s.print_name(param.name));
+                try!(s.word_space(":"));
+                s.print_type(&param.ty)
             }
         }));
 
@@ -2121,11 +2172,13 @@ impl<'a> State<'a> {
                         }
                     }
                 }
-                &hir::WherePredicate::EqPredicate(hir::WhereEqPredicate{ref path, ref ty, ..}) => {
-                    try!(self.print_path(path, false, 0));
+                &hir::WherePredicate::EqPredicate(hir::WhereEqPredicate{ref lhs_ty,
+                                                                        ref rhs_ty,
+                                                                        ..}) => {
+                    try!(self.print_type(lhs_ty));
                     try!(space(&mut self.s));
-                    try!(self.word_space("="));
-                    try!(self.print_type(&ty));
+                    try!(self.word_space("=="));
+                    try!(self.print_type(rhs_ty));
                 }
             }
         }
@@ -2246,6 +2299,7 @@ impl<'a> State<'a> {
         let generics = hir::Generics {
             lifetimes: hir::HirVec::new(),
             ty_params: hir::HirVec::new(),
+            const_params: hir::HirVec::new(),
             where_clause: hir::WhereClause {
                 id: ast::DUMMY_NODE_ID,
                 predicates: hir::HirVec::new(),
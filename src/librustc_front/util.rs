@@ -337,6 +337,7 @@ pub fn empty_generics() -> Generics {
     Generics {
         lifetimes: HirVec::new(),
         ty_params: HirVec::new(),
+        const_params: HirVec::new(),
         where_clause: WhereClause {
             id: DUMMY_NODE_ID,
             predicates: HirVec::new(),
@@ -355,6 +356,7 @@ pub fn ident_to_path(s: Span, ident: Ident) -> Path {
             parameters: hir::AngleBracketedParameters(hir::AngleBracketedParameterData {
                 lifetimes: HirVec::new(),
                 types: HirVec::new(),
+                consts: HirVec::new(),
                 bindings: HirVec::new(),
             }),
         }],
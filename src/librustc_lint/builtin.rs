@@ -32,7 +32,7 @@ use middle::{cfg, infer, stability, traits};
 use middle::def::Def;
 use middle::cstore::CrateStore;
 use middle::def_id::DefId;
-use middle::subst::Substs;
+use middle::subst::{ParamSpace, Substs};
 use middle::ty::{self, Ty, TyCtxt};
 use middle::ty::adjustment;
 use rustc::front::map as hir_map;
@@ -115,15 +115,17 @@ impl LateLintPass for BoxPointers {
             hir::ItemFn(..) |
             hir::ItemTy(..) |
             hir::ItemEnum(..) |
-            hir::ItemStruct(..) =>
+            hir::ItemStruct(..) |
+            hir::ItemUnion(..) =>
                 self.check_heap_type(cx, it.span,
                                      cx.tcx.node_id_to_type(it.id)),
             _ => ()
         }
 
-        // If it's a struct, we also have to check the fields' types
+        // If it's a struct or union, we also have to check the fields' types
         match it.node {
-            hir::ItemStruct(ref struct_def, _) => {
+            hir::ItemStruct(ref struct_def, _) |
+            hir::ItemUnion(ref struct_def, _) => {
                 for struct_field in struct_def.fields() {
                     self.check_heap_type(cx, struct_field.span,
                                          cx.tcx.node_id_to_type(struct_field.id));
@@ -361,6 +363,7 @@ impl LateLintPass for MissingDoc {
             hir::ItemMod(..) => "a module",
             hir::ItemEnum(..) => "an enum",
             hir::ItemStruct(..) => "a struct",
+            hir::ItemUnion(..) => "a union",
             hir::ItemTrait(_, _, _, ref items) => {
                 // Issue #11592, traits are always considered exported, even when private.
                 if it.vis == hir::Visibility::Inherited {
@@ -373,6 +376,7 @@ impl LateLintPass for MissingDoc {
                 "a trait"
             },
             hir::ItemTy(..) => "a type alias",
+            hir::ItemTraitAlias(..) => "a trait alias",
             hir::ItemImpl(_, _, _, Some(ref trait_ref), _, ref impl_items) => {
                 // If the trait is private, add the impl items to private_traits so they don't get
                 // reported for missing docs.
@@ -1170,3 +1174,192 @@ impl LateLintPass for DropWithReprExtern {
         })
     }
 }
+
+declare_lint! {
+    pub REDUNDANT_BOUNDS,
+    Warn,
+    "trait bounds that are duplicated or implied by another bound"
+}
+
+/// Lints trait bounds on type parameters that are written more than once,
+/// or that are already implied by the supertraits of another bound on the
+/// same parameter (e.g. the `PartialOrd` in `T: Ord + PartialOrd`).
+///
+/// This works from the predicates collect recorded for each item; the bounds
+/// as written are only consulted to find out where to point.
+pub struct RedundantBounds;
+
+impl LintPass for RedundantBounds {
+    fn get_lints(&self) -> LintArray {
+        lint_array!(REDUNDANT_BOUNDS)
+    }
+}
+
+impl LateLintPass for RedundantBounds {
+    fn check_item(&mut self, cx: &LateContext, it: &hir::Item) {
+        match it.node {
+            hir::ItemFn(_, _, _, _, ref generics, _) |
+            hir::ItemTy(_, ref generics) |
+            hir::ItemEnum(_, ref generics) |
+            hir::ItemStruct(_, ref generics) |
+            hir::ItemUnion(_, ref generics) |
+            hir::ItemTrait(_, ref generics, _, _) |
+            hir::ItemImpl(_, _, ref generics, _, _, _) => {
+                check_redundant_bounds(cx, it.id, ParamSpace::TypeSpace, generics);
+            }
+            _ => {}
+        }
+    }
+
+    fn check_trait_item(&mut self, cx: &LateContext, trait_item: &hir::TraitItem) {
+        if let hir::MethodTraitItem(ref sig, _) = trait_item.node {
+            check_redundant_bounds(cx, trait_item.id, ParamSpace::FnSpace, &sig.generics);
+        }
+    }
+
+    fn check_impl_item(&mut self, cx: &LateContext, impl_item: &hir::ImplItem) {
+        if let hir::ImplItemKind::Method(ref sig, _) = impl_item.node {
+            check_redundant_bounds(cx, impl_item.id, ParamSpace::FnSpace, &sig.generics);
+        }
+    }
+}
+
+/// A trait bound as written on a type parameter: the parameter, the trait,
+/// the span of the bound and the span to remove along with it.
+type WrittenBound = (ty::ParamTy, DefId, Span, Span);
+
+fn check_redundant_bounds(cx: &LateContext,
+                          id: ast::NodeId,
+                          space: ParamSpace,
+                          generics: &hir::Generics) {
+    let tcx = cx.tcx;
+
+    let mut written = vec![];
+    for (index, param) in generics.ty_params.iter().enumerate() {
+        let param_ty = ty::ParamTy::new(space, index as u32, param.name);
+        collect_written_bounds(tcx, param_ty, &param.bounds, None, &mut written);
+    }
+    let where_predicates = &generics.where_clause.predicates;
+    let where_span = |predicate: &hir::WherePredicate| match *predicate {
+        hir::WherePredicate::BoundPredicate(ref p) => p.span,
+        hir::WherePredicate::RegionPredicate(ref p) => p.span,
+        hir::WherePredicate::EqPredicate(ref p) => p.span,
+    };
+    for (i, predicate) in where_predicates.iter().enumerate() {
+        let bound_pred = match *predicate {
+            hir::WherePredicate::BoundPredicate(ref bound_pred) => bound_pred,
+            _ => continue,
+        };
+        // Only bounds on the type parameters themselves end up keyed by a
+        // parameter in the predicates, so those are all we can match up.
+        let def = tcx.def_map.borrow().get(&bound_pred.bounded_ty.id).map(|d| d.full_def());
+        if let Some(Def::TyParam(param_space, index, _, name)) = def {
+            let param_ty = ty::ParamTy::new(param_space, index, name);
+            // A bound that is alone in its predicate goes with the predicate
+            // and the `,` on one side of it.
+            let predicate_span = if i > 0 {
+                Span { lo: where_span(&where_predicates[i - 1]).hi, ..bound_pred.span }
+            } else if where_predicates.len() > 1 {
+                Span { hi: where_span(&where_predicates[1]).lo, ..bound_pred.span }
+            } else {
+                bound_pred.span
+            };
+            collect_written_bounds(tcx, param_ty, &bound_pred.bounds, Some(predicate_span),
+                                   &mut written);
+        }
+    }
+    if written.len() < 2 {
+        return;
+    }
+
+    // The predicates of the item's own space follow the order in which the
+    // bounds were written, per parameter and trait. Builtin bounds are the
+    // exception, as they are collected into a set, and implicit bounds can
+    // add predicates that were never written; give up on those.
+    let predicates = tcx.lookup_predicates(tcx.map.local_def_id(id));
+    let recorded = predicates.predicates.get_slice(space).iter().filter_map(|predicate| {
+        match *predicate {
+            ty::Predicate::Trait(ref data) => match data.0.self_ty().sty {
+                ty::TyParam(param_ty) => Some((param_ty, data.to_poly_trait_ref())),
+                _ => None,
+            },
+            _ => None,
+        }
+    }).collect::<Vec<_>>();
+
+    let mut bounds = vec![];
+    for (i, &(param_ty, trait_def_id, span, removal_span)) in written.iter().enumerate() {
+        let same_key = |&&(p, d, _, _): &&WrittenBound| p == param_ty && d == trait_def_id;
+        let nth = written[..i].iter().filter(&same_key).count();
+        let written_count = written.iter().filter(&same_key).count();
+        let candidates = recorded.iter().filter(|&&(p, ref trait_ref)| {
+            p == param_ty && trait_ref.def_id() == trait_def_id
+        }).collect::<Vec<_>>();
+
+        let trait_ref = if candidates.len() == written_count {
+            candidates[nth].1.clone()
+        } else if candidates.len() == 1 && tcx.lang_items.to_builtin_kind(trait_def_id).is_some() {
+            candidates[0].1.clone()
+        } else {
+            continue;
+        };
+        bounds.push((param_ty, trait_ref, span, removal_span));
+    }
+
+    for (i, &(param_ty, ref trait_ref, span, removal_span)) in bounds.iter().enumerate() {
+        for (j, &(other_param_ty, ref other_ref, _, _)) in bounds.iter().enumerate() {
+            if i == j || param_ty != other_param_ty {
+                continue;
+            }
+
+            if trait_ref == other_ref {
+                // Only report the second and later occurrences.
+                if j < i {
+                    let msg = format!("duplicate bound `{}: {}`", param_ty, trait_ref);
+                    cx.struct_span_lint(REDUNDANT_BOUNDS, span, &msg)
+                      .span_suggestion(removal_span, "remove this bound", String::new())
+                      .emit();
+                    break;
+                }
+                continue;
+            }
+
+            if traits::supertraits(tcx, other_ref.clone()).any(|t| t == *trait_ref) {
+                let msg = format!("bound `{}: {}` is implied by `{}: {}`",
+                                  param_ty, trait_ref, param_ty, other_ref);
+                cx.struct_span_lint(REDUNDANT_BOUNDS, span, &msg)
+                  .span_suggestion(removal_span, "remove this bound", String::new())
+                  .emit();
+                break;
+            }
+        }
+    }
+}
+
+fn collect_written_bounds(tcx: &TyCtxt,
+                          param_ty: ty::ParamTy,
+                          bounds: &[hir::TyParamBound],
+                          predicate_span: Option<Span>,
+                          written: &mut Vec<WrittenBound>) {
+    let bound_span = |bound: &hir::TyParamBound| match *bound {
+        hir::TraitTyParamBound(ref ptr, _) => ptr.span,
+        hir::RegionTyParamBound(ref lifetime) => lifetime.span,
+    };
+
+    for (i, bound) in bounds.iter().enumerate() {
+        let ptr = match *bound {
+            hir::TraitTyParamBound(ref ptr, hir::TraitBoundModifier::None) => ptr,
+            _ => continue,
+        };
+        // Take the ` + ` on one side along with the bound.
+        let removal_span = if i > 0 {
+            Span { lo: bound_span(&bounds[i - 1]).hi, ..ptr.span }
+        } else if bounds.len() > 1 {
+            Span { hi: bound_span(&bounds[1]).lo, ..ptr.span }
+        } else {
+            predicate_span.unwrap_or(ptr.span)
+        };
+        let trait_def_id = tcx.trait_ref_to_def_id(&ptr.trait_ref);
+        written.push((param_ty, trait_def_id, ptr.span, removal_span));
+    }
+}
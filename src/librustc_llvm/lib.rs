@@ -2139,6 +2139,12 @@ extern {
     pub fn LLVMGetDiagInfoKind(DI: DiagnosticInfoRef) -> DiagnosticKind;
 
     pub fn LLVMWriteDebugLocToString(C: ContextRef, DL: DebugLocRef, s: RustStringRef);
+    pub fn LLVMRustUnpackDebugLoc(DL: DebugLocRef,
+                                  line_out: *mut c_uint,
+                                  col_out: *mut c_uint,
+                                  file_out: RustStringRef,
+                                  dir_out: RustStringRef)
+                                  -> bool;
 
     pub fn LLVMSetInlineAsmDiagnosticHandler(C: ContextRef,
                                              H: InlineAsmDiagHandler,
@@ -2353,6 +2359,36 @@ pub unsafe fn debug_loc_to_string(c: ContextRef, tr: DebugLocRef) -> String {
         .expect("got a non-UTF8 DebugLoc from LLVM")
 }
 
+/// Where a debug location points to in the source.
+pub struct SourceLoc {
+    pub file: String,
+    pub directory: String,
+    /// 1-based.
+    pub line: usize,
+    /// 1-based, or 0 if unknown.
+    pub col: usize,
+}
+
+pub unsafe fn unpack_debug_loc(dl: DebugLocRef) -> Option<SourceLoc> {
+    let mut line = 0;
+    let mut col = 0;
+    let mut file = RefCell::new(Vec::new());
+    let mut dir = RefCell::new(Vec::new());
+    if !LLVMRustUnpackDebugLoc(dl,
+                               &mut line,
+                               &mut col,
+                               &mut file as RustStringRepr as RustStringRef,
+                               &mut dir as RustStringRepr as RustStringRef) {
+        return None;
+    }
+    Some(SourceLoc {
+        file: String::from_utf8_lossy(&file.into_inner()).into_owned(),
+        directory: String::from_utf8_lossy(&dir.into_inner()).into_owned(),
+        line: line as usize,
+        col: col as usize,
+    })
+}
+
 pub fn initialize_available_targets() {
     macro_rules! init_target(
         ($cfg:meta, $($method:ident),*) => { {
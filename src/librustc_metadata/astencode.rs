@@ -401,6 +401,9 @@ impl tr for Def {
               Def::AssociatedTy(trait_did.tr(dcx), did.tr(dcx)),
           Def::PrimTy(p) => Def::PrimTy(p),
           Def::TyParam(s, index, def_id, n) => Def::TyParam(s, index, def_id.tr(dcx), n),
+          Def::ConstParam(s, index, def_id, n) => {
+              Def::ConstParam(s, index, def_id.tr(dcx), n)
+          }
           Def::Upvar(_, nid1, index, nid2) => {
               let nid1 = dcx.tr_id(nid1);
               let nid2 = dcx.tr_id(nid2);
@@ -752,6 +755,20 @@ fn encode_side_tables_for_id(ecx: &e::EncodeContext,
         })
     }
 
+    if let Some(adjustments) = tcx.tables.borrow().pat_adjustments.get(&id) {
+        rbml_w.tag(c::tag_table_pat_adjustments, |rbml_w| {
+            rbml_w.id(id);
+            rbml_w.emit_tys(ecx, adjustments);
+        })
+    }
+
+    if let Some(mode) = tcx.tables.borrow().pat_binding_modes.get(&id) {
+        rbml_w.tag(c::tag_table_pat_binding_modes, |rbml_w| {
+            rbml_w.id(id);
+            mode.encode(rbml_w).unwrap();
+        })
+    }
+
     if let Some(item_substs) = tcx.tables.borrow().item_substs.get(&id) {
         rbml_w.tag(c::tag_table_item_subst, |rbml_w| {
             rbml_w.id(id);
@@ -1169,6 +1186,14 @@ fn decode_side_tables(dcx: &DecodeContext,
                                id,  ty);
                         dcx.tcx.node_type_insert(id, ty);
                     }
+                    c::tag_table_pat_adjustments => {
+                        let adjustments = val_dsr.read_tys(dcx);
+                        dcx.tcx.tables.borrow_mut().pat_adjustments.insert(id, adjustments);
+                    }
+                    c::tag_table_pat_binding_modes => {
+                        let mode: hir::BindingMode = Decodable::decode(val_dsr).unwrap();
+                        dcx.tcx.tables.borrow_mut().pat_binding_modes.insert(id, mode);
+                    }
                     c::tag_table_item_subst => {
                         let item_substs = ty::ItemSubsts {
                             substs: val_dsr.read_substs(dcx)
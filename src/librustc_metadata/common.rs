@@ -123,7 +123,8 @@ enum_from_u32! {
         tag_mir = 0x52,
 
         tag_table = 0x53,
-        // GAP 0x54, 0x55
+        tag_table_pat_adjustments = 0x54,
+        tag_table_pat_binding_modes = 0x55,
         tag_table_def = 0x56,
         tag_table_node_type = 0x57,
         tag_table_item_subst = 0x58,
@@ -144,6 +145,9 @@ pub const tag_item_trait_item_sort: usize = 0x70;
 
 pub const tag_crate_triple: usize = 0x105; // top-level only
 
+// The edition the crate was compiled with, as a string such as "2015"
+pub const tag_crate_edition: usize = 0x113; // top-level only
+
 pub const tag_dylib_dependency_formats: usize = 0x106; // top-level only
 
 // Language items are a top-level directory (for speed). Hierarchy:
@@ -222,9 +226,40 @@ pub const tag_polarity: usize = 0x9d;
 
 pub const tag_macro_defs: usize = 0x10e; // top-level only
 pub const tag_macro_def: usize = 0x9e;
+// The body's token trees, RBML-encoded, with spans in the codemap of the
+// defining crate.
 pub const tag_macro_def_body: usize = 0x9f;
-pub const tag_macro_def_span_lo: usize = 0xa8;
-pub const tag_macro_def_span_hi: usize = 0xa9;
+// Four bytes of inline `CompactSpan`, or eight bytes with `lo` in the upper
+// 32 bits and `hi - lo` in the lower 32 bits.
+pub const tag_macro_def_span: usize = 0xa8;
+
+pub const tag_methods_referencing_self: usize = 0xa9;
+pub const tag_method_referencing_self: usize = 0xaa;
+
+// Encoded like `tag_macro_def_span`.
+pub const tag_item_span: usize = 0xab;
+
+// Laid out like `tag_region_param_def`, with a `tag_items_data_item_type`
+// for the type of the parameter instead of bounds.
+pub const tag_const_param_def: usize = 0xac;
+
+// Present, and empty, on the item of a trait alias.
+pub const tag_trait_alias: usize = 0xad;
+
+// 'd' for a `default` impl item, 'f' otherwise; trait items, which impls may
+// always override, have none.
+pub const tag_items_data_item_defaultness: usize = 0xae;
+
+// The def-id of the impl that an impl specializes, if any.
+pub const tag_impl_parent: usize = 0xaf;
+
+// The traits marked `#[default_bound]`, as def indices.
+pub const tag_default_bound_traits: usize = 0x114; // top-level only
+pub const tag_default_bound_trait: usize = 0xb0;
+
+// Replaces `tag_macro_def_body` for a macro reexported from another crate:
+// the body's source text, since its spans can't be translated.
+pub const tag_macro_def_body_text: usize = 0xb1;
 
 pub const tag_paren_sugar: usize = 0xa0;
 
@@ -71,9 +71,10 @@ fn dump_crates(cstore: &CStore) {
         info!("  hash: {}", data.hash());
         info!("  reqd: {}", data.explicitly_linked.get());
         opt_source.map(|cs| {
-            let CrateSource { dylib, rlib, cnum: _ } = cs;
+            let CrateSource { dylib, rlib, rmeta, cnum: _ } = cs;
             dylib.map(|dl| info!("  dylib: {}", dl.0.display()));
             rlib.map(|rl|  info!("   rlib: {}", rl.0.display()));
+            rmeta.map(|rm| info!("  rmeta: {}", rm.0.display()));
         });
     })
 }
@@ -143,6 +144,14 @@ impl PMDSource {
             PMDSource::Owned(ref mdb) => mdb.as_slice(),
         }
     }
+
+    /// The crate's filemaps, imported into `codemap`.
+    fn imported_filemaps(&self, codemap: &codemap::CodeMap) -> Vec<cstore::ImportedFileMap> {
+        match *self {
+            PMDSource::Registered(ref cmd) => cmd.imported_filemaps(codemap).clone(),
+            PMDSource::Owned(ref mdb) => import_codemap(codemap, mdb),
+        }
+    }
 }
 
 impl<'a> CrateReader<'a> {
@@ -230,7 +239,8 @@ impl<'a> CrateReader<'a> {
                 let found = locs.iter().any(|l| {
                     let l = fs::canonicalize(l).ok();
                     source.dylib.as_ref().map(|p| &p.0) == l.as_ref() ||
-                    source.rlib.as_ref().map(|p| &p.0) == l.as_ref()
+                    source.rlib.as_ref().map(|p| &p.0) == l.as_ref() ||
+                    source.rmeta.as_ref().map(|p| &p.0) == l.as_ref()
                 });
                 if found {
                     ret = Some(cnum);
@@ -245,6 +255,7 @@ impl<'a> CrateReader<'a> {
             // path (this is a top-level dependency) as we don't want to
             // implicitly load anything inside the dependency lookup path.
             let prev_kind = source.dylib.as_ref().or(source.rlib.as_ref())
+                                  .or(source.rmeta.as_ref())
                                   .unwrap().1;
             if ret.is_none() && (prev_kind == kind || prev_kind == PathKind::All) {
                 ret = Some(cnum);
@@ -293,6 +304,7 @@ impl<'a> CrateReader<'a> {
                 ident: ident.to_string(),
                 dylib: lib.dylib.clone().map(|p| p.0),
                 rlib:  lib.rlib.clone().map(|p| p.0),
+                rmeta: lib.rmeta.clone().map(|p| p.0),
             })
         } else {
             None
@@ -300,7 +312,7 @@ impl<'a> CrateReader<'a> {
         // Maintain a reference to the top most crate.
         let root = if root.is_some() { root } else { &crate_paths };
 
-        let loader::Library { dylib, rlib, metadata } = lib;
+        let loader::Library { dylib, rlib, rmeta, metadata } = lib;
 
         let cnum_map = self.resolve_crate_deps(root, metadata.as_slice(), span);
         let staged_api = self.is_staged_api(metadata.as_slice());
@@ -323,6 +335,7 @@ impl<'a> CrateReader<'a> {
         let source = cstore::CrateSource {
             dylib: dylib,
             rlib: rlib,
+            rmeta: rmeta,
             cnum: cnum,
         };
 
@@ -371,6 +384,8 @@ impl<'a> CrateReader<'a> {
                     rejected_via_hash: vec!(),
                     rejected_via_triple: vec!(),
                     rejected_via_kind: vec!(),
+                    rejected_via_name: vec!(),
+                    rejected_via_metadata: vec!(),
                     should_match_name: true,
                 };
                 let library = load_ctxt.load_library_crate();
@@ -448,6 +463,8 @@ impl<'a> CrateReader<'a> {
             rejected_via_hash: vec!(),
             rejected_via_triple: vec!(),
             rejected_via_kind: vec!(),
+            rejected_via_name: vec!(),
+            rejected_via_metadata: vec!(),
             should_match_name: true,
         };
         let library = match load_ctxt.maybe_load_library_crate() {
@@ -493,26 +510,41 @@ impl<'a> CrateReader<'a> {
         let ci = self.extract_crate_info(item).unwrap();
         let ekrate = self.read_extension_crate(item.span, &ci);
 
+        // Spans in the macros point into the defining crate's sources.
+        let imported_filemaps = ekrate.metadata.imported_filemaps(self.sess.codemap());
         let source_name = format!("<{} macros>", item.ident);
         let mut macros = vec![];
         decoder::each_exported_macro(ekrate.metadata.as_slice(),
                                      &self.cstore.intr,
             |name, attrs, span, body| {
-                // NB: Don't use parse::parse_tts_from_source_str because it parses with
-                // quote_depth > 0.
-                let mut p = parse::new_parser_from_source_str(&self.sess.parse_sess,
-                                                              self.sess.opts.cfg.clone(),
-                                                              source_name.clone(),
-                                                              body);
-                let lo = p.span.lo;
-                let body = match p.parse_all_token_trees() {
-                    Ok(body) => body,
-                    Err(mut err) => {
-                        err.emit();
-                        panic!(FatalError);
+                let (body, local_span) = match body {
+                    decoder::MacroBody::Tokens(body) => {
+                        let body = decoder::translate_macro_spans(&imported_filemaps, &body);
+                        let local_span = decoder::translate_span_in(&imported_filemaps,
+                                                                    &Cell::new(0),
+                                                                    span);
+                        (body, local_span)
+                    }
+                    decoder::MacroBody::Text(body) => {
+                        // A reexported macro: its spans are those of a
+                        // synthetic `<crate macros>` filemap holding its text.
+                        // NB: Don't use parse::parse_tts_from_source_str because it
+                        // parses with quote_depth > 0.
+                        let mut p = parse::new_parser_from_source_str(&self.sess.parse_sess,
+                                                                      self.sess.opts.cfg.clone(),
+                                                                      source_name.clone(),
+                                                                      body);
+                        let lo = p.span.lo;
+                        let body = match p.parse_all_token_trees() {
+                            Ok(body) => body,
+                            Err(mut err) => {
+                                err.emit();
+                                panic!(FatalError);
+                            }
+                        };
+                        (body, mk_sp(lo, p.last_span.hi))
                     }
                 };
-                let local_span = mk_sp(lo, p.last_span.hi);
 
                 // Mark the attrs as used
                 for attr in &attrs {
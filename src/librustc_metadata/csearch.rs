@@ -31,6 +31,7 @@ use std::rc::Rc;
 use std::path::PathBuf;
 use syntax::ast;
 use syntax::attr;
+use syntax::codemap::Span;
 use syntax::parse::token;
 use rustc_back::svh::Svh;
 use rustc_back::target::Target;
@@ -157,6 +158,11 @@ impl<'tcx> CrateStore<'tcx> for cstore::CStore {
         decoder::get_item_name(&self.intr, &cdata, def.index)
     }
 
+    fn item_span(&self, tcx: &TyCtxt<'tcx>, def: DefId) -> Option<Span> {
+        let cdata = self.get_crate_data(def.krate);
+        decoder::get_item_span(&cdata, def.index, tcx.sess.codemap())
+    }
+
 
     fn inherent_implementations_for_type(&self, def_id: DefId) -> Vec<DefId>
     {
@@ -218,6 +224,11 @@ impl<'tcx> CrateStore<'tcx> for cstore::CStore {
         decoder::get_custom_coerce_unsized_kind(&cdata, def.index)
     }
 
+    fn impl_parent(&self, impl_def_id: DefId) -> Option<DefId> {
+        let cdata = self.get_crate_data(impl_def_id.krate);
+        decoder::get_impl_parent(&cdata, impl_def_id.index)
+    }
+
     // FIXME: killme
     fn associated_consts(&self, tcx: &TyCtxt<'tcx>, def: DefId)
                          -> Vec<Rc<ty::AssociatedConst<'tcx>>> {
@@ -293,6 +304,11 @@ impl<'tcx> CrateStore<'tcx> for cstore::CStore {
         decoder::get_dylib_dependency_formats(&cdata)
     }
 
+    fn crate_dependencies(&self, cnum: ast::CrateNum) -> Vec<ast::CrateNum>
+    {
+        self.do_crate_dependencies(cnum)
+    }
+
     fn lang_items(&self, cnum: ast::CrateNum) -> Vec<(DefIndex, usize)>
     {
         let mut result = vec![];
@@ -368,6 +384,18 @@ impl<'tcx> CrateStore<'tcx> for cstore::CStore {
         decoder::get_reachable_ids(&cdata)
     }
 
+    fn default_bound_traits(&self, cnum: ast::CrateNum) -> Vec<DefId>
+    {
+        let cdata = self.get_crate_data(cnum);
+        decoder::get_default_bound_traits(&cdata)
+    }
+
+    fn def_key(&self, def: DefId) -> hir_map::DefKey
+    {
+        let cdata = self.get_crate_data(def.krate);
+        decoder::get_def_key(&cdata, def.index)
+    }
+
     fn def_path(&self, def: DefId) -> hir_map::DefPath
     {
         let cdata = self.get_crate_data(def.krate);
@@ -21,6 +21,7 @@ use index;
 use loader;
 
 use rustc::back::svh::Svh;
+use rustc::middle::cstore::LOCAL_CRATE;
 use rustc::front::map as ast_map;
 use rustc::util::nodemap::{FnvHashMap, NodeMap, NodeSet};
 
@@ -48,10 +49,16 @@ pub type cnum_map = FnvHashMap<ast::CrateNum, ast::CrateNum>;
 pub enum MetadataBlob {
     MetadataVec(Bytes),
     MetadataArchive(loader::ArchiveMetadata),
+    /// The contents of a `.rmeta` file, see `--emit=metadata`.
+    MetadataFile(Vec<u8>),
+    /// A blob kept by the loader's metadata cache, see
+    /// `loader::enable_metadata_cache`.
+    MetadataShared(Rc<MetadataBlob>),
 }
 
 /// Holds information about a codemap::FileMap imported from another crate.
 /// See creader::import_codemap() for more information.
+#[derive(Clone)]
 pub struct ImportedFileMap {
     /// This FileMap's byte-offset within the codemap of its original crate
     pub original_start_pos: codemap::BytePos,
@@ -244,6 +251,14 @@ impl CStore {
     {
         self.extern_mod_crate_map.borrow().get(&emod_id).cloned()
     }
+
+    pub fn do_crate_dependencies(&self, cnum: ast::CrateNum) -> Vec<ast::CrateNum> {
+        if cnum == LOCAL_CRATE {
+            self.extern_mod_crate_map.borrow().values().cloned().collect()
+        } else {
+            self.get_crate_data(cnum).cnum_map.borrow().values().cloned().collect()
+        }
+    }
 }
 
 impl crate_metadata {
@@ -325,6 +340,8 @@ impl MetadataBlob {
         let slice = match *self {
             MetadataVec(ref vec) => &vec[..],
             MetadataArchive(ref ar) => ar.as_slice(),
+            MetadataFile(ref vec) => &vec[..],
+            MetadataShared(ref blob) => return blob.as_slice(),
         };
         if slice.len() < 4 {
             &[] // corrupt metadata
@@ -23,7 +23,7 @@ use tydecode::TyDecoder;
 
 use rustc::back::svh::Svh;
 use rustc::front::map as hir_map;
-use rustc::util::nodemap::FnvHashMap;
+use rustc::util::nodemap::{FnvHashMap, FnvHashSet};
 use rustc_front::hir;
 
 use middle::cstore::{LOCAL_CRATE, FoundAst, InlinedItem, LinkagePreference};
@@ -51,7 +51,9 @@ use syntax::attr;
 use syntax::parse::token::{self, IdentInterner};
 use syntax::ast;
 use syntax::abi::Abi;
-use syntax::codemap::{self, Span, BytePos, NO_EXPANSION};
+use syntax::codemap::{self, Span, BytePos, CompactSpan, NO_EXPANSION};
+use syntax::edition::{Edition, DEFAULT_EDITION};
+use syntax::fold::{self, Folder};
 use syntax::print::pprust;
 use syntax::ptr::P;
 
@@ -104,6 +106,7 @@ enum Family {
     DefaultImpl,           // d
     Trait,                 // I
     Struct(VariantKind),   // S, s, u
+    Union,                 // U
     PublicField,           // g
     InheritedField,        // N
     Constant,              // C
@@ -131,6 +134,7 @@ fn item_family(item: rbml::Doc) -> Family {
       'S' => Struct(VariantKind::Struct),
       's' => Struct(VariantKind::Tuple),
       'u' => Struct(VariantKind::Unit),
+      'U' => Union,
       'g' => PublicField,
       'N' => InheritedField,
        c => panic!("unexpected family char: {}", c)
@@ -150,6 +154,19 @@ fn item_visibility(item: rbml::Doc) -> hir::Visibility {
     }
 }
 
+fn item_defaultness(item: rbml::Doc) -> hir::Defaultness {
+    match reader::maybe_get_doc(item, tag_items_data_item_defaultness) {
+        None => hir::Defaultness::Default,
+        Some(defaultness_doc) => {
+            match reader::doc_as_u8(defaultness_doc) as char {
+                'd' => hir::Defaultness::Default,
+                'f' => hir::Defaultness::Final,
+                _ => panic!("unknown defaultness character")
+            }
+        }
+    }
+}
+
 fn fn_constness(item: rbml::Doc) -> hir::Constness {
     match reader::maybe_get_doc(item, tag_items_data_item_constness) {
         None => hir::Constness::NotConst,
@@ -264,9 +281,9 @@ fn item_name(intr: &IdentInterner, item: rbml::Doc) -> ast::Name {
     }
 }
 
-fn family_to_variant_kind<'tcx>(family: Family) -> Option<ty::VariantKind> {
+fn family_to_variant_kind(family: Family) -> Option<ty::VariantKind> {
     match family {
-        Struct(VariantKind::Struct) | Variant(VariantKind::Struct) =>
+        Struct(VariantKind::Struct) | Variant(VariantKind::Struct) | Union =>
             Some(ty::VariantKind::Struct),
         Struct(VariantKind::Tuple) | Variant(VariantKind::Tuple) =>
             Some(ty::VariantKind::Tuple),
@@ -293,7 +310,7 @@ fn item_to_def_like(cdata: Cmd, item: rbml::Doc, did: DefId) -> DefLike {
         }
         ImmStatic => DlDef(Def::Static(did, false)),
         MutStatic => DlDef(Def::Static(did, true)),
-        Struct(..) => DlDef(Def::Struct(did)),
+        Struct(..) | Union => DlDef(Def::Struct(did)),
         Fn        => DlDef(Def::Fn(did)),
         Method | StaticMethod => {
             DlDef(Def::Method(did))
@@ -349,6 +366,14 @@ fn parse_associated_type_names(item_doc: rbml::Doc) -> Vec<ast::Name> {
         .collect()
 }
 
+fn parse_methods_referencing_self(item_doc: rbml::Doc) -> Option<FnvHashSet<ast::Name>> {
+    reader::maybe_get_doc(item_doc, tag_methods_referencing_self).map(|names_doc| {
+        reader::tagged_docs(names_doc, tag_method_referencing_self)
+            .map(|name_doc| token::intern(name_doc.as_str_slice()))
+            .collect()
+    })
+}
+
 pub fn get_trait_def<'tcx>(cdata: Cmd,
                            item_id: DefIndex,
                            tcx: &TyCtxt<'tcx>) -> ty::TraitDef<'tcx>
@@ -359,11 +384,18 @@ pub fn get_trait_def<'tcx>(cdata: Cmd,
     let associated_type_names = parse_associated_type_names(item_doc);
     let paren_sugar = parse_paren_sugar(item_doc);
 
-    ty::TraitDef::new(unsafety,
-                      paren_sugar,
-                      generics,
-                      item_trait_ref(item_doc, tcx, cdata),
-                      associated_type_names)
+    let trait_def = ty::TraitDef::new(unsafety,
+                                      paren_sugar,
+                                      generics,
+                                      item_trait_ref(item_doc, tcx, cdata),
+                                      associated_type_names);
+    if let Some(names) = parse_methods_referencing_self(item_doc) {
+        trait_def.set_methods_referencing_self(names);
+    }
+    if reader::maybe_get_doc(item_doc, tag_trait_alias).is_some() {
+        trait_def.set_is_alias();
+    }
+    trait_def
 }
 
 pub fn get_adt_def<'tcx>(intr: &IdentInterner,
@@ -451,6 +483,10 @@ pub fn get_adt_def<'tcx>(intr: &IdentInterner,
             (ty::AdtKind::Struct,
              vec![get_struct_variant(intr, cdata, doc, ctor_did, tcx)])
         }
+        Union => {
+            (ty::AdtKind::Union,
+             vec![get_struct_variant(intr, cdata, doc, did, tcx)])
+        }
         _ => tcx.sess.bug(
             &format!("get_adt_def called on a non-ADT {:?} - {:?}",
                      item_family(doc), did))
@@ -560,9 +596,9 @@ pub fn get_repr_attrs(cdata: Cmd, id: DefIndex) -> Vec<attr::ReprAttr> {
     }
 }
 
-pub fn get_impl_polarity<'tcx>(cdata: Cmd,
-                               id: DefIndex)
-                               -> Option<hir::ImplPolarity>
+pub fn get_impl_polarity(cdata: Cmd,
+                         id: DefIndex)
+                         -> Option<hir::ImplPolarity>
 {
     let item_doc = cdata.lookup_item(id);
     let fam = item_family(item_doc);
@@ -574,7 +610,7 @@ pub fn get_impl_polarity<'tcx>(cdata: Cmd,
     }
 }
 
-pub fn get_custom_coerce_unsized_kind<'tcx>(
+pub fn get_custom_coerce_unsized_kind(
     cdata: Cmd,
     id: DefIndex)
     -> Option<ty::adjustment::CustomCoerceUnsized>
@@ -586,6 +622,13 @@ pub fn get_custom_coerce_unsized_kind<'tcx>(
     })
 }
 
+pub fn get_impl_parent(cdata: Cmd, id: DefIndex) -> Option<DefId> {
+    let item_doc = cdata.lookup_item(id);
+    reader::maybe_get_doc(item_doc, tag_impl_parent).map(|doc| {
+        translated_def_id(cdata, doc)
+    })
+}
+
 pub fn get_impl_trait<'tcx>(cdata: Cmd,
                             id: DefIndex,
                             tcx: &TyCtxt<'tcx>)
@@ -835,7 +878,7 @@ pub fn maybe_get_item_ast<'tcx>(cdata: Cmd,
     }
 }
 
-pub fn is_item_mir_available<'tcx>(cdata: Cmd, id: DefIndex) -> bool {
+pub fn is_item_mir_available(cdata: Cmd, id: DefIndex) -> bool {
     if let Some(item_doc) = cdata.get_item(id) {
         return reader::maybe_get_doc(item_doc, tag_mir as usize).is_some();
     }
@@ -974,6 +1017,7 @@ pub fn get_impl_or_trait_item<'tcx>(intr: Rc<IdentInterner>,
 
     let name = item_name(&intr, item_doc);
     let vis = item_visibility(item_doc);
+    let defaultness = item_defaultness(item_doc);
 
     match item_sort(item_doc) {
         sort @ Some('C') | sort @ Some('c') => {
@@ -982,6 +1026,7 @@ pub fn get_impl_or_trait_item<'tcx>(intr: Rc<IdentInterner>,
                 name: name,
                 ty: ty,
                 vis: vis,
+                defaultness: defaultness,
                 def_id: def_id,
                 container: container,
                 has_value: sort == Some('C')
@@ -1005,15 +1050,21 @@ pub fn get_impl_or_trait_item<'tcx>(intr: Rc<IdentInterner>,
                                                         fty,
                                                         explicit_self,
                                                         vis,
+                                                        defaultness,
                                                         def_id,
                                                         container)))
         }
         Some('t') => {
+            let generics = doc_generics(item_doc, tcx, cdata, tag_method_ty_generics);
+            let predicates = doc_predicates(item_doc, tcx, cdata, tag_method_ty_generics);
             let ty = maybe_doc_type(item_doc, tcx, cdata);
             ty::TypeTraitItem(Rc::new(ty::AssociatedType {
                 name: name,
+                generics: generics,
+                predicates: predicates,
                 ty: ty,
                 vis: vis,
+                defaultness: defaultness,
                 def_id: def_id,
                 container: container,
             }))
@@ -1298,6 +1349,15 @@ pub fn get_crate_triple(data: &[u8]) -> Option<String> {
     triple_doc.map(|s| s.as_str().to_string())
 }
 
+/// The edition a crate was compiled with. Crates built before editions were
+/// recorded are treated as using the default edition.
+pub fn get_crate_edition(data: &[u8]) -> Edition {
+    let cratedoc = rbml::Doc::new(data);
+    reader::maybe_get_doc(cratedoc, tag_crate_edition).and_then(|doc| {
+        doc.as_str_slice().parse().ok()
+    }).unwrap_or(DEFAULT_EDITION)
+}
+
 pub fn get_crate_name(data: &[u8]) -> String {
     maybe_get_crate_name(data).expect("no crate name in crate")
 }
@@ -1306,6 +1366,7 @@ pub fn list_crate_metadata(bytes: &[u8], out: &mut io::Write) -> io::Result<()>
     let hash = get_crate_hash(bytes);
     let md = rbml::Doc::new(bytes);
     try!(list_crate_attributes(md, &hash, out));
+    try!(write!(out, "=Edition=\n{}\n\n", get_crate_edition(bytes)));
     list_crate_deps(bytes, out)
 }
 
@@ -1353,6 +1414,15 @@ pub fn translate_span(cdata: Cmd,
                       last_filemap_index_hint: &Cell<usize>,
                       span: codemap::Span)
                       -> codemap::Span {
+    translate_span_in(&cdata.imported_filemaps(&codemap), last_filemap_index_hint, span)
+}
+
+/// Like `translate_span`, for a crate whose filemaps have already been
+/// imported (the crate need not be registered in the cstore).
+pub fn translate_span_in(imported_filemaps: &[cstore::ImportedFileMap],
+                         last_filemap_index_hint: &Cell<usize>,
+                         span: codemap::Span)
+                         -> codemap::Span {
     let span = if span.lo > span.hi {
         // Currently macro expansion sometimes produces invalid Span values
         // where lo > hi. In order not to crash the compiler when trying to
@@ -1366,7 +1436,6 @@ pub fn translate_span(cdata: Cmd,
         span
     };
 
-    let imported_filemaps = cdata.imported_filemaps(&codemap);
     let filemap = {
         // Optimize for the case that most spans within a translated item
         // originate from the same filemap.
@@ -1478,27 +1547,118 @@ pub fn get_plugin_registrar_fn(data: &[u8]) -> Option<DefIndex> {
         .map(|doc| DefIndex::from_u32(reader::doc_as_u32(doc)))
 }
 
+/// The body of an exported macro.
+pub enum MacroBody {
+    /// Token trees with spans in the codemap of the crate; see
+    /// `translate_macro_spans`.
+    Tokens(Vec<ast::TokenTree>),
+    /// The source text of a macro the crate reexports from another crate,
+    /// whose spans point into sources that can't be found from here.
+    Text(String),
+}
+
+/// Calls `f` with the name, attributes, span and body of each macro the
+/// crate exports. The spans, including those within the body, are those of
+/// the exporting crate.
 pub fn each_exported_macro<F>(data: &[u8], intr: &IdentInterner, mut f: F) where
-    F: FnMut(ast::Name, Vec<ast::Attribute>, Span, String) -> bool,
+    F: FnMut(ast::Name, Vec<ast::Attribute>, Span, MacroBody) -> bool,
 {
     let macros = reader::get_doc(rbml::Doc::new(data), tag_macro_defs);
     for macro_doc in reader::tagged_docs(macros, tag_macro_def) {
         let name = item_name(intr, macro_doc);
         let attrs = get_attributes(macro_doc);
         let span = get_macro_span(macro_doc);
-        let body = reader::get_doc(macro_doc, tag_macro_def_body);
-        if !f(name, attrs, span, body.as_str().to_string()) {
+        let body = match reader::maybe_get_doc(macro_doc, tag_macro_def_body_text) {
+            Some(text_doc) => MacroBody::Text(text_doc.as_str().to_string()),
+            None => {
+                let body_doc = reader::get_doc(macro_doc, tag_macro_def_body);
+                MacroBody::Tokens(Decodable::decode(&mut reader::Decoder::new(body_doc))
+                                      .unwrap())
+            }
+        };
+        if !f(name, attrs, span, body) {
             break;
         }
     }
 }
 
+/// Moves the spans of a macro body read by `each_exported_macro` into the
+/// local codemap, so that errors in its expansions point into the source
+/// of the defining crate.
+pub fn translate_macro_spans(imported_filemaps: &[cstore::ImportedFileMap],
+                             tts: &[ast::TokenTree])
+                             -> Vec<ast::TokenTree> {
+    let mut translator = SpanTranslator {
+        imported_filemaps: imported_filemaps,
+        last_filemap_index: Cell::new(0),
+    };
+    translator.fold_tts(tts)
+}
+
+struct SpanTranslator<'a> {
+    imported_filemaps: &'a [cstore::ImportedFileMap],
+    last_filemap_index: Cell<usize>,
+}
+
+impl<'a> Folder for SpanTranslator<'a> {
+    fn new_span(&mut self, span: Span) -> Span {
+        translate_span_in(self.imported_filemaps, &self.last_filemap_index, span)
+    }
+
+    // `noop_fold_tt` leaves the spans of token trees alone.
+    fn fold_tt(&mut self, tt: &ast::TokenTree) -> ast::TokenTree {
+        match *tt {
+            ast::TokenTree::Token(span, ref tok) => {
+                ast::TokenTree::Token(self.new_span(span), self.fold_token(tok.clone()))
+            }
+            ast::TokenTree::Delimited(span, ref delimed) => {
+                ast::TokenTree::Delimited(self.new_span(span), Rc::new(ast::Delimited {
+                    delim: delimed.delim,
+                    open_span: self.new_span(delimed.open_span),
+                    tts: self.fold_tts(&delimed.tts),
+                    close_span: self.new_span(delimed.close_span),
+                }))
+            }
+            ast::TokenTree::Sequence(span, ref seq) => {
+                ast::TokenTree::Sequence(self.new_span(span), Rc::new(ast::SequenceRepetition {
+                    tts: self.fold_tts(&seq.tts),
+                    separator: seq.separator.clone(),
+                    ..**seq
+                }))
+            }
+        }
+    }
+
+    // Macros can only occur here inside interpolated fragments.
+    fn fold_mac(&mut self, mac: ast::Mac) -> ast::Mac {
+        fold::noop_fold_mac(mac, self)
+    }
+}
+
 pub fn get_macro_span(doc: rbml::Doc) -> Span {
-    let lo_doc = reader::get_doc(doc, tag_macro_def_span_lo);
-    let lo = BytePos(reader::doc_as_u32(lo_doc));
-    let hi_doc = reader::get_doc(doc, tag_macro_def_span_hi);
-    let hi = BytePos(reader::doc_as_u32(hi_doc));
-    return Span { lo: lo, hi: hi, expn_id: NO_EXPANSION };
+    doc_span(reader::get_doc(doc, tag_macro_def_span))
+}
+
+fn doc_span(span_doc: rbml::Doc) -> Span {
+    // See `encode_span`: four bytes hold an inline `CompactSpan`.
+    if span_doc.end - span_doc.start == 4 {
+        let compact = CompactSpan::from_u32(reader::doc_as_u32(span_doc));
+        return compact.inline_span().expect("span table handle in metadata");
+    }
+
+    let interval = reader::doc_as_u64(span_doc);
+    let lo = (interval >> 32) as u32;
+    let len = interval as u32;
+    return Span { lo: BytePos(lo), hi: BytePos(lo + len), expn_id: NO_EXPANSION };
+}
+
+/// Returns the span of an item's declaration, translated into the local
+/// codemap, if one was recorded for it.
+pub fn get_item_span(cdata: Cmd, id: DefIndex, codemap: &codemap::CodeMap) -> Option<Span> {
+    let item_doc = cdata.lookup_item(id);
+    reader::maybe_get_doc(item_doc, tag_item_span).map(|span_doc| {
+        translate_span(cdata, codemap, &Cell::new(0), doc_span(span_doc))
+    })
 }
 
 pub fn get_dylib_dependency_formats(cdata: Cmd)
@@ -1559,6 +1719,17 @@ pub fn get_reachable_ids(cdata: Cmd) -> Vec<DefId> {
     }).collect()
 }
 
+pub fn get_default_bound_traits(cdata: Cmd) -> Vec<DefId> {
+    let traits = reader::get_doc(rbml::Doc::new(cdata.data()),
+                                 tag_default_bound_traits);
+    reader::tagged_docs(traits, tag_default_bound_trait).map(|doc| {
+        DefId {
+            krate: cdata.cnum,
+            index: DefIndex::from_u32(reader::doc_as_u32(doc)),
+        }
+    }).collect()
+}
+
 pub fn is_typedef(cdata: Cmd, id: DefIndex) -> bool {
     let item_doc = cdata.lookup_item(id);
     match item_family(item_doc) {
@@ -1655,7 +1826,29 @@ fn doc_generics<'tcx>(base_doc: rbml::Doc,
                                                      bounds: bounds });
     }
 
-    ty::Generics { types: types, regions: regions }
+    let mut consts = subst::VecPerParamSpace::empty();
+    for cp_doc in reader::tagged_docs(doc, tag_const_param_def) {
+        let ident_str_doc = reader::get_doc(cp_doc,
+                                            tag_region_param_def_ident);
+        let name = item_name(&token::get_ident_interner(), ident_str_doc);
+        let def_id_doc = reader::get_doc(cp_doc,
+                                         tag_region_param_def_def_id);
+        let def_id = translated_def_id(cdata, def_id_doc);
+
+        let doc = reader::get_doc(cp_doc, tag_region_param_def_space);
+        let space = subst::ParamSpace::from_uint(reader::doc_as_u64(doc) as usize);
+
+        let doc = reader::get_doc(cp_doc, tag_region_param_def_index);
+        let index = reader::doc_as_u64(doc) as u32;
+
+        consts.push(space, ty::ConstParameterDef { name: name,
+                                                   def_id: def_id,
+                                                   space: space,
+                                                   index: index,
+                                                   ty: doc_type(cp_doc, tcx, cdata) });
+    }
+
+    ty::Generics { types: types, regions: regions, consts: consts }
 }
 
 fn doc_predicate<'tcx>(cdata: Cmd,
@@ -1749,6 +1942,10 @@ fn def_key(item_doc: rbml::Doc) -> hir_map::DefKey {
     }
 }
 
+pub fn get_def_key(cdata: Cmd, id: DefIndex) -> hir_map::DefKey {
+    def_key(cdata.lookup_item(id))
+}
+
 pub fn def_path(cdata: Cmd, id: DefIndex) -> hir_map::DefPath {
     debug!("def_path(id={:?})", id);
     hir_map::definitions::make_def_path(id, |parent| {
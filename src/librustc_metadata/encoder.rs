@@ -42,7 +42,8 @@ use std::rc::Rc;
 use std::u32;
 use syntax::abi::Abi;
 use syntax::ast::{self, NodeId, Name, CRATE_NODE_ID, CrateNum};
-use syntax::codemap::BytePos;
+use syntax::codemap::{BytePos, CompactSpan, Span, NO_EXPANSION};
+use syntax::edition::Edition;
 use syntax::attr;
 use syntax::attr::AttrMetaMethods;
 use syntax::errors::Handler;
@@ -230,7 +231,11 @@ fn encode_symbol(ecx: &EncodeContext,
             rbml_w.wr_tagged_str(tag_items_data_item_symbol, x);
         }
         None => {
-            ecx.diag.bug(&format!("encode_symbol: id not found {}", id));
+            // With `--emit=metadata` the crate is never translated, so
+            // there are no symbols to record.
+            if !ecx.tcx.sess.opts.only_metadata_requested() {
+                ecx.diag.bug(&format!("encode_symbol: id not found {}", id));
+            }
         }
     }
 }
@@ -359,6 +364,13 @@ fn encode_reexports(ecx: &EncodeContext,
     match ecx.reexports.get(&id) {
         Some(exports) => {
             debug!("(encoding info for module) found reexports for {}", id);
+            // Resolve collects a module's exports from a hash map.
+            let mut exports: Vec<_> = exports.iter().collect();
+            if ecx.tcx.sess.deterministic_output() {
+                exports.sort_by(|a, b| {
+                    (a.name.as_str(), a.def_id).cmp(&(b.name.as_str(), b.def_id))
+                });
+            }
             for exp in exports {
                 debug!("(encoding info for module) reexport '{}' ({:?}) for \
                         {}",
@@ -438,6 +450,14 @@ fn encode_visibility(rbml_w: &mut Encoder, visibility: hir::Visibility) {
     rbml_w.wr_tagged_u8(tag_items_data_item_visibility, ch as u8);
 }
 
+fn encode_defaultness(rbml_w: &mut Encoder, defaultness: hir::Defaultness) {
+    let ch = match defaultness {
+        hir::Defaultness::Default => 'd',
+        hir::Defaultness::Final => 'f',
+    };
+    rbml_w.wr_tagged_u8(tag_items_data_item_defaultness, ch as u8);
+}
+
 fn encode_constness(rbml_w: &mut Encoder, constness: hir::Constness) {
     rbml_w.start_tag(tag_items_data_item_constness);
     let ch = match constness {
@@ -478,6 +498,27 @@ fn encode_explicit_self(rbml_w: &mut Encoder,
     }
 }
 
+fn encode_span(rbml_w: &mut Encoder, tag: usize, span: Span) {
+    // Expansion info isn't kept in metadata, so most spans fit into the four
+    // bytes of an inline `CompactSpan`. The others take eight bytes, `lo` and
+    // then `hi - lo`; the decoder tells the two apart by their length.
+    if let Some(compact) = CompactSpan::inline(Span { expn_id: NO_EXPANSION, ..span }) {
+        let raw = compact.as_u32() as u64;
+        let bytes: Vec<u8> = (0..4).rev().map(|i| (raw >> (i * 8)) as u8).collect();
+        rbml_w.wr_tagged_bytes(tag, &bytes);
+        return;
+    }
+
+    let BytePos(lo) = span.lo;
+    let BytePos(hi) = span.hi;
+    // Spans stitched together by macro expansion can end before they start;
+    // record those as empty rather than wrapping around.
+    let len = hi.saturating_sub(lo);
+    let interval = (lo as u64) << 32 | len as u64;
+    let bytes: Vec<u8> = (0..8).rev().map(|i| (interval >> (i * 8)) as u8).collect();
+    rbml_w.wr_tagged_bytes(tag, &bytes);
+}
+
 fn encode_item_sort(rbml_w: &mut Encoder, sort: char) {
     rbml_w.wr_tagged_u8(tag_item_trait_item_sort, sort as u8);
 }
@@ -584,6 +625,27 @@ fn encode_generics<'a, 'tcx>(rbml_w: &mut Encoder,
         rbml_w.end_tag();
     }
 
+    for param in &generics.consts {
+        rbml_w.start_tag(tag_const_param_def);
+
+        rbml_w.start_tag(tag_region_param_def_ident);
+        encode_name(rbml_w, param.name);
+        rbml_w.end_tag();
+
+        rbml_w.wr_tagged_u64(tag_region_param_def_def_id,
+                             def_to_u64(param.def_id));
+
+        rbml_w.wr_tagged_u64(tag_region_param_def_space,
+                             param.space.to_uint() as u64);
+
+        rbml_w.wr_tagged_u64(tag_region_param_def_index,
+                             param.index as u64);
+
+        encode_type(ecx, rbml_w, param.ty);
+
+        rbml_w.end_tag();
+    }
+
     encode_predicates_in_current_doc(rbml_w, ecx, index, predicates);
 
     rbml_w.end_tag();
@@ -627,6 +689,7 @@ fn encode_method_ty_fields<'a, 'tcx>(ecx: &EncodeContext<'a, 'tcx>,
                     &method_ty.generics, &method_ty.predicates,
                     tag_method_ty_generics);
     encode_visibility(rbml_w, method_ty.vis);
+    encode_defaultness(rbml_w, method_ty.defaultness);
     encode_explicit_self(rbml_w, &method_ty.explicit_self);
     match method_ty.explicit_self {
         ty::ExplicitSelfCategory::Static => {
@@ -653,6 +716,7 @@ fn encode_info_for_associated_const<'a, 'tcx>(ecx: &EncodeContext<'a, 'tcx>,
     encode_def_id_and_key(ecx, rbml_w, associated_const.def_id);
     encode_name(rbml_w, associated_const.name);
     encode_visibility(rbml_w, associated_const.vis);
+    encode_defaultness(rbml_w, associated_const.defaultness);
     encode_family(rbml_w, 'C');
 
     encode_parent_item(rbml_w, ecx.tcx.map.local_def_id(parent_id));
@@ -750,9 +814,13 @@ fn encode_info_for_associated_type<'a, 'tcx>(ecx: &EncodeContext<'a, 'tcx>,
     encode_def_id_and_key(ecx, rbml_w, associated_type.def_id);
     encode_name(rbml_w, associated_type.name);
     encode_visibility(rbml_w, associated_type.vis);
+    encode_defaultness(rbml_w, associated_type.defaultness);
     encode_family(rbml_w, 'y');
     encode_parent_item(rbml_w, ecx.tcx.map.local_def_id(parent_id));
     encode_item_sort(rbml_w, 't');
+    encode_generics(rbml_w, ecx, index,
+                    &associated_type.generics, &associated_type.predicates,
+                    tag_method_ty_generics);
 
     let stab = stability::lookup_stability(ecx.tcx, associated_type.def_id);
     let depr = stability::lookup_deprecation(ecx.tcx, associated_type.def_id);
@@ -875,8 +943,12 @@ fn encode_xrefs<'a, 'tcx>(ecx: &EncodeContext<'a, 'tcx>,
                           xrefs: FnvHashMap<XRef<'tcx>, u32>)
 {
     let mut xref_positions = vec![0; xrefs.len()];
+    let mut xrefs: Vec<_> = xrefs.into_iter().collect();
+    if ecx.tcx.sess.deterministic_output() {
+        xrefs.sort_by(|a, b| a.1.cmp(&b.1));
+    }
     rbml_w.start_tag(tag_xref_data);
-    for (xref, id) in xrefs.into_iter() {
+    for (xref, id) in xrefs {
         xref_positions[id as usize] = rbml_w.mark_stable_position() as u32;
         match xref {
             XRef::Predicate(p) => {
@@ -1039,7 +1111,7 @@ fn encode_info_for_item<'a, 'tcx>(ecx: &EncodeContext<'a, 'tcx>,
                                  vis,
                                  index);
       }
-      hir::ItemStruct(ref struct_def, _) => {
+      hir::ItemStruct(ref struct_def, _) | hir::ItemUnion(ref struct_def, _) => {
         let def = ecx.tcx.lookup_adt_def(def_id);
         let variant = def.struct_variant();
 
@@ -1050,6 +1122,7 @@ fn encode_info_for_item<'a, 'tcx>(ecx: &EncodeContext<'a, 'tcx>,
         rbml_w.start_tag(tag_items_data_item);
         encode_def_id_and_key(ecx, rbml_w, def_id);
         encode_family(rbml_w, match *struct_def {
+            _ if def.is_union() => 'U',
             hir::VariantData::Struct(..) => 'S',
             hir::VariantData::Tuple(..) => 's',
             hir::VariantData::Unit(..) => 'u',
@@ -1129,6 +1202,10 @@ fn encode_info_for_item<'a, 'tcx>(ecx: &EncodeContext<'a, 'tcx>,
             None => {}
         }
 
+        if let Some(parent) = tcx.impl_parent(def_id) {
+            rbml_w.wr_tagged_u64(tag_impl_parent, def_to_u64(parent));
+        }
+
         for &item_def_id in items {
             rbml_w.start_tag(tag_item_impl_item);
             match item_def_id {
@@ -1199,7 +1276,12 @@ fn encode_info_for_item<'a, 'tcx>(ecx: &EncodeContext<'a, 'tcx>,
             }
         }
       }
-      hir::ItemTrait(_, _, _, ref ms) => {
+      hir::ItemTrait(..) | hir::ItemTraitAlias(..) => {
+        // Trait aliases are encoded as traits without items.
+        let ms = match item.node {
+            hir::ItemTrait(_, _, _, ref ms) => &ms[..],
+            _ => &[][..],
+        };
         index.record(def_id, rbml_w);
         rbml_w.start_tag(tag_items_data_item);
         encode_def_id_and_key(ecx, rbml_w, def_id);
@@ -1209,8 +1291,12 @@ fn encode_info_for_item<'a, 'tcx>(ecx: &EncodeContext<'a, 'tcx>,
         let trait_predicates = tcx.lookup_predicates(def_id);
         encode_unsafety(rbml_w, trait_def.unsafety);
         encode_paren_sugar(rbml_w, trait_def.paren_sugar);
+        if trait_def.is_alias() {
+            rbml_w.wr_tagged_bytes(tag_trait_alias, &[]);
+        }
         encode_defaulted(rbml_w, tcx.trait_has_default_impl(def_id));
         encode_associated_type_names(rbml_w, &trait_def.associated_type_names);
+        encode_methods_referencing_self(rbml_w, trait_def);
         encode_generics(rbml_w, ecx, index,
                         &trait_def.generics, &trait_predicates,
                         tag_item_generics);
@@ -1321,6 +1407,9 @@ fn encode_info_for_item<'a, 'tcx>(ecx: &EncodeContext<'a, 'tcx>,
 
                     encode_item_sort(rbml_w, 't');
                     encode_family(rbml_w, 'y');
+                    encode_generics(rbml_w, ecx, index,
+                                    &associated_type.generics, &associated_type.predicates,
+                                    tag_method_ty_generics);
 
                     if let Some(ty) = associated_type.ty {
                         encode_type(ecx, rbml_w, ty);
@@ -1332,6 +1421,9 @@ fn encode_info_for_item<'a, 'tcx>(ecx: &EncodeContext<'a, 'tcx>,
 
             let trait_item = &ms[i];
             encode_attributes(rbml_w, &trait_item.attrs);
+            // Lets other crates point at the declaration, e.g. when an
+            // impl is missing this item.
+            encode_span(rbml_w, tag_item_span, trait_item.span);
             match trait_item.node {
                 hir::ConstTraitItem(_, ref default) => {
                     if default.is_some() {
@@ -1455,6 +1547,29 @@ fn my_visit_expr(expr: &hir::Expr,
     }
 }
 
+fn my_visit_ty(ty: &hir::Ty,
+               rbml_w: &mut Encoder,
+               ecx: &EncodeContext,
+               index: &mut CrateIndex) {
+    match ty.node {
+        hir::TyImplTrait(_) => {
+            let def_id = ecx.tcx.map.local_def_id(ty.id);
+
+            index.record(def_id, rbml_w);
+
+            rbml_w.start_tag(tag_items_data_item);
+            encode_def_id_and_key(ecx, rbml_w, def_id);
+            encode_bounds_and_type(rbml_w,
+                                   ecx,
+                                   index,
+                                   &ecx.tcx.lookup_item_type(def_id),
+                                   &ecx.tcx.lookup_predicates(def_id));
+            rbml_w.end_tag();
+        }
+        _ => { }
+    }
+}
+
 fn my_visit_item<'a, 'tcx>(i: &hir::Item,
                            rbml_w: &mut Encoder,
                            ecx: &EncodeContext<'a, 'tcx>,
@@ -1491,6 +1606,10 @@ impl<'a, 'b, 'c, 'tcx> Visitor<'tcx> for EncodeVisitor<'a, 'b, 'c, 'tcx> {
         intravisit::walk_expr(self, ex);
         my_visit_expr(ex, self.rbml_w_for_visit_item, self.ecx, self.index);
     }
+    fn visit_ty(&mut self, ty: &'tcx hir::Ty) {
+        intravisit::walk_ty(self, ty);
+        my_visit_ty(ty, self.rbml_w_for_visit_item, self.ecx, self.index);
+    }
     fn visit_item(&mut self, i: &'tcx hir::Item) {
         intravisit::walk_item(self, i);
         my_visit_item(i, self.rbml_w_for_visit_item, self.ecx, self.index);
@@ -1604,6 +1723,20 @@ fn encode_associated_type_names(rbml_w: &mut Encoder, names: &[Name]) {
     rbml_w.end_tag();
 }
 
+fn encode_methods_referencing_self(rbml_w: &mut Encoder, trait_def: &ty::TraitDef) {
+    let mut names = match trait_def.methods_referencing_self() {
+        Some(names) => names,
+        None => return,
+    };
+    // Keep the metadata deterministic.
+    names.sort_by(|a, b| a.as_str().cmp(&b.as_str()));
+    rbml_w.start_tag(tag_methods_referencing_self);
+    for name in names {
+        rbml_w.wr_tagged_str(tag_method_referencing_self, &name.as_str());
+    }
+    rbml_w.end_tag();
+}
+
 fn encode_polarity(rbml_w: &mut Encoder, polarity: hir::ImplPolarity) {
     let byte: u8 = match polarity {
         hir::ImplPolarity::Positive => 0,
@@ -1718,22 +1851,32 @@ fn encode_codemap(ecx: &EncodeContext, rbml_w: &mut Encoder) {
     rbml_w.end_tag();
 }
 
-/// Serialize the text of the exported macros
-fn encode_macro_defs(rbml_w: &mut Encoder,
+/// Serialize the exported macros, with their bodies as token trees
+fn encode_macro_defs(ecx: &EncodeContext,
+                     rbml_w: &mut Encoder,
                      krate: &hir::Crate) {
+    let codemap = ecx.tcx.sess.codemap();
     rbml_w.start_tag(tag_macro_defs);
     for def in &krate.exported_macros {
         rbml_w.start_tag(tag_macro_def);
 
         encode_name(rbml_w, def.name);
         encode_attributes(rbml_w, &def.attrs);
-        let &BytePos(lo) = &def.span.lo;
-        let &BytePos(hi) = &def.span.hi;
-        rbml_w.wr_tagged_u32(tag_macro_def_span_lo, lo);
-        rbml_w.wr_tagged_u32(tag_macro_def_span_hi, hi);
-
-        rbml_w.wr_tagged_str(tag_macro_def_body,
-                             &::syntax::print::pprust::tts_to_string(&def.body));
+        encode_span(rbml_w, tag_macro_def_span, def.span);
+
+        if codemap.lookup_char_pos(def.span.lo).file.is_imported() {
+            // A `#[macro_reexport]`ed macro: its spans point into the
+            // filemaps of the crate defining it, which `encode_codemap`
+            // doesn't export, so only its text can be passed on.
+            rbml_w.wr_tagged_str(tag_macro_def_body_text,
+                                 &::syntax::print::pprust::tts_to_string(&def.body));
+        } else {
+            // Identifiers are encoded by name alone, without their syntax
+            // contexts; hygiene information is not preserved across crates.
+            rbml_w.start_tag(tag_macro_def_body);
+            def.body.encode(rbml_w).unwrap();
+            rbml_w.end_tag();
+        }
 
         rbml_w.end_tag();
     }
@@ -1793,8 +1936,13 @@ fn encode_impls<'a>(ecx: &'a EncodeContext,
     };
     krate.visit_all_items(&mut visitor);
 
+    let mut impls: Vec<_> = visitor.impls.into_iter().collect();
+    if ecx.tcx.sess.deterministic_output() {
+        impls.sort_by(|a, b| a.0.cmp(&b.0));
+    }
+
     rbml_w.start_tag(tag_impls);
-    for (trait_, trait_impls) in visitor.impls {
+    for (trait_, trait_impls) in impls {
         rbml_w.start_tag(tag_impls_trait);
         encode_def_id(rbml_w, trait_);
         for impl_ in trait_impls {
@@ -1836,14 +1984,30 @@ fn encode_misc_info(ecx: &EncodeContext,
 // symbol associated with them (they weren't translated) or if they're an FFI
 // definition (as that's not defined in this crate).
 fn encode_reachable(ecx: &EncodeContext, rbml_w: &mut Encoder) {
+    let mut reachable: Vec<_> = ecx.reachable.iter().cloned().collect();
+    if ecx.tcx.sess.deterministic_output() {
+        reachable.sort();
+    }
     rbml_w.start_tag(tag_reachable_ids);
-    for &id in ecx.reachable {
+    for id in reachable {
         let def_id = ecx.tcx.map.local_def_id(id);
         rbml_w.wr_tagged_u32(tag_reachable_id, def_id.index.as_u32());
     }
     rbml_w.end_tag();
 }
 
+/// Lists the traits marked `#[default_bound]`, so that crates using this
+/// one apply them to their own type parameters too.
+fn encode_default_bound_traits(ecx: &EncodeContext, rbml_w: &mut Encoder) {
+    rbml_w.start_tag(tag_default_bound_traits);
+    for def_id in ecx.tcx.default_bound_traits.borrow().iter() {
+        if def_id.is_local() {
+            rbml_w.wr_tagged_u32(tag_default_bound_trait, def_id.index.as_u32());
+        }
+    }
+    rbml_w.end_tag();
+}
+
 fn encode_crate_dep(rbml_w: &mut Encoder,
                     dep: &cstore::crate_metadata) {
     rbml_w.start_tag(tag_crate_dep);
@@ -1871,6 +2035,10 @@ fn encode_crate_triple(rbml_w: &mut Encoder, triple: &str) {
     rbml_w.wr_tagged_str(tag_crate_triple, triple);
 }
 
+fn encode_crate_edition(rbml_w: &mut Encoder, edition: Edition) {
+    rbml_w.wr_tagged_str(tag_crate_edition, edition.as_str());
+}
+
 fn encode_dylib_dependency_formats(rbml_w: &mut Encoder, ecx: &EncodeContext) {
     let tag = tag_dylib_dependency_formats;
     match ecx.tcx.sess.dependency_formats.borrow().get(&config::CrateTypeDylib) {
@@ -2001,6 +2169,7 @@ fn encode_metadata_inner(rbml_w: &mut Encoder,
     encode_rustc_version(rbml_w);
     encode_crate_name(rbml_w, &ecx.link_meta.crate_name);
     encode_crate_triple(rbml_w, &ecx.tcx.sess.opts.target_triple);
+    encode_crate_edition(rbml_w, ecx.tcx.sess.edition());
     encode_hash(rbml_w, &ecx.link_meta.crate_hash);
     encode_dylib_dependency_formats(rbml_w, &ecx);
 
@@ -2034,7 +2203,7 @@ fn encode_metadata_inner(rbml_w: &mut Encoder,
 
     // Encode macro definitions
     i = rbml_w.writer.seek(SeekFrom::Current(0)).unwrap();
-    encode_macro_defs(rbml_w, krate);
+    encode_macro_defs(&ecx, rbml_w, krate);
     stats.macro_defs_bytes = rbml_w.writer.seek(SeekFrom::Current(0)).unwrap() - i;
 
     // Encode the def IDs of impls, for coherence checking.
@@ -2046,6 +2215,7 @@ fn encode_metadata_inner(rbml_w: &mut Encoder,
     i = rbml_w.writer.seek(SeekFrom::Current(0)).unwrap();
     encode_misc_info(&ecx, krate, rbml_w);
     encode_reachable(&ecx, rbml_w);
+    encode_default_bound_traits(&ecx, rbml_w);
     stats.misc_bytes = rbml_w.writer.seek(SeekFrom::Current(0)).unwrap() - i;
 
     // Encode and index the items.
@@ -19,6 +19,7 @@
 
 #![feature(box_patterns)]
 #![feature(enumset)]
+#![feature(fs_time)]
 #![feature(quote)]
 #![feature(rustc_diagnostic_macros)]
 #![feature(rustc_private)]
@@ -26,7 +27,6 @@
 
 #[macro_use] extern crate log;
 #[macro_use] extern crate syntax;
-#[macro_use] #[no_link] extern crate rustc_bitflags;
 
 extern crate flate;
 extern crate rbml;
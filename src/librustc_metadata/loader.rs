@@ -61,6 +61,10 @@
 //! Most parts of this loading systems keep the dylib/rlib as just separate
 //! variables.
 //!
+//! A crate may also be found as a `lib*.rmeta` file, as written by
+//! `--emit=metadata`. It holds nothing but the metadata, so it is enough to
+//! type-check a dependent crate against, but it can't be linked.
+//!
 //! ## Where to look?
 //!
 //! We can't exactly scan your whole hard drive when looking for dependencies,
@@ -212,7 +216,7 @@
 //! no means all of the necessary details. Take a look at the rest of
 //! metadata::loader or metadata::creader for all the juicy details!
 
-use cstore::{MetadataBlob, MetadataVec, MetadataArchive};
+use cstore::{MetadataBlob, MetadataVec, MetadataArchive, MetadataShared, MetadataFile};
 use decoder;
 use encoder;
 
@@ -229,6 +233,7 @@ use syntax::codemap::Span;
 use syntax::errors::DiagnosticBuilder;
 use rustc_back::target::Target;
 
+use std::cell::RefCell;
 use std::cmp;
 use std::collections::HashMap;
 use std::fs;
@@ -236,8 +241,9 @@ use std::io::prelude::*;
 use std::io;
 use std::path::{Path, PathBuf};
 use std::ptr;
+use std::rc::Rc;
 use std::slice;
-use std::time::Instant;
+use std::time::{Instant, SystemTime};
 
 use flate;
 
@@ -260,12 +266,15 @@ pub struct Context<'a> {
     pub rejected_via_hash: Vec<CrateMismatch>,
     pub rejected_via_triple: Vec<CrateMismatch>,
     pub rejected_via_kind: Vec<CrateMismatch>,
+    pub rejected_via_name: Vec<CrateMismatch>,
+    pub rejected_via_metadata: Vec<CrateMismatch>,
     pub should_match_name: bool,
 }
 
 pub struct Library {
     pub dylib: Option<(PathBuf, PathKind)>,
     pub rlib: Option<(PathBuf, PathKind)>,
+    pub rmeta: Option<(PathBuf, PathKind)>,
     pub metadata: MetadataBlob,
 }
 
@@ -278,19 +287,22 @@ pub struct ArchiveMetadata {
 pub struct CratePaths {
     pub ident: String,
     pub dylib: Option<PathBuf>,
-    pub rlib: Option<PathBuf>
+    pub rlib: Option<PathBuf>,
+    pub rmeta: Option<PathBuf>,
 }
 
 pub const METADATA_FILENAME: &'static str = "rust.metadata.bin";
 
+#[derive(Copy, Clone, PartialEq)]
+enum CrateFlavor {
+    Rlib,
+    Rmeta,
+    Dylib,
+}
+
 impl CratePaths {
     fn paths(&self) -> Vec<PathBuf> {
-        match (&self.dylib, &self.rlib) {
-            (&None,    &None)              => vec!(),
-            (&Some(ref p), &None) |
-            (&None, &Some(ref p))          => vec!(p.clone()),
-            (&Some(ref p1), &Some(ref p2)) => vec!(p1.clone(), p2.clone()),
-        }
+        self.dylib.iter().chain(self.rlib.iter()).chain(self.rmeta.iter()).cloned().collect()
     }
 }
 
@@ -371,11 +383,59 @@ impl<'a> Context<'a> {
                                            self.ident, i+1, path.display()));
             }
         }
+        self.note_search(&mut err);
 
         err.emit();
         self.sess.abort_if_errors();
     }
 
+    /// With `-Z explain-crate-loading`, lists every directory that was
+    /// searched and every candidate that was turned down, with the reason.
+    fn note_search(&self, err: &mut DiagnosticBuilder) {
+        if !self.sess.opts.debugging_opts.explain_crate_loading {
+            return;
+        }
+
+        if self.hash.is_none() && self.sess.opts.externs.contains_key(self.crate_name) {
+            err.note(&format!("only the `--extern {}` locations were considered",
+                              self.crate_name));
+        } else {
+            self.filesearch.for_each_lib_search_path(|dir, _| {
+                err.note(&format!("searched `{}`", dir.display()));
+            });
+        }
+
+        let triple = self.rejected_via_triple.iter().map(|m| {
+            (m, format!("compiled for target `{}`, expected `{}`", m.got, self.triple))
+        });
+        let hash = self.rejected_via_hash.iter().map(|m| {
+            (m, format!("has hash `{}`, expected `{}`; it is a different version of the crate",
+                        m.got, self.hash.map_or("", |h| h.as_str())))
+        });
+        let kind = self.rejected_via_kind.iter().map(|m| {
+            (m, "is a staticlib".to_string())
+        });
+        let name = self.rejected_via_name.iter().map(|m| {
+            if m.got.is_empty() {
+                (m, "has no crate name in its metadata".to_string())
+            } else {
+                (m, format!("is crate `{}`", m.got))
+            }
+        });
+        let metadata = self.rejected_via_metadata.iter().map(|m| {
+            (m, format!("its metadata can't be used: {}", m.got))
+        });
+
+        let mut any = false;
+        for (m, reason) in triple.chain(hash).chain(kind).chain(name).chain(metadata) {
+            err.note(&format!("rejected `{}`: {}", m.path.display(), reason));
+            any = true;
+        }
+        if !any {
+            err.note(&format!("no file named like crate `{}` was found", self.crate_name));
+        }
+    }
+
     fn find_library_crate(&mut self) -> Option<Library> {
         // If an SVH is specified, then this is a transitive dependency that
         // must be loaded via -L plus some filtering.
@@ -387,6 +447,15 @@ impl<'a> Context<'a> {
             self.should_match_name = true;
         }
 
+        // Crates listed in a sysroot manifest are only ever loaded from
+        // there, including as dependencies of other crates.
+        let sess = self.sess;
+        if let Some(ref manifest) = sess.sysroot_manifest {
+            if let Some(loc) = manifest.get(self.crate_name) {
+                return self.find_manifest_library(&manifest.path, loc);
+            }
+        }
+
         let dypair = self.dylibname();
         let staticpair = self.staticlibname();
 
@@ -416,14 +485,18 @@ impl<'a> Context<'a> {
                 None => return FileDoesntMatch,
                 Some(file) => file,
             };
-            let (hash, rlib) = if file.starts_with(&rlib_prefix[..]) &&
-                                  file.ends_with(".rlib") {
+            let (hash, flavor) = if file.starts_with(&rlib_prefix[..]) &&
+                                    file.ends_with(".rlib") {
                 (&file[(rlib_prefix.len()) .. (file.len() - ".rlib".len())],
-                 true)
+                 CrateFlavor::Rlib)
+            } else if file.starts_with(&rlib_prefix[..]) &&
+                      file.ends_with(".rmeta") {
+                (&file[(rlib_prefix.len()) .. (file.len() - ".rmeta".len())],
+                 CrateFlavor::Rmeta)
             } else if file.starts_with(&dylib_prefix) &&
                       file.ends_with(&dypair.1) {
                 (&file[(dylib_prefix.len()) .. (file.len() - dypair.1.len())],
-                 false)
+                 CrateFlavor::Dylib)
             } else {
                 if file.starts_with(&staticlib_prefix[..]) &&
                    file.ends_with(&staticpair.1) {
@@ -437,15 +510,16 @@ impl<'a> Context<'a> {
             info!("lib candidate: {}", path.display());
 
             let hash_str = hash.to_string();
-            let slot = candidates.entry(hash_str)
-                                 .or_insert_with(|| (HashMap::new(), HashMap::new()));
-            let (ref mut rlibs, ref mut dylibs) = *slot;
+            let slot = candidates.entry(hash_str).or_insert_with(|| {
+                (HashMap::new(), HashMap::new(), HashMap::new())
+            });
+            let (ref mut rlibs, ref mut rmetas, ref mut dylibs) = *slot;
             fs::canonicalize(path).map(|p| {
-                if rlib {
-                    rlibs.insert(p, kind);
-                } else {
-                    dylibs.insert(p, kind);
-                }
+                match flavor {
+                    CrateFlavor::Rlib => rlibs.insert(p, kind),
+                    CrateFlavor::Rmeta => rmetas.insert(p, kind),
+                    CrateFlavor::Dylib => dylibs.insert(p, kind),
+                };
                 FileMatches
             }).unwrap_or(FileDoesntMatch)
         });
@@ -453,22 +527,24 @@ impl<'a> Context<'a> {
 
         // We have now collected all known libraries into a set of candidates
         // keyed of the filename hash listed. For each filename, we also have a
-        // list of rlibs/dylibs that apply. Here, we map each of these lists
+        // list of rlibs/rmetas/dylibs that apply. Here, we map each of these lists
         // (per hash), to a Library candidate for returning.
         //
         // A Library candidate is created if the metadata for the set of
         // libraries corresponds to the crate id and hash criteria that this
         // search is being performed for.
         let mut libraries = Vec::new();
-        for (_hash, (rlibs, dylibs)) in candidates {
+        for (_hash, (rlibs, rmetas, dylibs)) in candidates {
             let mut metadata = None;
             let rlib = self.extract_one(rlibs, "rlib", &mut metadata);
+            let rmeta = self.extract_one(rmetas, "rmeta", &mut metadata);
             let dylib = self.extract_one(dylibs, "dylib", &mut metadata);
             match metadata {
                 Some(metadata) => {
                     libraries.push(Library {
                         dylib: dylib,
                         rlib: rlib,
+                        rmeta: rmeta,
                         metadata: metadata,
                     })
                 }
@@ -502,10 +578,18 @@ impl<'a> Context<'a> {
                         }
                         None => {}
                     }
+                    match lib.rmeta {
+                        Some((ref p, _)) => {
+                            err.note(&format!("path: {}",
+                                              p.display()));
+                        }
+                        None => {}
+                    }
                     let data = lib.metadata.as_slice();
                     let name = decoder::get_crate_name(data);
                     note_crate_name(&mut err, &name);
                 }
+                self.note_search(&mut err);
                 err.emit();
                 None
             }
@@ -551,6 +635,10 @@ impl<'a> Context<'a> {
                 }
                 Err(err) => {
                     info!("no metadata found: {}", err);
+                    self.rejected_via_metadata.push(CrateMismatch {
+                        path: lib.clone(),
+                        got: err,
+                    });
                     continue
                 }
             };
@@ -597,12 +685,23 @@ impl<'a> Context<'a> {
         if self.should_match_name {
             match decoder::maybe_get_crate_name(crate_data) {
                 Some(ref name) if self.crate_name == *name => {}
-                _ => { info!("Rejecting via crate name"); return false }
+                name => {
+                    info!("Rejecting via crate name");
+                    self.rejected_via_name.push(CrateMismatch {
+                        path: libpath.to_path_buf(),
+                        got: name.unwrap_or(String::new()),
+                    });
+                    return false
+                }
             }
         }
         let hash = match decoder::maybe_get_crate_hash(crate_data) {
             Some(hash) => hash, None => {
                 info!("Rejecting via lack of crate hash");
+                self.rejected_via_metadata.push(CrateMismatch {
+                    path: libpath.to_path_buf(),
+                    got: "it has no crate hash".to_string(),
+                });
                 return false;
             }
         };
@@ -627,7 +726,7 @@ impl<'a> Context<'a> {
                     info!("Rejecting via hash: expected {} got {}", *myhash, hash);
                     self.rejected_via_hash.push(CrateMismatch {
                         path: libpath.to_path_buf(),
-                        got: myhash.as_str().to_string()
+                        got: hash.as_str().to_string()
                     });
                     false
                 } else {
@@ -652,13 +751,77 @@ impl<'a> Context<'a> {
         (t.options.staticlib_prefix.clone(), t.options.staticlib_suffix.clone())
     }
 
+    /// Loads the crate from the file a sysroot manifest lists for it. A
+    /// bad entry is reported as such here, as the usual "can't find crate"
+    /// error wouldn't point at the manifest.
+    fn find_manifest_library(&mut self, manifest: &Path, loc: &Path) -> Option<Library> {
+        let (sess, span, crate_name) = (self.sess, self.span, self.crate_name);
+        let bad_entry = |msg: &str| {
+            sess.struct_span_err(span,
+                                 &format!("invalid entry for `{}` in sysroot manifest `{}`",
+                                          crate_name, manifest.display()))
+                .note(&format!("`{}` {}", loc.display(), msg))
+                .emit();
+            sess.abort_if_errors();
+        };
+
+        if !loc.is_file() {
+            bad_entry("does not exist or is not a file");
+            return None;
+        }
+        let file = loc.file_name().and_then(|s| s.to_str()).unwrap_or("");
+        let (ref dylib_prefix, ref dylib_suffix) = self.dylibname();
+        let flavor = if file.starts_with("lib") && file.ends_with(".rlib") {
+            CrateFlavor::Rlib
+        } else if file.starts_with("lib") && file.ends_with(".rmeta") {
+            CrateFlavor::Rmeta
+        } else if file.starts_with(&dylib_prefix[..]) && file.ends_with(&dylib_suffix[..]) {
+            CrateFlavor::Dylib
+        } else {
+            bad_entry(&format!("is not an rlib, rmeta or dylib; the file name should be \
+                                lib*.rlib, lib*.rmeta or {}*{}", dylib_prefix, dylib_suffix));
+            return None;
+        };
+        let metadata = match get_metadata_section(self.target, loc) {
+            Ok(blob) => blob,
+            Err(e) => {
+                bad_entry(&format!("is not a Rust library: {}", e));
+                return None;
+            }
+        };
+        match decoder::maybe_get_crate_name(metadata.as_slice()) {
+            Some(ref name) if self.crate_name == *name => {}
+            Some(ref name) => {
+                bad_entry(&format!("is crate `{}`", name));
+                return None;
+            }
+            None => {
+                bad_entry("has no crate name in its metadata");
+                return None;
+            }
+        }
+        // Target and hash mismatches are reported by `report_load_errs`.
+        if !self.crate_matches(metadata.as_slice(), loc) {
+            return None;
+        }
+
+        let loc = Some((fs::canonicalize(loc).unwrap(), PathKind::All));
+        Some(Library {
+            dylib: if flavor == CrateFlavor::Dylib { loc.clone() } else { None },
+            rlib: if flavor == CrateFlavor::Rlib { loc.clone() } else { None },
+            rmeta: if flavor == CrateFlavor::Rmeta { loc } else { None },
+            metadata: metadata,
+        })
+    }
+
     fn find_commandline_library(&mut self, locs: &[String]) -> Option<Library> {
         // First, filter out all libraries that look suspicious. We only accept
         // files which actually exist that have the correct naming scheme for
-        // rlibs/dylibs.
+        // rlibs/rmetas/dylibs.
         let sess = self.sess;
         let dylibname = self.dylibname();
         let mut rlibs = HashMap::new();
+        let mut rmetas = HashMap::new();
         let mut dylibs = HashMap::new();
         {
             let locs = locs.iter().map(|l| PathBuf::from(l)).filter(|loc| {
@@ -675,7 +838,8 @@ impl<'a> Context<'a> {
                         return false;
                     }
                 };
-                if file.starts_with("lib") && file.ends_with(".rlib") {
+                if file.starts_with("lib") &&
+                   (file.ends_with(".rlib") || file.ends_with(".rmeta")) {
                     return true
                 } else {
                     let (ref prefix, ref suffix) = dylibname;
@@ -686,18 +850,22 @@ impl<'a> Context<'a> {
                 }
                 sess.struct_err(&format!("extern location for {} is of an unknown type: {}",
                                          self.crate_name, loc.display()))
-                    .help(&format!("file name should be lib*.rlib or {}*.{}",
+                    .help(&format!("file name should be lib*.rlib, lib*.rmeta or {}*.{}",
                                    dylibname.0, dylibname.1))
                     .emit();
                 false
             });
 
             // Now that we have an iterator of good candidates, make sure
-            // there's at most one rlib and at most one dylib.
+            // there's at most one rlib, at most one rmeta and at most one
+            // dylib.
             for loc in locs {
                 if loc.file_name().unwrap().to_str().unwrap().ends_with(".rlib") {
                     rlibs.insert(fs::canonicalize(&loc).unwrap(),
                                  PathKind::ExternFlag);
+                } else if loc.file_name().unwrap().to_str().unwrap().ends_with(".rmeta") {
+                    rmetas.insert(fs::canonicalize(&loc).unwrap(),
+                                  PathKind::ExternFlag);
                 } else {
                     dylibs.insert(fs::canonicalize(&loc).unwrap(),
                                   PathKind::ExternFlag);
@@ -705,16 +873,18 @@ impl<'a> Context<'a> {
             }
         };
 
-        // Extract the rlib/dylib pair.
+        // Extract the rlib/rmeta/dylib triple.
         let mut metadata = None;
         let rlib = self.extract_one(rlibs, "rlib", &mut metadata);
+        let rmeta = self.extract_one(rmetas, "rmeta", &mut metadata);
         let dylib = self.extract_one(dylibs, "dylib", &mut metadata);
 
-        if rlib.is_none() && dylib.is_none() { return None }
+        if rlib.is_none() && rmeta.is_none() && dylib.is_none() { return None }
         match metadata {
             Some(metadata) => Some(Library {
                 dylib: dylib,
                 rlib: rlib,
+                rmeta: rmeta,
                 metadata: metadata,
             }),
             None => None,
@@ -750,21 +920,95 @@ impl ArchiveMetadata {
     pub fn as_slice<'a>(&'a self) -> &'a [u8] { unsafe { &*self.data } }
 }
 
+// The metadata of a library, along with the modification time and length of
+// the file it was read from.
+struct CachedMetadata {
+    modified: SystemTime,
+    len: u64,
+    blob: Rc<MetadataBlob>,
+}
+
+// Keyed by target triple and library path.
+type MetadataCache = HashMap<(String, PathBuf), CachedMetadata>;
+
+// `None` while caching is off.
+thread_local!(static METADATA_CACHE: RefCell<Option<MetadataCache>> = RefCell::new(None));
+
+/// Keeps the metadata of every library read on this thread from now on, so
+/// that later compilations on the same thread don't read (and, for dylibs,
+/// inflate) it again unless the file changed. Used by the compile server,
+/// which runs one compilation after the other.
+pub fn enable_metadata_cache() {
+    METADATA_CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        if cache.is_none() {
+            *cache = Some(HashMap::new());
+        }
+    })
+}
+
 // Just a small wrapper to time how long reading metadata takes.
 fn get_metadata_section(target: &Target, filename: &Path)
                         -> Result<MetadataBlob, String> {
     let start = Instant::now();
-    let ret = get_metadata_section_imp(target, filename);
+    let ret = get_cached_metadata_section(target, filename);
     info!("reading {:?} => {:?}", filename.file_name().unwrap(),
           start.elapsed());
     return ret
 }
 
+fn get_cached_metadata_section(target: &Target, filename: &Path)
+                               -> Result<MetadataBlob, String> {
+    let enabled = METADATA_CACHE.with(|cache| cache.borrow().is_some());
+    if !enabled {
+        return get_metadata_section_imp(target, filename);
+    }
+    let stamp = fs::metadata(filename).and_then(|m| m.modified().map(|t| (t, m.len())));
+    let (modified, len) = match stamp {
+        Ok(stamp) => stamp,
+        Err(_) => return get_metadata_section_imp(target, filename),
+    };
+
+    let key = (target.llvm_target.clone(), filename.to_path_buf());
+    let cached = METADATA_CACHE.with(|cache| {
+        cache.borrow().as_ref().unwrap().get(&key).and_then(|entry| {
+            if entry.modified == modified && entry.len == len {
+                Some(entry.blob.clone())
+            } else {
+                None
+            }
+        })
+    });
+    if let Some(blob) = cached {
+        debug!("using cached metadata for {}", filename.display());
+        return Ok(MetadataShared(blob));
+    }
+
+    let blob = Rc::new(try!(get_metadata_section_imp(target, filename)));
+    METADATA_CACHE.with(|cache| {
+        cache.borrow_mut().as_mut().unwrap().insert(key, CachedMetadata {
+            modified: modified,
+            len: len,
+            blob: blob.clone(),
+        });
+    });
+    Ok(MetadataShared(blob))
+}
+
 fn get_metadata_section_imp(target: &Target, filename: &Path)
                             -> Result<MetadataBlob, String> {
     if !filename.exists() {
         return Err(format!("no such file: '{}'", filename.display()));
     }
+    if filename.file_name().unwrap().to_str().unwrap().ends_with(".rmeta") {
+        // Written by `--emit=metadata`: the file is the metadata itself.
+        let mut buf = Vec::new();
+        return match fs::File::open(filename).and_then(|mut f| f.read_to_end(&mut buf)) {
+            Ok(_) => Ok(MetadataFile(buf)),
+            Err(e) => Err(format!("failed to read rmeta metadata: '{}': {}",
+                                  filename.display(), e)),
+        };
+    }
     if filename.file_name().unwrap().to_str().unwrap().ends_with(".rlib") {
         // Use ArchiveRO for speed here, it's backed by LLVM and uses mmap
         // internally to read the file. We also avoid even using a memcpy by
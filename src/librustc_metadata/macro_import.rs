@@ -137,10 +137,10 @@ impl<'a, 'v> Visitor<'v> for MacroLoader<'a> {
 }
 
 impl<'a> MacroLoader<'a> {
-    fn load_macros<'b>(&mut self,
-                       vi: &ast::Item,
-                       import: Option<MacroSelection>,
-                       reexport: MacroSelection) {
+    fn load_macros(&mut self,
+                   vi: &ast::Item,
+                   import: Option<MacroSelection>,
+                   reexport: MacroSelection) {
         if let Some(sel) = import.as_ref() {
             if sel.is_empty() && reexport.is_empty() {
                 return;
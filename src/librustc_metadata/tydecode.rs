@@ -146,7 +146,30 @@ impl<'a,'tcx> TyDecoder<'a,'tcx> {
     pub fn parse_substs(&mut self) -> subst::Substs<'tcx> {
         let regions = self.parse_region_substs();
         let types = self.parse_vec_per_param_space(|this| this.parse_ty());
-        subst::Substs { types: types, regions: regions }
+        let consts = self.parse_vec_per_param_space(|this| this.parse_const_arg());
+        subst::Substs { types: types, regions: regions, consts: consts }
+    }
+
+    fn parse_const_arg(&mut self) -> subst::ConstArg {
+        match self.next() {
+            'c' => {
+                let n = self.parse_u64();
+                assert_eq!(self.next(), '|');
+                subst::ConstArg::Value(n)
+            }
+            'p' => subst::ConstArg::Param(self.parse_param_const()),
+            c => panic!("parse_const_arg: bad input `{}`", c)
+        }
+    }
+
+    fn parse_param_const(&mut self) -> ty::ParamConst {
+        assert_eq!(self.next(), '[');
+        let index = self.parse_u32();
+        assert_eq!(self.next(), '|');
+        let space = self.parse_param_space();
+        assert_eq!(self.next(), '|');
+        let name = token::intern(&self.parse_str(']'));
+        ty::ParamConst::new(space, index, name)
     }
 
     fn parse_region_substs(&mut self) -> subst::RegionSubsts {
@@ -368,6 +391,11 @@ impl<'a,'tcx> TyDecoder<'a,'tcx> {
                     None => tcx.mk_slice(t)
                 };
             }
+            'q' => {
+                let t = self.parse_ty();
+                let len = self.parse_param_const();
+                return tcx.mk_param_array(t, len);
+            }
             'v' => {
                 return tcx.mk_str();
             }
@@ -449,6 +477,13 @@ impl<'a,'tcx> TyDecoder<'a,'tcx> {
                 let name = token::intern(&self.parse_str(']'));
                 return tcx.mk_projection(trait_ref, name);
             }
+            'A' => {
+                assert_eq!(self.next(), '[');
+                let def_id = self.parse_def();
+                let substs = self.parse_substs();
+                assert_eq!(self.next(), ']');
+                return self.tcx.mk_anon(def_id, self.tcx.mk_substs(substs));
+            }
             'e' => {
                 return tcx.types.err;
             }
@@ -484,6 +519,17 @@ impl<'a,'tcx> TyDecoder<'a,'tcx> {
         };
     }
 
+    fn parse_u64(&mut self) -> u64 {
+        let mut n = 0;
+        loop {
+            let cur = self.peek();
+            if cur < '0' || cur > '9' { return n; }
+            self.pos = self.pos + 1;
+            n *= 10;
+            n += (cur as u64) - ('0' as u64);
+        };
+    }
+
     fn parse_u32(&mut self) -> u32 {
         let n = self.parse_uint();
         let m = n as u32;
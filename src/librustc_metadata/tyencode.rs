@@ -21,7 +21,7 @@ use middle::def_id::DefId;
 use middle::region;
 use middle::subst;
 use middle::subst::VecPerParamSpace;
-use middle::ty::ParamTy;
+use middle::ty::{ParamConst, ParamTy};
 use middle::ty::{self, Ty, TyCtxt};
 use rustc::util::nodemap::FnvHashMap;
 
@@ -151,6 +151,11 @@ pub fn enc_ty<'a, 'tcx>(w: &mut Cursor<Vec<u8>>, cx: &ctxt<'a, 'tcx>, t: Ty<'tcx
         ty::TyParam(ParamTy {space, idx, name}) => {
             write!(w, "p[{}|{}|{}]", idx, space.to_uint(), name);
         }
+        ty::TyParamArray(t, p) => {
+            write!(w, "q");
+            enc_ty(w, cx, t);
+            enc_param_const(w, p);
+        }
         ty::TyStruct(def, substs) => {
             write!(w, "a[{}|", (cx.ds)(def.did));
             enc_substs(w, cx, substs);
@@ -170,6 +175,11 @@ pub fn enc_ty<'a, 'tcx>(w: &mut Cursor<Vec<u8>>, cx: &ctxt<'a, 'tcx>, t: Ty<'tcx
             enc_trait_ref(w, cx, data.trait_ref);
             write!(w, "{}]", data.item_name);
         }
+        ty::TyAnon(def_id, substs) => {
+            write!(w, "A[{}|", (cx.ds)(def_id));
+            enc_substs(w, cx, substs);
+            write!(w, "]");
+        }
         ty::TyError => {
             write!(w, "e");
         }
@@ -249,6 +259,24 @@ pub fn enc_substs<'a, 'tcx>(w: &mut Cursor<Vec<u8>>, cx: &ctxt<'a, 'tcx>,
     enc_region_substs(w, cx, &substs.regions);
     enc_vec_per_param_space(w, cx, &substs.types,
                             |w, cx, &ty| enc_ty(w, cx, ty));
+    enc_vec_per_param_space(w, cx, &substs.consts,
+                            |w, _, &c| enc_const_arg(w, c));
+}
+
+fn enc_const_arg(w: &mut Cursor<Vec<u8>>, c: subst::ConstArg) {
+    match c {
+        subst::ConstArg::Value(v) => {
+            write!(w, "c{}|", v);
+        }
+        subst::ConstArg::Param(p) => {
+            write!(w, "p");
+            enc_param_const(w, p);
+        }
+    }
+}
+
+fn enc_param_const(w: &mut Cursor<Vec<u8>>, p: ParamConst) {
+    write!(w, "[{}|{}|{}]", p.idx, p.space.to_uint(), p.name);
 }
 
 fn enc_region_substs(w: &mut Cursor<Vec<u8>>, cx: &ctxt, substs: &subst::RegionSubsts) {
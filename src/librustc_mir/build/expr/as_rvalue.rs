@@ -149,6 +149,17 @@ impl<'a,'tcx> Builder<'a,'tcx> {
                           .map(|f| (f.name, unpack!(block = this.as_operand(block, f.expr))))
                           .collect();
 
+                if adt_def.is_union() {
+                    // Typeck only accepts union expressions that set
+                    // exactly one field, without a base; the other
+                    // fields share its storage.
+                    assert!(base.is_none() && fields_map.len() == 1);
+                    let (&field, operand) = fields_map.iter().next().unwrap();
+                    let kind = AggregateKind::Adt(adt_def, variant_index, substs,
+                                                  Some(field.index()));
+                    return block.and(Rvalue::Aggregate(kind, vec![operand.clone()]));
+                }
+
                 let field_names = this.hir.all_fields(adt_def, variant_index);
 
                 let fields = if let Some(FruInfo { base, field_types }) = base {
@@ -168,8 +179,8 @@ impl<'a,'tcx> Builder<'a,'tcx> {
                     field_names.iter().map(|n| fields_map[n].clone()).collect()
                 };
 
-                block.and(Rvalue::Aggregate(AggregateKind::Adt(adt_def, variant_index, substs),
-                                            fields))
+                let kind = AggregateKind::Adt(adt_def, variant_index, substs, None);
+                block.and(Rvalue::Aggregate(kind, fields))
             }
             ExprKind::Literal { .. } |
             ExprKind::Block { .. } |
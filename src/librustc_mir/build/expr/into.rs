@@ -244,11 +244,24 @@ impl<'a,'tcx> Builder<'a,'tcx> {
                     }
                     _ => false
                 };
+                let track_caller = match ty.sty {
+                    ty::TyFnDef(def_id, _, _) => this.hir.tcx().is_track_caller(def_id),
+                    _ => false
+                };
                 let fun = unpack!(block = this.as_operand(block, fun));
-                let args: Vec<_> =
+                let mut args: Vec<_> =
                     args.into_iter()
                         .map(|arg| unpack!(block = this.as_operand(block, arg)))
                         .collect();
+                if track_caller {
+                    // `#[track_caller]` functions take the location of the
+                    // call as an implicit trailing argument.
+                    args.push(Operand::Constant(Constant {
+                        span: expr_span,
+                        ty: this.hir.tcx().caller_location_ty(),
+                        literal: Literal::CallerLocation,
+                    }));
+                }
 
                 let success = this.cfg.start_new_block();
                 let cleanup = this.diverge_cleanup();
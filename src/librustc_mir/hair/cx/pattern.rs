@@ -124,7 +124,8 @@ impl<'patcx, 'cx, 'tcx> PatCx<'patcx, 'cx, 'tcx> {
                         },
 
                     ty::TySlice(..) |
-                    ty::TyArray(..) =>
+                    ty::TyArray(..) |
+                    ty::TyParamArray(..) =>
                         self.slice_or_array_pattern(pat.span, ty, prefix, slice, suffix),
 
                     ref sty =>
@@ -159,6 +160,7 @@ impl<'patcx, 'cx, 'tcx> PatCx<'patcx, 'cx, 'tcx> {
                     ty::TyRef(&r, _) => Some(r),
                     _ => None,
                 };
+                let bm = self.cx.tcx.tables.borrow().binding_mode(pat.id, bm);
                 let (mutability, mode) = match bm {
                     hir::BindByValue(hir::MutMutable) =>
                         (Mutability::Mut, BindingMode::ByValue),
@@ -236,11 +238,22 @@ impl<'patcx, 'cx, 'tcx> PatCx<'patcx, 'cx, 'tcx> {
 
         let ty = self.cx.tcx.node_id_to_type(pat.id);
 
-        Pattern {
+        let pattern = Pattern {
             span: pat.span,
             ty: ty,
             kind: Box::new(kind),
-        }
+        };
+
+        // Dereference whatever typeck dereferenced implicitly because of a
+        // default binding mode.
+        let tables = self.cx.tcx.tables.borrow();
+        tables.pat_adjustments(pat.id).iter().rev().fold(pattern, |subpattern, &ref_ty| {
+            Pattern {
+                span: pat.span,
+                ty: ref_ty,
+                kind: Box::new(PatternKind::Deref { subpattern: subpattern }),
+            }
+        })
     }
 
     fn to_patterns(&mut self, pats: &[P<hir::Pat>]) -> Vec<Pattern<'tcx>> {
@@ -278,6 +291,16 @@ impl<'patcx, 'cx, 'tcx> PatCx<'patcx, 'cx, 'tcx> {
                 }
             }
 
+            ty::TyParamArray(..) => {
+                // array of parameter length; typeck only accepts a lone `..`
+                assert!(prefix.is_empty() && suffix.is_empty());
+                PatternKind::Array {
+                    prefix: vec![],
+                    slice: self.to_opt_pattern(slice),
+                    suffix: vec![],
+                }
+            }
+
             _ => {
                 self.cx.tcx.sess.span_bug(span, "unexpanded macro or bad constant etc");
             }
@@ -0,0 +1,881 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! An interpreter for MIR, used by `-Z interpret=<fn>` to run a function of
+//! the crate being compiled instead of translating it.
+//!
+//! Values are dynamically typed; the types in the MIR are only consulted for
+//! the width of integers and to pick the impl of a trait method. Every local
+//! lives in a slot of its own, and a reference is a slot plus the path of
+//! field indices leading to the referent, so nothing depends on a memory
+//! layout. Integer arithmetic and casts between scalars are done by the
+//! functions constant evaluation uses, so they are checked and reported the
+//! same way.
+//!
+//! Whatever isn't supported is reported as an error at the statement that
+//! needed it: reading or writing statics (taking a reference to one is fine,
+//! which is all a panic does with its location), calling closures and trait
+//! objects, inline assembly, and calls to anything without MIR, which
+//! includes every function of other crates except those that panic.
+//! Destructors aren't run.
+
+use rustc::middle::const_eval::{self, ConstEvalErr, ConstVal, EvalHint};
+use rustc::middle::def_id::DefId;
+use rustc::middle::infer;
+use rustc::middle::subst::{Subst, Substs};
+use rustc::middle::traits;
+use rustc::middle::ty::{self, Ty, TyCtxt};
+use rustc::mir::mir_map::MirMap;
+use rustc::mir::repr::*;
+use syntax::ast;
+use syntax::codemap::{Span, DUMMY_SP};
+use syntax::parse::token::InternedString;
+
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::fmt;
+use std::rc::Rc;
+
+/// How many statements and terminators may be executed before the program
+/// is assumed not to terminate.
+const STEP_LIMIT: usize = 10_000_000;
+
+/// How deeply calls may nest; the interpreter itself recurses on calls.
+const CALL_DEPTH_LIMIT: usize = 1000;
+
+#[derive(Clone)]
+pub enum Value<'tcx> {
+    /// The contents of a slot that hasn't been written yet.
+    Undef,
+    Bool(bool),
+    /// Signed integers, sign-extended from their width.
+    Int(i64),
+    /// Unsigned integers and `char`s.
+    Uint(u64),
+    Float(f64),
+    /// A `&str`, which can't be dereferenced.
+    Str(InternedString),
+    /// Tuples and closures.
+    Tuple(Vec<Value<'tcx>>),
+    /// A struct or an enum: the index of the variant, and its fields.
+    Adt(usize, Vec<Value<'tcx>>),
+    Array(Vec<Value<'tcx>>),
+    Fn(DefId, &'tcx Substs<'tcx>),
+    /// References, raw pointers and boxes alike.
+    Ptr(Pointer<'tcx>),
+    /// What the slot of a static holds: the static can be referred to, but
+    /// its contents are opaque.
+    Static(DefId),
+}
+
+impl<'tcx> Value<'tcx> {
+    fn is_unit(&self) -> bool {
+        match *self {
+            Value::Undef => true,
+            Value::Tuple(ref fields) => fields.is_empty(),
+            _ => false,
+        }
+    }
+}
+
+fn write_list<'tcx>(f: &mut fmt::Formatter, values: &[Value<'tcx>]) -> fmt::Result {
+    for (i, value) in values.iter().enumerate() {
+        if i > 0 {
+            try!(write!(f, ", "));
+        }
+        try!(write!(f, "{}", value));
+    }
+    Ok(())
+}
+
+impl<'tcx> fmt::Display for Value<'tcx> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Value::Undef => write!(f, "<uninitialized>"),
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::Int(i) => write!(f, "{}", i),
+            Value::Uint(u) => write!(f, "{}", u),
+            Value::Float(x) => write!(f, "{}", x),
+            Value::Str(ref s) => write!(f, "{:?}", s),
+            Value::Tuple(ref fields) => {
+                try!(write!(f, "("));
+                try!(write_list(f, fields));
+                if fields.len() == 1 {
+                    try!(write!(f, ","));
+                }
+                write!(f, ")")
+            }
+            Value::Adt(variant, ref fields) => {
+                try!(write!(f, "#{}(", variant));
+                try!(write_list(f, fields));
+                write!(f, ")")
+            }
+            Value::Array(ref elements) => {
+                try!(write!(f, "["));
+                try!(write_list(f, elements));
+                write!(f, "]")
+            }
+            Value::Fn(..) => write!(f, "<fn>"),
+            Value::Static(..) => write!(f, "<static>"),
+            Value::Ptr(ref ptr) => {
+                if let Value::Static(..) = *ptr.slot.borrow() {
+                    return write!(f, "&<static>");
+                }
+                match ptr.read(DUMMY_SP) {
+                    Ok(value) => write!(f, "&{}", value),
+                    Err(_) => write!(f, "&<uninitialized>"),
+                }
+            }
+        }
+    }
+}
+
+type Slot<'tcx> = Rc<RefCell<Value<'tcx>>>;
+
+fn new_slot<'tcx>(value: Value<'tcx>) -> Slot<'tcx> {
+    Rc::new(RefCell::new(value))
+}
+
+/// A place a value can be read from or written to: a field (of a field...)
+/// of the value in a slot.
+#[derive(Clone)]
+pub struct Pointer<'tcx> {
+    slot: Slot<'tcx>,
+    path: Vec<usize>,
+}
+
+fn project<'v, 'tcx>(value: &'v Value<'tcx>, path: &[usize]) -> Option<&'v Value<'tcx>> {
+    match path.split_first() {
+        None => Some(value),
+        Some((&i, rest)) => match *value {
+            Value::Tuple(ref fields) |
+            Value::Adt(_, ref fields) |
+            Value::Array(ref fields) => fields.get(i).and_then(|field| project(field, rest)),
+            _ => None,
+        },
+    }
+}
+
+fn project_mut<'v, 'tcx>(value: &'v mut Value<'tcx>, path: &[usize])
+                         -> Option<&'v mut Value<'tcx>> {
+    match path.split_first() {
+        None => Some(value),
+        Some((&i, rest)) => match *value {
+            Value::Tuple(ref mut fields) |
+            Value::Adt(_, ref mut fields) |
+            Value::Array(ref mut fields) => {
+                fields.get_mut(i).and_then(|field| project_mut(field, rest))
+            }
+            _ => None,
+        },
+    }
+}
+
+impl<'tcx> Pointer<'tcx> {
+    fn to(slot: Slot<'tcx>) -> Pointer<'tcx> {
+        Pointer { slot: slot, path: vec![] }
+    }
+
+    fn field(mut self, i: usize) -> Pointer<'tcx> {
+        self.path.push(i);
+        self
+    }
+
+    fn read(&self, span: Span) -> InterpResult<Value<'tcx>> {
+        let slot = self.slot.borrow();
+        if let Value::Static(..) = *slot {
+            return unsupported(span, "reading a static".to_string());
+        }
+        match project(&slot, &self.path) {
+            Some(&Value::Undef) | None => Err(EvalError::new(span, ErrorKind::Undef)),
+            Some(value) => Ok(value.clone()),
+        }
+    }
+
+    fn write(&self, span: Span, value: Value<'tcx>) -> InterpResult<()> {
+        let mut slot = self.slot.borrow_mut();
+        if let Value::Static(..) = *slot {
+            return unsupported(span, "writing to a static".to_string());
+        }
+        match project_mut(&mut slot, &self.path) {
+            Some(place) => {
+                *place = value;
+                Ok(())
+            }
+            None => {
+                let msg = "initializing the fields of a value one at a time".to_string();
+                Err(EvalError::new(span, ErrorKind::Unsupported(msg)))
+            }
+        }
+    }
+}
+
+pub enum ErrorKind {
+    Unsupported(String),
+    /// Overflow or division by zero, as constant evaluation reports it.
+    Arith(ConstEvalErr),
+    IndexOutOfBounds { len: u64, index: u64 },
+    /// A panic, with its message if it has one that isn't formatted.
+    Panic(Option<String>),
+    Undef,
+    StepLimit,
+    CallDepthLimit,
+}
+
+impl ErrorKind {
+    pub fn description(&self) -> String {
+        match *self {
+            ErrorKind::Unsupported(ref what) => {
+                format!("{} is not supported by the interpreter", what)
+            }
+            ErrorKind::Arith(ref err) => err.description().into_owned(),
+            ErrorKind::IndexOutOfBounds { len, index } => {
+                format!("index out of bounds: the len is {} but the index is {}", len, index)
+            }
+            ErrorKind::Panic(Some(ref msg)) => format!("the program panicked: '{}'", msg),
+            ErrorKind::Panic(None) => "the program panicked".to_string(),
+            ErrorKind::Undef => "attempted to read an uninitialized value".to_string(),
+            ErrorKind::StepLimit => {
+                format!("the program ran for more than {} steps", STEP_LIMIT)
+            }
+            ErrorKind::CallDepthLimit => {
+                format!("calls nested more than {} deep", CALL_DEPTH_LIMIT)
+            }
+        }
+    }
+}
+
+pub struct EvalError {
+    pub span: Span,
+    pub kind: ErrorKind,
+    /// The calls that led to the error, innermost first: where each was made,
+    /// and the function called.
+    pub backtrace: Vec<(Span, DefId)>,
+}
+
+impl EvalError {
+    fn new(span: Span, kind: ErrorKind) -> EvalError {
+        EvalError { span: span, kind: kind, backtrace: vec![] }
+    }
+
+    pub fn report(&self, tcx: &TyCtxt) {
+        let mut err = tcx.sess.struct_span_err(self.span,
+            &format!("interpreted program failed: {}", self.kind.description()));
+        for &(span, def_id) in &self.backtrace {
+            err.span_note(span, &format!("inside the call to `{}` here",
+                                         tcx.item_path_str(def_id)));
+        }
+        err.emit();
+    }
+}
+
+pub type InterpResult<T> = Result<T, EvalError>;
+
+fn unsupported<T>(span: Span, what: String) -> InterpResult<T> {
+    Err(EvalError::new(span, ErrorKind::Unsupported(what)))
+}
+
+struct Frame<'a, 'tcx: 'a> {
+    mir: &'a Mir<'tcx>,
+    substs: &'tcx Substs<'tcx>,
+    args: Vec<Slot<'tcx>>,
+    vars: Vec<Slot<'tcx>>,
+    temps: Vec<Slot<'tcx>>,
+    ret: Slot<'tcx>,
+}
+
+pub struct Interpreter<'a, 'tcx: 'a> {
+    tcx: &'a TyCtxt<'tcx>,
+    mir_map: &'a MirMap<'tcx>,
+    steps: usize,
+    depth: usize,
+}
+
+/// Runs the function of the local crate whose path is `name` (e.g. `main`
+/// or `tests::fib`), reporting what goes wrong as an error. What it returns
+/// is printed, unless it's `()`.
+pub fn run_named<'tcx>(tcx: &TyCtxt<'tcx>, mir_map: &MirMap<'tcx>, name: &str) {
+    let found = mir_map.map.keys()
+                           .map(|&id| tcx.map.local_def_id(id))
+                           .find(|&def_id| tcx.item_path_str(def_id) == name);
+    let def_id = match found {
+        Some(def_id) => def_id,
+        None => {
+            tcx.sess.err(&format!("cannot interpret `{}`: no such function", name));
+            return;
+        }
+    };
+    let node_id = tcx.map.as_local_node_id(def_id).unwrap();
+    if !mir_map.map[&node_id].arg_decls.is_empty() {
+        tcx.sess.span_err(tcx.map.span(node_id),
+                          &format!("cannot interpret `{}`: it takes arguments", name));
+        return;
+    }
+    // There is nothing to substitute for the parameters of a generic
+    // function, nor of the impl or trait a method is in.
+    let generics = &tcx.lookup_item_type(def_id).generics;
+    if !generics.types.is_empty() || !generics.consts.is_empty() {
+        tcx.sess.span_err(tcx.map.span(node_id),
+                          &format!("cannot interpret `{}`: it is generic", name));
+        return;
+    }
+
+    let mut interpreter = Interpreter::new(tcx, mir_map);
+    match interpreter.run(def_id) {
+        Ok(value) => {
+            if !value.is_unit() {
+                println!("{}", value);
+            }
+        }
+        Err(err) => err.report(tcx),
+    }
+}
+
+impl<'a, 'tcx> Interpreter<'a, 'tcx> {
+    pub fn new(tcx: &'a TyCtxt<'tcx>, mir_map: &'a MirMap<'tcx>) -> Interpreter<'a, 'tcx> {
+        Interpreter { tcx: tcx, mir_map: mir_map, steps: 0, depth: 0 }
+    }
+
+    /// Calls `def_id`, which must be a function of this crate that takes no
+    /// arguments and isn't generic, and returns what it returns.
+    pub fn run(&mut self, def_id: DefId) -> InterpResult<Value<'tcx>> {
+        let substs = self.tcx.mk_substs(Substs::empty().erase_regions());
+        self.call(DUMMY_SP, def_id, substs, vec![])
+    }
+
+    fn call(&mut self,
+            span: Span,
+            def_id: DefId,
+            substs: &'tcx Substs<'tcx>,
+            args: Vec<Value<'tcx>>)
+            -> InterpResult<Value<'tcx>> {
+        let (def_id, substs) = try!(self.resolve(span, def_id, substs));
+        let mir_map = self.mir_map;
+        let mir = match self.tcx.map.as_local_node_id(def_id).and_then(|id| mir_map.map.get(&id)) {
+            Some(mir) => mir,
+            None => {
+                return unsupported(span, format!("calling `{}`, which has no MIR,",
+                                                 self.tcx.item_path_str(def_id)));
+            }
+        };
+        if args.len() != mir.arg_decls.len() {
+            return unsupported(span, format!("calling `{}` through the `Fn` traits",
+                                             self.tcx.item_path_str(def_id)));
+        }
+        if self.depth == CALL_DEPTH_LIMIT {
+            return Err(EvalError::new(span, ErrorKind::CallDepthLimit));
+        }
+
+        let frame = Frame {
+            mir: mir,
+            substs: substs,
+            args: args.into_iter().map(new_slot).collect(),
+            vars: mir.var_decls.iter().map(|_| new_slot(Value::Undef)).collect(),
+            temps: mir.temp_decls.iter().map(|_| new_slot(Value::Undef)).collect(),
+            ret: new_slot(Value::Undef),
+        };
+        self.depth += 1;
+        let result = self.run_frame(&frame);
+        self.depth -= 1;
+        result.map_err(|mut err| {
+            if span != DUMMY_SP {
+                err.backtrace.push((span, def_id));
+            }
+            err
+        })
+    }
+
+    /// Finds the method of the impl that a call to a trait method refers to.
+    /// Other functions are returned as they are.
+    fn resolve(&self, span: Span, def_id: DefId, substs: &'tcx Substs<'tcx>)
+               -> InterpResult<(DefId, &'tcx Substs<'tcx>)> {
+        // Only trait methods have a `Self` type to be resolved.
+        if substs.self_ty().is_none() {
+            return Ok((def_id, substs));
+        }
+        let tcx = self.tcx;
+        let trait_id = tcx.impl_or_trait_item(def_id).container().id();
+        let trait_ref = ty::Binder(substs.to_trait_ref(tcx, trait_id));
+        let trait_ref = tcx.erase_regions(&trait_ref);
+
+        let infcx = infer::normalizing_infer_ctxt(tcx, &tcx.tables);
+        let mut selcx = traits::SelectionContext::new(&infcx);
+        let obligation =
+            traits::Obligation::new(traits::ObligationCause::misc(span, ast::DUMMY_NODE_ID),
+                                    trait_ref.to_poly_trait_predicate());
+        let selection = match selcx.select(&obligation) {
+            Ok(Some(selection)) => selection,
+            _ => {
+                return unsupported(span, format!("calling `{}` for `{:?}`",
+                                                 tcx.item_path_str(def_id), trait_ref));
+            }
+        };
+        let mut fulfill_cx = traits::FulfillmentContext::new();
+        let vtable = selection.map(|predicate| {
+            fulfill_cx.register_predicate_obligation(&infcx, predicate);
+        });
+        let vtable = infer::drain_fulfillment_cx_or_panic(span, &infcx, &mut fulfill_cx, &vtable);
+
+        match vtable {
+            traits::VtableImpl(data) => {
+                let impl_substs = tcx.mk_substs(data.substs.with_method_from(substs));
+                let method = tcx.get_impl_method(data.impl_def_id,
+                                                 impl_substs,
+                                                 tcx.item_name(def_id));
+                Ok((method.method.def_id, method.substs))
+            }
+            _ => {
+                unsupported(span, format!("calling `{}` other than through an impl",
+                                          tcx.item_path_str(def_id)))
+            }
+        }
+    }
+
+    fn is_panic_fn(&self, def_id: DefId) -> bool {
+        let lang_items = &self.tcx.lang_items;
+        if Some(def_id) == lang_items.panic_fn() ||
+           Some(def_id) == lang_items.panic_bounds_check_fn() ||
+           Some(def_id) == lang_items.panic_fmt() {
+            return true;
+        }
+        // What `panic!` expands to, `std::rt::begin_unwind{,_fmt}`.
+        let name = self.tcx.item_name(def_id).as_str();
+        !def_id.is_local() && (name == "begin_unwind" || name == "begin_unwind_fmt")
+    }
+
+    fn step(&mut self, span: Span) -> InterpResult<()> {
+        self.steps += 1;
+        if self.steps > STEP_LIMIT {
+            return Err(EvalError::new(span, ErrorKind::StepLimit));
+        }
+        Ok(())
+    }
+
+    fn run_frame(&mut self, frame: &Frame<'a, 'tcx>) -> InterpResult<Value<'tcx>> {
+        let mut bb = START_BLOCK;
+        loop {
+            let data = frame.mir.basic_block_data(bb);
+            for statement in &data.statements {
+                try!(self.step(statement.span));
+                match statement.kind {
+                    StatementKind::Assign(ref lvalue, ref rvalue) => {
+                        let value = try!(self.eval_rvalue(frame, statement.span, rvalue));
+                        let ptr = try!(self.eval_lvalue(frame, statement.span, lvalue));
+                        try!(ptr.write(statement.span, value));
+                    }
+                }
+            }
+
+            // Terminators don't have spans; the last statement of the block
+            // is usually what led to it.
+            let span = data.statements.last().map_or(frame.mir.span, |s| s.span);
+            try!(self.step(span));
+            bb = match *data.terminator() {
+                Terminator::Goto { target } => target,
+                Terminator::If { ref cond, targets: (then_bb, else_bb) } => {
+                    match try!(self.eval_operand(frame, span, cond)) {
+                        Value::Bool(true) => then_bb,
+                        Value::Bool(false) => else_bb,
+                        _ => return unsupported(span, "branching on a non-`bool`".to_string()),
+                    }
+                }
+                Terminator::Switch { ref discr, ref targets, .. } => {
+                    let ptr = try!(self.eval_lvalue(frame, span, discr));
+                    match try!(ptr.read(span)) {
+                        Value::Adt(variant, _) => targets[variant],
+                        _ => return unsupported(span, "matching on a non-enum".to_string()),
+                    }
+                }
+                Terminator::SwitchInt { ref discr, ref values, ref targets, .. } => {
+                    let ptr = try!(self.eval_lvalue(frame, span, discr));
+                    let discr = try!(ptr.read(span));
+                    let mut target = targets[values.len()];
+                    for (i, value) in values.iter().enumerate() {
+                        let value = try!(self.const_val(span, value));
+                        if compare(&discr, &value) == Some(Ordering::Equal) {
+                            target = targets[i];
+                            break;
+                        }
+                    }
+                    target
+                }
+                Terminator::Resume => return unsupported(span, "unwinding".to_string()),
+                Terminator::Return => return Ok(frame.ret.borrow().clone()),
+                Terminator::Drop { target, .. } => target,
+                Terminator::Call { ref func, ref args, ref destination, .. } => {
+                    let span = match *func {
+                        Operand::Constant(ref constant) => constant.span,
+                        Operand::Consume(_) => span,
+                    };
+                    let (def_id, substs) = match try!(self.eval_operand(frame, span, func)) {
+                        Value::Fn(def_id, substs) => (def_id, substs),
+                        _ => return unsupported(span, "calling a closure".to_string()),
+                    };
+                    if self.is_panic_fn(def_id) {
+                        // Only the message is of interest; the location is a
+                        // static, and the span of the `panic!` says as much.
+                        let msg = match args.first().map(|a| self.eval_operand(frame, span, a)) {
+                            Some(Ok(Value::Str(msg))) => Some(msg.to_string()),
+                            _ => None,
+                        };
+                        let span = self.tcx.sess.codemap().source_callsite(span);
+                        return Err(EvalError::new(span, ErrorKind::Panic(msg)));
+                    }
+                    let mut arg_values = Vec::with_capacity(args.len());
+                    for arg in args {
+                        arg_values.push(try!(self.eval_operand(frame, span, arg)));
+                    }
+                    let result = try!(self.call(span, def_id, substs, arg_values));
+                    match *destination {
+                        Some((ref lvalue, target)) => {
+                            let ptr = try!(self.eval_lvalue(frame, span, lvalue));
+                            try!(ptr.write(span, result));
+                            target
+                        }
+                        None => {
+                            return unsupported(span,
+                                               "returning from a diverging function".to_string());
+                        }
+                    }
+                }
+            };
+        }
+    }
+
+    fn monomorphize(&self, frame: &Frame<'a, 'tcx>, ty: Ty<'tcx>) -> Ty<'tcx> {
+        self.tcx.erase_regions(&ty.subst(self.tcx, frame.substs))
+    }
+
+    fn eval_lvalue(&mut self, frame: &Frame<'a, 'tcx>, span: Span, lvalue: &Lvalue<'tcx>)
+                   -> InterpResult<Pointer<'tcx>> {
+        Ok(match *lvalue {
+            Lvalue::Var(i) => Pointer::to(frame.vars[i as usize].clone()),
+            Lvalue::Temp(i) => Pointer::to(frame.temps[i as usize].clone()),
+            Lvalue::Arg(i) => Pointer::to(frame.args[i as usize].clone()),
+            Lvalue::ReturnPointer => Pointer::to(frame.ret.clone()),
+            Lvalue::Static(def_id) => Pointer::to(new_slot(Value::Static(def_id))),
+            Lvalue::Projection(ref proj) => {
+                let base = try!(self.eval_lvalue(frame, span, &proj.base));
+                match proj.elem {
+                    ProjectionElem::Deref => match try!(base.read(span)) {
+                        Value::Ptr(ptr) => ptr,
+                        _ => return unsupported(span, "dereferencing a `&str`".to_string()),
+                    },
+                    ProjectionElem::Field(field, _) => base.field(field.index()),
+                    ProjectionElem::Downcast(..) => base,
+                    ProjectionElem::Index(ref index) => {
+                        let index = match try!(self.eval_operand(frame, span, index)) {
+                            Value::Uint(index) => index,
+                            _ => return unsupported(span, "a non-`usize` index".to_string()),
+                        };
+                        let len = try!(self.len(span, &base));
+                        if index >= len {
+                            let kind = ErrorKind::IndexOutOfBounds { len: len, index: index };
+                            return Err(EvalError::new(span, kind));
+                        }
+                        base.field(index as usize)
+                    }
+                    ProjectionElem::ConstantIndex { offset, from_end, .. } => {
+                        let len = try!(self.len(span, &base));
+                        let index = if from_end { len - offset as u64 } else { offset as u64 };
+                        base.field(index as usize)
+                    }
+                }
+            }
+        })
+    }
+
+    fn len(&self, span: Span, ptr: &Pointer<'tcx>) -> InterpResult<u64> {
+        match try!(ptr.read(span)) {
+            Value::Array(ref elements) => Ok(elements.len() as u64),
+            Value::Str(ref s) => Ok(s.len() as u64),
+            _ => unsupported(span, "the length of a non-array".to_string()),
+        }
+    }
+
+    fn eval_operand(&mut self, frame: &Frame<'a, 'tcx>, span: Span, operand: &Operand<'tcx>)
+                    -> InterpResult<Value<'tcx>> {
+        match *operand {
+            Operand::Consume(ref lvalue) => try!(self.eval_lvalue(frame, span, lvalue)).read(span),
+            Operand::Constant(ref constant) => self.eval_constant(frame, constant),
+        }
+    }
+
+    fn eval_constant(&self, frame: &Frame<'a, 'tcx>, constant: &Constant<'tcx>)
+                     -> InterpResult<Value<'tcx>> {
+        let tcx = self.tcx;
+        let span = constant.span;
+        match constant.literal {
+            Literal::Item { def_id, substs } => {
+                if let ty::TyFnDef(..) = constant.ty.sty {
+                    let substs = substs.subst(tcx, frame.substs).erase_regions();
+                    return Ok(Value::Fn(def_id, tcx.mk_substs(substs)));
+                }
+                let expr = match const_eval::lookup_const_by_id(tcx, def_id, None, None) {
+                    Some(expr) => expr,
+                    None => {
+                        return unsupported(span, format!("using the constant `{}`",
+                                                         tcx.item_path_str(def_id)));
+                    }
+                };
+                match const_eval::eval_const_expr_partial(tcx, expr,
+                                                          EvalHint::ExprTypeChecked, None) {
+                    Ok(val) => self.const_val(span, &val),
+                    Err(err) => {
+                        unsupported(span, format!("using the constant `{}` ({})",
+                                                  tcx.item_path_str(def_id),
+                                                  err.description()))
+                    }
+                }
+            }
+            Literal::Value { ref value } => self.const_val(span, value),
+            Literal::CallerLocation => unsupported(span, "`#[track_caller]`".to_string()),
+        }
+    }
+
+    fn const_val(&self, span: Span, val: &ConstVal) -> InterpResult<Value<'tcx>> {
+        Ok(match *val {
+            ConstVal::Float(f) => Value::Float(f),
+            ConstVal::Int(i) => Value::Int(i),
+            ConstVal::Uint(u) => Value::Uint(u),
+            ConstVal::Str(ref s) => Value::Str(s.clone()),
+            ConstVal::Bool(b) => Value::Bool(b),
+            ConstVal::Function(def_id) => {
+                Value::Fn(def_id, self.tcx.mk_substs(Substs::empty().erase_regions()))
+            }
+            _ => return unsupported(span, format!("a constant {}", val.description())),
+        })
+    }
+
+    fn eval_rvalue(&mut self, frame: &Frame<'a, 'tcx>, span: Span, rvalue: &Rvalue<'tcx>)
+                   -> InterpResult<Value<'tcx>> {
+        match *rvalue {
+            Rvalue::Use(ref operand) => self.eval_operand(frame, span, operand),
+            Rvalue::Repeat(ref operand, ref count) => {
+                let value = try!(self.eval_operand(frame, span, operand));
+                let count = match count.value {
+                    ConstVal::Uint(count) => count,
+                    _ => return unsupported(span, "a non-`usize` repeat count".to_string()),
+                };
+                Ok(Value::Array(vec![value; count as usize]))
+            }
+            Rvalue::Ref(_, _, ref lvalue) => {
+                Ok(Value::Ptr(try!(self.eval_lvalue(frame, span, lvalue))))
+            }
+            Rvalue::Len(ref lvalue) => {
+                let ptr = try!(self.eval_lvalue(frame, span, lvalue));
+                Ok(Value::Uint(try!(self.len(span, &ptr))))
+            }
+            Rvalue::Cast(CastKind::Misc, ref operand, ty) => {
+                let from = self.monomorphize(frame, frame.mir.operand_ty(self.tcx, operand));
+                let to = self.monomorphize(frame, ty);
+                let value = try!(self.eval_operand(frame, span, operand));
+                self.cast(span, value, from, to)
+            }
+            Rvalue::Cast(_, ref operand, _) => self.eval_operand(frame, span, operand),
+            Rvalue::BinaryOp(op, ref lhs, ref rhs) => {
+                let ty = self.monomorphize(frame, frame.mir.operand_ty(self.tcx, lhs));
+                let lhs = try!(self.eval_operand(frame, span, lhs));
+                let rhs = try!(self.eval_operand(frame, span, rhs));
+                self.binary_op(span, op, lhs, rhs, ty)
+            }
+            Rvalue::UnaryOp(op, ref operand) => {
+                let ty = self.monomorphize(frame, frame.mir.operand_ty(self.tcx, operand));
+                let value = try!(self.eval_operand(frame, span, operand));
+                self.unary_op(span, op, value, ty)
+            }
+            Rvalue::Box(_) => Ok(Value::Ptr(Pointer::to(new_slot(Value::Undef)))),
+            Rvalue::Aggregate(ref kind, ref operands) => {
+                let mut fields = Vec::with_capacity(operands.len());
+                for operand in operands {
+                    fields.push(try!(self.eval_operand(frame, span, operand)));
+                }
+                Ok(match *kind {
+                    AggregateKind::Vec => Value::Array(fields),
+                    AggregateKind::Tuple | AggregateKind::Closure(..) => Value::Tuple(fields),
+                    AggregateKind::Adt(adt_def, variant, _, Some(active_field)) => {
+                        // Only the active field of a union holds a value;
+                        // reading any other one is a read of undef.
+                        let len = adt_def.variants[variant].fields.len();
+                        let mut all_fields = vec![Value::Undef; len];
+                        all_fields[active_field] = fields.pop().unwrap();
+                        Value::Adt(variant, all_fields)
+                    }
+                    AggregateKind::Adt(_, variant, _, None) => Value::Adt(variant, fields),
+                })
+            }
+            Rvalue::Slice { .. } => unsupported(span, "slice patterns".to_string()),
+            Rvalue::InlineAsm(..) => unsupported(span, "inline assembly".to_string()),
+        }
+    }
+
+    /// The integer type constant evaluation should check arithmetic on
+    /// values of type `ty` against: one of a signed and an unsigned type, or
+    /// neither if `ty` isn't an integer type.
+    fn int_tys(&self, ty: Ty<'tcx>) -> (Option<const_eval::IntTy>, Option<const_eval::UintTy>) {
+        match ty.sty {
+            ty::TyInt(t) => (Some(const_eval::IntTy::from(self.tcx, t)), None),
+            ty::TyUint(t) => (None, Some(const_eval::UintTy::from(self.tcx, t))),
+            _ => (None, None),
+        }
+    }
+
+    fn cast(&self, span: Span, value: Value<'tcx>, from: Ty<'tcx>, to: Ty<'tcx>)
+            -> InterpResult<Value<'tcx>> {
+        let cannot_cast = || unsupported(span, format!("a cast from `{}` to `{}`", from, to));
+        let val = match value {
+            Value::Int(i) => ConstVal::Int(i),
+            Value::Uint(u) => ConstVal::Uint(u),
+            Value::Bool(b) => ConstVal::Bool(b),
+            Value::Float(f) => ConstVal::Float(f),
+            Value::Adt(variant, _) => match from.sty {
+                ty::TyEnum(adt_def, _) => ConstVal::Uint(adt_def.variants[variant].disr_val),
+                _ => return cannot_cast(),
+            },
+            // Pointer casts leave the pointer as it is, but its address
+            // isn't known.
+            Value::Ptr(_) => return match to.sty {
+                ty::TyInt(_) | ty::TyUint(_) => cannot_cast(),
+                _ => Ok(value),
+            },
+            _ => return cannot_cast(),
+        };
+        match (&to.sty, val) {
+            // Only `u8` can be cast to `char`, so there is nothing to truncate.
+            (&ty::TyChar, ConstVal::Uint(u)) => Ok(Value::Uint(u)),
+            (_, val) => match const_eval::cast_const(self.tcx, val, to) {
+                Ok(val) => self.const_val(span, &val),
+                Err(_) => cannot_cast(),
+            },
+        }
+    }
+
+    fn binary_op(&self, span: Span, op: BinOp, lhs: Value<'tcx>, rhs: Value<'tcx>, ty: Ty<'tcx>)
+                 -> InterpResult<Value<'tcx>> {
+        let ordering = compare(&lhs, &rhs);
+        let result = match op {
+            BinOp::Eq => Some(ordering == Some(Ordering::Equal)),
+            BinOp::Ne => Some(ordering != Some(Ordering::Equal)),
+            BinOp::Lt => Some(ordering == Some(Ordering::Less)),
+            BinOp::Le => Some(ordering == Some(Ordering::Less) ||
+                              ordering == Some(Ordering::Equal)),
+            BinOp::Gt => Some(ordering == Some(Ordering::Greater)),
+            BinOp::Ge => Some(ordering == Some(Ordering::Greater) ||
+                              ordering == Some(Ordering::Equal)),
+            _ => None,
+        };
+        if let Some(result) = result {
+            return match (&lhs, &rhs) {
+                (&Value::Float(_), &Value::Float(_)) => Ok(Value::Bool(result)),
+                _ if ordering.is_some() => Ok(Value::Bool(result)),
+                _ => unsupported(span, format!("comparing values of type `{}`", ty)),
+            };
+        }
+
+        match (lhs, rhs) {
+            (Value::Bool(a), Value::Bool(b)) => match op {
+                BinOp::BitAnd => Ok(Value::Bool(a & b)),
+                BinOp::BitOr => Ok(Value::Bool(a | b)),
+                BinOp::BitXor => Ok(Value::Bool(a ^ b)),
+                _ => unsupported(span, format!("`{:?}` on `bool`", op)),
+            },
+            (Value::Float(a), Value::Float(b)) => match op {
+                BinOp::Add => Ok(Value::Float(a + b)),
+                BinOp::Sub => Ok(Value::Float(a - b)),
+                BinOp::Mul => Ok(Value::Float(a * b)),
+                BinOp::Div => Ok(Value::Float(a / b)),
+                BinOp::Rem => Ok(Value::Float(a % b)),
+                _ => unsupported(span, format!("`{:?}` on `{}`", op, ty)),
+            },
+            (lhs, rhs) => self.int_op(span, op, lhs, rhs, ty),
+        }
+    }
+
+    fn int_op(&self, span: Span, op: BinOp, lhs: Value<'tcx>, rhs: Value<'tcx>, ty: Ty<'tcx>)
+              -> InterpResult<Value<'tcx>> {
+        let (int_ty, uint_ty) = self.int_tys(ty);
+        if int_ty.is_none() && uint_ty.is_none() {
+            return unsupported(span, format!("`{:?}` on `{}`", op, ty));
+        }
+        let result = match (lhs, rhs) {
+            (Value::Int(a), Value::Int(b)) => match op {
+                BinOp::Add => const_eval::const_int_checked_add(a, b, span, int_ty),
+                BinOp::Sub => const_eval::const_int_checked_sub(a, b, span, int_ty),
+                BinOp::Mul => const_eval::const_int_checked_mul(a, b, span, int_ty),
+                BinOp::Div => const_eval::const_int_checked_div(a, b, span, int_ty),
+                BinOp::Rem => const_eval::const_int_checked_rem(a, b, span, int_ty),
+                BinOp::Shl => const_eval::const_int_checked_shl(a, b, span, int_ty),
+                BinOp::Shr => const_eval::const_int_checked_shr(a, b, span, int_ty),
+                BinOp::BitXor => Ok(ConstVal::Int(a ^ b)),
+                BinOp::BitAnd => Ok(ConstVal::Int(a & b)),
+                BinOp::BitOr => Ok(ConstVal::Int(a | b)),
+                _ => return unsupported(span, format!("`{:?}` on `{}`", op, ty)),
+            },
+            (Value::Uint(a), Value::Uint(b)) => match op {
+                BinOp::Add => const_eval::const_uint_checked_add(a, b, span, uint_ty),
+                BinOp::Sub => const_eval::const_uint_checked_sub(a, b, span, uint_ty),
+                BinOp::Mul => const_eval::const_uint_checked_mul(a, b, span, uint_ty),
+                BinOp::Div => const_eval::const_uint_checked_div(a, b, span, uint_ty),
+                BinOp::Rem => const_eval::const_uint_checked_rem(a, b, span, uint_ty),
+                BinOp::Shl => const_eval::const_uint_checked_shl(a, b, span, uint_ty),
+                BinOp::Shr => const_eval::const_uint_checked_shr(a, b, span, uint_ty),
+                BinOp::BitXor => Ok(ConstVal::Uint(a ^ b)),
+                BinOp::BitAnd => Ok(ConstVal::Uint(a & b)),
+                BinOp::BitOr => Ok(ConstVal::Uint(a | b)),
+                _ => return unsupported(span, format!("`{:?}` on `{}`", op, ty)),
+            },
+            // Shifts can have any integer type on the right.
+            (Value::Int(a), Value::Uint(b)) => match op {
+                BinOp::Shl => const_eval::const_int_checked_shl_via_uint(a, b, span, int_ty),
+                BinOp::Shr => const_eval::const_int_checked_shr_via_uint(a, b, span, int_ty),
+                _ => return unsupported(span, format!("`{:?}` on `{}`", op, ty)),
+            },
+            (Value::Uint(a), Value::Int(b)) => match op {
+                BinOp::Shl => const_eval::const_uint_checked_shl_via_int(a, b, span, uint_ty),
+                BinOp::Shr => const_eval::const_uint_checked_shr_via_int(a, b, span, uint_ty),
+                _ => return unsupported(span, format!("`{:?}` on `{}`", op, ty)),
+            },
+            _ => return unsupported(span, format!("`{:?}` on `{}`", op, ty)),
+        };
+        match result {
+            Ok(val) => self.const_val(span, &val),
+            Err(err) => Err(EvalError::new(span, ErrorKind::Arith(err))),
+        }
+    }
+
+    fn unary_op(&self, span: Span, op: UnOp, value: Value<'tcx>, ty: Ty<'tcx>)
+                -> InterpResult<Value<'tcx>> {
+        let (int_ty, uint_ty) = self.int_tys(ty);
+        let result = match (op, value) {
+            (UnOp::Not, Value::Bool(b)) => return Ok(Value::Bool(!b)),
+            (UnOp::Not, Value::Int(i)) => return Ok(Value::Int(!i)),
+            (UnOp::Not, Value::Uint(u)) => Ok(const_eval::const_uint_not(u, uint_ty)),
+            (UnOp::Neg, Value::Int(i)) => const_eval::const_int_checked_neg(i, span, int_ty),
+            (UnOp::Neg, Value::Float(f)) => return Ok(Value::Float(-f)),
+            (op, _) => return unsupported(span, format!("`{:?}` on `{}`", op, ty)),
+        };
+        match result {
+            Ok(val) => self.const_val(span, &val),
+            Err(err) => Err(EvalError::new(span, ErrorKind::Arith(err))),
+        }
+    }
+}
+
+/// Orders two scalars of the same type; anything else is incomparable.
+fn compare<'tcx>(lhs: &Value<'tcx>, rhs: &Value<'tcx>) -> Option<Ordering> {
+    match (lhs, rhs) {
+        (&Value::Bool(a), &Value::Bool(b)) => a.partial_cmp(&b),
+        (&Value::Int(a), &Value::Int(b)) => a.partial_cmp(&b),
+        (&Value::Uint(a), &Value::Uint(b)) => a.partial_cmp(&b),
+        (&Value::Float(a), &Value::Float(b)) => a.partial_cmp(&b),
+        (&Value::Str(ref a), &Value::Str(ref b)) => a[..].partial_cmp(&b[..]),
+        _ => None,
+    }
+}
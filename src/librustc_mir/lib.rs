@@ -29,12 +29,12 @@ extern crate graphviz as dot;
 extern crate rustc;
 extern crate rustc_data_structures;
 extern crate rustc_front;
-extern crate rustc_back;
 extern crate syntax;
 
 pub mod build;
 pub mod graphviz;
 mod hair;
+pub mod interpret;
 pub mod mir_map;
 pub mod pretty;
 pub mod transform;
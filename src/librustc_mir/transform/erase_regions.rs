@@ -92,7 +92,7 @@ impl<'a, 'tcx> MutVisitor<'tcx> for EraseRegionsVisitor<'a, 'tcx> {
 
             Rvalue::Aggregate(AggregateKind::Vec, _) |
             Rvalue::Aggregate(AggregateKind::Tuple, _) => {},
-            Rvalue::Aggregate(AggregateKind::Adt(_, _, ref mut substs), _) =>
+            Rvalue::Aggregate(AggregateKind::Adt(_, _, ref mut substs, _), _) =>
                 *substs = self.tcx.mk_substs(self.tcx.erase_regions(*substs)),
             Rvalue::Aggregate(AggregateKind::Closure(def_id, ref mut closure_substs), _) => {
                 let cloned = Box::new(closure_substs.clone());
@@ -113,7 +113,8 @@ impl<'a, 'tcx> MutVisitor<'tcx> for EraseRegionsVisitor<'a, 'tcx> {
             Literal::Item { ref mut substs, .. } => {
                 *substs = self.tcx.mk_substs(self.tcx.erase_regions(substs));
             }
-            Literal::Value { .. } => { /* nothing to do */ }
+            Literal::Value { .. } |
+            Literal::CallerLocation => { /* nothing to do */ }
         }
         self.super_constant(constant);
     }
@@ -121,7 +122,11 @@ impl<'a, 'tcx> MutVisitor<'tcx> for EraseRegionsVisitor<'a, 'tcx> {
 
 pub struct EraseRegions;
 
-impl Pass for EraseRegions {}
+impl Pass for EraseRegions {
+    fn name(&self) -> &'static str {
+        "erase-regions"
+    }
+}
 
 impl<'tcx> MirPass<'tcx> for EraseRegions {
     fn run_pass(&mut self, tcx: &TyCtxt<'tcx>, _: NodeId, mir: &mut Mir<'tcx>) {
@@ -49,4 +49,8 @@ impl<'tcx> MirPass<'tcx> for NoLandingPads {
     }
 }
 
-impl Pass for NoLandingPads {}
+impl Pass for NoLandingPads {
+    fn name(&self) -> &'static str {
+        "no-landing-pads"
+    }
+}
@@ -60,7 +60,11 @@ impl<'tcx> MirPass<'tcx> for RemoveDeadBlocks {
     }
 }
 
-impl Pass for RemoveDeadBlocks {}
+impl Pass for RemoveDeadBlocks {
+    fn name(&self) -> &'static str {
+        "remove-dead-blocks"
+    }
+}
 
 /// Mass removal of basic blocks to keep the ID-remapping cheap.
 fn retain_basic_blocks(mir: &mut Mir, keep: &BitVector) {
@@ -114,4 +114,8 @@ impl<'tcx> MirPass<'tcx> for SimplifyCfg {
     }
 }
 
-impl Pass for SimplifyCfg {}
+impl Pass for SimplifyCfg {
+    fn name(&self) -> &'static str {
+        "simplify-cfg"
+    }
+}
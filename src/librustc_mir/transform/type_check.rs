@@ -421,14 +421,18 @@ impl<'a, 'tcx> TypeChecker<'a, 'tcx> {
             Terminator::Call { ref func, ref args, ref destination, .. } => {
                 let func_ty = mir.operand_ty(tcx, func);
                 debug!("check_terminator: call, func_ty={:?}", func_ty);
-                let func_ty = match func_ty.sty {
-                    ty::TyFnDef(_, _, func_ty) | ty::TyFnPtr(func_ty) => func_ty,
+                let (func_ty, track_caller) = match func_ty.sty {
+                    ty::TyFnDef(def_id, _, func_ty) => (func_ty, tcx.is_track_caller(def_id)),
+                    ty::TyFnPtr(func_ty) => (func_ty, false),
                     _ => {
                         span_mirbug!(self, term, "call to non-function {:?}", func_ty);
                         return;
                     }
                 };
-                let sig = tcx.erase_late_bound_regions(&func_ty.sig);
+                let mut sig = tcx.erase_late_bound_regions(&func_ty.sig);
+                if track_caller {
+                    sig.inputs.push(tcx.caller_location_ty());
+                }
                 self.check_call_dest(mir, term, &sig, destination);
 
                 if self.is_box_free(func) {
@@ -597,4 +601,8 @@ impl<'tcx> MirPass<'tcx> for TypeckMir {
     }
 }
 
-impl Pass for TypeckMir {}
+impl Pass for TypeckMir {
+    fn name(&self) -> &'static str {
+        "typeck-mir"
+    }
+}
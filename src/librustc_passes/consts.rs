@@ -20,6 +20,10 @@
 //       - doesn't contain a struct literal or a call to an enum variant / struct constructor where
 //           - the type of the struct/enum has a dtor
 //
+// Every expression is also qualified for promotion to a constant
+// (`tcx.const_qualif_map`); for those that can't be, the subexpression
+// responsible is recorded in `tcx.not_const_causes`.
+//
 // Rules Enforced Elsewhere:
 // - It's not possible to take the address of a static item with unsafe interior. This is enforced
 // by borrowck::gather_loans
@@ -38,7 +42,7 @@ use rustc::middle::mem_categorization::Categorization;
 use rustc::middle::traits;
 use rustc::middle::ty::{self, Ty, TyCtxt};
 use rustc::util::nodemap::NodeMap;
-use rustc::middle::const_qualif::ConstQualif;
+use rustc::middle::const_qualif::{ConstQualif, NotConstCause, NotConstReason};
 use rustc::lint::builtin::CONST_ERR;
 
 use rustc_front::hir::{self, PatKind};
@@ -68,6 +72,8 @@ struct CheckCrateVisitor<'a, 'tcx: 'a> {
     tcx: &'a TyCtxt<'tcx>,
     mode: Mode,
     qualif: ConstQualif,
+    // What made `qualif` `NOT_CONST`, if it is.
+    cause: Option<NotConstCause>,
     rvalue_borrows: NodeMap<hir::Mutability>
 }
 
@@ -75,12 +81,14 @@ impl<'a, 'tcx> CheckCrateVisitor<'a, 'tcx> {
     fn with_mode<F, R>(&mut self, mode: Mode, f: F) -> R where
         F: FnOnce(&mut CheckCrateVisitor<'a, 'tcx>) -> R,
     {
-        let (old_mode, old_qualif) = (self.mode, self.qualif);
+        let (old_mode, old_qualif, old_cause) = (self.mode, self.qualif, self.cause);
         self.mode = mode;
         self.qualif = ConstQualif::empty();
+        self.cause = None;
         let r = f(self);
         self.mode = old_mode;
         self.qualif = old_qualif;
+        self.cause = old_cause;
         r
     }
 
@@ -160,6 +168,13 @@ impl<'a, 'tcx> CheckCrateVisitor<'a, 'tcx> {
         self.qualif = self.qualif | qualif;
     }
 
+    fn add_not_const(&mut self, span: Span, reason: NotConstReason) {
+        self.add_qualif(ConstQualif::NOT_CONST);
+        if self.cause.is_none() {
+            self.cause = Some(NotConstCause { span: span, reason: reason });
+        }
+    }
+
     /// Returns true if the call is to a const fn or method.
     fn handle_const_fn_call(&mut self,
                             expr: &hir::Expr,
@@ -360,7 +375,7 @@ impl<'a, 'tcx, 'v> Visitor<'v> for CheckCrateVisitor<'a, 'tcx> {
                 hir::StmtExpr(_, _) => {},
                 hir::StmtSemi(_, _) => {},
             }
-            self.add_qualif(ConstQualif::NOT_CONST);
+            self.add_not_const(stmt.span, NotConstReason::Statement);
             // anything else should have been caught by check_const_fn
             assert_eq!(self.mode, Mode::Var);
         }
@@ -369,6 +384,7 @@ impl<'a, 'tcx, 'v> Visitor<'v> for CheckCrateVisitor<'a, 'tcx> {
 
     fn visit_expr(&mut self, ex: &hir::Expr) {
         let mut outer = self.qualif;
+        let mut outer_cause = self.cause.take();
         self.qualif = ConstQualif::empty();
 
         let node_ty = self.tcx.node_id_to_type(ex.id);
@@ -454,6 +470,10 @@ impl<'a, 'tcx, 'v> Visitor<'v> for CheckCrateVisitor<'a, 'tcx> {
                 let tc = node_ty.type_contents(self.tcx);
                 if self.qualif.intersects(ConstQualif::MUTABLE_MEM) && tc.interior_unsafe() {
                     outer = outer | ConstQualif::NOT_CONST;
+                    outer_cause = outer_cause.or(Some(NotConstCause {
+                        span: ex.span,
+                        reason: NotConstReason::InteriorMutableBorrow,
+                    }));
                     if self.mode != Mode::Var {
                         span_err!(self.tcx.sess, ex.span, E0492,
                                   "cannot borrow a constant which contains \
@@ -472,6 +492,10 @@ impl<'a, 'tcx, 'v> Visitor<'v> for CheckCrateVisitor<'a, 'tcx> {
                 if self.qualif.intersects(ConstQualif::NON_ZERO_SIZED) {
                     if self.mode == Mode::Var {
                         outer = outer | ConstQualif::NOT_CONST;
+                        outer_cause = outer_cause.or(Some(NotConstCause {
+                            span: ex.span,
+                            reason: NotConstReason::MutableBorrow,
+                        }));
                         self.add_qualif(ConstQualif::MUTABLE_MEM);
                     } else {
                         span_err!(self.tcx.sess, ex.span, E0017,
@@ -486,8 +510,12 @@ impl<'a, 'tcx, 'v> Visitor<'v> for CheckCrateVisitor<'a, 'tcx> {
             None => {}
         }
         self.tcx.const_qualif_map.borrow_mut().insert(ex.id, self.qualif);
+        if let Some(cause) = self.cause {
+            self.tcx.not_const_causes.borrow_mut().insert(ex.id, cause);
+        }
         // Don't propagate certain flags.
         self.qualif = outer | (self.qualif - ConstQualif::HAS_STATIC_BORROWS);
+        self.cause = outer_cause.or(self.cause);
     }
 }
 
@@ -517,14 +545,14 @@ fn check_expr<'a, 'tcx>(v: &mut CheckCrateVisitor<'a, 'tcx>,
         hir::ExprUnary(..) |
         hir::ExprBinary(..) |
         hir::ExprIndex(..) if v.tcx.tables.borrow().method_map.contains_key(&method_call) => {
-            v.add_qualif(ConstQualif::NOT_CONST);
+            v.add_not_const(e.span, NotConstReason::OverloadedOperator);
             if v.mode != Mode::Var {
                 span_err!(v.tcx.sess, e.span, E0011,
                             "user-defined operators are not allowed in {}s", v.msg());
             }
         }
         hir::ExprBox(_) => {
-            v.add_qualif(ConstQualif::NOT_CONST);
+            v.add_not_const(e.span, NotConstReason::Allocation);
             if v.mode != Mode::Var {
                 span_err!(v.tcx.sess, e.span, E0010,
                           "allocations are not allowed in {}s", v.msg());
@@ -535,7 +563,7 @@ fn check_expr<'a, 'tcx>(v: &mut CheckCrateVisitor<'a, 'tcx>,
                 ty::TyRawPtr(_) => {
                     assert!(op == hir::UnDeref);
 
-                    v.add_qualif(ConstQualif::NOT_CONST);
+                    v.add_not_const(e.span, NotConstReason::RawPtrDeref);
                     if v.mode != Mode::Var {
                         span_err!(v.tcx.sess, e.span, E0396,
                                   "raw pointers cannot be dereferenced in {}s", v.msg());
@@ -551,7 +579,7 @@ fn check_expr<'a, 'tcx>(v: &mut CheckCrateVisitor<'a, 'tcx>,
                             op.node == hir::BiLe || op.node == hir::BiLt ||
                             op.node == hir::BiGe || op.node == hir::BiGt);
 
-                    v.add_qualif(ConstQualif::NOT_CONST);
+                    v.add_not_const(e.span, NotConstReason::RawPtrComparison);
                     if v.mode != Mode::Var {
                         span_err!(v.tcx.sess, e.span, E0395,
                                   "raw pointers cannot be compared in {}s", v.msg());
@@ -565,7 +593,7 @@ fn check_expr<'a, 'tcx>(v: &mut CheckCrateVisitor<'a, 'tcx>,
             match v.tcx.cast_kinds.borrow().get(&from.id) {
                 None => v.tcx.sess.span_bug(e.span, "no kind for cast"),
                 Some(&CastKind::PtrAddrCast) | Some(&CastKind::FnPtrAddrCast) => {
-                    v.add_qualif(ConstQualif::NOT_CONST);
+                    v.add_not_const(e.span, NotConstReason::RawPtrToIntCast);
                     if v.mode != Mode::Var {
                         span_err!(v.tcx.sess, e.span, E0018,
                                   "raw pointers cannot be cast to integers in {}s", v.msg());
@@ -599,7 +627,7 @@ fn check_expr<'a, 'tcx>(v: &mut CheckCrateVisitor<'a, 'tcx>,
                                 "{}s cannot refer to other statics, insert \
                                  an intermediate constant instead", v.msg());
                         }
-                        Mode::Var => v.add_qualif(ConstQualif::NOT_CONST)
+                        Mode::Var => v.add_not_const(e.span, NotConstReason::StaticRef)
                     }
                 }
                 Some(Def::Const(did)) |
@@ -609,14 +637,18 @@ fn check_expr<'a, 'tcx>(v: &mut CheckCrateVisitor<'a, 'tcx>,
                                                                        None) {
                         let inner = v.global_expr(Mode::Const, expr);
                         v.add_qualif(inner);
+                        if inner.intersects(ConstQualif::NOT_CONST) && v.cause.is_none() {
+                            v.cause = v.tcx.not_const_causes.borrow().get(&expr.id).cloned();
+                        }
                     }
                 }
                 Some(Def::Local(..)) if v.mode == Mode::ConstFn => {
                     // Sadly, we can't determine whether the types are zero-sized.
-                    v.add_qualif(ConstQualif::NOT_CONST | ConstQualif::NON_ZERO_SIZED);
+                    v.add_not_const(e.span, NotConstReason::LocalVariable);
+                    v.add_qualif(ConstQualif::NON_ZERO_SIZED);
                 }
                 def => {
-                    v.add_qualif(ConstQualif::NOT_CONST);
+                    v.add_not_const(e.span, NotConstReason::NonConstPath);
                     if v.mode != Mode::Var {
                         debug!("(checking const) found bad def: {:?}", def);
                         span_err!(v.tcx.sess, e.span, E0014,
@@ -659,7 +691,7 @@ fn check_expr<'a, 'tcx>(v: &mut CheckCrateVisitor<'a, 'tcx>,
                 _ => false
             };
             if !is_const {
-                v.add_qualif(ConstQualif::NOT_CONST);
+                v.add_not_const(e.span, NotConstReason::NonConstFnCall);
                 if v.mode != Mode::Var {
                     // FIXME(#24111) Remove this check when const fn stabilizes
                     let (msg, note) =
@@ -692,7 +724,7 @@ fn check_expr<'a, 'tcx>(v: &mut CheckCrateVisitor<'a, 'tcx>,
                 ty::TraitContainer(_) => false
             };
             if !is_const {
-                v.add_qualif(ConstQualif::NOT_CONST);
+                v.add_not_const(e.span, NotConstReason::NonConstMethodCall);
                 if v.mode != Mode::Var {
                     span_err!(v.tcx.sess, e.span, E0378,
                               "method calls in {}s are limited to \
@@ -722,7 +754,7 @@ fn check_expr<'a, 'tcx>(v: &mut CheckCrateVisitor<'a, 'tcx>,
             if v.tcx.with_freevars(e.id, |fv| !fv.is_empty()) {
                 assert!(v.mode == Mode::Var,
                         "global closures can't capture anything");
-                v.add_qualif(ConstQualif::NOT_CONST);
+                v.add_not_const(e.span, NotConstReason::CapturingClosure);
             }
         }
 
@@ -751,7 +783,7 @@ fn check_expr<'a, 'tcx>(v: &mut CheckCrateVisitor<'a, 'tcx>,
         hir::ExprAssign(..) |
         hir::ExprAssignOp(..) |
         hir::ExprInlineAsm(_) => {
-            v.add_qualif(ConstQualif::NOT_CONST);
+            v.add_not_const(e.span, NotConstReason::Unsupported);
             if v.mode != Mode::Var {
                 span_err!(v.tcx.sess, e.span, E0019,
                           "{} contains unimplemented expression type", v.msg());
@@ -774,7 +806,7 @@ fn check_adjustments<'a, 'tcx>(v: &mut CheckCrateVisitor<'a, 'tcx>, e: &hir::Exp
             if (0..autoderefs as u32).any(|autoderef| {
                     v.tcx.is_overloaded_autoderef(e.id, autoderef)
             }) {
-                v.add_qualif(ConstQualif::NOT_CONST);
+                v.add_not_const(e.span, NotConstReason::OverloadedDeref);
                 if v.mode != Mode::Var {
                     span_err!(v.tcx.sess, e.span, E0400,
                               "user-defined dereference operators are not allowed in {}s",
@@ -790,6 +822,7 @@ pub fn check_crate(tcx: &TyCtxt) {
         tcx: tcx,
         mode: Mode::Var,
         qualif: ConstQualif::NOT_CONST,
+        cause: None,
         rvalue_borrows: NodeMap()
     });
     tcx.sess.abort_if_errors();
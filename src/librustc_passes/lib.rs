@@ -27,7 +27,6 @@
 #![feature(staged_api)]
 #![feature(rustc_private)]
 
-extern crate core;
 extern crate rustc;
 extern crate rustc_front;
 
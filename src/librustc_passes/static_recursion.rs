@@ -14,6 +14,7 @@
 use rustc::dep_graph::DepNode;
 use rustc::front::map as ast_map;
 use rustc::session::{Session, CompileResult};
+use rustc::middle::const_eval;
 use rustc::middle::def::{Def, DefMap};
 use rustc::util::nodemap::NodeMap;
 
@@ -28,6 +29,7 @@ use std::cell::RefCell;
 struct CheckCrateVisitor<'a, 'ast: 'a> {
     sess: &'a Session,
     def_map: &'a DefMap,
+    type_relative_items: &'a const_eval::TypeRelativeItems,
     ast_map: &'a ast_map::Map<'ast>,
     // `discriminant_map` is a cache that associates the `NodeId`s of local
     // variant definitions with the discriminant expression that applies to
@@ -92,6 +94,7 @@ impl<'a, 'ast: 'a> Visitor<'ast> for CheckCrateVisitor<'a, 'ast> {
 
 pub fn check_crate<'ast>(sess: &Session,
                          def_map: &DefMap,
+                         type_relative_items: &const_eval::TypeRelativeItems,
                          ast_map: &ast_map::Map<'ast>)
                          -> CompileResult {
     let _task = ast_map.dep_graph.in_task(DepNode::CheckStaticRecursion);
@@ -99,6 +102,7 @@ pub fn check_crate<'ast>(sess: &Session,
     let mut visitor = CheckCrateVisitor {
         sess: sess,
         def_map: def_map,
+        type_relative_items: type_relative_items,
         ast_map: ast_map,
         discriminant_map: RefCell::new(NodeMap()),
     };
@@ -112,6 +116,7 @@ struct CheckItemRecursionVisitor<'a, 'ast: 'a> {
     sess: &'a Session,
     ast_map: &'a ast_map::Map<'ast>,
     def_map: &'a DefMap,
+    type_relative_items: &'a const_eval::TypeRelativeItems,
     discriminant_map: &'a RefCell<NodeMap<Option<&'ast hir::Expr>>>,
     idstack: Vec<ast::NodeId>,
 }
@@ -124,6 +129,7 @@ impl<'a, 'ast: 'a> CheckItemRecursionVisitor<'a, 'ast> {
             sess: v.sess,
             ast_map: v.ast_map,
             def_map: v.def_map,
+            type_relative_items: v.type_relative_items,
             discriminant_map: &v.discriminant_map,
             idstack: Vec::new(),
         }
@@ -239,8 +245,18 @@ impl<'a, 'ast: 'a> Visitor<'ast> for CheckItemRecursionVisitor<'a, 'ast> {
 
     fn visit_expr(&mut self, e: &'ast hir::Expr) {
         match e.node {
-            hir::ExprPath(..) => {
-                match self.def_map.get(&e.id).map(|d| d.base_def) {
+            hir::ExprPath(_, ref path) => {
+                let def = match self.def_map.get(&e.id) {
+                    // Constant evaluation resolves `Foo::BAR` to an associated
+                    // const or const fn of `Foo` before type checking does.
+                    Some(&resolution) if resolution.depth != 0 => {
+                        self.type_relative_items.resolve(self.ast_map, self.def_map,
+                                                         e.id, path, resolution)
+                    }
+                    Some(resolution) => Some(resolution.base_def),
+                    None => None,
+                };
+                match def {
                     Some(Def::Static(def_id, _)) |
                     Some(Def::AssociatedConst(def_id)) |
                     Some(Def::Const(def_id)) => {
@@ -262,6 +278,28 @@ impl<'a, 'ast: 'a> Visitor<'ast> for CheckItemRecursionVisitor<'a, 'ast> {
                             }
                         }
                     }
+                    // A const fn is evaluated along with the constants calling it.
+                    Some(Def::Fn(def_id)) |
+                    Some(Def::Method(def_id)) => {
+                        if let Some(node_id) = self.ast_map.as_local_node_id(def_id) {
+                            match self.ast_map.get(node_id) {
+                                ast_map::NodeItem(item) => {
+                                    if let hir::ItemFn(_, _, hir::Constness::Const, _, _, _) =
+                                        item.node {
+                                        self.visit_item(item);
+                                    }
+                                }
+                                ast_map::NodeImplItem(item) => {
+                                    if let hir::ImplItemKind::Method(ref sig, _) = item.node {
+                                        if sig.constness == hir::Constness::Const {
+                                            self.visit_impl_item(item);
+                                        }
+                                    }
+                                }
+                                _ => {}
+                            }
+                        }
+                    }
                     // For variants, we only want to check expressions that
                     // affect the specific variant used, but we need to check
                     // the whole enum definition to see what expression that
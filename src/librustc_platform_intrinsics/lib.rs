@@ -15,7 +15,6 @@
 #![feature(staged_api, rustc_private)]
 #![cfg_attr(not(stage0), deny(warnings))]
 
-extern crate rustc_llvm as llvm;
 extern crate rustc;
 
 use rustc::middle::ty::TyCtxt;
@@ -0,0 +1,194 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Hosts the plugins written against the `rustc_plugin_abi` facade, by
+//! wrapping the passes they register in ordinary lint and MIR passes.
+
+use registry::Registry;
+
+use rustc::lint::{self, LateContext, LateLintPass, LintArray, LintContext, LintPass, Lint};
+use rustc::middle::ty::TyCtxt;
+use rustc::mir::repr::Mir;
+use rustc::mir::transform::{MirPass, Pass};
+use rustc_front::hir;
+use rustc_plugin_abi as abi;
+
+use syntax::ast::NodeId;
+use syntax::codemap::{self, BytePos, Span};
+
+/// Calls a plugin's registrar, adding the passes it registers to `reg`.
+pub fn register(reg: &mut Registry, fun: abi::RegistrarFn) {
+    let registrar = abi::Registrar {
+        version: abi::ABI_VERSION,
+        cx: reg as *mut Registry as *mut abi::RawRegistrarContext,
+        register_lint_pass: register_lint_pass,
+        register_mir_pass: register_mir_pass,
+    };
+    fun(&registrar);
+}
+
+extern "C" fn register_lint_pass(cx: *mut abi::RawRegistrarContext, pass: abi::RawLintPass) {
+    let reg = unsafe { &mut *(cx as *mut Registry) };
+    reg.register_late_lint_pass(Box::new(AbiLintPass::new(pass)));
+}
+
+extern "C" fn register_mir_pass(cx: *mut abi::RawRegistrarContext, pass: abi::RawMirPass) {
+    let reg = unsafe { &mut *(cx as *mut Registry) };
+    reg.register_mir_pass(Box::new(AbiMirPass { raw: pass }));
+}
+
+fn to_abi_span(span: Span) -> abi::Span {
+    abi::Span { lo: span.lo.0, hi: span.hi.0 }
+}
+
+fn from_abi_span(span: abi::Span) -> Span {
+    codemap::mk_sp(BytePos(span.lo), BytePos(span.hi))
+}
+
+struct AbiLintPass {
+    raw: abi::RawLintPass,
+    lints: LintArray,
+}
+
+impl AbiLintPass {
+    fn new(raw: abi::RawLintPass) -> AbiLintPass {
+        let decls = unsafe { ::std::slice::from_raw_parts(raw.lints, raw.num_lints) };
+        // Plugins are never unloaded, so their lints can live forever, just
+        // like the `static`s that `declare_lint!` produces.
+        let lints = decls.iter().map(|decl| {
+            let lint = Box::new(Lint {
+                name: decl.name.as_str(),
+                default_level: match decl.default_level {
+                    abi::Level::Allow => lint::Allow,
+                    abi::Level::Warn => lint::Warn,
+                    abi::Level::Deny => lint::Deny,
+                    abi::Level::Forbid => lint::Forbid,
+                },
+                desc: decl.desc.as_str(),
+                edition_level: None,
+            });
+            unsafe { &*Box::into_raw(lint) }
+        }).collect::<Vec<&'static Lint>>();
+
+        AbiLintPass {
+            raw: raw,
+            lints: unsafe { &*Box::into_raw(lints.into_boxed_slice()) },
+        }
+    }
+}
+
+impl Drop for AbiLintPass {
+    fn drop(&mut self) {
+        (self.raw.drop)(self.raw.data);
+    }
+}
+
+impl LintPass for AbiLintPass {
+    fn get_lints(&self) -> LintArray {
+        self.lints
+    }
+}
+
+/// What the `abi::RawLintContext` handed to a lint pass points to.
+struct LintCallContext<'a, 'b: 'a, 'tcx: 'b> {
+    cx: &'a LateContext<'b, 'tcx>,
+    lints: LintArray,
+}
+
+extern "C" fn span_lint(cx: *mut abi::RawLintContext,
+                        lint: usize,
+                        span: abi::Span,
+                        msg: abi::Str) {
+    let call_cx = unsafe { &*(cx as *const LintCallContext) };
+    let span = from_abi_span(span);
+    match call_cx.lints.get(lint) {
+        Some(&lint) => call_cx.cx.span_lint(lint, span, msg.as_str()),
+        None => {
+            call_cx.cx.sess().span_err(span, &format!("plugin emitted undeclared lint #{}: {}",
+                                                      lint, msg.as_str()));
+        }
+    }
+}
+
+impl LateLintPass for AbiLintPass {
+    fn check_item(&mut self, cx: &LateContext, it: &hir::Item) {
+        let kind = match it.node {
+            hir::ItemFn(..) => abi::ItemKind::Fn,
+            hir::ItemStruct(..) => abi::ItemKind::Struct,
+            hir::ItemEnum(..) => abi::ItemKind::Enum,
+            hir::ItemTrait(..) => abi::ItemKind::Trait,
+            hir::ItemImpl(..) | hir::ItemDefaultImpl(..) => abi::ItemKind::Impl,
+            hir::ItemMod(..) => abi::ItemKind::Mod,
+            hir::ItemStatic(..) => abi::ItemKind::Static,
+            hir::ItemConst(..) => abi::ItemKind::Const,
+            hir::ItemTy(..) => abi::ItemKind::Type,
+            _ => abi::ItemKind::Other,
+        };
+        let name = it.name.as_str();
+        let item = abi::Item {
+            kind: kind,
+            name: abi::Str::new(&name),
+            span: to_abi_span(it.span),
+        };
+
+        let call_cx = LintCallContext { cx: cx, lints: self.lints };
+        let lint_cx = abi::LintContext {
+            cx: &call_cx as *const LintCallContext as *mut abi::RawLintContext,
+            span_lint: span_lint,
+        };
+        if !(self.raw.check_item)(self.raw.data, &lint_cx, &item) {
+            cx.sess().span_err(it.span, &format!("a plugin lint pass panicked while checking `{}`",
+                                                 name));
+        }
+    }
+}
+
+struct AbiMirPass {
+    raw: abi::RawMirPass,
+}
+
+impl Drop for AbiMirPass {
+    fn drop(&mut self) {
+        (self.raw.drop)(self.raw.data);
+    }
+}
+
+impl Pass for AbiMirPass {
+    fn name(&self) -> &'static str {
+        self.raw.name.as_str()
+    }
+}
+
+extern "C" fn span_warn(cx: *mut abi::RawMirContext, span: abi::Span, msg: abi::Str) {
+    let tcx = unsafe { &*(cx as *const TyCtxt) };
+    tcx.sess.span_warn(from_abi_span(span), msg.as_str());
+}
+
+impl<'tcx> MirPass<'tcx> for AbiMirPass {
+    fn run_pass(&mut self, tcx: &TyCtxt<'tcx>, id: NodeId, mir: &mut Mir<'tcx>) {
+        let path = tcx.map.path_to_string(id);
+        let mir_fn = abi::MirFn {
+            path: abi::Str::new(&path),
+            span: to_abi_span(mir.span),
+            basic_blocks: mir.basic_blocks.len(),
+            args: mir.arg_decls.len(),
+            vars: mir.var_decls.len(),
+            temps: mir.temp_decls.len(),
+        };
+        let mir_cx = abi::MirContext {
+            cx: tcx as *const TyCtxt as *mut abi::RawMirContext,
+            span_warn: span_warn,
+        };
+        if !(self.raw.run)(self.raw.data, &mir_cx, &mir_fn) {
+            tcx.sess.span_err(mir.span, &format!("plugin MIR pass `{}` panicked on `{}`",
+                                                 self.raw.name.as_str(), path));
+        }
+    }
+}
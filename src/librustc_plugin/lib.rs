@@ -49,6 +49,10 @@
 //!
 //! See the [Plugins Chapter](../../book/compiler-plugins.html) of the book
 //! for more examples.
+//!
+//! Plugins which only need lints or MIR passes can instead be written
+//! against the stable `rustc_plugin_abi` facade, which does not break
+//! along with the compiler's internals; see the `abi` module.
 
 #![crate_name = "rustc_plugin"]
 #![unstable(feature = "rustc_private", issue = "27812")]
@@ -63,19 +67,18 @@
 #![feature(rustc_diagnostic_macros)]
 #![feature(rustc_private)]
 
-#[macro_use] extern crate log;
 #[macro_use] extern crate syntax;
-#[macro_use] #[no_link] extern crate rustc_bitflags;
 
 extern crate rustc;
 extern crate rustc_back;
 extern crate rustc_front;
 extern crate rustc_metadata;
-extern crate rustc_mir;
+extern crate rustc_plugin_abi;
 
 pub use self::registry::Registry;
 
 pub mod diagnostics;
 pub mod registry;
 pub mod load;
+pub mod abi;
 pub mod build;
@@ -13,6 +13,7 @@
 use rustc::session::Session;
 use rustc_metadata::creader::CrateReader;
 use rustc_metadata::cstore::CStore;
+use rustc_plugin_abi as abi;
 use registry::Registry;
 
 use std::borrow::ToOwned;
@@ -33,6 +34,12 @@ pub struct PluginRegistrar {
     pub args: Vec<P<ast::MetaItem>>,
 }
 
+/// The registrar of a plugin built against the `rustc_plugin_abi` facade;
+/// see `abi::register`.
+pub struct AbiPluginRegistrar {
+    pub fun: abi::RegistrarFn,
+}
+
 struct PluginLoader<'a> {
     sess: &'a Session,
     reader: CrateReader<'a>,
@@ -81,6 +88,68 @@ pub fn load_plugins(sess: &Session, cstore: &CStore, krate: &ast::Crate,
     loader.plugins
 }
 
+/// Dynamically load the plugins given with `-Z abi-plugins`. These are
+/// found by path rather than as crates, as they need not have been built
+/// by this compiler; instead, they must speak the same plugin ABI.
+pub fn load_abi_plugins(sess: &Session) -> Vec<AbiPluginRegistrar> {
+    use rustc_back::dynamic_lib::DynamicLibrary;
+
+    let mut registrars = vec![];
+    for path in &sess.opts.debugging_opts.abi_plugins {
+        if !sess.parse_sess.io.borrow().may_load_plugins() {
+            sess.struct_err(&format!("plugin `{}` may not be loaded", path))
+                .note("the session's I/O policy doesn't allow loading compiler plugins")
+                .emit();
+            continue;
+        }
+
+        let full_path = env::current_dir().unwrap().join(path);
+        let lib = match DynamicLibrary::open(Some(&full_path)) {
+            Ok(lib) => lib,
+            Err(err) => {
+                sess.err(&format!("couldn't load plugin `{}`: {}", path, err));
+                continue;
+            }
+        };
+
+        unsafe {
+            let version = match lib.symbol(abi::VERSION_SYMBOL) {
+                Ok(version) => mem::transmute::<*mut u8, abi::VersionFn>(version)(),
+                Err(_) => {
+                    sess.struct_err(&format!("`{}` is not a plugin built against \
+                                              `rustc_plugin_abi`", path))
+                        .help("plugins built against `rustc_plugin_abi` must use \
+                               `declare_plugin!`")
+                        .emit();
+                    continue;
+                }
+            };
+            if version != abi::ABI_VERSION {
+                sess.struct_err(&format!("plugin `{}` was built for version {} of the \
+                                          plugin ABI", path, version))
+                    .note(&format!("this compiler supports version {}", abi::ABI_VERSION))
+                    .emit();
+                continue;
+            }
+
+            let fun = match lib.symbol(abi::REGISTRAR_SYMBOL) {
+                Ok(fun) => mem::transmute::<*mut u8, abi::RegistrarFn>(fun),
+                Err(err) => {
+                    sess.err(&format!("couldn't load plugin `{}`: {}", path, err));
+                    continue;
+                }
+            };
+
+            // As with other plugins, never unload the library: the passes it
+            // registers live as long as the compiler.
+            mem::forget(lib);
+
+            registrars.push(AbiPluginRegistrar { fun: fun });
+        }
+    }
+    registrars
+}
+
 impl<'a> PluginLoader<'a> {
     fn new(sess: &'a Session, cstore: &'a CStore) -> PluginLoader<'a> {
         PluginLoader {
@@ -91,6 +160,13 @@ impl<'a> PluginLoader<'a> {
     }
 
     fn load_plugin(&mut self, span: Span, name: &str, args: Vec<P<ast::MetaItem>>) {
+        if !self.sess.parse_sess.io.borrow().may_load_plugins() {
+            self.sess.struct_span_err(span, &format!("plugin `{}` may not be loaded", name))
+                .note("the session's I/O policy doesn't allow loading compiler plugins")
+                .emit();
+            return;
+        }
+
         let registrar = self.reader.find_plugin_registrar(span, name);
 
         if let Some((lib, symbol)) = registrar {
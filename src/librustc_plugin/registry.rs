@@ -14,6 +14,7 @@ use rustc::lint::{EarlyLintPassObject, LateLintPassObject, LintId, Lint};
 use rustc::session::Session;
 
 use rustc::mir::transform::MirMapPass;
+use rustc::middle::traits::ObligationObserverObject;
 
 use syntax::ext::base::{SyntaxExtension, NamedSyntaxExtension, NormalTT};
 use syntax::ext::base::{IdentTT, MultiModifier, MultiDecorator};
@@ -58,6 +59,9 @@ pub struct Registry<'a> {
     #[doc(hidden)]
     pub mir_passes: Vec<Box<for<'pcx> MirMapPass<'pcx>>>,
 
+    #[doc(hidden)]
+    pub obligation_observers: Vec<ObligationObserverObject>,
+
     #[doc(hidden)]
     pub lint_groups: HashMap<&'static str, Vec<LintId>>,
 
@@ -82,6 +86,7 @@ impl<'a> Registry<'a> {
             llvm_passes: vec!(),
             attributes: vec!(),
             mir_passes: Vec::new(),
+            obligation_observers: Vec::new(),
         }
     }
 
@@ -145,6 +150,12 @@ impl<'a> Registry<'a> {
         self.mir_passes.push(pass);
     }
 
+    /// Register an observer of the trait obligations that come up while
+    /// type checking.
+    pub fn register_obligation_observer(&mut self, observer: ObligationObserverObject) {
+        self.obligation_observers.push(observer);
+    }
+
     /// Register an LLVM pass.
     ///
     /// Registration with LLVM itself is handled through static C++ objects with
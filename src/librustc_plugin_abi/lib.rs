@@ -0,0 +1,334 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A small, versioned C ABI for compiler plugins.
+//!
+//! Ordinary plugins link against `rustc` itself and break whenever its
+//! internals change. Plugins written against this crate instead only see
+//! the `#[repr(C)]` types below, and talk to the compiler through function
+//! pointers, so a plugin keeps working with any compiler that speaks the
+//! same `ABI_VERSION`. This crate depends on nothing but `std`, and is only
+//! ever linked statically, so it can also be copied into a plugin's sources.
+//!
+//! What a plugin can see is deliberately narrow: lint passes are shown each
+//! item's kind, name and span, and MIR passes a summary of each function's
+//! MIR. Neither can change the program; both can emit diagnostics.
+//!
+//! A plugin is a `dylib` which declares its registrar with
+//! `declare_plugin!`, and is loaded with `-Z abi-plugins=path/to/plugin`:
+//!
+//! ```rust,ignore
+//! #[macro_use] extern crate rustc_plugin_abi;
+//!
+//! use rustc_plugin_abi::{Item, ItemKind, Level, LintContext, LintDecl, LintPass, Registrar};
+//!
+//! struct NoFoo;
+//!
+//! impl LintPass for NoFoo {
+//!     fn lints(&self) -> Vec<LintDecl> {
+//!         vec![LintDecl::new("no_foo", Level::Warn, "items named `foo`")]
+//!     }
+//!
+//!     fn check_item(&mut self, cx: &LintContext, item: &Item) {
+//!         if item.name() == "foo" {
+//!             cx.span_lint(0, item.span, "item is named `foo`");
+//!         }
+//!     }
+//! }
+//!
+//! fn register(reg: &Registrar) {
+//!     reg.register_lint_pass(NoFoo);
+//! }
+//!
+//! declare_plugin!(register);
+//! ```
+
+#![crate_name = "rustc_plugin_abi"]
+#![unstable(feature = "rustc_private", issue = "27812")]
+#![crate_type = "rlib"]
+#![doc(html_logo_url = "https://www.rust-lang.org/logos/rust-logo-128x128-blk-v2.png",
+       html_favicon_url = "https://doc.rust-lang.org/favicon.ico",
+       html_root_url = "https://doc.rust-lang.org/nightly/")]
+#![cfg_attr(not(stage0), deny(warnings))]
+
+#![feature(recover)]
+#![feature(staged_api)]
+#![feature(std_panic)]
+
+use std::marker::PhantomData;
+use std::panic::{self, AssertRecoverSafe};
+use std::os::raw::c_void;
+use std::slice;
+use std::str;
+
+/// The version of the ABI described by this crate. It is bumped whenever
+/// any of the types below change in an incompatible way; the compiler
+/// refuses to load plugins built for a different version.
+pub const ABI_VERSION: u32 = 2;
+
+/// The symbol under which a plugin exports an `extern fn() -> u32`
+/// returning the `ABI_VERSION` it was built with.
+pub const VERSION_SYMBOL: &'static str = "__rustc_plugin_abi_version";
+
+/// The symbol under which a plugin exports its `RegistrarFn`.
+pub const REGISTRAR_SYMBOL: &'static str = "__rustc_plugin_abi_registrar";
+
+pub type VersionFn = extern "C" fn() -> u32;
+pub type RegistrarFn = extern "C" fn(registrar: *const Registrar);
+
+/// A borrowed UTF-8 string.
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct Str<'a> {
+    pub ptr: *const u8,
+    pub len: usize,
+    marker: PhantomData<&'a str>,
+}
+
+impl<'a> Str<'a> {
+    pub fn new(s: &'a str) -> Str<'a> {
+        Str { ptr: s.as_ptr(), len: s.len(), marker: PhantomData }
+    }
+
+    pub fn as_str(&self) -> &'a str {
+        unsafe { str::from_utf8_unchecked(slice::from_raw_parts(self.ptr, self.len)) }
+    }
+}
+
+/// A region of source code, as byte positions in the compiler's codemap.
+/// Plugins should only pass back spans that the compiler gave them.
+#[repr(C)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Span {
+    pub lo: u32,
+    pub hi: u32,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Level {
+    Allow,
+    Warn,
+    Deny,
+    Forbid,
+}
+
+/// A lint a plugin can emit, declared up front so that it can be
+/// controlled with `#[allow]` and friends like any other lint.
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct LintDecl {
+    pub name: Str<'static>,
+    pub default_level: Level,
+    pub desc: Str<'static>,
+}
+
+impl LintDecl {
+    pub fn new(name: &'static str, default_level: Level, desc: &'static str) -> LintDecl {
+        LintDecl { name: Str::new(name), default_level: default_level, desc: Str::new(desc) }
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ItemKind {
+    Fn,
+    Struct,
+    Enum,
+    Trait,
+    Impl,
+    Mod,
+    Static,
+    Const,
+    Type,
+    Other,
+}
+
+/// What a lint pass gets to see of an item.
+#[repr(C)]
+pub struct Item<'a> {
+    pub kind: ItemKind,
+    pub name: Str<'a>,
+    pub span: Span,
+}
+
+impl<'a> Item<'a> {
+    pub fn name(&self) -> &'a str {
+        self.name.as_str()
+    }
+}
+
+/// What a MIR pass gets to see of the MIR of a function.
+#[repr(C)]
+pub struct MirFn<'a> {
+    /// The path to the function from the crate root.
+    pub path: Str<'a>,
+    pub span: Span,
+    pub basic_blocks: usize,
+    pub args: usize,
+    pub vars: usize,
+    pub temps: usize,
+}
+
+impl<'a> MirFn<'a> {
+    pub fn path(&self) -> &'a str {
+        self.path.as_str()
+    }
+}
+
+/// Opaque handles to compiler state.
+pub enum RawLintContext {}
+pub enum RawMirContext {}
+pub enum RawRegistrarContext {}
+
+/// The compiler's side of a lint pass callback.
+#[repr(C)]
+pub struct LintContext {
+    pub cx: *mut RawLintContext,
+    /// Emits the lint with the given index into the pass's `lints`.
+    pub span_lint: extern "C" fn(cx: *mut RawLintContext, lint: usize, span: Span, msg: Str),
+}
+
+impl LintContext {
+    pub fn span_lint(&self, lint: usize, span: Span, msg: &str) {
+        (self.span_lint)(self.cx, lint, span, Str::new(msg))
+    }
+}
+
+/// The compiler's side of a MIR pass callback.
+#[repr(C)]
+pub struct MirContext {
+    pub cx: *mut RawMirContext,
+    pub span_warn: extern "C" fn(cx: *mut RawMirContext, span: Span, msg: Str),
+}
+
+impl MirContext {
+    pub fn span_warn(&self, span: Span, msg: &str) {
+        (self.span_warn)(self.cx, span, Str::new(msg))
+    }
+}
+
+/// A lint pass, as seen across the ABI. `data` is owned by the pass, and
+/// handed back to each callback; `drop` is called when the compiler is done.
+/// Callbacks must not unwind into the compiler: `check_item` returns `false`
+/// instead if the pass panicked, and the compiler reports an error.
+#[repr(C)]
+pub struct RawLintPass {
+    pub data: *mut c_void,
+    pub lints: *const LintDecl,
+    pub num_lints: usize,
+    pub check_item: extern "C" fn(data: *mut c_void,
+                                  cx: *const LintContext,
+                                  item: *const Item) -> bool,
+    pub drop: extern "C" fn(data: *mut c_void),
+}
+
+/// A MIR pass, as seen across the ABI; see `RawLintPass`. `run` returns
+/// `false` if the pass panicked.
+#[repr(C)]
+pub struct RawMirPass {
+    pub data: *mut c_void,
+    pub name: Str<'static>,
+    pub run: extern "C" fn(data: *mut c_void, cx: *const MirContext, mir: *const MirFn) -> bool,
+    pub drop: extern "C" fn(data: *mut c_void),
+}
+
+/// Handed to the plugin's registrar function.
+#[repr(C)]
+pub struct Registrar {
+    /// The `ABI_VERSION` of the compiler.
+    pub version: u32,
+    pub cx: *mut RawRegistrarContext,
+    pub register_lint_pass: extern "C" fn(cx: *mut RawRegistrarContext, pass: RawLintPass),
+    pub register_mir_pass: extern "C" fn(cx: *mut RawRegistrarContext, pass: RawMirPass),
+}
+
+/// A lint pass written in Rust; see `Registrar::register_lint_pass`.
+pub trait LintPass: 'static {
+    fn lints(&self) -> Vec<LintDecl>;
+    fn check_item(&mut self, _cx: &LintContext, _item: &Item) {}
+}
+
+/// A MIR pass written in Rust; see `Registrar::register_mir_pass`.
+pub trait MirPass: 'static {
+    fn name(&self) -> &'static str;
+    fn run(&mut self, cx: &MirContext, mir: &MirFn);
+}
+
+impl Registrar {
+    pub fn register_lint_pass<P: LintPass>(&self, pass: P) {
+        extern "C" fn check_item<P: LintPass>(data: *mut c_void,
+                                              cx: *const LintContext,
+                                              item: *const Item) -> bool {
+            let pass = unsafe { &mut (*(data as *mut (P, Vec<LintDecl>))).0 };
+            let (cx, item) = unsafe { (&*cx, &*item) };
+            panic::recover(AssertRecoverSafe::new(|| pass.check_item(cx, item))).is_ok()
+        }
+        extern "C" fn drop<P: LintPass>(data: *mut c_void) {
+            // There is nothing left to report a panic to, so it is dropped
+            // here rather than unwinding into the compiler.
+            let _ = panic::recover(AssertRecoverSafe::new(|| {
+                let _pass = unsafe { Box::from_raw(data as *mut (P, Vec<LintDecl>)) };
+            }));
+        }
+
+        let lints = pass.lints();
+        let data = Box::into_raw(Box::new((pass, lints)));
+        let (lints, num_lints) = unsafe { ((*data).1.as_ptr(), (*data).1.len()) };
+        (self.register_lint_pass)(self.cx, RawLintPass {
+            data: data as *mut c_void,
+            lints: lints,
+            num_lints: num_lints,
+            check_item: check_item::<P>,
+            drop: drop::<P>,
+        })
+    }
+
+    pub fn register_mir_pass<P: MirPass>(&self, pass: P) {
+        extern "C" fn run<P: MirPass>(data: *mut c_void,
+                                      cx: *const MirContext,
+                                      mir: *const MirFn) -> bool {
+            let pass = unsafe { &mut *(data as *mut P) };
+            let (cx, mir) = unsafe { (&*cx, &*mir) };
+            panic::recover(AssertRecoverSafe::new(|| pass.run(cx, mir))).is_ok()
+        }
+        extern "C" fn drop<P: MirPass>(data: *mut c_void) {
+            // See `register_lint_pass`.
+            let _ = panic::recover(AssertRecoverSafe::new(|| {
+                let _pass = unsafe { Box::from_raw(data as *mut P) };
+            }));
+        }
+
+        let name = Str::new(pass.name());
+        (self.register_mir_pass)(self.cx, RawMirPass {
+            data: Box::into_raw(Box::new(pass)) as *mut c_void,
+            name: name,
+            run: run::<P>,
+            drop: drop::<P>,
+        })
+    }
+}
+
+/// Exports the symbols the compiler looks for, given the path of a
+/// `fn(&Registrar)` that registers the plugin's passes.
+#[macro_export]
+macro_rules! declare_plugin {
+    ($registrar:path) => {
+        #[no_mangle]
+        pub extern "C" fn __rustc_plugin_abi_version() -> u32 {
+            $crate::ABI_VERSION
+        }
+
+        #[no_mangle]
+        pub extern "C" fn __rustc_plugin_abi_registrar(registrar: *const $crate::Registrar) {
+            $registrar(unsafe { &*registrar })
+        }
+    }
+}
@@ -41,7 +41,7 @@ use rustc::lint;
 use rustc::middle::cstore::CrateStore;
 use rustc::middle::def::{self, Def};
 use rustc::middle::def_id::DefId;
-use rustc::middle::privacy::{AccessLevel, AccessLevels};
+use rustc::middle::privacy::{AccessLevel, AccessLevels, AccessReason};
 use rustc::middle::ty::{self, TyCtxt};
 use rustc::util::nodemap::{NodeMap, NodeSet};
 use rustc::front::map as ast_map;
@@ -162,12 +162,16 @@ struct EmbargoVisitor<'a, 'tcx: 'a> {
     access_levels: AccessLevels,
     // Previous accessibility level, None means unreachable
     prev_level: Option<AccessLevel>,
+    // Item or module the previous level belongs to
+    prev_id: ast::NodeId,
     // Have something changed in the level map?
     changed: bool,
 }
 
 struct ReachEverythingInTheInterfaceVisitor<'b, 'a: 'b, 'tcx: 'a> {
     ev: &'b mut EmbargoVisitor<'a, 'tcx>,
+    // Item whose interface is being visited
+    item_id: ast::NodeId,
 }
 
 impl<'a, 'tcx> EmbargoVisitor<'a, 'tcx> {
@@ -203,12 +207,23 @@ impl<'a, 'tcx> EmbargoVisitor<'a, 'tcx> {
         self.access_levels.map.get(&id).cloned()
     }
 
+    // Reason for a node to inherit `level` from the enclosing `parent`
+    fn inherited_reason(&self, level: Option<AccessLevel>, parent: ast::NodeId) -> AccessReason {
+        if level == Some(AccessLevel::Public) {
+            AccessReason::PublicPath
+        } else {
+            AccessReason::Parent(parent)
+        }
+    }
+
     // Updates node level and returns the updated level
-    fn update(&mut self, id: ast::NodeId, level: Option<AccessLevel>) -> Option<AccessLevel> {
+    fn update(&mut self, id: ast::NodeId, level: Option<AccessLevel>, reason: AccessReason)
+              -> Option<AccessLevel> {
         let old_level = self.get(id);
         // Accessibility levels can only grow
         if level > old_level {
             self.access_levels.map.insert(id, level.unwrap());
+            self.access_levels.reasons.insert(id, reason);
             self.changed = true;
             level
         } else {
@@ -216,8 +231,9 @@ impl<'a, 'tcx> EmbargoVisitor<'a, 'tcx> {
         }
     }
 
-    fn reach<'b>(&'b mut self) -> ReachEverythingInTheInterfaceVisitor<'b, 'a, 'tcx> {
-        ReachEverythingInTheInterfaceVisitor { ev: self }
+    fn reach<'b>(&'b mut self, item_id: ast::NodeId)
+                 -> ReachEverythingInTheInterfaceVisitor<'b, 'a, 'tcx> {
+        ReachEverythingInTheInterfaceVisitor { ev: self, item_id: item_id }
     }
 }
 
@@ -251,49 +267,53 @@ impl<'a, 'tcx, 'v> Visitor<'v> for EmbargoVisitor<'a, 'tcx> {
         };
 
         // Update level of the item itself
-        let item_level = self.update(item.id, inherited_item_level);
+        let reason = self.inherited_reason(inherited_item_level, self.prev_id);
+        let item_level = self.update(item.id, inherited_item_level, reason);
+        let nested_reason = self.inherited_reason(item_level, item.id);
 
         // Update levels of nested things
         match item.node {
             hir::ItemEnum(ref def, _) => {
                 for variant in &def.variants {
-                    let variant_level = self.update(variant.node.data.id(), item_level);
+                    let variant_id = variant.node.data.id();
+                    let variant_level = self.update(variant_id, item_level, nested_reason);
+                    let field_reason = self.inherited_reason(variant_level, variant_id);
                     for field in variant.node.data.fields() {
-                        self.update(field.id, variant_level);
+                        self.update(field.id, variant_level, field_reason);
                     }
                 }
             }
             hir::ItemImpl(_, _, _, None, _, ref impl_items) => {
                 for impl_item in impl_items {
                     if impl_item.vis == hir::Public {
-                        self.update(impl_item.id, item_level);
+                        self.update(impl_item.id, item_level, nested_reason);
                     }
                 }
             }
             hir::ItemImpl(_, _, _, Some(_), _, ref impl_items) => {
                 for impl_item in impl_items {
-                    self.update(impl_item.id, item_level);
+                    self.update(impl_item.id, item_level, nested_reason);
                 }
             }
             hir::ItemTrait(_, _, _, ref trait_items) => {
                 for trait_item in trait_items {
-                    self.update(trait_item.id, item_level);
+                    self.update(trait_item.id, item_level, nested_reason);
                 }
             }
-            hir::ItemStruct(ref def, _) => {
+            hir::ItemStruct(ref def, _) | hir::ItemUnion(ref def, _) => {
                 if !def.is_struct() {
-                    self.update(def.id(), item_level);
+                    self.update(def.id(), item_level, nested_reason);
                 }
                 for field in def.fields() {
                     if field.vis == hir::Public {
-                        self.update(field.id, item_level);
+                        self.update(field.id, item_level, nested_reason);
                     }
                 }
             }
             hir::ItemForeignMod(ref foreign_mod) => {
                 for foreign_item in &foreign_mod.items {
                     if foreign_item.vis == hir::Public {
-                        self.update(foreign_item.id, item_level);
+                        self.update(foreign_item.id, item_level, nested_reason);
                     }
                 }
             }
@@ -310,24 +330,26 @@ impl<'a, 'tcx, 'v> Visitor<'v> for EmbargoVisitor<'a, 'tcx> {
             hir::ItemUse(..) => {}
             // Visit everything
             hir::ItemConst(..) | hir::ItemStatic(..) | hir::ItemFn(..) |
-            hir::ItemTrait(..) | hir::ItemTy(..) | hir::ItemImpl(_, _, _, Some(..), _, _) => {
+            hir::ItemTrait(..) | hir::ItemTraitAlias(..) | hir::ItemTy(..) |
+            hir::ItemImpl(_, _, _, Some(..), _, _) => {
                 if item_level.is_some() {
-                    self.reach().visit_item(item);
+                    self.reach(item.id).visit_item(item);
                 }
             }
             // Visit everything, but enum variants have their own levels
             hir::ItemEnum(ref def, ref generics) => {
                 if item_level.is_some() {
-                    self.reach().visit_generics(generics);
+                    self.reach(item.id).visit_generics(generics);
                 }
                 for variant in &def.variants {
                     if self.get(variant.node.data.id()).is_some() {
                         for field in variant.node.data.fields() {
-                            self.reach().visit_struct_field(field);
+                            self.reach(item.id).visit_struct_field(field);
                         }
                         // Corner case: if the variant is reachable, but its
                         // enum is not, make the enum reachable as well.
-                        self.update(item.id, Some(AccessLevel::Reachable));
+                        let reason = AccessReason::Interface(variant.node.data.id());
+                        self.update(item.id, Some(AccessLevel::Reachable), reason);
                     }
                 }
             }
@@ -335,17 +357,18 @@ impl<'a, 'tcx, 'v> Visitor<'v> for EmbargoVisitor<'a, 'tcx> {
             hir::ItemForeignMod(ref foreign_mod) => {
                 for foreign_item in &foreign_mod.items {
                     if self.get(foreign_item.id).is_some() {
-                        self.reach().visit_foreign_item(foreign_item);
+                        self.reach(item.id).visit_foreign_item(foreign_item);
                     }
                 }
             }
             // Visit everything except for private fields
-            hir::ItemStruct(ref struct_def, ref generics) => {
+            hir::ItemStruct(ref struct_def, ref generics) |
+            hir::ItemUnion(ref struct_def, ref generics) => {
                 if item_level.is_some() {
-                    self.reach().visit_generics(generics);
+                    self.reach(item.id).visit_generics(generics);
                     for field in struct_def.fields() {
                         if self.get(field.id).is_some() {
-                            self.reach().visit_struct_field(field);
+                            self.reach(item.id).visit_struct_field(field);
                         }
                     }
                 }
@@ -355,10 +378,10 @@ impl<'a, 'tcx, 'v> Visitor<'v> for EmbargoVisitor<'a, 'tcx> {
             // Visit everything except for private impl items
             hir::ItemImpl(_, _, ref generics, None, _, ref impl_items) => {
                 if item_level.is_some() {
-                    self.reach().visit_generics(generics);
+                    self.reach(item.id).visit_generics(generics);
                     for impl_item in impl_items {
                         if self.get(impl_item.id).is_some() {
-                            self.reach().visit_impl_item(impl_item);
+                            self.reach(item.id).visit_impl_item(impl_item);
                         }
                     }
                 }
@@ -366,11 +389,14 @@ impl<'a, 'tcx, 'v> Visitor<'v> for EmbargoVisitor<'a, 'tcx> {
         }
 
         let orig_level = self.prev_level;
+        let orig_id = self.prev_id;
         self.prev_level = item_level;
+        self.prev_id = item.id;
 
         intravisit::walk_item(self, item);
 
         self.prev_level = orig_level;
+        self.prev_id = orig_id;
     }
 
     fn visit_block(&mut self, b: &'v hir::Block) {
@@ -391,7 +417,8 @@ impl<'a, 'tcx, 'v> Visitor<'v> for EmbargoVisitor<'a, 'tcx> {
             if let Some(exports) = self.export_map.get(&id) {
                 for export in exports {
                     if let Some(node_id) = self.tcx.map.as_local_node_id(export.def_id) {
-                        self.update(node_id, Some(AccessLevel::Exported));
+                        self.update(node_id, Some(AccessLevel::Exported),
+                                    AccessReason::Reexport(id));
                     }
                 }
             }
@@ -401,7 +428,7 @@ impl<'a, 'tcx, 'v> Visitor<'v> for EmbargoVisitor<'a, 'tcx> {
     }
 
     fn visit_macro_def(&mut self, md: &'v hir::MacroDef) {
-        self.update(md.id, Some(AccessLevel::Public));
+        self.update(md.id, Some(AccessLevel::Public), AccessReason::Macro);
     }
 }
 
@@ -437,7 +464,8 @@ impl<'b, 'a, 'tcx: 'a, 'v> Visitor<'v> for ReachEverythingInTheInterfaceVisitor<
                                 self.reach_aliased_type(item, path);
                             }
                         } else {
-                            self.ev.update(item.id, Some(AccessLevel::Reachable));
+                            let reason = AccessReason::Interface(self.item_id);
+                            self.ev.update(item.id, Some(AccessLevel::Reachable), reason);
                         }
                     }
                 }
@@ -453,7 +481,8 @@ impl<'b, 'a, 'tcx: 'a, 'v> Visitor<'v> for ReachEverythingInTheInterfaceVisitor<
         let def_id = self.ev.tcx.trait_ref_to_def_id(trait_ref);
         if let Some(node_id) = self.ev.tcx.map.as_local_node_id(def_id) {
             let item = self.ev.tcx.map.expect_item(node_id);
-            self.ev.update(item.id, Some(AccessLevel::Reachable));
+            let reason = AccessReason::Interface(self.item_id);
+            self.ev.update(item.id, Some(AccessLevel::Reachable), reason);
         }
 
         intravisit::walk_trait_ref(self, trait_ref);
@@ -734,7 +763,9 @@ impl<'a, 'tcx> PrivacyVisitor<'a, 'tcx> {
         let desc = match item.node {
             hir::ItemMod(..) => "module",
             hir::ItemTrait(..) => "trait",
+            hir::ItemTraitAlias(..) => "trait alias",
             hir::ItemStruct(..) => "struct",
+            hir::ItemUnion(..) => "union",
             hir::ItemEnum(..) => "enum",
             _ => return Some((err_span, err_msg, None))
         };
@@ -762,6 +793,8 @@ impl<'a, 'tcx> PrivacyVisitor<'a, 'tcx> {
         let struct_desc = match def.adt_kind() {
             ty::AdtKind::Struct =>
                 format!("struct `{}`", self.tcx.item_path_str(def.did)),
+            ty::AdtKind::Union =>
+                format!("union `{}`", self.tcx.item_path_str(def.did)),
             // struct variant fields have inherited visibility
             ty::AdtKind::Enum => return
         };
@@ -990,7 +1023,8 @@ impl<'a, 'tcx> SanePrivacyVisitor<'a, 'tcx> {
                     }
                 }
             }
-            hir::ItemStruct(..) | hir::ItemTrait(..) |
+            hir::ItemStruct(..) | hir::ItemUnion(..) | hir::ItemTrait(..) |
+            hir::ItemTraitAlias(..) |
             hir::ItemConst(..) | hir::ItemStatic(..) | hir::ItemFn(..) |
             hir::ItemMod(..) | hir::ItemExternCrate(..) |
             hir::ItemUse(..) | hir::ItemTy(..) => {}
@@ -1103,7 +1137,8 @@ impl<'a, 'tcx, 'v> Visitor<'v> for ObsoleteVisiblePrivateTypesVisitor<'a, 'tcx>
             // namespace (the contents have their own privacies).
             hir::ItemForeignMod(_) => {}
 
-            hir::ItemTrait(_, _, ref bounds, _) => {
+            hir::ItemTrait(_, _, ref bounds, _) |
+            hir::ItemTraitAlias(_, ref bounds) => {
                 if !self.trait_is_public(item.id) {
                     return
                 }
@@ -1290,7 +1325,8 @@ impl<'a, 'tcx, 'v> Visitor<'v> for ObsoleteVisiblePrivateTypesVisitor<'a, 'tcx>
                 }
                 &hir::WherePredicate::RegionPredicate(_) => {}
                 &hir::WherePredicate::EqPredicate(ref eq_pred) => {
-                    self.visit_ty(&eq_pred.ty);
+                    self.visit_ty(&eq_pred.lhs_ty);
+                    self.visit_ty(&eq_pred.rhs_ty);
                 }
             }
         }
@@ -1351,9 +1387,43 @@ struct SearchInterfaceForPrivateItemsVisitor<'a, 'tcx: 'a> {
     // Is private component found?
     is_public: bool,
     old_error_set: &'a NodeSet,
+    access_levels: &'a AccessLevels,
+    // Item whose interface is searched
+    item_id: ast::NodeId,
 }
 
 impl<'a, 'tcx: 'a> SearchInterfaceForPrivateItemsVisitor<'a, 'tcx> {
+    // Why the interface being searched is visible outside the crate, if it is
+    fn visibility_reason(&self) -> Option<String> {
+        self.access_levels.describe(self.tcx, self.item_id)
+    }
+
+    fn report(&self, span: Span, node_id: ast::NodeId, ref_id: ast::NodeId, what: &str) {
+        let reason = self.visibility_reason();
+        if self.old_error_set.contains(&ref_id) {
+            let mut err = if what == "type" {
+                struct_span_err!(self.tcx.sess, span, E0446, "private type in public interface")
+            } else {
+                struct_span_err!(self.tcx.sess, span, E0445, "private trait in public interface")
+            };
+            if let Some(reason) = reason {
+                err.span_note(span, &format!("the interface is visible outside the crate \
+                                              because {}", reason));
+            }
+            err.emit();
+        } else {
+            let mut msg = format!("private {} in public interface", what);
+            if what == "trait" {
+                msg.push_str(" (error E0445)");
+            }
+            if let Some(reason) = reason {
+                msg.push_str(&format!("; the interface is visible outside the crate because {}",
+                                      reason));
+            }
+            self.tcx.sess.add_lint(lint::builtin::PRIVATE_IN_PUBLIC, node_id, span, msg);
+        }
+    }
+
     // Check if the type alias contain private types when substituted
     fn is_public_type_alias(&self, item: &hir::Item, path: &hir::Path) -> bool {
         // We substitute type aliases only when determining impl publicity
@@ -1415,17 +1485,7 @@ impl<'a, 'tcx: 'a, 'v> Visitor<'v> for SearchInterfaceForPrivateItemsVisitor<'a,
                         let item = self.tcx.map.expect_item(node_id);
                         if item.vis != hir::Public && !self.is_public_type_alias(item, path) {
                             if !self.is_quiet {
-                                if self.old_error_set.contains(&ty.id) {
-                                    span_err!(self.tcx.sess, ty.span, E0446,
-                                              "private type in public interface");
-                                } else {
-                                    self.tcx.sess.add_lint (
-                                        lint::builtin::PRIVATE_IN_PUBLIC,
-                                        node_id,
-                                        ty.span,
-                                        format!("private type in public interface"),
-                                    );
-                                }
+                                self.report(ty.span, node_id, ty.id, "type");
                             }
                             self.is_public = false;
                         }
@@ -1449,16 +1509,7 @@ impl<'a, 'tcx: 'a, 'v> Visitor<'v> for SearchInterfaceForPrivateItemsVisitor<'a,
             let item = self.tcx.map.expect_item(node_id);
             if item.vis != hir::Public {
                 if !self.is_quiet {
-                    if self.old_error_set.contains(&trait_ref.ref_id) {
-                        span_err!(self.tcx.sess, trait_ref.path.span, E0445,
-                                  "private trait in public interface");
-                    } else {
-                        self.tcx.sess.add_lint(lint::builtin::PRIVATE_IN_PUBLIC,
-                                               node_id,
-                                               trait_ref.path.span,
-                                               "private trait in public interface (error E0445)"
-                                                    .to_string());
-                    }
+                    self.report(trait_ref.path.span, node_id, trait_ref.ref_id, "trait");
                 }
                 self.is_public = false;
             }
@@ -1478,13 +1529,15 @@ impl<'a, 'tcx: 'a, 'v> Visitor<'v> for SearchInterfaceForPrivateItemsVisitor<'a,
 struct PrivateItemsInPublicInterfacesVisitor<'a, 'tcx: 'a> {
     tcx: &'a TyCtxt<'tcx>,
     old_error_set: &'a NodeSet,
+    access_levels: &'a AccessLevels,
 }
 
 impl<'a, 'tcx> PrivateItemsInPublicInterfacesVisitor<'a, 'tcx> {
     // A type is considered public if it doesn't contain any private components
     fn is_public_ty(&self, ty: &hir::Ty) -> bool {
         let mut check = SearchInterfaceForPrivateItemsVisitor {
-            tcx: self.tcx, is_quiet: true, is_public: true, old_error_set: self.old_error_set
+            tcx: self.tcx, is_quiet: true, is_public: true, old_error_set: self.old_error_set,
+            access_levels: self.access_levels, item_id: ast::DUMMY_NODE_ID,
         };
         check.visit_ty(ty);
         check.is_public
@@ -1493,7 +1546,8 @@ impl<'a, 'tcx> PrivateItemsInPublicInterfacesVisitor<'a, 'tcx> {
     // A trait reference is considered public if it doesn't contain any private components
     fn is_public_trait_ref(&self, trait_ref: &hir::TraitRef) -> bool {
         let mut check = SearchInterfaceForPrivateItemsVisitor {
-            tcx: self.tcx, is_quiet: true, is_public: true, old_error_set: self.old_error_set
+            tcx: self.tcx, is_quiet: true, is_public: true, old_error_set: self.old_error_set,
+            access_levels: self.access_levels, item_id: ast::DUMMY_NODE_ID,
         };
         check.visit_trait_ref(trait_ref);
         check.is_public
@@ -1503,7 +1557,8 @@ impl<'a, 'tcx> PrivateItemsInPublicInterfacesVisitor<'a, 'tcx> {
 impl<'a, 'tcx, 'v> Visitor<'v> for PrivateItemsInPublicInterfacesVisitor<'a, 'tcx> {
     fn visit_item(&mut self, item: &hir::Item) {
         let mut check = SearchInterfaceForPrivateItemsVisitor {
-            tcx: self.tcx, is_quiet: false, is_public: true, old_error_set: self.old_error_set
+            tcx: self.tcx, is_quiet: false, is_public: true, old_error_set: self.old_error_set,
+            access_levels: self.access_levels, item_id: item.id,
         };
         match item.node {
             // Crates are always public
@@ -1514,7 +1569,8 @@ impl<'a, 'tcx, 'v> Visitor<'v> for PrivateItemsInPublicInterfacesVisitor<'a, 'tc
             hir::ItemUse(..) => {}
             // Subitems of these items have inherited publicity
             hir::ItemConst(..) | hir::ItemStatic(..) | hir::ItemFn(..) |
-            hir::ItemEnum(..) | hir::ItemTrait(..) | hir::ItemTy(..) => {
+            hir::ItemEnum(..) | hir::ItemTrait(..) | hir::ItemTraitAlias(..) |
+            hir::ItemTy(..) => {
                 if item.vis == hir::Public {
                     check.visit_item(item);
                 }
@@ -1527,8 +1583,9 @@ impl<'a, 'tcx, 'v> Visitor<'v> for PrivateItemsInPublicInterfacesVisitor<'a, 'tc
                     }
                 }
             }
-            // Subitems of structs have their own publicity
-            hir::ItemStruct(ref struct_def, ref generics) => {
+            // Subitems of structs and unions have their own publicity
+            hir::ItemStruct(ref struct_def, ref generics) |
+            hir::ItemUnion(ref struct_def, ref generics) => {
                 if item.vis == hir::Public {
                     check.visit_generics(generics);
                     for field in struct_def.fields() {
@@ -1601,6 +1658,7 @@ pub fn check_crate(tcx: &TyCtxt, export_map: &def::ExportMap) -> AccessLevels {
         export_map: export_map,
         access_levels: Default::default(),
         prev_level: Some(AccessLevel::Public),
+        prev_id: ast::CRATE_NODE_ID,
         changed: false,
     };
     loop {
@@ -1611,7 +1669,7 @@ pub fn check_crate(tcx: &TyCtxt, export_map: &def::ExportMap) -> AccessLevels {
             break
         }
     }
-    visitor.update(ast::CRATE_NODE_ID, Some(AccessLevel::Public));
+    visitor.update(ast::CRATE_NODE_ID, Some(AccessLevel::Public), AccessReason::PublicPath);
 
     {
         let mut visitor = ObsoleteVisiblePrivateTypesVisitor {
@@ -1626,6 +1684,7 @@ pub fn check_crate(tcx: &TyCtxt, export_map: &def::ExportMap) -> AccessLevels {
         let mut visitor = PrivateItemsInPublicInterfacesVisitor {
             tcx: tcx,
             old_error_set: &visitor.old_error_set,
+            access_levels: visitor.access_levels,
         };
         krate.visit_all_items(&mut visitor);
     }
@@ -40,7 +40,7 @@ use rustc_front::hir::{Block, DeclItem};
 use rustc_front::hir::{ForeignItem, ForeignItemFn, ForeignItemStatic};
 use rustc_front::hir::{Item, ItemConst, ItemEnum, ItemExternCrate, ItemFn};
 use rustc_front::hir::{ItemForeignMod, ItemImpl, ItemMod, ItemStatic, ItemDefaultImpl};
-use rustc_front::hir::{ItemStruct, ItemTrait, ItemTy, ItemUse};
+use rustc_front::hir::{ItemStruct, ItemTrait, ItemTraitAlias, ItemTy, ItemUnion, ItemUse};
 use rustc_front::hir::{PathListIdent, PathListMod, StmtDecl};
 use rustc_front::hir::{Variant, ViewPathGlob, ViewPathList, ViewPathSimple};
 use rustc_front::hir::Visibility;
@@ -378,6 +378,22 @@ impl<'a, 'b:'a, 'tcx:'b> GraphBuilder<'a, 'b, 'tcx> {
                 parent
             }
 
+            // Unions always have named fields, so they only live in the type namespace.
+            ItemUnion(ref struct_def, _) => {
+                let def = Def::Struct(self.ast_map.local_def_id(item.id));
+                self.define(parent, name, TypeNS, (def, sp, modifiers));
+
+                // Record the def ID and fields of this union.
+                let field_names = struct_def.fields()
+                                            .iter()
+                                            .map(|f| f.name)
+                                            .collect();
+                let item_def_id = self.ast_map.local_def_id(item.id);
+                self.structs.insert(item_def_id, field_names);
+
+                parent
+            }
+
             ItemDefaultImpl(_, _) |
             ItemImpl(..) => parent,
 
@@ -407,6 +423,18 @@ impl<'a, 'b:'a, 'tcx:'b> GraphBuilder<'a, 'b, 'tcx> {
 
                 parent
             }
+
+            // Trait aliases have no items of their own, but are otherwise
+            // treated like traits, matching what external crates produce.
+            ItemTraitAlias(..) => {
+                let def_id = self.ast_map.local_def_id(item.id);
+                let parent_link = ModuleParentLink(parent, name);
+                let def = Def::Trait(def_id);
+                let module_parent = self.new_module(parent_link, Some(def), false, is_public);
+                self.define(parent, name, TypeNS, (module_parent, sp));
+
+                parent
+            }
         }
     }
 
@@ -570,6 +598,7 @@ impl<'a, 'b:'a, 'tcx:'b> GraphBuilder<'a, 'b, 'tcx> {
             Def::Local(..) |
             Def::PrimTy(..) |
             Def::TyParam(..) |
+            Def::ConstParam(..) |
             Def::Upvar(..) |
             Def::Label(..) |
             Def::SelfTy(..) |
@@ -24,6 +24,7 @@ use Namespace::{TypeNS, ValueNS};
 
 use rustc::lint;
 use syntax::ast;
+use syntax::attr::{self, AttrMetaMethods};
 use syntax::codemap::{Span, DUMMY_SP};
 
 use rustc_front::hir;
@@ -71,25 +72,53 @@ impl<'a, 'b, 'tcx> UnusedImportCheckVisitor<'a, 'b, 'tcx> {
     }
 }
 
+impl<'a, 'b, 'tcx> UnusedImportCheckVisitor<'a, 'b, 'tcx> {
+    // An `extern crate` is used if any name resolved through it, if any of
+    // the macros it brought in were expanded, or if it contributes something
+    // the crate needs without naming it (lang items, the allocator).
+    fn check_extern_crate(&mut self, item: &hir::Item, crate_num: ast::CrateNum) {
+        let macros_used = self.session.used_macro_crates.borrow().contains(&item.name);
+        let macros_reexported = attr::contains_name(&item.attrs, "macro_reexport");
+        let names_used = self.used_crates.contains(&crate_num);
+
+        if !names_used && !macros_used && !macros_reexported &&
+           self.session.cstore.lang_items(crate_num).is_empty() &&
+           !self.session.cstore.is_allocator(crate_num) {
+            self.session.add_lint(lint::builtin::UNUSED_EXTERN_CRATES,
+                                  item.id,
+                                  item.span,
+                                  "unused extern crate, consider removing it".to_string());
+            return;
+        }
+
+        if !macros_used && !macros_reexported {
+            if let Some(attr) = item.attrs.iter().find(|a| a.check_name("macro_use")) {
+                self.session.add_lint(lint::builtin::UNUSED_MACRO_IMPORTS,
+                                      item.id,
+                                      attr.span,
+                                      format!("no macros from `{}` are used, consider \
+                                               removing this `#[macro_use]`",
+                                              item.name));
+            }
+        }
+    }
+}
+
 impl<'a, 'b, 'v, 'tcx> Visitor<'v> for UnusedImportCheckVisitor<'a, 'b, 'tcx> {
     fn visit_item(&mut self, item: &hir::Item) {
         // Ignore is_public import statements because there's no way to be sure
-        // whether they're used or not. Also ignore imports with a dummy span
-        // because this means that they were generated in some fashion by the
-        // compiler and we don't need to consider them.
-        if item.vis == hir::Public || item.span.source_equal(&DUMMY_SP) {
+        // whether they're used or not. Also ignore imports with a dummy or
+        // injected span because this means that they were generated in some
+        // fashion by the compiler and we don't need to consider them.
+        if item.vis == hir::Public || item.span.source_equal(&DUMMY_SP) ||
+           self.session.codemap().span_is_injected(item.span) {
             return;
         }
 
         match item.node {
             hir::ItemExternCrate(_) => {
                 if let Some(crate_num) = self.session.cstore.extern_mod_stmt_cnum(item.id) {
-                    if !self.used_crates.contains(&crate_num) {
-                        self.session.add_lint(lint::builtin::UNUSED_EXTERN_CRATES,
-                                              item.id,
-                                              item.span,
-                                              "unused extern crate".to_string());
-                    }
+                    self.check_extern_crate(item, crate_num);
                 }
             }
             hir::ItemUse(ref p) => {
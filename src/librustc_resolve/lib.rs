@@ -57,7 +57,7 @@ use rustc::middle::def_id::DefId;
 use rustc::middle::pat_util::pat_bindings;
 use rustc::middle::subst::{ParamSpace, FnSpace, TypeSpace};
 use rustc::middle::ty::{Freevar, FreevarMap, TraitMap, GlobMap};
-use rustc::util::nodemap::{NodeMap, FnvHashMap};
+use rustc::util::nodemap::{NodeMap, NodeSet, FnvHashMap};
 
 use syntax::ast::{self, FloatTy};
 use syntax::ast::{CRATE_NODE_ID, Name, NodeId, CrateNum, IntTy, UintTy};
@@ -77,7 +77,7 @@ use rustc_front::hir::{ExprPath, ExprStruct, FnDecl};
 use rustc_front::hir::{ForeignItemFn, ForeignItemStatic, Generics};
 use rustc_front::hir::{ImplItem, Item, ItemConst, ItemEnum, ItemExternCrate};
 use rustc_front::hir::{ItemFn, ItemForeignMod, ItemImpl, ItemMod, ItemStatic, ItemDefaultImpl};
-use rustc_front::hir::{ItemStruct, ItemTrait, ItemTy, ItemUse};
+use rustc_front::hir::{ItemStruct, ItemTrait, ItemTraitAlias, ItemTy, ItemUnion, ItemUse};
 use rustc_front::hir::Local;
 use rustc_front::hir::{Pat, PatKind, Path, PrimTy};
 use rustc_front::hir::{PathSegment, PathParameters};
@@ -135,8 +135,6 @@ pub enum ResolutionError<'a> {
     IsNotATrait(&'a str),
     /// error E0405: use of undeclared trait name
     UndeclaredTraitName(&'a str, SuggestedCandidates),
-    /// error E0406: undeclared associated type
-    UndeclaredAssociatedType,
     /// error E0407: method is not a member of trait
     MethodNotMemberOfTrait(Name, &'a str),
     /// error E0437: type is not a member of trait
@@ -264,9 +262,6 @@ fn resolve_struct_error<'b, 'a: 'b, 'tcx: 'a>(resolver: &'b Resolver<'a, 'tcx>,
             show_candidates(&mut err, span, &candidates);
             err
         }
-        ResolutionError::UndeclaredAssociatedType => {
-            struct_span_err!(resolver.session, span, E0406, "undeclared associated type")
-        }
         ResolutionError::MethodNotMemberOfTrait(method, trait_) => {
             struct_span_err!(resolver.session,
                              span,
@@ -593,6 +588,12 @@ impl<'a, 'v, 'tcx> Visitor<'v> for Resolver<'a, 'tcx> {
     fn visit_ty(&mut self, ty: &Ty) {
         self.resolve_type(ty);
     }
+    fn visit_path_parameters(&mut self, path_span: Span, parameters: &hir::PathParameters) {
+        if let hir::AngleBracketedParameters(ref data) = *parameters {
+            walk_list!(self, visit_expr, &data.consts);
+        }
+        intravisit::walk_path_parameters(self, path_span, parameters);
+    }
     fn visit_generics(&mut self, generics: &Generics) {
         self.resolve_generics(generics);
     }
@@ -624,6 +625,7 @@ impl<'a, 'v, 'tcx> Visitor<'v> for Resolver<'a, 'tcx> {
                                 generics,
                                 item_id,
                                 variant.span);
+        intravisit::walk_where_clause(self, &variant.node.where_clause);
     }
     fn visit_foreign_item(&mut self, foreign_item: &hir::ForeignItem) {
         execute_callback!(hir_map::Node::NodeForeignItem(foreign_item), self);
@@ -1100,7 +1102,8 @@ pub struct Resolver<'a, 'tcx: 'a> {
 
     make_glob_map: bool,
     // Maps imports to the names of items actually imported (this actually maps
-    // all imports, but only glob imports are actually interesting).
+    // all imports, but only glob imports are actually interesting). Uses are
+    // only recorded here; the map is grouped lazily when first queried.
     glob_map: GlobMap,
 
     used_imports: HashSet<(NodeId, Namespace)>,
@@ -1185,7 +1188,7 @@ impl<'a, 'tcx> Resolver<'a, 'tcx> {
 
             emit_errors: true,
             make_glob_map: make_glob_map == MakeGlobMap::Yes,
-            glob_map: HashMap::new(),
+            glob_map: GlobMap::new(),
 
             callback: None,
             resolved: false,
@@ -1243,17 +1246,9 @@ impl<'a, 'tcx> Resolver<'a, 'tcx> {
             self.privacy_errors.push((**error).clone());
         }
 
-        if !self.make_glob_map {
-            return;
+        if self.make_glob_map {
+            self.glob_map.record(import_id, name);
         }
-        if self.glob_map.contains_key(&import_id) {
-            self.glob_map.get_mut(&import_id).unwrap().insert(name);
-            return;
-        }
-
-        let mut new_set = HashSet::new();
-        new_set.insert(name);
-        self.glob_map.insert(import_id, new_set);
     }
 
     fn get_trait_name(&self, did: DefId) -> Name {
@@ -1641,8 +1636,19 @@ impl<'a, 'tcx> Resolver<'a, 'tcx> {
 
         match item.node {
             ItemEnum(_, ref generics) |
-            ItemTy(_, ref generics) |
-            ItemStruct(_, ref generics) => {
+            ItemStruct(_, ref generics) |
+            ItemUnion(_, ref generics) => {
+                self.check_if_primitive_type_name(name, item.span);
+
+                // `Self` names the type being defined.
+                self.with_type_parameter_rib(HasTypeParameters(generics, TypeSpace, ItemRibKind),
+                                             |this| {
+                    this.with_self_rib(Def::SelfTy(None, Some((item.id, item.id))), |this| {
+                        intravisit::walk_item(this, item)
+                    })
+                });
+            }
+            ItemTy(_, ref generics) => {
                 self.check_if_primitive_type_name(name, item.span);
 
                 self.with_type_parameter_rib(HasTypeParameters(generics, TypeSpace, ItemRibKind),
@@ -1700,10 +1706,16 @@ impl<'a, 'tcx> Resolver<'a, 'tcx> {
                                         intravisit::walk_trait_item(this, trait_item)
                                     });
                                 }
-                                hir::TypeTraitItem(..) => {
+                                hir::TypeTraitItem(ref generics, _, _) => {
                                     this.check_if_primitive_type_name(trait_item.name,
                                                                       trait_item.span);
-                                    this.with_type_parameter_rib(NoTypeParameters, |this| {
+                                    // Like a method, a generic associated type can
+                                    // name its own parameters and the trait's.
+                                    let type_parameters =
+                                        HasTypeParameters(generics,
+                                                          FnSpace,
+                                                          MethodRibKind);
+                                    this.with_type_parameter_rib(type_parameters, |this| {
                                         intravisit::walk_trait_item(this, trait_item)
                                     });
                                 }
@@ -1713,6 +1725,21 @@ impl<'a, 'tcx> Resolver<'a, 'tcx> {
                 });
             }
 
+            ItemTraitAlias(ref generics, ref bounds) => {
+                self.check_if_primitive_type_name(name, item.span);
+
+                self.with_type_parameter_rib(HasTypeParameters(generics,
+                                                               TypeSpace,
+                                                               ItemRibKind),
+                                             |this| {
+                    let local_def_id = this.ast_map.local_def_id(item.id);
+                    this.with_self_rib(Def::SelfTy(Some(local_def_id), None), |this| {
+                        this.visit_generics(generics);
+                        walk_list!(this, visit_ty_param_bound, bounds);
+                    });
+                });
+            }
+
             ItemMod(_) | ItemForeignMod(_) => {
                 self.with_scope(item.id, |this| {
                     intravisit::walk_item(this, item);
@@ -1793,6 +1820,8 @@ impl<'a, 'tcx> Resolver<'a, 'tcx> {
                     }
                     seen_bindings.insert(name);
 
+                    self.check_type_parameter_shadowing(type_parameter);
+
                     // plain insert (no renaming)
                     function_type_rib.bindings
                                      .insert(name,
@@ -1802,6 +1831,26 @@ impl<'a, 'tcx> Resolver<'a, 'tcx> {
                                                                   .local_def_id(type_parameter.id),
                                                               name)));
                 }
+                // Const parameters share the rib, so that they go out of scope
+                // in nested items exactly like type parameters do; they are
+                // found from the value namespace by `resolve_const_param`.
+                for (index, const_parameter) in generics.const_params.iter().enumerate() {
+                    let name = const_parameter.name;
+                    if seen_bindings.contains(&name) {
+                        resolve_error(self,
+                                      const_parameter.span,
+                                      ResolutionError::NameAlreadyUsedInTypeParameterList(name));
+                    }
+                    seen_bindings.insert(name);
+
+                    let def_id = self.ast_map.local_def_id(const_parameter.id);
+                    function_type_rib.bindings
+                                     .insert(name,
+                                             DlDef(Def::ConstParam(space,
+                                                                   index as u32,
+                                                                   def_id,
+                                                                   name)));
+                }
                 self.type_ribs.push(function_type_rib);
             }
 
@@ -1822,6 +1871,34 @@ impl<'a, 'tcx> Resolver<'a, 'tcx> {
         }
     }
 
+    /// Warns when a type parameter has the same name as a type that would
+    /// otherwise be visible at this point, e.g. `struct Foo<Option>`. Inside
+    /// the item every use of that name refers to the parameter, which tends
+    /// to produce very confusing type errors further down.
+    fn check_type_parameter_shadowing(&mut self, type_parameter: &hir::TyParam) {
+        let name = type_parameter.name;
+        let shadowed = if self.primitive_type_table.primitive_types.contains_key(&name) {
+            Some("primitive type")
+        } else {
+            match self.resolve_item_in_lexical_scope(name, TypeNS, false) {
+                Success(binding) => match binding.def() {
+                    Some(Def::Mod(..)) | Some(Def::ForeignMod(..)) | Some(Def::Err) | None => None,
+                    Some(_) if binding.is_import() => Some("imported type"),
+                    Some(_) => Some("type"),
+                },
+                _ => None,
+            }
+        };
+
+        if let Some(kind) = shadowed {
+            self.session.add_lint(lint::builtin::TYPE_PARAM_SHADOWING,
+                                  type_parameter.id,
+                                  type_parameter.span,
+                                  format!("type parameter `{}` shadows the {} `{}` in scope",
+                                          name, kind, name));
+        }
+    }
+
     fn with_label_rib<F>(&mut self, f: F)
         where F: FnOnce(&mut Resolver)
     {
@@ -1931,23 +2008,6 @@ impl<'a, 'tcx> Resolver<'a, 'tcx> {
         for type_parameter in generics.ty_params.iter() {
             self.check_if_primitive_type_name(type_parameter.name, type_parameter.span);
         }
-        for predicate in &generics.where_clause.predicates {
-            match predicate {
-                &hir::WherePredicate::BoundPredicate(_) |
-                &hir::WherePredicate::RegionPredicate(_) => {}
-                &hir::WherePredicate::EqPredicate(ref eq_pred) => {
-                    let path_res = self.resolve_path(eq_pred.id, &eq_pred.path, 0, TypeNS);
-                    if let Some(PathResolution { base_def: Def::TyParam(..), .. }) = path_res {
-                        self.record_def(eq_pred.id, path_res.unwrap());
-                    } else {
-                        resolve_error(self,
-                                      eq_pred.span,
-                                      ResolutionError::UndeclaredAssociatedType);
-                        self.record_def(eq_pred.id, err_path_resolution());
-                    }
-                }
-            }
-        }
         intravisit::walk_generics(self, generics);
     }
 
@@ -2770,7 +2830,7 @@ impl<'a, 'tcx> Resolver<'a, 'tcx> {
                     }
                 }
             }
-            Def::TyParam(..) | Def::SelfTy(..) => {
+            Def::TyParam(..) | Def::ConstParam(..) | Def::SelfTy(..) => {
                 for rib in ribs {
                     match rib.kind {
                         NormalRibKind | MethodRibKind | ClosureRibKind(..) |
@@ -2932,10 +2992,30 @@ impl<'a, 'tcx> Resolver<'a, 'tcx> {
                     }
                 }
                 // We can only see through anonymous modules
-                if module.def.is_some() { return None; }
+                if module.def.is_some() { break; }
             }
         }
 
+        match namespace {
+            ValueNS => self.resolve_const_param(ident.unhygienic_name),
+            TypeNS => None,
+        }
+    }
+
+    /// Finds the const parameter called `name` in scope, if any. Const
+    /// parameters live in the type ribs with the type parameters they're
+    /// declared with; locals and items still shadow them in the value
+    /// namespace, as they're only looked for when nothing else was found.
+    fn resolve_const_param(&self, name: Name) -> Option<LocalDef> {
+        for i in (0 .. self.type_ribs.len()).rev() {
+            let binding = self.type_ribs[i].bindings.get(&name);
+            if let Some(&DlDef(def @ Def::ConstParam(..))) = binding {
+                return Some(LocalDef {
+                    ribs: Some((TypeNS, i)),
+                    def: def,
+                });
+            }
+        }
         None
     }
 
@@ -3530,6 +3610,128 @@ impl<'a, 'tcx> Resolver<'a, 'tcx> {
             }
         }
     }
+
+    /// Finds the local items that code configured away by `cfg(test)` refers
+    /// to, so that the dead code lint leaves them alone. Paths are resolved
+    /// from the module the stripped item was in, which is also what the
+    /// usual `use super::*` in a stripped `mod tests` brings into scope.
+    fn resolve_test_only_uses(&mut self) {
+        let session = self.session;
+        let mut items = NodeSet();
+        let mut assoc_names = vec![];
+        for path in &session.test_only_uses.borrow().paths {
+            self.resolve_test_only_path(path, &mut items, &mut assoc_names);
+        }
+        session.test_only_items.borrow_mut().extend(items);
+        session.test_only_uses.borrow_mut().method_names.extend(assoc_names);
+    }
+
+    fn resolve_test_only_path(&mut self,
+                              path: &syntax::config::TestOnlyPath,
+                              items: &mut NodeSet,
+                              assoc_names: &mut Vec<Name>) {
+        let root = self.graph_root;
+        let mut module = root;
+        for &name in &path.module {
+            match self.resolve_name_in_module(module, name, TypeNS, true, false) {
+                Success(binding) => match binding.module() {
+                    Some(child) => module = child,
+                    None => return,
+                },
+                _ => return,
+            }
+        }
+
+        if path.global {
+            self.mark_test_only_path(root, &path.segments, items, assoc_names);
+            return;
+        }
+
+        let mut segments = &path.segments[..];
+        let mut depth = path.depth;
+        let mut has_prefix = false;
+        if segments.first().map_or(false, |name| name.as_str() == "self") {
+            if depth > 0 {
+                // An item of the stripped module itself.
+                return;
+            }
+            segments = &segments[1..];
+            has_prefix = true;
+        }
+        while segments.first().map_or(false, |name| name.as_str() == "super") {
+            if depth > 0 {
+                depth -= 1;
+            } else {
+                match self.get_nearest_normal_module_parent(module) {
+                    Some(parent) => module = parent,
+                    None => return,
+                }
+            }
+            segments = &segments[1..];
+            has_prefix = true;
+        }
+        if has_prefix && depth > 0 {
+            return;
+        }
+
+        // Without a prefix, `use` paths start at the crate root and others
+        // in the enclosing scope; we can't tell them apart anymore.
+        if !self.mark_test_only_path(module, segments, items, assoc_names) && !has_prefix {
+            self.mark_test_only_path(root, segments, items, assoc_names);
+        }
+    }
+
+    /// Resolves `segments` from `module`, marking the local items along the
+    /// way. Returns false if the path doesn't resolve.
+    fn mark_test_only_path(&mut self,
+                           mut module: Module<'a>,
+                           segments: &[Name],
+                           items: &mut NodeSet,
+                           assoc_names: &mut Vec<Name>)
+                           -> bool {
+        for (i, &name) in segments.iter().enumerate() {
+            if i + 1 == segments.len() {
+                let mut found = false;
+                for &namespace in &[TypeNS, ValueNS] {
+                    if let Success(binding) =
+                            self.resolve_name_in_module(module, name, namespace, true, false) {
+                        self.mark_test_only_binding(binding, items);
+                        found = true;
+                    }
+                }
+                return found;
+            }
+
+            match self.resolve_name_in_module(module, name, TypeNS, true, false) {
+                Success(binding) => {
+                    self.mark_test_only_binding(binding, items);
+                    match binding.module() {
+                        Some(child) => module = child,
+                        None => {
+                            // A type, and the rest of the path one of its
+                            // associated items.
+                            assoc_names.push(segments[i + 1]);
+                            return true;
+                        }
+                    }
+                }
+                _ => return false,
+            }
+        }
+        false
+    }
+
+    fn mark_test_only_binding(&self, binding: &NameBinding, items: &mut NodeSet) {
+        match binding.def() {
+            Some(Def::Label(..)) | Some(Def::PrimTy(..)) | Some(Def::SelfTy(..)) |
+            Some(Def::Local(..)) | Some(Def::Upvar(..)) | Some(Def::Err) | None => {}
+            Some(def) => {
+                if let Some(node_id) = self.ast_map.as_local_node_id(def.def_id()) {
+                    items.insert(node_id);
+                }
+            }
+        }
+    }
 }
 
 
@@ -3683,6 +3885,7 @@ pub fn resolve_crate<'a, 'tcx>(session: &'a Session,
     let mut resolver = create_resolver(session, ast_map, krate, make_glob_map, &arenas, None);
 
     resolver.resolve_crate(krate);
+    resolver.resolve_test_only_uses();
 
     check_unused::check_crate(&mut resolver, krate);
     resolver.report_privacy_errors();
@@ -10,6 +10,7 @@
 
 use back::lto;
 use back::link::{get_linker, remove};
+use rustc_back::jobserver;
 use session::config::{OutputFilenames, Passes, SomePasses, AllPasses};
 use session::Session;
 use session::config::{self, OutputType};
@@ -19,16 +20,20 @@ use llvm::SMDiagnosticRef;
 use trans::{CrateTranslation, ModuleTranslation};
 use util::common::time;
 use util::common::path2cstr;
-use syntax::codemap;
+use syntax::codemap::{self, BytePos, CodeMap, Pos, Span};
 use syntax::errors::{self, Handler, Level};
 use syntax::errors::emitter::Emitter;
 
+use std::cmp;
 use std::collections::HashMap;
 use std::ffi::{CStr, CString};
 use std::fs;
+use std::io::Read;
+use std::iter;
 use std::path::{Path, PathBuf};
 use std::str;
 use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::channel;
 use std::thread;
 use libc::{c_uint, c_int, c_void};
@@ -79,7 +84,16 @@ impl SharedEmitter {
         }
     }
 
-    fn dump(&mut self, handler: &Handler) {
+    /// The buffered diagnostics as `level: message` lines.
+    fn messages(&self) -> Vec<String> {
+        let buffer = self.buffer.lock().unwrap();
+        buffer.iter().map(|diag| format!("{}: {}", diag.lvl, diag.msg)).collect()
+    }
+
+    /// Emits the buffered diagnostics through `handler`, and returns them
+    /// as they are returned by `messages`.
+    fn dump(&mut self, handler: &Handler) -> Vec<String> {
+        let emitted = self.messages();
         let mut buffer = self.buffer.lock().unwrap();
         for diag in &*buffer {
             match diag.code {
@@ -97,6 +111,7 @@ impl SharedEmitter {
             }
         }
         buffer.clear();
+        emitted
     }
 }
 
@@ -258,6 +273,10 @@ pub struct ModuleConfig {
     // make the object file bitcode. Provides easy compatibility with
     // emscripten's ecc compiler, when used as the linker.
     obj_is_bitcode: bool,
+    // Whether the module's LLVM context is disposed of along with it. The
+    // copies made for `-Z verify-parallel-determinism` share the context of
+    // the original module.
+    owns_context: bool,
 }
 
 unsafe impl Send for ModuleConfig { }
@@ -284,7 +303,8 @@ impl ModuleConfig {
             vectorize_loop: false,
             vectorize_slp: false,
             merge_functions: false,
-            inline_threshold: None
+            inline_threshold: None,
+            owns_context: true,
         }
     }
 
@@ -292,7 +312,9 @@ impl ModuleConfig {
         self.no_verify = sess.no_verify();
         self.no_prepopulate_passes = sess.opts.cg.no_prepopulate_passes;
         self.no_builtins = trans.no_builtins;
-        self.time_passes = sess.time_passes();
+        // Passes timed on the codegen threads are only ever printed, so leave
+        // them out when the timings are being recorded as JSON instead.
+        self.time_passes = sess.opts.debugging_opts.time_passes;
         self.inline_threshold = sess.opts.cg.inline_threshold;
         self.obj_is_bitcode = sess.target.target.options.obj_is_bitcode;
 
@@ -388,18 +410,34 @@ unsafe extern "C" fn diagnostic_handler(info: DiagnosticInfoRef, user: *mut c_vo
             let pass_name = str::from_utf8(CStr::from_ptr(opt.pass_name).to_bytes())
                                 .ok()
                                 .expect("got a non-UTF8 pass name from LLVM");
-            let enabled = match cgcx.remark {
-                AllPasses => true,
-                SomePasses(ref v) => v.iter().any(|s| *s == pass_name),
-            };
+            if !remark_enabled(&cgcx.remark, pass_name) {
+                return;
+            }
 
-            if enabled {
-                let loc = llvm::debug_loc_to_string(llcx, opt.debug_loc);
-                cgcx.handler.note_without_error(&format!("optimization {} for {} at {}: {}",
-                                                opt.kind.describe(),
-                                                pass_name,
-                                                if loc.is_empty() { "[unknown]" } else { &*loc },
-                                                llvm::twine_to_string(opt.message)));
+            // Remarks are attached to the code they are about when we can
+            // get at the codemap, i.e. when not on a worker thread.
+            let span = cgcx.lto_ctxt.and_then(|(sess, _)| {
+                llvm::unpack_debug_loc(opt.debug_loc)
+                    .and_then(|loc| source_span(sess.codemap(), &loc))
+                    .map(|span| (sess, span))
+            });
+            let message = llvm::twine_to_string(opt.message);
+            match span {
+                Some((sess, span)) => {
+                    sess.span_note_without_error(span, &format!("optimization {} for {}: {}",
+                                                                opt.kind.describe(),
+                                                                pass_name,
+                                                                message));
+                }
+                None => {
+                    let loc = llvm::debug_loc_to_string(llcx, opt.debug_loc);
+                    let loc = if loc.is_empty() { "[unknown]" } else { &*loc };
+                    cgcx.handler.note_without_error(&format!("optimization {} for {} at {}: {}",
+                                                             opt.kind.describe(),
+                                                             pass_name,
+                                                             loc,
+                                                             message));
+                }
             }
         }
 
@@ -407,6 +445,51 @@ unsafe extern "C" fn diagnostic_handler(info: DiagnosticInfoRef, user: *mut c_vo
     }
 }
 
+/// Names that `-C remark` accepts for groups of LLVM passes, besides the
+/// names of the passes themselves.
+const REMARK_GROUPS: &'static [(&'static str, &'static [&'static str])] = &[
+    ("inline", &["inline", "always-inline"]),
+    ("vectorize", &["loop-vectorize", "slp-vectorizer"]),
+];
+
+fn remark_enabled(remark: &Passes, pass_name: &str) -> bool {
+    match *remark {
+        AllPasses => true,
+        SomePasses(ref v) => v.iter().any(|s| {
+            *s == pass_name || REMARK_GROUPS.iter().any(|&(group, passes)| {
+                *s == group && passes.contains(&pass_name)
+            })
+        }),
+    }
+}
+
+/// The part of a line of source a debug location points to. We give all
+/// debug locations a column of 0, in which case that's the whole line.
+fn source_span(codemap: &CodeMap, loc: &llvm::SourceLoc) -> Option<Span> {
+    if loc.line == 0 {
+        return None;
+    }
+    let path = Path::new(&loc.directory).join(&loc.file);
+    let files = codemap.files.borrow();
+    let filemap = match files.iter().find(|fm| fm.name == loc.file || Path::new(&fm.name) == path) {
+        Some(filemap) => filemap,
+        None => return None,
+    };
+    let text = match filemap.get_line(loc.line - 1) {
+        Some(text) => text,
+        None => return None,
+    };
+    let line_start = filemap.lines.borrow()[loc.line - 1];
+    let lo = if loc.col > 0 {
+        cmp::min(loc.col - 1, text.len())
+    } else {
+        text.len() - text.trim_left().len()
+    };
+    let hi = cmp::max(lo, text.trim_right().len());
+    Some(codemap::mk_sp(line_start + BytePos::from_usize(lo),
+                        line_start + BytePos::from_usize(hi)))
+}
+
 // Unsafe due to LLVM calls.
 unsafe fn optimize_and_codegen(cgcx: &CodegenContext,
                                mtrans: ModuleTranslation,
@@ -605,7 +688,9 @@ unsafe fn optimize_and_codegen(cgcx: &CodegenContext,
     }
 
     llvm::LLVMDisposeModule(llmod);
-    llvm::LLVMContextDispose(llcx);
+    if config.owns_context {
+        llvm::LLVMContextDispose(llcx);
+    }
     llvm::LLVMRustDisposeTargetMachine(tm);
 }
 
@@ -676,42 +761,75 @@ pub fn run_passes(sess: &Session,
                 modules_config.emit_obj = true;
                 metadata_config.emit_obj = true;
             },
-            OutputType::DepInfo => {}
+            OutputType::DepInfo |
+            OutputType::Metadata |
+            OutputType::Mir |
+            OutputType::ImplGraph |
+            OutputType::AstJson |
+            OutputType::AccessLevels => {}
         }
     }
 
+    let mut verify_determinism = sess.opts.debugging_opts.verify_parallel_determinism;
+    if verify_determinism && sess.opts.cg.codegen_units == 1 {
+        sess.warn("`-Z verify-parallel-determinism` has no effect with a single codegen unit");
+        verify_determinism = false;
+    }
+    if verify_determinism {
+        // Keep the module as translated and as optimized, so a difference
+        // can be traced to the passes that introduced it.
+        modules_config.emit_no_opt_bc = true;
+        modules_config.emit_bc = true;
+    }
+
     modules_config.set_flags(sess, trans);
     metadata_config.set_flags(sess, trans);
 
+    // This has to happen before the parallel run disposes of the modules.
+    let serial_run = if verify_determinism {
+        Some(run_serial_copy(sess, trans, &modules_config, crate_output))
+    } else {
+        None
+    };
 
-    // Populate a buffer with a list of codegen threads.  Items are processed in
-    // LIFO order, just because it's a tiny bit simpler that way.  (The order
-    // doesn't actually matter.)
-    let mut work_items = Vec::with_capacity(1 + trans.modules.len());
-
-    {
-        let work = build_work_item(sess,
-                                   trans.metadata_module,
-                                   metadata_config.clone(),
-                                   crate_output.clone(),
-                                   "metadata".to_string());
-        work_items.push(work);
-    }
 
-    for (index, mtrans) in trans.modules.iter().enumerate() {
-        let work = build_work_item(sess,
-                                   *mtrans,
-                                   modules_config.clone(),
-                                   crate_output.clone(),
-                                   format!("{}", index));
-        work_items.push(work);
-    }
+    // The work items are built lazily, one codegen unit at a time, so that
+    // the workers can start on the first unit while the target machines for
+    // the rest are still being created. The metadata module comes last.
+    // (The order doesn't otherwise matter.)
+    //
+    // Translation itself is over by now: it spreads items over every codegen
+    // unit as it goes, so no unit is complete before `trans_crate` returns,
+    // and LLVM never runs alongside it.
+    let module_items = trans.modules.iter().enumerate().map(|(index, mtrans)| {
+        build_work_item(sess,
+                        *mtrans,
+                        modules_config.clone(),
+                        crate_output.clone(),
+                        format!("{}", index))
+    });
+    let metadata_item = iter::once(()).map(|()| {
+        build_work_item(sess,
+                        trans.metadata_module,
+                        metadata_config.clone(),
+                        crate_output.clone(),
+                        "metadata".to_string())
+    });
+    let work_items = module_items.chain(metadata_item);
 
     // Process the work items, optionally using worker threads.
     if sess.opts.cg.codegen_units == 1 {
         run_work_singlethreaded(sess, &trans.reachable, work_items);
     } else {
-        run_work_multithreaded(sess, work_items, sess.opts.cg.codegen_units);
+        let diagnostics = run_work_multithreaded(sess, work_items, sess.opts.cg.codegen_units);
+        if let Some((serial_output, serial_diagnostics)) = serial_run {
+            compare_with_serial_run(sess,
+                                    trans.modules.len(),
+                                    crate_output,
+                                    &serial_output,
+                                    &diagnostics,
+                                    &serial_diagnostics);
+        }
     }
 
     // All codegen is finished.
@@ -780,7 +898,12 @@ pub fn run_passes(sess: &Session,
                 copy_if_one_unit("0.o", OutputType::Object, true);
             }
             OutputType::Exe |
-            OutputType::DepInfo => {}
+            OutputType::DepInfo |
+            OutputType::Metadata |
+            OutputType::Mir |
+            OutputType::ImplGraph |
+            OutputType::AstJson |
+            OutputType::AccessLevels => {}
         }
     }
     let user_wants_bitcode = user_wants_bitcode;
@@ -877,28 +1000,46 @@ fn execute_work_item(cgcx: &CodegenContext,
     }
 }
 
-fn run_work_singlethreaded(sess: &Session,
-                           reachable: &[String],
-                           work_items: Vec<WorkItem>) {
+fn run_work_singlethreaded<I>(sess: &Session,
+                              reachable: &[String],
+                              work_items: I)
+    where I: Iterator<Item=WorkItem>
+{
     let cgcx = CodegenContext::new_with_session(sess, reachable);
 
     // Since we're running single-threaded, we can pass the session to
     // the proc, allowing `optimize_and_codegen` to perform LTO.
-    for work in work_items.into_iter().rev() {
+    for work in work_items {
         execute_work_item(&cgcx, work);
     }
 }
 
-fn run_work_multithreaded(sess: &Session,
-                          work_items: Vec<WorkItem>,
-                          num_workers: usize) {
-    // Run some workers to process the work items.
-    let work_items_arc = Arc::new(Mutex::new(work_items));
+/// Runs the work items on `num_workers` threads, returning the diagnostics
+/// they produced in the order they were emitted.
+fn run_work_multithreaded<I>(sess: &Session,
+                             work_items: I,
+                             num_workers: usize)
+                             -> Vec<String>
+    where I: Iterator<Item=WorkItem>
+{
+    // Start the workers first, then hand them the work items over a channel
+    // as each one is built, so no worker sits idle waiting for the whole
+    // batch.
+    let (work_tx, work_rx) = channel::<WorkItem>();
+    let work_rx = Arc::new(Mutex::new(work_rx));
+    // Set once the first worker stops, which it only does when the channel
+    // is drained or it has panicked, to wake up workers still waiting for a
+    // jobserver token.
+    let work_done = Arc::new(AtomicBool::new(false));
+    let jobserver = jobserver::Client::from_env().map(Arc::new);
     let mut diag_emitter = SharedEmitter::new();
+    let mut diagnostics = vec![];
     let mut futures = Vec::with_capacity(num_workers);
 
     for i in 0..num_workers {
-        let work_items_arc = work_items_arc.clone();
+        let work_rx = work_rx.clone();
+        let work_done = work_done.clone();
+        let jobserver = jobserver.clone();
         let diag_emitter = diag_emitter.clone();
         let plugin_passes = sess.plugin_llvm_passes.borrow().clone();
         let remark = sess.opts.cg.remark.clone();
@@ -908,6 +1049,7 @@ fn run_work_multithreaded(sess: &Session,
         futures.push(rx);
 
         thread::Builder::new().name(format!("codegen-{}", i)).spawn(move || {
+            let _done = if i == 0 { Some(SetOnDrop(work_done.clone())) } else { None };
             let diag_handler = Handler::with_emitter(true, false, box diag_emitter);
 
             // Must construct cgcx inside the proc because it has non-Send
@@ -921,24 +1063,55 @@ fn run_work_multithreaded(sess: &Session,
             };
 
             loop {
+                // When run under a make jobserver, every worker but the first
+                // takes a token for each work item it runs. The first one
+                // runs on the job slot rustc itself was started with.
+                let token = match jobserver {
+                    Some(ref client) if i > 0 => {
+                        match jobserver::Client::acquire(client, &work_done) {
+                            Ok(Some(token)) => Some(token),
+                            Ok(None) => break,
+                            Err(e) => {
+                                cgcx.handler.warn(&format!("failed to acquire a jobserver \
+                                                            token: {}", e));
+                                break;
+                            }
+                        }
+                    }
+                    _ => None,
+                };
+
                 // Avoid holding the lock for the entire duration of the match.
-                let maybe_work = work_items_arc.lock().unwrap().pop();
+                // The channel is closed once every work item has been sent.
+                let maybe_work = work_rx.lock().unwrap().recv();
                 match maybe_work {
-                    Some(work) => {
+                    Ok(work) => {
                         execute_work_item(&cgcx, work);
 
                         // Make sure to fail the worker so the main thread can
                         // tell that there were errors.
                         cgcx.handler.abort_if_errors();
                     }
-                    None => break,
+                    Err(_) => break,
                 }
+                drop(token);
             }
 
             tx.take().unwrap().send(()).unwrap();
         }).unwrap();
     }
 
+    for work in work_items {
+        // If every worker has died there is no one left to send to; the
+        // panic is reported below.
+        if work_tx.send(work).is_err() {
+            break;
+        }
+        // Show diagnostics from the units that are already done.
+        diagnostics.extend(diag_emitter.dump(sess.diagnostic()));
+    }
+    drop(work_tx);
+
     let mut panicked = false;
     for rx in futures {
         match rx.recv() {
@@ -948,11 +1121,154 @@ fn run_work_multithreaded(sess: &Session,
             },
         }
         // Display any new diagnostics.
-        diag_emitter.dump(sess.diagnostic());
+        diagnostics.extend(diag_emitter.dump(sess.diagnostic()));
     }
     if panicked {
         sess.fatal("aborting due to worker thread panic");
     }
+    diagnostics
+}
+
+struct SetOnDrop(Arc<AtomicBool>);
+
+impl Drop for SetOnDrop {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+/// The serial half of `-Z verify-parallel-determinism`: runs copies of the
+/// codegen units one after the other on this thread, writing their outputs
+/// to a `<crate>.serial` directory next to the real ones, under the same
+/// names. Returns the names of those outputs and the diagnostics the run
+/// produced.
+fn run_serial_copy(sess: &Session,
+                   trans: &CrateTranslation,
+                   config: &ModuleConfig,
+                   crate_output: &OutputFilenames)
+                   -> (OutputFilenames, Vec<String>) {
+    // A `<crate>.serial` stem would not do: `with_extension` would replace
+    // its `.serial` part, giving the same paths as the real outputs.
+    let mut serial_output = crate_output.clone();
+    serial_output.out_directory =
+        crate_output.out_directory.join(format!("{}.serial", crate_output.filestem()));
+    if let Err(e) = fs::create_dir_all(&serial_output.out_directory) {
+        sess.fatal(&format!("failed to create `{}` for the serial build: {}",
+                            serial_output.out_directory.display(), e));
+    }
+
+    let diag_emitter = SharedEmitter::new();
+    let diag_handler = Handler::with_emitter(true, false, box diag_emitter.clone());
+    let cgcx = CodegenContext {
+        lto_ctxt: None,
+        handler: &diag_handler,
+        plugin_passes: sess.plugin_llvm_passes.borrow().clone(),
+        remark: sess.opts.cg.remark.clone(),
+        worker: 0,
+    };
+
+    for (index, mtrans) in trans.modules.iter().enumerate() {
+        let copy = ModuleTranslation {
+            llcx: mtrans.llcx,
+            llmod: unsafe { llvm::LLVMCloneModule(mtrans.llmod) },
+        };
+        let mut config = config.clone();
+        config.owns_context = false;
+        let work = build_work_item(sess, copy, config, serial_output.clone(),
+                                   format!("{}", index));
+        execute_work_item(&cgcx, work);
+    }
+
+    // The diagnostics are only compared, not shown; the parallel run will
+    // report the same problems.
+    (serial_output, diag_emitter.messages())
+}
+
+/// Compares the outputs and diagnostics of the parallel run with those of
+/// the serial one, and reports the first difference. The serial outputs are
+/// kept if they differ, for inspection, and removed otherwise.
+fn compare_with_serial_run(sess: &Session,
+                           units: usize,
+                           crate_output: &OutputFilenames,
+                           serial_output: &OutputFilenames,
+                           diagnostics: &[String],
+                           serial_diagnostics: &[String]) {
+    // The outputs of each unit in the order the pipeline produces them,
+    // with the passes that produce them.
+    let stages = [("no-opt.bc", "translation"),
+                  ("bc", "the LLVM optimization passes"),
+                  ("ll", "the LLVM optimization passes"),
+                  ("s", "the codegen passes"),
+                  ("o", "the codegen passes")];
+
+    let mut divergence = None;
+    'units: for unit in 0..units {
+        for &(ext, pass) in &stages {
+            let ext = format!("{}.{}", unit, ext);
+            let serial = serial_output.with_extension(&ext);
+            if !serial.exists() {
+                continue;
+            }
+            let parallel = crate_output.with_extension(&ext);
+            if !same_contents(&parallel, &serial) {
+                divergence = Some((unit, pass, parallel, serial));
+                break 'units;
+            }
+        }
+    }
+
+    match divergence {
+        Some((unit, pass, parallel, serial)) => {
+            sess.struct_err(&format!("parallel codegen is not deterministic: codegen unit {} \
+                                      differs from a serial build after {}", unit, pass))
+                .note(&format!("parallel output: `{}`", parallel.display()))
+                .note(&format!("serial output: `{}`", serial.display()))
+                .emit();
+        }
+        None => {
+            for unit in 0..units {
+                for &(ext, _) in &stages {
+                    let path = serial_output.with_extension(&format!("{}.{}", unit, ext));
+                    if path.exists() {
+                        remove(sess, &path);
+                    }
+                }
+            }
+            // Anything else left in there was not ours to remove.
+            let _ = fs::remove_dir(&serial_output.out_directory);
+        }
+    }
+
+    if diagnostics != serial_diagnostics {
+        let mut err = sess.struct_err("parallel codegen is not deterministic: its diagnostics \
+                                       differ from those of a serial build");
+        let first = diagnostics.iter().zip(serial_diagnostics).position(|(a, b)| a != b)
+                               .unwrap_or(cmp::min(diagnostics.len(), serial_diagnostics.len()));
+        let none = "nothing".to_string();
+        err.note(&format!("diagnostic #{} of the parallel build: {}",
+                          first + 1, diagnostics.get(first).unwrap_or(&none)));
+        err.note(&format!("diagnostic #{} of the serial build: {}",
+                          first + 1, serial_diagnostics.get(first).unwrap_or(&none)));
+        err.emit();
+    }
+
+    if !sess.opts.cg.save_temps {
+        // Only kept for the comparison.
+        for unit in 0..units {
+            remove(sess, &crate_output.with_extension(&format!("{}.no-opt.bc", unit)));
+        }
+    }
+}
+
+fn same_contents(a: &Path, b: &Path) -> bool {
+    let read = |path: &Path| {
+        let mut contents = vec![];
+        fs::File::open(path).and_then(|mut f| f.read_to_end(&mut contents)).map(|_| contents)
+    };
+    match (read(a), read(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
 }
 
 pub fn run_assembler(sess: &Session, outputs: &OutputFilenames) {
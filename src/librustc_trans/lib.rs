@@ -39,7 +39,6 @@
 
 extern crate arena;
 extern crate flate;
-extern crate getopts;
 extern crate graphviz;
 extern crate libc;
 extern crate rustc;
@@ -47,7 +46,6 @@ extern crate rustc_back;
 extern crate rustc_data_structures;
 extern crate rustc_front;
 pub extern crate rustc_llvm as llvm;
-extern crate rustc_mir;
 extern crate rustc_platform_intrinsics as intrinsics;
 extern crate serialize;
 
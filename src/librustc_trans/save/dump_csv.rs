@@ -281,6 +281,7 @@ impl <'l, 'tcx> DumpCsvVisitor<'l, 'tcx> {
             Def::AssociatedConst(..) |
             Def::Local(..) |
             Def::Variant(..) |
+            Def::ConstParam(..) |
             Def::Upvar(..) => Some(recorder::VarRef),
 
             Def::Fn(..) => Some(recorder::FnRef),
@@ -907,8 +908,8 @@ impl<'l, 'tcx, 'v> Visitor<'v> for DumpCsvVisitor<'l, 'tcx> {
                         let mut name_string = String::new();
                         let glob_map = &self.analysis.glob_map;
                         let glob_map = glob_map.as_ref().unwrap();
-                        if glob_map.contains_key(&item.id) {
-                            for n in glob_map.get(&item.id).unwrap() {
+                        if let Some(names) = glob_map.names_imported_by(item.id) {
+                            for n in names {
                                 if !name_string.is_empty() {
                                     name_string.push_str(", ");
                                 }
@@ -986,6 +987,8 @@ impl<'l, 'tcx, 'v> Visitor<'v> for DumpCsvVisitor<'l, 'tcx> {
             }
             Trait(_, ref generics, ref trait_refs, ref methods) =>
                 self.process_trait(item, generics, trait_refs, methods),
+            TraitAlias(ref generics, ref bounds) =>
+                self.process_trait(item, generics, bounds, &[]),
             Mod(ref m) => {
                 self.process_mod(item);
                 self.nest(item.id, |v| visit::walk_mod(v, m));
@@ -571,6 +571,7 @@ impl<'l, 'tcx: 'l> SaveContext<'l, 'tcx> {
             Def::Static(..) |
             Def::Const(..) |
             Def::AssociatedConst(..) |
+            Def::ConstParam(..) |
             Def::Variant(..) => {
                 Some(Data::VariableRefData(VariableRefData {
                     name: self.span_utils.snippet(sub_span.unwrap()),
@@ -692,9 +693,11 @@ impl<'l, 'tcx: 'l> SaveContext<'l, 'tcx> {
         let callee = option_try!(callee);
         let callee_span = option_try!(callee.span);
 
-        // Ignore attribute macros, their spans are usually mangled
-        if let MacroAttribute(_) = callee.format {
-            return None;
+        // Ignore attribute macros, their spans are usually mangled, and code
+        // the compiler generated by itself.
+        match callee.format {
+            MacroAttribute(_) | MacroDerive(_) | CompilerInjection(_) => return None,
+            MacroBang(_) => {}
         }
 
         // If the callee is an imported macro from an external crate, need to get
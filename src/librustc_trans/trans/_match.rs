@@ -496,13 +496,13 @@ fn expand_nested_bindings<'a, 'p, 'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
     }).collect()
 }
 
-fn enter_match<'a, 'b, 'p, 'blk, 'tcx, F>(bcx: Block<'blk, 'tcx>,
-                                          dm: &RefCell<DefMap>,
-                                          m: &[Match<'a, 'p, 'blk, 'tcx>],
-                                          col: usize,
-                                          val: MatchInput,
-                                          mut e: F)
-                                          -> Vec<Match<'a, 'p, 'blk, 'tcx>> where
+fn enter_match<'a, 'p, 'blk, 'tcx, F>(bcx: Block<'blk, 'tcx>,
+                                      dm: &RefCell<DefMap>,
+                                      m: &[Match<'a, 'p, 'blk, 'tcx>],
+                                      col: usize,
+                                      val: MatchInput,
+                                      mut e: F)
+                                      -> Vec<Match<'a, 'p, 'blk, 'tcx>> where
     F: FnMut(&[&'p hir::Pat]) -> Option<Vec<&'p hir::Pat>>,
 {
     debug!("enter_match(bcx={}, m={:?}, col={}, val={})",
@@ -1531,6 +1531,7 @@ fn create_bindings_map<'blk, 'tcx>(bcx: Block<'blk, 'tcx>, pat: &hir::Pat,
     let reassigned = is_discr_reassigned(bcx, discr, body);
     let mut bindings_map = FnvHashMap();
     pat_bindings(&tcx.def_map, &pat, |bm, p_id, span, path1| {
+        let bm = tcx.tables.borrow().binding_mode(p_id, bm);
         let name = path1.node;
         let variable_ty = node_id_type(bcx, p_id);
         let llvariable_ty = type_of::type_of(ccx, variable_ty);
@@ -1807,9 +1808,19 @@ pub fn bind_irrefutable_pat<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
     let mut bcx = bcx;
     let tcx = bcx.tcx();
     let ccx = bcx.ccx();
+
+    // Follow the references typeck dereferenced implicitly to match the
+    // value against `pat` (default binding modes).
+    let mut val = val;
+    let implicit_derefs = tcx.tables.borrow().pat_adjustments(pat.id).len();
+    for _ in 0..implicit_derefs {
+        val = MatchInput::from_val(Load(bcx, val.val));
+    }
+
     match pat.node {
         PatKind::Ident(pat_binding_mode, ref path1, ref inner) => {
             if pat_is_binding(&tcx.def_map.borrow(), &pat) {
+                let pat_binding_mode = tcx.tables.borrow().binding_mode(pat.id, pat_binding_mode);
                 // Allocate the stack slot where the value of this
                 // binding will live and place it into the appropriate
                 // map.
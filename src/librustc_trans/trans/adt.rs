@@ -141,7 +141,10 @@ pub enum Repr<'tcx> {
         nndiscr: Disr,
         discrfield: DiscrField,
         nullfields: Vec<Ty<'tcx>>,
-    }
+    },
+    /// Untagged unions: every field starts at offset zero, and nothing
+    /// records which of them currently holds a value.
+    UntaggedUnion(Union<'tcx>),
 }
 
 /// For structs, and struct-like parts of anything fancier.
@@ -156,6 +159,16 @@ pub struct Struct<'tcx> {
     pub fields: Vec<Ty<'tcx>>,
 }
 
+/// For untagged unions.
+#[derive(Eq, PartialEq, Debug)]
+pub struct Union<'tcx> {
+    /// The size of the largest field, rounded up to the alignment.
+    pub size: u64,
+    pub align: u32,
+    pub packed: bool,
+    pub fields: Vec<Ty<'tcx>>,
+}
+
 #[derive(Copy, Clone)]
 pub struct MaybeSizedValue {
     pub value: ValueRef,
@@ -255,6 +268,13 @@ fn represent_type_uncached<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
         ty::TyTuple(ref elems) => {
             Univariant(mk_struct(cx, &elems[..], false, t), 0)
         }
+        ty::TyStruct(def, substs) if def.is_union() => {
+            let ftys = def.struct_variant().fields.iter().map(|field| {
+                monomorphize::field_ty(cx.tcx(), substs, field)
+            }).collect::<Vec<_>>();
+            let packed = cx.tcx().lookup_packed(def.did);
+            UntaggedUnion(mk_union(cx, &ftys[..], packed, t))
+        }
         ty::TyStruct(def, substs) => {
             let mut ftys = def.struct_variant().fields.iter().map(|field| {
                 monomorphize::field_ty(cx.tcx(), substs, field)
@@ -576,6 +596,33 @@ fn mk_struct<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
     }
 }
 
+fn mk_union<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
+                      tys: &[Ty<'tcx>], packed: bool,
+                      scapegoat: Ty<'tcx>)
+                      -> Union<'tcx> {
+    let mut size = 0;
+    let mut align = 1;
+    for &ty in tys {
+        let llty = type_of::sizing_type_of(cx, ty);
+        size = std::cmp::max(size, machine::llsize_of_alloc(cx, llty));
+        if !packed {
+            align = std::cmp::max(align, machine::llalign_of_min(cx, llty));
+        }
+    }
+    let size = roundup(size, align);
+
+    if size >= cx.obj_size_bound() {
+        cx.report_overbig_object(scapegoat);
+    }
+
+    Union {
+        size: size,
+        align: align,
+        packed: packed,
+        fields: tys.to_vec(),
+    }
+}
+
 #[derive(Debug)]
 struct IntBounds {
     slo: i64,
@@ -748,7 +795,7 @@ pub fn incomplete_type_of<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
 pub fn finish_type_of<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
                                 r: &Repr<'tcx>, llty: &mut Type) {
     match *r {
-        CEnum(..) | General(..) | RawNullablePointer { .. } => { }
+        CEnum(..) | General(..) | RawNullablePointer { .. } | UntaggedUnion(..) => { }
         Univariant(ref st, _) | StructWrappedNullablePointer { nonnull: ref st, .. } =>
             llty.set_struct_body(&struct_llfields(cx, st, false, false),
                                  st.packed)
@@ -822,17 +869,7 @@ fn generic_type_of<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
 
             let discr_ty = ll_inttype(cx, ity);
             let discr_size = machine::llsize_of_alloc(cx, discr_ty);
-            let fill_ty = match align_s {
-                1 => Type::array(&Type::i8(cx), align_units),
-                2 => Type::array(&Type::i16(cx), align_units),
-                4 => Type::array(&Type::i32(cx), align_units),
-                8 if machine::llalign_of_min(cx, Type::i64(cx)) == 8 =>
-                                 Type::array(&Type::i64(cx), align_units),
-                a if a.count_ones() == 1 => Type::array(&Type::vector(&Type::i32(cx), a / 4),
-                                                              align_units),
-                _ => panic!("unsupported enum alignment: {}", align)
-            };
-            assert_eq!(machine::llalign_of_min(cx, fill_ty), align);
+            let fill_ty = aligned_fill_ty(cx, align, align_units);
             assert_eq!(align_s % discr_size, 0);
             let mut fields: Vec<Type> =
                 [discr_ty,
@@ -856,9 +893,41 @@ fn generic_type_of<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
                 }
             }
         }
+        UntaggedUnion(ref un) => {
+            // The fields overlap, so the union is just enough
+            // alignment-sized units to hold the largest of them.
+            let align_s = un.align as u64;
+            assert_eq!(un.size % align_s, 0);
+            let fields = [aligned_fill_ty(cx, un.align, un.size / align_s)];
+            match name {
+                None => TypeContext::direct(Type::struct_(cx, &fields, false)),
+                Some(name) => {
+                    let mut llty = Type::named_struct(cx, name);
+                    llty.set_struct_body(&fields, false);
+                    TypeContext::direct(llty)
+                }
+            }
+        }
     }
 }
 
+/// An array of `units` integer-like elements, each of which has size and
+/// alignment `align`.
+fn aligned_fill_ty(cx: &CrateContext, align: machine::llalign, units: u64) -> Type {
+    let fill_ty = match align {
+        1 => Type::array(&Type::i8(cx), units),
+        2 => Type::array(&Type::i16(cx), units),
+        4 => Type::array(&Type::i32(cx), units),
+        8 if machine::llalign_of_min(cx, Type::i64(cx)) == 8 =>
+                         Type::array(&Type::i64(cx), units),
+        a if a.count_ones() == 1 => Type::array(&Type::vector(&Type::i32(cx), a as u64 / 4),
+                                                units),
+        _ => panic!("unsupported alignment: {}", align)
+    };
+    assert_eq!(machine::llalign_of_min(cx, fill_ty), align);
+    fill_ty
+}
+
 fn struct_llfields<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>, st: &Struct<'tcx>,
                              sizing: bool, dst: bool) -> Vec<Type> {
     if sizing {
@@ -884,7 +953,7 @@ pub fn trans_switch<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
             (_match::Switch, Some(trans_get_discr(bcx, r, scrutinee, None,
                                                   range_assert)))
         }
-        Univariant(..) => {
+        Univariant(..) | UntaggedUnion(..) => {
             // N.B.: Univariant means <= 1 enum variants (*not* == 1 variants).
             (_match::Single, None)
         }
@@ -896,6 +965,7 @@ pub fn is_discr_signed<'tcx>(r: &Repr<'tcx>) -> bool {
         CEnum(ity, _, _) => ity.is_signed(),
         General(ity, _, _) => ity.is_signed(),
         Univariant(..) => false,
+        UntaggedUnion(..) => false,
         RawNullablePointer { .. } => false,
         StructWrappedNullablePointer { .. } => false,
     }
@@ -916,7 +986,7 @@ pub fn trans_get_discr<'blk, 'tcx>(bcx: Block<'blk, 'tcx>, r: &Repr<'tcx>,
             load_discr(bcx, ity, ptr, Disr(0), Disr(cases.len() as u64 - 1),
                        range_assert)
         }
-        Univariant(..) => C_u8(bcx.ccx(), 0),
+        Univariant(..) | UntaggedUnion(..) => C_u8(bcx.ccx(), 0),
         RawNullablePointer { nndiscr, nnty, .. } =>  {
             let cmp = if nndiscr == Disr(0) { IntEQ } else { IntNE };
             let llptrty = type_of::sizing_type_of(bcx.ccx(), nnty);
@@ -978,8 +1048,8 @@ pub fn trans_case<'blk, 'tcx>(bcx: Block<'blk, 'tcx>, r: &Repr, discr: Disr)
         General(ity, _, _) => {
             C_integral(ll_inttype(bcx.ccx(), ity), discr.0, true)
         }
-        Univariant(..) => {
-            bcx.ccx().sess().bug("no cases for univariants or structs")
+        Univariant(..) | UntaggedUnion(..) => {
+            bcx.ccx().sess().bug("no cases for univariants, structs or unions")
         }
         RawNullablePointer { .. } |
         StructWrappedNullablePointer { .. } => {
@@ -1015,6 +1085,9 @@ pub fn trans_set_discr<'blk, 'tcx>(bcx: Block<'blk, 'tcx>, r: &Repr<'tcx>,
                       StructGEP(bcx, val, st.fields.len() - 1));
             }
         }
+        UntaggedUnion(..) => {
+            assert_eq!(discr, Disr(0));
+        }
         RawNullablePointer { nndiscr, nnty, ..} => {
             if discr != nndiscr {
                 let llptrty = type_of::sizing_type_of(bcx.ccx(), nnty);
@@ -1056,6 +1129,10 @@ pub fn num_args(r: &Repr, discr: Disr) -> usize {
         General(_, ref cases, dtor) => {
             cases[discr.0 as usize].fields.len() - 1 - (if dtor_active(dtor) { 1 } else { 0 })
         }
+        UntaggedUnion(ref un) => {
+            assert_eq!(discr, Disr(0));
+            un.fields.len()
+        }
         RawNullablePointer { nndiscr, ref nullfields, .. } => {
             if discr == nndiscr { 1 } else { nullfields.len() }
         }
@@ -1083,6 +1160,12 @@ pub fn trans_field_ptr<'blk, 'tcx>(bcx: Block<'blk, 'tcx>, r: &Repr<'tcx>,
         General(_, ref cases, _) => {
             struct_field_ptr(bcx, &cases[discr.0 as usize], val, ix + 1, true)
         }
+        UntaggedUnion(ref un) => {
+            assert_eq!(discr, Disr(0));
+            // Every field lives at the start of the union.
+            let ty = type_of::in_memory_type_of(bcx.ccx(), un.fields[ix]);
+            PointerCast(bcx, val.value, ty.ptr_to())
+        }
         RawNullablePointer { nndiscr, ref nullfields, .. } |
         StructWrappedNullablePointer { nndiscr, ref nullfields, .. } if discr != nndiscr => {
             // The unit-like case might have a nonzero number of unit-like fields.
@@ -1345,6 +1428,14 @@ pub fn trans_const<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>, r: &Repr<'tcx>, discr
             let contents = build_const_struct(ccx, st, vals);
             C_struct(ccx, &contents[..], st.packed)
         }
+        UntaggedUnion(ref un) => {
+            // Only the initialized field is given; pad it out to the
+            // size of the whole union.
+            assert_eq!(discr, Disr(0));
+            assert_eq!(vals.len(), 1);
+            let val_size = machine::llsize_of_alloc(ccx, val_ty(vals[0]));
+            C_struct(ccx, &[vals[0], padding(ccx, un.size - val_size)], un.packed)
+        }
         RawNullablePointer { nndiscr, nnty, .. } => {
             if discr == nndiscr {
                 assert_eq!(vals.len(), 1);
@@ -1456,7 +1547,7 @@ pub fn const_get_discrim(ccx: &CrateContext, r: &Repr, val: ValueRef) -> Disr {
                 attr::UnsignedInt(..) => Disr(const_to_uint(const_get_elt(ccx, val, &[0])))
             }
         }
-        Univariant(..) => Disr(0),
+        Univariant(..) | UntaggedUnion(..) => Disr(0),
         RawNullablePointer { .. } | StructWrappedNullablePointer { .. } => {
             ccx.sess().bug("const discrim access of non c-like enum")
         }
@@ -1474,6 +1565,7 @@ pub fn const_get_field(ccx: &CrateContext, r: &Repr, val: ValueRef,
         CEnum(..) => ccx.sess().bug("element access in C-like enum const"),
         Univariant(..) => const_struct_field(ccx, val, ix),
         General(..) => const_struct_field(ccx, val, ix + 1),
+        UntaggedUnion(..) => ccx.sess().bug("field access in union const"),
         RawNullablePointer { .. } => {
             assert_eq!(ix, 0);
             val
@@ -15,7 +15,7 @@ use middle::ty;
 use middle::infer;
 use session::config::NoDebugInfo;
 use syntax::abi::Abi;
-pub use syntax::attr::InlineAttr;
+pub use syntax::attr::{InlineAttr, OptimizeAttr};
 use syntax::ast;
 use rustc_front::hir;
 use trans::base;
@@ -75,7 +75,6 @@ pub fn unwind(val: ValueRef, can_unwind: bool) {
 
 /// Tell LLVM whether it should optimise function for size.
 #[inline]
-#[allow(dead_code)] // possibly useful function
 pub fn set_optimize_for_size(val: ValueRef, optimize: bool) {
     if optimize {
         llvm::SetFunctionAttribute(val, llvm::Attribute::OptimizeForSize);
@@ -89,11 +88,29 @@ pub fn set_optimize_for_size(val: ValueRef, optimize: bool) {
     }
 }
 
+/// Mark LLVM function to be optimized as `#[optimize]` asks, whatever the crate's opt-level.
+pub fn optimize(val: ValueRef, optimize: OptimizeAttr) {
+    match optimize {
+        OptimizeAttr::Default => {}
+        OptimizeAttr::Size => set_optimize_for_size(val, true),
+        OptimizeAttr::Speed => set_optimize_for_size(val, false),
+        OptimizeAttr::Off => {
+            // LLVM only accepts `optnone` along with `noinline`.
+            set_optimize_for_size(val, false);
+            inline(val, InlineAttr::None);
+            inline(val, InlineAttr::Never);
+            llvm::SetFunctionAttribute(val, llvm::Attribute::OptimizeNone);
+        }
+    }
+}
+
 /// Composite function which sets LLVM attributes for function depending on its AST (#[attribute])
 /// attributes.
 pub fn from_fn_attrs(ccx: &CrateContext, attrs: &[ast::Attribute], llfn: ValueRef) {
     use syntax::attr::*;
     inline(llfn, find_inline_attr(Some(ccx.sess().diagnostic()), attrs));
+    // The attribute was validated by `check_attr`.
+    optimize(llfn, find_optimize_attr(None, attrs));
 
     // FIXME: #11906: Omitting frame pointers breaks retrieving the value of a
     // parameter.
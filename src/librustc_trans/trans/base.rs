@@ -40,6 +40,7 @@ use middle::def_id::DefId;
 use middle::infer;
 use middle::lang_items::{LangItem, ExchangeMallocFnLangItem, StartFnLangItem};
 use middle::weak_lang_items;
+use middle::reachable;
 use middle::pat_util::simple_name;
 use middle::subst::{self, Substs};
 use middle::traits;
@@ -47,7 +48,7 @@ use middle::ty::{self, Ty, TyCtxt, TypeFoldable};
 use middle::ty::adjustment::CustomCoerceUnsized;
 use rustc::dep_graph::DepNode;
 use rustc::front::map as hir_map;
-use rustc::util::common::time;
+use rustc::util::common::{time, time_item};
 use rustc::mir::mir_map::MirMap;
 use session::config::{self, NoDebugInfo, FullDebugInfo};
 use session::Session;
@@ -904,14 +905,20 @@ pub fn get_extern_fn<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
     let attrs = ccx.sess().cstore.item_attrs(def_id);
     let ty = ccx.tcx().lookup_item_type(def_id).ty;
     match ty.sty {
-        ty::TyFnDef(_, _, fty) => {
+        ty::TyFnDef(_, substs, fty) => {
             let abi = fty.abi;
             let fty = infer::normalize_associated_type(ccx.tcx(), fty);
-            let ty = ccx.tcx().mk_fn_ptr(fty);
+            let ty = ccx.tcx().mk_fn_ptr(fty.clone());
             let llfn = match ccx.sess().target.target.adjust_abi(abi) {
                 Abi::RustIntrinsic | Abi::PlatformIntrinsic => {
                     ccx.sess().bug("unexpected intrinsic in get_extern_fn")
                 }
+                Abi::Rust | Abi::RustCall if ccx.tcx().is_track_caller(def_id) => {
+                    // Declare through the fn item type, so the declaration
+                    // includes the implicit caller location argument.
+                    let fn_def_ty = ccx.tcx().mk_fn_def(def_id, substs, fty);
+                    get_extern_rust_fn(ccx, fn_def_ty, &name, &attrs)
+                }
                 Abi::Rust | Abi::RustCall => {
                     get_extern_rust_fn(ccx, ty, &name, &attrs)
                 }
@@ -1605,6 +1612,7 @@ pub fn new_fn_ctxt<'a, 'tcx>(ccx: &'a CrateContext<'a, 'tcx>,
         mir: mir,
         llfn: llfndecl,
         llenv: None,
+        caller_location: None,
         llretslotptr: Cell::new(None),
         param_env: ccx.tcx().empty_parameter_environment(),
         alloca_insert_pt: Cell::new(None),
@@ -1630,6 +1638,14 @@ pub fn new_fn_ctxt<'a, 'tcx>(ccx: &'a CrateContext<'a, 'tcx>,
         fcx.llenv = Some(get_param(fcx.llfn, fcx.env_arg_pos() as c_uint))
     }
 
+    let track_caller = ccx.tcx().map.opt_local_def_id(id).map_or(false, |def_id| {
+        ccx.tcx().is_track_caller(def_id)
+    });
+    if track_caller {
+        let last_param = unsafe { llvm::LLVMCountParams(fcx.llfn) } - 1;
+        fcx.caller_location = Some(get_param(fcx.llfn, last_param));
+    }
+
     fcx
 }
 
@@ -2139,16 +2155,20 @@ pub fn trans_fn<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
     let sig = infer::normalize_associated_type(ccx.tcx(), &sig);
     let output_type = sig.output;
     let abi = fn_ty.fn_abi();
-    trans_closure(ccx,
-                  decl,
-                  body,
-                  llfndecl,
-                  param_substs,
-                  id,
-                  attrs,
-                  output_type,
-                  abi,
-                  closure::ClosureEnv::NotClosure);
+    // All instantiations of a generic function are attributed to the
+    // function itself.
+    time_item(ccx.sess().time_items(), "translation",
+              || ccx.tcx().def_path_str(ccx.tcx().map.local_def_id(id)),
+              || trans_closure(ccx,
+                               decl,
+                               body,
+                               llfndecl,
+                               param_substs,
+                               id,
+                               attrs,
+                               output_type,
+                               abi,
+                               closure::ClosureEnv::NotClosure));
 }
 
 pub fn trans_enum_variant<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
@@ -3149,6 +3169,14 @@ pub fn trans_crate<'tcx>(tcx: &TyCtxt<'tcx>,
 
     let ty::CrateAnalysis { export_map, reachable, name, .. } = analysis;
 
+    // Typeck only hands out error types after reporting an error, and an
+    // error stops compilation before translation, so any error type left in
+    // the node type tables here is a compiler bug. Say where it came from
+    // before translation trips over it.
+    if tcx.tracks_err_types() {
+        tcx.report_err_types("an error type reached translation");
+    }
+
     let check_overflow = if let Some(v) = tcx.sess.opts.debugging_opts.force_overflow_checks {
         v
     } else {
@@ -3289,7 +3317,15 @@ pub fn trans_crate<'tcx>(tcx: &TyCtxt<'tcx>,
         }
     }
 
-    if codegen_units > 1 {
+    // The symbols come from a hash set; they end up in the linker's export
+    // list and in LTO's list of symbols to preserve.
+    if sess.deterministic_output() {
+        reachable_symbols.sort();
+    }
+
+    // With an explicit export list everything else must become internal,
+    // even when there is only one codegen unit.
+    if codegen_units > 1 || reachable::has_explicit_exports(sess) {
         internalize_symbols(&shared_ccx,
                             &reachable_symbols.iter().map(|x| &x[..]).collect());
     }
@@ -3317,6 +3353,30 @@ pub fn trans_crate<'tcx>(tcx: &TyCtxt<'tcx>,
     }
 }
 
+/// Encodes the metadata of the crate without translating it, for
+/// `--emit=metadata`. No items are translated, so no symbol names are
+/// recorded: the result is enough to type-check dependent crates
+/// against, but not to link them.
+pub fn encode_crate_metadata<'tcx>(tcx: &TyCtxt<'tcx>,
+                                   mir_map: &MirMap<'tcx>,
+                                   analysis: ty::CrateAnalysis)
+                                   -> Vec<u8> {
+    let _task = tcx.dep_graph.in_task(DepNode::TransCrate);
+    let krate = tcx.map.krate();
+
+    let ty::CrateAnalysis { export_map, reachable, name, .. } = analysis;
+    let link_meta = link::build_link_meta(&tcx.sess, krate, name);
+    let item_symbols = RefCell::new(NodeMap());
+
+    tcx.sess.cstore.encode_metadata(tcx,
+                                    &export_map,
+                                    &item_symbols,
+                                    &link_meta,
+                                    &reachable,
+                                    mir_map,
+                                    krate)
+}
+
 /// We visit all the items in the krate and translate them.  We do
 /// this in two walks. The first walk just finds module items. It then
 /// walks the full contents of those module items and translates all
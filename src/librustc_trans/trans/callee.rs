@@ -43,6 +43,7 @@ use trans::glue;
 use trans::inline;
 use trans::foreign;
 use trans::intrinsic;
+use trans::machine;
 use trans::meth;
 use trans::monomorphize;
 use trans::type_::Type;
@@ -53,8 +54,9 @@ use rustc_front::hir;
 
 use syntax::abi::Abi;
 use syntax::ast;
-use syntax::codemap::DUMMY_SP;
+use syntax::codemap::{DUMMY_SP, Span};
 use syntax::errors;
+use syntax::parse::token::{self, InternedString};
 use syntax::ptr::P;
 
 pub enum CalleeData<'tcx> {
@@ -150,7 +152,16 @@ impl<'tcx> Callee<'tcx> {
             }
             _ => {}
         }
-        Callee::ptr(trans_fn_ref_with_substs(ccx, def_id, Some(ty), substs))
+        let datum = trans_fn_ref_with_substs(ccx, def_id, Some(ty), substs);
+        if tcx.is_track_caller(def_id) {
+            // Keep the fn item type, so that calls know to pass the
+            // caller's location.
+            return Callee {
+                data: Fn(datum.val),
+                ty: ty
+            };
+        }
+        Callee::ptr(datum)
     }
 
     /// This behemoth of a function translates function calls. Unfortunately, in
@@ -405,7 +416,8 @@ pub fn trans_fn_ref_with_substs<'a, 'tcx>(
         }
     }
     let must_monomorphise =
-        !substs.types.is_empty() || is_named_tuple_constructor(tcx, def_id);
+        !substs.types.is_empty() || !substs.consts.is_empty() ||
+        is_named_tuple_constructor(tcx, def_id);
 
     debug!("trans_fn_ref_with_substs({:?}) must_monomorphise: {}",
            def_id, must_monomorphise);
@@ -426,7 +438,7 @@ pub fn trans_fn_ref_with_substs<'a, 'tcx>(
             }
             _ => unreachable!("expected fn item type, found {}", fn_ty)
         };
-        if must_cast && ref_ty.is_some() {
+        if must_cast && ref_ty.is_some() && !tcx.is_track_caller(def_id) {
             let llptrty = type_of::type_of(ccx, fn_ptr_ty);
             if llptrty != common::val_ty(val) {
                 val = consts::ptrcast(val, llptrty);
@@ -479,8 +491,10 @@ pub fn trans_fn_ref_with_substs<'a, 'tcx>(
     // This can occur on either a crate-local or crate-external
     // reference. It also occurs when testing libcore and in some
     // other weird situations. Annoying.
+    // `#[track_caller]` functions take an extra argument that their fn
+    // pointer type does not know about, so keep them as declared.
     let llptrty = type_of::type_of(ccx, datum.ty);
-    if common::val_ty(datum.val) != llptrty {
+    if common::val_ty(datum.val) != llptrty && !tcx.is_track_caller(def_id) {
         debug!("trans_fn_ref_with_substs(): casting pointer!");
         datum.val = consts::ptrcast(datum.val, llptrty);
     } else {
@@ -493,6 +507,30 @@ pub fn trans_fn_ref_with_substs<'a, 'tcx>(
 // ______________________________________________________________________
 // Translating calls
 
+/// The location to pass to a `#[track_caller]` function called at `span`:
+/// the location the enclosing function was called from, if it is itself
+/// `#[track_caller]`, or else a constant `(file, line, column)` for `span`.
+pub fn caller_location(fcx: &FunctionContext, span: Option<Span>) -> ValueRef {
+    if let Some(location) = fcx.caller_location {
+        return location;
+    }
+
+    let ccx = fcx.ccx;
+    let (file, line, col) = match span {
+        Some(span) if span != DUMMY_SP => {
+            let loc = ccx.sess().codemap().lookup_char_pos(span.lo);
+            (token::intern_and_get_ident(&loc.file.name), loc.line as u32, loc.col.0 as u32 + 1)
+        }
+        _ => (InternedString::new("<unknown>"), 0, 0)
+    };
+    let location = common::C_struct(ccx, &[common::C_str_slice(ccx, file),
+                                           common::C_u32(ccx, line),
+                                           common::C_u32(ccx, col)], false);
+    let align = machine::llalign_of_min(ccx, common::val_ty(location));
+    let llptr = consts::addr_of(ccx, location, align, "caller_location");
+    consts::ptrcast(llptr, type_of::type_of(ccx, ccx.tcx().caller_location_ty()))
+}
+
 pub fn trans_lang_call<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
                                    did: DefId,
                                    args: &[ValueRef],
@@ -635,6 +673,18 @@ fn trans_call_inner<'a, 'blk, 'tcx>(mut bcx: Block<'blk, 'tcx>,
 
         fcx.scopes.borrow_mut().last_mut().unwrap().drop_non_lifetime_clean();
 
+        // `#[track_caller]` functions take the location of the call as an
+        // implicit trailing argument.
+        if let ty::TyFnDef(def_id, _, _) = callee.ty.sty {
+            if bcx.tcx().is_track_caller(def_id) {
+                let span = match debug_loc {
+                    DebugLoc::At(_, span) => Some(span),
+                    DebugLoc::None => None
+                };
+                llargs.push(caller_location(fcx, span));
+            }
+        }
+
         let datum = match callee.data {
             Fn(f) => immediate_rvalue(f, callee.ty),
             Virtual(idx) => {
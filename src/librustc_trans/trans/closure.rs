@@ -140,7 +140,8 @@ pub fn get_or_create_closure_declaration<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
     let substs = ccx.tcx().erase_regions(substs);
     let mono_id = MonoId {
         def: closure_id,
-        params: &substs.func_substs.types
+        params: &substs.func_substs.types,
+        consts: &substs.func_substs.consts
     };
 
     if let Some(&llfn) = ccx.closure_vals().borrow().get(&mono_id) {
@@ -435,11 +435,12 @@ fn check_recursion_limit<'a, 'tcx: 'a>(ccx: &CrateContext<'a, 'tcx>,
     if recursion_depth > ccx.sess().recursion_limit.get() {
         if let Some(node_id) = ccx.tcx().map.as_local_node_id(def_id) {
             ccx.sess().span_fatal(ccx.tcx().map.span(node_id),
-                "reached the recursion limit during monomorphization");
+                &format!("reached the recursion limit during monomorphization of `{}`",
+                         ccx.tcx().def_path_str(def_id)));
         } else {
             let error = format!("reached the recursion limit during \
-                                monomorphization of '{}'",
-                                ccx.tcx().item_path_str(def_id));
+                                monomorphization of `{}`",
+                                ccx.tcx().def_path_str(def_id));
             ccx.sess().fatal(&error[..]);
         }
     }
@@ -736,7 +737,9 @@ fn find_drop_glue_neighbors<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
             }
         }
         ty::TyProjection(_) |
+        ty::TyAnon(..)      |
         ty::TyParam(_)      |
+        ty::TyParamArray(..) |
         ty::TyInfer(_)      |
         ty::TyError         => {
             ccx.sess().bug("encountered unexpected type");
@@ -1022,6 +1025,7 @@ impl<'b, 'a, 'v> hir_visit::Visitor<'v> for RootCollector<'b, 'a, 'v> {
             hir::ItemTy(..)          |
             hir::ItemDefaultImpl(..) |
             hir::ItemTrait(..)       |
+            hir::ItemTraitAlias(..)  |
             hir::ItemConst(..)       |
             hir::ItemMod(..)         => {
                 // Nothing to do, just keep recursing...
@@ -1037,7 +1041,8 @@ impl<'b, 'a, 'v> hir_visit::Visitor<'v> for RootCollector<'b, 'a, 'v> {
             }
 
             hir::ItemEnum(_, ref generics)        |
-            hir::ItemStruct(_, ref generics)      => {
+            hir::ItemStruct(_, ref generics)      |
+            hir::ItemUnion(_, ref generics)       => {
                 if !generics.is_parameterized() {
                     let ty = {
                         let tables = self.ccx.tcx().tables.borrow();
@@ -1164,7 +1169,11 @@ fn create_trans_items_for_default_impls<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
                                                   callee_substs,
                                                   default_impl.name);
 
-                    assert!(mth.is_provided);
+                    // The method may instead come from an impl that this
+                    // one specializes, which isn't a default method at all.
+                    if !mth.is_provided {
+                        continue;
+                    }
 
                     let predicates = mth.method.predicates.predicates.subst(tcx, mth.substs);
                     if !normalize_and_test_predicates(ccx, predicates.into_vec()) {
@@ -1332,7 +1341,9 @@ pub fn push_unique_type_name<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
         ty::TyError |
         ty::TyInfer(_) |
         ty::TyProjection(..) |
-        ty::TyParam(_) => {
+        ty::TyAnon(..) |
+        ty::TyParam(_) |
+        ty::TyParamArray(..) => {
             cx.sess().bug(&format!("debuginfo: Trying to create type name for \
                 unexpected type: {:?}", t));
         }
@@ -1372,7 +1383,7 @@ fn push_type_params<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
                               substs: &Substs<'tcx>,
                               projections: &[ty::PolyProjectionPredicate<'tcx>],
                               output: &mut String) {
-    if substs.types.is_empty() && projections.is_empty() {
+    if substs.types.is_empty() && substs.consts.is_empty() && projections.is_empty() {
         return;
     }
 
@@ -1383,6 +1394,11 @@ fn push_type_params<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
         output.push_str(", ");
     }
 
+    for const_arg in &substs.consts {
+        output.push_str(&const_arg.to_string());
+        output.push_str(", ");
+    }
+
     for projection in projections {
         let projection = projection.skip_binder();
         let name = token::get_ident_interner().get(projection.projection_ty.item_name);
@@ -1570,6 +1586,14 @@ pub fn print_collection_results<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>) {
                (100 * predicted_but_not_generated.len()) / generated.len());
     }
 
+    // The item lists come from hash sets.
+    let mut not_predicted_but_generated: Vec<_> = not_predicted_but_generated.into_iter().collect();
+    let mut predicted_but_not_generated: Vec<_> = predicted_but_not_generated.into_iter().collect();
+    if ccx.sess().deterministic_output() {
+        not_predicted_but_generated.sort();
+        predicted_but_not_generated.sort();
+    }
+
     debug!("");
     debug!("Not predicted but generated:");
     debug!("============================");
@@ -309,6 +309,10 @@ pub struct FunctionContext<'a, 'tcx: 'a> {
     // The environment argument in a closure.
     pub llenv: Option<ValueRef>,
 
+    // The implicit trailing argument of a `#[track_caller]` function: the
+    // location it was called from.
+    pub caller_location: Option<ValueRef>,
+
     // A pointer to where to store the return value. If the return type is
     // immediate, this points to an alloca in the function. Otherwise, it's a
     // pointer to the hidden first parameter of the function. After function
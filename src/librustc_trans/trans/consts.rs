@@ -477,7 +477,7 @@ fn check_unary_expr_validity(cx: &CrateContext, e: &hir::Expr, t: Ty,
                     None => return Ok(()),
                 };
                 const_int_checked_neg(
-                    input, e, Some(const_eval::IntTy::from(cx.tcx(), int_type)))
+                    input, e.span, Some(const_eval::IntTy::from(cx.tcx(), int_type)))
             }
             ty::TyUint(uint_type) => {
                 let input = match const_to_opt_uint(te) {
@@ -485,7 +485,7 @@ fn check_unary_expr_validity(cx: &CrateContext, e: &hir::Expr, t: Ty,
                     None => return Ok(()),
                 };
                 const_uint_checked_neg(
-                    input, e, Some(const_eval::UintTy::from(cx.tcx(), uint_type)))
+                    input, e.span, Some(const_eval::UintTy::from(cx.tcx(), uint_type)))
             }
             _ => return Ok(()),
         };
@@ -531,13 +531,13 @@ fn check_binary_expr_validity(cx: &CrateContext, e: &hir::Expr, t: Ty,
 
             let opt_ety = Some(const_eval::IntTy::from(cx.tcx(), int_type));
             match b.node {
-                hir::BiAdd => const_int_checked_add(lhs, rhs, e, opt_ety),
-                hir::BiSub => const_int_checked_sub(lhs, rhs, e, opt_ety),
-                hir::BiMul => const_int_checked_mul(lhs, rhs, e, opt_ety),
-                hir::BiDiv => const_int_checked_div(lhs, rhs, e, opt_ety),
-                hir::BiRem => const_int_checked_rem(lhs, rhs, e, opt_ety),
-                hir::BiShl => const_int_checked_shl(lhs, rhs, e, opt_ety),
-                hir::BiShr => const_int_checked_shr(lhs, rhs, e, opt_ety),
+                hir::BiAdd => const_int_checked_add(lhs, rhs, e.span, opt_ety),
+                hir::BiSub => const_int_checked_sub(lhs, rhs, e.span, opt_ety),
+                hir::BiMul => const_int_checked_mul(lhs, rhs, e.span, opt_ety),
+                hir::BiDiv => const_int_checked_div(lhs, rhs, e.span, opt_ety),
+                hir::BiRem => const_int_checked_rem(lhs, rhs, e.span, opt_ety),
+                hir::BiShl => const_int_checked_shl(lhs, rhs, e.span, opt_ety),
+                hir::BiShr => const_int_checked_shr(lhs, rhs, e.span, opt_ety),
                 _ => return Ok(()),
             }
         }
@@ -550,13 +550,13 @@ fn check_binary_expr_validity(cx: &CrateContext, e: &hir::Expr, t: Ty,
 
             let opt_ety = Some(const_eval::UintTy::from(cx.tcx(), uint_type));
             match b.node {
-                hir::BiAdd => const_uint_checked_add(lhs, rhs, e, opt_ety),
-                hir::BiSub => const_uint_checked_sub(lhs, rhs, e, opt_ety),
-                hir::BiMul => const_uint_checked_mul(lhs, rhs, e, opt_ety),
-                hir::BiDiv => const_uint_checked_div(lhs, rhs, e, opt_ety),
-                hir::BiRem => const_uint_checked_rem(lhs, rhs, e, opt_ety),
-                hir::BiShl => const_uint_checked_shl(lhs, rhs, e, opt_ety),
-                hir::BiShr => const_uint_checked_shr(lhs, rhs, e, opt_ety),
+                hir::BiAdd => const_uint_checked_add(lhs, rhs, e.span, opt_ety),
+                hir::BiSub => const_uint_checked_sub(lhs, rhs, e.span, opt_ety),
+                hir::BiMul => const_uint_checked_mul(lhs, rhs, e.span, opt_ety),
+                hir::BiDiv => const_uint_checked_div(lhs, rhs, e.span, opt_ety),
+                hir::BiRem => const_uint_checked_rem(lhs, rhs, e.span, opt_ety),
+                hir::BiShl => const_uint_checked_shl(lhs, rhs, e.span, opt_ety),
+                hir::BiShr => const_uint_checked_shr(lhs, rhs, e.span, opt_ety),
                 _ => return Ok(()),
             }
         }
@@ -839,6 +839,13 @@ fn const_expr_unadjusted<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
             };
 
             let VariantInfo { discr, fields } = VariantInfo::of_node(cx.tcx(), ety, e.id);
+            if let adt::UntaggedUnion(..) = *repr {
+                // Only the one initialized field goes into the constant.
+                assert_eq!(fs.len(), 1);
+                let (val, _) = try!(const_expr(cx, &fs[0].expr, param_substs, fn_args,
+                                               trueconst));
+                return Ok(adt::trans_const(cx, &repr, discr, &[val]));
+            }
             let cs = fields.iter().enumerate().map(|(ix, &Field(f_name, _))| {
                 match (fs.iter().find(|f| f_name == f.name.node), base_val) {
                     (Some(ref f), _) => {
@@ -213,7 +213,7 @@ pub enum HintKind {
 }
 
 impl Lvalue { // Constructors for various Lvalues.
-    pub fn new<'blk, 'tcx>(source: &'static str) -> Lvalue {
+    pub fn new(source: &'static str) -> Lvalue {
         debug!("Lvalue at {} no drop flag info", source);
         Lvalue { source: source, drop_flag_info: DropFlagInfo::None }
     }
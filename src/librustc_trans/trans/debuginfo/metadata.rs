@@ -368,6 +368,18 @@ impl<'tcx> TypeMap<'tcx> {
 
                 output.push('>');
             }
+
+            let consts = substs.consts.get_slice(subst::TypeSpace);
+            if !consts.is_empty() {
+                output.push('[');
+
+                for c in consts {
+                    output.push_str(&c.to_string());
+                    output.push(',');
+                }
+
+                output.push(']');
+            }
         }
     }
 
@@ -1104,6 +1116,7 @@ struct StructMemberDescriptionFactory<'tcx> {
     variant: ty::VariantDef<'tcx>,
     substs: &'tcx subst::Substs<'tcx>,
     is_simd: bool,
+    is_union: bool,
     span: Span,
 }
 
@@ -1136,6 +1149,9 @@ impl<'tcx> StructMemberDescriptionFactory<'tcx> {
 
             let offset = if self.is_simd {
                 FixedMemberOffset { bytes: i * field_size.unwrap() }
+            } else if self.is_union {
+                // All fields of a union share the same storage.
+                FixedMemberOffset { bytes: 0 }
             } else {
                 ComputedMemberOffset
             };
@@ -1160,8 +1176,8 @@ fn prepare_struct_metadata<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
     let struct_name = compute_debuginfo_type_name(cx, struct_type, false);
     let struct_llvm_type = type_of::in_memory_type_of(cx, struct_type);
 
-    let (variant, substs) = match struct_type.sty {
-        ty::TyStruct(def, substs) => (def.struct_variant(), substs),
+    let (variant, substs, is_union) = match struct_type.sty {
+        ty::TyStruct(def, substs) => (def.struct_variant(), substs, def.is_union()),
         _ => cx.tcx().sess.bug("prepare_struct_metadata on a non-struct")
     };
 
@@ -1183,6 +1199,7 @@ fn prepare_struct_metadata<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
             variant: variant,
             substs: substs,
             is_simd: struct_type.is_simd(),
+            is_union: is_union,
             span: span,
         })
     )
@@ -1452,7 +1469,8 @@ impl<'tcx> EnumMemberDescriptionFactory<'tcx> {
                     }
                 ]
             },
-            adt::CEnum(..) => cx.sess().span_bug(self.span, "This should be unreachable.")
+            adt::CEnum(..) | adt::UntaggedUnion(..) =>
+                cx.sess().span_bug(self.span, "This should be unreachable.")
         }
     }
 }
@@ -1652,6 +1670,7 @@ fn prepare_enum_metadata<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
         adt::StructWrappedNullablePointer { .. } |
         adt::Univariant(..)                      => None,
         adt::General(inttype, _, _) => Some(discriminant_type_metadata(inttype)),
+        adt::UntaggedUnion(..) => cx.sess().bug("enum metadata requested for a union"),
     };
 
     let enum_llvm_type = type_of::type_of(cx, enum_type);
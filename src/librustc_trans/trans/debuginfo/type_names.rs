@@ -153,7 +153,9 @@ pub fn push_debuginfo_type_name<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
         ty::TyError |
         ty::TyInfer(_) |
         ty::TyProjection(..) |
-        ty::TyParam(_) => {
+        ty::TyAnon(..) |
+        ty::TyParam(_) |
+        ty::TyParamArray(..) => {
             cx.sess().bug(&format!("debuginfo: Trying to create type name for \
                 unexpected type: {:?}", t));
         }
@@ -198,7 +200,7 @@ pub fn push_debuginfo_type_name<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
     fn push_type_params<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
                                   substs: &subst::Substs<'tcx>,
                                   output: &mut String) {
-        if substs.types.is_empty() {
+        if substs.types.is_empty() && substs.consts.is_empty() {
             return;
         }
 
@@ -209,6 +211,11 @@ pub fn push_debuginfo_type_name<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
             output.push_str(", ");
         }
 
+        for const_arg in &substs.consts {
+            output.push_str(&const_arg.to_string());
+            output.push_str(", ");
+        }
+
         output.pop();
         output.pop();
 
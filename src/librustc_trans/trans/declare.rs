@@ -105,10 +105,12 @@ pub fn declare_rust_fn<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>, name: &str,
            fn_type);
 
     let function_type; // placeholder so that the memory ownership works out ok
-    let (sig, abi, env) = match fn_type.sty {
-        ty::TyFnDef(_, _, f) |
+    let (sig, abi, env, caller_location) = match fn_type.sty {
+        ty::TyFnDef(def_id, _, f) => {
+            (&f.sig, f.abi, None, ccx.tcx().is_track_caller(def_id))
+        }
         ty::TyFnPtr(f) => {
-            (&f.sig, f.abi, None)
+            (&f.sig, f.abi, None, false)
         }
         ty::TyClosure(closure_did, ref substs) => {
             let infcx = infer::normalizing_infer_ctxt(ccx.tcx(), &ccx.tcx().tables);
@@ -117,7 +119,7 @@ pub fn declare_rust_fn<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>, name: &str,
             let llenvironment_type = type_of::type_of_explicit_arg(ccx, self_type);
             debug!("declare_rust_fn function_type={:?} self_type={:?}",
                    function_type, self_type);
-            (&function_type.sig, Abi::RustCall, Some(llenvironment_type))
+            (&function_type.sig, Abi::RustCall, Some(llenvironment_type), false)
         }
         _ => ccx.sess().bug("expected closure or fn")
     };
@@ -125,7 +127,7 @@ pub fn declare_rust_fn<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>, name: &str,
     let sig = ccx.tcx().erase_late_bound_regions(sig);
     let sig = infer::normalize_associated_type(ccx.tcx(), &sig);
     debug!("declare_rust_fn (after region erasure) sig={:?}", sig);
-    let llfty = type_of::type_of_rust_fn(ccx, env, &sig, abi);
+    let llfty = type_of::type_of_rust_fn(ccx, env, &sig, abi, caller_location);
     debug!("declare_rust_fn llfty={}", ccx.tn().type_to_string(llfty));
 
     // it is ok to directly access sig.0.output because we erased all
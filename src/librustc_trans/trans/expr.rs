@@ -1323,7 +1323,9 @@ fn trans_struct<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
                                  fields: leftovers })
         }
         None => {
-            if need_base.iter().any(|b| *b) {
+            // A union literal initializes only one of its fields.
+            let is_union = ty.ty_adt_def().map_or(false, |def| def.is_union());
+            if !is_union && need_base.iter().any(|b| *b) {
                 tcx.sess.span_bug(expr_span, "missing fields and no base expr")
             }
             None
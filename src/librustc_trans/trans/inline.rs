@@ -17,6 +17,17 @@ use trans::common::*;
 
 use rustc::dep_graph::DepNode;
 use rustc_front::hir;
+use syntax::ast;
+
+/// The inlined copy of a `#[track_caller]` function takes the caller's
+/// location just like the original, so it has to be known as such before
+/// it is declared.
+fn inherit_track_caller(ccx: &CrateContext, fn_id: DefId, inline_id: ast::NodeId) {
+    if ccx.tcx().is_track_caller(fn_id) {
+        let inline_def_id = ccx.tcx().map.local_def_id(inline_id);
+        ccx.tcx().track_caller_fns.borrow_mut().insert(inline_def_id);
+    }
+}
 
 fn instantiate_inline(ccx: &CrateContext, fn_id: DefId) -> Option<DefId> {
     debug!("instantiate_inline({:?})", fn_id);
@@ -50,6 +61,7 @@ fn instantiate_inline(ccx: &CrateContext, fn_id: DefId) -> Option<DefId> {
             ccx.external_srcs().borrow_mut().insert(item.id, fn_id);
 
             ccx.stats().n_inlines.set(ccx.stats().n_inlines.get() + 1);
+            inherit_track_caller(ccx, fn_id, item.id);
             trans_item(ccx, item);
 
             let linkage = match item.node {
@@ -152,6 +164,7 @@ fn instantiate_inline(ccx: &CrateContext, fn_id: DefId) -> Option<DefId> {
             ccx.external_srcs().borrow_mut().insert(impl_item.id, fn_id);
 
             ccx.stats().n_inlines.set(ccx.stats().n_inlines.get() + 1);
+            inherit_track_caller(ccx, fn_id, impl_item.id);
 
             // Translate monomorphic impl methods immediately.
             if let hir::ImplItemKind::Method(ref sig, ref body) = impl_item.node {
@@ -15,6 +15,7 @@ use rustc::middle::const_eval::{self, ConstVal};
 use rustc::mir::repr as mir;
 use trans::common::{self, BlockAndBuilder, C_bool, C_bytes, C_floating_f64, C_integral,
                     C_str_slice, C_nil, C_undef};
+use trans::callee;
 use trans::consts;
 use trans::expr;
 use trans::inline;
@@ -110,6 +111,13 @@ impl<'bcx, 'tcx> MirContext<'bcx, 'tcx> {
             mir::Literal::Value { ref value } => {
                 self.trans_constval(bcx, value, ty)
             }
+            mir::Literal::CallerLocation => {
+                OperandRef {
+                    val: OperandValue::Immediate(callee::caller_location(bcx.fcx(),
+                                                                         Some(constant.span))),
+                    ty: ty
+                }
+            }
         }
     }
 }
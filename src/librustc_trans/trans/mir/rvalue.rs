@@ -109,7 +109,7 @@ impl<'bcx, 'tcx> MirContext<'bcx, 'tcx> {
 
             mir::Rvalue::Aggregate(ref kind, ref operands) => {
                 match *kind {
-                    mir::AggregateKind::Adt(adt_def, index, _) => {
+                    mir::AggregateKind::Adt(adt_def, index, _, active_field) => {
                         let repr = adt::represent_type(bcx.ccx(), dest.ty.to_ty(bcx.tcx()));
                         let disr = Disr::from(adt_def.variants[index].disr_val);
                         bcx.with_block(|bcx| {
@@ -119,6 +119,8 @@ impl<'bcx, 'tcx> MirContext<'bcx, 'tcx> {
                             let op = self.trans_operand(&bcx, operand);
                             // Do not generate stores and GEPis for zero-sized fields.
                             if !common::type_is_zero_size(bcx.ccx(), op.ty) {
+                                // A union's only operand is its active field.
+                                let i = active_field.unwrap_or(i);
                                 let val = adt::MaybeSizedValue::sized(dest.llval);
                                 let lldest_i = bcx.with_block(|bcx| {
                                     adt::trans_field_ptr(bcx, &repr, val, disr, i)
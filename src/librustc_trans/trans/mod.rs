@@ -11,7 +11,7 @@
 use llvm::{ContextRef, ModuleRef};
 use middle::cstore::LinkMeta;
 
-pub use self::base::trans_crate;
+pub use self::base::{encode_crate_metadata, trans_crate};
 pub use self::context::CrateContext;
 pub use self::common::gensym_name;
 pub use self::disr::Disr;
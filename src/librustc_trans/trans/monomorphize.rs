@@ -50,7 +50,8 @@ pub fn monomorphic_fn<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
 
     let hash_id = MonoId {
         def: fn_id,
-        params: &psubsts.types
+        params: &psubsts.types,
+        consts: &psubsts.consts
     };
 
     let item_ty = ccx.tcx().lookup_item_type(fn_id).ty;
@@ -111,7 +112,8 @@ pub fn monomorphic_fn<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
         // to be causing an infinite expansion.
         if depth > ccx.sess().recursion_limit.get() {
             ccx.sess().span_fatal(ccx.tcx().map.span(fn_node_id),
-                "reached the recursion limit during monomorphization");
+                &format!("reached the recursion limit during monomorphization of `{}`",
+                         ccx.tcx().def_path_str(fn_id)));
         }
 
         monomorphizing.insert(fn_id, depth + 1);
@@ -284,7 +286,8 @@ pub fn monomorphic_fn<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
 #[derive(PartialEq, Eq, Hash, Debug)]
 pub struct MonoId<'tcx> {
     pub def: DefId,
-    pub params: &'tcx subst::VecPerParamSpace<Ty<'tcx>>
+    pub params: &'tcx subst::VecPerParamSpace<Ty<'tcx>>,
+    pub consts: &'tcx subst::VecPerParamSpace<subst::ConstArg>
 }
 
 /// Monomorphizes a type from the AST by first applying the in-scope
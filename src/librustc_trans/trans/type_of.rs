@@ -91,7 +91,8 @@ pub fn untuple_arguments<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
 pub fn type_of_rust_fn<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
                                  llenvironment_type: Option<Type>,
                                  sig: &ty::FnSig<'tcx>,
-                                 abi: Abi)
+                                 abi: Abi,
+                                 caller_location: bool)
                                  -> Type
 {
     debug!("type_of_rust_fn(sig={:?},abi={:?})",
@@ -147,6 +148,11 @@ pub fn type_of_rust_fn<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
         }
     }
 
+    // ... and finally the location of the call, for `#[track_caller]` fns.
+    if caller_location {
+        atys.push(type_of(cx, cx.tcx().caller_location_ty()));
+    }
+
     Type::func(&atys[..], &lloutputtype)
 }
 
@@ -227,7 +233,8 @@ pub fn sizing_type_of<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>, t: Ty<'tcx>) -> Typ
             }
         }
 
-        ty::TyProjection(..) | ty::TyInfer(..) | ty::TyParam(..) | ty::TyError => {
+        ty::TyProjection(..) | ty::TyAnon(..) | ty::TyInfer(..) | ty::TyParam(..) |
+        ty::TyParamArray(..) | ty::TyError => {
             cx.sess().bug(&format!("fictitious type {:?} in sizing_type_of()",
                                    t))
         }
@@ -401,7 +408,7 @@ pub fn in_memory_type_of<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>, t: Ty<'tcx>) ->
         if f.abi == Abi::Rust || f.abi == Abi::RustCall {
             let sig = cx.tcx().erase_late_bound_regions(&f.sig);
             let sig = infer::normalize_associated_type(cx.tcx(), &sig);
-            type_of_rust_fn(cx, None, &sig, f.abi).ptr_to()
+            type_of_rust_fn(cx, None, &sig, f.abi, false).ptr_to()
         } else {
             foreign::lltype_for_foreign_fn(cx, t).ptr_to()
         }
@@ -436,7 +443,9 @@ pub fn in_memory_type_of<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>, t: Ty<'tcx>) ->
 
       ty::TyInfer(..) => cx.sess().bug("type_of with TyInfer"),
       ty::TyProjection(..) => cx.sess().bug("type_of with TyProjection"),
+      ty::TyAnon(..) => cx.sess().bug("type_of with TyAnon"),
       ty::TyParam(..) => cx.sess().bug("type_of with ty_param"),
+      ty::TyParamArray(..) => cx.sess().bug("type_of with TyParamArray"),
       ty::TyError => cx.sess().bug("type_of with TyError"),
     };
 
@@ -53,15 +53,17 @@ use middle::const_eval::{self, ConstVal};
 use middle::const_eval::EvalHint::UncheckedExprHint;
 use middle::def::{self, Def};
 use middle::def_id::DefId;
+use middle::lang_items::SizedTraitLangItem;
 use middle::resolve_lifetime as rl;
-use middle::subst::{FnSpace, TypeSpace, SelfSpace, Subst, Substs, ParamSpace};
+use middle::subst::{ConstArg, FnSpace, TypeSpace, SelfSpace, Subst, Substs, ParamSpace};
+use middle::subst::VecPerParamSpace;
 use middle::traits;
 use middle::ty::{self, Ty, TyCtxt, ToPredicate, TypeFoldable};
 use middle::ty::wf::object_region_bounds;
 use require_c_abi_if_variadic;
-use rscope::{self, UnelidableRscope, RegionScope, ElidableRscope,
+use rscope::{self, UnelidableRscope, RegionScope, ElidableRscope, ExplicitRscope,
              ObjectLifetimeDefaultRscope, ShiftedRscope, BindingRscope,
-             ElisionFailureInfo, ElidedLifetime};
+             ElisionFailureInfo, ElidedLifetime, AnonTypeScope, MaybeWithAnonTypes};
 use util::common::{ErrorReported, FN_OUTPUT_NAME};
 use util::nodemap::FnvHashSet;
 
@@ -70,11 +72,14 @@ use syntax::codemap::{Span, Pos};
 use syntax::errors::DiagnosticBuilder;
 use syntax::feature_gate::{GateIssue, emit_feature_err};
 use syntax::parse::token;
+use syntax::ptr::P;
 
 use rustc_front::print::pprust;
 use rustc_front::hir;
 use rustc_back::slice;
 
+use std::iter::repeat;
+
 pub trait AstConv<'tcx> {
     fn tcx<'a>(&'a self) -> &'a TyCtxt<'tcx>;
 
@@ -113,6 +118,21 @@ pub trait AstConv<'tcx> {
         None
     }
 
+    /// Converts `ast_bounds`, the bounds written on `param_ty` (e.g. the
+    /// `Clone + 'a` in `T: Clone + 'a`), into the predicates they impose, in
+    /// the same way collect does for the bounds on an item's generics.
+    fn bound_predicates(&self,
+                        param_ty: Ty<'tcx>,
+                        ast_bounds: &[hir::TyParamBound],
+                        sized_by_default: SizedByDefault,
+                        span: Span)
+                        -> Vec<ty::Predicate<'tcx>>
+        where Self: Sized
+    {
+        compute_bounds(self, param_ty, ast_bounds, sized_by_default, span)
+            .predicates(self.tcx(), param_ty)
+    }
+
     /// What type should we use when a type is omitted?
         fn ty_infer(&self,
                     param_and_substs: Option<ty::TypeParameterDef<'tcx>>,
@@ -202,6 +222,7 @@ fn report_elision_failure(
     let mut m = String::new();
     let len = params.len();
     let mut any_lifetimes = false;
+    let mut total_lifetimes = 0;
 
     for (i, info) in params.into_iter().enumerate() {
         let ElisionFailureInfo {
@@ -209,6 +230,7 @@ fn report_elision_failure(
         } = info;
 
         any_lifetimes = any_lifetimes || (n > 0);
+        total_lifetimes += n;
 
         let help_name = if name.is_empty() {
             format!("argument {}", i + 1)
@@ -246,16 +268,27 @@ fn report_elision_failure(
         fileline_help!(db, default_span,
                        "consider giving it an explicit bounded or 'static \
                         lifetime");
-    } else if len == 1 {
-        fileline_help!(db, default_span,
-                       "this function's return type contains a borrowed value, but \
-                        the signature does not say which {} it is borrowed from",
-                       m);
     } else {
-        fileline_help!(db, default_span,
-                       "this function's return type contains a borrowed value, but \
-                        the signature does not say whether it is borrowed from {}",
-                       m);
+        if len == 1 {
+            fileline_help!(db, default_span,
+                           "this function's return type contains a borrowed value, but \
+                            the signature does not say which {} it is borrowed from",
+                           m);
+        } else {
+            fileline_help!(db, default_span,
+                           "this function's return type contains a borrowed value, but \
+                            the signature does not say whether it is borrowed from {}",
+                           m);
+        }
+        // Both elision rules that could have supplied the lifetime failed;
+        // spell them out so it is clear why the signature is ambiguous.
+        db.fileline_note(default_span,
+                         &format!("an elided return lifetime is taken from the only \
+                                   input lifetime, but there are {} input lifetimes",
+                                  total_lifetimes));
+        db.fileline_note(default_span,
+                         "an elided return lifetime is taken from `&self` or \
+                          `&mut self`, but this function has no such receiver");
     }
 }
 
@@ -331,13 +364,145 @@ pub fn ast_path_substs_for_ty<'tcx>(
 
     prohibit_projections(this.tcx(), &assoc_bindings);
 
-    create_substs_for_ast_path(this,
-                               span,
-                               param_mode,
-                               decl_generics,
-                               None,
-                               types,
-                               regions)
+    let substs = create_substs_for_ast_path(this,
+                                            span,
+                                            param_mode,
+                                            decl_generics,
+                                            None,
+                                            types,
+                                            regions);
+
+    let consts = match item_segment.parameters {
+        hir::AngleBracketedParameters(ref data) => &data.consts[..],
+        hir::ParenthesizedParameters(..) => &[],
+    };
+    let consts = const_args_for_path(tcx, span, decl_generics.consts.get_slice(TypeSpace), consts);
+    substs.with_consts(VecPerParamSpace::new(consts, vec![], vec![]))
+}
+
+/// Converts the const arguments written on a path into the arguments for
+/// the const parameters `defs`. Const arguments are never inferred, so all
+/// of them must be given; if any are wrong, none are returned and the
+/// parameters are left unsubstituted.
+pub fn const_args_for_path<'tcx>(tcx: &TyCtxt<'tcx>,
+                                 span: Span,
+                                 defs: &[ty::ConstParameterDef<'tcx>],
+                                 provided: &[P<hir::Expr>])
+                                 -> Vec<ConstArg>
+{
+    if provided.len() != defs.len() {
+        span_err!(tcx.sess, span, E0541,
+                  "wrong number of const arguments: expected {}, found {}",
+                  defs.len(), provided.len());
+        return vec![];
+    }
+
+    let consts: Option<Vec<_>> =
+        defs.iter()
+            .zip(provided)
+            .map(|(def, e)| const_arg_for_expr(tcx, def, e))
+            .collect();
+    consts.unwrap_or(vec![])
+}
+
+fn const_arg_for_expr<'tcx>(tcx: &TyCtxt<'tcx>,
+                            def: &ty::ConstParameterDef<'tcx>,
+                            e: &hir::Expr)
+                            -> Option<ConstArg>
+{
+    if let Some((param, ty)) = const_param_of_expr(tcx, e) {
+        if ty != def.ty {
+            span_err!(tcx.sess, e.span, E0308,
+                      "mismatched types: expected `{}`, found `{}`", def.ty, ty);
+            return None;
+        }
+        return Some(ConstArg::Param(param));
+    }
+
+    match const_eval::eval_const_expr_partial(tcx, e, UncheckedExprHint(def.ty), None) {
+        Ok(val) => {
+            match (&def.ty.sty, &val) {
+                (&ty::TyInt(_), &ConstVal::Int(_)) |
+                (&ty::TyUint(_), &ConstVal::Int(_)) |
+                (&ty::TyInt(_), &ConstVal::Uint(_)) |
+                (&ty::TyUint(_), &ConstVal::Uint(_)) => {
+                    let value = int_const_arg(tcx, def.ty, &val);
+                    if value.is_none() {
+                        span_err!(tcx.sess, e.span, E0545,
+                                  "const argument out of range for `{}`", def.ty);
+                    }
+                    value.map(ConstArg::Value)
+                }
+                (&ty::TyBool, &ConstVal::Bool(b)) => Some(ConstArg::Value(b as u64)),
+                _ => {
+                    span_err!(tcx.sess, e.span, E0308,
+                              "mismatched types: expected `{}`, found {}",
+                              def.ty, val.description());
+                    None
+                }
+            }
+        }
+        Err(err) => {
+            let mut diag = struct_span_err!(tcx.sess, err.span, E0080,
+                                            "constant evaluation error: {}",
+                                            err.description());
+            if !e.span.contains(err.span) {
+                diag.span_note(e.span, "for const argument here");
+            }
+            diag.emit();
+            None
+        }
+    }
+}
+
+/// The bits of the integer `val` as a const argument of the integer type
+/// `ty`, or `None` if `ty` can't represent it. Signed values are stored
+/// sign-extended.
+fn int_const_arg<'tcx>(tcx: &TyCtxt<'tcx>, ty: Ty<'tcx>, val: &ConstVal) -> Option<u64> {
+    let target = &tcx.sess.target;
+    match (&ty.sty, val) {
+        (&ty::TyInt(ity), &ConstVal::Int(i)) => {
+            let bits = ity.bit_width().or(target.int_type.bit_width()).unwrap();
+            // Sign-extending the low `bits` bits has to give back `i`.
+            let shift = 64 - bits;
+            if (i << shift) >> shift == i { Some(i as u64) } else { None }
+        }
+        (&ty::TyInt(ity), &ConstVal::Uint(u)) => {
+            let bits = ity.bit_width().or(target.int_type.bit_width()).unwrap();
+            if u >> (bits - 1) == 0 { Some(u) } else { None }
+        }
+        (&ty::TyUint(_), &ConstVal::Int(i)) if i >= 0 => {
+            int_const_arg(tcx, ty, &ConstVal::Uint(i as u64))
+        }
+        (&ty::TyUint(uty), &ConstVal::Uint(u)) => {
+            let bits = uty.bit_width().or(target.uint_type.bit_width()).unwrap();
+            if bits == 64 || u >> bits == 0 { Some(u) } else { None }
+        }
+        _ => None,
+    }
+}
+
+/// If `e` names a const parameter, possibly in braces as `{N}`, returns
+/// the parameter and its type.
+pub fn const_param_of_expr<'tcx>(tcx: &TyCtxt<'tcx>, e: &hir::Expr)
+                             -> Option<(ty::ParamConst, Ty<'tcx>)> {
+    match e.node {
+        hir::ExprBlock(ref block) if block.stmts.is_empty() &&
+                                     block.rules == hir::DefaultBlock => {
+            block.expr.as_ref().and_then(|e| const_param_of_expr(tcx, e))
+        }
+        hir::ExprPath(None, _) => {
+            match tcx.def_map.borrow().get(&e.id).map(|d| d.full_def()) {
+                Some(Def::ConstParam(space, index, def_id, name)) => {
+                    let node_id = tcx.map.as_local_node_id(def_id).unwrap();
+                    let ty = tcx.const_parameter_def(node_id).ty;
+                    Some((ty::ParamConst::new(space, index, name), ty))
+                }
+                _ => None
+            }
+        }
+        _ => None
+    }
 }
 
 #[derive(PartialEq, Eq)]
@@ -608,20 +773,21 @@ fn find_implied_output_region<'tcx>(tcx: &TyCtxt<'tcx>,
 
 fn convert_ty_with_lifetime_elision<'tcx>(this: &AstConv<'tcx>,
                                           elided_lifetime: ElidedLifetime,
-                                          ty: &hir::Ty)
+                                          ty: &hir::Ty,
+                                          anon_scope: Option<AnonTypeScope>)
                                           -> Ty<'tcx>
 {
     match elided_lifetime {
         Ok(implied_output_region) => {
             let rb = ElidableRscope::new(implied_output_region);
-            ast_ty_to_ty(this, &rb, ty)
+            ast_ty_to_ty(this, &MaybeWithAnonTypes::new(rb, anon_scope), ty)
         }
         Err(param_lifetimes) => {
             // All regions must be explicitly specified in the output
             // if the lifetime elision rules do not apply. This saves
             // the user from potentially-confusing errors.
             let rb = UnelidableRscope::new(param_lifetimes);
-            ast_ty_to_ty(this, &rb, ty)
+            ast_ty_to_ty(this, &MaybeWithAnonTypes::new(rb, anon_scope), ty)
         }
     }
 }
@@ -654,7 +820,8 @@ fn convert_parenthesized_parameters<'tcx>(this: &AstConv<'tcx>,
         Some(ref output_ty) => {
             (convert_ty_with_lifetime_elision(this,
                                               implied_output_region,
-                                              &output_ty),
+                                              &output_ty,
+                                              None),
              output_ty.span)
         }
         None => {
@@ -728,7 +895,7 @@ fn trait_def_id<'tcx>(this: &AstConv<'tcx>, trait_ref: &hir::TraitRef) -> DefId
     }
 }
 
-fn object_path_to_poly_trait_ref<'a,'tcx>(
+fn object_path_to_poly_trait_ref<'tcx>(
     this: &AstConv<'tcx>,
     rscope: &RegionScope,
     span: Span,
@@ -748,7 +915,7 @@ fn object_path_to_poly_trait_ref<'a,'tcx>(
                                projections)
 }
 
-fn ast_path_to_poly_trait_ref<'a,'tcx>(
+fn ast_path_to_poly_trait_ref<'tcx>(
     this: &AstConv<'tcx>,
     rscope: &RegionScope,
     span: Span,
@@ -798,14 +965,14 @@ fn ast_path_to_poly_trait_ref<'a,'tcx>(
     poly_trait_ref
 }
 
-fn ast_path_to_mono_trait_ref<'a,'tcx>(this: &AstConv<'tcx>,
-                                       rscope: &RegionScope,
-                                       span: Span,
-                                       param_mode: PathParamMode,
-                                       trait_def_id: DefId,
-                                       self_ty: Option<Ty<'tcx>>,
-                                       trait_segment: &hir::PathSegment)
-                                       -> ty::TraitRef<'tcx>
+fn ast_path_to_mono_trait_ref<'tcx>(this: &AstConv<'tcx>,
+                                    rscope: &RegionScope,
+                                    span: Span,
+                                    param_mode: PathParamMode,
+                                    trait_def_id: DefId,
+                                    self_ty: Option<Ty<'tcx>>,
+                                    trait_segment: &hir::PathSegment)
+                                    -> ty::TraitRef<'tcx>
 {
     let (substs, assoc_bindings) =
         create_substs_for_ast_trait_ref(this,
@@ -819,14 +986,14 @@ fn ast_path_to_mono_trait_ref<'a,'tcx>(this: &AstConv<'tcx>,
     ty::TraitRef::new(trait_def_id, substs)
 }
 
-fn create_substs_for_ast_trait_ref<'a,'tcx>(this: &AstConv<'tcx>,
-                                            rscope: &RegionScope,
-                                            span: Span,
-                                            param_mode: PathParamMode,
-                                            trait_def_id: DefId,
-                                            self_ty: Option<Ty<'tcx>>,
-                                            trait_segment: &hir::PathSegment)
-                                            -> (&'tcx Substs<'tcx>, Vec<ConvertedBinding<'tcx>>)
+fn create_substs_for_ast_trait_ref<'tcx>(this: &AstConv<'tcx>,
+                                         rscope: &RegionScope,
+                                         span: Span,
+                                         param_mode: PathParamMode,
+                                         trait_def_id: DefId,
+                                         self_ty: Option<Ty<'tcx>>,
+                                         trait_segment: &hir::PathSegment)
+                                         -> (&'tcx Substs<'tcx>, Vec<ConvertedBinding<'tcx>>)
 {
     debug!("create_substs_for_ast_trait_ref(trait_segment={:?})",
            trait_segment);
@@ -906,6 +1073,8 @@ fn ast_type_binding_to_poly_projection_predicate<'tcx>(
 
     // Simple case: X is defined in the current trait.
     if this.trait_defines_associated_type_named(trait_ref.def_id(), binding.item_name) {
+        try!(check_not_generic_associated_type(tcx, binding.span, trait_ref.def_id(),
+                                               binding.item_name));
         return Ok(ty::Binder(ty::ProjectionPredicate {      // <-------------------+
             projection_ty: ty::ProjectionTy {               //                     |
                 trait_ref: trait_ref.skip_binder().clone(), // Binder moved here --+
@@ -956,6 +1125,8 @@ fn ast_type_binding_to_poly_projection_predicate<'tcx>(
                                                   &trait_ref.to_string(),
                                                   &binding.item_name.as_str(),
                                                   binding.span));
+    try!(check_not_generic_associated_type(tcx, binding.span, candidate.def_id(),
+                                           binding.item_name));
 
     Ok(ty::Binder(ty::ProjectionPredicate {             // <-------------------------+
         projection_ty: ty::ProjectionTy {               //                           |
@@ -1087,6 +1258,12 @@ fn ast_ty_to_trait_ref<'tcx>(this: &AstConv<'tcx>,
     }
 }
 
+/// Converts `trait_ref` and `bounds` into an object type. The trait
+/// references of an object type have no `Self` type in their substs, since
+/// the type behind the object is unknown. Code that needs one, e.g. to walk
+/// the supertraits of the principal, temporarily gives them the fresh type
+/// `FreshTy(0)` as `Self` and removes it again from the results, checking
+/// that it did not leak into any other position.
 fn trait_ref_to_object_type<'tcx>(this: &AstConv<'tcx>,
                                   rscope: &RegionScope,
                                   span: Span,
@@ -1109,12 +1286,82 @@ fn trait_ref_to_object_type<'tcx>(this: &AstConv<'tcx>,
     result
 }
 
+/// Replaces a trait alias used as the principal of an object type by the
+/// single non-builtin trait it expands to, moving any builtin traits (like
+/// `Send`) among its bounds into `builtin_bounds`.
+fn expand_trait_alias_principal<'tcx>(this: &AstConv<'tcx>,
+                                      span: Span,
+                                      principal: ty::PolyTraitRef<'tcx>,
+                                      builtin_bounds: &mut ty::BuiltinBounds)
+                                      -> Result<ty::PolyTraitRef<'tcx>, ErrorReported>
+{
+    let tcx = this.tcx();
+    let alias_def_id = principal.def_id();
+    if !tcx.lookup_trait_def(alias_def_id).is_alias() {
+        return Ok(principal);
+    }
+    try!(this.ensure_super_predicates(span, alias_def_id));
+
+    // The alias's bounds are stated in terms of `Self`, which for an object
+    // type is not known; stand in for it as in `trait_ref_to_object_type`.
+    let dummy_self_ty = tcx.mk_infer(ty::FreshTy(0));
+    let alias_trait_ref = ty::Binder(ty::TraitRef {
+        def_id: alias_def_id,
+        substs: tcx.mk_substs(principal.0.substs.with_self_ty(dummy_self_ty)),
+    });
+
+    let mut expanded = None;
+    for predicate in &tcx.lookup_super_predicates(alias_def_id).predicates {
+        let trait_ref = match predicate.subst_supertrait(tcx, &alias_trait_ref) {
+            ty::Predicate::Trait(ref data) => data.to_poly_trait_ref(),
+            _ => continue,
+        };
+        if tcx.try_add_builtin_trait(trait_ref.def_id(), builtin_bounds) {
+            continue;
+        }
+        if expanded.is_some() {
+            span_err!(tcx.sess, span, E0225,
+                      "only the builtin traits can be used as closure or object bounds");
+            return Err(ErrorReported);
+        }
+        let mut substs = trait_ref.0.substs.clone();
+        assert!(substs.self_ty() == Some(dummy_self_ty));
+        substs.types.pop(SelfSpace);
+        if substs.types.iter().any(|t| t.walk().any(|t| t == dummy_self_ty)) {
+            traits::report_object_safety_error(
+                tcx, span, alias_def_id, vec![traits::ObjectSafetyViolation::SupertraitSelf])
+                .emit();
+            return Err(ErrorReported);
+        }
+        expanded = Some(ty::Binder(ty::TraitRef {
+            def_id: trait_ref.def_id(),
+            substs: tcx.mk_substs(substs),
+        }));
+    }
+
+    match expanded {
+        Some(principal) => expand_trait_alias_principal(this, span, principal, builtin_bounds),
+        None => {
+            span_err!(tcx.sess, span, E0224,
+                      "at least one non-builtin trait is required for an object type");
+            Err(ErrorReported)
+        }
+    }
+}
+
 fn make_object_type<'tcx>(this: &AstConv<'tcx>,
                           span: Span,
                           principal: ty::PolyTraitRef<'tcx>,
-                          bounds: ty::ExistentialBounds<'tcx>)
+                          mut bounds: ty::ExistentialBounds<'tcx>)
                           -> Ty<'tcx> {
     let tcx = this.tcx();
+    let principal = match expand_trait_alias_principal(this,
+                                                       span,
+                                                       principal,
+                                                       &mut bounds.builtin_bounds) {
+        Ok(principal) => principal,
+        Err(ErrorReported) => return tcx.types.err,
+    };
     let object = ty::TraitTy {
         principal: principal,
         bounds: bounds
@@ -1177,6 +1424,46 @@ fn report_ambiguous_associated_type(tcx: &TyCtxt,
               type_str, trait_str, name);
 }
 
+/// Generic associated types can be declared, but we cannot yet project out
+/// of them: a projection has nowhere to put the associated type's own
+/// parameters. Report an error at the use site if `assoc_name` is one.
+fn check_not_generic_associated_type(tcx: &TyCtxt,
+                                     span: Span,
+                                     trait_def_id: DefId,
+                                     assoc_name: ast::Name)
+                                     -> Result<(), ErrorReported> {
+    let is_generic = if let Some(trait_id) = tcx.map.as_local_node_id(trait_def_id) {
+        // Type collection may not have reached the trait yet, so use the HIR.
+        match tcx.map.expect_item(trait_id).node {
+            hir::ItemTrait(_, _, _, ref trait_items) => {
+                trait_items.iter().any(|trait_item| match trait_item.node {
+                    hir::TypeTraitItem(ref generics, _, _) => {
+                        trait_item.name == assoc_name && generics.is_parameterized()
+                    }
+                    _ => false,
+                })
+            }
+            _ => false,
+        }
+    } else {
+        tcx.trait_items(trait_def_id).iter().any(|item| match *item {
+            ty::TypeTraitItem(ref assoc_ty) => {
+                assoc_ty.name == assoc_name && assoc_ty.is_generic()
+            }
+            _ => false,
+        })
+    };
+
+    if is_generic {
+        span_err!(tcx.sess, span, E0526,
+                  "projections from the generic associated type `{}` are not yet supported",
+                  assoc_name);
+        Err(ErrorReported)
+    } else {
+        Ok(())
+    }
+}
+
 // Search for a bound on a type parameter which includes the associated item
 // given by assoc_name. ty_param_node_id is the node id for the type parameter
 // (which might be `Self`, but only if it is the `Self` of a trait, not an
@@ -1247,6 +1534,11 @@ fn one_bound_for_assoc_type<'tcx>(tcx: &TyCtxt<'tcx>,
                        ty_param_name,
                        bound);
         }
+        fileline_help!(&mut err, span,
+                       "use fully-qualified syntax to pick one, e.g. `<{} as {}>::{}`",
+                       ty_param_name,
+                       tcx.item_path_str(bounds[0].def_id()),
+                       assoc_name);
         err.emit();
     }
 
@@ -1338,6 +1630,9 @@ fn associated_path_def_to_ty<'tcx>(this: &AstConv<'tcx>,
     };
 
     let trait_did = bound.0.def_id;
+    if check_not_generic_associated_type(tcx, span, trait_did, assoc_name).is_err() {
+        return (tcx.types.err, ty_path_def);
+    }
     let ty = this.projected_ty_from_poly_trait_ref(span, bound, assoc_name);
 
     let item_did = if let Some(trait_id) = tcx.map.as_local_node_id(trait_did) {
@@ -1373,6 +1668,11 @@ fn qpath_to_ty<'tcx>(this: &AstConv<'tcx>,
 {
     let tcx = this.tcx();
 
+    if check_not_generic_associated_type(tcx, span, trait_def_id,
+                                         item_segment.identifier.name).is_err() {
+        return tcx.types.err;
+    }
+
     prohibit_type_params(tcx, slice::ref_slice(item_segment));
 
     let self_ty = if let Some(ty) = opt_self_ty {
@@ -1483,6 +1783,21 @@ fn base_def_to_ty<'tcx>(this: &AstConv<'tcx>,
             prohibit_type_params(tcx, base_segments);
             tcx.mk_param(space, index, name)
         }
+        Def::SelfTy(None, Some((item_id, self_ty_id))) if item_id == self_ty_id => {
+            // Self in a struct or enum: the type being defined, applied to
+            // its own parameters. Going through the item's type scheme
+            // catches definitions whose type depends on itself.
+            prohibit_type_params(tcx, base_segments);
+            if !tcx.sess.features.borrow().self_in_typedefs {
+                emit_feature_err(&tcx.sess.parse_sess.span_diagnostic,
+                                 "self_in_typedefs", span, GateIssue::Language,
+                                 "`Self` in struct and enum definitions is experimental");
+            }
+            match this.get_item_type_scheme(span, tcx.map.local_def_id(item_id)) {
+                Ok(scheme) => scheme.ty,
+                Err(ErrorReported) => tcx.types.err,
+            }
+        }
         Def::SelfTy(_, Some((_, self_ty_id))) => {
             // Self in impl (we know the concrete type).
             prohibit_type_params(tcx, base_segments);
@@ -1636,11 +1951,14 @@ pub fn ast_ty_to_ty<'tcx>(this: &AstConv<'tcx>,
         }
         hir::TyBareFn(ref bf) => {
             require_c_abi_if_variadic(tcx, &bf.decl, bf.abi, ast_ty.span);
-            tcx.mk_fn_ptr(ty_of_bare_fn(this, bf.unsafety, bf.abi, &bf.decl))
+            tcx.mk_fn_ptr(ty_of_bare_fn(this, None, bf.unsafety, bf.abi, &bf.decl, None))
         }
         hir::TyPolyTraitRef(ref bounds) => {
             conv_ty_poly_trait_ref(this, rscope, ast_ty.span, bounds)
         }
+        hir::TyImplTrait(ref bounds) => {
+            conv_impl_trait(this, rscope, ast_ty, bounds)
+        }
         hir::TyPath(ref maybe_qself, ref path) => {
             let path_res = if let Some(&d) = tcx.def_map.borrow().get(&ast_ty.id) {
                 d
@@ -1677,6 +1995,16 @@ pub fn ast_ty_to_ty<'tcx>(this: &AstConv<'tcx>,
 
             ty
         }
+        hir::TyFixedLengthVec(ref ty, ref e) if const_param_of_expr(tcx, e).is_some() => {
+            let (param, param_ty) = const_param_of_expr(tcx, e).unwrap();
+            if param_ty != tcx.types.usize {
+                span_err!(tcx.sess, e.span, E0308,
+                          "mismatched types: expected `usize`, found `{}`", param_ty);
+                tcx.types.err
+            } else {
+                tcx.mk_param_array(ast_ty_to_ty(this, rscope, &ty), param)
+            }
+        }
         hir::TyFixedLengthVec(ref ty, ref e) => {
             let hint = UncheckedExprHint(tcx.types.usize);
             match const_eval::eval_const_expr_partial(tcx, &e, hint, None) {
@@ -1745,9 +2073,12 @@ struct SelfInfo<'a, 'tcx> {
     explicit_self: &'a hir::ExplicitSelf,
 }
 
+/// Converts the signature of the method with node id `id`.
 pub fn ty_of_method<'tcx>(this: &AstConv<'tcx>,
+                          id: ast::NodeId,
                           sig: &hir::MethodSig,
-                          untransformed_self_ty: Ty<'tcx>)
+                          untransformed_self_ty: Ty<'tcx>,
+                          anon_scope: Option<AnonTypeScope>)
                           -> (ty::BareFnTy<'tcx>, ty::ExplicitSelfCategory) {
     let self_info = Some(SelfInfo {
         untransformed_self_ty: untransformed_self_ty,
@@ -1755,24 +2086,34 @@ pub fn ty_of_method<'tcx>(this: &AstConv<'tcx>,
     });
     let (bare_fn_ty, optional_explicit_self_category) =
         ty_of_method_or_bare_fn(this,
+                                Some(id),
                                 sig.unsafety,
                                 sig.abi,
                                 self_info,
-                                &sig.decl);
+                                &sig.decl,
+                                anon_scope);
     (bare_fn_ty, optional_explicit_self_category.unwrap())
 }
 
-pub fn ty_of_bare_fn<'tcx>(this: &AstConv<'tcx>, unsafety: hir::Unsafety, abi: abi::Abi,
-                                              decl: &hir::FnDecl) -> ty::BareFnTy<'tcx> {
-    let (bare_fn_ty, _) = ty_of_method_or_bare_fn(this, unsafety, abi, None, decl);
+/// Converts a function signature. `fn_id` is the node id of the function
+/// item, if any; it is `None` for `fn` pointer types. `anon_scope` is
+/// only given where `impl Trait` is allowed in the return type.
+pub fn ty_of_bare_fn<'tcx>(this: &AstConv<'tcx>, fn_id: Option<ast::NodeId>,
+                           unsafety: hir::Unsafety, abi: abi::Abi,
+                           decl: &hir::FnDecl,
+                           anon_scope: Option<AnonTypeScope>) -> ty::BareFnTy<'tcx> {
+    let (bare_fn_ty, _) =
+        ty_of_method_or_bare_fn(this, fn_id, unsafety, abi, None, decl, anon_scope);
     bare_fn_ty
 }
 
 fn ty_of_method_or_bare_fn<'a, 'tcx>(this: &AstConv<'tcx>,
+                                     fn_id: Option<ast::NodeId>,
                                      unsafety: hir::Unsafety,
                                      abi: abi::Abi,
                                      opt_self_info: Option<SelfInfo<'a, 'tcx>>,
-                                     decl: &hir::FnDecl)
+                                     decl: &hir::FnDecl,
+                                     anon_scope: Option<AnonTypeScope>)
                                      -> (ty::BareFnTy<'tcx>, Option<ty::ExplicitSelfCategory>)
 {
     debug!("ty_of_method_or_bare_fn");
@@ -1797,11 +2138,34 @@ fn ty_of_method_or_bare_fn<'a, 'tcx>(this: &AstConv<'tcx>,
     } else {
         &decl.inputs[..]
     };
-    let arg_tys: Vec<Ty> =
-        arg_params.iter().map(|a| ty_of_arg(this, &rb, a, None)).collect();
     let arg_pats: Vec<String> =
         arg_params.iter().map(|a| pprust::pat_to_string(&a.pat)).collect();
 
+    // Remember which argument introduced each anonymous region, so that
+    // region errors can point back at the elided lifetime.
+    let mut anon_positions = vec!["`self`".to_string(); rb.anon_region_spans().len()];
+    let arg_tys: Vec<Ty> = arg_params.iter().zip(&arg_pats).enumerate().map(|(i, (a, pat))| {
+        let ty = ty_of_arg(this, &rb, a, None);
+        let position = if pat.is_empty() {
+            format!("argument {}", i + 1)
+        } else {
+            format!("argument `{}`", pat)
+        };
+        let new_anon_regions = rb.anon_region_spans().len() - anon_positions.len();
+        anon_positions.extend(repeat(position).take(new_anon_regions));
+        ty
+    }).collect();
+
+    if let Some(fn_id) = fn_id {
+        let origins = rb.anon_region_spans().into_iter().zip(anon_positions)
+                        .map(|(span, position)| ty::AnonRegionOrigin {
+                            span: span,
+                            position: position,
+                        })
+                        .collect();
+        this.tcx().anon_region_origins.borrow_mut().insert(fn_id, origins);
+    }
+
     // Second, if there was exactly one lifetime (either a substitution or a
     // reference) in the arguments, then any anonymous regions in the output
     // have that lifetime.
@@ -1814,7 +2178,8 @@ fn ty_of_method_or_bare_fn<'a, 'tcx>(this: &AstConv<'tcx>,
         hir::Return(ref output) =>
             ty::FnConverging(convert_ty_with_lifetime_elision(this,
                                                               implied_output_region,
-                                                              &output)),
+                                                              &output,
+                                                              anon_scope)),
         hir::DefaultReturn(..) => ty::FnConverging(this.tcx().mk_nil()),
         hir::NoReturn(..) => ty::FnDiverging
     };
@@ -2034,6 +2399,70 @@ fn conv_ty_poly_trait_ref<'tcx>(
     make_object_type(this, span, main_trait_bound, bounds)
 }
 
+/// Converts `impl Trait` into an anonymized type whose bounds are recorded
+/// as its predicates. The concrete type behind it is only filled in once
+/// the body of the enclosing function has been checked.
+fn conv_impl_trait<'tcx>(this: &AstConv<'tcx>,
+                         rscope: &RegionScope,
+                         ast_ty: &hir::Ty,
+                         ast_bounds: &[hir::TyParamBound])
+                         -> Ty<'tcx>
+{
+    let tcx = this.tcx();
+    let anon_scope = match rscope.anon_type_scope() {
+        Some(anon_scope) => anon_scope,
+        None => {
+            span_err!(tcx.sess, ast_ty.span, E0533,
+                      "`impl Trait` not allowed outside of function \
+                       and inherent method return types");
+            return tcx.types.err;
+        }
+    };
+
+    let def_id = tcx.map.local_def_id(ast_ty.id);
+    let ty = tcx.mk_anon(def_id, tcx.mk_substs(anon_scope.fresh_substs(tcx)));
+
+    // Unlike `compute_bounds`, convert the bounds in the current scope, so
+    // that they can elide lifetimes and contain `impl Trait` themselves.
+    let PartitionedBounds {
+        builtin_bounds,
+        trait_bounds,
+        region_bounds
+    } = partition_bounds(tcx, ast_ty.span, ast_bounds);
+
+    let mut projection_bounds = Vec::new();
+    let trait_bounds = trait_bounds.iter().map(|bound| {
+        instantiate_poly_trait_ref(this, rscope, *bound, Some(ty), &mut projection_bounds)
+    }).collect();
+
+    let mut bounds = Bounds {
+        region_bounds: region_bounds.into_iter()
+                                    .map(|r| ast_region_to_region(tcx, r))
+                                    .collect(),
+        builtin_bounds: builtin_bounds,
+        trait_bounds: trait_bounds,
+        projection_bounds: projection_bounds,
+    };
+    add_default_bounds(this, ty, &mut bounds, ast_bounds, ast_ty.span);
+
+    let predicates = bounds.predicates(tcx, ty);
+
+    // The anonymized type only closes over the early-bound parameters of
+    // the function, so it cannot name any of its late-bound lifetimes.
+    if predicates.iter().any(|p| p.has_escaping_regions()) {
+        span_err!(tcx.sess, ast_ty.span, E0534,
+                  "`impl Trait` cannot capture lifetimes that are late-bound \
+                   to the enclosing function");
+        return tcx.types.err;
+    }
+
+    tcx.predicates.borrow_mut().insert(def_id, ty::GenericPredicates {
+        predicates: VecPerParamSpace::new(predicates, vec![], vec![])
+    });
+
+    ty
+}
+
 pub fn conv_existential_bounds_from_partitioned_bounds<'tcx>(
     this: &AstConv<'tcx>,
     rscope: &RegionScope,
@@ -2258,12 +2687,9 @@ impl<'tcx> Bounds<'tcx> {
     {
         let mut vec = Vec::new();
 
-        for builtin_bound in &self.builtin_bounds {
-            match traits::trait_ref_for_builtin_bound(tcx, builtin_bound, param_ty) {
-                Ok(trait_ref) => { vec.push(trait_ref.to_predicate()); }
-                Err(ErrorReported) => { }
-            }
-        }
+        vec.extend(tcx.builtin_bound_predicates(param_ty, self.builtin_bounds)
+                      .iter()
+                      .cloned());
 
         for &region_bound in &self.region_bounds {
             // account for the binder being introduced below; no need to shift `param_ty`
@@ -2283,3 +2709,137 @@ impl<'tcx> Bounds<'tcx> {
         vec
     }
 }
+
+/// Whether `compute_bounds` should add the implicit default bounds (`Sized`
+/// and the traits marked `#[default_bound]`), as it does for type parameters
+/// but not for `Self` in a trait's supertrait list.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum SizedByDefault { Yes, No, }
+
+/// Translate the AST's notion of ty param bounds (which are an enum consisting of a newtyped Ty or
+/// a region) to ty's notion of ty param bounds, which can either be user-defined traits, or the
+/// built-in trait (formerly known as kind): Send.
+///
+/// This is how collect converts the bounds on type parameters, associated
+/// types and supertraits; passes outside of typeck that need the same
+/// conversion should use this (or `AstConv::bound_predicates`) rather than
+/// reimplementing it.
+pub fn compute_bounds<'tcx>(astconv: &AstConv<'tcx>,
+                            param_ty: Ty<'tcx>,
+                            ast_bounds: &[hir::TyParamBound],
+                            sized_by_default: SizedByDefault,
+                            span: Span)
+                            -> Bounds<'tcx>
+{
+    let mut bounds =
+        conv_param_bounds(astconv,
+                          span,
+                          param_ty,
+                          ast_bounds);
+
+    if let SizedByDefault::Yes = sized_by_default {
+        add_default_bounds(astconv,
+                           param_ty,
+                           &mut bounds,
+                           ast_bounds,
+                           span);
+    }
+
+    bounds.trait_bounds.sort_by(|a,b| a.def_id().cmp(&b.def_id()));
+
+    bounds
+}
+
+/// Converts the bounds written on `param_ty`, without adding any implicit
+/// `Sized` bound.
+pub fn conv_param_bounds<'tcx>(astconv: &AstConv<'tcx>,
+                               span: Span,
+                               param_ty: Ty<'tcx>,
+                               ast_bounds: &[hir::TyParamBound])
+                               -> Bounds<'tcx>
+{
+    let tcx = astconv.tcx();
+    let PartitionedBounds {
+        builtin_bounds,
+        trait_bounds,
+        region_bounds
+    } = partition_bounds(tcx, span, &ast_bounds);
+
+    let mut projection_bounds = Vec::new();
+
+    let trait_bounds: Vec<ty::PolyTraitRef> =
+        trait_bounds.iter()
+                    .map(|bound| instantiate_poly_trait_ref(astconv,
+                                                            &ExplicitRscope,
+                                                            *bound,
+                                                            Some(param_ty),
+                                                            &mut projection_bounds))
+                    .collect();
+
+    let region_bounds: Vec<ty::Region> =
+        region_bounds.into_iter()
+                     .map(|r| ast_region_to_region(tcx, r))
+                     .collect();
+
+    Bounds {
+        region_bounds: region_bounds,
+        builtin_bounds: builtin_bounds,
+        trait_bounds: trait_bounds,
+        projection_bounds: projection_bounds,
+    }
+}
+
+// Add the default bounds: `Sized` and the traits marked `#[default_bound]`,
+// except those the type parameter opts out of with `?Trait`.
+fn add_default_bounds<'tcx>(astconv: &AstConv<'tcx>,
+                            param_ty: Ty<'tcx>,
+                            bounds: &mut Bounds<'tcx>,
+                            ast_bounds: &[hir::TyParamBound],
+                            span: Span)
+{
+    let tcx = astconv.tcx();
+
+    // Find the bounds that are relaxed.
+    let mut relaxed = Vec::new();
+    for ab in ast_bounds {
+        if let &hir::TraitTyParamBound(ref ptr, hir::TraitBoundModifier::Maybe) = ab  {
+            assert!(ptr.bound_lifetimes.is_empty());
+            let trait_def_id = tcx.trait_ref_to_def_id(&ptr.trait_ref);
+            if relaxed.contains(&trait_def_id) {
+                span_err!(tcx.sess, span, E0203,
+                          "type parameter relaxes the default bound `{}` more than once",
+                          tcx.item_path_str(trait_def_id));
+            } else {
+                relaxed.push(trait_def_id);
+            }
+        }
+    }
+
+    let kind_id = tcx.lang_items.require(SizedTraitLangItem).ok();
+    let default_bounds = tcx.default_bound_traits.borrow();
+    for &trait_def_id in &relaxed {
+        if Some(trait_def_id) != kind_id && !default_bounds.contains(&trait_def_id) {
+            tcx.sess.span_warn(span,
+                               &format!("default bound relaxed for a type parameter, but \
+                                         this does nothing because `{}` is not a default \
+                                         bound. Only `?Sized` and traits marked \
+                                         `#[default_bound]` are supported",
+                                        tcx.item_path_str(trait_def_id)));
+        }
+    }
+
+    // No lang item for Sized, so we can't add it as a bound.
+    if let Some(kind_id) = kind_id {
+        if !relaxed.contains(&kind_id) {
+            tcx.try_add_builtin_trait(kind_id, &mut bounds.builtin_bounds);
+        }
+    }
+
+    for &trait_def_id in default_bounds.iter() {
+        if !relaxed.contains(&trait_def_id) {
+            let substs = Substs::new_trait(vec![], vec![], param_ty);
+            let trait_ref = ty::TraitRef::new(trait_def_id, tcx.mk_substs(substs));
+            bounds.trait_bounds.push(ty::Binder(trait_ref));
+        }
+    }
+}
@@ -13,7 +13,7 @@ use middle::infer::{self, TypeOrigin};
 use middle::pat_util::{PatIdMap, pat_id_map, pat_is_binding};
 use middle::pat_util::pat_is_resolved_const;
 use middle::subst::Substs;
-use middle::ty::{self, Ty, TypeFoldable, LvaluePreference};
+use middle::ty::{self, Ty, TyCtxt, TypeFoldable, LvaluePreference};
 use check::{check_expr, check_expr_has_type, check_expr_with_expectation};
 use check::{demand, FnCtxt, Expectation};
 use check::{check_expr_with_lvalue_pref};
@@ -24,6 +24,7 @@ use require_same_types;
 use util::nodemap::FnvHashMap;
 use session::Session;
 
+use std::cell::Cell;
 use std::cmp;
 use std::collections::hash_map::Entry::{Occupied, Vacant};
 use syntax::ast;
@@ -37,6 +38,96 @@ use rustc_front::util as hir_util;
 pub fn check_pat<'a, 'tcx>(pcx: &pat_ctxt<'a, 'tcx>,
                            pat: &'tcx hir::Pat,
                            expected: Ty<'tcx>)
+{
+    // The default binding mode set up for `pat` only applies to its own
+    // subpatterns, so restore the enclosing one afterwards.
+    let default_binding_mode = pcx.default_binding_mode.get();
+    let expected = peel_off_references(pcx, pat, expected);
+    check_pat_kind(pcx, pat, expected);
+    pcx.default_binding_mode.set(default_binding_mode);
+}
+
+/// Implements default binding modes: when a non-reference pattern is
+/// matched against a reference, the reference is dereferenced implicitly
+/// and the bindings further down bind by reference. Records the references
+/// that were dereferenced in `pat_adjustments` and returns the type the
+/// pattern itself is checked against.
+fn peel_off_references<'a, 'tcx>(pcx: &pat_ctxt<'a, 'tcx>,
+                                 pat: &hir::Pat,
+                                 expected: Ty<'tcx>)
+                                 -> Ty<'tcx> {
+    let fcx = pcx.fcx;
+    let tcx = pcx.fcx.ccx.tcx;
+
+    if !tcx.sess.features.borrow().match_default_bindings || !is_non_ref_pat(tcx, pat) {
+        return expected;
+    }
+
+    let mut expected = fcx.infcx().shallow_resolve(expected);
+    let mut adjustments = vec![];
+    while let ty::TyRef(_, mt) = expected.sty {
+        if let PatKind::Vec(..) = pat.node {
+            // Slice patterns already match references to slices.
+            if let ty::TySlice(_) = fcx.infcx().shallow_resolve(mt.ty).sty {
+                break;
+            }
+        }
+
+        // Once something has been borrowed immutably, bindings below it
+        // can only borrow it immutably too.
+        let mode = match (pcx.default_binding_mode.get(), mt.mutbl) {
+            (hir::BindByRef(hir::MutImmutable), _) |
+            (_, hir::MutImmutable) => hir::BindByRef(hir::MutImmutable),
+            (_, hir::MutMutable) => hir::BindByRef(hir::MutMutable),
+        };
+        pcx.default_binding_mode.set(mode);
+
+        adjustments.push(expected);
+        expected = fcx.infcx().shallow_resolve(mt.ty);
+    }
+
+    debug!("peel_off_references(pat={:?}) adjustments={:?}", pat, adjustments);
+
+    if !adjustments.is_empty() {
+        fcx.inh.tables.borrow_mut().pat_adjustments.insert(pat.id, adjustments);
+    }
+    expected
+}
+
+/// Whether matching `pat` against a reference dereferences the reference
+/// implicitly. That is the case for every pattern that can never match a
+/// reference itself.
+fn is_non_ref_pat(tcx: &TyCtxt, pat: &hir::Pat) -> bool {
+    match pat.node {
+        PatKind::Struct(..) |
+        PatKind::TupleStruct(..) |
+        PatKind::Tup(..) |
+        PatKind::Box(..) |
+        PatKind::Range(..) |
+        PatKind::Vec(..) => true,
+        PatKind::Lit(ref lt) => {
+            match lt.node {
+                // String literals are references themselves.
+                hir::ExprLit(ref lt) => match lt.node {
+                    ast::LitKind::Str(..) | ast::LitKind::ByteStr(..) => false,
+                    _ => true,
+                },
+                _ => true,
+            }
+        }
+        PatKind::Path(..) | PatKind::Ident(..) => {
+            match tcx.def_map.borrow().get(&pat.id).map(|d| d.full_def()) {
+                Some(Def::Variant(..)) | Some(Def::Struct(..)) => true,
+                _ => false,
+            }
+        }
+        PatKind::Wild | PatKind::Ref(..) | PatKind::QPath(..) => false,
+    }
+}
+
+fn check_pat_kind<'a, 'tcx>(pcx: &pat_ctxt<'a, 'tcx>,
+                            pat: &'tcx hir::Pat,
+                            expected: Ty<'tcx>)
 {
     let fcx = pcx.fcx;
     let tcx = pcx.fcx.ccx.tcx;
@@ -157,6 +248,24 @@ pub fn check_pat<'a, 'tcx>(pcx: &pat_ctxt<'a, 'tcx>,
             }
         }
         PatKind::Ident(bm, ref path, ref sub) if pat_is_binding(&tcx.def_map.borrow(), pat) => {
+            let bm = match (bm, pcx.default_binding_mode.get()) {
+                (hir::BindByValue(hir::MutImmutable), mode @ hir::BindByRef(_)) => {
+                    fcx.inh.tables.borrow_mut().pat_binding_modes.insert(pat.id, mode);
+                    mode
+                }
+                (hir::BindByValue(hir::MutMutable), hir::BindByRef(_)) => {
+                    // `mut x` always binds by value, which is easy to miss
+                    // when everything around it binds by reference.
+                    tcx.sess.add_lint(lint::builtin::MUT_BINDING_RESETS_DEFAULT_MODE,
+                                      pat.id,
+                                      pat.span,
+                                      format!("`mut {}` binds by value although it is \
+                                               matched through a reference",
+                                              path.node.name));
+                    bm
+                }
+                _ => bm,
+            };
             let typ = fcx.local_ty(pat.span, pat.id);
             match bm {
                 hir::BindByRef(mutbl) => {
@@ -315,6 +424,17 @@ pub fn check_pat<'a, 'tcx>(pcx: &pat_ctxt<'a, 'tcx>,
                         None => min_len
                     }
                 }),
+                ty::TyParamArray(_, len) => {
+                    // The length is only known after monomorphization, so
+                    // only a lone `..` binding is guaranteed to match.
+                    if slice.is_none() || !before.is_empty() || !after.is_empty() {
+                        span_err!(tcx.sess, pat.span, E0546,
+                                  "a slice pattern on `{}` must be a single `..` binding, \
+                                   since its length `{}` is a const parameter",
+                                  expected_ty, len);
+                    }
+                    tcx.mk_param_array(inner_ty, len)
+                }
                 _ => {
                     let region = fcx.infcx().next_region_var(infer::PatternRegion(pat.span));
                     tcx.mk_ref(tcx.mk_region(region), ty::TypeAndMut {
@@ -477,6 +597,7 @@ pub fn check_match<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
         let mut pcx = pat_ctxt {
             fcx: fcx,
             map: pat_id_map(&tcx.def_map, &arm.pats[0]),
+            default_binding_mode: Cell::new(hir::BindByValue(hir::MutImmutable)),
         };
         for p in &arm.pats {
             check_pat(&mut pcx, &p, discrim_ty);
@@ -561,6 +682,9 @@ pub fn check_match<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
 pub struct pat_ctxt<'a, 'tcx: 'a> {
     pub fcx: &'a FnCtxt<'a, 'tcx>,
     pub map: PatIdMap,
+    /// How bindings that don't say `ref` bind at the current point in the
+    /// pattern: by value, unless a reference was dereferenced implicitly.
+    pub default_binding_mode: Cell<hir::BindingMode>,
 }
 
 pub fn check_pat_struct<'a, 'tcx>(pcx: &pat_ctxt<'a, 'tcx>, pat: &'tcx hir::Pat,
@@ -586,6 +710,18 @@ pub fn check_pat_struct<'a, 'tcx>(pcx: &pat_ctxt<'a, 'tcx>, pat: &'tcx hir::Pat,
     };
 
     let pat_ty = pcx.fcx.instantiate_type(def.def_id(), path);
+    if let ty::TyStruct(adt, _) = pat_ty.sty {
+        if adt.is_union() {
+            span_err!(tcx.sess, pat.span, E0538,
+                      "pattern matching on unions is not supported");
+            fcx.write_error(pat.id);
+
+            for field in fields {
+                check_pat(pcx, &field.node.pat, tcx.types.err);
+            }
+            return;
+        }
+    }
     let item_substs = match pat_ty.sty {
         ty::TyStruct(_, substs) | ty::TyEnum(_, substs) => substs,
         _ => tcx.sess.span_bug(pat.span, "struct variant is not an ADT")
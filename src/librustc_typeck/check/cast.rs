@@ -359,17 +359,20 @@ impl<'tcx> CastCheck<'tcx> {
         // array-ptr-cast.
 
         if m_expr.mutbl == hir::MutImmutable && m_cast.mutbl == hir::MutImmutable {
-            if let ty::TyArray(ety, _) = m_expr.ty.sty {
-                // Due to the limitations of LLVM global constants,
-                // region pointers end up pointing at copies of
-                // vector elements instead of the original values.
-                // To allow raw pointers to work correctly, we
-                // need to special-case obtaining a raw pointer
-                // from a region pointer to a vector.
-
-                // this will report a type mismatch if needed
-                demand::eqtype(fcx, self.span, ety, m_cast.ty);
-                return Ok(CastKind::ArrayPtrCast);
+            match m_expr.ty.sty {
+                ty::TyArray(ety, _) | ty::TyParamArray(ety, _) => {
+                    // Due to the limitations of LLVM global constants,
+                    // region pointers end up pointing at copies of
+                    // vector elements instead of the original values.
+                    // To allow raw pointers to work correctly, we
+                    // need to special-case obtaining a raw pointer
+                    // from a region pointer to a vector.
+
+                    // this will report a type mismatch if needed
+                    demand::eqtype(fcx, self.span, ety, m_cast.ty);
+                    return Ok(CastKind::ArrayPtrCast);
+                }
+                _ => {}
             }
         }
 
@@ -333,9 +333,9 @@ impl<'a,'tcx> ConfirmContext<'a,'tcx> {
                 pick.item.as_opt_method().unwrap()
                     .generics.regions.get_slice(subst::FnSpace));
 
-        let subst::Substs { types, regions } = substs;
+        let subst::Substs { types, regions, consts } = substs;
         let regions = regions.map(|r| r.with_slice(subst::FnSpace, &method_regions));
-        let mut final_substs = subst::Substs { types: types, regions: regions };
+        let mut final_substs = subst::Substs { types: types, regions: regions, consts: consts };
 
         if num_supplied_types == 0 {
             self.fcx.infcx().type_vars_for_defs(
@@ -213,7 +213,7 @@ fn create_steps<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
     });
 
     match final_ty.sty {
-        ty::TyArray(elem_ty, _) => {
+        ty::TyArray(elem_ty, _) | ty::TyParamArray(elem_ty, _) => {
             steps.push(CandidateStep {
                 self_ty: fcx.tcx().mk_slice(elem_ty),
                 autoderefs: dereferences,
@@ -764,16 +764,17 @@ impl<'a,'tcx> ProbeContext<'a,'tcx> {
             debug!("assemble_projection_candidates: step={:?}",
                    step);
 
-            let projection_trait_ref = match step.self_ty.sty {
-                ty::TyProjection(ref data) => &data.trait_ref,
+            let (def_id, substs) = match step.self_ty.sty {
+                ty::TyProjection(ref data) => (data.trait_ref.def_id, data.trait_ref.substs),
+                ty::TyAnon(def_id, substs) => (def_id, substs),
                 _ => continue,
             };
 
-            debug!("assemble_projection_candidates: projection_trait_ref={:?}",
-                   projection_trait_ref);
+            debug!("assemble_projection_candidates: def_id={:?} substs={:?}",
+                   def_id, substs);
 
-            let trait_predicates = self.tcx().lookup_predicates(projection_trait_ref.def_id);
-            let bounds = trait_predicates.instantiate(self.tcx(), projection_trait_ref.substs);
+            let trait_predicates = self.tcx().lookup_predicates(def_id);
+            let bounds = trait_predicates.instantiate(self.tcx(), substs);
             let predicates = bounds.predicates.into_vec();
             debug!("assemble_projection_candidates: predicates={:?}",
                    predicates);
@@ -784,8 +785,8 @@ impl<'a,'tcx> ProbeContext<'a,'tcx> {
             {
                 let bound = self.erase_late_bound_regions(&poly_bound);
 
-                debug!("assemble_projection_candidates: projection_trait_ref={:?} bound={:?}",
-                       projection_trait_ref,
+                debug!("assemble_projection_candidates: def_id={:?} bound={:?}",
+                       def_id,
                        bound);
 
                 if self.infcx().can_equate(&step.self_ty, &bound.self_ty()).is_ok() {
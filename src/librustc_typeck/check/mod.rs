@@ -105,11 +105,12 @@ use middle::ty::relate::TypeRelation;
 use middle::ty::util::Representability;
 use require_c_abi_if_variadic;
 use rscope::{ElisionFailureInfo, RegionScope};
+use rustc::front::map as hir_map;
 use session::{Session, CompileResult};
 use {CrateCtxt, lookup_full_def};
 use TypeAndSubsts;
 use lint;
-use util::common::{block_query, ErrorReported, indenter, loop_query};
+use util::common::{block_query, ErrorReported, indenter, loop_query, time_item};
 use util::nodemap::{DefIdMap, FnvHashMap, NodeMap};
 
 use std::cell::{Cell, Ref, RefCell};
@@ -175,6 +176,10 @@ pub struct Inherited<'a, 'tcx: 'a> {
     deferred_call_resolutions: RefCell<DefIdMap<Vec<DeferredCallResolutionHandler<'tcx>>>>,
 
     deferred_cast_checks: RefCell<Vec<cast::CastCheck<'tcx>>>,
+
+    // Anonymized types found in the signature of the function being
+    // checked, mapped to the type variables standing in for them.
+    anon_types: RefCell<DefIdMap<Ty<'tcx>>>,
 }
 
 trait DeferredCallResolution<'tcx> {
@@ -313,6 +318,7 @@ impl<'a, 'tcx> Inherited<'a, 'tcx> {
             tables: tables,
             deferred_call_resolutions: RefCell::new(DefIdMap()),
             deferred_cast_checks: RefCell::new(Vec::new()),
+            anon_types: RefCell::new(DefIdMap()),
         }
     }
 
@@ -330,6 +336,60 @@ impl<'a, 'tcx> Inherited<'a, 'tcx> {
                                              value)
     }
 
+    /// Replaces every anonymized type in `value` with a fresh type variable,
+    /// requiring that variable to satisfy the bounds of the anonymized type.
+    /// The type the variable is eventually resolved to becomes the concrete
+    /// type behind the `impl Trait`.
+    fn instantiate_anon_types<T>(&self,
+                                 span: Span,
+                                 body_id: ast::NodeId,
+                                 value: &T)
+                                 -> T
+        where T : TypeFoldable<'tcx>
+    {
+        let tcx = self.infcx.tcx;
+        let mut pending = vec![];
+        let value = self.replace_anon_types(value, &mut pending);
+
+        // The bounds may themselves mention anonymized types, e.g. the
+        // `impl Debug` in `impl Iterator<Item=impl Debug>`.
+        while let Some((def_id, substs)) = pending.pop() {
+            let bounds = tcx.lookup_predicates(def_id).instantiate(tcx, substs);
+            for predicate in bounds.predicates.into_vec() {
+                let predicate = self.replace_anon_types(&predicate, &mut pending);
+                let cause = traits::ObligationCause::misc(span, body_id);
+                self.fulfillment_cx.borrow_mut().register_predicate_obligation(
+                    &self.infcx,
+                    traits::Obligation::new(cause, predicate));
+            }
+        }
+
+        value
+    }
+
+    fn replace_anon_types<T>(&self,
+                             value: &T,
+                             pending: &mut Vec<(DefId, &'tcx Substs<'tcx>)>)
+                             -> T
+        where T : TypeFoldable<'tcx>
+    {
+        value.fold_with(&mut ty::fold::BottomUpFolder {
+            tcx: self.infcx.tcx,
+            fldop: |ty| {
+                if let ty::TyAnon(def_id, substs) = ty.sty {
+                    if let Some(&ty_var) = self.anon_types.borrow().get(&def_id) {
+                        return ty_var;
+                    }
+                    let ty_var = self.infcx.next_ty_var();
+                    self.anon_types.borrow_mut().insert(def_id, ty_var);
+                    pending.push((def_id, substs));
+                    ty_var
+                } else {
+                    ty
+                }
+            }
+        })
+    }
 }
 
 // Used by check_const and check_enum_variants
@@ -370,7 +430,7 @@ impl<'a, 'tcx> Visitor<'tcx> for CheckItemTypesVisitor<'a, 'tcx> {
     fn visit_ty(&mut self, t: &'tcx hir::Ty) {
         match t.node {
             hir::TyFixedLengthVec(_, ref expr) => {
-                check_const_in_type(self.ccx, &expr, self.ccx.tcx.types.usize);
+                check_array_length_in_type(self.ccx, &expr);
             }
             _ => {}
         }
@@ -449,6 +509,8 @@ fn check_bare_fn<'a, 'tcx>(ccx: &CrateCtxt<'a, 'tcx>,
                 inh.normalize_associated_types_in(body.span,
                                                   body.id,
                                                   &fn_sig);
+            let fn_sig =
+                inh.instantiate_anon_types(decl.output.span(), body.id, &fn_sig);
 
             let fcx = check_fn(ccx, fn_ty.unsafety, fn_id, &fn_sig,
                                decl, fn_id, body, &inh);
@@ -536,7 +598,7 @@ impl<'a, 'tcx> Visitor<'tcx> for GatherLocalsVisitor<'a, 'tcx> {
         match t.node {
             hir::TyFixedLengthVec(ref ty, ref count_expr) => {
                 self.visit_ty(&ty);
-                check_expr_with_hint(self.fcx, &count_expr, self.fcx.tcx().types.usize);
+                check_array_length(self.fcx, &count_expr);
             }
             hir::TyBareFn(ref function_declaration) => {
                 intravisit::walk_fn_decl_nopat(self, &function_declaration.decl);
@@ -551,6 +613,41 @@ impl<'a, 'tcx> Visitor<'tcx> for GatherLocalsVisitor<'a, 'tcx> {
                 _: &'tcx hir::Block, _: Span, _: ast::NodeId) { }
 }
 
+/// Collects the ids of an expression's immediate operands, patterns and
+/// blocks, without descending any further; see `err_type_parent`.
+struct OperandIds(Vec<ast::NodeId>);
+
+impl<'v> Visitor<'v> for OperandIds {
+    fn visit_expr(&mut self, e: &'v hir::Expr) { self.0.push(e.id); }
+    fn visit_pat(&mut self, p: &'v hir::Pat) { self.0.push(p.id); }
+    fn visit_block(&mut self, b: &'v hir::Block) { self.0.push(b.id); }
+}
+
+/// Finds the type aliases named in a type, or in an item's signature, and
+/// requires their bounds to hold; see `register_alias_bound_obligations`.
+/// Bodies, expressions and the items of traits and impls are left out, as
+/// they are checked with their own parameter environment.
+struct AliasBoundsVisitor<'a, 'tcx: 'a> {
+    fcx: &'a FnCtxt<'a, 'tcx>,
+}
+
+impl<'a, 'tcx, 'v> Visitor<'v> for AliasBoundsVisitor<'a, 'tcx> {
+    fn visit_ty(&mut self, t: &'v hir::Ty) {
+        if let hir::TyPath(_, ref path) = t.node {
+            let def = self.fcx.tcx().def_map.borrow().get(&t.id).cloned();
+            if let Some(def::PathResolution { base_def: Def::TyAlias(did), depth: 0 }) = def {
+                self.fcx.register_alias_predicates(t.span, did, path.segments.last().unwrap());
+            }
+        }
+        intravisit::walk_ty(self, t);
+    }
+
+    fn visit_block(&mut self, _: &'v hir::Block) { }
+    fn visit_expr(&mut self, _: &'v hir::Expr) { }
+    fn visit_trait_item(&mut self, _: &'v hir::TraitItem) { }
+    fn visit_impl_item(&mut self, _: &'v hir::ImplItem) { }
+}
+
 /// Helper used by check_bare_fn and check_expr_fn. Does the grungy work of checking a function
 /// body and returns the function context used for that purpose, since in the case of a fn item
 /// there is still a bit more to do.
@@ -625,6 +722,7 @@ fn check_fn<'a, 'tcx>(ccx: &'a CrateCtxt<'a, 'tcx>,
             let pcx = pat_ctxt {
                 fcx: &fcx,
                 map: pat_id_map(&tcx.def_map, &input.pat),
+                default_binding_mode: Cell::new(hir::BindByValue(hir::MutImmutable)),
             };
             _match::check_pat(&pcx, &input.pat, *arg_ty);
         }
@@ -654,6 +752,26 @@ pub fn check_struct(ccx: &CrateCtxt, id: ast::NodeId, span: Span) {
     }
 }
 
+/// Unions have no way of knowing which field is active, so they never run
+/// destructors for their contents; forbid fields that would need one.
+fn check_union(ccx: &CrateCtxt, id: ast::NodeId, span: Span) {
+    let tcx = ccx.tcx;
+
+    check_representable(tcx, span, id, "union");
+
+    let def = tcx.lookup_adt_def(tcx.map.local_def_id(id));
+    let param_env = ty::ParameterEnvironment::for_item(tcx, id);
+    for field in &def.struct_variant().fields {
+        let field_ty = field.ty(tcx, &param_env.free_substs);
+        if tcx.type_needs_drop_given_env(field_ty, &param_env) {
+            let field_span = tcx.map.span_if_local(field.did).unwrap_or(span);
+            span_err!(tcx.sess, field_span, E0536,
+                      "union field `{}` has type `{}`, which needs to be dropped",
+                      field.name, field_ty);
+        }
+    }
+}
+
 pub fn check_item_type<'a,'tcx>(ccx: &CrateCtxt<'a,'tcx>, it: &'tcx hir::Item) {
     debug!("check_item_type(it.id={}, it.name={})",
            it.id,
@@ -672,10 +790,12 @@ pub fn check_item_type<'a,'tcx>(ccx: &CrateCtxt<'a,'tcx>, it: &'tcx hir::Item) {
       hir::ItemFn(..) => {} // entirely within check_item_body
       hir::ItemImpl(_, _, _, _, _, ref impl_items) => {
           debug!("ItemImpl {} with id {}", it.name, it.id);
-          match ccx.tcx.impl_trait_ref(ccx.tcx.map.local_def_id(it.id)) {
+          let impl_def_id = ccx.tcx.map.local_def_id(it.id);
+          match ccx.tcx.impl_trait_ref(impl_def_id) {
               Some(impl_trait_ref) => {
                 check_impl_items_against_trait(ccx,
                                                it.span,
+                                               impl_def_id,
                                                &impl_trait_ref,
                                                impl_items);
               }
@@ -688,6 +808,9 @@ pub fn check_item_type<'a,'tcx>(ccx: &CrateCtxt<'a,'tcx>, it: &'tcx hir::Item) {
       hir::ItemStruct(..) => {
         check_struct(ccx, it.id, it.span);
       }
+      hir::ItemUnion(..) => {
+        check_union(ccx, it.id, it.span);
+      }
       hir::ItemTy(_, ref generics) => {
         let pty_ty = ccx.tcx.node_id_to_type(it.id);
         check_bounds_are_used(ccx, &generics.ty_params, pty_ty);
@@ -703,16 +826,6 @@ pub fn check_item_type<'a,'tcx>(ccx: &CrateCtxt<'a,'tcx>, it: &'tcx hir::Item) {
             }
         } else {
             for item in &m.items {
-                let pty = ccx.tcx.lookup_item_type(ccx.tcx.map.local_def_id(item.id));
-                if !pty.generics.types.is_empty() {
-                    let mut err = struct_span_err!(ccx.tcx.sess, item.span, E0044,
-                        "foreign items may not have type parameters");
-                    span_help!(&mut err, item.span,
-                        "consider using specialization instead of \
-                        type parameters");
-                    err.emit();
-                }
-
                 if let hir::ForeignItemFn(ref fn_decl, _) = item.node {
                     require_c_abi_if_variadic(ccx.tcx, fn_decl, m.abi, item.span);
                 }
@@ -723,6 +836,15 @@ pub fn check_item_type<'a,'tcx>(ccx: &CrateCtxt<'a,'tcx>, it: &'tcx hir::Item) {
     }
 }
 
+/// Type-checks the fn or method body with the given id, timing it for
+/// `-Z time-items`.
+fn time_body<'a, 'tcx, F: FnOnce()>(ccx: &CrateCtxt<'a, 'tcx>, id: ast::NodeId, f: F) {
+    let tcx = ccx.tcx;
+    time_item(tcx.sess.time_items(), "type checking",
+              || tcx.def_path_str(tcx.map.local_def_id(id)),
+              f)
+}
+
 pub fn check_item_body<'a,'tcx>(ccx: &CrateCtxt<'a,'tcx>, it: &'tcx hir::Item) {
     debug!("check_item_body(it.id={}, it.name={})",
            it.id,
@@ -732,7 +854,9 @@ pub fn check_item_body<'a,'tcx>(ccx: &CrateCtxt<'a,'tcx>, it: &'tcx hir::Item) {
       hir::ItemFn(ref decl, _, _, _, _, ref body) => {
         let fn_pty = ccx.tcx.lookup_item_type(ccx.tcx.map.local_def_id(it.id));
         let param_env = ParameterEnvironment::for_item(ccx.tcx, it.id);
-        check_bare_fn(ccx, &decl, &body, it.id, it.span, fn_pty.ty, param_env);
+        time_body(ccx, it.id, || {
+            check_bare_fn(ccx, &decl, &body, it.id, it.span, fn_pty.ty, param_env)
+        });
       }
       hir::ItemImpl(_, _, _, _, _, ref impl_items) => {
         debug!("ItemImpl {} with id {}", it.name, it.id);
@@ -742,11 +866,13 @@ pub fn check_item_body<'a,'tcx>(ccx: &CrateCtxt<'a,'tcx>, it: &'tcx hir::Item) {
         for impl_item in impl_items {
             match impl_item.node {
                 hir::ImplItemKind::Const(_, ref expr) => {
-                    check_const(ccx, impl_item.span, &expr, impl_item.id)
+                    check_associated_const(ccx, impl_item.span, &expr, impl_item.id)
                 }
                 hir::ImplItemKind::Method(ref sig, ref body) => {
-                    check_method_body(ccx, &impl_pty.generics, sig, body,
-                                      impl_item.id, impl_item.span);
+                    time_body(ccx, impl_item.id, || {
+                        check_method_body(ccx, &impl_pty.generics, sig, body,
+                                          impl_item.id, impl_item.span)
+                    });
                 }
                 hir::ImplItemKind::Type(_) => {
                     // Nothing to do here.
@@ -759,13 +885,15 @@ pub fn check_item_body<'a,'tcx>(ccx: &CrateCtxt<'a,'tcx>, it: &'tcx hir::Item) {
         for trait_item in trait_items {
             match trait_item.node {
                 hir::ConstTraitItem(_, Some(ref expr)) => {
-                    check_const(ccx, trait_item.span, &expr, trait_item.id)
+                    check_associated_const(ccx, trait_item.span, &expr, trait_item.id)
                 }
                 hir::MethodTraitItem(ref sig, Some(ref body)) => {
                     check_trait_fn_not_const(ccx, trait_item.span, sig.constness);
 
-                    check_method_body(ccx, &trait_def.generics, sig, body,
-                                      trait_item.id, trait_item.span);
+                    time_body(ccx, trait_item.id, || {
+                        check_method_body(ccx, &trait_def.generics, sig, body,
+                                          trait_item.id, trait_item.span)
+                    });
                 }
                 hir::MethodTraitItem(ref sig, None) => {
                     check_trait_fn_not_const(ccx, trait_item.span, sig.constness);
@@ -864,6 +992,7 @@ fn check_method_body<'a, 'tcx>(ccx: &CrateCtxt<'a, 'tcx>,
 
 fn check_impl_items_against_trait<'a, 'tcx>(ccx: &CrateCtxt<'a, 'tcx>,
                                             impl_span: Span,
+                                            impl_def_id: DefId,
                                             impl_trait_ref: &ty::TraitRef<'tcx>,
                                             impl_items: &[hir::ImplItem]) {
     // Locate trait methods
@@ -931,6 +1060,11 @@ fn check_impl_items_against_trait<'a, 'tcx>(ccx: &CrateCtxt<'a, 'tcx>,
                     };
 
                     if let &ty::TypeTraitItem(ref at) = ty_trait_item {
+                        if at.is_generic() {
+                            span_err!(tcx.sess, impl_item.span, E0527,
+                                      "implementations of generic associated types \
+                                       are not yet supported");
+                        }
                         if let Some(_) = at.ty {
                             overridden_associated_type = Some(impl_item);
                         }
@@ -951,6 +1085,20 @@ fn check_impl_items_against_trait<'a, 'tcx>(ccx: &CrateCtxt<'a, 'tcx>,
     let mut missing_items = Vec::new();
     let mut invalidated_items = Vec::new();
     let associated_type_overridden = overridden_associated_type.is_some();
+    // Items inherited from an impl that this one specializes are implemented.
+    let parent = tcx.impl_parent(impl_def_id);
+    let is_inherited = |trait_item: &ty::ImplOrTraitItem| {
+        parent.map_or(false, |parent| {
+            traits::find_impl_item(tcx, parent, |item| {
+                item.name() == trait_item.name() && match (item, trait_item) {
+                    (&ty::ConstTraitItem(..), &ty::ConstTraitItem(..)) |
+                    (&ty::MethodTraitItem(..), &ty::MethodTraitItem(..)) |
+                    (&ty::TypeTraitItem(..), &ty::TypeTraitItem(..)) => true,
+                    _ => false,
+                }
+            }).is_some()
+        })
+    };
     for trait_item in trait_items.iter() {
         match *trait_item {
             ty::ConstTraitItem(ref associated_const) => {
@@ -964,9 +1112,9 @@ fn check_impl_items_against_trait<'a, 'tcx>(ccx: &CrateCtxt<'a, 'tcx>,
                 });
                 let is_provided = associated_const.has_value;
 
-                if !is_implemented {
+                if !is_implemented && !is_inherited(trait_item) {
                     if !is_provided {
-                        missing_items.push(associated_const.name);
+                        missing_items.push((associated_const.name, associated_const.def_id));
                     } else if associated_type_overridden {
                         invalidated_items.push(associated_const.name);
                     }
@@ -984,9 +1132,9 @@ fn check_impl_items_against_trait<'a, 'tcx>(ccx: &CrateCtxt<'a, 'tcx>,
                     });
                 let is_provided =
                     provided_methods.iter().any(|m| m.name == trait_method.name);
-                if !is_implemented {
+                if !is_implemented && !is_inherited(trait_item) {
                     if !is_provided {
-                        missing_items.push(trait_method.name);
+                        missing_items.push((trait_method.name, trait_method.def_id));
                     } else if associated_type_overridden {
                         invalidated_items.push(trait_method.name);
                     }
@@ -1002,9 +1150,9 @@ fn check_impl_items_against_trait<'a, 'tcx>(ccx: &CrateCtxt<'a, 'tcx>,
                     }
                 });
                 let is_provided = associated_type.ty.is_some();
-                if !is_implemented {
+                if !is_implemented && !is_inherited(trait_item) {
                     if !is_provided {
-                        missing_items.push(associated_type.name);
+                        missing_items.push((associated_type.name, associated_type.def_id));
                     } else if associated_type_overridden {
                         invalidated_items.push(associated_type.name);
                     }
@@ -1014,11 +1162,24 @@ fn check_impl_items_against_trait<'a, 'tcx>(ccx: &CrateCtxt<'a, 'tcx>,
     }
 
     if !missing_items.is_empty() {
-        span_err!(tcx.sess, impl_span, E0046,
+        let mut err = struct_span_err!(tcx.sess, impl_span, E0046,
             "not all trait items implemented, missing: `{}`",
             missing_items.iter()
-                  .map(|name| name.to_string())
-                  .collect::<Vec<_>>().join("`, `"))
+                  .map(|&(name, _)| name.to_string())
+                  .collect::<Vec<_>>().join("`, `"));
+        // For a trait from another crate, point at where each missing item
+        // is declared there, since the trait's source isn't otherwise shown.
+        if impl_trait_ref.def_id.krate != LOCAL_CRATE {
+            let crate_name = tcx.sess.cstore.crate_name(impl_trait_ref.def_id.krate);
+            for &(name, def_id) in &missing_items {
+                if let Some(span) = tcx.sess.cstore.item_span(tcx, def_id) {
+                    span_note!(&mut err, span,
+                               "`{}` is required by this declaration in crate `{}`",
+                               name, crate_name);
+                }
+            }
+        }
+        err.emit();
     }
 
     if !invalidated_items.is_empty() {
@@ -1284,9 +1445,75 @@ impl<'a, 'tcx> FnCtxt<'a, 'tcx> {
     pub fn write_ty(&self, node_id: ast::NodeId, ty: Ty<'tcx>) {
         debug!("write_ty({}, {:?}) in fcx {}",
                node_id, ty, self.tag());
+        if ty.references_error() {
+            self.record_err_type(node_id, false);
+        }
         self.inh.tables.borrow_mut().node_types.insert(node_id, ty);
     }
 
+    /// Records `node_id` in `err_type_origins`, linked to the node it most
+    /// likely got its error type from.
+    pub fn record_err_type(&self, node_id: ast::NodeId, at_writeback: bool) {
+        if !self.tcx().tracks_err_types() {
+            return;
+        }
+        self.tcx().record_err_type(node_id, ty::ErrTypeOrigin {
+            span: self.tcx().map.opt_span(node_id).unwrap_or(codemap::DUMMY_SP),
+            parent: self.err_type_parent(node_id),
+            diagnostic: self.tcx().sess.err_count(),
+            at_writeback: at_writeback,
+        });
+    }
+
+    /// The first node among `node_id`'s operands, or the variable a path
+    /// refers to, or a binding's initializer, whose type (as far as it is
+    /// known) references the error type.
+    fn err_type_parent(&self, node_id: ast::NodeId) -> Option<ast::NodeId> {
+        let candidates = match self.tcx().map.find(node_id) {
+            Some(hir_map::NodeExpr(expr)) => {
+                let local = match expr.node {
+                    hir::ExprPath(..) => {
+                        match self.tcx().def_map.borrow().get(&expr.id).map(|d| d.full_def()) {
+                            Some(Def::Local(_, id)) => Some(id),
+                            _ => None,
+                        }
+                    }
+                    _ => None,
+                };
+                match local {
+                    Some(id) => vec![id],
+                    None => {
+                        let mut operands = OperandIds(vec![]);
+                        intravisit::walk_expr(&mut operands, expr);
+                        operands.0
+                    }
+                }
+            }
+            Some(hir_map::NodeBlock(block)) => block.expr.iter().map(|e| e.id).collect(),
+            Some(hir_map::NodeLocal(_)) => {
+                let parent = self.tcx().map.get_parent_node(node_id);
+                let decl = match self.tcx().map.find(parent) {
+                    Some(hir_map::NodeStmt(stmt)) => match stmt.node {
+                        hir::StmtDecl(ref decl, _) => Some(decl),
+                        _ => None,
+                    },
+                    _ => None,
+                };
+                match decl.map(|decl| &decl.node) {
+                    Some(&hir::DeclLocal(ref local)) => local.init.iter().map(|e| e.id).collect(),
+                    _ => vec![],
+                }
+            }
+            _ => vec![],
+        };
+        let tables = self.inh.tables.borrow();
+        candidates.into_iter().find(|&id| {
+            id != node_id && tables.node_types.get(&id).map_or(false, |&ty| {
+                self.infcx().resolve_type_vars_if_possible(&ty).references_error()
+            })
+        })
+    }
+
     pub fn write_substs(&self, node_id: ast::NodeId, substs: ty::ItemSubsts<'tcx>) {
         if !substs.substs.is_noop() {
             debug!("write_substs({}, {:?}) in fcx {}",
@@ -1514,9 +1741,67 @@ impl<'a, 'tcx> FnCtxt<'a, 'tcx> {
     pub fn to_ty(&self, ast_t: &hir::Ty) -> Ty<'tcx> {
         let t = ast_ty_to_ty(self, self, ast_t);
         self.register_wf_obligation(t, ast_t.span, traits::MiscObligation);
+        if self.tcx().sess.features.borrow().type_alias_bounds {
+            AliasBoundsVisitor { fcx: self }.visit_ty(ast_t);
+        }
         t
     }
 
+    /// Under `#![feature(type_alias_bounds)]`, requires the trait bounds
+    /// declared by every type alias named in the signature of the item,
+    /// trait item or impl item `id` to hold for the arguments given there.
+    pub fn register_alias_bound_obligations(&self, id: ast::NodeId) {
+        if !self.tcx().sess.features.borrow().type_alias_bounds {
+            return;
+        }
+        let mut visitor = AliasBoundsVisitor { fcx: self };
+        match self.tcx().map.find(id) {
+            Some(hir_map::NodeItem(item)) => intravisit::walk_item(&mut visitor, item),
+            Some(hir_map::NodeTraitItem(item)) => intravisit::walk_trait_item(&mut visitor, item),
+            Some(hir_map::NodeImplItem(item)) => intravisit::walk_impl_item(&mut visitor, item),
+            _ => {}
+        }
+    }
+
+    fn register_alias_predicates(&self,
+                                 span: Span,
+                                 alias_did: DefId,
+                                 segment: &hir::PathSegment) {
+        let tcx = self.tcx();
+        let predicates = tcx.lookup_predicates(alias_did);
+        if predicates.predicates.is_empty() {
+            return;
+        }
+
+        // A wrong number of arguments has already been reported.
+        let generics = tcx.lookup_item_type(alias_did).generics;
+        let decl_tys = generics.types.get_slice(subst::TypeSpace);
+        let required = decl_tys.iter().take_while(|d| d.default.is_none()).count();
+        let supplied = segment.parameters.types().len();
+        if supplied < required || supplied > decl_tys.len() {
+            return;
+        }
+
+        let substs = astconv::ast_path_substs_for_ty(self, self, span, PathParamMode::Explicit,
+                                                     &generics, segment);
+        let predicates = predicates.instantiate(tcx, &substs);
+        let cause = traits::ObligationCause::new(span, self.body_id,
+                                                 traits::ItemObligation(alias_did));
+        for obligation in traits::predicates_for_generics(cause, &predicates) {
+            // Only trait bounds are enforced; lifetimes named in the
+            // arguments may be bound by an enclosing signature.
+            match obligation.predicate {
+                ty::Predicate::Trait(..) | ty::Predicate::Projection(..) => {}
+                _ => continue,
+            }
+            if obligation.predicate.has_escaping_regions() {
+                continue;
+            }
+            let predicate = tcx.erase_regions(&obligation.predicate);
+            self.register_predicate(traits::Obligation { predicate: predicate, ..obligation });
+        }
+    }
+
     pub fn expr_ty(&self, ex: &hir::Expr) -> Ty<'tcx> {
         match self.inh.tables.borrow().node_types.get(&ex.id) {
             Some(&t) => t,
@@ -2030,6 +2315,10 @@ impl<'a, 'tcx> RegionScope for FnCtxt<'a, 'tcx> {
         self.infcx().next_region_var(infer::MiscVariable(span))
     }
 
+    fn infers_object_lifetimes(&self) -> bool {
+        true
+    }
+
     fn anon_regions(&self, span: Span, count: usize)
                     -> Result<Vec<ty::Region>, Option<Vec<ElisionFailureInfo>>> {
         Ok((0..count).map(|_| {
@@ -2207,7 +2496,11 @@ fn lookup_indexing<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
 
     // After we have fully autoderef'd, if the resulting type is [T; n], then
     // do a final unsized coercion to yield [T].
-    if let ty::TyArray(element_ty, _) = ty.sty {
+    let element_ty = match ty.sty {
+        ty::TyArray(element_ty, _) | ty::TyParamArray(element_ty, _) => Some(element_ty),
+        _ => None
+    };
+    if let Some(element_ty) = element_ty {
         let adjusted_ty = fcx.tcx().mk_slice(element_ty);
         try_index_step(fcx, MethodCall::expr(expr.id), expr, base_expr,
                        adjusted_ty, autoderefs, true, lvalue_pref, idx_ty)
@@ -2467,7 +2760,7 @@ fn check_argument_types<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
             supplied_arg_count
         };
         for (i, arg) in args.iter().take(t).enumerate() {
-            if any_diverges && !warned {
+            if any_diverges && !warned && !is_desugared(fcx, arg.id) {
                 fcx.ccx
                     .tcx
                     .sess
@@ -2511,13 +2804,15 @@ fn check_argument_types<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
         }
         if any_diverges && !warned {
             let parent = fcx.ccx.tcx.map.get_parent_node(args[0].id);
-            fcx.ccx
-                .tcx
-                .sess
-                .add_lint(lint::builtin::UNREACHABLE_CODE,
-                          parent,
-                          sp,
-                          "unreachable call".to_string());
+            if !is_desugared(fcx, parent) {
+                fcx.ccx
+                    .tcx
+                    .sess
+                    .add_lint(lint::builtin::UNREACHABLE_CODE,
+                              parent,
+                              sp,
+                              "unreachable call".to_string());
+            }
             warned = true;
         }
 
@@ -3078,8 +3373,9 @@ fn check_expr_with_expectation_and_lvalue_pref<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
                                           ast_fields: &'tcx [hir::Field],
                                           check_completeness: bool) {
         let tcx = fcx.ccx.tcx;
-        let substs = match adt_ty.sty {
-            ty::TyStruct(_, substs) | ty::TyEnum(_, substs) => substs,
+        let (substs, is_union) = match adt_ty.sty {
+            ty::TyStruct(def, substs) => (substs, def.is_union()),
+            ty::TyEnum(_, substs) => (substs, false),
             _ => tcx.sess.span_bug(span, "non-ADT passed to check_expr_struct_fields")
         };
 
@@ -3113,8 +3409,14 @@ fn check_expr_with_expectation_and_lvalue_pref<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
             check_expr_coercable_to_type(fcx, &field.expr, expected_field_type);
         }
 
-            // Make sure the programmer specified all the fields.
-        if check_completeness &&
+        // Make sure the programmer specified all the fields; a union is
+        // initialized through exactly one of its fields instead.
+        if is_union {
+            if ast_fields.len() != 1 {
+                span_err!(tcx.sess, span, E0537,
+                          "union expressions should have exactly one field");
+            }
+        } else if check_completeness &&
             !error_happened &&
             !remaining_fields.is_empty()
         {
@@ -3179,7 +3481,7 @@ fn check_expr_with_expectation_and_lvalue_pref<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
         if let &Some(ref base_expr) = base_expr {
             check_expr_has_type(fcx, base_expr, expr_ty);
             match expr_ty.sty {
-                ty::TyStruct(adt, substs) => {
+                ty::TyStruct(adt, substs) if !adt.is_union() => {
                     fcx.inh.tables.borrow_mut().fru_field_types.insert(
                         expr.id,
                         adt.struct_variant().fields.iter().map(|f| {
@@ -3201,6 +3503,13 @@ fn check_expr_with_expectation_and_lvalue_pref<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
 
     let tcx = fcx.ccx.tcx;
     let id = expr.id;
+
+    if tcx.sess.opts.debugging_opts.expected_type.is_some() {
+        if let Some(ty) = expected.only_has_type(fcx) {
+            fcx.inh.tables.borrow_mut().expected_types.insert(id, ty);
+        }
+    }
+
     match expr.node {
       hir::ExprBox(ref subexpr) => {
         let expected_inner = expected.to_option(fcx).map_or(NoExpectation, |ty| {
@@ -3348,7 +3657,11 @@ fn check_expr_with_expectation_and_lvalue_pref<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
           if let Some((opt_ty, segments, def)) =
                   resolve_ty_and_def_ufcs(fcx, path_res, opt_self_ty, path,
                                           expr.span, expr.id) {
-              if def != Def::Err {
+              if let Def::ConstParam(_, _, _, name) = def {
+                  span_err!(tcx.sess, expr.span, E0542,
+                            "const parameter `{}` can only be used as an array length", name);
+                  fcx.write_error(id);
+              } else if def != Def::Err {
                   let (scheme, predicates) = type_scheme_and_predicates_for_def(fcx,
                                                                                 expr.span,
                                                                                 def);
@@ -3480,7 +3793,7 @@ fn check_expr_with_expectation_and_lvalue_pref<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
       }
       hir::ExprCast(ref e, ref t) => {
         if let hir::TyFixedLengthVec(_, ref count_expr) = t.node {
-            check_expr_with_hint(fcx, &count_expr, tcx.types.usize);
+            check_array_length(fcx, &count_expr);
         }
 
         // Find the type of `e`. Supply hints based on the type we are casting to,
@@ -3515,7 +3828,7 @@ fn check_expr_with_expectation_and_lvalue_pref<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
       hir::ExprVec(ref args) => {
         let uty = expected.to_option(fcx).and_then(|uty| {
             match uty.sty {
-                ty::TyArray(ty, _) | ty::TySlice(ty) => Some(ty),
+                ty::TyArray(ty, _) | ty::TyParamArray(ty, _) | ty::TySlice(ty) => Some(ty),
                 _ => None
             }
         });
@@ -3552,7 +3865,7 @@ fn check_expr_with_expectation_and_lvalue_pref<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
         let uty = match expected {
             ExpectHasType(uty) => {
                 match uty.sty {
-                    ty::TyArray(ty, _) | ty::TySlice(ty) => Some(ty),
+                    ty::TyArray(ty, _) | ty::TyParamArray(ty, _) | ty::TySlice(ty) => Some(ty),
                     _ => None
                 }
             }
@@ -3830,6 +4143,7 @@ pub fn check_decl_local<'a,'tcx>(fcx: &FnCtxt<'a,'tcx>, local: &'tcx hir::Local)
     let pcx = pat_ctxt {
         fcx: fcx,
         map: pat_id_map(&tcx.def_map, &local.pat),
+        default_binding_mode: Cell::new(hir::BindByValue(hir::MutImmutable)),
     };
     _match::check_pat(&pcx, &local.pat, t);
     let pat_ty = fcx.node_ty(local.pat.id);
@@ -3893,6 +4207,12 @@ pub fn check_block_no_value<'a,'tcx>(fcx: &FnCtxt<'a,'tcx>, blk: &'tcx hir::Bloc
     }
 }
 
+/// Whether `id` was generated by lowering a desugared construct. Lints about
+/// such nodes would point at code the user never wrote, so we skip them.
+fn is_desugared(fcx: &FnCtxt, id: ast::NodeId) -> bool {
+    fcx.tcx().map.desugaring_kind(id).is_some()
+}
+
 fn check_block_with_expected<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
                                        blk: &'tcx hir::Block,
                                        expected: Expectation<'tcx>) {
@@ -3909,7 +4229,7 @@ fn check_block_with_expected<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
         check_stmt(fcx, s);
         let s_id = ::rustc_front::util::stmt_id(s);
         let s_ty = fcx.node_ty(s_id);
-        if any_diverges && !warned && match s.node {
+        if any_diverges && !warned && !is_desugared(fcx, s_id) && match s.node {
             hir::StmtDecl(ref decl, _) => {
                 match decl.node {
                     hir::DeclLocal(_) => true,
@@ -3939,7 +4259,7 @@ fn check_block_with_expected<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
             fcx.write_nil(blk.id);
         },
         Some(ref e) => {
-            if any_diverges && !warned {
+            if any_diverges && !warned && !is_desugared(fcx, e.id) {
                 fcx.ccx
                     .tcx
                     .sess
@@ -3984,6 +4304,41 @@ fn check_const_in_type<'a,'tcx>(ccx: &'a CrateCtxt<'a,'tcx>,
     check_const_with_ty(&fcx, expr.span, expr, expected_type);
 }
 
+fn check_array_length_in_type<'a,'tcx>(ccx: &'a CrateCtxt<'a,'tcx>,
+                                       count_expr: &'tcx hir::Expr) {
+    match astconv::const_param_of_expr(ccx.tcx, count_expr) {
+        Some((_, ty)) => {
+            let tables = RefCell::new(ty::Tables::empty());
+            let inh = static_inherited_fields(ccx, &tables);
+            let fcx = blank_fn_ctxt(ccx, &inh, ty::FnConverging(ty), count_expr.id);
+            write_const_param_ty(&fcx, count_expr, ty);
+            writeback::resolve_type_vars_in_expr(&fcx, count_expr);
+        }
+        None => check_const_in_type(ccx, count_expr, ccx.tcx.types.usize)
+    }
+}
+
+/// Array lengths may name a const parameter, possibly in braces as `{N}`.
+/// It is not a value anywhere else, so it is only given its type here
+/// rather than checked as an expression; astconv has already checked
+/// that the type is `usize`.
+fn check_array_length<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>, count_expr: &'tcx hir::Expr) {
+    match astconv::const_param_of_expr(fcx.tcx(), count_expr) {
+        Some((_, ty)) => write_const_param_ty(fcx, count_expr, ty),
+        None => check_expr_with_hint(fcx, count_expr, fcx.tcx().types.usize)
+    }
+}
+
+fn write_const_param_ty<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>, e: &hir::Expr, ty: Ty<'tcx>) {
+    fcx.write_ty(e.id, ty);
+    if let hir::ExprBlock(ref block) = e.node {
+        fcx.write_ty(block.id, ty);
+        if let Some(ref e) = block.expr {
+            write_const_param_ty(fcx, e, ty);
+        }
+    }
+}
+
 fn check_const<'a,'tcx>(ccx: &CrateCtxt<'a,'tcx>,
                         sp: Span,
                         e: &'tcx hir::Expr,
@@ -3996,6 +4351,22 @@ fn check_const<'a,'tcx>(ccx: &CrateCtxt<'a,'tcx>,
     check_const_with_ty(&fcx, sp, e, declty);
 }
 
+/// Checks the value of an associated constant. Unlike free constants these
+/// can mention the type parameters (and `Self`) of their trait or impl, so
+/// they are checked in that item's parameter environment.
+fn check_associated_const<'a,'tcx>(ccx: &CrateCtxt<'a,'tcx>,
+                                   sp: Span,
+                                   e: &'tcx hir::Expr,
+                                   id: ast::NodeId) {
+    let tables = RefCell::new(ty::Tables::empty());
+    let param_env = ParameterEnvironment::for_item(ccx.tcx, id);
+    let inh = Inherited::new(ccx.tcx, &tables, param_env);
+    let declty = ccx.tcx.lookup_item_type(ccx.tcx.map.local_def_id(id)).ty;
+    let declty = declty.subst(ccx.tcx, &inh.infcx.parameter_environment.free_substs);
+    let fcx = blank_fn_ctxt(ccx, &inh, ty::FnConverging(declty), e.id);
+    check_const_with_ty(&fcx, sp, e, declty);
+}
+
 fn check_const_with_ty<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
                                  _: Span,
                                  e: &'tcx hir::Expr,
@@ -4201,6 +4572,7 @@ fn type_scheme_and_predicates_for_def<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
         Def::ForeignMod(..) |
         Def::Label(..) |
         Def::SelfTy(..) |
+        Def::ConstParam(..) |
         Def::Err => {
             fcx.ccx.tcx.sess.span_bug(sp, &format!("expected value, found {:?}", defn));
         }
@@ -4372,6 +4744,7 @@ pub fn instantiate_path<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
         Def::Local(..) |
         Def::Label(..) |
         Def::Upvar(..) |
+        Def::ConstParam(..) |
         Def::Err => {
             segment_spaces = vec![None; segments.len()];
         }
@@ -4418,6 +4791,23 @@ pub fn instantiate_path<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
         }
     }
 
+    // Const arguments are never inferred, so each space that takes any
+    // must be given all of them.
+    let const_defs = &type_scheme.generics.consts;
+    for (opt_space, segment) in segment_spaces.iter().zip(segments) {
+        if let Some(space) = *opt_space {
+            let provided = match segment.parameters {
+                hir::AngleBracketedParameters(ref data) => &data.consts[..],
+                hir::ParenthesizedParameters(..) => &[],
+            };
+            let consts = astconv::const_args_for_path(fcx.tcx(),
+                                                      span,
+                                                      const_defs.get_slice(space),
+                                                      provided);
+            substs.consts.replace(space, consts);
+        }
+    }
+
     // Now we have to compare the types that the user *actually*
     // provided against the types that were *expected*. If the user
     // did not provide any types, then we want to substitute inference
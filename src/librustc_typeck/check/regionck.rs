@@ -1189,11 +1189,15 @@ fn link_pattern<'t, 'a, 'tcx>(rcx: &Rcx<'a, 'tcx>,
            root_pat);
     let _ = mc.cat_pattern(discr_cmt, root_pat, |mc, sub_cmt, sub_pat| {
             match sub_pat.node {
-                // `ref x` pattern
-                PatKind::Ident(hir::BindByRef(mutbl), _, _) => {
-                    link_region_from_node_type(
-                        rcx, sub_pat.span, sub_pat.id,
-                        mutbl, sub_cmt);
+                // `ref x` pattern, or `x` bound by reference through a
+                // default binding mode
+                PatKind::Ident(bm, _, _) => {
+                    let bm = rcx.fcx.inh.tables.borrow().binding_mode(sub_pat.id, bm);
+                    if let hir::BindByRef(mutbl) = bm {
+                        link_region_from_node_type(
+                            rcx, sub_pat.span, sub_pat.id,
+                            mutbl, sub_cmt);
+                    }
                 }
 
                 // `[_, ..slice, _]` pattern
@@ -22,6 +22,7 @@ use middle::ty::fold::{TypeFolder};
 use std::cell::RefCell;
 use std::collections::HashSet;
 use syntax::ast;
+use syntax::attr;
 use syntax::codemap::{Span};
 use syntax::errors::DiagnosticBuilder;
 use syntax::parse::token::{special_idents};
@@ -109,14 +110,21 @@ impl<'ccx, 'tcx> CheckTypeWellFormedVisitor<'ccx, 'tcx> {
                 self.check_item_type(item);
             }
             hir::ItemStruct(ref struct_def, ref ast_generics) => {
-                self.check_type_defn(item, |fcx| {
+                self.check_type_defn(item, false, |fcx| {
+                    vec![struct_variant(fcx, struct_def)]
+                });
+
+                self.check_variances_for_type_defn(item, ast_generics);
+            }
+            hir::ItemUnion(ref struct_def, ref ast_generics) => {
+                self.check_type_defn(item, true, |fcx| {
                     vec![struct_variant(fcx, struct_def)]
                 });
 
                 self.check_variances_for_type_defn(item, ast_generics);
             }
             hir::ItemEnum(ref enum_def, ref ast_generics) => {
-                self.check_type_defn(item, |fcx| {
+                self.check_type_defn(item, false, |fcx| {
                     enum_variants(fcx, enum_def)
                 });
 
@@ -158,6 +166,7 @@ impl<'ccx, 'tcx> CheckTypeWellFormedVisitor<'ccx, 'tcx> {
                                                free_id_outlive, self_ty);
                 }
                 ty::TypeTraitItem(assoc_type) => {
+                    reject_shadowing_type_parameters(fcx.tcx(), span, &assoc_type.generics);
                     if let Some(ref ty) = assoc_type.ty {
                         let ty = fcx.instantiate_type_scheme(span, free_substs, ty);
                         fcx.register_wf_obligation(ty, span, code.clone());
@@ -186,28 +195,30 @@ impl<'ccx, 'tcx> CheckTypeWellFormedVisitor<'ccx, 'tcx> {
         let inh = Inherited::new(ccx.tcx, &tables, param_env);
         let fcx = blank_fn_ctxt(ccx, &inh, ty::FnDiverging, id);
         let wf_tys = f(&fcx, self);
+        fcx.register_alias_bound_obligations(id);
         fcx.select_all_obligations_or_error();
         regionck::regionck_item(&fcx, id, span, &wf_tys);
     }
 
     /// In a type definition, we check that to ensure that the types of the fields are well-formed.
-    fn check_type_defn<F>(&mut self, item: &hir::Item, mut lookup_fields: F) where
+    fn check_type_defn<F>(&mut self, item: &hir::Item, all_sized: bool, mut lookup_fields: F) where
         F: for<'fcx> FnMut(&FnCtxt<'fcx, 'tcx>) -> Vec<AdtVariant<'tcx>>,
     {
         self.with_item_fcx(item, |fcx, this| {
             let variants = lookup_fields(fcx);
 
             for variant in &variants {
-                // For DST, all intermediate types must be sized.
-                if let Some((_, fields)) = variant.fields.split_last() {
-                    for field in fields {
-                        fcx.register_builtin_bound(
-                            field.ty,
-                            ty::BoundSized,
-                            traits::ObligationCause::new(field.span,
-                                                         fcx.body_id,
-                                                         traits::FieldSized));
-                    }
+                // For DST, all intermediate types must be sized. Union fields
+                // all overlap, so none of them may be unsized.
+                let unsized_len = if all_sized { 0 } else { 1 };
+                let sized_len = variant.fields.len().saturating_sub(unsized_len);
+                for field in &variant.fields[..sized_len] {
+                    fcx.register_builtin_bound(
+                        field.ty,
+                        ty::BoundSized,
+                        traits::ObligationCause::new(field.span,
+                                                     fcx.body_id,
+                                                     traits::FieldSized));
                 }
 
                 // All field types must be well-formed.
@@ -237,6 +248,17 @@ impl<'ccx, 'tcx> CheckTypeWellFormedVisitor<'ccx, 'tcx> {
             }
         }
 
+        if attr::contains_name(&item.attrs, "default_bound") {
+            let generics = &self.tcx().lookup_trait_def(trait_def_id).generics;
+            if !self.ccx.tcx.trait_has_default_impl(trait_def_id) ||
+               !generics.types.is_empty_in(TypeSpace) ||
+               !generics.regions.is_empty_in(TypeSpace) {
+                span_err!(self.ccx.tcx.sess, item.span, E0530,
+                          "`#[default_bound]` traits must have a default impl \
+                           (`impl Trait for ..`) and no parameters");
+            }
+        }
+
         self.with_item_fcx(item, |fcx, this| {
             let free_substs = &fcx.inh.infcx.parameter_environment.free_substs;
             let predicates = fcx.tcx().lookup_predicates(trait_def_id);
@@ -519,7 +541,7 @@ impl<'ccx, 'tcx> CheckTypeWellFormedVisitor<'ccx, 'tcx> {
 fn reject_shadowing_type_parameters<'tcx>(tcx: &TyCtxt<'tcx>,
                                           span: Span,
                                           generics: &ty::Generics<'tcx>) {
-    let impl_params = generics.types.get_slice(subst::TypeSpace).iter()
+    let impl_params = generics.types.get_slice(TypeSpace).iter()
         .map(|tp| tp.name).collect::<HashSet<_>>();
 
     for method_param in generics.types.get_slice(subst::FnSpace) {
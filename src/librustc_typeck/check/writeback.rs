@@ -44,6 +44,7 @@ pub fn resolve_type_vars_in_expr(fcx: &FnCtxt, e: &hir::Expr) {
     wbcx.visit_closures();
     wbcx.visit_liberated_fn_sigs();
     wbcx.visit_fru_field_types();
+    wbcx.visit_expected_types();
 }
 
 pub fn resolve_type_vars_in_fn(fcx: &FnCtxt,
@@ -66,6 +67,8 @@ pub fn resolve_type_vars_in_fn(fcx: &FnCtxt,
     wbcx.visit_closures();
     wbcx.visit_liberated_fn_sigs();
     wbcx.visit_fru_field_types();
+    wbcx.visit_expected_types();
+    wbcx.visit_anon_types(decl.output.span());
 }
 
 ///////////////////////////////////////////////////////////////////////////
@@ -181,6 +184,7 @@ impl<'cx, 'tcx, 'v> Visitor<'v> for WritebackCx<'cx, 'tcx> {
         }
 
         self.visit_node_id(ResolvingPattern(p.span), p.id);
+        self.visit_pat_adjustments(p.span, p.id);
 
         debug!("Type for pattern binding {} (id {}) resolved to {:?}",
                pat_to_string(p),
@@ -265,6 +269,11 @@ impl<'cx, 'tcx> WritebackCx<'cx, 'tcx> {
         // Resolve the type of the node with id `id`
         let n_ty = self.fcx.node_ty(id);
         let n_ty = self.resolve(&n_ty, reason);
+        if n_ty.references_error() {
+            // Error types `write_ty` saw are already recorded; this catches
+            // the ones hidden behind inference variables until now.
+            self.fcx.record_err_type(id, true);
+        }
         write_ty_to_tcx(self.tcx(), id, n_ty);
         debug!("Node {} has type {:?}", id, n_ty);
 
@@ -285,6 +294,16 @@ impl<'cx, 'tcx> WritebackCx<'cx, 'tcx> {
             Some(adjustment) => {
                 let resolved_adjustment = match adjustment {
                     adjustment::AdjustReifyFnPointer => {
+                        // A fn pointer call cannot pass the implicit caller
+                        // location, so such functions may only be called
+                        // directly.
+                        if let ty::TyFnDef(def_id, _, _) = self.fcx.node_ty(id).sty {
+                            if self.tcx().is_track_caller(def_id) {
+                                span_err!(self.tcx().sess, reason.span(self.tcx()), E0525,
+                                          "`#[track_caller]` functions cannot be \
+                                           converted to function pointers");
+                            }
+                        }
                         adjustment::AdjustReifyFnPointer
                     }
 
@@ -354,6 +373,19 @@ impl<'cx, 'tcx> WritebackCx<'cx, 'tcx> {
         }
     }
 
+    fn visit_pat_adjustments(&self, span: Span, id: ast::NodeId) {
+        let tables = self.fcx.inh.tables.borrow();
+
+        if let Some(adjustments) = tables.pat_adjustments.get(&id) {
+            let adjustments = self.resolve(adjustments, ResolvingPattern(span));
+            self.tcx().tables.borrow_mut().pat_adjustments.insert(id, adjustments);
+        }
+
+        if let Some(&mode) = tables.pat_binding_modes.get(&id) {
+            self.tcx().tables.borrow_mut().pat_binding_modes.insert(id, mode);
+        }
+    }
+
     fn visit_fru_field_types(&self) {
         for (&node_id, ftys) in self.fcx.inh.tables.borrow().fru_field_types.iter() {
             let ftys = self.resolve(ftys, ResolvingFieldTypes(node_id));
@@ -361,6 +393,51 @@ impl<'cx, 'tcx> WritebackCx<'cx, 'tcx> {
         }
     }
 
+    // Unlike the other tables, expected types are only of interest to tools,
+    // so whatever inference didn't get around to is left out quietly instead
+    // of being reported as an error.
+    fn visit_expected_types(&self) {
+        for (&node_id, ty) in self.fcx.inh.tables.borrow().expected_types.iter() {
+            let ty = self.fcx.infcx().resolve_type_vars_if_possible(ty);
+            let ty = self.tcx().erase_regions(&ty);
+            if !ty.needs_infer() {
+                self.tcx().tables.borrow_mut().expected_types.insert(node_id, ty);
+            }
+        }
+    }
+
+    fn visit_anon_types(&self, span: Span) {
+        if self.fcx.writeback_errors.get() {
+            return
+        }
+
+        for (&def_id, &concrete_ty) in self.fcx.inh.anon_types.borrow().iter() {
+            let concrete_ty = self.resolve(&concrete_ty, ResolvingAnonTy(span));
+            // The regions of the function body mean nothing outside of it,
+            // and trans does not look at regions anyway.
+            let concrete_ty = self.tcx().erase_regions(&concrete_ty);
+
+            // A function like `fn f() -> impl Trait { f() }` leaves the
+            // `impl Trait` standing for a type containing itself, which
+            // can never be revealed.
+            let is_recursive = concrete_ty.walk().any(|ty| match ty.sty {
+                ty::TyAnon(anon_def_id, _) => anon_def_id == def_id,
+                _ => false,
+            });
+            if is_recursive {
+                span_err!(self.tcx().sess, span, E0544,
+                          "the concrete type behind this `impl Trait` contains itself");
+                continue;
+            }
+
+            let generics = self.tcx().lookup_item_type(def_id).generics;
+            self.tcx().register_item_type(def_id, ty::TypeScheme {
+                generics: generics,
+                ty: concrete_ty
+            });
+        }
+    }
+
     fn resolve<T:TypeFoldable<'tcx>>(&self, t: &T, reason: ResolveReason) -> T {
         t.fold_with(&mut Resolver::new(self.fcx, reason))
     }
@@ -377,7 +454,8 @@ enum ResolveReason {
     ResolvingUpvar(ty::UpvarId),
     ResolvingClosure(DefId),
     ResolvingFnSig(ast::NodeId),
-    ResolvingFieldTypes(ast::NodeId)
+    ResolvingFieldTypes(ast::NodeId),
+    ResolvingAnonTy(Span),
 }
 
 impl ResolveReason {
@@ -386,6 +464,7 @@ impl ResolveReason {
             ResolvingExpr(s) => s,
             ResolvingLocal(s) => s,
             ResolvingPattern(s) => s,
+            ResolvingAnonTy(s) => s,
             ResolvingUpvar(upvar_id) => {
                 tcx.expr_span(upvar_id.closure_expr_id)
             }
@@ -472,6 +551,11 @@ impl<'cx, 'tcx> Resolver<'cx, 'tcx> {
                               "cannot determine a type for this closure")
                 }
 
+                ResolvingAnonTy(span) => {
+                    span_err!(self.tcx.sess, span, E0535,
+                              "cannot determine the concrete type behind this `impl Trait`")
+                }
+
                 ResolvingFnSig(id) | ResolvingFieldTypes(id) => {
                     // any failures here should also fail when
                     // resolving the patterns, closure types, or
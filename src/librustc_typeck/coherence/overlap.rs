@@ -27,7 +27,8 @@ use util::nodemap::{DefIdMap, DefIdSet};
 pub fn check(tcx: &TyCtxt) {
     let mut overlap = OverlapChecker { tcx: tcx,
                                        traits_checked: DefIdSet(),
-                                       default_impls: DefIdMap() };
+                                       default_impls: DefIdMap(),
+                                       specializing_impls: DefIdSet() };
 
     // this secondary walk specifically checks for some other cases,
     // like defaulted traits, for which additional overlap rules exist
@@ -47,6 +48,20 @@ struct OverlapChecker<'cx, 'tcx:'cx> {
 
     // maps from a trait def-id to an impl id
     default_impls: DefIdMap<ast::NodeId>,
+
+    // impls of the trait being checked that overlap another impl because
+    // one of the two specializes the other
+    specializing_impls: DefIdSet,
+}
+
+/// Whether two associated items are in the same namespace, so that one can
+/// override the other.
+fn same_namespace(a: &ty::ImplOrTraitItem, b: &ty::ImplOrTraitItem) -> bool {
+    match (a, b) {
+        (&ty::TypeTraitItem(..), &ty::TypeTraitItem(..)) => true,
+        (&ty::TypeTraitItem(..), _) | (_, &ty::TypeTraitItem(..)) => false,
+        _ => true,
+    }
 }
 
 impl<'cx, 'tcx> OverlapChecker<'cx, 'tcx> {
@@ -92,6 +107,71 @@ impl<'cx, 'tcx> OverlapChecker<'cx, 'tcx> {
                 }
             }
         }
+
+        if !self.specializing_impls.is_empty() {
+            let all_impls: Vec<DefId> =
+                blanket_impls.iter()
+                             .chain(nonblanket_impls.values().flat_map(|v| v.iter()))
+                             .cloned()
+                             .collect();
+            self.record_specialization_parents(&all_impls);
+        }
+    }
+
+    /// Records, for each local impl that was allowed to overlap another
+    /// because one specializes the other, the impl it directly specializes,
+    /// and checks that it only overrides `default` items of that impl.
+    fn record_specialization_parents(&mut self, trait_impls: &[DefId]) {
+        let mut specializing_impls: Vec<DefId> = self.specializing_impls.drain().collect();
+        specializing_impls.sort();
+
+        for &impl_def_id in &specializing_impls {
+            if !impl_def_id.is_local() {
+                continue;
+            }
+            if let Some(parent) = traits::find_specialization_parent(self.tcx,
+                                                                     impl_def_id,
+                                                                     trait_impls) {
+                debug!("record_specialization_parents: {:?} specializes {:?}",
+                       impl_def_id, parent);
+                self.tcx.impl_parents.borrow_mut().insert(impl_def_id, parent);
+            }
+        }
+
+        for &impl_def_id in &specializing_impls {
+            if let Some(parent) = self.tcx.impl_parent(impl_def_id) {
+                self.check_overridden_items_are_default(impl_def_id, parent);
+            }
+        }
+    }
+
+    fn check_overridden_items_are_default(&self, impl_def_id: DefId, parent: DefId) {
+        let impl_items = self.tcx.impl_items.borrow()[&impl_def_id].clone();
+        for item_id in impl_items {
+            let item = self.tcx.impl_or_trait_item(item_id.def_id());
+            let overridden = traits::find_impl_item(self.tcx, parent, |parent_item| {
+                parent_item.name() == item.name() && same_namespace(parent_item, &item)
+            });
+            if let Some((parent_impl, parent_item)) = overridden {
+                if !parent_item.defaultness().is_default() {
+                    let mut err = struct_span_err!(self.tcx.sess,
+                                                   self.span_of_def_id(item_id.def_id()),
+                                                   E0540,
+                                                   "`{}` specializes an item from a parent \
+                                                    `impl`, but that item is not marked \
+                                                    `default`",
+                                                   item.name());
+                    if parent_impl.is_local() {
+                        span_note!(&mut err, self.span_of_def_id(parent_item.def_id()),
+                                   "parent `impl` is here");
+                    } else {
+                        let cname = self.tcx.sess.cstore.crate_name(parent_impl.krate);
+                        err.note(&format!("parent implementation is in crate `{}`", cname));
+                    }
+                    err.emit();
+                }
+            }
+        }
     }
 
     // We need to coherently pick which impl will be displayed
@@ -116,7 +196,7 @@ impl<'cx, 'tcx> OverlapChecker<'cx, 'tcx> {
         }
     }
 
-    fn check_if_impls_overlap(&self,
+    fn check_if_impls_overlap(&mut self,
                               impl1_def_id: DefId,
                               impl2_def_id: DefId)
     {
@@ -129,6 +209,17 @@ impl<'cx, 'tcx> OverlapChecker<'cx, 'tcx> {
 
             let infcx = infer::new_infer_ctxt(self.tcx, &self.tcx.tables, None);
             if let Some(header) = traits::overlapping_impls(&infcx, impl1_def_id, impl2_def_id) {
+                // Overlap is fine when one impl is strictly more specific
+                // than the other; selection prefers it wherever both apply.
+                if self.tcx.sess.features.borrow().specialization &&
+                   traits::specializes(self.tcx, impl1_def_id, impl2_def_id) !=
+                   traits::specializes(self.tcx, impl2_def_id, impl1_def_id) {
+                    self.specializing_impls.insert(impl1_def_id);
+                    self.specializing_impls.insert(impl2_def_id);
+                    return;
+                }
+
+                self.tcx.overlapping_impls.borrow_mut().push((impl1_def_id, impl2_def_id));
                 self.report_overlap_error(impl1_def_id, impl2_def_id, header.trait_ref.unwrap());
             }
         }
@@ -160,7 +251,8 @@ impl<'cx, 'tcx> OverlapChecker<'cx, 'tcx> {
                        "conflicting implementation is here:");
         } else {
             let cname = self.tcx.sess.cstore.crate_name(impl2.krate);
-            err.note(&format!("conflicting implementation in crate `{}`", cname));
+            err.note(&format!("conflicting implementation `{}` in crate `{}`",
+                              self.tcx.def_path_str(impl2), cname));
         }
         err.emit();
     }
@@ -231,7 +323,7 @@ impl<'cx, 'tcx,'v> intravisit::Visitor<'v> for OverlapChecker<'cx, 'tcx> {
                 self.check_for_overlapping_impls_of_trait(trait_def_id);
             }
 
-            hir::ItemEnum(..) | hir::ItemStruct(..) => {
+            hir::ItemEnum(..) | hir::ItemStruct(..) | hir::ItemUnion(..) => {
                 let type_def_id = self.tcx.map.local_def_id(item.id);
                 self.check_for_overlapping_inherent_impls(type_def_id);
             }
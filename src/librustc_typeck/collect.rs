@@ -29,10 +29,27 @@ parameters themselves are represented as `ty_param()` instances.
 The phasing of type conversion is somewhat complicated. There is no
 clear set of phases we can enforce (e.g., converting traits first,
 then types, or something like that) because the user can introduce
-arbitrary interdependencies. So instead we generally convert things
-lazilly and on demand, and include logic that checks for cycles.
-Demand is driven by calls to `AstConv::get_item_type_scheme` or
-`AstConv::lookup_trait_def`.
+arbitrary interdependencies. So while converting one item, whatever
+it needs of other items is computed right then, as a *query*. A query
+is keyed by what it computes and for which item (see `Query`): the
+type scheme of an item, the definition of a trait, the predicates or
+supertraits of an item, or the bounds of a type parameter. Each is
+computed at most once and its result is stored in the matching `tcx`
+table (`tcache`, `trait_defs`, `predicates` and `super_predicates`).
+Within collect, demand is driven by calls to
+`AstConv::get_item_type_scheme` or `AstConv::lookup_trait_def`, and by
+`predicates_of`.
+
+This is only on demand within the collect phase. Passes after it read
+the `tcx` tables directly and don't go through the queries, which is
+why the walk described below has to convert every item up front.
+
+All queries go through `CrateCtxt::query`, which keeps the stack of
+queries being computed. A query that demands itself, directly or not,
+is a cycle in the user's source: it is reported, and the demand fails
+with `ErrorReported`. Each caller decides how to recover from that;
+`ast_ty_to_ty` uses the error type, for example, and `predicates_of`
+gives the item no predicates.
 
 Currently, we "convert" types and traits in two phases (note that
 conversion only affects the types of items / enum variants / methods;
@@ -43,9 +60,10 @@ it does not e.g. compute the types of individual expressions):
 
 Conversion itself is done by simply walking each of the items in turn
 and invoking an appropriate function (e.g., `trait_def_of_item` or
-`convert_item`). However, it is possible that while converting an
-item, we may need to compute the *type scheme* or *trait definition*
-for other items.
+`convert_item`). The walk only ensures that every item ends up
+converted: whatever an item needs of other items, such as their *type
+scheme* or *trait definition*, is demanded from the queries, and an
+item already converted that way is just read back from the cache.
 
 There are some shortcomings in this design:
 
@@ -59,16 +77,17 @@ There are some shortcomings in this design:
 */
 
 use astconv::{self, AstConv, ty_of_arg, ast_ty_to_ty, ast_region_to_region};
+use astconv::{compute_bounds, SizedByDefault};
 use lint;
-use middle::def::Def;
+use middle::def::{self, Def};
 use middle::def_id::DefId;
 use constrained_type_params as ctp;
 use coherence;
-use middle::lang_items::SizedTraitLangItem;
 use middle::resolve_lifetime;
 use middle::const_eval::{self, ConstVal};
 use middle::const_eval::EvalHint::UncheckedExprHint;
-use middle::subst::{Substs, FnSpace, ParamSpace, SelfSpace, TypeSpace, VecPerParamSpace};
+use middle::subst::{ConstArg, Substs, FnSpace, ParamSpace, SelfSpace, TypeSpace, VecPerParamSpace};
+use middle::traits;
 use middle::ty::{ToPredicate, ImplContainer, ImplOrTraitItemContainer, TraitContainer};
 use middle::ty::{self, ToPolyTraitRef, Ty, TyCtxt, TypeScheme};
 use middle::ty::{VariantKind};
@@ -82,36 +101,76 @@ use util::nodemap::{FnvHashMap, FnvHashSet};
 use write_ty_to_tcx;
 
 use std::cell::RefCell;
+use std::cmp;
 use std::collections::HashSet;
 use std::rc::Rc;
 
 use syntax::abi;
 use syntax::ast;
 use syntax::attr;
-use syntax::codemap::Span;
+use syntax::codemap::{BytePos, Span, NO_EXPANSION};
+use syntax::errors::DiagnosticBuilder;
 use syntax::parse::token::special_idents;
 use syntax::ptr::P;
 use rustc_front::hir::{self, PatKind};
+use rustc_front::util::empty_generics;
 use rustc_front::intravisit;
 use rustc_front::print::pprust;
 
 ///////////////////////////////////////////////////////////////////////////
 // Main entry point
 
+/// Converts every item in the crate, whether or not anything demands it,
+/// since later passes read the results straight from the `tcx` tables.
 pub fn collect_item_types(tcx: &TyCtxt) {
+    collect_default_bound_traits(tcx);
+
     let ccx = &CrateCtxt { tcx: tcx, stack: RefCell::new(Vec::new()) };
     let mut visitor = CollectItemTypesVisitor{ ccx: ccx };
     ccx.tcx.visit_all_items_in_krate(DepNode::CollectItem, &mut visitor);
 }
 
+/// Records the traits marked `#[default_bound]`, here and in the crates we
+/// use, before any bounds are computed, since every type parameter gets
+/// them implicitly. Local traits with parameters are left out, as there is
+/// nothing to instantiate them with, and so are those without a default
+/// impl, which no type would satisfy; wfcheck reports both.
+fn collect_default_bound_traits(tcx: &TyCtxt) {
+    let mut default_bound_traits = tcx.default_bound_traits.borrow_mut();
+    for cnum in tcx.sess.cstore.crates() {
+        default_bound_traits.extend(tcx.sess.cstore.default_bound_traits(cnum));
+    }
+
+    // The default impls haven't been collected yet, so look for them here.
+    let krate = tcx.map.krate();
+    let default_impl_traits: Vec<_> = krate.items.values().filter_map(|item| {
+        match item.node {
+            hir::ItemDefaultImpl(_, ref trait_ref) => Some(tcx.trait_ref_to_def_id(trait_ref)),
+            _ => None,
+        }
+    }).collect();
+
+    for item in krate.items.values() {
+        if let hir::ItemTrait(_, ref generics, _, _) = item.node {
+            let def_id = tcx.map.local_def_id(item.id);
+            if attr::contains_name(&item.attrs, "default_bound") &&
+               !generics.is_parameterized() &&
+               default_impl_traits.contains(&def_id) {
+                default_bound_traits.push(def_id);
+            }
+        }
+    }
+}
+
 ///////////////////////////////////////////////////////////////////////////
 
 struct CrateCtxt<'a,'tcx:'a> {
     tcx: &'a TyCtxt<'tcx>,
 
-    // This stack is used to identify cycles in the user's source.
-    // Note that these cycles can cross multiple items.
-    stack: RefCell<Vec<AstConvRequest>>,
+    // The queries being computed, innermost last, each with the span that
+    // demanded it. This stack is used to identify cycles in the user's
+    // source. Note that these cycles can cross multiple items.
+    stack: RefCell<Vec<(Query, Span)>>,
 }
 
 /// Context specific to some particular item. This is what implements
@@ -130,12 +189,14 @@ struct ItemCtxt<'a,'tcx:'a> {
     param_bounds: &'a (GetTypeParameterBounds<'tcx>+'a),
 }
 
-#[derive(Copy, Clone, PartialEq, Eq)]
-enum AstConvRequest {
-    GetItemTypeScheme(DefId),
-    GetTraitDef(DefId),
-    EnsureSuperPredicates(DefId),
-    GetTypeParameterBounds(ast::NodeId),
+/// Something converting one item demands of another; see `CrateCtxt::query`.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum Query {
+    TypeSchemeOf(DefId),
+    TraitDefOf(DefId),
+    PredicatesOf(DefId),
+    SuperPredicatesOf(DefId),
+    TypeParameterBounds(ast::NodeId),
 }
 
 ///////////////////////////////////////////////////////////////////////////
@@ -158,16 +219,18 @@ impl<'a,'tcx> CrateCtxt<'a,'tcx> {
         ItemCtxt { ccx: self, param_bounds: param_bounds }
     }
 
-    fn cycle_check<F,R>(&self,
-                        span: Span,
-                        request: AstConvRequest,
-                        code: F)
-                        -> Result<R,ErrorReported>
+    /// Runs `compute` to answer `query`, unless that would be a cycle, which
+    /// is reported instead.
+    fn query<F,R>(&self,
+                  span: Span,
+                  query: Query,
+                  compute: F)
+                  -> Result<R,ErrorReported>
         where F: FnOnce() -> Result<R,ErrorReported>
     {
         {
             let mut stack = self.stack.borrow_mut();
-            match stack.iter().enumerate().rev().find(|&(_, r)| *r == request) {
+            match stack.iter().enumerate().rev().find(|&(_, &(q, _))| q == query) {
                 None => { }
                 Some((i, _)) => {
                     let cycle = &stack[i..];
@@ -175,93 +238,100 @@ impl<'a,'tcx> CrateCtxt<'a,'tcx> {
                     return Err(ErrorReported);
                 }
             }
-            stack.push(request);
+            stack.push((query, span));
         }
 
-        let result = code();
+        let result = compute();
 
         self.stack.borrow_mut().pop();
         result
     }
 
+    fn describe_query(&self, query: Query) -> String {
+        let tcx = self.tcx;
+        match query {
+            Query::TypeSchemeOf(def_id) |
+            Query::TraitDefOf(def_id) => {
+                format!("processing `{}`", tcx.def_path_str(def_id))
+            }
+            Query::PredicatesOf(def_id) => {
+                format!("computing the predicates of `{}`", tcx.def_path_str(def_id))
+            }
+            Query::SuperPredicatesOf(def_id) => {
+                format!("computing the supertraits of `{}`", tcx.def_path_str(def_id))
+            }
+            Query::TypeParameterBounds(id) => {
+                let def = tcx.type_parameter_def(id);
+                format!("computing the bounds for type parameter `{}`", def.name)
+            }
+        }
+    }
+
+    /// The item or type parameter `query` is about, and where it is defined
+    /// if it is in this crate.
+    fn query_subject(&self, query: Query) -> (String, Option<Span>) {
+        let tcx = self.tcx;
+        match query {
+            Query::TypeSchemeOf(def_id) |
+            Query::TraitDefOf(def_id) |
+            Query::PredicatesOf(def_id) |
+            Query::SuperPredicatesOf(def_id) => {
+                (tcx.def_path_str(def_id), tcx.map.span_if_local(def_id))
+            }
+            Query::TypeParameterBounds(id) => {
+                (tcx.type_parameter_def(id).name.to_string(), tcx.map.opt_span(id))
+            }
+        }
+    }
+
+    /// Reports that demanding `cycle[0]` at `span` closes a cycle. Each step
+    /// of the cycle is noted at the span that demanded it, except the first,
+    /// which is noted at the item it is about: the span that demanded it
+    /// may lie outside of the cycle.
     fn report_cycle(&self,
                     span: Span,
-                    cycle: &[AstConvRequest])
+                    cycle: &[(Query, Span)])
     {
         assert!(!cycle.is_empty());
         let tcx = self.tcx;
+        let (first, first_span) = cycle[0];
 
         let mut err = struct_span_err!(tcx.sess, span, E0391,
             "unsupported cyclic reference between types/traits detected");
 
-        match cycle[0] {
-            AstConvRequest::GetItemTypeScheme(def_id) |
-            AstConvRequest::GetTraitDef(def_id) => {
-                err.note(
-                    &format!("the cycle begins when processing `{}`...",
-                             tcx.item_path_str(def_id)));
-            }
-            AstConvRequest::EnsureSuperPredicates(def_id) => {
-                err.note(
-                    &format!("the cycle begins when computing the supertraits of `{}`...",
-                             tcx.item_path_str(def_id)));
-            }
-            AstConvRequest::GetTypeParameterBounds(id) => {
-                let def = tcx.type_parameter_def(id);
-                err.note(
-                    &format!("the cycle begins when computing the bounds \
-                              for type parameter `{}`...",
-                             def.name));
-            }
-        }
+        let msg = format!("the cycle begins when {}...", self.describe_query(first));
+        err.span_note(self.query_subject(first).1.unwrap_or(first_span), &msg);
 
-        for request in &cycle[1..] {
-            match *request {
-                AstConvRequest::GetItemTypeScheme(def_id) |
-                AstConvRequest::GetTraitDef(def_id) => {
-                    err.note(
-                        &format!("...which then requires processing `{}`...",
-                                 tcx.item_path_str(def_id)));
-                }
-                AstConvRequest::EnsureSuperPredicates(def_id) => {
-                    err.note(
-                        &format!("...which then requires computing the supertraits of `{}`...",
-                                 tcx.item_path_str(def_id)));
-                }
-                AstConvRequest::GetTypeParameterBounds(id) => {
-                    let def = tcx.type_parameter_def(id);
-                    err.note(
-                        &format!("...which then requires computing the bounds \
-                                  for type parameter `{}`...",
-                                 def.name));
-                }
-            }
+        for &(query, query_span) in &cycle[1..] {
+            err.span_note(query_span,
+                          &format!("...which then requires {}...", self.describe_query(query)));
         }
 
-        match cycle[0] {
-            AstConvRequest::GetItemTypeScheme(def_id) |
-            AstConvRequest::GetTraitDef(def_id) => {
-                err.note(
-                    &format!("...which then again requires processing `{}`, completing the cycle.",
-                             tcx.item_path_str(def_id)));
-            }
-            AstConvRequest::EnsureSuperPredicates(def_id) => {
-                err.note(
-                    &format!("...which then again requires computing the supertraits of `{}`, \
-                              completing the cycle.",
-                             tcx.item_path_str(def_id)));
-            }
-            AstConvRequest::GetTypeParameterBounds(id) => {
-                let def = tcx.type_parameter_def(id);
-                err.note(
-                    &format!("...which then again requires computing the bounds \
-                              for type parameter `{}`, completing the cycle.",
-                             def.name));
-            }
-        }
+        err.note(&format!("...which then again requires {}, completing the cycle.",
+                          self.describe_query(first)));
+
+        // Every step is needed for the cycle, so removing any of them breaks
+        // it; the last one is the one the user is looking at.
+        let (last, _) = cycle[cycle.len() - 1];
+        err.span_help(span,
+                      &format!("removing this reference to `{}` from {} would break the \
+                                cycle, as would removing any of the references noted above",
+                               self.query_subject(first).0,
+                               self.describe_subject(last)));
         err.emit();
     }
 
+    /// `query`'s subject, as the thing whose source contains the references
+    /// it depends on.
+    fn describe_subject(&self, query: Query) -> String {
+        let (name, _) = self.query_subject(query);
+        match query {
+            Query::TypeParameterBounds(_) => format!("the bounds of `{}`", name),
+            Query::SuperPredicatesOf(_) => format!("the supertraits of `{}`", name),
+            _ => format!("`{}`", name),
+        }
+    }
+
     /// Loads the trait def for a given trait, returning ErrorReported if a cycle arises.
     fn get_trait_def(&self, trait_id: DefId)
                      -> &'tcx ty::TraitDef<'tcx>
@@ -286,11 +356,11 @@ impl<'a,'tcx> CrateCtxt<'a,'tcx> {
     fn ensure_super_predicates(&self, span: Span, trait_def_id: DefId)
                                -> Result<(), ErrorReported>
     {
-        self.cycle_check(span, AstConvRequest::EnsureSuperPredicates(trait_def_id), || {
-            let def_ids = ensure_super_predicates_step(self, trait_def_id);
+        self.query(span, Query::SuperPredicatesOf(trait_def_id), || {
+            let supertraits = ensure_super_predicates_step(self, trait_def_id);
 
-            for def_id in def_ids {
-                try!(self.ensure_super_predicates(span, def_id));
+            for (def_id, bound_span) in supertraits {
+                try!(self.ensure_super_predicates(bound_span, def_id));
             }
 
             Ok(())
@@ -310,7 +380,10 @@ impl<'a, 'tcx> AstConv<'tcx> for ItemCtxt<'a, 'tcx> {
     fn get_item_type_scheme(&self, span: Span, id: DefId)
                             -> Result<ty::TypeScheme<'tcx>, ErrorReported>
     {
-        self.ccx.cycle_check(span, AstConvRequest::GetItemTypeScheme(id), || {
+        if let Some(scheme) = self.tcx().tcache.borrow().get(&id) {
+            return Ok(scheme.clone());
+        }
+        self.ccx.query(span, Query::TypeSchemeOf(id), || {
             Ok(type_scheme_of_def_id(self.ccx, id))
         })
     }
@@ -318,7 +391,10 @@ impl<'a, 'tcx> AstConv<'tcx> for ItemCtxt<'a, 'tcx> {
     fn get_trait_def(&self, span: Span, id: DefId)
                      -> Result<&'tcx ty::TraitDef<'tcx>, ErrorReported>
     {
-        self.ccx.cycle_check(span, AstConvRequest::GetTraitDef(id), || {
+        if let Some(&trait_def) = self.tcx().trait_defs.borrow().get(&id) {
+            return Ok(trait_def);
+        }
+        self.ccx.query(span, Query::TraitDefOf(id), || {
             Ok(self.ccx.get_trait_def(id))
         })
     }
@@ -340,7 +416,7 @@ impl<'a, 'tcx> AstConv<'tcx> for ItemCtxt<'a, 'tcx> {
                                  node_id: ast::NodeId)
                                  -> Result<Vec<ty::PolyTraitRef<'tcx>>, ErrorReported>
     {
-        self.ccx.cycle_check(span, AstConvRequest::GetTypeParameterBounds(node_id), || {
+        self.ccx.query(span, Query::TypeParameterBounds(node_id), || {
             let v = self.param_bounds.get_type_parameter_bounds(self, span, node_id)
                                      .into_iter()
                                      .filter_map(|p| p.to_opt_poly_trait_ref())
@@ -530,18 +606,29 @@ fn convert_method<'a, 'tcx>(ccx: &CrateCtxt<'a, 'tcx>,
                             name: ast::Name,
                             id: ast::NodeId,
                             vis: hir::Visibility,
+                            defaultness: hir::Defaultness,
                             sig: &hir::MethodSig,
                             untransformed_rcvr_ty: Ty<'tcx>,
                             rcvr_ty_generics: &ty::Generics<'tcx>,
                             rcvr_ty_predicates: &ty::GenericPredicates<'tcx>) {
-    let ty_generics = ty_generics_for_fn(ccx, &sig.generics, rcvr_ty_generics);
+    let mut ty_generics = ty_generics_for_fn(ccx, &sig.generics, rcvr_ty_generics);
+    reject_const_params(ccx, &sig.generics, &mut ty_generics, FnSpace, "methods");
 
     let ty_generic_predicates =
         ty_generic_predicates_for_fn(ccx, &sig.generics, rcvr_ty_predicates);
 
+    // `impl Trait` is only allowed in the return type of inherent methods.
+    let anon_scope = match container {
+        ImplContainer(impl_def_id) if ccx.tcx.impl_trait_ref(impl_def_id).is_none() => {
+            Some(AnonTypeScope::new(&ty_generics))
+        }
+        _ => None
+    };
+
     let (fty, explicit_self_category) =
         astconv::ty_of_method(&ccx.icx(&(rcvr_ty_predicates, &sig.generics)),
-                              sig, untransformed_rcvr_ty);
+                              id, sig, untransformed_rcvr_ty, anon_scope);
+    convert_anon_types(ccx, &sig.decl, &ty_generics);
 
     let def_id = ccx.tcx.map.local_def_id(id);
     let substs = ccx.tcx.mk_substs(mk_item_substs(ccx, &ty_generics));
@@ -552,6 +639,7 @@ fn convert_method<'a, 'tcx>(ccx: &CrateCtxt<'a, 'tcx>,
                                     fty,
                                     explicit_self_category,
                                     vis,
+                                    defaultness,
                                     def_id,
                                     container);
 
@@ -598,17 +686,29 @@ fn convert_associated_const<'a, 'tcx>(ccx: &CrateCtxt<'a, 'tcx>,
                                       name: ast::Name,
                                       id: ast::NodeId,
                                       vis: hir::Visibility,
+                                      defaultness: hir::Defaultness,
                                       ty: ty::Ty<'tcx>,
-                                      has_value: bool)
+                                      has_value: bool,
+                                      container_generics: &ty::Generics<'tcx>,
+                                      container_predicates: &ty::GenericPredicates<'tcx>)
 {
+    // Like methods, constants share the generics and predicates of their
+    // container. In a trait this includes `Self: Trait`, which is what ties
+    // the trait's type parameters down when a default body uses `Self::CONST`.
+    ccx.tcx.register_item_type(ccx.tcx.map.local_def_id(id),
+                               TypeScheme {
+                                   generics: container_generics.clone(),
+                                   ty: ty,
+                               });
     ccx.tcx.predicates.borrow_mut().insert(ccx.tcx.map.local_def_id(id),
-                                           ty::GenericPredicates::empty());
+                                           container_predicates.clone());
 
     write_ty_to_tcx(ccx.tcx, id, ty);
 
     let associated_const = Rc::new(ty::AssociatedConst {
         name: name,
         vis: vis,
+        defaultness: defaultness,
         def_id: ccx.tcx.map.local_def_id(id),
         container: container,
         ty: ty,
@@ -623,11 +723,17 @@ fn convert_associated_type<'a, 'tcx>(ccx: &CrateCtxt<'a, 'tcx>,
                                      name: ast::Name,
                                      id: ast::NodeId,
                                      vis: hir::Visibility,
-                                     ty: Option<Ty<'tcx>>)
+                                     defaultness: hir::Defaultness,
+                                     ty: Option<Ty<'tcx>>,
+                                     generics: ty::Generics<'tcx>,
+                                     predicates: ty::GenericPredicates<'tcx>)
 {
     let associated_type = Rc::new(ty::AssociatedType {
         name: name,
+        generics: generics,
+        predicates: predicates,
         vis: vis,
+        defaultness: defaultness,
         ty: ty,
         def_id: ccx.tcx.map.local_def_id(id),
         container: container
@@ -668,12 +774,18 @@ fn ensure_no_ty_param_bounds(ccx: &CrateCtxt,
 fn convert_item(ccx: &CrateCtxt, it: &hir::Item) {
     let tcx = ccx.tcx;
     debug!("convert: item {} with id {}", it.name, it.id);
+    let fn_abi = match it.node {
+        hir::ItemFn(_, _, _, abi, _, _) => Some(abi),
+        _ => None
+    };
+    convert_track_caller(ccx, it.id, it.span, &it.attrs, fn_abi);
     match it.node {
         // These don't define types.
         hir::ItemExternCrate(_) | hir::ItemUse(_) | hir::ItemMod(_) => {
         }
         hir::ItemForeignMod(ref foreign_mod) => {
             for item in &foreign_mod.items {
+                convert_track_caller(ccx, item.id, item.span, &item.attrs, None);
                 convert_foreign_item(ccx, item);
             }
         }
@@ -692,6 +804,7 @@ fn convert_item(ccx: &CrateCtxt, it: &hir::Item) {
                                                     &ExplicitRscope,
                                                     ast_trait_ref,
                                                     None);
+            check_impl_of_trait_alias(ccx, it.span, trait_ref.def_id);
 
             tcx.record_trait_has_default_impl(trait_ref.def_id);
 
@@ -706,38 +819,53 @@ fn convert_item(ccx: &CrateCtxt, it: &hir::Item) {
             // Create generics from the generics specified in the impl head.
             debug!("convert: ast_generics={:?}", generics);
             let def_id = ccx.tcx.map.local_def_id(it.id);
-            let ty_generics = ty_generics_for_type_or_impl(ccx, generics);
+            let mut ty_generics = ty_generics_for_type_or_impl(ccx, generics);
+            reject_const_params(ccx, generics, &mut ty_generics, TypeSpace, "impls");
             let mut ty_predicates = ty_generic_predicates_for_type_or_impl(ccx, generics);
 
             debug!("convert: impl_bounds={:?}", ty_predicates);
 
-            let selfty = ccx.icx(&ty_predicates).to_ty(&ExplicitRscope, &selfty);
+            // Lifetimes elided in the impl header become anonymous
+            // lifetime parameters of the impl.
+            let header_rscope = ImplHeaderRscope::new(generics.lifetimes.len() as u32);
+            let selfty = ccx.icx(&ty_predicates).to_ty(&header_rscope, &selfty);
             write_ty_to_tcx(tcx, it.id, selfty);
 
-            tcx.register_item_type(def_id,
-                                   TypeScheme { generics: ty_generics.clone(),
-                                                ty: selfty });
             let trait_ref = opt_trait_ref.as_ref().map(|ast_trait_ref| {
                 astconv::instantiate_mono_trait_ref(&ccx.icx(&ty_predicates),
-                                                    &ExplicitRscope,
+                                                    &header_rscope,
                                                     ast_trait_ref,
                                                     Some(selfty))
             });
+            if let Some(ref trait_ref) = trait_ref {
+                check_impl_of_trait_alias(ccx, it.span, trait_ref.def_id);
+            }
+
+            for region in header_rscope.anon_region_params() {
+                ty_generics.regions.push(TypeSpace, ty::RegionParameterDef {
+                    name: region.name,
+                    def_id: def_id,
+                    space: region.space,
+                    index: region.index,
+                    bounds: vec![],
+                });
+            }
+            debug!("convert: impl generics with anonymous lifetimes={:?}", ty_generics);
+
+            tcx.register_item_type(def_id,
+                                   TypeScheme { generics: ty_generics.clone(),
+                                                ty: selfty });
             tcx.impl_trait_refs.borrow_mut().insert(def_id, trait_ref);
 
-            enforce_impl_params_are_constrained(tcx, generics, &mut ty_predicates, def_id);
+            enforce_impl_params_are_constrained(tcx, it, generics, &mut ty_predicates, def_id);
             tcx.predicates.borrow_mut().insert(def_id, ty_predicates.clone());
 
 
-            // If there is a trait reference, treat the methods as always public.
-            // This is to work around some incorrect behavior in privacy checking:
-            // when the method belongs to a trait, it should acquire the privacy
-            // from the trait, not the impl. Forcing the visibility to be public
-            // makes things sorta work.
-            let parent_visibility = if opt_trait_ref.is_some() {
-                hir::Public
-            } else {
-                it.vis
+            // The items of a trait impl are exactly as visible as the trait
+            // they implement; those of an inherent impl inherit from the impl.
+            let parent_visibility = match trait_ref {
+                Some(ref trait_ref) => trait_visibility(tcx, trait_ref.def_id),
+                None => it.vis,
             };
 
             // Convert all the associated consts.
@@ -746,6 +874,15 @@ fn convert_item(ccx: &CrateCtxt, it: &hir::Item) {
             let mut seen_value_items = FnvHashSet();
 
             for impl_item in impl_items {
+                let method_abi = match impl_item.node {
+                    hir::ImplItemKind::Method(ref sig, _) if opt_trait_ref.is_none() => {
+                        Some(sig.abi)
+                    }
+                    _ => None
+                };
+                convert_track_caller(ccx, impl_item.id, impl_item.span,
+                                     &impl_item.attrs, method_abi);
+
                 let seen_items = match impl_item.node {
                     hir::ImplItemKind::Type(_) => &mut seen_type_items,
                     _                    => &mut seen_value_items,
@@ -755,17 +892,15 @@ fn convert_item(ccx: &CrateCtxt, it: &hir::Item) {
                 }
 
                 if let hir::ImplItemKind::Const(ref ty, _) = impl_item.node {
+                    // Elided lifetimes in the type of a constant are 'static.
                     let ty = ccx.icx(&ty_predicates)
-                                .to_ty(&ExplicitRscope, &ty);
-                    tcx.register_item_type(ccx.tcx.map.local_def_id(impl_item.id),
-                                           TypeScheme {
-                                               generics: ty_generics.clone(),
-                                               ty: ty,
-                                           });
+                                .to_ty(&ElidableRscope::new(ty::ReStatic), &ty);
                     convert_associated_const(ccx, ImplContainer(def_id),
                                              impl_item.name, impl_item.id,
                                              impl_item.vis.inherit_from(parent_visibility),
-                                             ty, true /* has_value */);
+                                             impl_item.defaultness,
+                                             ty, true /* has_value */,
+                                             &ty_generics, &ty_predicates);
                 }
             }
 
@@ -780,8 +915,11 @@ fn convert_item(ccx: &CrateCtxt, it: &hir::Item) {
                     let typ = ccx.icx(&ty_predicates).to_ty(&ExplicitRscope, ty);
 
                     convert_associated_type(ccx, ImplContainer(def_id),
-                                            impl_item.name, impl_item.id, impl_item.vis,
-                                            Some(typ));
+                                            impl_item.name, impl_item.id,
+                                            impl_item.vis.inherit_from(parent_visibility),
+                                            impl_item.defaultness,
+                                            Some(typ), ty_generics.clone(),
+                                            ty_predicates.clone());
                 }
             }
 
@@ -795,58 +933,99 @@ fn convert_item(ccx: &CrateCtxt, it: &hir::Item) {
 
                     convert_method(ccx, ImplContainer(def_id),
                                    impl_item.name, impl_item.id, method_vis,
-                                   sig, selfty, &ty_generics, &ty_predicates);
+                                   impl_item.defaultness, sig, selfty,
+                                   &ty_generics, &ty_predicates);
+
+                    let mut lifetimes = LifetimeUseCollector::new();
+                    intravisit::walk_impl_item(&mut lifetimes, impl_item);
+                    warn_unused_lifetimes(tcx, impl_item.id, &sig.generics, &lifetimes.used);
                 }
             }
 
             enforce_impl_lifetimes_are_constrained(tcx, generics, def_id, impl_items);
+
+            let mut lifetimes = LifetimeUseCollector::new();
+            intravisit::walk_item(&mut lifetimes, it);
+            warn_unused_lifetimes(tcx, it.id, generics, &lifetimes.used);
         },
         hir::ItemTrait(_, _, _, ref trait_items) => {
             let trait_def = trait_def_of_item(ccx, it);
             let def_id = trait_def.trait_ref.def_id;
-            let _: Result<(), ErrorReported> = // any error is already reported, can ignore
-                ccx.ensure_super_predicates(it.span, def_id);
-            convert_trait_predicates(ccx, it);
-            let trait_predicates = tcx.lookup_predicates(def_id);
+            // any error is already reported, can ignore
+            let super_predicates_ok = ccx.ensure_super_predicates(it.span, def_id).is_ok();
+            let trait_predicates = predicates_of(ccx, it);
 
             debug!("convert: trait_bounds={:?}", trait_predicates);
 
+            if super_predicates_ok {
+                check_shadowed_associated_types(ccx, trait_def, trait_items);
+            }
+
             // FIXME: is the ordering here important? I think it is.
             let container = TraitContainer(def_id);
 
             // Convert all the associated constants.
             for trait_item in trait_items {
+                convert_track_caller(ccx, trait_item.id, trait_item.span,
+                                     &trait_item.attrs, None);
+
                 if let hir::ConstTraitItem(ref ty, ref default) = trait_item.node {
+                    // Elided lifetimes in the type of a constant are 'static.
                     let ty = ccx.icx(&trait_predicates)
-                        .to_ty(&ExplicitRscope, ty);
-                    tcx.register_item_type(ccx.tcx.map.local_def_id(trait_item.id),
-                                           TypeScheme {
-                                               generics: trait_def.generics.clone(),
-                                               ty: ty,
-                                           });
+                        .to_ty(&ElidableRscope::new(ty::ReStatic), ty);
                     convert_associated_const(ccx,
                                              container,
                                              trait_item.name,
                                              trait_item.id,
                                              hir::Public,
+                                             hir::Defaultness::Default,
                                              ty,
-                                             default.is_some())
+                                             default.is_some(),
+                                             &trait_def.generics,
+                                             &trait_predicates)
                 }
             }
 
             // Convert all the associated types.
             for trait_item in trait_items {
-                if let hir::TypeTraitItem(_, ref opt_ty) = trait_item.node {
-                    let typ = opt_ty.as_ref().map({
-                        |ty| ccx.icx(&trait_predicates).to_ty(&ExplicitRscope, &ty)
-                    });
+                if let hir::TypeTraitItem(ref generics, _, ref opt_ty) = trait_item.node {
+                    // The parameters of a generic associated type go in the
+                    // `FnSpace`, like those of a method, but its lifetimes
+                    // are all early bound.
+                    let ty_generics = ty_generics_with_lifetimes(ccx,
+                                                                 FnSpace,
+                                                                 generics,
+                                                                 &generics.lifetimes,
+                                                                 &trait_def.generics);
+                    let ty_predicates =
+                        ty_generic_predicates_with_lifetimes(ccx,
+                                                             FnSpace,
+                                                             generics,
+                                                             &generics.lifetimes,
+                                                             &trait_predicates);
+
+                    let typ = match *opt_ty {
+                        Some(ref ty) if generics.is_parameterized() => {
+                            span_err!(tcx.sess, ty.span, E0527,
+                                      "defaults for generic associated types are not \
+                                       yet supported");
+                            None
+                        }
+                        Some(ref ty) => {
+                            Some(ccx.icx(&trait_predicates).to_ty(&ExplicitRscope, &ty))
+                        }
+                        None => None,
+                    };
 
                     convert_associated_type(ccx,
                                             container,
                                             trait_item.name,
                                             trait_item.id,
                                             hir::Public,
-                                            typ);
+                                            hir::Defaultness::Default,
+                                            typ,
+                                            ty_generics,
+                                            ty_predicates);
                 }
             }
 
@@ -858,11 +1037,15 @@ fn convert_item(ccx: &CrateCtxt, it: &hir::Item) {
                                    trait_item.name,
                                    trait_item.id,
                                    hir::Inherited,
+                                   hir::Defaultness::Default,
                                    sig,
                                    tcx.mk_self_type(),
                                    &trait_def.generics,
                                    &trait_predicates);
 
+                    let mut lifetimes = LifetimeUseCollector::new();
+                    intravisit::walk_trait_item(&mut lifetimes, trait_item);
+                    warn_unused_lifetimes(tcx, trait_item.id, &sig.generics, &lifetimes.used);
                 }
             }
 
@@ -877,6 +1060,25 @@ fn convert_item(ccx: &CrateCtxt, it: &hir::Item) {
             }).collect());
             tcx.trait_item_def_ids.borrow_mut().insert(ccx.tcx.map.local_def_id(it.id),
                                                        trait_item_def_ids);
+
+            // Now that the method signatures are known, note which of them
+            // mention `Self` in a way that rules out calling them on a trait
+            // object. This needs the supertraits, to tell which projections
+            // out of `Self` are allowed.
+            if super_predicates_ok {
+                traits::record_methods_referencing_self(tcx, def_id);
+            }
+        },
+        hir::ItemTraitAlias(..) => {
+            // A trait alias is a trait without items whose supertraits are
+            // the bounds it stands for; selection and astconv expand it.
+            let trait_def = trait_def_of_item(ccx, it);
+            let def_id = trait_def.trait_ref.def_id;
+            // any error is already reported, can ignore
+            let _ = ccx.ensure_super_predicates(it.span, def_id);
+            predicates_of(ccx, it);
+
+            tcx.trait_item_def_ids.borrow_mut().insert(def_id, Rc::new(vec![]));
         },
         hir::ItemStruct(ref struct_def, _) => {
             let (scheme, predicates) = convert_typed_item(ccx, it);
@@ -893,8 +1095,31 @@ fn convert_item(ccx: &CrateCtxt, it: &hir::Item) {
                 convert_variant_ctor(ccx, struct_def.id(), variant, scheme, predicates);
             }
         },
+        hir::ItemUnion(ref union_def, _) => {
+            let (scheme, predicates) = convert_typed_item(ccx, it);
+            write_ty_to_tcx(tcx, it.id, scheme.ty);
+
+            let it_def_id = ccx.tcx.map.local_def_id(it.id);
+            let variant = tcx.lookup_adt_def_master(it_def_id).struct_variant();
+
+            for (f, ty_f) in union_def.fields().iter().zip(variant.fields.iter()) {
+                convert_field(ccx, &scheme.generics, &predicates, f, ty_f)
+            }
+        },
+        hir::ItemFn(_, _, _, _, ref generics, _) => {
+            let (scheme, _) = convert_typed_item(ccx, it);
+            write_ty_to_tcx(tcx, it.id, scheme.ty);
+
+            let mut lifetimes = LifetimeUseCollector::new();
+            intravisit::walk_item(&mut lifetimes, it);
+            warn_unused_lifetimes(tcx, it.id, generics, &lifetimes.used);
+        },
         hir::ItemTy(_, ref generics) => {
-            ensure_no_ty_param_bounds(ccx, it.span, generics, "type");
+            // Under `#![feature(type_alias_bounds)]` the bounds are enforced
+            // where the alias is used, by typeck.
+            if !tcx.sess.features.borrow().type_alias_bounds {
+                ensure_no_ty_param_bounds(ccx, it.span, generics, "type");
+            }
             let (scheme, _) = convert_typed_item(ccx, it);
             write_ty_to_tcx(tcx, it.id, scheme.ty);
         },
@@ -908,6 +1133,39 @@ fn convert_item(ccx: &CrateCtxt, it: &hir::Item) {
     }
 }
 
+/// Records an item marked `#[track_caller]` in `tcx.track_caller_fns`.
+/// `fn_abi` is the ABI of the item if it is a free function or an
+/// inherent method; the attribute is rejected anywhere else, since trait
+/// methods, foreign functions and the entry point are all called without
+/// knowing to pass the caller's location.
+fn convert_track_caller(ccx: &CrateCtxt,
+                        id: ast::NodeId,
+                        span: Span,
+                        attrs: &[ast::Attribute],
+                        fn_abi: Option<abi::Abi>) {
+    let tcx = ccx.tcx;
+    if !attr::contains_name(attrs, "track_caller") {
+        return;
+    }
+    match fn_abi {
+        None => {
+            span_err!(tcx.sess, span, E0523,
+                      "`#[track_caller]` can only be applied to functions and inherent methods");
+        }
+        Some(abi) if abi != abi::Abi::Rust => {
+            span_err!(tcx.sess, span, E0524,
+                      "`#[track_caller]` requires the Rust ABI, found {}", abi);
+        }
+        Some(_) if tcx.sess.entry_fn.borrow().map(|(entry, _)| entry) == Some(id) => {
+            span_err!(tcx.sess, span, E0523,
+                      "`#[track_caller]` cannot be applied to the entry point");
+        }
+        Some(_) => {
+            tcx.track_caller_fns.borrow_mut().insert(tcx.map.local_def_id(id));
+        }
+    }
+}
+
 fn convert_variant_ctor<'a, 'tcx>(ccx: &CrateCtxt<'a, 'tcx>,
                                   ctor_id: ast::NodeId,
                                   variant: ty::VariantDef<'tcx>,
@@ -955,6 +1213,19 @@ fn convert_enum_variant_types<'a, 'tcx>(ccx: &CrateCtxt<'a, 'tcx>,
             convert_field(ccx, &scheme.generics, &predicates, f, ty_f)
         }
 
+        // Bounds written on the variant itself (under
+        // `#![feature(variant_where_clauses)]`) only have to hold where the
+        // variant is constructed, so they are added to its ctor alone.
+        let ctor_predicates = if variant.node.where_clause.predicates.is_empty() {
+            predicates.clone()
+        } else {
+            let variant_generics = hir::Generics {
+                where_clause: variant.node.where_clause.clone(),
+                ..empty_generics()
+            };
+            ty_generic_predicates(ccx, TypeSpace, &variant_generics, &predicates)
+        };
+
         // Convert the ctor, if any. This also registers the variant as
         // an item.
         convert_variant_ctor(
@@ -962,7 +1233,7 @@ fn convert_enum_variant_types<'a, 'tcx>(ccx: &CrateCtxt<'a, 'tcx>,
             variant.node.data.id(),
             ty_variant,
             scheme.clone(),
-            predicates.clone()
+            ctor_predicates
         );
     }
 }
@@ -973,8 +1244,8 @@ fn convert_struct_variant<'tcx>(tcx: &TyCtxt<'tcx>,
                                 disr_val: ty::Disr,
                                 def: &hir::VariantData) -> ty::VariantDefData<'tcx, 'tcx> {
     let mut seen_fields: FnvHashMap<ast::Name, Span> = FnvHashMap();
-    let fields = def.fields().iter().map(|f| {
-        let fid = tcx.map.local_def_id(f.id);
+    let field_def_ids = tcx.map.local_def_ids(def.fields().iter().map(|f| f.id));
+    let fields = def.fields().iter().zip(field_def_ids).map(|(f, fid)| {
         let dup_span = seen_fields.get(&f.name).cloned();
         if let Some(prev_span) = dup_span {
             let mut err = struct_span_err!(tcx.sess, f.span, E0124,
@@ -1016,6 +1287,19 @@ fn convert_struct_def<'tcx>(tcx: &TyCtxt<'tcx>,
     )
 }
 
+fn convert_union_def<'tcx>(tcx: &TyCtxt<'tcx>,
+                           it: &hir::Item,
+                           def: &hir::VariantData)
+                           -> ty::AdtDefMaster<'tcx>
+{
+    let did = tcx.map.local_def_id(it.id);
+    tcx.intern_adt_def(
+        did,
+        ty::AdtKind::Union,
+        vec![convert_struct_variant(tcx, did, it.name, 0, def)]
+    )
+}
+
 fn convert_enum_def<'tcx>(tcx: &TyCtxt<'tcx>,
                           it: &hir::Item,
                           def: &hir::EnumDef)
@@ -1045,6 +1329,9 @@ fn convert_enum_def<'tcx>(tcx: &TyCtxt<'tcx>,
                 let mut diag = struct_span_err!(tcx.sess, err.span, E0080,
                                                 "constant evaluation error: {}",
                                                 err.description());
+                for step in &err.trace {
+                    diag.span_note(step.span, &step.description());
+                }
                 if !e.span.contains(err.span) {
                     diag.span_note(e.span, "for enum discriminant here");
                 }
@@ -1086,10 +1373,10 @@ fn convert_enum_def<'tcx>(tcx: &TyCtxt<'tcx>,
     }
     fn convert_enum_variant<'tcx>(tcx: &TyCtxt<'tcx>,
                                   v: &hir::Variant,
+                                  did: DefId,
                                   disr: ty::Disr)
                                   -> ty::VariantDefData<'tcx, 'tcx>
     {
-        let did = tcx.map.local_def_id(v.node.data.id());
         let name = v.node.name;
         convert_struct_variant(tcx, did, name, disr, &v.node.data)
     }
@@ -1097,17 +1384,18 @@ fn convert_enum_def<'tcx>(tcx: &TyCtxt<'tcx>,
     let repr_hints = tcx.lookup_repr_hints(did);
     let (repr_type, repr_type_ty) = tcx.enum_repr_type(repr_hints.get(0));
     let mut prev_disr = None;
-    let variants = def.variants.iter().map(|v| {
+    let variant_def_ids = tcx.map.local_def_ids(def.variants.iter().map(|v| v.node.data.id()));
+    let variants = def.variants.iter().zip(variant_def_ids).map(|(v, variant_did)| {
         let disr = match v.node.disr_expr {
             Some(ref e) => evaluate_disr_expr(tcx, repr_type_ty, e),
             None => next_disr(tcx, v, repr_type, prev_disr)
         }.unwrap_or(repr_type.disr_wrap_incr(prev_disr));
 
-        let v = convert_enum_variant(tcx, v, disr);
+        let v = convert_enum_variant(tcx, v, variant_did, disr);
         prev_disr = Some(disr);
         v
     }).collect();
-    tcx.intern_adt_def(tcx.map.local_def_id(it.id), ty::AdtKind::Enum, variants)
+    tcx.intern_adt_def(did, ty::AdtKind::Enum, variants)
 }
 
 /// Ensures that the super-predicates of the trait with def-id
@@ -1116,10 +1404,11 @@ fn convert_enum_def<'tcx>(tcx: &TyCtxt<'tcx>,
 /// the `ensure_super_predicates()` method in the `AstConv` impl
 /// above. Returns a list of trait def-ids that must be ensured as
 /// well to guarantee that the transitive superpredicates are
-/// converted.
+/// converted, each with the span of the bound that names it (or of
+/// the trait, if it comes from a where clause).
 fn ensure_super_predicates_step(ccx: &CrateCtxt,
                                 trait_def_id: DefId)
-                                -> Vec<DefId>
+                                -> Vec<(DefId, Span)>
 {
     let tcx = ccx.tcx;
 
@@ -1144,7 +1433,8 @@ fn ensure_super_predicates_step(ccx: &CrateCtxt,
         };
 
         let (generics, bounds) = match item.node {
-            hir::ItemTrait(_, ref generics, ref supertraits, _) => (generics, supertraits),
+            hir::ItemTrait(_, ref generics, ref supertraits, _) |
+            hir::ItemTraitAlias(ref generics, ref supertraits) => (generics, supertraits),
             _ => tcx.sess.span_bug(item.span,
                                    "ensure_super_predicates_step invoked on non-trait"),
         };
@@ -1188,15 +1478,36 @@ fn ensure_super_predicates_step(ccx: &CrateCtxt,
         superpredicates
     });
 
-    let def_ids: Vec<_> = superpredicates.predicates
-                                         .iter()
-                                         .filter_map(|p| p.to_opt_poly_trait_ref())
-                                         .map(|tr| tr.def_id())
-                                         .collect();
+    let item = tcx.map.expect_item(trait_node_id);
+    let bounds: &[hir::TyParamBound] = match item.node {
+        hir::ItemTrait(_, _, ref supertraits, _) |
+        hir::ItemTraitAlias(_, ref supertraits) => supertraits,
+        _ => &[],
+    };
+    let bound_span = |def_id: DefId| {
+        bounds.iter().filter_map(|bound| {
+            match *bound {
+                hir::TraitTyParamBound(ref ptr, _) => {
+                    match tcx.def_map.borrow().get(&ptr.trait_ref.ref_id) {
+                        Some(&def::PathResolution { base_def: Def::Trait(did), depth: 0 })
+                            if did == def_id => Some(ptr.span),
+                        _ => None,
+                    }
+                }
+                hir::RegionTyParamBound(..) => None,
+            }
+        }).next().unwrap_or(item.span)
+    };
+
+    let supertraits: Vec<_> = superpredicates.predicates
+                                             .iter()
+                                             .filter_map(|p| p.to_opt_poly_trait_ref())
+                                             .map(|tr| (tr.def_id(), bound_span(tr.def_id())))
+                                             .collect();
 
-    debug!("ensure_super_predicates_step: def_ids={:?}", def_ids);
+    debug!("ensure_super_predicates_step: supertraits={:?}", supertraits);
 
-    def_ids
+    supertraits
 }
 
 fn trait_def_of_item<'a, 'tcx>(ccx: &CrateCtxt<'a, 'tcx>,
@@ -1211,7 +1522,8 @@ fn trait_def_of_item<'a, 'tcx>(ccx: &CrateCtxt<'a, 'tcx>,
     }
 
     let (unsafety, generics, items) = match it.node {
-        hir::ItemTrait(unsafety, ref generics, _, ref items) => (unsafety, generics, items),
+        hir::ItemTrait(unsafety, ref generics, _, ref items) => (unsafety, generics, &items[..]),
+        hir::ItemTraitAlias(ref generics, _) => (hir::Unsafety::Normal, generics, &[][..]),
         _ => tcx.sess.span_bug(it.span, "trait_def_of_item invoked on non-trait"),
     };
 
@@ -1229,14 +1541,19 @@ fn trait_def_of_item<'a, 'tcx>(ccx: &CrateCtxt<'a, 'tcx>,
 
     let substs = ccx.tcx.mk_substs(mk_trait_substs(ccx, generics));
 
-    let ty_generics = ty_generics_for_trait(ccx, it.id, substs, generics);
+    let mut ty_generics = ty_generics_for_trait(ccx, it.id, substs, generics);
+    reject_const_params(ccx, generics, &mut ty_generics, TypeSpace, "traits");
 
-    let associated_type_names: Vec<_> = items.iter().filter_map(|trait_item| {
-        match trait_item.node {
-            hir::TypeTraitItem(..) => Some(trait_item.name),
-            _ => None,
+    // Keep the names in declaration order. A duplicate has already been
+    // reported by resolve, so only its first occurrence is recorded.
+    let mut associated_type_names: Vec<ast::Name> = vec![];
+    for trait_item in items {
+        if let hir::TypeTraitItem(..) = trait_item.node {
+            if !associated_type_names.contains(&trait_item.name) {
+                associated_type_names.push(trait_item.name);
+            }
         }
-    }).collect();
+    }
 
     let trait_ref = ty::TraitRef {
         def_id: def_id,
@@ -1248,6 +1565,9 @@ fn trait_def_of_item<'a, 'tcx>(ccx: &CrateCtxt<'a, 'tcx>,
                                       ty_generics,
                                       trait_ref,
                                       associated_type_names);
+    if let hir::ItemTraitAlias(..) = it.node {
+        trait_def.set_is_alias();
+    }
 
     return tcx.intern_trait_def(trait_def);
 
@@ -1285,6 +1605,22 @@ fn trait_def_of_item<'a, 'tcx>(ccx: &CrateCtxt<'a, 'tcx>,
     }
 }
 
+/// Trait aliases only stand for other bounds; there is nothing to implement.
+fn check_impl_of_trait_alias(ccx: &CrateCtxt, span: Span, trait_def_id: DefId) {
+    if ccx.get_trait_def(trait_def_id).is_alias() {
+        span_err!(ccx.tcx.sess, span, E0539,
+                  "cannot implement trait alias `{}`",
+                  ccx.tcx.item_path_str(trait_def_id));
+    }
+}
+
+fn trait_visibility(tcx: &TyCtxt, trait_def_id: DefId) -> hir::Visibility {
+    match tcx.map.as_local_node_id(trait_def_id) {
+        Some(trait_node_id) => tcx.map.expect_item(trait_node_id).vis,
+        None => tcx.sess.cstore.visibility(trait_def_id),
+    }
+}
+
 fn trait_defines_associated_type_named(ccx: &CrateCtxt,
                                        trait_node_id: ast::NodeId,
                                        assoc_name: ast::Name)
@@ -1297,6 +1633,7 @@ fn trait_defines_associated_type_named(ccx: &CrateCtxt,
 
     let trait_items = match item.node {
         hir::ItemTrait(_, _, _, ref trait_items) => trait_items,
+        hir::ItemTraitAlias(..) => return false,
         _ => ccx.tcx.sess.bug(&format!("trait_node_id {} is not a trait", trait_node_id))
     };
 
@@ -1308,14 +1645,56 @@ fn trait_defines_associated_type_named(ccx: &CrateCtxt,
     })
 }
 
-fn convert_trait_predicates<'a, 'tcx>(ccx: &CrateCtxt<'a, 'tcx>, it: &hir::Item) {
+/// Reports associated types of a trait that share their name with an
+/// associated type of one of its supertraits. Within the trait (and for any
+/// type bounded by it) a path like `Self::Name` could refer to either, so the
+/// shadowing declaration is rejected rather than left to fail at every use.
+fn check_shadowed_associated_types<'a, 'tcx>(ccx: &CrateCtxt<'a, 'tcx>,
+                                             trait_def: &'tcx ty::TraitDef<'tcx>,
+                                             trait_items: &[hir::TraitItem]) {
+    let tcx = ccx.tcx;
+    let trait_ref = ty::Binder(trait_def.trait_ref.clone());
+
+    for supertrait in traits::supertraits(tcx, trait_ref) {
+        let super_def_id = supertrait.def_id();
+        if super_def_id == trait_def.trait_ref.def_id {
+            continue;
+        }
+
+        let super_def = ccx.get_trait_def(super_def_id);
+        for trait_item in trait_items {
+            if let hir::TypeTraitItem(..) = trait_item.node {
+                if !super_def.associated_type_names.contains(&trait_item.name) {
+                    continue;
+                }
+
+                // This used to be accepted, so it is only a warning for now.
+                tcx.sess.add_lint(lint::builtin::SHADOWED_ASSOCIATED_TYPES,
+                                  trait_item.id,
+                                  trait_item.span,
+                                  format!("associated type `{}` is already declared in \
+                                           supertrait `{}`, so `Self::{}` is ambiguous \
+                                           between the two",
+                                          trait_item.name,
+                                          tcx.item_path_str(super_def_id),
+                                          trait_item.name));
+            }
+        }
+    }
+}
+
+fn compute_trait_predicates<'a, 'tcx>(ccx: &CrateCtxt<'a, 'tcx>,
+                                      it: &hir::Item)
+                                      -> ty::GenericPredicates<'tcx>
+{
     let tcx = ccx.tcx;
     let trait_def = trait_def_of_item(ccx, it);
 
     let def_id = ccx.tcx.map.local_def_id(it.id);
 
     let (generics, items) = match it.node {
-        hir::ItemTrait(_, ref generics, _, ref items) => (generics, items),
+        hir::ItemTrait(_, ref generics, _, ref items) => (generics, &items[..]),
+        hir::ItemTraitAlias(ref generics, _) => (generics, &[][..]),
         ref s => {
             tcx.sess.span_bug(
                 it.span,
@@ -1348,10 +1727,7 @@ fn convert_trait_predicates<'a, 'tcx>(ccx: &CrateCtxt<'a, 'tcx>, it: &hir::Item)
                                                            items);
     trait_predicates.predicates.extend(TypeSpace, assoc_predicates.into_iter());
 
-    let prev_predicates = tcx.predicates.borrow_mut().insert(def_id, trait_predicates);
-    assert!(prev_predicates.is_none());
-
-    return;
+    return trait_predicates;
 
     fn predicates_for_associated_types<'a, 'tcx>(ccx: &CrateCtxt<'a, 'tcx>,
                                                  ast_generics: &hir::Generics,
@@ -1362,7 +1738,19 @@ fn convert_trait_predicates<'a, 'tcx>(ccx: &CrateCtxt<'a, 'tcx>, it: &hir::Item)
     {
         trait_items.iter().flat_map(|trait_item| {
             let bounds = match trait_item.node {
-                hir::TypeTraitItem(ref bounds, _) => bounds,
+                hir::TypeTraitItem(ref generics, ref bounds, _) => {
+                    if generics.is_parameterized() {
+                        // There is no projection to hang these on yet, so
+                        // not even the implicit `Sized` bound is added.
+                        if !bounds.is_empty() {
+                            span_err!(ccx.tcx.sess, trait_item.span, E0527,
+                                      "bounds on generic associated types are not \
+                                       yet supported");
+                        }
+                        return vec!().into_iter();
+                    }
+                    bounds
+                }
                 _ => {
                     return vec!().into_iter();
                 }
@@ -1432,7 +1820,10 @@ fn compute_type_scheme_of_item<'a,'tcx>(ccx: &CrateCtxt<'a,'tcx>,
         }
         hir::ItemFn(ref decl, unsafety, _, abi, ref generics, _) => {
             let ty_generics = ty_generics_for_fn(ccx, generics, &ty::Generics::empty());
-            let tofd = astconv::ty_of_bare_fn(&ccx.icx(generics), unsafety, abi, &decl);
+            let tofd = astconv::ty_of_bare_fn(&ccx.icx(generics), Some(it.id),
+                                              unsafety, abi, &decl,
+                                              Some(AnonTypeScope::new(&ty_generics)));
+            convert_anon_types(ccx, decl, &ty_generics);
             let def_id = ccx.tcx.map.local_def_id(it.id);
             let substs = tcx.mk_substs(mk_item_substs(ccx, &ty_generics));
             let ty = tcx.mk_fn_def(def_id, substs, tofd);
@@ -1457,8 +1848,16 @@ fn compute_type_scheme_of_item<'a,'tcx>(ccx: &CrateCtxt<'a,'tcx>,
             let t = tcx.mk_struct(def, tcx.mk_substs(substs));
             ty::TypeScheme { ty: t, generics: ty_generics }
         }
+        hir::ItemUnion(ref ui, ref generics) => {
+            let ty_generics = ty_generics_for_type_or_impl(ccx, generics);
+            let substs = mk_item_substs(ccx, &ty_generics);
+            let def = convert_union_def(tcx, it, ui);
+            let t = tcx.mk_struct(def, tcx.mk_substs(substs));
+            ty::TypeScheme { ty: t, generics: ty_generics }
+        }
         hir::ItemDefaultImpl(..) |
         hir::ItemTrait(..) |
+        hir::ItemTraitAlias(..) |
         hir::ItemImpl(..) |
         hir::ItemMod(..) |
         hir::ItemForeignMod(..) |
@@ -1472,47 +1871,70 @@ fn compute_type_scheme_of_item<'a,'tcx>(ccx: &CrateCtxt<'a,'tcx>,
     }
 }
 
-fn convert_typed_item<'a, 'tcx>(ccx: &CrateCtxt<'a, 'tcx>,
-                                it: &hir::Item)
-                                -> (ty::TypeScheme<'tcx>, ty::GenericPredicates<'tcx>)
+/// The predicates of a local item other than an impl (whose predicates are
+/// recorded by `convert_item`), computed on first demand and cached in
+/// `tcx.predicates`. If computing them runs into a cycle, the item is
+/// given no predicates.
+fn predicates_of<'a,'tcx>(ccx: &CrateCtxt<'a,'tcx>,
+                          it: &hir::Item)
+                          -> ty::GenericPredicates<'tcx>
 {
-    let tcx = ccx.tcx;
+    let def_id = ccx.tcx.map.local_def_id(it.id);
+    if let Some(predicates) = ccx.tcx.predicates.borrow().get(&def_id) {
+        return predicates.clone();
+    }
 
-    let tag = type_scheme_of_item(ccx, it);
-    let scheme = TypeScheme { generics: tag.generics, ty: tag.ty };
-    let predicates = match it.node {
+    let predicates = ccx.query(it.span, Query::PredicatesOf(def_id), || {
+        Ok(compute_predicates_of_item(ccx, it))
+    }).unwrap_or_else(|ErrorReported| ty::GenericPredicates::empty());
+
+    ccx.tcx.predicates.borrow_mut().insert(def_id, predicates.clone());
+    predicates
+}
+
+fn compute_predicates_of_item<'a,'tcx>(ccx: &CrateCtxt<'a,'tcx>,
+                                       it: &hir::Item)
+                                       -> ty::GenericPredicates<'tcx>
+{
+    match it.node {
         hir::ItemStatic(..) | hir::ItemConst(..) => {
             ty::GenericPredicates::empty()
         }
         hir::ItemFn(_, _, _, _, ref ast_generics, _) => {
             ty_generic_predicates_for_fn(ccx, ast_generics, &ty::GenericPredicates::empty())
         }
-        hir::ItemTy(_, ref generics) => {
+        hir::ItemTy(_, ref generics) |
+        hir::ItemEnum(_, ref generics) |
+        hir::ItemStruct(_, ref generics) |
+        hir::ItemUnion(_, ref generics) => {
             ty_generic_predicates_for_type_or_impl(ccx, generics)
         }
-        hir::ItemEnum(_, ref generics) => {
-            ty_generic_predicates_for_type_or_impl(ccx, generics)
-        }
-        hir::ItemStruct(_, ref generics) => {
-            ty_generic_predicates_for_type_or_impl(ccx, generics)
+        hir::ItemTrait(..) | hir::ItemTraitAlias(..) => {
+            compute_trait_predicates(ccx, it)
         }
         hir::ItemDefaultImpl(..) |
-        hir::ItemTrait(..) |
         hir::ItemExternCrate(..) |
         hir::ItemUse(..) |
         hir::ItemImpl(..) |
         hir::ItemMod(..) |
         hir::ItemForeignMod(..) => {
-            tcx.sess.span_bug(
+            ccx.tcx.sess.span_bug(
                 it.span,
-                &format!("compute_type_scheme_of_item: unexpected item type: {:?}",
+                &format!("compute_predicates_of_item: unexpected item type: {:?}",
                          it.node));
         }
-    };
+    }
+}
 
-    let prev_predicates = tcx.predicates.borrow_mut().insert(ccx.tcx.map.local_def_id(it.id),
-                                                             predicates.clone());
-    assert!(prev_predicates.is_none());
+fn convert_typed_item<'a, 'tcx>(ccx: &CrateCtxt<'a, 'tcx>,
+                                it: &hir::Item)
+                                -> (ty::TypeScheme<'tcx>, ty::GenericPredicates<'tcx>)
+{
+    let tcx = ccx.tcx;
+
+    let tag = type_scheme_of_item(ccx, it);
+    let scheme = TypeScheme { generics: tag.generics, ty: tag.ty };
+    let predicates = predicates_of(ccx, it);
 
     // Debugging aid.
     if tcx.has_attr(ccx.tcx.map.local_def_id(it.id), "rustc_object_lifetime_default") {
@@ -1578,6 +2000,25 @@ fn convert_foreign_item<'a, 'tcx>(ccx: &CrateCtxt<'a, 'tcx>,
     let tcx = ccx.tcx;
     let abi = tcx.map.get_foreign_abi(it.id);
 
+    // Intrinsics are instantiated by the compiler itself, but any other
+    // foreign function is a single symbol and can't be generic over types.
+    // Lifetime parameters are fine, as they don't change the signature.
+    if let hir::ForeignItemFn(_, ref generics) = it.node {
+        let is_intrinsic = abi == abi::Abi::RustIntrinsic ||
+                           abi == abi::Abi::PlatformIntrinsic;
+        if !is_intrinsic && !generics.ty_params.is_empty() {
+            let mut err = struct_span_err!(tcx.sess, generics.ty_params[0].span, E0044,
+                                           "foreign items may not have type parameters");
+            span_note!(&mut err, it.span,
+                       "`{}` is defined outside of Rust, so it can't be monomorphized \
+                        into a separate function for each choice of type",
+                       it.name);
+            fileline_help!(&mut err, it.span,
+                           "declare a separate foreign function for each type you need");
+            err.emit();
+        }
+    }
+
     let scheme = type_scheme_of_foreign_item(ccx, it, abi);
     write_ty_to_tcx(ccx.tcx, it.id, scheme.ty);
 
@@ -1652,6 +2093,22 @@ fn ty_generics_for_fn<'a,'tcx>(ccx: &CrateCtxt<'a,'tcx>,
     ty_generics(ccx, FnSpace, generics, base_generics)
 }
 
+/// Const arguments are never inferred, so const parameters are only
+/// allowed where every use spells its arguments out: on types and free
+/// functions. Reports any others and drops them from `ty_generics`.
+fn reject_const_params<'a, 'tcx>(ccx: &CrateCtxt<'a, 'tcx>,
+                                 ast_generics: &hir::Generics,
+                                 ty_generics: &mut ty::Generics<'tcx>,
+                                 space: ParamSpace,
+                                 what: &str)
+{
+    for param in ast_generics.const_params.iter() {
+        ccx.tcx.sess.span_err(param.span,
+                              &format!("const parameters are not yet supported on {}", what));
+    }
+    ty_generics.consts.truncate(space, 0);
+}
+
 fn ty_generic_predicates_for_fn<'a,'tcx>(ccx: &CrateCtxt<'a,'tcx>,
                                          generics: &hir::Generics,
                                          base_predicates: &ty::GenericPredicates<'tcx>)
@@ -1660,53 +2117,6 @@ fn ty_generic_predicates_for_fn<'a,'tcx>(ccx: &CrateCtxt<'a,'tcx>,
     ty_generic_predicates(ccx, FnSpace, generics, base_predicates)
 }
 
-// Add the Sized bound, unless the type parameter is marked as `?Sized`.
-fn add_unsized_bound<'tcx>(astconv: &AstConv<'tcx>,
-                           bounds: &mut ty::BuiltinBounds,
-                           ast_bounds: &[hir::TyParamBound],
-                           span: Span)
-{
-    let tcx = astconv.tcx();
-
-    // Try to find an unbound in bounds.
-    let mut unbound = None;
-    for ab in ast_bounds {
-        if let &hir::TraitTyParamBound(ref ptr, hir::TraitBoundModifier::Maybe) = ab  {
-            if unbound.is_none() {
-                assert!(ptr.bound_lifetimes.is_empty());
-                unbound = Some(ptr.trait_ref.clone());
-            } else {
-                span_err!(tcx.sess, span, E0203,
-                          "type parameter has more than one relaxed default \
-                                                bound, only one is supported");
-            }
-        }
-    }
-
-    let kind_id = tcx.lang_items.require(SizedTraitLangItem);
-    match unbound {
-        Some(ref tpb) => {
-            // FIXME(#8559) currently requires the unbound to be built-in.
-            let trait_def_id = tcx.trait_ref_to_def_id(tpb);
-            match kind_id {
-                Ok(kind_id) if trait_def_id != kind_id => {
-                    tcx.sess.span_warn(span,
-                                       "default bound relaxed for a type parameter, but \
-                                       this does nothing because the given bound is not \
-                                       a default. Only `?Sized` is supported");
-                    tcx.try_add_builtin_trait(kind_id, bounds);
-                }
-                _ => {}
-            }
-        }
-        _ if kind_id.is_ok() => {
-            tcx.try_add_builtin_trait(kind_id.unwrap(), bounds);
-        }
-        // No lang item for Sized, so we can't add it as a bound.
-        None => {}
-    }
-}
-
 /// Returns the early-bound lifetimes declared in this generics
 /// listing.  For anything other than fns/methods, this is just all
 /// the lifetimes that are declared. For fns or methods, we have to
@@ -1727,6 +2137,20 @@ fn ty_generic_predicates<'a,'tcx>(ccx: &CrateCtxt<'a,'tcx>,
                                   ast_generics: &hir::Generics,
                                   base_predicates: &ty::GenericPredicates<'tcx>)
                                   -> ty::GenericPredicates<'tcx>
+{
+    let early_lifetimes = early_bound_lifetimes_from_generics(space, ast_generics);
+    ty_generic_predicates_with_lifetimes(ccx, space, ast_generics, &early_lifetimes,
+                                         base_predicates)
+}
+
+/// Like `ty_generic_predicates`, but with the early-bound lifetimes given
+/// explicitly rather than worked out from `space`.
+fn ty_generic_predicates_with_lifetimes<'a,'tcx>(ccx: &CrateCtxt<'a,'tcx>,
+                                                 space: ParamSpace,
+                                                 ast_generics: &hir::Generics,
+                                                 early_lifetimes: &[hir::LifetimeDef],
+                                                 base_predicates: &ty::GenericPredicates<'tcx>)
+                                                 -> ty::GenericPredicates<'tcx>
 {
     let tcx = ccx.tcx;
     let mut result = base_predicates.clone();
@@ -1748,7 +2172,6 @@ fn ty_generic_predicates<'a,'tcx>(ccx: &CrateCtxt<'a,'tcx>,
     // Collect the region predicates that were declared inline as
     // well. In the case of parameters declared on a fn or method, we
     // have to be careful to only iterate over early-bound regions.
-    let early_lifetimes = early_bound_lifetimes_from_generics(space, ast_generics);
     for (index, param) in early_lifetimes.iter().enumerate() {
         let index = index as u32;
         let region =
@@ -1810,10 +2233,29 @@ fn ty_generic_predicates<'a,'tcx>(ccx: &CrateCtxt<'a,'tcx>,
             }
 
             &hir::WherePredicate::EqPredicate(ref eq_pred) => {
-                // FIXME(#20041)
-                tcx.sess.span_bug(eq_pred.span,
-                                    "Equality constraints are not yet \
-                                        implemented (#20041)")
+                // `T::Out == u32` means the same as the `Out = u32` in
+                // `T: Trait<Out = u32>`, whichever side the associated type
+                // is written on.
+                let icx = ccx.icx(&(base_predicates, ast_generics));
+                let lhs = ast_ty_to_ty(&icx, &ExplicitRscope, &eq_pred.lhs_ty);
+                let rhs = ast_ty_to_ty(&icx, &ExplicitRscope, &eq_pred.rhs_ty);
+                let (projection_ty, ty) = match (&lhs.sty, &rhs.sty) {
+                    (&ty::TyProjection(ref data), _) => (data.clone(), rhs),
+                    (_, &ty::TyProjection(ref data)) => (data.clone(), lhs),
+                    _ => {
+                        if !lhs.references_error() && !rhs.references_error() {
+                            span_err!(tcx.sess, eq_pred.span, E0529,
+                                      "equality constraints must have an associated type \
+                                       on one side");
+                        }
+                        continue;
+                    }
+                };
+                let pred = ty::Binder(ty::ProjectionPredicate {
+                    projection_ty: projection_ty,
+                    ty: ty,
+                });
+                result.predicates.push(space, ty::Predicate::Projection(pred));
             }
         }
     }
@@ -1826,19 +2268,32 @@ fn ty_generics<'a,'tcx>(ccx: &CrateCtxt<'a,'tcx>,
                         ast_generics: &hir::Generics,
                         base_generics: &ty::Generics<'tcx>)
                         -> ty::Generics<'tcx>
+{
+    let early_lifetimes = early_bound_lifetimes_from_generics(space, ast_generics);
+    ty_generics_with_lifetimes(ccx, space, ast_generics, &early_lifetimes, base_generics)
+}
+
+/// Like `ty_generics`, but with the early-bound lifetimes given explicitly
+/// rather than worked out from `space`.
+fn ty_generics_with_lifetimes<'a,'tcx>(ccx: &CrateCtxt<'a,'tcx>,
+                                       space: ParamSpace,
+                                       ast_generics: &hir::Generics,
+                                       early_lifetimes: &[hir::LifetimeDef],
+                                       base_generics: &ty::Generics<'tcx>)
+                                       -> ty::Generics<'tcx>
 {
     let tcx = ccx.tcx;
     let mut result = base_generics.clone();
 
-    let early_lifetimes = early_bound_lifetimes_from_generics(space, ast_generics);
-    for (i, l) in early_lifetimes.iter().enumerate() {
+    let lifetime_def_ids = tcx.map.local_def_ids(early_lifetimes.iter().map(|l| l.lifetime.id));
+    for (i, (l, def_id)) in early_lifetimes.iter().zip(lifetime_def_ids).enumerate() {
         let bounds = l.bounds.iter()
                              .map(|l| ast_region_to_region(tcx, l))
                              .collect();
         let def = ty::RegionParameterDef { name: l.lifetime.name,
                                            space: space,
                                            index: i as u32,
-                                           def_id: ccx.tcx.map.local_def_id(l.lifetime.id),
+                                           def_id: def_id,
                                            bounds: bounds };
         result.regions.push(space, def);
     }
@@ -1852,6 +2307,28 @@ fn ty_generics<'a,'tcx>(ccx: &CrateCtxt<'a,'tcx>,
         result.types.push(space, def);
     }
 
+    for (i, param) in ast_generics.const_params.iter().enumerate() {
+        let ty = ast_ty_to_ty(&ccx.icx(&()), &ExplicitRscope, &param.ty);
+        match ty.sty {
+            ty::TyInt(_) | ty::TyUint(_) | ty::TyBool | ty::TyError => {}
+            _ => {
+                span_err!(tcx.sess, param.ty.span, E0532,
+                          "the type of a const parameter must be an integer or `bool`, \
+                           not `{}`", ty);
+            }
+        }
+        let def = ty::ConstParameterDef {
+            name: param.name,
+            def_id: tcx.map.local_def_id(param.id),
+            space: space,
+            index: i as u32,
+            ty: ty,
+        };
+        debug!("ty_generics: def for const param: {:?}, {:?}", def, space);
+        tcx.const_param_defs.borrow_mut().insert(param.id, def.clone());
+        result.consts.push(space, def);
+    }
+
     result
 }
 
@@ -1945,7 +2422,11 @@ fn compute_object_lifetime_default<'a,'tcx>(ccx: &CrateCtxt<'a,'tcx>,
     let all_bounds: HashSet<_> = inline_bounds.into_iter()
                                               .chain(where_bounds)
                                               .collect();
-    return if all_bounds.len() > 1 {
+    return if all_bounds.contains(&ty::ReStatic) {
+        // `T: 'static` implies all the other bounds, so `'static` is
+        // the only default that satisfies them all.
+        ty::ObjectLifetimeDefault::Specific(ty::ReStatic)
+    } else if all_bounds.len() > 1 {
         ty::ObjectLifetimeDefault::Ambiguous
     } else if all_bounds.len() == 0 {
         ty::ObjectLifetimeDefault::BaseDefault
@@ -1997,36 +2478,6 @@ fn compute_object_lifetime_default<'a,'tcx>(ccx: &CrateCtxt<'a,'tcx>,
     }
 }
 
-enum SizedByDefault { Yes, No, }
-
-/// Translate the AST's notion of ty param bounds (which are an enum consisting of a newtyped Ty or
-/// a region) to ty's notion of ty param bounds, which can either be user-defined traits, or the
-/// built-in trait (formerly known as kind): Send.
-fn compute_bounds<'tcx>(astconv: &AstConv<'tcx>,
-                        param_ty: ty::Ty<'tcx>,
-                        ast_bounds: &[hir::TyParamBound],
-                        sized_by_default: SizedByDefault,
-                        span: Span)
-                        -> astconv::Bounds<'tcx>
-{
-    let mut bounds =
-        conv_param_bounds(astconv,
-                          span,
-                          param_ty,
-                          ast_bounds);
-
-    if let SizedByDefault::Yes = sized_by_default {
-        add_unsized_bound(astconv,
-                          &mut bounds.builtin_bounds,
-                          ast_bounds,
-                          span);
-    }
-
-    bounds.trait_bounds.sort_by(|a,b| a.def_id().cmp(&b.def_id()));
-
-    bounds
-}
-
 /// Converts a specific TyParamBound from the AST into a set of
 /// predicates that apply to the self-type. A vector is returned
 /// because this can be anywhere from 0 predicates (`T:?Sized` adds no
@@ -2070,42 +2521,6 @@ fn conv_poly_trait_ref<'tcx>(astconv: &AstConv<'tcx>,
                                         projections)
 }
 
-fn conv_param_bounds<'a,'tcx>(astconv: &AstConv<'tcx>,
-                              span: Span,
-                              param_ty: ty::Ty<'tcx>,
-                              ast_bounds: &[hir::TyParamBound])
-                              -> astconv::Bounds<'tcx>
-{
-    let tcx = astconv.tcx();
-    let astconv::PartitionedBounds {
-        builtin_bounds,
-        trait_bounds,
-        region_bounds
-    } = astconv::partition_bounds(tcx, span, &ast_bounds);
-
-    let mut projection_bounds = Vec::new();
-
-    let trait_bounds: Vec<ty::PolyTraitRef> =
-        trait_bounds.iter()
-                    .map(|bound| conv_poly_trait_ref(astconv,
-                                                     param_ty,
-                                                     *bound,
-                                                     &mut projection_bounds))
-                    .collect();
-
-    let region_bounds: Vec<ty::Region> =
-        region_bounds.into_iter()
-                     .map(|r| ast_region_to_region(tcx, r))
-                     .collect();
-
-    astconv::Bounds {
-        region_bounds: region_bounds,
-        builtin_bounds: builtin_bounds,
-        trait_bounds: trait_bounds,
-        projection_bounds: projection_bounds,
-    }
-}
-
 fn compute_type_scheme_of_foreign_fn_decl<'a, 'tcx>(
     ccx: &CrateCtxt<'a, 'tcx>,
     id: DefId,
@@ -2169,11 +2584,16 @@ fn mk_item_substs<'a, 'tcx>(ccx: &CrateCtxt<'a, 'tcx>,
         ty_generics.regions.map(
             |def| def.to_early_bound_region());
 
-    Substs::new(types, regions)
+    let consts =
+        ty_generics.consts.map(
+            |def| ConstArg::Param(ty::ParamConst::for_def(def)));
+
+    Substs::new(types, regions).with_consts(consts)
 }
 
 /// Checks that all the type parameters on an impl
 fn enforce_impl_params_are_constrained<'tcx>(tcx: &TyCtxt<'tcx>,
+                                             it: &hir::Item,
                                              ast_generics: &hir::Generics,
                                              impl_predicates: &mut ty::GenericPredicates<'tcx>,
                                              impl_def_id: DefId)
@@ -2203,9 +2623,148 @@ fn enforce_impl_params_are_constrained<'tcx>(tcx: &TyCtxt<'tcx>,
                                      idx: index as u32,
                                      name: ty_param.name };
         if !input_parameters.contains(&ctp::Parameter::Type(param_ty)) {
-            report_unused_parameter(tcx, ty_param.span, "type", &param_ty.to_string());
+            let mut err = report_unused_parameter(tcx, ty_param.span, "type",
+                                                  &param_ty.to_string());
+            if ty_param.span.expn_id == NO_EXPANSION {
+                suggest_constraining_parameter(tcx, &mut err, it, ast_generics, index,
+                                               &input_parameters);
+            }
+            err.emit();
+        }
+    }
+}
+
+/// Adds to an E0207 error about the `index`th type parameter of the impl `it`
+/// the ways to fix it: removing the parameter if nothing else mentions it,
+/// using it in the impl header, or binding it to an associated type in the
+/// bounds of a constrained parameter.
+fn suggest_constraining_parameter(tcx: &TyCtxt,
+                                  err: &mut DiagnosticBuilder,
+                                  it: &hir::Item,
+                                  ast_generics: &hir::Generics,
+                                  index: usize,
+                                  input_parameters: &HashSet<ctp::Parameter>)
+{
+    let ty_param = &ast_generics.ty_params[index];
+    let name = ty_param.name;
+
+    let mut uses = ParamUseFinder { name: name, found: false };
+    intravisit::walk_generics(&mut uses, ast_generics);
+    if let hir::ItemImpl(_, _, _, ref opt_trait_ref, ref self_ty, ref impl_items) = it.node {
+        if let Some(ref trait_ref) = *opt_trait_ref {
+            intravisit::walk_trait_ref(&mut uses, trait_ref);
+        }
+        intravisit::walk_ty(&mut uses, self_ty);
+        for impl_item in impl_items {
+            intravisit::walk_impl_item(&mut uses, impl_item);
+        }
+
+        if !uses.found {
+            // Take the parameter out along with a comma next to it, or with
+            // the angle brackets if it's the only one.
+            let extents: Vec<Span> =
+                ast_generics.lifetimes.iter().map(lifetime_def_extent)
+                            .chain(ast_generics.ty_params.iter().map(ty_param_extent))
+                            .collect();
+            let i = ast_generics.lifetimes.len() + index;
+            let span = if i + 1 < extents.len() {
+                Span { hi: extents[i + 1].lo, ..extents[i] }
+            } else if i > 0 {
+                Span { lo: extents[i - 1].hi, ..extents[i] }
+            } else {
+                let brackets = Span { lo: extents[i].lo - BytePos(1),
+                                      hi: extents[i].hi + BytePos(1),
+                                      ..extents[i] };
+                match tcx.sess.codemap().span_to_snippet(brackets) {
+                    Ok(ref s) if s.starts_with("<") && s.ends_with(">") => brackets,
+                    _ => extents[i],
+                }
+            };
+            err.span_suggestion(span, "remove the parameter:", String::new());
+        }
+
+        let header = if opt_trait_ref.is_some() { "the self type or the trait" } else {
+            "the self type"
+        };
+        err.span_help(self_ty.span, &format!("or use `{}` in {}", name, header));
+    }
+
+    // A parameter is also constrained by being the value of an associated
+    // type of a constrained one, as `T` is in `U: Iterator<Item = T>`.
+    for (i, other) in ast_generics.ty_params.iter().enumerate() {
+        let other_ty = ty::ParamTy { space: TypeSpace, idx: i as u32, name: other.name };
+        if !input_parameters.contains(&ctp::Parameter::Type(other_ty)) {
+            continue;
+        }
+        for bound in other.bounds.iter() {
+            let poly_trait_ref = match *bound {
+                hir::TraitTyParamBound(ref poly_trait_ref, hir::TraitBoundModifier::None) => {
+                    poly_trait_ref
+                }
+                _ => continue,
+            };
+            let path = &poly_trait_ref.trait_ref.path;
+            if !path.segments.last().map_or(false, |s| s.parameters.is_empty()) {
+                continue;
+            }
+            let def = tcx.def_map.borrow().get(&poly_trait_ref.trait_ref.ref_id)
+                                          .map(|d| d.full_def());
+            let trait_def = match def {
+                Some(Def::Trait(trait_def_id)) => tcx.lookup_trait_def(trait_def_id),
+                _ => continue,
+            };
+            if trait_def.associated_type_names.len() != 1 {
+                continue;
+            }
+            if let Ok(snippet) = tcx.sess.codemap().span_to_snippet(path.span) {
+                let suggestion = format!("{}<{} = {}>", snippet,
+                                         trait_def.associated_type_names[0], name);
+                err.span_suggestion(path.span,
+                                    &format!("or constrain `{}` through the bounds of `{}`:",
+                                             name, other.name),
+                                    suggestion);
+                return;
+            }
+        }
+    }
+}
+
+/// Looks for paths starting with a given name, i.e. uses of a type parameter.
+struct ParamUseFinder {
+    name: ast::Name,
+    found: bool,
+}
+
+impl<'v> intravisit::Visitor<'v> for ParamUseFinder {
+    fn visit_path(&mut self, path: &'v hir::Path, _id: ast::NodeId) {
+        if !path.global && path.segments[0].identifier.name == self.name {
+            self.found = true;
         }
+        intravisit::walk_path(self, path);
+    }
+}
+
+/// The span of a type parameter together with its bounds and default.
+fn ty_param_extent(ty_param: &hir::TyParam) -> Span {
+    let mut hi = ty_param.span.hi;
+    for bound in ty_param.bounds.iter() {
+        let bound_span = match *bound {
+            hir::TraitTyParamBound(ref poly_trait_ref, _) => poly_trait_ref.span,
+            hir::RegionTyParamBound(ref lifetime) => lifetime.span,
+        };
+        hi = cmp::max(hi, bound_span.hi);
     }
+    if let Some(ref default) = ty_param.default {
+        hi = cmp::max(hi, default.span.hi);
+    }
+    Span { hi: hi, ..ty_param.span }
+}
+
+/// The span of a lifetime parameter together with its bounds.
+fn lifetime_def_extent(lifetime_def: &hir::LifetimeDef) -> Span {
+    let hi = lifetime_def.bounds.iter().fold(lifetime_def.lifetime.span.hi,
+                                             |hi, bound| cmp::max(hi, bound.span.hi));
+    Span { hi: hi, ..lifetime_def.lifetime.span }
 }
 
 fn enforce_impl_lifetimes_are_constrained<'tcx>(tcx: &TyCtxt<'tcx>,
@@ -2249,7 +2808,7 @@ fn enforce_impl_lifetimes_are_constrained<'tcx>(tcx: &TyCtxt<'tcx>,
             !input_parameters.contains(&ctp::Parameter::Region(region))
         {
             report_unused_parameter(tcx, lifetime_def.lifetime.span,
-                                    "lifetime", &region.name.to_string());
+                                    "lifetime", &region.name.to_string()).emit();
         }
     }
 
@@ -2273,13 +2832,102 @@ fn enforce_impl_lifetimes_are_constrained<'tcx>(tcx: &TyCtxt<'tcx>,
     // used elsewhere are not projected back out.
 }
 
-fn report_unused_parameter(tcx: &TyCtxt,
-                           span: Span,
-                           kind: &str,
-                           name: &str)
+/// Registers a type scheme for each `impl Trait` in the return type of
+/// `decl`, sharing the generics of the function. Until the body of the
+/// function has been checked, the scheme just names the anonymized type
+/// itself; writeback replaces it with the concrete type.
+fn convert_anon_types<'a, 'tcx>(ccx: &CrateCtxt<'a, 'tcx>,
+                                decl: &hir::FnDecl,
+                                ty_generics: &ty::Generics<'tcx>) {
+    let mut collector = AnonTypeCollector { anon_ty_ids: vec![] };
+    intravisit::walk_fn_ret_ty(&mut collector, &decl.output);
+
+    let tcx = ccx.tcx;
+    let anon_scope = AnonTypeScope::new(ty_generics);
+    for id in collector.anon_ty_ids {
+        let def_id = tcx.map.local_def_id(id);
+        // Conversion reported an error if it recorded no predicates.
+        if !tcx.predicates.borrow().contains_key(&def_id) {
+            continue;
+        }
+        let substs = tcx.mk_substs(anon_scope.fresh_substs(tcx));
+        tcx.register_item_type(def_id, TypeScheme {
+            generics: ty_generics.clone(),
+            ty: tcx.mk_anon(def_id, substs)
+        });
+    }
+}
+
+/// Collects the node ids of all `impl Trait` types within a type.
+struct AnonTypeCollector {
+    anon_ty_ids: Vec<ast::NodeId>,
+}
+
+impl<'v> intravisit::Visitor<'v> for AnonTypeCollector {
+    fn visit_ty(&mut self, ty: &'v hir::Ty) {
+        if let hir::TyImplTrait(_) = ty.node {
+            self.anon_ty_ids.push(ty.id);
+        }
+        intravisit::walk_ty(self, ty);
+    }
+}
+
+/// Collects the names of all lifetimes referenced within an item,
+/// ignoring the lifetimes that are merely declared.
+struct LifetimeUseCollector {
+    used: FnvHashSet<ast::Name>,
+}
+
+impl LifetimeUseCollector {
+    fn new() -> LifetimeUseCollector {
+        LifetimeUseCollector { used: FnvHashSet() }
+    }
+}
+
+impl<'v> intravisit::Visitor<'v> for LifetimeUseCollector {
+    fn visit_lifetime(&mut self, lifetime: &'v hir::Lifetime) {
+        self.used.insert(lifetime.name);
+    }
+
+    fn visit_lifetime_def(&mut self, lifetime_def: &'v hir::LifetimeDef) {
+        // Only the bounds of a lifetime definition are uses.
+        for bound in &lifetime_def.bounds {
+            self.visit_lifetime(bound);
+        }
+    }
+}
+
+/// Lints the lifetime parameters declared in `ast_generics` that never
+/// appear in `used`, i.e. anywhere in the signature or body of the item.
+/// Lifetimes introduced by macro expansion are skipped, since a macro
+/// often declares lifetimes that only some of its expansions need.
+fn warn_unused_lifetimes(tcx: &TyCtxt,
+                         id: ast::NodeId,
+                         ast_generics: &hir::Generics,
+                         used: &FnvHashSet<ast::Name>)
+{
+    for lifetime_def in ast_generics.lifetimes.iter() {
+        let lifetime = &lifetime_def.lifetime;
+        if lifetime.span.expn_id != NO_EXPANSION || used.contains(&lifetime.name) {
+            continue;
+        }
+        tcx.sess.add_lint(lint::builtin::UNUSED_LIFETIMES,
+                          id,
+                          lifetime.span,
+                          format!("lifetime parameter `{}` is never used, \
+                                   consider removing it",
+                                  lifetime.name));
+    }
+}
+
+fn report_unused_parameter<'a>(tcx: &'a TyCtxt,
+                               span: Span,
+                               kind: &str,
+                               name: &str)
+                               -> DiagnosticBuilder<'a>
 {
-    span_err!(tcx.sess, span, E0207,
-              "the {} parameter `{}` is not constrained by the \
-               impl trait, self type, or predicates",
-              kind, name);
+    struct_span_err!(tcx.sess, span, E0207,
+                     "the {} parameter `{}` is not constrained by the \
+                      impl trait, self type, or predicates",
+                     kind, name)
 }
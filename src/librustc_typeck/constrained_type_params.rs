@@ -64,7 +64,8 @@ fn parameters_for_type_shallow<'tcx>(ty: Ty<'tcx>) -> Vec<Parameter> {
         ty::TyRef(region, _) =>
             parameters_for_region(region).into_iter().collect(),
         ty::TyStruct(_, substs) |
-        ty::TyEnum(_, substs) =>
+        ty::TyEnum(_, substs) |
+        ty::TyAnon(_, substs) =>
             parameters_for_regions_in_substs(substs),
         ty::TyTrait(ref data) =>
             parameters_for_regions_in_substs(&data.principal.skip_binder().substs),
@@ -72,7 +73,7 @@ fn parameters_for_type_shallow<'tcx>(ty: Ty<'tcx>) -> Vec<Parameter> {
             parameters_for_regions_in_substs(&pi.trait_ref.substs),
         ty::TyBool | ty::TyChar | ty::TyInt(..) | ty::TyUint(..) |
         ty::TyFloat(..) | ty::TyBox(..) | ty::TyStr |
-        ty::TyArray(..) | ty::TySlice(..) |
+        ty::TyArray(..) | ty::TyParamArray(..) | ty::TySlice(..) |
         ty::TyFnDef(..) | ty::TyFnPtr(_) |
         ty::TyTuple(..) | ty::TyRawPtr(..) |
         ty::TyInfer(..) | ty::TyClosure(..) | ty::TyError =>
@@ -443,13 +443,22 @@ You can't use type parameters on foreign items. Example of erroneous code:
 extern { fn some_func<T>(x: T); }
 ```
 
-To fix this, replace the type parameter with the specializations that you
-need:
+A generic Rust function is compiled separately for each choice of its type
+parameters, but a foreign function is a single symbol defined elsewhere, so
+there is nothing to instantiate. To fix this, declare a separate function for
+each type that you need:
 
 ```
 extern { fn some_func_i32(x: i32); }
 extern { fn some_func_i64(x: i64); }
 ```
+
+Lifetime parameters are allowed, since they don't affect the function's
+signature at the machine level:
+
+```
+extern { fn some_func<'a>(x: &'a i32) -> &'a i32; }
+```
 "##,
 
 E0045: r##"
@@ -2633,7 +2642,9 @@ by `Foo` or the one defined by `Bar`.
 
 There are two options to work around this issue. The first is simply to rename
 one of the types. Alternatively, one can specify the intended type using the
-following syntax:
+following syntax, though note that declaring `A` in `Bar` at all triggers the
+`shadowed_associated_types` lint, as this is being phased out and will become
+a hard error in a future release; renaming is the better fix:
 
 ```
 trait T1 {}
@@ -3645,8 +3656,7 @@ register_diagnostics! {
 //  E0189, // deprecated: can only cast a boxed pointer to a boxed object
 //  E0190, // deprecated: can only cast a &-pointer to an &-object
     E0196, // cannot determine a type for this closure
-    E0203, // type parameter has more than one relaxed default bound,
-           // and only one is supported
+    E0203, // type parameter relaxes the same default bound more than once
     E0208,
 //  E0209, // builtin traits can only be implemented on structs or enums
     E0212, // cannot extract an associated type from a higher-ranked trait bound
@@ -3695,5 +3705,27 @@ register_diagnostics! {
     E0399, // trait items need to be implemented because the associated
            // type `{}` was overridden
     E0436, // functional record update requires a struct
-    E0513  // no type for local variable ..
+    E0513, // no type for local variable ..
+    E0523, // `#[track_caller]` on something other than a function
+    E0524, // `#[track_caller]` on a function with a non-Rust ABI
+    E0525, // `#[track_caller]` function converted to a function pointer
+    E0526, // projection from a generic associated type
+    E0527, // unsupported use of a generic associated type in its declaration
+//  E0528, // associated type shadows one declared in a supertrait, now a lint
+    E0529, // equality constraint without an associated type
+    E0530, // `#[default_bound]` trait without a default impl or with parameters
+    E0532, // the type of a const parameter must be an integer or `bool`
+    E0533, // `impl Trait` not allowed outside of function and inherent method return types
+    E0534, // `impl Trait` cannot capture lifetimes late-bound to the enclosing function
+    E0535, // cannot determine the concrete type behind an `impl Trait`
+    E0536, // union field needs to be dropped
+    E0537, // union expression without exactly one field
+    E0538, // pattern matching on a union
+    E0539, // impl of a trait alias
+    E0540, // specializing impl overrides an item that isn't `default`
+    E0541, // wrong number of const arguments
+    E0542, // const parameter used as a value outside an array length
+    E0544, // the concrete type behind an `impl Trait` contains itself
+    E0545, // const argument out of range for the type of its parameter
+    E0546, // slice pattern on an array whose length is a const parameter
 }
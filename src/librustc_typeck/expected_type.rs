@@ -0,0 +1,52 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Answers "what type is expected here?" for a position in a function body,
+//! after type checking, so that tools can rank completions by whether they
+//! fit. The answer comes from the `expected_types` table, which typeck only
+//! fills in under `-Z expected-type`.
+
+use middle::ty::{Ty, TyCtxt};
+use rustc_front::hir;
+use rustc_front::intravisit::{self, Visitor};
+use syntax::codemap::{BytePos, Span};
+
+/// The innermost expression around `pos` that was checked against a known
+/// type, with that type. Positions at either end of an expression count as
+/// inside it, so that a cursor just after an identifier still finds it.
+pub fn expected_type_at<'tcx>(tcx: &TyCtxt<'tcx>, pos: BytePos) -> Option<(Span, Ty<'tcx>)> {
+    let mut finder = ExpectedTypeFinder { tcx: tcx, pos: pos, found: None };
+    tcx.map.krate().visit_all_items(&mut finder);
+    finder.found
+}
+
+struct ExpectedTypeFinder<'a, 'tcx: 'a> {
+    tcx: &'a TyCtxt<'tcx>,
+    pos: BytePos,
+    found: Option<(Span, Ty<'tcx>)>,
+}
+
+impl<'a, 'tcx, 'v> Visitor<'v> for ExpectedTypeFinder<'a, 'tcx> {
+    fn visit_expr(&mut self, expr: &'v hir::Expr) {
+        if expr.span.lo > self.pos || expr.span.hi < self.pos {
+            return;
+        }
+        if let Some(&ty) = self.tcx.tables.borrow().expected_types.get(&expr.id) {
+            let innermost = match self.found {
+                Some((span, _)) => expr.span.hi - expr.span.lo <= span.hi - span.lo,
+                None => true,
+            };
+            if innermost {
+                self.found = Some((expr.span, ty));
+            }
+        }
+        intravisit::walk_expr(self, expr);
+    }
+}
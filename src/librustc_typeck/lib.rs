@@ -121,11 +121,12 @@ pub mod diagnostics;
 
 pub mod check;
 mod rscope;
-mod astconv;
+pub mod astconv;
 pub mod collect;
 mod constrained_type_params;
 pub mod coherence;
 pub mod variance;
+pub mod expected_type;
 
 pub struct TypeAndSubsts<'tcx> {
     pub substs: Substs<'tcx>,
@@ -343,22 +344,33 @@ pub fn check_crate(tcx: &TyCtxt, trait_map: ty::TraitMap) -> CompileResult {
 
     }));
 
-    time(time_passes, "variance inference", ||
-         variance::infer_variance(tcx));
+    // From here on, variances of local items are inferred the first time
+    // anyone asks for them, e.g. coherence or wf checking relating types.
+    tcx.variance_provider.set(Some(variance::infer_variance));
 
     try!(tcx.sess.track_errors(|| {
       time(time_passes, "coherence checking", ||
           coherence::check_coherence(&ccx));
     }));
 
-    try!(time(time_passes, "wf checking", ||
-        check::check_wf_new(&ccx)));
+    let wf = time(time_passes, "wf checking", ||
+        check::check_wf_new(&ccx));
+
+    // Nothing may have asked for variances yet if the crate defines no
+    // types; make sure `#[rustc_variance]` is reported either way.
+    time(time_passes, "variance inference", ||
+         variance::infer_variance(tcx));
+    try!(wf);
 
     try!(time(time_passes, "item-types checking", ||
         check::check_item_types(&ccx)));
 
-    try!(time(time_passes, "item-bodies checking", ||
-        check::check_item_bodies(&ccx)));
+    let bodies = time(time_passes, "item-bodies checking", ||
+        check::check_item_bodies(&ccx));
+    if bodies.is_err() && tcx.tracks_err_types() {
+        tcx.report_err_types("an error type spread to here");
+    }
+    try!(bodies);
 
     try!(time(time_passes, "drop-impl checking", ||
         check::check_drop_impls(&ccx)));
@@ -9,10 +9,12 @@
 // except according to those terms.
 
 
-use middle::ty;
+use middle::subst::{self, Substs, VecPerParamSpace};
+use middle::ty::{self, TyCtxt};
 
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
 use syntax::codemap::Span;
+use syntax::parse::token;
 
 #[derive(Clone)]
 pub struct ElisionFailureInfo {
@@ -50,6 +52,85 @@ pub trait RegionScope {
     /// computing `object_lifetime_default` (in particular, in legacy
     /// modes, it may not be relevant).
     fn base_object_lifetime_default(&self, span: Span) -> ty::Region;
+
+    /// Whether the lifetime bound of an object type is inferred in this
+    /// scope rather than fixed by a default, i.e. whether we are in a fn
+    /// body. If so, an object type whose default is ambiguous needs no
+    /// explicit bound either.
+    fn infers_object_lifetimes(&self) -> bool {
+        false
+    }
+
+    /// If this scope allows anonymized types (`impl Trait`), returns
+    /// the scope they are created in. This is only the case for the
+    /// return types of functions and inherent methods.
+    fn anon_type_scope(&self) -> Option<&AnonTypeScope> {
+        None
+    }
+}
+
+/// The parameters that an anonymized type closes over, i.e. those of
+/// the function whose return type it appears in.
+#[derive(Clone)]
+pub struct AnonTypeScope {
+    types: VecPerParamSpace<ty::ParamTy>,
+    regions: VecPerParamSpace<ty::Region>,
+}
+
+impl AnonTypeScope {
+    pub fn new(generics: &ty::Generics) -> AnonTypeScope {
+        AnonTypeScope {
+            types: generics.types.map(ty::ParamTy::for_def),
+            regions: generics.regions.map(|def| def.to_early_bound_region()),
+        }
+    }
+
+    /// The substitutions for an anonymized type created in this scope,
+    /// which map each parameter of the function to itself.
+    pub fn fresh_substs<'tcx>(&self, tcx: &TyCtxt<'tcx>) -> Substs<'tcx> {
+        Substs::new(self.types.map(|p| p.to_ty(tcx)), self.regions.clone())
+    }
+}
+
+/// A scope wrapper which optionally allows anonymized types.
+pub struct MaybeWithAnonTypes<R> {
+    base_scope: R,
+    anon_scope: Option<AnonTypeScope>,
+}
+
+impl<R: RegionScope> MaybeWithAnonTypes<R> {
+    pub fn new(base_scope: R, anon_scope: Option<AnonTypeScope>) -> Self {
+        MaybeWithAnonTypes {
+            base_scope: base_scope,
+            anon_scope: anon_scope,
+        }
+    }
+}
+
+impl<R: RegionScope> RegionScope for MaybeWithAnonTypes<R> {
+    fn object_lifetime_default(&self, span: Span) -> Option<ty::Region> {
+        self.base_scope.object_lifetime_default(span)
+    }
+
+    fn base_object_lifetime_default(&self, span: Span) -> ty::Region {
+        self.base_scope.base_object_lifetime_default(span)
+    }
+
+    fn infers_object_lifetimes(&self) -> bool {
+        self.base_scope.infers_object_lifetimes()
+    }
+
+    fn anon_regions(&self,
+                    span: Span,
+                    count: usize)
+                    -> Result<Vec<ty::Region>, Option<Vec<ElisionFailureInfo>>>
+    {
+        self.base_scope.anon_regions(span, count)
+    }
+
+    fn anon_type_scope(&self) -> Option<&AnonTypeScope> {
+        self.anon_scope.as_ref()
+    }
 }
 
 // A scope in which all regions must be explicitly named. This is used
@@ -140,18 +221,27 @@ impl RegionScope for ElidableRscope {
 /// omitted regions. This occurs in function signatures.
 pub struct BindingRscope {
     anon_bindings: Cell<u32>,
+    anon_spans: RefCell<Vec<Span>>,
 }
 
 impl BindingRscope {
     pub fn new() -> BindingRscope {
         BindingRscope {
             anon_bindings: Cell::new(0),
+            anon_spans: RefCell::new(Vec::new()),
         }
     }
 
-    fn next_region(&self) -> ty::Region {
+    /// The span at which each anonymous region was generated, indexed by
+    /// its `BrAnon` number.
+    pub fn anon_region_spans(&self) -> Vec<Span> {
+        self.anon_spans.borrow().clone()
+    }
+
+    fn next_region(&self, span: Span) -> ty::Region {
         let idx = self.anon_bindings.get();
         self.anon_bindings.set(idx + 1);
+        self.anon_spans.borrow_mut().push(span);
         ty::ReLateBound(ty::DebruijnIndex::new(1), ty::BrAnon(idx))
     }
 }
@@ -168,6 +258,65 @@ impl RegionScope for BindingRscope {
         ty::ReStatic
     }
 
+    fn anon_regions(&self,
+                    span: Span,
+                    count: usize)
+                    -> Result<Vec<ty::Region>, Option<Vec<ElisionFailureInfo>>>
+    {
+        Ok((0..count).map(|_| self.next_region(span)).collect())
+    }
+}
+
+/// A scope in which each omitted region in an impl header becomes a
+/// fresh, anonymous lifetime parameter of the impl. This lets one
+/// write `impl Trait for &Foo` as shorthand for
+/// `impl<'a> Trait for &'a Foo`. The caller is expected to collect
+/// the generated parameters with `anon_region_params` and add them to
+/// the generics of the impl.
+pub struct ImplHeaderRscope {
+    first_index: u32,
+    anon_params: Cell<u32>,
+}
+
+impl ImplHeaderRscope {
+    /// `first_index` is the number of lifetimes declared explicitly on
+    /// the impl; anonymous lifetimes are numbered after them.
+    pub fn new(first_index: u32) -> ImplHeaderRscope {
+        ImplHeaderRscope {
+            first_index: first_index,
+            anon_params: Cell::new(0),
+        }
+    }
+
+    /// Returns the anonymous regions created so far, in order.
+    pub fn anon_region_params(&self) -> Vec<ty::EarlyBoundRegion> {
+        (0..self.anon_params.get()).map(|i| self.early_bound_region(i)).collect()
+    }
+
+    fn early_bound_region(&self, i: u32) -> ty::EarlyBoundRegion {
+        ty::EarlyBoundRegion {
+            space: subst::TypeSpace,
+            index: self.first_index + i,
+            name: token::intern(&format!("'_{}", i)),
+        }
+    }
+
+    fn next_region(&self) -> ty::Region {
+        let idx = self.anon_params.get();
+        self.anon_params.set(idx + 1);
+        ty::ReEarlyBound(self.early_bound_region(idx))
+    }
+}
+
+impl RegionScope for ImplHeaderRscope {
+    fn object_lifetime_default(&self, span: Span) -> Option<ty::Region> {
+        Some(self.base_object_lifetime_default(span))
+    }
+
+    fn base_object_lifetime_default(&self, _span: Span) -> ty::Region {
+        ty::ReStatic
+    }
+
     fn anon_regions(&self,
                     _: Span,
                     count: usize)
@@ -198,8 +347,13 @@ impl<'r> ObjectLifetimeDefaultRscope<'r> {
 impl<'r> RegionScope for ObjectLifetimeDefaultRscope<'r> {
     fn object_lifetime_default(&self, span: Span) -> Option<ty::Region> {
         match self.default {
+            // Only an error if the bound is not going to be inferred anyway.
             ty::ObjectLifetimeDefault::Ambiguous =>
-                None,
+                if self.infers_object_lifetimes() {
+                    Some(self.base_object_lifetime_default(span))
+                } else {
+                    None
+                },
 
             ty::ObjectLifetimeDefault::BaseDefault =>
                 // NB: This behavior changed in Rust 1.3.
@@ -214,6 +368,10 @@ impl<'r> RegionScope for ObjectLifetimeDefaultRscope<'r> {
         self.base_scope.base_object_lifetime_default(span)
     }
 
+    fn infers_object_lifetimes(&self) -> bool {
+        self.base_scope.infers_object_lifetimes()
+    }
+
     fn anon_regions(&self,
                     span: Span,
                     count: usize)
@@ -221,6 +379,10 @@ impl<'r> RegionScope for ObjectLifetimeDefaultRscope<'r> {
     {
         self.base_scope.anon_regions(span, count)
     }
+
+    fn anon_type_scope(&self) -> Option<&AnonTypeScope> {
+        self.base_scope.anon_type_scope()
+    }
 }
 
 /// A scope which simply shifts the Debruijn index of other scopes
@@ -245,6 +407,10 @@ impl<'r> RegionScope for ShiftedRscope<'r> {
         ty::fold::shift_region(self.base_scope.base_object_lifetime_default(span), 1)
     }
 
+    fn infers_object_lifetimes(&self) -> bool {
+        self.base_scope.infers_object_lifetimes()
+    }
+
     fn anon_regions(&self,
                     span: Span,
                     count: usize)
@@ -262,4 +428,8 @@ impl<'r> RegionScope for ShiftedRscope<'r> {
             }
         }
     }
+
+    fn anon_type_scope(&self) -> Option<&AnonTypeScope> {
+        self.base_scope.anon_type_scope()
+    }
 }
@@ -82,7 +82,7 @@ impl<'a, 'tcx, 'v> Visitor<'v> for ConstraintContext<'a, 'tcx> {
         debug!("visit_item item={}", tcx.map.node_to_string(item.id));
 
         match item.node {
-            hir::ItemEnum(..) | hir::ItemStruct(..) => {
+            hir::ItemEnum(..) | hir::ItemStruct(..) | hir::ItemUnion(..) => {
                 let scheme = tcx.lookup_item_type(did);
 
                 // Not entirely obvious: constraints on structs/enums do not
@@ -97,7 +97,7 @@ impl<'a, 'tcx, 'v> Visitor<'v> for ConstraintContext<'a, 'tcx> {
                                                  self.covariant);
                 }
             }
-            hir::ItemTrait(..) => {
+            hir::ItemTrait(..) | hir::ItemTraitAlias(..) => {
                 let trait_def = tcx.lookup_trait_def(did);
                 self.add_constraints_from_trait_ref(&trait_def.generics,
                                                     trait_def.trait_ref,
@@ -188,7 +188,9 @@ impl<'a, 'tcx> ConstraintContext<'a, 'tcx> {
                         hir::ItemTy(..) |
                         hir::ItemEnum(..) |
                         hir::ItemStruct(..) |
-                        hir::ItemTrait(..)   => is_inferred = true,
+                        hir::ItemUnion(..) |
+                        hir::ItemTrait(..) |
+                        hir::ItemTraitAlias(..) => is_inferred = true,
                         hir::ItemFn(..)      => is_inferred = false,
                         _                    => cannot_happen!(),
                     }
@@ -337,7 +339,7 @@ impl<'a, 'tcx> ConstraintContext<'a, 'tcx> {
                 self.add_constraints_from_mt(generics, mt, variance);
             }
 
-            ty::TyBox(typ) | ty::TyArray(typ, _) | ty::TySlice(typ) => {
+            ty::TyBox(typ) | ty::TyArray(typ, _) | ty::TyParamArray(typ, _) | ty::TySlice(typ) => {
                 self.add_constraints_from_ty(generics, typ, variance);
             }
 
@@ -395,6 +397,18 @@ impl<'a, 'tcx> ConstraintContext<'a, 'tcx> {
                     variance);
             }
 
+            ty::TyAnon(_, substs) => {
+                // The concrete type behind an `impl Trait` may use the
+                // parameters it closes over in any way, so they are all
+                // invariant.
+                for &ty in substs.types.iter() {
+                    self.add_constraints_from_ty(generics, ty, self.invariant);
+                }
+                for &region in substs.regions().iter() {
+                    self.add_constraints_from_region(generics, region, self.invariant);
+                }
+            }
+
             ty::TyTrait(ref data) => {
                 let poly_trait_ref =
                     data.principal_trait_ref_with_self_ty(self.tcx(),
@@ -27,7 +27,15 @@ mod solve;
 /// Code for transforming variances.
 mod xform;
 
+/// Infers the variance of every type and lifetime parameter in the crate
+/// and records the results in `tcx.item_variance_map`. Does nothing if
+/// that has already happened, e.g. because someone asked for the
+/// variances of a local item through `tcx.item_variances`.
 pub fn infer_variance(tcx: &TyCtxt) {
+    if tcx.variance_computed.get() {
+        return;
+    }
+
     let mut arena = arena::TypedArena::new();
     let terms_cx = terms::determine_parameters_to_be_inferred(tcx, &mut arena);
     let constraints_cx = constraints::add_constraints_from_crate(terms_cx);
@@ -109,6 +109,7 @@ impl<'a, 'tcx> SolveContext<'a, 'tcx> {
         let num_inferred = self.terms_cx.num_inferred();
         while index < num_inferred {
             let item_id = inferred_infos[index].item_id;
+            let item_start = index;
             let mut types = VecPerParamSpace::empty();
             let mut regions = VecPerParamSpace::empty();
 
@@ -136,9 +137,25 @@ impl<'a, 'tcx> SolveContext<'a, 'tcx> {
             let item_def_id = tcx.map.local_def_id(item_id);
 
             // For unit testing: check for a special "rustc_variance"
-            // attribute and report an error with various results if found.
+            // attribute and report an error with various results if found,
+            // with a note on each parameter giving its variance.
             if tcx.has_attr(item_def_id, "rustc_variance") {
-                span_err!(tcx.sess, tcx.map.span(item_id), E0208, "{:?}", item_variances);
+                let mut err = struct_span_err!(tcx.sess, tcx.map.span(item_id), E0208,
+                                               "{:?}", item_variances);
+                for i in item_start..index {
+                    let param_id = inferred_infos[i].param_id;
+                    let (name, span) = if param_id == item_id {
+                        // The implicit `Self` parameter of a trait.
+                        ("Self".to_string(), tcx.map.span(item_id))
+                    } else {
+                        (tcx.map.get_path_elem(param_id).name().to_string(),
+                         tcx.map.span(param_id))
+                    };
+                    err.span_note(span, &format!("`{}` is {}",
+                                                 name,
+                                                 variance_name(solutions[i])));
+                }
+                err.emit();
             }
 
             let newly_added = tcx.item_variance_map.borrow_mut()
@@ -165,3 +182,12 @@ impl<'a, 'tcx> SolveContext<'a, 'tcx> {
         }
     }
 }
+
+fn variance_name(v: ty::Variance) -> &'static str {
+    match v {
+        ty::Covariant => "covariant",
+        ty::Contravariant => "contravariant",
+        ty::Invariant => "invariant",
+        ty::Bivariant => "bivariant",
+    }
+}
@@ -187,10 +187,12 @@ fn build_external_function(cx: &DocContext, tcx: &TyCtxt, did: DefId) -> clean::
 fn build_struct(cx: &DocContext, tcx: &TyCtxt, did: DefId) -> clean::Struct {
     let t = tcx.lookup_item_type(did);
     let predicates = tcx.lookup_predicates(did);
-    let variant = tcx.lookup_adt_def(did).struct_variant();
+    let def = tcx.lookup_adt_def(did);
+    let variant = def.struct_variant();
 
     clean::Struct {
         struct_type: match &*variant.fields {
+            _ if def.is_union() => doctree::Union,
             [] => doctree::Unit,
             [_] if variant.kind == ty::VariantKind::Tuple => doctree::Newtype,
             [..] if variant.kind == ty::VariantKind::Tuple => doctree::Tuple,
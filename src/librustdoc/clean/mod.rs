@@ -205,7 +205,7 @@ impl<'a, 'tcx> Clean<Crate> for visit_ast::RustdocVisitor<'a, 'tcx> {
                     current_dir().unwrap().join(path)
                 }
             },
-            Input::Str(_) => PathBuf::new() // FIXME: this is wrong
+            Input::Str(_) | Input::ExpandedCrate(_) => PathBuf::new() // FIXME: this is wrong
         };
 
         Crate {
@@ -822,8 +822,11 @@ impl Clean<WherePredicate> for hir::WherePredicate {
                 }
             }
 
-            hir::WherePredicate::EqPredicate(_) => {
-                unimplemented!() // FIXME(#20041)
+            hir::WherePredicate::EqPredicate(ref wep) => {
+                WherePredicate::EqPredicate {
+                    lhs: wep.lhs_ty.clean(cx),
+                    rhs: wep.rhs_ty.clean(cx)
+                }
             }
         }
     }
@@ -1271,7 +1274,7 @@ impl Clean<Item> for hir::TraitItem {
             hir::MethodTraitItem(ref sig, None) => {
                 TyMethodItem(sig.clean(cx))
             }
-            hir::TypeTraitItem(ref bounds, ref default) => {
+            hir::TypeTraitItem(_, ref bounds, ref default) => {
                 AssociatedTypeItem(bounds.clean(cx), default.clean(cx))
             }
         };
@@ -1457,6 +1460,9 @@ pub enum Type {
 
     // for<'a> Foo(&'a)
     PolyTraitRef(Vec<TyParamBound>),
+
+    // impl TraitA+TraitB
+    ImplTrait(Vec<TyParamBound>),
 }
 
 #[derive(Clone, RustcEncodable, RustcDecodable, PartialEq, Eq, Hash, Copy, Debug)]
@@ -1631,6 +1637,7 @@ impl Clean<Type> for hir::Ty {
             }
             TyBareFn(ref barefn) => BareFunction(box barefn.clean(cx)),
             TyPolyTraitRef(ref bounds) => PolyTraitRef(bounds.clean(cx)),
+            TyImplTrait(ref bounds) => ImplTrait(bounds.clean(cx)),
             TyInfer => Infer,
             TyTypeof(..) => panic!("Unimplemented type {:?}", self.node),
         }
@@ -1664,6 +1671,8 @@ impl<'tcx> Clean<Type> for ty::Ty<'tcx> {
             ty::TySlice(ty) => Vector(box ty.clean(cx)),
             ty::TyArray(ty, i) => FixedVector(box ty.clean(cx),
                                               format!("{}", i)),
+            ty::TyParamArray(ty, p) => FixedVector(box ty.clean(cx),
+                                                   p.name.to_string()),
             ty::TyRawPtr(mt) => RawPointer(mt.mutbl.clean(cx), box mt.ty.clean(cx)),
             ty::TyRef(r, mt) => BorrowedRef {
                 lifetime: r.clean(cx),
@@ -1715,6 +1724,18 @@ impl<'tcx> Clean<Type> for ty::Ty<'tcx> {
 
             ty::TyProjection(ref data) => data.clean(cx),
 
+            ty::TyAnon(def_id, substs) => {
+                // Grab the "TraitA + TraitB" from `impl TraitA + TraitB`,
+                // by looking up the bounds recorded for the anonymized type.
+                let tcx = cx.tcx();
+                let predicates = tcx.lookup_predicates(def_id).instantiate(tcx, substs);
+                ImplTrait(predicates.predicates.into_vec().into_iter()
+                                    .filter_map(|p| p.to_opt_poly_trait_ref())
+                                    .filter(|t| Some(t.def_id()) != tcx.lang_items.sized_trait())
+                                    .map(|t| t.0.clean(cx))
+                                    .collect())
+            }
+
             ty::TyParam(ref p) => Generic(p.name.to_string()),
 
             ty::TyClosure(..) => Tuple(vec![]), // FIXME(pcwalton)
@@ -1963,6 +1984,11 @@ impl Clean<Span> for syntax::codemap::Span {
         let filename = cm.span_to_filename(*self);
         let lo = cm.lookup_char_pos(self.lo);
         let hi = cm.lookup_char_pos(self.hi);
+        // Items expanded from macros of other crates point into those crates'
+        // sources, which we have no way to render.
+        if lo.file.is_imported() {
+            return Span::empty();
+        }
         Span {
             filename: filename.to_string(),
             loline: lo.line,
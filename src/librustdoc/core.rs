@@ -154,6 +154,7 @@ pub fn run_core(search_paths: SearchPaths, cfgs: Vec<String>, externs: Externs,
                                                      &arenas,
                                                      &name,
                                                      resolve::MakeGlobMap::No,
+                                                     &|_, _, _| {},
                                                      |tcx, _, analysis, result| {
         // Return if the driver hit an err (in `result`)
         if let Err(_) = result {
@@ -168,7 +169,8 @@ pub fn run_core(search_paths: SearchPaths, cfgs: Vec<String>, externs: Externs,
         let access_levels = AccessLevels {
             map: access_levels.map.into_iter()
                                   .map(|(k, v)| (tcx.map.local_def_id(k), v))
-                                  .collect()
+                                  .collect(),
+            reasons: Default::default(),
         };
 
         let ctxt = DocContext {
@@ -87,7 +87,9 @@ pub enum StructType {
     /// A newtype struct (tuple struct with one element)
     Newtype,
     /// A unit struct
-    Unit
+    Unit,
+    /// An untagged union
+    Union
 }
 
 pub enum TypeBound {
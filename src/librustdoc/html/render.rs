@@ -1178,7 +1178,7 @@ impl DocFolder for Cache {
     }
 }
 
-impl<'a> Cache {
+impl Cache {
     fn generics(&mut self, generics: &clean::Generics) {
         for typ in &generics.type_params {
             self.typarams.insert(typ.did, typ.name.clone());
@@ -2148,7 +2148,11 @@ fn item_struct(w: &mut fmt::Formatter, cx: &Context, it: &clean::Item,
             _ => false,
         }
     }).peekable();
-    if let doctree::Plain = s.struct_type {
+    let named_fields = match s.struct_type {
+        doctree::Plain | doctree::Union => true,
+        _ => false,
+    };
+    if named_fields {
         if fields.peek().is_some() {
             try!(write!(w, "<h2 class='fields'>Fields</h2>\n<table>"));
             for field in fields {
@@ -2280,15 +2284,19 @@ fn render_struct(w: &mut fmt::Formatter, it: &clean::Item,
                  fields: &[clean::Item],
                  tab: &str,
                  structhead: bool) -> fmt::Result {
+    let head = match ty {
+        doctree::Union => "union ",
+        _ => "struct ",
+    };
     try!(write!(w, "{}{}{}",
                   VisSpace(it.visibility),
-                  if structhead {"struct "} else {""},
+                  if structhead {head} else {""},
                   it.name.as_ref().unwrap()));
     if let Some(g) = g {
         try!(write!(w, "{}{}", *g, WhereClause(g)))
     }
     match ty {
-        doctree::Plain => {
+        doctree::Plain | doctree::Union => {
             try!(write!(w, " {{\n{}", tab));
             let mut fields_stripped = false;
             for field in fields {
@@ -30,7 +30,6 @@
 #![feature(test)]
 #![feature(unicode)]
 
-extern crate arena;
 extern crate getopts;
 extern crate libc;
 extern crate rustc;
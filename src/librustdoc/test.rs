@@ -267,7 +267,7 @@ fn runtest(test: &str, cratename: &str, cfgs: Vec<String>, libs: SearchPaths,
     } {
         Ok(r) => {
             match r {
-                Err(count) if count > 0 && compile_fail == false => {
+                Err(_) if compile_fail == false => {
                     sess.fatal("aborting due to previous error(s)")
                 }
                 Ok(()) if compile_fail => panic!("test compiled while it wasn't supposed to"),
@@ -339,6 +339,11 @@ impl<'a, 'tcx> RustdocVisitor<'a, 'tcx> {
                 om.enums.push(self.visit_enum_def(item, name, ed, gen)),
             hir::ItemStruct(ref sd, ref gen) =>
                 om.structs.push(self.visit_variant_data(item, name, sd, gen)),
+            hir::ItemUnion(ref sd, ref gen) => {
+                let mut s = self.visit_variant_data(item, name, sd, gen);
+                s.struct_type = Union;
+                om.structs.push(s);
+            }
             hir::ItemFn(ref fd, ref unsafety, constness, ref abi, ref gen, _) =>
                 om.fns.push(self.visit_fn(item, name, &**fd, unsafety,
                                           constness, abi, gen)),
@@ -401,6 +406,22 @@ impl<'a, 'tcx> RustdocVisitor<'a, 'tcx> {
                 };
                 om.traits.push(t);
             },
+            hir::ItemTraitAlias(ref gen, ref b) => {
+                let t = Trait {
+                    unsafety: hir::Unsafety::Normal,
+                    name: name,
+                    items: hir::HirVec::new(),
+                    generics: gen.clone(),
+                    bounds: b.iter().cloned().collect(),
+                    id: item.id,
+                    attrs: item.attrs.clone(),
+                    whence: item.span,
+                    vis: item.vis,
+                    stab: self.stability(item.id),
+                    depr: self.deprecation(item.id),
+                };
+                om.traits.push(t);
+            },
             hir::ItemImpl(unsafety, polarity, ref gen, ref tr, ref ty, ref items) => {
                 let i = Impl {
                     unsafety: unsafety,
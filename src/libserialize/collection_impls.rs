@@ -66,7 +66,7 @@ impl<T:Decodable> Decodable for VecDeque<T> {
 }
 
 impl<
-    K: Encodable + PartialEq + Ord,
+    K: Encodable + Ord,
     V: Encodable + PartialEq
 > Encodable for BTreeMap<K, V> {
     fn encode<S: Encoder>(&self, e: &mut S) -> Result<(), S::Error> {
@@ -83,7 +83,7 @@ impl<
 }
 
 impl<
-    K: Decodable + PartialEq + Ord,
+    K: Decodable + Ord,
     V: Decodable + PartialEq
 > Decodable for BTreeMap<K, V> {
     fn decode<D: Decoder>(d: &mut D) -> Result<BTreeMap<K, V>, D::Error> {
@@ -100,7 +100,7 @@ impl<
 }
 
 impl<
-    T: Encodable + PartialEq + Ord
+    T: Encodable + Ord
 > Encodable for BTreeSet<T> {
     fn encode<S: Encoder>(&self, s: &mut S) -> Result<(), S::Error> {
         s.emit_seq(self.len(), |s| {
@@ -115,7 +115,7 @@ impl<
 }
 
 impl<
-    T: Decodable + PartialEq + Ord
+    T: Decodable + Ord
 > Decodable for BTreeSet<T> {
     fn decode<D: Decoder>(d: &mut D) -> Result<BTreeSet<T>, D::Error> {
         d.read_seq(|d, len| {
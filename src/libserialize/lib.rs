@@ -38,9 +38,7 @@ Core encoding and decoding interfaces.
 
 // test harness access
 #[cfg(test)] extern crate test;
-#[macro_use] extern crate log;
 
-extern crate rustc_unicode;
 extern crate collections;
 
 pub use self::serialize::{Decoder, Encoder, Decodable, Encodable,
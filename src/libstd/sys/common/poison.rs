@@ -86,6 +86,7 @@ pub enum TryLockError<T> {
 /// the associated guard, and it can be acquired through the `into_inner`
 /// method.
 #[stable(feature = "rust1", since = "1.0.0")]
+#[allow(type_param_shadowing)]
 pub type LockResult<Guard> = Result<Guard, PoisonError<Guard>>;
 
 /// A type alias for the result of a nonblocking locking method.
@@ -94,6 +95,7 @@ pub type LockResult<Guard> = Result<Guard, PoisonError<Guard>>;
 /// necessarily hold the associated guard in the `Err` type as the lock may not
 /// have been acquired for other reasons.
 #[stable(feature = "rust1", since = "1.0.0")]
+#[allow(type_param_shadowing)]
 pub type TryLockResult<Guard> = Result<Guard, TryLockError<Guard>>;
 
 #[stable(feature = "rust1", since = "1.0.0")]
@@ -232,6 +232,7 @@ impl PathParameters {
         PathParameters::AngleBracketed(AngleBracketedParameterData {
             lifetimes: Vec::new(),
             types: P::empty(),
+            consts: P::empty(),
             bindings: P::empty(),
         })
     }
@@ -305,6 +306,9 @@ pub struct AngleBracketedParameterData {
     pub lifetimes: Vec<Lifetime>,
     /// The type parameters for this path segment, if present.
     pub types: P<[P<Ty>]>,
+    /// The values of const parameters for this path segment, if present.
+    /// e.g., the `3` and `{N}` of `Grid<3, {N}>`.
+    pub consts: P<[P<Expr>]>,
     /// Bindings (equality constraints) on associated types, if present.
     /// e.g., `Foo<A=Bar>`.
     pub bindings: P<[TypeBinding]>,
@@ -312,7 +316,8 @@ pub struct AngleBracketedParameterData {
 
 impl AngleBracketedParameterData {
     fn is_empty(&self) -> bool {
-        self.lifetimes.is_empty() && self.types.is_empty() && self.bindings.is_empty()
+        self.lifetimes.is_empty() && self.types.is_empty() && self.consts.is_empty() &&
+            self.bindings.is_empty()
     }
 }
 
@@ -375,12 +380,23 @@ pub struct TyParam {
     pub span: Span
 }
 
-/// Represents lifetimes and type parameters attached to a declaration
-/// of a function, enum, trait, etc.
+/// A const parameter, `const N: usize` (experimental, see the
+/// `const_generics` feature).
+#[derive(Clone, PartialEq, Eq, RustcEncodable, RustcDecodable, Hash, Debug)]
+pub struct ConstParam {
+    pub ident: Ident,
+    pub id: NodeId,
+    pub ty: P<Ty>,
+    pub span: Span
+}
+
+/// Represents lifetimes, type parameters and const parameters attached to a
+/// declaration of a function, enum, trait, etc.
 #[derive(Clone, PartialEq, Eq, RustcEncodable, RustcDecodable, Hash, Debug)]
 pub struct Generics {
     pub lifetimes: Vec<LifetimeDef>,
     pub ty_params: P<[TyParam]>,
+    pub const_params: P<[ConstParam]>,
     pub where_clause: WhereClause,
 }
 
@@ -388,8 +404,10 @@ impl Generics {
     pub fn is_lt_parameterized(&self) -> bool {
         !self.lifetimes.is_empty()
     }
+    /// Const parameters count as well: like type parameters, they have to
+    /// be instantiated before the item can be translated.
     pub fn is_type_parameterized(&self) -> bool {
-        !self.ty_params.is_empty()
+        !self.ty_params.is_empty() || !self.const_params.is_empty()
     }
     pub fn is_parameterized(&self) -> bool {
         self.is_lt_parameterized() || self.is_type_parameterized()
@@ -401,6 +419,7 @@ impl Default for Generics {
         Generics {
             lifetimes: Vec::new(),
             ty_params: P::empty(),
+            const_params: P::empty(),
             where_clause: WhereClause {
                 id: DUMMY_NODE_ID,
                 predicates: Vec::new(),
@@ -423,7 +442,7 @@ pub enum WherePredicate {
     BoundPredicate(WhereBoundPredicate),
     /// A lifetime predicate, e.g. `'a: 'b+'c`
     RegionPredicate(WhereRegionPredicate),
-    /// An equality predicate (unsupported)
+    /// An equality predicate, e.g. `T::Item == u32`
     EqPredicate(WhereEqPredicate),
 }
 
@@ -447,13 +466,12 @@ pub struct WhereRegionPredicate {
     pub bounds: Vec<Lifetime>,
 }
 
-/// An equality predicate (unsupported), e.g. `T=int`
+/// An equality predicate, e.g. `T::Item == u32`
 #[derive(Clone, PartialEq, Eq, RustcEncodable, RustcDecodable, Hash, Debug)]
 pub struct WhereEqPredicate {
-    pub id: NodeId,
     pub span: Span,
-    pub path: Path,
-    pub ty: P<Ty>,
+    pub lhs_ty: P<Ty>,
+    pub rhs_ty: P<Ty>,
 }
 
 /// The set of MetaItems that define the compilation environment of the crate,
@@ -1345,7 +1363,9 @@ pub struct TraitItem {
 pub enum TraitItemKind {
     Const(P<Ty>, Option<P<Expr>>),
     Method(MethodSig, Option<P<Block>>),
-    Type(TyParamBounds, Option<P<Ty>>),
+    /// An associated type, possibly with generic parameters of its own
+    /// (`type Item<T>: Bound where T: Clone;`).
+    Type(Generics, TyParamBounds, Option<P<Ty>>),
 }
 
 #[derive(Clone, PartialEq, Eq, RustcEncodable, RustcDecodable, Hash, Debug)]
@@ -1353,6 +1373,7 @@ pub struct ImplItem {
     pub id: NodeId,
     pub ident: Ident,
     pub vis: Visibility,
+    pub defaultness: Defaultness,
     pub attrs: Vec<Attribute>,
     pub node: ImplItemKind,
     pub span: Span,
@@ -1568,6 +1589,9 @@ pub enum TyKind {
     ObjectSum(P<Ty>, TyParamBounds),
     /// A type like `for<'a> Foo<&'a Bar>`
     PolyTraitRef(TyParamBounds),
+    /// An `impl Bound1 + Bound2 + Bound3` type
+    /// where `Bound` is a trait or a lifetime.
+    ImplTrait(TyParamBounds),
     /// No-op; kept solely so that we can pretty-print faithfully
     Paren(P<Ty>),
     /// Unused for now
@@ -1654,6 +1678,19 @@ pub enum Constness {
     NotConst,
 }
 
+/// Whether an impl item may be overridden by a more specialized impl.
+#[derive(Copy, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable, Hash, Debug)]
+pub enum Defaultness {
+    Default,
+    Final,
+}
+
+impl Defaultness {
+    pub fn is_default(&self) -> bool {
+        *self == Defaultness::Default
+    }
+}
+
 impl fmt::Display for Unsafety {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         fmt::Display::fmt(match *self {
@@ -1748,6 +1785,9 @@ pub struct Variant_ {
     pub data: VariantData,
     /// Explicit discriminant, eg `Foo = 1`
     pub disr_expr: Option<P<Expr>>,
+    /// Bounds that only hold for this variant, eg `Foo(T) where T: Copy`.
+    /// Only accepted with `#![feature(variant_where_clauses)]`.
+    pub where_clause: WhereClause,
 }
 
 pub type Variant = Spanned<Variant_>;
@@ -2001,11 +2041,15 @@ pub enum ItemKind {
     Enum(EnumDef, Generics),
     /// A struct definition, e.g. `struct Foo<A> {x: A}`
     Struct(VariantData, Generics),
+    /// An untagged union definition, e.g. `union Foo<A> {x: A, y: u32}`
+    Union(VariantData, Generics),
     /// Represents a Trait Declaration
     Trait(Unsafety,
               Generics,
               TyParamBounds,
               Vec<TraitItem>),
+    /// A trait alias, e.g. `trait Foo = Bar + Send;`
+    TraitAlias(Generics, TyParamBounds),
 
     // Default trait implementations
     ///
@@ -2035,7 +2079,9 @@ impl ItemKind {
             ItemKind::Ty(..) => "type alias",
             ItemKind::Enum(..) => "enum",
             ItemKind::Struct(..) => "struct",
+            ItemKind::Union(..) => "union",
             ItemKind::Trait(..) => "trait",
+            ItemKind::TraitAlias(..) => "trait alias",
             ItemKind::Mac(..) |
             ItemKind::Impl(..) |
             ItemKind::DefaultImpl(..) => "item"
@@ -43,6 +43,7 @@ pub fn ident_to_path(s: Span, identifier: Ident) -> Path {
                 parameters: ast::PathParameters::AngleBracketed(ast::AngleBracketedParameterData {
                     lifetimes: Vec::new(),
                     types: P::empty(),
+                    consts: P::empty(),
                     bindings: P::empty(),
                 })
             }
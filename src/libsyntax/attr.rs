@@ -47,6 +47,13 @@ pub fn mark_used(attr: &Attribute) {
     });
 }
 
+/// Forgets which attributes have been marked as used and restarts attribute
+/// ids from zero. Only valid once no attribute from an earlier crate is live.
+pub fn reset_used_attrs() {
+    USED_ATTRS.with(|slot| slot.borrow_mut().clear());
+    NEXT_ATTR_ID.with(|slot| slot.set(0));
+}
+
 pub fn is_used(attr: &Attribute) -> bool {
     let AttrId(id) = attr.node.id;
     USED_ATTRS.with(|slot| {
@@ -364,6 +371,50 @@ pub fn requests_inline(attrs: &[Attribute]) -> bool {
     }
 }
 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum OptimizeAttr {
+    /// No `#[optimize]`: the function is optimized like the rest of the crate.
+    Default,
+    Size,
+    Speed,
+    /// `#[optimize(none)]`
+    Off,
+}
+
+/// Determine what `#[optimize]` attribute is present in `attrs`, if any.
+pub fn find_optimize_attr(diagnostic: Option<&Handler>, attrs: &[Attribute]) -> OptimizeAttr {
+    attrs.iter().fold(OptimizeAttr::Default, |oa, attr| {
+        match attr.node.value.node {
+            MetaItemKind::Word(ref n) if *n == "optimize" => {
+                mark_used(attr);
+                diagnostic.map(|d| {
+                    d.span_err(attr.span, "expected one argument: `size`, `speed` or `none`");
+                });
+                OptimizeAttr::Default
+            }
+            MetaItemKind::List(ref n, ref items) if *n == "optimize" => {
+                mark_used(attr);
+                if items.len() != 1 {
+                    diagnostic.map(|d| {
+                        d.span_err(attr.span, "expected one argument: `size`, `speed` or `none`");
+                    });
+                    OptimizeAttr::Default
+                } else if contains_name(&items[..], "size") {
+                    OptimizeAttr::Size
+                } else if contains_name(&items[..], "speed") {
+                    OptimizeAttr::Speed
+                } else if contains_name(&items[..], "none") {
+                    OptimizeAttr::Off
+                } else {
+                    diagnostic.map(|d| { d.span_err((*items[0]).span, "invalid argument"); });
+                    OptimizeAttr::Default
+                }
+            }
+            _ => oa
+        }
+    })
+}
+
 /// Tests if a cfg-pattern matches the cfg set
 pub fn cfg_matches<T: CfgDiag>(cfgs: &[P<MetaItem>],
                            cfg: &ast::MetaItem,
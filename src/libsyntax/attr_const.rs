@@ -0,0 +1,368 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Constant folding for attribute values.
+//!
+//! Meta items can only carry string literals, so an attribute that wants a
+//! computed value takes it as a small constant expression inside the string,
+//! as in `#![recursion_limit = "2 * 64"]`. The expression language is
+//! deliberately tiny:
+//!
+//! * unsigned integers, in decimal or with a `0x`, `0o` or `0b` prefix, with
+//!   optional `_` separators;
+//! * string literals in double quotes, with the escapes `\"`, `\\`, `\n` and
+//!   `\t`;
+//! * the integer operators `*`, `/`, `%`, `+`, `-`, `<<`, `>>`, `&`, `^` and
+//!   `|`, with Rust's precedence, and parentheses;
+//! * `+` between two strings, which concatenates them.
+//!
+//! Integers are `u64`, and overflow is an error rather than wrapping. Callers
+//! report errors under their own diagnostic codes, so evaluation returns a
+//! plain message rather than emitting anything.
+
+use ast::{LitKind, MetaItem, MetaItemKind};
+
+use std::fmt;
+
+/// The value of a constant attribute expression.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum AttrConst {
+    Int(u64),
+    Str(String),
+}
+
+impl AttrConst {
+    pub fn as_int(&self) -> Option<u64> {
+        match *self {
+            AttrConst::Int(n) => Some(n),
+            AttrConst::Str(_) => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match *self {
+            AttrConst::Int(_) => None,
+            AttrConst::Str(ref s) => Some(s),
+        }
+    }
+}
+
+impl fmt::Display for AttrConst {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            AttrConst::Int(n) => write!(f, "{}", n),
+            AttrConst::Str(ref s) => write!(f, "{:?}", s),
+        }
+    }
+}
+
+/// Evaluates the value of a `name = "..."` meta item.
+pub fn eval_meta_item(item: &MetaItem) -> Result<AttrConst, String> {
+    match item.node {
+        MetaItemKind::NameValue(_, ref lit) => {
+            match lit.node {
+                LitKind::Str(ref s, _) => eval_str(s),
+                LitKind::Int(n, _) => Ok(AttrConst::Int(n)),
+                _ => Err("expected a string or an integer".to_string()),
+            }
+        }
+        MetaItemKind::Word(ref name) | MetaItemKind::List(ref name, _) => {
+            Err(format!("expected a value, as in `{} = \"...\"`", name))
+        }
+    }
+}
+
+/// Evaluates a constant expression.
+pub fn eval_str(expr: &str) -> Result<AttrConst, String> {
+    let tokens = try!(tokenize(expr));
+    let mut parser = Parser { tokens: &tokens, pos: 0 };
+    let value = try!(parser.expr(0));
+    match parser.peek() {
+        None => Ok(value),
+        Some(tok) => Err(format!("unexpected {}", tok)),
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+enum Token {
+    Int(u64),
+    Str(String),
+    Op(BinOp),
+    OpenParen,
+    CloseParen,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Token::Int(n) => write!(f, "`{}`", n),
+            Token::Str(ref s) => write!(f, "`{:?}`", s),
+            Token::Op(op) => write!(f, "`{}`", op.as_str()),
+            Token::OpenParen => write!(f, "`(`"),
+            Token::CloseParen => write!(f, "`)`"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum BinOp {
+    Mul,
+    Div,
+    Rem,
+    Add,
+    Sub,
+    Shl,
+    Shr,
+    BitAnd,
+    BitXor,
+    BitOr,
+}
+
+impl BinOp {
+    fn as_str(self) -> &'static str {
+        match self {
+            BinOp::Mul => "*",
+            BinOp::Div => "/",
+            BinOp::Rem => "%",
+            BinOp::Add => "+",
+            BinOp::Sub => "-",
+            BinOp::Shl => "<<",
+            BinOp::Shr => ">>",
+            BinOp::BitAnd => "&",
+            BinOp::BitXor => "^",
+            BinOp::BitOr => "|",
+        }
+    }
+
+    /// Binding strength, as for the same operators in Rust expressions.
+    fn precedence(self) -> usize {
+        match self {
+            BinOp::Mul | BinOp::Div | BinOp::Rem => 5,
+            BinOp::Add | BinOp::Sub => 4,
+            BinOp::Shl | BinOp::Shr => 3,
+            BinOp::BitAnd => 2,
+            BinOp::BitXor => 1,
+            BinOp::BitOr => 0,
+        }
+    }
+
+    fn apply(self, lhs: AttrConst, rhs: AttrConst) -> Result<AttrConst, String> {
+        let (a, b) = match (lhs, rhs) {
+            (AttrConst::Int(a), AttrConst::Int(b)) => (a, b),
+            (AttrConst::Str(a), AttrConst::Str(b)) => {
+                if self == BinOp::Add {
+                    return Ok(AttrConst::Str(a + &b));
+                }
+                return Err(format!("`{}` can't be applied to strings", self.as_str()));
+            }
+            _ => {
+                return Err(format!("`{}` can't be applied to a string and an integer",
+                                   self.as_str()));
+            }
+        };
+        let result = match self {
+            BinOp::Mul => a.checked_mul(b),
+            BinOp::Div => a.checked_div(b),
+            BinOp::Rem => a.checked_rem(b),
+            BinOp::Add => a.checked_add(b),
+            BinOp::Sub => a.checked_sub(b),
+            // `checked_shl` only checks the shift amount, not the bits
+            // shifted out.
+            BinOp::Shl => {
+                if a.leading_zeros() as u64 >= b { a.checked_shl(b as u32) } else { None }
+            }
+            BinOp::Shr => if b < 64 { a.checked_shr(b as u32) } else { None },
+            BinOp::BitAnd => Some(a & b),
+            BinOp::BitXor => Some(a ^ b),
+            BinOp::BitOr => Some(a | b),
+        };
+        match result {
+            Some(n) => Ok(AttrConst::Int(n)),
+            None if b == 0 && (self == BinOp::Div || self == BinOp::Rem) => {
+                Err(format!("attempted to divide `{}` by zero", a))
+            }
+            None => Err(format!("`{} {} {}` overflows", a, self.as_str(), b)),
+        }
+    }
+}
+
+fn tokenize(s: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = vec![];
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        let tok = match c {
+            c if c.is_whitespace() => continue,
+            '(' => Token::OpenParen,
+            ')' => Token::CloseParen,
+            '*' => Token::Op(BinOp::Mul),
+            '/' => Token::Op(BinOp::Div),
+            '%' => Token::Op(BinOp::Rem),
+            '+' => Token::Op(BinOp::Add),
+            '-' => Token::Op(BinOp::Sub),
+            '&' => Token::Op(BinOp::BitAnd),
+            '^' => Token::Op(BinOp::BitXor),
+            '|' => Token::Op(BinOp::BitOr),
+            '<' | '>' => {
+                if chars.next() != Some(c) {
+                    return Err(format!("expected `{}{}`", c, c));
+                }
+                Token::Op(if c == '<' { BinOp::Shl } else { BinOp::Shr })
+            }
+            '"' => {
+                let mut lit = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => {
+                            match chars.next() {
+                                Some('"') => lit.push('"'),
+                                Some('\\') => lit.push('\\'),
+                                Some('n') => lit.push('\n'),
+                                Some('t') => lit.push('\t'),
+                                Some(c) => return Err(format!("unknown escape `\\{}`", c)),
+                                None => return Err("unterminated string".to_string()),
+                            }
+                        }
+                        Some(c) => lit.push(c),
+                        None => return Err("unterminated string".to_string()),
+                    }
+                }
+                Token::Str(lit)
+            }
+            '0' ... '9' => {
+                let mut digits = String::new();
+                digits.push(c);
+                while let Some(&c) = chars.peek() {
+                    if !c.is_alphanumeric() && c != '_' {
+                        break;
+                    }
+                    digits.push(c);
+                    chars.next();
+                }
+                Token::Int(try!(parse_int(&digits)))
+            }
+            c => return Err(format!("unexpected character `{}`", c)),
+        };
+        tokens.push(tok);
+    }
+    Ok(tokens)
+}
+
+fn parse_int(s: &str) -> Result<u64, String> {
+    let (radix, digits) = if s.starts_with("0x") {
+        (16, &s[2..])
+    } else if s.starts_with("0o") {
+        (8, &s[2..])
+    } else if s.starts_with("0b") {
+        (2, &s[2..])
+    } else {
+        (10, s)
+    };
+    let digits = digits.replace("_", "");
+    if digits.is_empty() {
+        return Err(format!("invalid integer `{}`", s));
+    }
+    u64::from_str_radix(&digits, radix).map_err(|_| {
+        if digits.chars().all(|c| c.is_digit(radix)) {
+            format!("integer `{}` is too large", s)
+        } else {
+            format!("invalid integer `{}`", s)
+        }
+    })
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&'a Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<&'a Token> {
+        let tok = self.tokens.get(self.pos);
+        self.pos += 1;
+        tok
+    }
+
+    /// Parses an expression whose operators all bind at least as tightly as
+    /// `min_prec`.
+    fn expr(&mut self, min_prec: usize) -> Result<AttrConst, String> {
+        let mut lhs = try!(self.primary());
+        loop {
+            let op = match self.peek() {
+                Some(&Token::Op(op)) if op.precedence() >= min_prec => op,
+                _ => return Ok(lhs),
+            };
+            self.next();
+            let rhs = try!(self.expr(op.precedence() + 1));
+            lhs = try!(op.apply(lhs, rhs));
+        }
+    }
+
+    fn primary(&mut self) -> Result<AttrConst, String> {
+        match self.next() {
+            Some(&Token::Int(n)) => Ok(AttrConst::Int(n)),
+            Some(&Token::Str(ref s)) => Ok(AttrConst::Str(s.clone())),
+            Some(&Token::OpenParen) => {
+                let value = try!(self.expr(0));
+                match self.next() {
+                    Some(&Token::CloseParen) => Ok(value),
+                    Some(tok) => Err(format!("expected `)`, found {}", tok)),
+                    None => Err("expected `)`".to_string()),
+                }
+            }
+            Some(tok) => Err(format!("expected a value, found {}", tok)),
+            None => Err("expected a value".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{eval_str, AttrConst};
+
+    #[test]
+    fn integers() {
+        assert_eq!(eval_str("16"), Ok(AttrConst::Int(16)));
+        assert_eq!(eval_str("0x1_0 + 0o10 + 0b10"), Ok(AttrConst::Int(26)));
+        assert_eq!(eval_str("2 * 8 + 1"), Ok(AttrConst::Int(17)));
+        assert_eq!(eval_str("2 * (8 + 1)"), Ok(AttrConst::Int(18)));
+        assert_eq!(eval_str("10 - 4 - 3"), Ok(AttrConst::Int(3)));
+        assert_eq!(eval_str("1 << 4 | 1"), Ok(AttrConst::Int(17)));
+        assert_eq!(eval_str("1 << 63"), Ok(AttrConst::Int(1 << 63)));
+    }
+
+    #[test]
+    fn strings() {
+        assert_eq!(eval_str("\"foo\" + \"\\\"bar\\\"\""),
+                   Ok(AttrConst::Str("foo\"bar\"".to_string())));
+        assert!(eval_str("\"foo\" + 1").is_err());
+        assert!(eval_str("\"foo\" * \"bar\"").is_err());
+    }
+
+    #[test]
+    fn errors() {
+        assert!(eval_str("").is_err());
+        assert!(eval_str("2 *").is_err());
+        assert!(eval_str("(2").is_err());
+        assert!(eval_str("2 2").is_err());
+        assert!(eval_str("1 / 0").is_err());
+        assert!(eval_str("0 - 1").is_err());
+        assert!(eval_str("1 << 64").is_err());
+        assert!(eval_str("2 << 63").is_err());
+        assert!(eval_str("0xff << 60").is_err());
+        assert!(eval_str("0xffff_ffff_ffff_ffff + 1").is_err());
+        assert!(eval_str("18446744073709551616").is_err());
+        assert!(eval_str("12abc").is_err());
+    }
+}
@@ -20,6 +20,7 @@
 pub use self::ExpnFormat::*;
 
 use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
 use std::ops::{Add, Sub};
 use std::path::Path;
 use std::rc::Rc;
@@ -414,6 +415,11 @@ pub enum ExpnFormat {
     MacroAttribute(Name),
     /// e.g. `format!()`
     MacroBang(Name),
+    /// The code generated by `#[derive(Trait)]`; the name is the trait's
+    MacroDerive(Name),
+    /// Code the compiler adds to the crate by itself, e.g. the prelude
+    /// import (`std_inject`) or the test harness (`test`)
+    CompilerInjection(Name),
 }
 
 #[derive(Clone, Hash, Debug)]
@@ -435,6 +441,8 @@ impl NameAndSpan {
         match self.format {
             ExpnFormat::MacroAttribute(s) => s,
             ExpnFormat::MacroBang(s) => s,
+            ExpnFormat::MacroDerive(s) => s,
+            ExpnFormat::CompilerInjection(s) => s,
         }
     }
 }
@@ -521,6 +529,9 @@ pub struct FileMap {
     pub lines: RefCell<Vec<BytePos>>,
     /// Locations of multi-byte characters in the source code
     pub multibyte_chars: RefCell<Vec<MultiByteChar>>,
+    /// Whether the source was synthesized by the compiler for code it
+    /// injected into the crate, see `CodeMap::new_synthetic_filemap`
+    pub synthetic: bool,
 }
 
 impl Encodable for FileMap {
@@ -635,7 +646,8 @@ impl Decodable for FileMap {
                 end_pos: end_pos,
                 src: None,
                 lines: RefCell::new(lines),
-                multibyte_chars: RefCell::new(multibyte_chars)
+                multibyte_chars: RefCell::new(multibyte_chars),
+                synthetic: false,
             })
         })
     }
@@ -706,6 +718,24 @@ impl FileMap {
         self.src.is_none()
     }
 
+    pub fn is_synthetic(&self) -> bool {
+        self.synthetic
+    }
+
+    /// Returns the span of the first occurrence of `snippet` in the source of
+    /// this filemap, or the span of the whole filemap if there is none.
+    pub fn span_of(&self, snippet: &str) -> Span {
+        let start = self.start_pos.to_usize();
+        match self.src.as_ref().and_then(|src| src.find(snippet)) {
+            Some(offset) => Span {
+                lo: BytePos::from_usize(start + offset),
+                hi: BytePos::from_usize(start + offset + snippet.len()),
+                expn_id: NO_EXPANSION,
+            },
+            None => Span { lo: self.start_pos, hi: self.end_pos, expn_id: NO_EXPANSION },
+        }
+    }
+
     fn count_lines(&self) -> usize {
         self.lines.borrow().len()
     }
@@ -783,7 +813,12 @@ impl CodeMap {
 
     /// Creates a new filemap without setting its line information. If you don't
     /// intend to set the line information yourself, you should use new_filemap_and_lines.
-    pub fn new_filemap(&self, filename: FileName, mut src: String) -> Rc<FileMap> {
+    pub fn new_filemap(&self, filename: FileName, src: String) -> Rc<FileMap> {
+        self.new_filemap_(filename, src, false)
+    }
+
+    fn new_filemap_(&self, filename: FileName, mut src: String, synthetic: bool)
+                    -> Rc<FileMap> {
         let start_pos = self.next_start_pos();
         let mut files = self.files.borrow_mut();
 
@@ -801,6 +836,7 @@ impl CodeMap {
             end_pos: Pos::from_usize(end_pos),
             lines: RefCell::new(Vec::new()),
             multibyte_chars: RefCell::new(Vec::new()),
+            synthetic: synthetic,
         });
 
         files.push(filemap.clone());
@@ -822,6 +858,21 @@ impl CodeMap {
         fm
     }
 
+    /// Creates a filemap named `<name>` holding the source of code the
+    /// compiler adds to the crate by itself, such as the injected `extern
+    /// crate std` or the test harness. Spans into it let diagnostics and
+    /// save-analysis attribute that code to a "virtual" file rather than to
+    /// whatever user code happens to be nearby.
+    pub fn new_synthetic_filemap(&self, name: &str, src: &str) -> Rc<FileMap> {
+        let fm = self.new_filemap_(format!("<{}>", name), src.to_owned(), true);
+        let mut offset = 0;
+        for line in src.lines() {
+            fm.next_line(fm.start_pos + BytePos::from_usize(offset));
+            offset += line.len() + 1;
+        }
+        fm
+    }
+
 
     /// Allocates a new FileMap representing a source file from an external
     /// crate. The source code of such an "imported filemap" is not available,
@@ -854,6 +905,7 @@ impl CodeMap {
             end_pos: end_pos,
             lines: RefCell::new(file_local_lines),
             multibyte_chars: RefCell::new(file_local_multibyte_chars),
+            synthetic: false,
         });
 
         files.push(filemap.clone());
@@ -1333,6 +1385,42 @@ impl CodeMap {
         }
     }
 
+    /// Check if a span was made up by the compiler rather than taken from
+    /// the source, i.e. it comes from a derive or from code injected into the
+    /// crate and is not the span of some input to the derive (such as a
+    /// field) or of the synthetic filemap describing the injected code. Such
+    /// spans have no meaningful snippet to show.
+    pub fn span_is_generated(&self, span: Span) -> bool {
+        self.with_expn_info(span.expn_id, |expninfo| {
+            expninfo.map_or(false, |info| {
+                match info.callee.format {
+                    MacroDerive(..) => {
+                        info.callee.span.map_or(true, |derive_span| derive_span.contains(span))
+                    }
+                    CompilerInjection(..) => {
+                        // Injected code described by a synthetic filemap has
+                        // a snippet to show, anything else doesn't.
+                        info.callee.span.map_or(true, |file_span| !file_span.contains(span))
+                    }
+                    MacroAttribute(..) | MacroBang(..) => false,
+                }
+            })
+        })
+    }
+
+    /// Check if a span belongs to code the compiler added to the crate by
+    /// itself, e.g. the injected `extern crate std` or the test harness.
+    pub fn span_is_injected(&self, span: Span) -> bool {
+        self.with_expn_info(span.expn_id, |expninfo| {
+            expninfo.map_or(false, |info| {
+                match info.callee.format {
+                    CompilerInjection(..) => true,
+                    MacroAttribute(..) | MacroBang(..) | MacroDerive(..) => false,
+                }
+            })
+        })
+    }
+
     /// Check if a span is "internal" to a macro in which #[unstable]
     /// items can be used (that is, a macro marked with
     /// `#[allow_internal_unstable]`).
@@ -1381,6 +1469,142 @@ impl CodeMap {
     }
 }
 
+// _____________________________________________________________________________
+// CompactSpan, SpanTable
+//
+
+/// A 32-bit handle for a `Span`.
+///
+/// Most spans written by the parser are short, live in the first 16MB of the
+/// codemap and carry no expansion info. Those are stored inline: the low bit
+/// is clear, the next 7 bits hold the length and the upper 24 bits hold `lo`.
+/// Everything else (long spans, spans far into the codemap and spans created
+/// by macro expansion) is interned into the overflow side table of a
+/// `SpanTable`, and the handle stores the table index with the low bit set.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct CompactSpan(u32);
+
+const COMPACT_SPAN_OVERFLOW_TAG: u32 = 1;
+const COMPACT_SPAN_LEN_BITS: u32 = 7;
+const COMPACT_SPAN_LO_BITS: u32 = 24;
+const COMPACT_SPAN_MAX_LEN: u32 = (1 << COMPACT_SPAN_LEN_BITS) - 1;
+const COMPACT_SPAN_MAX_LO: u32 = (1 << COMPACT_SPAN_LO_BITS) - 1;
+
+impl CompactSpan {
+    /// The inline handle for `span`, if it fits into one.
+    pub fn inline(span: Span) -> Option<CompactSpan> {
+        if span.expn_id != NO_EXPANSION || span.hi < span.lo {
+            return None;
+        }
+        let BytePos(lo) = span.lo;
+        let len = span.hi.0 - lo;
+        if lo > COMPACT_SPAN_MAX_LO || len > COMPACT_SPAN_MAX_LEN {
+            return None;
+        }
+        Some(CompactSpan(lo << (COMPACT_SPAN_LEN_BITS + 1) | len << 1))
+    }
+
+    pub fn is_inline(self) -> bool {
+        self.0 & COMPACT_SPAN_OVERFLOW_TAG == 0
+    }
+
+    /// The span stored in an inline handle; `None` for side table handles,
+    /// which only the `SpanTable` that handed them out can resolve.
+    pub fn inline_span(self) -> Option<Span> {
+        if !self.is_inline() {
+            return None;
+        }
+        let lo = self.0 >> (COMPACT_SPAN_LEN_BITS + 1);
+        let len = (self.0 >> 1) & COMPACT_SPAN_MAX_LEN;
+        Some(mk_sp(BytePos(lo), BytePos(lo + len)))
+    }
+
+    pub fn as_u32(self) -> u32 {
+        self.0
+    }
+
+    pub fn from_u32(raw: u32) -> CompactSpan {
+        CompactSpan(raw)
+    }
+}
+
+/// Statistics about the spans interned into a `SpanTable`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SpanTableStats {
+    /// Number of `intern` calls.
+    pub interned: usize,
+    /// Number of spans that fit into the inline encoding.
+    pub inline: usize,
+    /// Number of distinct spans that had to go into the side table.
+    pub overflow: usize,
+}
+
+impl SpanTableStats {
+    /// Bytes needed to store every interned span as a full `Span`.
+    pub fn uncompressed_bytes(&self) -> usize {
+        self.interned * ::std::mem::size_of::<Span>()
+    }
+
+    /// Bytes needed for the compact handles plus the overflow table.
+    pub fn compressed_bytes(&self) -> usize {
+        self.interned * ::std::mem::size_of::<CompactSpan>() +
+            self.overflow * ::std::mem::size_of::<Span>()
+    }
+}
+
+/// Interval-encoded span storage: spans are handed out as `CompactSpan`s and
+/// only those that do not fit inline occupy a slot in the side table. Spans
+/// in the side table are deduplicated, which matters for macro-heavy code
+/// where every token of an expansion shares a handful of spans.
+pub struct SpanTable {
+    overflow: Vec<Span>,
+    overflow_index: HashMap<Span, u32>,
+    stats: SpanTableStats,
+}
+
+impl SpanTable {
+    pub fn new() -> SpanTable {
+        SpanTable {
+            overflow: Vec::new(),
+            overflow_index: HashMap::new(),
+            stats: SpanTableStats::default(),
+        }
+    }
+
+    pub fn intern(&mut self, span: Span) -> CompactSpan {
+        self.stats.interned += 1;
+        if let Some(compact) = CompactSpan::inline(span) {
+            self.stats.inline += 1;
+            return compact;
+        }
+
+        if let Some(&index) = self.overflow_index.get(&span) {
+            return CompactSpan(index << 1 | COMPACT_SPAN_OVERFLOW_TAG);
+        }
+
+        let index = self.overflow.len();
+        if index > (u32::max_value() >> 1) as usize {
+            panic!("too many spans in the span table!");
+        }
+        let index = index as u32;
+        self.overflow.push(span);
+        self.overflow_index.insert(span, index);
+        self.stats.overflow += 1;
+        CompactSpan(index << 1 | COMPACT_SPAN_OVERFLOW_TAG)
+    }
+
+    pub fn get(&self, compact: CompactSpan) -> Span {
+        match compact.inline_span() {
+            Some(span) => span,
+            None => self.overflow[(compact.0 >> 1) as usize],
+        }
+    }
+
+    pub fn stats(&self) -> SpanTableStats {
+        self.stats
+    }
+}
+
 // _____________________________________________________________________________
 // SpanLinesError, SpanSnippetError, DistinctSources, MalformedCodemapPositions
 //
@@ -1451,6 +1675,34 @@ mod tests {
         fm.next_line(BytePos(2));
     }
 
+    #[test]
+    fn t_compact_span_roundtrip() {
+        let mut table = SpanTable::new();
+        let short = mk_sp(BytePos(10), BytePos(20));
+        let long = mk_sp(BytePos(10), BytePos(1000));
+        let far = mk_sp(BytePos(1 << 25), BytePos((1 << 25) + 3));
+        let expanded = Span { lo: BytePos(1), hi: BytePos(2), expn_id: ExpnId::from_u32(0) };
+
+        let c_short = table.intern(short);
+        assert!(c_short.is_inline());
+        assert_eq!(c_short.inline_span(), Some(short));
+        assert_eq!(table.get(c_short), short);
+
+        for &sp in &[long, far, expanded] {
+            let c = table.intern(sp);
+            assert!(!c.is_inline());
+            assert_eq!(c.inline_span(), None);
+            assert_eq!(table.get(c), sp);
+        }
+
+        // Overflowing spans are deduplicated.
+        assert_eq!(table.intern(long), table.intern(long));
+        let stats = table.stats();
+        assert_eq!(stats.interned, 6);
+        assert_eq!(stats.inline, 1);
+        assert_eq!(stats.overflow, 3);
+    }
+
     fn init_code_map() -> CodeMap {
         let cm = CodeMap::new();
         let fm1 = cm.new_filemap("blork.rs".to_string(),
@@ -15,21 +15,63 @@ use fold::Folder;
 use {ast, fold, attr};
 use visit;
 use codemap::{Spanned, respan};
+use parse::token;
 use ptr::P;
 
 use util::small_vector::SmallVector;
 
+use std::collections::HashSet;
+
 /// A folder that strips out items that do not belong in the current
 /// configuration.
 struct Context<'a, F> where F: FnMut(&[ast::Attribute]) -> bool {
     in_cfg: F,
     diagnostic: &'a Handler,
+    /// If set, collects what stripped test-only code refers to.
+    test_only_uses: Option<&'a mut TestOnlyUses>,
+    /// The names of the modules enclosing the item being folded.
+    module_path: Vec<ast::Name>,
+}
+
+/// What the code configured away by a `cfg` depending on `test` or `doctest`
+/// refers to, for name resolution to find the items only the tests use.
+pub struct TestOnlyUses {
+    pub paths: Vec<TestOnlyPath>,
+    /// The names of methods called with method-call syntax, which can't be
+    /// resolved without the types of the receivers.
+    pub method_names: HashSet<ast::Name>,
+}
+
+impl TestOnlyUses {
+    pub fn new() -> TestOnlyUses {
+        TestOnlyUses {
+            paths: Vec::new(),
+            method_names: HashSet::new(),
+        }
+    }
+}
+
+/// A path written in stripped test-only code.
+pub struct TestOnlyPath {
+    /// The names of the modules enclosing the stripped item, from the crate
+    /// root down.
+    pub module: Vec<ast::Name>,
+    /// How many modules deep into the stripped item the path was written,
+    /// e.g. 1 in the usual `#[cfg(test)] mod tests`.
+    pub depth: usize,
+    pub global: bool,
+    pub segments: Vec<ast::Name>,
 }
 
 // Support conditional compilation by transforming the AST, stripping out
-// any items that do not belong in the current configuration
+// any items that do not belong in the current configuration.
+//
+// The paths used by items stripped because of `cfg(test)` or `cfg(doctest)`
+// are added to `test_only_uses`, so that later passes can tell helpers that
+// only the tests use apart from code that is truly unused.
 pub fn strip_unconfigured_items(diagnostic: &Handler, krate: ast::Crate,
-                                feature_gated_cfgs: &mut Vec<GatedCfgAttr>)
+                                feature_gated_cfgs: &mut Vec<GatedCfgAttr>,
+                                test_only_uses: &mut TestOnlyUses)
                                 -> ast::Crate
 {
     // Need to do this check here because cfg runs before feature_gates
@@ -37,15 +79,19 @@ pub fn strip_unconfigured_items(diagnostic: &Handler, krate: ast::Crate,
 
     let krate = process_cfg_attr(diagnostic, krate, feature_gated_cfgs);
     let config = krate.config.clone();
-    strip_items(diagnostic,
-                krate,
-                |attrs| {
-                    let mut diag = CfgDiagReal {
-                        diag: diagnostic,
-                        feature_gated_cfgs: feature_gated_cfgs,
-                    };
-                    in_cfg(&config, attrs, &mut diag)
-                })
+    let mut ctxt = Context {
+        in_cfg: |attrs: &[ast::Attribute]| {
+            let mut diag = CfgDiagReal {
+                diag: diagnostic,
+                feature_gated_cfgs: feature_gated_cfgs,
+            };
+            in_cfg(&config, attrs, &mut diag)
+        },
+        diagnostic: diagnostic,
+        test_only_uses: Some(test_only_uses),
+        module_path: Vec::new(),
+    };
+    ctxt.fold_crate(krate)
 }
 
 impl<'a, F> fold::Folder for Context<'a, F> where F: FnMut(&[ast::Attribute]) -> bool {
@@ -90,6 +136,8 @@ pub fn strip_items<'a, F>(diagnostic: &'a Handler,
     let mut ctxt = Context {
         in_cfg: in_cfg,
         diagnostic: diagnostic,
+        test_only_uses: None,
+        module_path: Vec::new(),
     };
     ctxt.fold_crate(krate)
 }
@@ -123,8 +171,20 @@ fn fold_item<F>(cx: &mut Context<F>, item: P<ast::Item>) -> SmallVector<P<ast::I
     F: FnMut(&[ast::Attribute]) -> bool
 {
     if item_in_cfg(cx, &item) {
-        SmallVector::one(item.map(|i| cx.fold_item_simple(i)))
+        let is_mod = match item.node {
+            ast::ItemKind::Mod(..) => true,
+            _ => false,
+        };
+        if is_mod {
+            cx.module_path.push(item.ident.name);
+        }
+        let item = item.map(|i| cx.fold_item_simple(i));
+        if is_mod {
+            cx.module_path.pop();
+        }
+        SmallVector::one(item)
     } else {
+        record_test_only_uses(cx, &item.attrs, |v| visit::walk_item(v, &item));
         SmallVector::zero()
     }
 }
@@ -134,9 +194,13 @@ fn fold_item_kind<F>(cx: &mut Context<F>, item: ast::ItemKind) -> ast::ItemKind
 {
     let item = match item {
         ast::ItemKind::Impl(u, o, a, b, c, impl_items) => {
-            let impl_items = impl_items.into_iter()
-                                       .filter(|ii| (cx.in_cfg)(&ii.attrs))
-                                       .collect();
+            let impl_items = impl_items.into_iter().filter(|ii| {
+                if (cx.in_cfg)(&ii.attrs) {
+                    return true;
+                }
+                record_test_only_uses(cx, &ii.attrs, |v| visit::walk_impl_item(v, ii));
+                false
+            }).collect();
             ast::ItemKind::Impl(u, o, a, b, c, impl_items)
         }
         ast::ItemKind::Trait(u, a, b, methods) => {
@@ -148,6 +212,9 @@ fn fold_item_kind<F>(cx: &mut Context<F>, item: ast::ItemKind) -> ast::ItemKind
         ast::ItemKind::Struct(def, generics) => {
             ast::ItemKind::Struct(fold_struct(cx, def), generics)
         }
+        ast::ItemKind::Union(def, generics) => {
+            ast::ItemKind::Union(fold_struct(cx, def), generics)
+        }
         ast::ItemKind::Enum(def, generics) => {
             let variants = def.variants.into_iter().filter_map(|v| {
                 if !(cx.in_cfg)(&v.node.attrs) {
@@ -159,6 +226,7 @@ fn fold_item_kind<F>(cx: &mut Context<F>, item: ast::ItemKind) -> ast::ItemKind
                             attrs: v.node.attrs,
                             data: fold_struct(cx, v.node.data),
                             disr_expr: v.node.disr_expr,
+                            where_clause: v.node.where_clause,
                         },
                         span: v.span
                     })
@@ -260,6 +328,111 @@ fn is_cfg(attr: &ast::Attribute) -> bool {
     attr.check_name("cfg")
 }
 
+/// Returns true if any `#[cfg]` among `attrs` depends on `test` or
+/// `doctest`, e.g. `#[cfg(test)]` or `#[cfg(all(test, unix))]`.
+fn is_test_only(attrs: &[ast::Attribute]) -> bool {
+    fn mentions_test(mi: &ast::MetaItem) -> bool {
+        match mi.node {
+            ast::MetaItemKind::Word(ref name) => *name == "test" || *name == "doctest",
+            ast::MetaItemKind::List(_, ref mis) => mis.iter().any(|mi| mentions_test(mi)),
+            ast::MetaItemKind::NameValue(..) => false,
+        }
+    }
+
+    attrs.iter().filter(|attr| is_cfg(attr)).any(|attr| {
+        attr.meta_item_list().map_or(false, |mis| mis.iter().any(|mi| mentions_test(mi)))
+    })
+}
+
+fn record_test_only_uses<F, W>(cx: &mut Context<F>, attrs: &[ast::Attribute], walk: W) where
+    F: FnMut(&[ast::Attribute]) -> bool,
+    W: FnOnce(&mut TestOnlyUseCollector),
+{
+    if let Some(ref mut uses) = cx.test_only_uses {
+        if is_test_only(attrs) {
+            walk(&mut TestOnlyUseCollector {
+                uses: &mut **uses,
+                module: &cx.module_path,
+                depth: 0,
+            });
+        }
+    }
+}
+
+/// Collects the paths that some (unexpanded, unresolved) code refers to,
+/// along with the module they are to be resolved from. Macro arguments are
+/// only seen as tokens, so every identifier in them is taken to be a path of
+/// its own, or a method name.
+struct TestOnlyUseCollector<'a> {
+    uses: &'a mut TestOnlyUses,
+    module: &'a [ast::Name],
+    depth: usize,
+}
+
+impl<'a> TestOnlyUseCollector<'a> {
+    fn record_path(&mut self, global: bool, segments: Vec<ast::Name>) {
+        self.uses.paths.push(TestOnlyPath {
+            module: self.module.to_vec(),
+            depth: self.depth,
+            global: global,
+            segments: segments,
+        });
+    }
+
+    fn collect_tts(&mut self, tts: &[ast::TokenTree]) {
+        for tt in tts {
+            match *tt {
+                ast::TokenTree::Token(_, token::Ident(ident, _)) => {
+                    self.record_path(false, vec![ident.name]);
+                    self.uses.method_names.insert(ident.name);
+                }
+                ast::TokenTree::Token(..) => {}
+                ast::TokenTree::Delimited(_, ref delimed) => self.collect_tts(&delimed.tts),
+                ast::TokenTree::Sequence(_, ref seq) => self.collect_tts(&seq.tts),
+            }
+        }
+    }
+}
+
+impl<'a, 'v> visit::Visitor<'v> for TestOnlyUseCollector<'a> {
+    fn visit_item(&mut self, item: &'v ast::Item) {
+        if let ast::ItemKind::Mod(..) = item.node {
+            self.depth += 1;
+            visit::walk_item(self, item);
+            self.depth -= 1;
+        } else {
+            visit::walk_item(self, item);
+        }
+    }
+
+    fn visit_path(&mut self, path: &'v ast::Path, _: ast::NodeId) {
+        let segments = path.segments.iter().map(|s| s.identifier.name).collect();
+        self.record_path(path.global, segments);
+        visit::walk_path(self, path);
+    }
+
+    fn visit_path_list_item(&mut self, prefix: &'v ast::Path, item: &'v ast::PathListItem) {
+        if let Some(ident) = item.node.name() {
+            let mut segments = prefix.segments.iter().map(|s| s.identifier.name)
+                                     .collect::<Vec<_>>();
+            segments.push(ident.name);
+            self.record_path(prefix.global, segments);
+        }
+        visit::walk_path_list_item(self, prefix, item);
+    }
+
+    fn visit_expr(&mut self, expr: &'v ast::Expr) {
+        if let ast::ExprKind::MethodCall(ref ident, _, _) = expr.node {
+            self.uses.method_names.insert(ident.node.name);
+        }
+        visit::walk_expr(self, expr);
+    }
+
+    fn visit_mac(&mut self, mac: &'v ast::Mac) {
+        self.collect_tts(&mac.node.tts);
+    }
+}
+
 // Determine if an item should be translated in the current crate
 // configuration based on the item's attributes
 fn in_cfg<T: CfgDiag>(cfg: &[P<ast::MetaItem>],
@@ -49,6 +49,12 @@ fn with_registered_diagnostics<T, F>(f: F) -> T where
     })
 }
 
+/// Forgets the diagnostic codes registered so far; they are keyed by
+/// interned name, so must be cleared whenever the interner is.
+pub fn reset_registered_diagnostics() {
+    with_registered_diagnostics(|diagnostics| diagnostics.clear());
+}
+
 pub fn expand_diagnostic_used<'cx>(ecx: &'cx mut ExtCtxt,
                                    span: Span,
                                    token_tree: &[TokenTree])
@@ -0,0 +1,89 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Editions let a crate opt into changes to the surface language that would
+//! break existing code, such as new keywords or stricter lint defaults.
+//!
+//! A crate selects its edition with `--edition` or `#![edition = "..."]`;
+//! crates that do neither get `DEFAULT_EDITION`. Each crate is compiled
+//! according to its own edition, regardless of the editions of its
+//! dependencies.
+
+use ast;
+use attr::AttrMetaMethods;
+use errors::Handler;
+
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum Edition {
+    Edition2015,
+    Edition2018,
+}
+
+/// The edition used by crates that don't request one.
+pub const DEFAULT_EDITION: Edition = Edition::Edition2015;
+
+pub const ALL_EDITIONS: &'static [Edition] = &[Edition::Edition2015, Edition::Edition2018];
+
+impl Edition {
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            Edition::Edition2015 => "2015",
+            Edition::Edition2018 => "2018",
+        }
+    }
+}
+
+impl fmt::Display for Edition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Edition {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Edition, ()> {
+        ALL_EDITIONS.iter().find(|e| e.as_str() == s).cloned().ok_or(())
+    }
+}
+
+/// Finds the edition requested by a crate's `#![edition = "..."]`
+/// attribute, reporting malformed or unknown values.
+pub fn find_crate_edition(diag: &Handler, attrs: &[ast::Attribute]) -> Option<Edition> {
+    let mut edition = None;
+    for attr in attrs.iter().filter(|a| a.check_name("edition")) {
+        let value = match attr.value_str() {
+            Some(value) => value,
+            None => {
+                diag.span_err(attr.span, "malformed edition attribute, \
+                                          expected #![edition = \"...\"]");
+                continue
+            }
+        };
+        match value.parse::<Edition>() {
+            Ok(e) => {
+                if edition.is_some() && edition != Some(e) {
+                    diag.span_err(attr.span, "conflicting edition attributes");
+                }
+                edition = Some(e);
+            }
+            Err(()) => {
+                let known = ALL_EDITIONS.iter().map(|e| e.as_str()).collect::<Vec<_>>();
+                diag.struct_span_err(attr.span, &format!("unknown edition `{}`", value))
+                    .fileline_help(attr.span, &format!("expected one of: {}", known.join(", ")))
+                    .emit();
+            }
+        }
+    }
+    edition
+}
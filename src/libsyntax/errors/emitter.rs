@@ -97,6 +97,80 @@ impl BasicEmitter {
     }
 }
 
+/// An emitter for `--error-format=short`, which prints every diagnostic on a
+/// single line, as `file:line:col: level[code]: message`, for editors and
+/// other tools that read compiler output. Snippets, macro backtraces and
+/// the notes attached to a diagnostic are left out.
+pub struct ShortEmitter {
+    dst: Destination,
+    cm: Rc<codemap::CodeMap>,
+}
+
+impl Emitter for ShortEmitter {
+    fn emit(&mut self,
+            msp: Option<&MultiSpan>,
+            msg: &str,
+            code: Option<&str>,
+            lvl: Level) {
+        let sp = msp.map(|msp| msp.to_span_bounds());
+        if let Err(e) = self.emit_(sp, msg, code, lvl) {
+            panic!("failed to print diagnostics: {:?}", e);
+        }
+    }
+
+    fn custom_emit(&mut self, rsp: &RenderSpan, msg: &str, lvl: Level) {
+        let sp = rsp.span().to_span_bounds();
+        if let Err(e) = self.emit_(Some(sp), msg, None, lvl) {
+            panic!("failed to print diagnostics: {:?}", e);
+        }
+    }
+
+    fn emit_struct(&mut self, db: &DiagnosticBuilder) {
+        self.emit(db.span.as_ref(), &db.message, db.code.as_ref().map(|s| &**s), db.level);
+    }
+}
+
+impl ShortEmitter {
+    pub fn stderr(color_config: ColorConfig, code_map: Rc<codemap::CodeMap>) -> ShortEmitter {
+        if color_config.use_color() {
+            ShortEmitter { dst: Destination::from_stderr(), cm: code_map }
+        } else {
+            ShortEmitter { dst: Raw(Box::new(io::stderr())), cm: code_map }
+        }
+    }
+
+    pub fn new(dst: Box<Write + Send>, code_map: Rc<codemap::CodeMap>) -> ShortEmitter {
+        ShortEmitter { dst: Raw(dst), cm: code_map }
+    }
+
+    fn emit_(&mut self,
+             sp: Option<Span>,
+             msg: &str,
+             code: Option<&str>,
+             lvl: Level)
+             -> io::Result<()> {
+        match sp {
+            Some(COMMAND_LINE_SP) | Some(DUMMY_SP) | None => {}
+            Some(sp) => {
+                let loc = self.cm.lookup_char_pos(sp.lo);
+                try!(write!(self.dst, "{}:{}:{}: ", loc.file.name, loc.line, loc.col.0 + 1));
+            }
+        }
+
+        let color = term::Attr::ForegroundColor(lvl.color());
+        try!(print_maybe_styled!(self.dst, color, "{}", lvl.to_string()));
+        if let Some(code) = code {
+            try!(print_maybe_styled!(self.dst, color, "[{}]", code));
+        }
+        try!(print_maybe_styled!(self.dst, color, ": "));
+
+        // Some messages are laid out over several lines; fold them into one.
+        let msg = msg.lines().map(|l| l.trim()).collect::<Vec<_>>().join(" ");
+        try!(print_maybe_styled!(self.dst, term::Attr::Bold, "{}", msg));
+        write!(self.dst, "\n")
+    }
+}
+
 pub struct EmitterWriter {
     dst: Destination,
     registry: Option<diagnostics::registry::Registry>,
@@ -187,6 +261,11 @@ impl EmitterWriter {
         try!(print_diagnostic(&mut self.dst, &ss[..], lvl, msg, code));
 
         match *rsp {
+            // Spans made up by the compiler have nothing useful to show;
+            // the backtrace says where they came from instead.
+            FullSpan(_) | EndSpan(_) if self.cm.span_is_generated(bounds) => {
+                try!(self.print_macro_backtrace(bounds));
+            }
             FullSpan(_) => {
                 try!(self.highlight_lines(msp, lvl));
                 try!(self.print_macro_backtrace(bounds));
@@ -583,15 +662,23 @@ impl EmitterWriter {
         loop {
             let span_name_span = self.cm.with_expn_info(span.expn_id, |expn_info| {
                 expn_info.map(|ei| {
-                    let (pre, post) = match ei.callee.format {
-                        codemap::MacroAttribute(..) => ("#[", "]"),
-                        codemap::MacroBang(..) => ("", "!"),
+                    let macro_decl_name = match ei.callee.format {
+                        codemap::MacroAttribute(name) => {
+                            format!("in this expansion of #[{}]", name)
+                        }
+                        codemap::MacroBang(name) => format!("in this expansion of {}!", name),
+                        codemap::MacroDerive(name) => {
+                            format!("in this derive macro expansion (#[derive({})])", name)
+                        }
+                        codemap::CompilerInjection(name) => {
+                            format!("in code injected by the compiler ({})", name)
+                        }
+                    };
+                    // Derives and injections are defined by the compiler.
+                    let def_site_span = match ei.callee.format {
+                        codemap::MacroAttribute(..) | codemap::MacroBang(..) => ei.callee.span,
+                        codemap::MacroDerive(..) | codemap::CompilerInjection(..) => None,
                     };
-                    let macro_decl_name = format!("in this expansion of {}{}{}",
-                                                  pre,
-                                                  ei.callee.name(),
-                                                  post);
-                    let def_site_span = ei.callee.span;
                     (ei.call_site, macro_decl_name, def_site_span)
                 })
             });
@@ -611,7 +698,11 @@ impl EmitterWriter {
                                                   self.cm.span_to_filename(def_site_span)));
                 }
 
-                let snippet = self.cm.span_to_string(span);
+                let snippet = if span.source_equal(&codemap::DUMMY_SP) {
+                    String::new()
+                } else {
+                    self.cm.span_to_string(span)
+                };
                 try!(print_diagnostic(&mut self.dst, &snippet, Note, &diag_string, None));
             }
             last_span = span;
@@ -549,6 +549,9 @@ pub struct ExtCtxt<'a> {
 
     pub mod_path: Vec<ast::Ident> ,
     pub exported_macros: Vec<ast::MacroDef>,
+    /// Names of the `extern crate` items whose imported macros were
+    /// expanded at least once.
+    pub used_macro_crates: HashSet<ast::Name>,
 
     pub syntax_env: SyntaxEnv,
     pub recursion_count: usize,
@@ -572,6 +575,7 @@ impl<'a> ExtCtxt<'a> {
             crate_root: None,
             feature_gated_cfgs: feature_gated_cfgs,
             exported_macros: Vec::new(),
+            used_macro_crates: HashSet::new(),
             syntax_env: env,
             recursion_count: 0,
 
@@ -330,6 +330,7 @@ impl<'a> AstBuilder for ExtCtxt<'a> {
             parameters: ast::PathParameters::AngleBracketed(ast::AngleBracketedParameterData {
                 lifetimes: lifetimes,
                 types: P::from_vec(types),
+                consts: P::empty(),
                 bindings: P::from_vec(bindings),
             })
         });
@@ -368,6 +369,7 @@ impl<'a> AstBuilder for ExtCtxt<'a> {
             parameters: ast::PathParameters::AngleBracketed(ast::AngleBracketedParameterData {
                 lifetimes: lifetimes,
                 types: P::from_vec(types),
+                consts: P::empty(),
                 bindings: P::from_vec(bindings),
             })
         });
@@ -1027,6 +1029,10 @@ impl<'a> AstBuilder for ExtCtxt<'a> {
                    attrs: Vec::new(),
                    data: vdata,
                    disr_expr: None,
+                   where_clause: ast::WhereClause {
+                       id: ast::DUMMY_NODE_ID,
+                       predicates: Vec::new(),
+                   },
                })
     }
 
@@ -18,7 +18,7 @@ use ext::build::AstBuilder;
 use attr;
 use attr::{AttrMetaMethods, WithAttrs};
 use codemap;
-use codemap::{Span, Spanned, ExpnInfo, NameAndSpan, MacroBang, MacroAttribute};
+use codemap::{Span, Spanned, ExpnInfo, NameAndSpan, MacroBang, MacroAttribute, MacroDerive};
 use ext::base::*;
 use feature_gate::{self, Features};
 use fold;
@@ -980,10 +980,18 @@ fn expand_decorators(a: Annotatable,
                 MultiDecorator(ref dec) => {
                     attr::mark_used(&attr);
 
+                    // `#[derive(Foo)]` has been turned into `#[derive_Foo]`.
+                    let name = attr.name();
+                    let format = if name.starts_with("derive_") {
+                        MacroDerive(intern(&name["derive_".len()..]))
+                    } else {
+                        MacroAttribute(mname)
+                    };
+
                     fld.cx.bt_push(ExpnInfo {
                         call_site: attr.span,
                         callee: NameAndSpan {
-                            format: MacroAttribute(mname),
+                            format: format,
                             span: Some(attr.span),
                             // attributes can do whatever they like,
                             // for now.
@@ -1061,6 +1069,7 @@ fn expand_impl_item(ii: ast::ImplItem, fld: &mut MacroExpander)
             ident: ii.ident,
             attrs: ii.attrs,
             vis: ii.vis,
+            defaultness: ii.defaultness,
             node: match ii.node  {
                 ast::ImplItemKind::Method(sig, body) => {
                     let (sig, body) = expand_and_rename_method(sig, body, fld);
@@ -1324,11 +1333,14 @@ impl<'feat> ExpansionConfig<'feat> {
     }
 }
 
+/// Expands all macros in `c`. Returns the expanded crate, the names of all
+/// macros available in the crate and the names of the `extern crate` items
+/// whose macros were actually used.
 pub fn expand_crate(mut cx: ExtCtxt,
                     // these are the macros being imported to this crate:
                     imported_macros: Vec<ast::MacroDef>,
                     user_exts: Vec<NamedSyntaxExtension>,
-                    c: Crate) -> (Crate, HashSet<Name>) {
+                    c: Crate) -> (Crate, HashSet<Name>, HashSet<Name>) {
     if std_inject::no_core(&c) {
         cx.crate_root = None;
     } else if std_inject::no_std(&c) {
@@ -1357,7 +1369,7 @@ pub fn expand_crate(mut cx: ExtCtxt,
 
         ret
     };
-    return (ret, cx.syntax_env.names);
+    return (ret, cx.syntax_env.names, cx.used_macro_crates);
 }
 
 // HYGIENIC CONTEXT EXTENSION:
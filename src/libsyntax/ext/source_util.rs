@@ -93,13 +93,15 @@ pub fn expand_include<'cx>(cx: &'cx mut ExtCtxt, sp: Span, tts: &[ast::TokenTree
         Some(f) => f,
         None => return DummyResult::expr(sp),
     };
+    let file = res_rel_file(cx, sp, Path::new(&file));
+    if !check_file_access(cx, sp, "include!", &file) {
+        return DummyResult::any(sp);
+    }
     // The file will be added to the code map by the parser
     let p =
         parse::new_sub_parser_from_file(cx.parse_sess(),
                                         cx.cfg(),
-                                        &res_rel_file(cx,
-                                                      sp,
-                                                      Path::new(&file)),
+                                        &file,
                                         true,
                                         None,
                                         sp);
@@ -137,6 +139,9 @@ pub fn expand_include_str(cx: &mut ExtCtxt, sp: Span, tts: &[ast::TokenTree])
         None => return DummyResult::expr(sp)
     };
     let file = res_rel_file(cx, sp, Path::new(&file));
+    if !check_file_access(cx, sp, "include_str!", &file) {
+        return DummyResult::expr(sp);
+    }
     let mut bytes = Vec::new();
     match File::open(&file).and_then(|mut f| f.read_to_end(&mut bytes)) {
         Ok(..) => {}
@@ -174,6 +179,9 @@ pub fn expand_include_bytes(cx: &mut ExtCtxt, sp: Span, tts: &[ast::TokenTree])
         None => return DummyResult::expr(sp)
     };
     let file = res_rel_file(cx, sp, Path::new(&file));
+    if !check_file_access(cx, sp, "include_bytes!", &file) {
+        return DummyResult::expr(sp);
+    }
     let mut bytes = Vec::new();
     match File::open(&file).and_then(|mut f| f.read_to_end(&mut bytes)) {
         Err(e) => {
@@ -192,6 +200,19 @@ pub fn expand_include_bytes(cx: &mut ExtCtxt, sp: Span, tts: &[ast::TokenTree])
     }
 }
 
+/// Checks the session's I/O policy before `mac` reads `file`, reporting an
+/// error if the read isn't allowed.
+fn check_file_access(cx: &ExtCtxt, sp: Span, mac: &str, file: &Path) -> bool {
+    let io = cx.parse_sess.io.borrow();
+    if io.may_read_file(file) {
+        return true;
+    }
+    cx.struct_span_err(sp, &format!("`{}` may not read `{}`", mac, file.display()))
+      .note(&io.readable_dirs_note())
+      .emit();
+    false
+}
+
 // resolve a file-system path to an absolute file-system path (if it
 // isn't already)
 fn res_rel_file(cx: &mut ExtCtxt, sp: codemap::Span, arg: &Path) -> PathBuf {
@@ -147,6 +147,9 @@ impl TTMacroExpander for MacroRulesMacroExpander {
         if !self.valid {
             return DummyResult::any(sp);
         }
+        if let Some(krate) = self.imported_from {
+            cx.used_macro_crates.insert(krate.name);
+        }
         generic_extension(cx,
                           sp,
                           self.name,
@@ -31,14 +31,18 @@ use ast::{NodeId, PatKind};
 use ast;
 use attr;
 use attr::AttrMetaMethods;
-use codemap::{CodeMap, Span};
+use codemap::{CodeMap, Span, Pos};
 use errors::Handler;
 use visit;
 use visit::{FnKind, Visitor};
-use parse::token::InternedString;
+use parse::token::{self, InternedString};
 
 use std::ascii::AsciiExt;
+use std::cell::RefCell;
 use std::cmp;
+use std::collections::HashSet;
+
+use rustc_serialize::json::as_json;
 
 // If you change this list without updating src/doc/reference.md, @cmr will be sad
 // Don't ever remove anything from this list; set them to 'Removed'.
@@ -247,7 +251,61 @@ const KNOWN_FEATURES: &'static [(&'static str, &'static str, Option<u32>, Status
     ("inclusive_range_syntax", "1.7.0", Some(28237), Active),
 
     // `expr?`
-    ("question_mark", "1.9.0", Some(31436), Active)
+    ("question_mark", "1.9.0", Some(31436), Active),
+
+    // Allows `#![edition = "..."]`
+    ("edition", "1.9.0", None, Active),
+
+    // Allows `#[export]` to mark the symbols exported from a library
+    ("export_attribute", "1.9.0", None, Active),
+
+    // Allows `#[track_caller]` to pass the caller's location to a function
+    ("track_caller", "1.9.0", None, Active),
+
+    // Allows `#[default]` on enum variants for `#[derive(Default)]`
+    ("derive_default_enum", "1.9.0", None, Active),
+
+    // Allows matching references with non-reference patterns, binding
+    // by reference implicitly
+    ("match_default_bindings", "1.9.0", None, Active),
+
+    // Allows associated types to have generic parameters of their own
+    ("generic_associated_types", "1.9.0", None, Active),
+
+    // Allows `where` clauses on individual enum variants
+    ("variant_where_clauses", "1.9.0", None, Active),
+
+    // Allows equality constraints in `where` clauses, e.g. `where T::Out == u32`
+    ("where_clause_equality", "1.9.0", Some(20041), Active),
+
+    // Allows `#[default_bound]` on auto traits, making them implicit bounds
+    // on type parameters which can be relaxed with `?Trait`
+    ("default_bounds", "1.9.0", None, Active),
+
+    // Allows `Self` in struct and enum definitions, standing for the type
+    // being defined
+    ("self_in_typedefs", "1.9.0", None, Active),
+
+    // Enforces the trait bounds of type aliases where they're used
+    ("type_alias_bounds", "1.9.0", None, Active),
+
+    // Allows `#[optimize(size|speed|none)]` on functions
+    ("optimize_attribute", "1.9.0", None, Active),
+
+    // Allows `const N: usize` parameters in generics
+    ("const_generics", "1.9.0", None, Active),
+
+    // Allows `impl Trait` in function return types
+    ("conservative_impl_trait", "1.9.0", None, Active),
+
+    // Allows `union` items, whose fields all share the same storage
+    ("untagged_unions", "1.9.0", None, Active),
+
+    // Allows `trait Foo = Bar + Baz;`, naming a bundle of bounds
+    ("trait_alias", "1.9.0", None, Active),
+
+    // Allows `default` impl items, which more specific impls may override
+    ("specialization", "1.9.0", None, Active)
 ];
 // (changing above list without updating src/doc/reference.md makes @cmr sad)
 
@@ -359,10 +417,23 @@ pub const KNOWN_ATTRIBUTES: &'static [(&'static str, AttributeType, AttributeGat
                                        "the `#[fundamental]` attribute \
                                         is an experimental feature")),
 
+    ("default_bound", Normal, Gated("default_bounds",
+                                    "the `#[default_bound]` attribute \
+                                     is an experimental feature")),
+
+    ("optimize", Whitelisted, Gated("optimize_attribute",
+                                    "the `#[optimize]` attribute \
+                                     is an experimental feature")),
+
     ("linked_from", Normal, Gated("linked_from",
                                   "the `#[linked_from]` attribute \
                                    is an experimental feature")),
 
+    // used by `#[derive(Default)]` on enums
+    ("default", Normal, Gated("derive_default_enum",
+                              "the `#[default]` attribute on enum variants \
+                               is an experimental feature")),
+
     // FIXME: #14408 whitelist docs since rustdoc looks at them
     ("doc", Whitelisted, Ungated),
 
@@ -376,6 +447,11 @@ pub const KNOWN_ATTRIBUTES: &'static [(&'static str, AttributeType, AttributeGat
     ("link_section", Whitelisted, Ungated),
     ("no_builtins", Whitelisted, Ungated),
     ("no_mangle", Whitelisted, Ungated),
+    ("export", Whitelisted, Gated("export_attribute",
+                                  "the `#[export]` attribute is an experimental feature")),
+    ("track_caller", Whitelisted, Gated("track_caller",
+                                        "the `#[track_caller]` attribute \
+                                         is an experimental feature")),
     ("no_debug", Whitelisted, Gated("no_debug",
                                     "the `#[no_debug]` attribute \
                                      is an experimental feature")),
@@ -413,6 +489,8 @@ pub const KNOWN_ATTRIBUTES: &'static [(&'static str, AttributeType, AttributeGat
     // Crate level attributes
     ("crate_name", CrateLevel, Ungated),
     ("crate_type", CrateLevel, Ungated),
+    ("edition", CrateLevel, Gated("edition",
+                                  "editions are experimental and subject to change")),
     ("crate_id", CrateLevel, Ungated),
     ("feature", CrateLevel, Ungated),
     ("no_start", CrateLevel, Ungated),
@@ -574,6 +652,13 @@ pub struct Features {
     pub stmt_expr_attributes: bool,
     pub deprecated: bool,
     pub question_mark: bool,
+    pub match_default_bindings: bool,
+    pub self_in_typedefs: bool,
+    pub type_alias_bounds: bool,
+    pub specialization: bool,
+    /// Every place a feature gate was checked, whether or not the feature
+    /// was enabled. Only recorded for `--print features`.
+    pub gate_uses: HashSet<GateUse>,
 }
 
 impl Features {
@@ -608,10 +693,101 @@ impl Features {
             stmt_expr_attributes: false,
             deprecated: false,
             question_mark: false,
+            match_default_bindings: false,
+            self_in_typedefs: false,
+            type_alias_bounds: false,
+            specialization: false,
+            gate_uses: HashSet::new(),
+        }
+    }
+
+    /// Carries over the gates recorded by an earlier gating pass, so that
+    /// the final set also covers code that was later expanded or stripped.
+    pub fn inherit_gate_uses(&mut self, earlier: &Features) {
+        self.gate_uses.extend(earlier.gate_uses.iter().cloned());
+    }
+}
+
+/// Whether a gate guards a language feature or an unstable library API.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GateKind {
+    Language,
+    Library,
+}
+
+/// A single place where a feature gate was checked.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct GateUse {
+    pub feature: InternedString,
+    pub kind: GateKind,
+    pub span: Span,
+    /// Whether the crate enabled the feature with `#![feature]`.
+    pub enabled: bool,
+}
+
+impl GateUse {
+    /// The tracking issue for this feature, if it has one.
+    pub fn issue(&self) -> Option<u32> {
+        match self.kind {
+            GateKind::Language => find_lang_feature_issue(&self.feature),
+            GateKind::Library => None,
+        }
+    }
+
+    /// The stability status of the feature, as recorded in `KNOWN_FEATURES`
+    /// for language features. Library gates are always unstable.
+    pub fn status(&self) -> &'static str {
+        if self.kind == GateKind::Library {
+            return "unstable";
+        }
+        match KNOWN_FEATURES.iter().find(|t| t.0 == &*self.feature) {
+            Some(&(_, _, _, Active)) => "active",
+            Some(&(_, _, _, Accepted)) => "accepted",
+            Some(&(_, _, _, Removed)) => "removed",
+            None => "unknown",
         }
     }
 }
 
+#[derive(RustcEncodable)]
+struct GateUseJson<'a> {
+    feature: &'a str,
+    kind: &'static str,
+    status: &'static str,
+    issue: Option<u32>,
+    enabled: bool,
+    file_name: String,
+    line: usize,
+    column: usize,
+}
+
+/// Renders the recorded gate uses as JSON, one object per line, sorted by
+/// source position. The format should be considered unstable.
+pub fn gate_uses_to_json(cm: &CodeMap, uses: &HashSet<GateUse>) -> String {
+    let mut uses = uses.iter().collect::<Vec<_>>();
+    uses.sort_by(|a, b| (a.span.lo, &*a.feature).cmp(&(b.span.lo, &*b.feature)));
+
+    let mut out = String::new();
+    for gate in uses {
+        let loc = cm.lookup_char_pos(gate.span.lo);
+        let data = GateUseJson {
+            feature: &gate.feature,
+            kind: match gate.kind {
+                GateKind::Language => "language",
+                GateKind::Library => "library",
+            },
+            status: gate.status(),
+            issue: gate.issue(),
+            enabled: gate.enabled,
+            file_name: loc.file.name.clone(),
+            line: loc.line,
+            column: loc.col.to_usize() + 1,
+        };
+        out.push_str(&format!("{}\n", as_json(&data)));
+    }
+    out
+}
+
 const EXPLAIN_BOX_SYNTAX: &'static str =
     "box expression syntax is experimental; you can call `Box::new` instead.";
 
@@ -650,6 +826,8 @@ struct Context<'a> {
     span_handler: &'a Handler,
     cm: &'a CodeMap,
     plugin_attributes: &'a [(String, AttributeType)],
+    record_gate_uses: bool,
+    gate_uses: RefCell<HashSet<GateUse>>,
 }
 
 impl<'a> Context<'a> {
@@ -661,6 +839,7 @@ impl<'a> Context<'a> {
     fn gate_feature(&self, feature: &str, span: Span, explain: &str) {
         let has_feature = self.has_feature(feature);
         debug!("gate_feature(feature = {:?}, span = {:?}); has? {}", feature, span, has_feature);
+        self.record_gate_use(feature, span, has_feature);
         if !has_feature {
             emit_feature_err(self.span_handler, feature, span, GateIssue::Language, explain);
         }
@@ -669,6 +848,19 @@ impl<'a> Context<'a> {
         self.features.iter().any(|&n| n == feature)
     }
 
+    fn record_gate_use(&self, feature: &str, span: Span, enabled: bool) {
+        if !self.record_gate_uses {
+            return;
+        }
+        let gate = GateUse {
+            feature: token::intern_and_get_ident(feature),
+            kind: GateKind::Language,
+            span: span,
+            enabled: enabled,
+        };
+        self.gate_uses.borrow_mut().insert(gate);
+    }
+
     fn check_attribute(&self, attr: &ast::Attribute, is_macro: bool) {
         debug!("check_attribute(attr = {:?})", attr);
         let name = &*attr.name();
@@ -934,6 +1126,16 @@ impl<'a, 'v> Visitor<'v> for PostExpansionVisitor<'a> {
                 }
             }
 
+            ast::ItemKind::Union(..) => {
+                self.gate_feature("untagged_unions", i.span,
+                                  "unions are unstable and possibly buggy");
+            }
+
+            ast::ItemKind::TraitAlias(..) => {
+                self.gate_feature("trait_alias", i.span,
+                                  "trait aliases are not yet fully implemented");
+            }
+
             ast::ItemKind::DefaultImpl(..) => {
                 self.gate_feature("optin_builtin_traits",
                                   i.span,
@@ -975,6 +1177,38 @@ impl<'a, 'v> Visitor<'v> for PostExpansionVisitor<'a> {
         visit::walk_struct_def(self, s)
     }
 
+    fn visit_variant(&mut self, v: &'v ast::Variant, g: &'v ast::Generics, item_id: ast::NodeId) {
+        if !v.node.where_clause.predicates.is_empty() {
+            self.gate_feature("variant_where_clauses", v.span,
+                              "`where` clauses on enum variants are experimental");
+        }
+        visit::walk_variant(self, v, g, item_id)
+    }
+
+    fn visit_generics(&mut self, g: &'v ast::Generics) {
+        for predicate in &g.where_clause.predicates {
+            if let ast::WherePredicate::EqPredicate(ref eq_pred) = *predicate {
+                self.gate_feature("where_clause_equality", eq_pred.span,
+                                  "equality constraints in `where` clauses are experimental");
+            }
+        }
+        for param in g.const_params.iter() {
+            self.gate_feature("const_generics", param.span,
+                              "const parameters are experimental");
+        }
+        visit::walk_generics(self, g)
+    }
+
+    fn visit_path_parameters(&mut self, span: Span, params: &'v ast::PathParameters) {
+        if let ast::PathParameters::AngleBracketed(ref data) = *params {
+            for arg in data.consts.iter() {
+                self.gate_feature("const_generics", arg.span,
+                                  "const arguments are experimental");
+            }
+        }
+        visit::walk_path_parameters(self, span, params)
+    }
+
     fn visit_foreign_item(&mut self, i: &ast::ForeignItem) {
         let links_to_llvm = match attr::first_attr_value_str_by_name(&i.attrs,
                                                                      "link_name") {
@@ -1014,6 +1248,14 @@ impl<'a, 'v> Visitor<'v> for PostExpansionVisitor<'a> {
         visit::walk_expr(self, e);
     }
 
+    fn visit_ty(&mut self, t: &ast::Ty) {
+        if let ast::TyKind::ImplTrait(..) = t.node {
+            self.gate_feature("conservative_impl_trait", t.span,
+                              "`impl Trait` is experimental");
+        }
+        visit::walk_ty(self, t);
+    }
+
     fn visit_pat(&mut self, pattern: &ast::Pat) {
         match pattern.node {
             PatKind::Vec(_, Some(_), ref last) if !last.is_empty() => {
@@ -1092,16 +1334,27 @@ impl<'a, 'v> Visitor<'v> for PostExpansionVisitor<'a> {
                     self.gate_feature("const_fn", ti.span, "const fn is unstable");
                 }
             }
-            ast::TraitItemKind::Type(_, Some(_)) => {
-                self.gate_feature("associated_type_defaults", ti.span,
-                                  "associated type defaults are unstable");
+            ast::TraitItemKind::Type(ref generics, _, ref default) => {
+                if generics.is_parameterized() ||
+                   !generics.where_clause.predicates.is_empty() {
+                    self.gate_feature("generic_associated_types", ti.span,
+                                      "generic associated types are unstable");
+                }
+                if default.is_some() {
+                    self.gate_feature("associated_type_defaults", ti.span,
+                                      "associated type defaults are unstable");
+                }
             }
-            _ => {}
         }
         visit::walk_trait_item(self, ti);
     }
 
     fn visit_impl_item(&mut self, ii: &'v ast::ImplItem) {
+        if ii.defaultness.is_default() {
+            self.gate_feature("specialization",
+                              ii.span,
+                              "specialization is unstable");
+        }
         match ii.node {
             ast::ImplItemKind::Const(..) => {
                 self.gate_feature("associated_consts",
@@ -1122,6 +1375,7 @@ impl<'a, 'v> Visitor<'v> for PostExpansionVisitor<'a> {
 fn check_crate_inner<F>(cm: &CodeMap, span_handler: &Handler,
                         krate: &ast::Crate,
                         plugin_attributes: &[(String, AttributeType)],
+                        record_gate_uses: bool,
                         check: F)
                        -> Features
     where F: FnOnce(&mut Context, &ast::Crate)
@@ -1131,6 +1385,8 @@ fn check_crate_inner<F>(cm: &CodeMap, span_handler: &Handler,
         span_handler: span_handler,
         cm: cm,
         plugin_attributes: plugin_attributes,
+        record_gate_uses: record_gate_uses,
+        gate_uses: RefCell::new(HashSet::new()),
     };
 
     let mut accepted_features = Vec::new();
@@ -1212,22 +1468,29 @@ fn check_crate_inner<F>(cm: &CodeMap, span_handler: &Handler,
         stmt_expr_attributes: cx.has_feature("stmt_expr_attributes"),
         deprecated: cx.has_feature("deprecated"),
         question_mark: cx.has_feature("question_mark"),
+        match_default_bindings: cx.has_feature("match_default_bindings"),
+        self_in_typedefs: cx.has_feature("self_in_typedefs"),
+        type_alias_bounds: cx.has_feature("type_alias_bounds"),
+        specialization: cx.has_feature("specialization"),
+        gate_uses: cx.gate_uses.into_inner(),
     }
 }
 
-pub fn check_crate_macros(cm: &CodeMap, span_handler: &Handler, krate: &ast::Crate)
+pub fn check_crate_macros(cm: &CodeMap, span_handler: &Handler, krate: &ast::Crate,
+                          record_gate_uses: bool)
 -> Features {
-    check_crate_inner(cm, span_handler, krate, &[] as &'static [_],
+    check_crate_inner(cm, span_handler, krate, &[] as &'static [_], record_gate_uses,
                       |ctx, krate| visit::walk_crate(&mut MacroVisitor { context: ctx }, krate))
 }
 
 pub fn check_crate(cm: &CodeMap, span_handler: &Handler, krate: &ast::Crate,
                    plugin_attributes: &[(String, AttributeType)],
-                   unstable: UnstableFeatures) -> Features
+                   unstable: UnstableFeatures,
+                   record_gate_uses: bool) -> Features
 {
     maybe_stage_features(span_handler, krate, unstable);
 
-    check_crate_inner(cm, span_handler, krate, plugin_attributes,
+    check_crate_inner(cm, span_handler, krate, plugin_attributes, record_gate_uses,
                       |ctx, krate| visit::walk_crate(&mut PostExpansionVisitor { context: ctx },
                                                      krate))
 }
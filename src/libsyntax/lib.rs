@@ -92,14 +92,17 @@ pub mod abi;
 pub mod ast;
 pub mod ast_util;
 pub mod attr;
+pub mod attr_const;
 pub mod codemap;
 pub mod config;
+pub mod edition;
 pub mod entry;
 pub mod feature_gate;
 pub mod fold;
 pub mod owned_slice;
 pub mod parse;
 pub mod ptr;
+pub mod session_io;
 pub mod show_span;
 pub mod std_inject;
 pub mod str;
@@ -125,3 +128,15 @@ pub mod ext {
         pub mod macro_rules;
     }
 }
+
+/// Returns the syntax state kept in thread-local storage (the ident interner,
+/// the hygiene tables, the set of used attributes and the registered
+/// diagnostic codes) to its initial state, so that another crate can be
+/// compiled on this thread. Every `Name` and AST from before the reset is
+/// invalidated by it.
+pub fn reset_thread_state() {
+    ext::mtwt::reset_tables();
+    parse::token::reset_ident_interner();
+    attr::reset_used_attrs();
+    diagnostics::plugin::reset_registered_diagnostics();
+}
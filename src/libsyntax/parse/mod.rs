@@ -12,13 +12,16 @@
 
 use ast;
 use codemap::{self, Span, CodeMap, FileMap};
+use edition::{Edition, DEFAULT_EDITION};
 use errors::{Handler, ColorConfig, DiagnosticBuilder};
 use parse::parser::Parser;
 use parse::token::InternedString;
 use ptr::P;
+use session_io::SessionIo;
 use str::char_at;
 
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
 use std::io::Read;
 use std::iter;
 use std::path::{Path, PathBuf};
@@ -44,6 +47,14 @@ pub struct ParseSess {
     /// Used to determine and report recursive mod inclusions
     included_mod_stack: RefCell<Vec<PathBuf>>,
     code_map: Rc<CodeMap>,
+    /// The edition of the crate being parsed. Set from the command line and
+    /// updated by the parser when it sees `#![edition = "..."]`.
+    pub edition: Cell<Edition>,
+    /// Environment variables read by `env!` and `option_env!`, with the
+    /// value each one had (`None` if it was unset). Used for dep-info.
+    pub env_depinfo: RefCell<BTreeMap<String, Option<String>>>,
+    /// What macros and plugins may read besides the crate's sources.
+    pub io: RefCell<SessionIo>,
 }
 
 impl ParseSess {
@@ -57,7 +68,10 @@ impl ParseSess {
         ParseSess {
             span_diagnostic: handler,
             included_mod_stack: RefCell::new(vec![]),
-            code_map: code_map
+            code_map: code_map,
+            edition: Cell::new(DEFAULT_EDITION),
+            env_depinfo: RefCell::new(BTreeMap::new()),
+            io: RefCell::new(SessionIo::unrestricted()),
         }
     }
 
@@ -507,7 +521,7 @@ pub fn byte_str_lit(lit: &str) -> Rc<Vec<u8>> {
     let error = |i| format!("lexer should have rejected {} at {}", lit, i);
 
     /// Eat everything up to a non-whitespace
-    fn eat<'a, I: Iterator<Item=(usize, u8)>>(it: &mut iter::Peekable<I>) {
+    fn eat<I: Iterator<Item=(usize, u8)>>(it: &mut iter::Peekable<I>) {
         loop {
             match it.peek().map(|x| x.1) {
                 Some(b' ') | Some(b'\n') | Some(b'\r') | Some(b'\t') => {
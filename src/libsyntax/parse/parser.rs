@@ -17,7 +17,8 @@ use ast::Unsafety;
 use ast::{Mod, Arg, Arm, Attribute, BindingMode, TraitItemKind};
 use ast::Block;
 use ast::{BlockCheckMode, CaptureBy};
-use ast::{Constness, Crate, CrateConfig};
+use ast::{Constness, ConstParam, Crate, CrateConfig};
+use ast::Defaultness;
 use ast::{Decl, DeclKind};
 use ast::{EMPTY_CTXT, EnumDef, ExplicitSelf};
 use ast::{Expr, ExprKind, RangeLimits};
@@ -46,6 +47,7 @@ use ast::{BinOpKind, UnOp};
 use ast;
 use ast_util::{self, ident_to_path};
 use codemap::{self, Span, BytePos, Spanned, spanned, mk_sp, CodeMap};
+use edition;
 use errors::{self, DiagnosticBuilder};
 use ext::tt::macro_parser;
 use parse;
@@ -672,6 +674,12 @@ impl<'a> Parser<'a> {
             let token_str = self.this_token_to_string();
             self.fatal(&format!("`{}` is a reserved keyword", token_str)).emit()
         }
+        let edition = self.sess.edition.get();
+        if self.token.is_edition_keyword(edition) {
+            let token_str = self.this_token_to_string();
+            self.fatal(&format!("`{}` is a reserved keyword in the {} edition",
+                                token_str, edition)).emit()
+        }
     }
 
     /// Expect and consume an `&`. If `&&` is seen, replace it with a single
@@ -1240,9 +1248,18 @@ impl<'a> Parser<'a> {
             let lo = p.span.lo;
 
             let (name, node) = if p.eat_keyword(keywords::Type) {
-                let TyParam {ident, bounds, default, ..} = try!(p.parse_ty_param());
+                let ident = try!(p.parse_ident());
+                let mut generics = try!(p.parse_generics());
+                let bounds =
+                    try!(p.parse_colon_then_ty_param_bounds(BoundParsingMode::Modified));
+                generics.where_clause = try!(p.parse_where_clause());
+                let default = if p.eat(&token::Eq) {
+                    Some(try!(p.parse_ty_sum()))
+                } else {
+                    None
+                };
                 try!(p.expect(&token::Semi));
-                (ident, TraitItemKind::Type(bounds, default))
+                (ident, TraitItemKind::Type(generics, bounds, default))
             } else if p.is_const_item() {
                 try!(p.expect_keyword(keywords::Const));
                 let ident = try!(p.parse_ident());
@@ -1443,6 +1460,14 @@ impl<'a> Parser<'a> {
             let e = try!(self.parse_expr());
             try!(self.expect(&token::CloseDelim(token::Paren)));
             TyKind::Typeof(e)
+        } else if self.eat_keyword(keywords::Impl) {
+            // IMPL TRAIT
+            let bounds = try!(self.parse_ty_param_bounds(BoundParsingMode::Modified));
+            if !bounds.iter().any(|b| if let TraitTyParamBound(..) = *b { true } else { false }) {
+                let last_span = self.last_span;
+                self.span_err(last_span, "at least one trait must be specified");
+            }
+            TyKind::ImplTrait(bounds)
         } else if self.eat_lt() {
 
             let (qself, path) =
@@ -1786,11 +1811,13 @@ impl<'a> Parser<'a> {
 
             // Parse types, optionally.
             let parameters = if self.eat_lt() {
-                let (lifetimes, types, bindings) = try!(self.parse_generic_values_after_lt());
+                let (lifetimes, types, consts, bindings) =
+                    try!(self.parse_generic_values_after_lt());
 
                 ast::PathParameters::AngleBracketed(ast::AngleBracketedParameterData {
                     lifetimes: lifetimes,
                     types: P::from_vec(types),
+                    consts: P::from_vec(consts),
                     bindings: P::from_vec(bindings),
                 })
             } else if self.eat(&token::OpenDelim(token::Paren)) {
@@ -1849,10 +1876,12 @@ impl<'a> Parser<'a> {
             // Check for a type segment.
             if self.eat_lt() {
                 // Consumed `a::b::<`, go look for types
-                let (lifetimes, types, bindings) = try!(self.parse_generic_values_after_lt());
+                let (lifetimes, types, consts, bindings) =
+                    try!(self.parse_generic_values_after_lt());
                 let parameters = ast::AngleBracketedParameterData {
                     lifetimes: lifetimes,
                     types: P::from_vec(types),
+                    consts: P::from_vec(consts),
                     bindings: P::from_vec(bindings),
                 };
                 segments.push(ast::PathSegment {
@@ -2479,13 +2508,17 @@ impl<'a> Parser<'a> {
                         self_value: P<Expr>,
                         lo: BytePos)
                         -> PResult<'a, P<Expr>> {
-        let (_, tys, bindings) = if self.eat(&token::ModSep) {
+        let (_, tys, consts, bindings) = if self.eat(&token::ModSep) {
             try!(self.expect_lt());
             try!(self.parse_generic_values_after_lt())
         } else {
-            (Vec::new(), Vec::new(), Vec::new())
+            (Vec::new(), Vec::new(), Vec::new(), Vec::new())
         };
 
+        if let Some(arg) = consts.first() {
+            self.span_err(arg.span, "const arguments are not yet supported in method calls");
+        }
+
         if !bindings.is_empty() {
             let last_span = self.last_span;
             self.span_err(last_span, "type bindings are only permitted on trait paths");
@@ -3767,7 +3800,9 @@ impl<'a> Parser<'a> {
         let name = try!(self.parse_ident());
         try!(self.expect(&token::Colon));
         let ty = try!(self.parse_ty_sum());
-        Ok(spanned(lo, self.last_span.hi, ast::StructField_ {
+        let hi = self.last_span.hi;
+        try!(self.recover_field_where_clause());
+        Ok(spanned(lo, hi, ast::StructField_ {
             kind: NamedField(name, pr),
             id: ast::DUMMY_NODE_ID,
             ty: ty,
@@ -3775,6 +3810,25 @@ impl<'a> Parser<'a> {
         }))
     }
 
+    /// Recovers from a `where` clause written after the type of a struct
+    /// field, as in `x: T where T: Copy`. Bounds can't be attached to a
+    /// single field, so this reports what to do instead and carries on.
+    fn recover_field_where_clause(&mut self) -> PResult<'a, ()> {
+        if !self.check_keyword(keywords::Where) {
+            return Ok(());
+        }
+        let lo = self.span.lo;
+        try!(self.parse_where_clause_common(|p| p.is_where_predicate_start(false)));
+        let span = mk_sp(lo, self.last_span.hi);
+        self.diagnostic()
+            .struct_span_err(span, "`where` clauses are not allowed on struct fields")
+            .help("move the bounds into the `where` clause of the struct itself")
+            .help("to bound only this field, make its type a separate struct with the \
+                   bounds, using `PhantomData` for any type parameter it does not store")
+            .emit();
+        Ok(())
+    }
+
     /// Emit an expected item after attributes error.
     fn expected_item_err(&self, attrs: &[Attribute]) {
         let message = match attrs.last() {
@@ -4235,21 +4289,43 @@ impl<'a> Parser<'a> {
         })
     }
 
+    /// Matches constparam = CONST IDENT COLON ty
+    fn parse_const_param(&mut self) -> PResult<'a, ConstParam> {
+        let lo = self.span.lo;
+        try!(self.expect_keyword(keywords::Const));
+        let ident = try!(self.parse_ident());
+        try!(self.expect(&token::Colon));
+        let ty = try!(self.parse_ty());
+
+        Ok(ConstParam {
+            ident: ident,
+            id: ast::DUMMY_NODE_ID,
+            ty: ty,
+            span: mk_sp(lo, self.last_span.hi),
+        })
+    }
+
     /// Parse a set of optional generic type parameter declarations. Where
     /// clauses are not parsed here, and must be added later via
     /// `parse_where_clause()`.
     ///
     /// matches generics = ( ) | ( < > ) | ( < typaramseq ( , )? > ) | ( < lifetimes ( , )? > )
     ///                  | ( < lifetimes , typaramseq ( , )? > )
-    /// where   typaramseq = ( typaram ) | ( typaram , typaramseq )
+    /// where   typaramseq = ( param ) | ( param , typaramseq )
+    ///         param = typaram | constparam
     pub fn parse_generics(&mut self) -> PResult<'a, ast::Generics> {
         maybe_whole!(self, NtGenerics);
 
         if self.eat(&token::Lt) {
             let lifetime_defs = try!(self.parse_lifetime_defs());
             let mut seen_default = false;
+            let mut const_params = Vec::new();
             let ty_params = try!(self.parse_seq_to_gt(Some(token::Comma), |p| {
                 try!(p.forbid_lifetime());
+                if p.check_keyword(keywords::Const) {
+                    const_params.push(try!(p.parse_const_param()));
+                    return Ok(None);
+                }
                 let ty_param = try!(p.parse_ty_param());
                 if ty_param.default.is_some() {
                     seen_default = true;
@@ -4258,11 +4334,13 @@ impl<'a> Parser<'a> {
                     p.span_err(last_span,
                                "type parameters with a default must be trailing");
                 }
-                Ok(ty_param)
+                Ok(Some(ty_param))
             }));
+            let ty_params = ty_params.into_vec().into_iter().filter_map(|p| p).collect();
             Ok(ast::Generics {
                 lifetimes: lifetime_defs,
-                ty_params: ty_params,
+                ty_params: P::from_vec(ty_params),
+                const_params: P::from_vec(const_params),
                 where_clause: WhereClause {
                     id: ast::DUMMY_NODE_ID,
                     predicates: Vec::new(),
@@ -4273,8 +4351,13 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// Parses the generic arguments of a path after its `<`: lifetimes, then
+    /// types and const arguments in any order, then type bindings. A const
+    /// argument is a literal, possibly negated, or a block (`{N}`), so that it
+    /// can't be mistaken for a type.
     fn parse_generic_values_after_lt(&mut self) -> PResult<'a, (Vec<ast::Lifetime>,
                                                             Vec<P<Ty>>,
+                                                            Vec<P<Expr>>,
                                                             Vec<TypeBinding>)> {
         let span_lo = self.span.lo;
         let lifetimes = try!(self.parse_lifetimes(token::Comma));
@@ -4308,22 +4391,31 @@ impl<'a> Parser<'a> {
             return Err(err);
         }
 
-        // First parse types.
+        // First parse types and const arguments.
+        let mut consts = Vec::new();
         let (types, returned) = try!(self.parse_seq_to_gt_or_return(
             Some(token::Comma),
             |p| {
                 try!(p.forbid_lifetime());
                 if p.look_ahead(1, |t| t == &token::Eq) {
                     Ok(None)
+                } else if p.token.is_lit() ||
+                          p.token == token::BinOp(token::Minus) ||
+                          p.token == token::OpenDelim(token::Brace) ||
+                          p.check_keyword(keywords::True) ||
+                          p.check_keyword(keywords::False) {
+                    consts.push(try!(p.parse_prefix_expr(None)));
+                    Ok(Some(None))
                 } else {
-                    Ok(Some(try!(p.parse_ty_sum())))
+                    Ok(Some(Some(try!(p.parse_ty_sum()))))
                 }
             }
         ));
+        let types: Vec<P<Ty>> = types.into_vec().into_iter().filter_map(|ty| ty).collect();
 
         // If we found the `>`, don't continue.
         if !returned {
-            return Ok((lifetimes, types.into_vec(), Vec::new()));
+            return Ok((lifetimes, types, consts, Vec::new()));
         }
 
         // Then parse type bindings.
@@ -4348,7 +4440,7 @@ impl<'a> Parser<'a> {
                 });
             }
         ));
-        Ok((lifetimes, types.into_vec(), bindings.into_vec()))
+        Ok((lifetimes, types, consts, bindings.into_vec()))
     }
 
     fn forbid_lifetime(&mut self) -> PResult<'a, ()> {
@@ -4368,6 +4460,39 @@ impl<'a> Parser<'a> {
     pub fn parse_where_clause(&mut self) -> PResult<'a, ast::WhereClause> {
         maybe_whole!(self, NtWhereClause);
 
+        self.parse_where_clause_common(|_| true)
+    }
+
+    /// Whether the token after the current one, a comma in a variant's
+    /// `where` clause, starts another predicate rather than the next
+    /// variant. `Foo:` counts as a predicate here; for struct fields, where
+    /// it is the next field, pass `ident_colon` as false.
+    fn is_where_predicate_start(&mut self, ident_colon: bool) -> bool {
+        if self.look_ahead(1, |t| t.is_keyword(keywords::For)) {
+            return true;
+        }
+        let next = self.look_ahead(1, |t| t.clone());
+        match next {
+            token::Lifetime(..) | token::Lt | token::ModSep => true,
+            token::Ident(..) => self.look_ahead(2, |t| {
+                match *t {
+                    token::Lt | token::ModSep => true,
+                    token::Colon => ident_colon,
+                    _ => false,
+                }
+            }),
+            _ => false,
+        }
+    }
+
+    /// Parses a `where` clause. A comma only continues it if `continues`,
+    /// called with the comma as the current token, says what follows
+    /// begins another predicate: a clause at the end of an enum variant or
+    /// struct field is followed by a comma and the next variant or field,
+    /// and that comma must be left for the caller.
+    fn parse_where_clause_common<F>(&mut self, continues: F) -> PResult<'a, ast::WhereClause>
+        where F: Fn(&mut Parser<'a>) -> bool
+    {
         let mut where_clause = WhereClause {
             id: ast::DUMMY_NODE_ID,
             predicates: Vec::new(),
@@ -4441,22 +4566,31 @@ impl<'a> Parser<'a> {
                         }));
 
                         parsed_something = true;
-                    } else if self.eat(&token::Eq) {
-                        // let ty = try!(self.parse_ty());
+                    } else if self.check(&token::EqEq) || self.check(&token::Eq) {
+                        if !self.eat(&token::EqEq) {
+                            self.bump();
+                            let last_span = self.last_span;
+                            self.span_err(last_span,
+                                          "equality constraints in `where` clauses are \
+                                           written with `==`");
+                        }
+                        let rhs_ty = try!(self.parse_ty());
                         let hi = self.last_span.hi;
                         let span = mk_sp(lo, hi);
-                        // where_clause.predicates.push(
-                        //     ast::WherePredicate::EqPredicate(ast::WhereEqPredicate {
-                        //         id: ast::DUMMY_NODE_ID,
-                        //         span: span,
-                        //         path: panic!("NYI"), //bounded_ty,
-                        //         ty: ty,
-                        // }));
-                        // parsed_something = true;
-                        // // FIXME(#18433)
-                        self.span_err(span,
-                                     "equality constraints are not yet supported \
-                                     in where clauses (#20041)");
+
+                        if !bound_lifetimes.is_empty() {
+                            self.span_err(span,
+                                          "equality constraints cannot be higher-ranked");
+                        }
+
+                        where_clause.predicates.push(ast::WherePredicate::EqPredicate(
+                                ast::WhereEqPredicate {
+                                    span: span,
+                                    lhs_ty: bounded_ty,
+                                    rhs_ty: rhs_ty,
+                        }));
+
+                        parsed_something = true;
                     } else {
                         let last_span = self.last_span;
                         self.span_err(last_span,
@@ -4465,9 +4599,10 @@ impl<'a> Parser<'a> {
                 }
             };
 
-            if !self.eat(&token::Comma) {
+            if !self.check(&token::Comma) || !continues(self) {
                 break
             }
+            self.bump();
         }
 
         if !parsed_something {
@@ -4846,6 +4981,7 @@ impl<'a> Parser<'a> {
         let mut attrs = try!(self.parse_outer_attributes());
         let lo = self.span.lo;
         let vis = try!(self.parse_visibility());
+        let defaultness = self.parse_defaultness();
         let (name, node) = if self.eat_keyword(keywords::Type) {
             let name = try!(self.parse_ident());
             try!(self.expect(&token::Eq));
@@ -4872,11 +5008,34 @@ impl<'a> Parser<'a> {
             span: mk_sp(lo, self.last_span.hi),
             ident: name,
             vis: vis,
+            defaultness: defaultness,
             attrs: attrs,
             node: node
         })
     }
 
+    /// Parse `default` in front of an impl item. Like `union`, `default` is
+    /// only a keyword in this position, i.e. when it is followed by the
+    /// start of an item.
+    fn parse_defaultness(&mut self) -> Defaultness {
+        let is_default = match self.token {
+            token::Ident(id, token::Plain) if id.name.as_str() == "default" => {
+                self.look_ahead(1, |t| {
+                    t.is_keyword(keywords::Fn) || t.is_keyword(keywords::Type) ||
+                    t.is_keyword(keywords::Const) || t.is_keyword(keywords::Unsafe) ||
+                    t.is_keyword(keywords::Extern)
+                })
+            }
+            _ => false
+        };
+        if is_default {
+            self.bump();
+            Defaultness::Default
+        } else {
+            Defaultness::Final
+        }
+    }
+
     fn complain_if_pub_macro(&mut self, visa: Visibility, span: Span) {
         match visa {
             Visibility::Public => {
@@ -4957,6 +5116,19 @@ impl<'a> Parser<'a> {
         let ident = try!(self.parse_ident());
         let mut tps = try!(self.parse_generics());
 
+        // Parse a trait alias, e.g. `trait Foo = Bar + Send;`
+        if self.check(&token::Eq) {
+            let eq_span = self.span;
+            self.bump();
+            if unsafety == Unsafety::Unsafe {
+                self.span_err(eq_span, "trait aliases cannot be `unsafe`");
+            }
+            let bounds = try!(self.parse_ty_param_bounds(BoundParsingMode::Bare));
+            tps.where_clause = try!(self.parse_where_clause());
+            try!(self.expect(&token::Semi));
+            return Ok((ident, ItemKind::TraitAlias(tps, bounds), None));
+        }
+
         // Parse supertrait bounds.
         let bounds = try!(self.parse_colon_then_ty_param_bounds(BoundParsingMode::Bare));
 
@@ -5127,6 +5299,36 @@ impl<'a> Parser<'a> {
         Ok((class_name, ItemKind::Struct(vdata, generics), None))
     }
 
+    /// Parse union Foo { ... }
+    fn parse_item_union(&mut self) -> PResult<'a, ItemInfo> {
+        let class_name = try!(self.parse_ident());
+        let mut generics = try!(self.parse_generics());
+
+        let vdata = if self.token.is_keyword(keywords::Where) {
+            generics.where_clause = try!(self.parse_where_clause());
+            VariantData::Struct(try!(self.parse_record_struct_body()), ast::DUMMY_NODE_ID)
+        } else if self.token == token::OpenDelim(token::Brace) {
+            VariantData::Struct(try!(self.parse_record_struct_body()), ast::DUMMY_NODE_ID)
+        } else {
+            let token_str = self.this_token_to_string();
+            return Err(self.fatal(&format!("expected `where` or `{{` after union \
+                                            name, found `{}`", token_str)))
+        };
+
+        Ok((class_name, ItemKind::Union(vdata, generics), None))
+    }
+
+    /// `union` is only a keyword when it starts an item, i.e. when it is
+    /// followed by the name of the union.
+    fn is_union_item(&mut self) -> bool {
+        match self.token {
+            token::Ident(id, token::Plain) if id.name.as_str() == "union" => {
+                self.look_ahead(1, |t| t.is_ident() && !t.is_any_keyword())
+            }
+            _ => false
+        }
+    }
+
     pub fn parse_record_struct_body(&mut self) -> PResult<'a, Vec<StructField>> {
         let mut fields = Vec::new();
         if self.eat(&token::OpenDelim(token::Brace)) {
@@ -5161,7 +5363,9 @@ impl<'a> Parser<'a> {
                     ty: try!(p.parse_ty_sum()),
                     attrs: attrs,
                 };
-                Ok(spanned(lo, p.span.hi, struct_field_))
+                let hi = p.span.hi;
+                try!(p.recover_field_where_clause());
+                Ok(spanned(lo, hi, struct_field_))
             }));
 
         Ok(fields)
@@ -5394,6 +5598,18 @@ impl<'a> Parser<'a> {
                               owns_directory: bool,
                               name: String,
                               id_sp: Span) -> PResult<'a, (ast::ItemKind, Vec<ast::Attribute> )> {
+        // A `#[path]` attribute can point anywhere, so out-of-line modules
+        // are subject to the session's I/O policy like `include!` is.
+        {
+            let io = self.sess.io.borrow();
+            if !io.may_read_file(&path) {
+                let mut err = self.span_fatal(id_sp, &format!("`mod {}` may not read `{}`",
+                                                              name, path.display()));
+                err.note(&io.readable_dirs_note());
+                return Err(err);
+            }
+        }
+
         let mut included_mod_stack = self.sess.included_mod_stack.borrow_mut();
         match included_mod_stack.iter().position(|p| *p == path) {
             Some(i) => {
@@ -5557,6 +5773,10 @@ impl<'a> Parser<'a> {
 
             let struct_def;
             let mut disr_expr = None;
+            let mut where_clause = WhereClause {
+                id: ast::DUMMY_NODE_ID,
+                predicates: Vec::new(),
+            };
             let ident = try!(self.parse_ident());
             if self.check(&token::OpenDelim(token::Brace)) {
                 // Parse a struct variant.
@@ -5575,11 +5795,20 @@ impl<'a> Parser<'a> {
                 struct_def = VariantData::Unit(ast::DUMMY_NODE_ID);
             }
 
+            // Per-variant bounds, `Foo(T) where T: Copy`. These are feature
+            // gated, which happens after expansion.
+            if disr_expr.is_none() && self.check_keyword(keywords::Where) {
+                where_clause = try!(self.parse_where_clause_common(|p| {
+                    p.is_where_predicate_start(true)
+                }));
+            }
+
             let vr = ast::Variant_ {
                 name: ident,
                 attrs: variant_attrs,
                 data: struct_def,
                 disr_expr: disr_expr,
+                where_clause: where_clause,
             };
             variants.push(spanned(vlo, self.last_span.hi, vr));
 
@@ -5901,6 +6130,19 @@ impl<'a> Parser<'a> {
                                     maybe_append(attrs, extra_attrs));
             return Ok(Some(item));
         }
+        if self.is_union_item() {
+            // UNION ITEM
+            self.bump();
+            let (ident, item_, extra_attrs) = try!(self.parse_item_union());
+            let last_span = self.last_span;
+            let item = self.mk_item(lo,
+                                    last_span.hi,
+                                    ident,
+                                    item_,
+                                    visibility,
+                                    maybe_append(attrs, extra_attrs));
+            return Ok(Some(item));
+        }
         self.parse_macro_use_or_failure(attrs,macros_allowed,attributes_allowed,lo,visibility)
     }
 
@@ -6127,8 +6369,13 @@ impl<'a> Parser<'a> {
     /// entry point for the parser.
     pub fn parse_crate_mod(&mut self) -> PResult<'a, Crate> {
         let lo = self.span.lo;
+        let attrs = try!(self.parse_inner_attributes());
+        // The rest of the crate must be parsed according to its edition.
+        if let Some(edition) = edition::find_crate_edition(&self.sess.span_diagnostic, &attrs) {
+            self.sess.edition.set(edition);
+        }
         Ok(ast::Crate {
-            attrs: try!(self.parse_inner_attributes()),
+            attrs: attrs,
             module: try!(self.parse_mod_items(&token::Eof, lo)),
             config: self.cfg.clone(),
             span: mk_sp(lo, self.span.lo),
@@ -16,6 +16,7 @@ pub use self::Lit::*;
 pub use self::Token::*;
 
 use ast::{self, BinOpKind};
+use edition::Edition;
 use ext::mtwt;
 use ptr::P;
 use util::interner::{RcStr, StrInterner};
@@ -364,6 +365,18 @@ impl Token {
         }
     }
 
+    /// Returns `true` if the token is an identifier that only becomes a
+    /// keyword from the given edition onward.
+    pub fn is_edition_keyword(&self, edition: Edition) -> bool {
+        match *self {
+            Ident(sid, Plain) => {
+                let name = sid.name.as_str();
+                EDITION_KEYWORDS.iter().any(|&(kw, since)| since <= edition && *name == *kw)
+            }
+            _ => false,
+        }
+    }
+
     /// Hygienic identifier equality comparison.
     ///
     /// See `styntax::ext::mtwt`.
@@ -608,6 +621,13 @@ declare_special_idents_and_keywords! {
     }
 }
 
+/// Identifiers that are only reserved in crates using the given edition or a
+/// later one. Unlike the keywords above these are not interned specially, as
+/// older crates may still use them as ordinary identifiers.
+const EDITION_KEYWORDS: &'static [(&'static str, Edition)] = &[
+    ("async", Edition::Edition2018),
+];
+
 // looks like we can get rid of this completely...
 pub type IdentInterner = StrInterner;
 
@@ -542,6 +542,15 @@ pub trait PrintState<'a> {
     }
 
     fn maybe_print_comment(&mut self, pos: BytePos) -> io::Result<()> {
+        // Injected code lives in synthetic filemaps placed after the crate's
+        // own source, so its positions say nothing about where the crate's
+        // comments go.
+        let synthetic = self.next_comment().is_some() && self.cm.map_or(false, |cm| {
+            cm.lookup_byte_offset(pos).fm.is_synthetic()
+        });
+        if synthetic {
+            return Ok(());
+        }
         loop {
             match self.next_comment() {
                 Some(ref cmnt) => {
@@ -993,6 +1002,7 @@ impl<'a> State<'a> {
                 let generics = ast::Generics {
                     lifetimes: f.lifetimes.clone(),
                     ty_params: P::empty(),
+                    const_params: P::empty(),
                     where_clause: ast::WhereClause {
                         id: ast::DUMMY_NODE_ID,
                         predicates: Vec::new(),
@@ -1018,6 +1028,9 @@ impl<'a> State<'a> {
             ast::TyKind::PolyTraitRef(ref bounds) => {
                 try!(self.print_bounds("", &bounds[..]));
             }
+            ast::TyKind::ImplTrait(ref bounds) => {
+                try!(self.print_bounds("impl", &bounds[..]));
+            }
             ast::TyKind::FixedLengthVec(ref ty, ref v) => {
                 try!(word(&mut self.s, "["));
                 try!(self.print_type(&ty));
@@ -1094,14 +1107,21 @@ impl<'a> State<'a> {
 
     fn print_associated_type(&mut self,
                              ident: ast::Ident,
+                             generics: Option<&ast::Generics>,
                              bounds: Option<&ast::TyParamBounds>,
                              ty: Option<&ast::Ty>)
                              -> io::Result<()> {
         try!(self.word_space("type"));
         try!(self.print_ident(ident));
+        if let Some(generics) = generics {
+            try!(self.print_generics(generics));
+        }
         if let Some(bounds) = bounds {
             try!(self.print_bounds(":", bounds));
         }
+        if let Some(generics) = generics {
+            try!(self.print_where_clause(&generics.where_clause));
+        }
         if let Some(ty) = ty {
             try!(space(&mut self.s));
             try!(self.word_space("="));
@@ -1234,6 +1254,10 @@ impl<'a> State<'a> {
                 try!(self.head(&visibility_qualified(item.vis,"struct")));
                 try!(self.print_struct(&struct_def, generics, item.ident, item.span, true));
             }
+            ast::ItemKind::Union(ref struct_def, ref generics) => {
+                try!(self.head(&visibility_qualified(item.vis,"union")));
+                try!(self.print_struct(&struct_def, generics, item.ident, item.span, true));
+            }
 
             ast::ItemKind::DefaultImpl(unsafety, ref trait_ref) => {
                 try!(self.head(""));
@@ -1316,6 +1340,16 @@ impl<'a> State<'a> {
                 }
                 try!(self.bclose(item.span));
             }
+            ast::ItemKind::TraitAlias(ref generics, ref bounds) => {
+                try!(self.head(&visibility_qualified(item.vis, "trait")));
+                try!(self.print_ident(item.ident));
+                try!(self.print_generics(generics));
+                try!(self.print_bounds(" =", &bounds[..]));
+                try!(self.print_where_clause(&generics.where_clause));
+                try!(word(&mut self.s, ";"));
+                try!(self.end()); // end inner head-block
+                try!(self.end()); // end outer head-block
+            }
             ast::ItemKind::Mac(codemap::Spanned { ref node, .. }) => {
                 try!(self.print_visibility(item.vis));
                 try!(self.print_path(&node.path, false, 0));
@@ -1520,6 +1554,7 @@ impl<'a> State<'a> {
         try!(self.head(""));
         let generics = ast::Generics::default();
         try!(self.print_struct(&v.node.data, &generics, v.node.name, v.span, false));
+        try!(self.print_where_clause(&v.node.where_clause));
         match v.node.disr_expr {
             Some(ref d) => {
                 try!(space(&mut self.s));
@@ -1569,8 +1604,8 @@ impl<'a> State<'a> {
                     try!(word(&mut self.s, ";"));
                 }
             }
-            ast::TraitItemKind::Type(ref bounds, ref default) => {
-                try!(self.print_associated_type(ti.ident, Some(bounds),
+            ast::TraitItemKind::Type(ref generics, ref bounds, ref default) => {
+                try!(self.print_associated_type(ti.ident, Some(generics), Some(bounds),
                                                 default.as_ref().map(|ty| &**ty)));
             }
         }
@@ -1582,18 +1617,26 @@ impl<'a> State<'a> {
         try!(self.hardbreak_if_not_bol());
         try!(self.maybe_print_comment(ii.span.lo));
         try!(self.print_outer_attributes(&ii.attrs));
+        // `default` goes between the visibility and the rest of the item.
+        let vis = if ii.defaultness.is_default() {
+            try!(word(&mut self.s, &visibility_qualified(ii.vis, "default")));
+            try!(self.nbsp());
+            ast::Visibility::Inherited
+        } else {
+            ii.vis
+        };
         match ii.node {
             ast::ImplItemKind::Const(ref ty, ref expr) => {
-                try!(self.print_associated_const(ii.ident, &ty, Some(&expr), ii.vis));
+                try!(self.print_associated_const(ii.ident, &ty, Some(&expr), vis));
             }
             ast::ImplItemKind::Method(ref sig, ref body) => {
                 try!(self.head(""));
-                try!(self.print_method_sig(ii.ident, sig, ii.vis));
+                try!(self.print_method_sig(ii.ident, sig, vis));
                 try!(self.nbsp());
                 try!(self.print_block_with_attrs(body, &ii.attrs));
             }
             ast::ImplItemKind::Type(ref ty) => {
-                try!(self.print_associated_type(ii.ident, None, Some(ty)));
+                try!(self.print_associated_type(ii.ident, None, None, Some(ty)));
             }
             ast::ImplItemKind::Macro(codemap::Spanned { ref node, .. }) => {
                 // code copied from ItemKind::Mac:
@@ -2423,6 +2466,14 @@ impl<'a> State<'a> {
                         comma = true;
                 }
 
+                if !data.consts.is_empty() {
+                    if comma {
+                        try!(self.word_space(","))
+                    }
+                    try!(self.commasep_exprs(Inconsistent, &data.consts));
+                    comma = true;
+                }
+
                 for binding in data.bindings.iter() {
                     if comma {
                         try!(self.word_space(","))
@@ -2809,7 +2860,8 @@ impl<'a> State<'a> {
                           generics: &ast::Generics)
                           -> io::Result<()>
     {
-        let total = generics.lifetimes.len() + generics.ty_params.len();
+        let total = generics.lifetimes.len() + generics.ty_params.len() +
+                    generics.const_params.len();
         if total == 0 {
             return Ok(());
         }
@@ -2825,10 +2877,17 @@ impl<'a> State<'a> {
             if idx < generics.lifetimes.len() {
                 let lifetime = &generics.lifetimes[idx];
                 s.print_lifetime_def(lifetime)
-            } else {
+            } else if idx < generics.lifetimes.len() + generics.ty_params.len() {
                 let idx = idx - generics.lifetimes.len();
                 let param = &generics.ty_params[idx];
                 s.print_ty_param(param)
+            } else {
+                let idx = idx - generics.lifetimes.len() - generics.ty_params.len();
+                let param = &generics.const_params[idx];
+                try!(s.word_nbsp("const"));
+                try!(s.print_ident(param.ident));
+                try!(s.word_space(":"));
+                s.print_type(&param.ty)
             }
         }));
 
@@ -2886,11 +2945,13 @@ impl<'a> State<'a> {
                         }
                     }
                 }
-                ast::WherePredicate::EqPredicate(ast::WhereEqPredicate{ref path, ref ty, ..}) => {
-                    try!(self.print_path(path, false, 0));
+                ast::WherePredicate::EqPredicate(ast::WhereEqPredicate{ref lhs_ty,
+                                                                       ref rhs_ty,
+                                                                       ..}) => {
+                    try!(self.print_type(lhs_ty));
                     try!(space(&mut self.s));
-                    try!(self.word_space("="));
-                    try!(self.print_type(&ty));
+                    try!(self.word_space("=="));
+                    try!(self.print_type(rhs_ty));
                 }
             }
         }
@@ -3027,6 +3088,7 @@ impl<'a> State<'a> {
         let generics = ast::Generics {
             lifetimes: Vec::new(),
             ty_params: P::empty(),
+            const_params: P::empty(),
             where_clause: ast::WhereClause {
                 id: ast::DUMMY_NODE_ID,
                 predicates: Vec::new(),
@@ -3177,6 +3239,10 @@ mod tests {
             // making this up as I go.... ?
             data: ast::VariantData::Unit(ast::DUMMY_NODE_ID),
             disr_expr: None,
+            where_clause: ast::WhereClause {
+                id: ast::DUMMY_NODE_ID,
+                predicates: Vec::new(),
+            },
         });
 
         let varstr = variant_to_string(&var);
@@ -0,0 +1,113 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! What a compilation may read besides its own source files.
+//!
+//! Macros such as `include_str!` and `env!`, modules loaded through `#[path]`
+//! and compiler plugins make the output of the compiler depend on files and
+//! environment variables that aren't among its inputs. A build farm that
+//! wants builds to be hermetic installs a restrictive `SessionIo` in the
+//! `ParseSess`; everything that reaches outside of the crate's sources asks
+//! it first and reports an error naming itself if it is turned down.
+//!
+//! The default policy allows everything.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Debug)]
+pub struct SessionIo {
+    /// The directories `include!` and friends, and out-of-line modules, may
+    /// read files from, or `None` to allow any file.
+    pub readable_dirs: Option<Vec<PathBuf>>,
+    /// The environment variables `env!` and `option_env!` may read, or
+    /// `None` to allow any variable.
+    pub readable_env: Option<Vec<String>>,
+    /// Whether compiler plugins may be loaded.
+    pub allow_plugins: bool,
+}
+
+impl SessionIo {
+    pub fn unrestricted() -> SessionIo {
+        SessionIo {
+            readable_dirs: None,
+            readable_env: None,
+            allow_plugins: true,
+        }
+    }
+
+    /// A policy that allows nothing beyond the crate's own sources.
+    pub fn sandboxed() -> SessionIo {
+        SessionIo {
+            readable_dirs: Some(vec![]),
+            readable_env: Some(vec![]),
+            allow_plugins: false,
+        }
+    }
+
+    /// Whether `path` is under one of the readable directories. Both sides
+    /// are canonicalized first, so that `..` and symlinks can't be used to
+    /// escape them.
+    pub fn may_read_file(&self, path: &Path) -> bool {
+        let dirs = match self.readable_dirs {
+            Some(ref dirs) => dirs,
+            None => return true,
+        };
+        let path = match fs::canonicalize(path) {
+            Ok(path) => path,
+            // A file that doesn't exist can't leak anything; let the caller
+            // report it as missing.
+            Err(_) => return true,
+        };
+        dirs.iter().any(|dir| {
+            fs::canonicalize(dir).map(|dir| path.starts_with(&dir)).unwrap_or(false)
+        })
+    }
+
+    pub fn may_read_env(&self, var: &str) -> bool {
+        match self.readable_env {
+            Some(ref vars) => vars.iter().any(|v| v == var),
+            None => true,
+        }
+    }
+
+    pub fn may_load_plugins(&self) -> bool {
+        self.allow_plugins
+    }
+
+    /// A note for a rejected file read, listing what may be read instead.
+    pub fn readable_dirs_note(&self) -> String {
+        match self.readable_dirs {
+            Some(ref dirs) if !dirs.is_empty() => {
+                let dirs: Vec<_> = dirs.iter().map(|d| format!("`{}`", d.display())).collect();
+                format!("only files under {} may be read", dirs.join(", "))
+            }
+            _ => "no files besides the crate's sources may be read".to_string(),
+        }
+    }
+
+    /// A note for a rejected environment variable, listing what may be read
+    /// instead.
+    pub fn readable_env_note(&self) -> String {
+        match self.readable_env {
+            Some(ref vars) if !vars.is_empty() => {
+                let vars: Vec<_> = vars.iter().map(|v| format!("`{}`", v)).collect();
+                format!("only the environment variables {} may be read", vars.join(", "))
+            }
+            _ => "no environment variables may be read".to_string(),
+        }
+    }
+}
+
+impl Default for SessionIo {
+    fn default() -> SessionIo {
+        SessionIo::unrestricted()
+    }
+}
@@ -10,7 +10,7 @@
 
 use ast;
 use attr;
-use codemap::{DUMMY_SP, Span, ExpnInfo, NameAndSpan, MacroAttribute};
+use codemap::{DUMMY_SP, Span, ExpnInfo, NameAndSpan, CompilerInjection};
 use codemap;
 use fold::Folder;
 use fold;
@@ -19,33 +19,44 @@ use parse::{token, ParseSess};
 use ptr::P;
 use util::small_vector::SmallVector;
 
-/// Craft a span that will be ignored by the stability lint's
-/// call to codemap's is_internal check.
-/// The expanded code uses the unstable `#[prelude_import]` attribute.
-fn ignored_span(sess: &ParseSess, sp: Span) -> Span {
+/// Craft a span for injected code whose source is `src`, pointing into a
+/// synthetic filemap named `<name>` rather than at the user's code.
+/// The span will be ignored by the stability lint's call to codemap's
+/// is_internal check, as the expanded code uses the unstable
+/// `#[prelude_import]` attribute.
+fn injected_span(sess: &ParseSess, name: &str, src: &str) -> Span {
+    let filemap = sess.codemap().new_synthetic_filemap(name, src);
+    let sp = filemap.span_of(src);
     let info = ExpnInfo {
         call_site: DUMMY_SP,
         callee: NameAndSpan {
-            format: MacroAttribute(intern("std_inject")),
-            span: None,
+            format: CompilerInjection(intern("std_inject")),
+            span: Some(sp),
             allow_internal_unstable: true,
         }
     };
     let expn_id = sess.codemap().record_expansion(info);
-    let mut sp = sp;
-    sp.expn_id = expn_id;
-    return sp;
+    Span { expn_id: expn_id, .. sp }
 }
 
-pub fn maybe_inject_crates_ref(krate: ast::Crate, alt_std_name: Option<String>)
+pub fn maybe_inject_crates_ref(sess: &ParseSess,
+                               krate: ast::Crate,
+                               alt_std_name: Option<String>)
                                -> ast::Crate {
     if no_core(&krate) {
         krate
     } else {
         let name = if no_std(&krate) {"core"} else {"std"};
+        let crate_name = alt_std_name.unwrap_or(name.to_string());
+        let src = if crate_name == name {
+            format!("#[macro_use]\nextern crate {};\n", name)
+        } else {
+            format!("#[macro_use]\nextern crate {} as {};\n", crate_name, name)
+        };
         let mut fold = CrateInjector {
+            span: injected_span(sess, "std_inject", &src),
             item_name: token::str_to_ident(name),
-            crate_name: token::intern(&alt_std_name.unwrap_or(name.to_string())),
+            crate_name: token::intern(&crate_name),
         };
         fold.fold_crate(krate)
     }
@@ -56,8 +67,9 @@ pub fn maybe_inject_prelude(sess: &ParseSess, krate: ast::Crate) -> ast::Crate {
         krate
     } else {
         let name = if no_std(&krate) {"core"} else {"std"};
+        let src = format!("#[prelude_import]\nuse {}::prelude::v1::*;\n", name);
         let mut fold = PreludeInjector {
-            span: ignored_span(sess, DUMMY_SP),
+            span: injected_span(sess, "prelude_inject", &src),
             crate_identifier: token::str_to_ident(name),
         };
         fold.fold_crate(krate)
@@ -77,6 +89,7 @@ fn no_prelude(attrs: &[ast::Attribute]) -> bool {
 }
 
 struct CrateInjector {
+    span: Span,
     item_name: ast::Ident,
     crate_name: ast::Name,
 }
@@ -91,7 +104,7 @@ impl fold::Folder for CrateInjector {
                         InternedString::new("macro_use")))),
             node: ast::ItemKind::ExternCrate(Some(self.crate_name)),
             vis: ast::Visibility::Inherited,
-            span: DUMMY_SP
+            span: self.span
         }));
 
         krate
@@ -145,7 +158,7 @@ impl fold::Folder for PreludeInjector {
             ],
         };
 
-        let vp = P(codemap::dummy_spanned(ast::ViewPathGlob(prelude_path)));
+        let vp = P(codemap::respan(self.span, ast::ViewPathGlob(prelude_path)));
         mod_.items.insert(0, P(ast::Item {
             id: ast::DUMMY_NODE_ID,
             ident: special_idents::invalid,
@@ -12,16 +12,17 @@
 
 #![allow(dead_code)]
 #![allow(unused_imports)]
-use self::HasTestSignature::*;
 
 use std::iter;
 use std::slice;
 use std::mem;
+use std::rc::Rc;
 use std::vec;
 use ast_util::*;
 use attr::AttrMetaMethods;
 use attr;
-use codemap::{DUMMY_SP, Span, ExpnInfo, NameAndSpan, MacroAttribute};
+use codemap::{DUMMY_SP, NO_EXPANSION, Span, ExpnInfo, ExpnId, NameAndSpan, CompilerInjection};
+use codemap::{FileMap, Pos};
 use codemap;
 use errors;
 use config;
@@ -38,18 +39,22 @@ use print::pprust;
 use {ast, ast_util};
 use ptr::P;
 use util::small_vector::SmallVector;
+use rustc_serialize::json::as_json;
 
-enum ShouldPanic {
+pub enum ShouldPanic {
     No,
+    /// `#[should_panic]`, with the message it expects, if any.
     Yes(Option<InternedString>),
 }
 
-struct Test {
-    span: Span,
-    path: Vec<ast::Ident> ,
-    bench: bool,
-    ignore: bool,
-    should_panic: ShouldPanic
+/// A test or benchmark function found while building the test harness.
+pub struct Test {
+    pub span: Span,
+    /// The path to the function from the crate root.
+    pub path: Vec<ast::Ident>,
+    pub bench: bool,
+    pub ignore: bool,
+    pub should_panic: ShouldPanic
 }
 
 struct TestCtxt<'a> {
@@ -64,14 +69,21 @@ struct TestCtxt<'a> {
 
     // top-level re-export submodule, filled out after folding is finished
     toplevel_reexport: Option<ast::Ident>,
+
+    // synthetic `<test harness>` source the generated items point into, and
+    // the expansion their spans are marked with
+    harness: Rc<FileMap>,
+    harness_expn_id: ExpnId,
 }
 
 // Traverse the crate, collecting all the test functions, eliding any
 // existing main functions, and synthesizing a main test harness
+/// Builds the test harness when compiling with `--test`, returning the
+/// tests it runs along with the crate; otherwise strips the test functions.
 pub fn modify_for_testing(sess: &ParseSess,
                           cfg: &ast::CrateConfig,
                           krate: ast::Crate,
-                          span_diagnostic: &errors::Handler) -> ast::Crate {
+                          span_diagnostic: &errors::Handler) -> (ast::Crate, Vec<Test>) {
     // We generate the test harness when building in the 'test'
     // configuration, either with the '--test' or '--cfg test'
     // command line options.
@@ -88,7 +100,7 @@ pub fn modify_for_testing(sess: &ParseSess,
     if should_test {
         generate_test_harness(sess, reexport_test_harness_main, krate, cfg, span_diagnostic)
     } else {
-        strip_test_functions(span_diagnostic, krate)
+        (strip_test_functions(span_diagnostic, krate), vec![])
     }
 }
 
@@ -123,37 +135,30 @@ impl<'a> fold::Folder for TestHarnessGenerator<'a> {
         debug!("current path: {}",
                ast_util::path_name_i(&self.cx.path));
 
-        let i = if is_test_fn(&self.cx, &i) || is_bench_fn(&self.cx, &i) {
-            match i.node {
-                ast::ItemKind::Fn(_, ast::Unsafety::Unsafe, _, _, _, _) => {
-                    let diag = self.cx.span_diagnostic;
-                    panic!(diag.span_fatal(i.span, "unsafe functions cannot be used for tests"));
-                }
-                _ => {
-                    debug!("this is a test function");
-                    let test = Test {
-                        span: i.span,
-                        path: self.cx.path.clone(),
-                        bench: is_bench_fn(&self.cx, &i),
-                        ignore: is_ignored(&i),
-                        should_panic: should_panic(&i)
-                    };
-                    self.cx.testfns.push(test);
-                    self.tests.push(i.ident);
-                    // debug!("have {} test/bench functions",
-                    //        cx.testfns.len());
-
-                    // Make all tests public so we can call them from outside
-                    // the module (note that the tests are re-exported and must
-                    // be made public themselves to avoid privacy errors).
-                    i.map(|mut i| {
-                        i.vis = ast::Visibility::Public;
-                        i
-                    })
-                }
+        // Validate the signature before the item is touched, so that errors
+        // point at what the user wrote rather than at the generated harness.
+        let i = match test_kind(&i) {
+            Some(kind) if check_test_signature(&self.cx, &i, kind) => {
+                debug!("this is a test function");
+                let test = Test {
+                    span: i.span,
+                    path: self.cx.path.clone(),
+                    bench: kind == TestKind::Bench,
+                    ignore: is_ignored(&i),
+                    should_panic: should_panic(&i)
+                };
+                self.cx.testfns.push(test);
+                self.tests.push(i.ident);
+
+                // Make all tests public so we can call them from outside
+                // the module (note that the tests are re-exported and must
+                // be made public themselves to avoid privacy errors).
+                i.map(|mut i| {
+                    i.vis = ast::Visibility::Public;
+                    i
+                })
             }
-        } else {
-            i
+            _ => i
         };
 
         // We don't want to recurse into anything other than mods, since
@@ -253,7 +258,7 @@ fn mk_reexport_mod(cx: &mut TestCtxt, tests: Vec<ast::Ident>,
     }));
 
     let reexport_mod = ast::Mod {
-        inner: DUMMY_SP,
+        inner: harness_span(cx, "{ /* ... */ }"),
         items: items.collect(),
     };
 
@@ -264,7 +269,7 @@ fn mk_reexport_mod(cx: &mut TestCtxt, tests: Vec<ast::Ident>,
         id: ast::DUMMY_NODE_ID,
         node: ast::ItemKind::Mod(reexport_mod),
         vis: ast::Visibility::Public,
-        span: DUMMY_SP,
+        span: harness_span(cx, "pub mod __test_reexports"),
     });
 
     (it, sym)
@@ -274,11 +279,28 @@ fn generate_test_harness(sess: &ParseSess,
                          reexport_test_harness_main: Option<InternedString>,
                          krate: ast::Crate,
                          cfg: &ast::CrateConfig,
-                         sd: &errors::Handler) -> ast::Crate {
+                         sd: &errors::Handler) -> (ast::Crate, Vec<Test>) {
     // Remove the entry points
     let mut cleaner = EntryPointCleaner { depth: 0 };
     let krate = cleaner.fold_crate(krate);
 
+    let is_test_crate = is_test_crate(&krate);
+    let harness = sess.codemap().new_synthetic_filemap(
+        "test harness",
+        &harness_source(is_test_crate, reexport_test_harness_main.as_ref()));
+    let harness_expn_id = sess.codemap().record_expansion(ExpnInfo {
+        call_site: DUMMY_SP,
+        callee: NameAndSpan {
+            format: CompilerInjection(intern("test")),
+            span: Some(Span {
+                lo: harness.start_pos,
+                hi: harness.end_pos,
+                expn_id: NO_EXPANSION,
+            }),
+            allow_internal_unstable: true,
+        }
+    });
+
     let mut feature_gated_cfgs = vec![];
     let mut cx: TestCtxt = TestCtxt {
         sess: sess,
@@ -289,16 +311,18 @@ fn generate_test_harness(sess: &ParseSess,
         path: Vec::new(),
         testfns: Vec::new(),
         reexport_test_harness_main: reexport_test_harness_main,
-        is_test_crate: is_test_crate(&krate),
+        is_test_crate: is_test_crate,
         config: krate.config.clone(),
         toplevel_reexport: None,
+        harness: harness,
+        harness_expn_id: harness_expn_id,
     };
     cx.ext_cx.crate_root = Some("std");
 
     cx.ext_cx.bt_push(ExpnInfo {
         call_site: DUMMY_SP,
         callee: NameAndSpan {
-            format: MacroAttribute(intern("test")),
+            format: CompilerInjection(intern("test")),
             span: None,
             allow_internal_unstable: false,
         }
@@ -311,7 +335,47 @@ fn generate_test_harness(sess: &ParseSess,
     };
     let res = fold.fold_crate(krate);
     fold.cx.ext_cx.bt_pop();
-    return res;
+    return (res, mem::replace(&mut fold.cx.testfns, vec![]));
+}
+
+#[derive(RustcEncodable)]
+struct TestJson<'a> {
+    name: String,
+    bench: bool,
+    ignore: bool,
+    should_panic: bool,
+    expected: Option<&'a str>,
+    file_name: String,
+    line: usize,
+    column: usize,
+}
+
+/// Renders the tests found by `modify_for_testing` as JSON, one object per
+/// line, in the order they appear in the crate. The format should be
+/// considered unstable.
+pub fn tests_to_json(cm: &codemap::CodeMap, tests: &[Test]) -> String {
+    let mut out = String::new();
+    for test in tests {
+        let loc = cm.lookup_char_pos(test.span.lo);
+        let data = TestJson {
+            name: ast_util::path_name_i(&test.path),
+            bench: test.bench,
+            ignore: test.ignore,
+            should_panic: match test.should_panic {
+                ShouldPanic::No => false,
+                ShouldPanic::Yes(_) => true,
+            },
+            expected: match test.should_panic {
+                ShouldPanic::Yes(Some(ref msg)) => Some(&msg[..]),
+                _ => None,
+            },
+            file_name: loc.file.name.clone(),
+            line: loc.line,
+            column: loc.col.to_usize() + 1,
+        };
+        out.push_str(&format!("{}\n", as_json(&data)));
+    }
+    out
 }
 
 fn strip_test_functions(diagnostic: &errors::Handler, krate: ast::Crate)
@@ -331,7 +395,7 @@ fn ignored_span(cx: &TestCtxt, sp: Span) -> Span {
     let info = ExpnInfo {
         call_site: DUMMY_SP,
         callee: NameAndSpan {
-            format: MacroAttribute(intern("test")),
+            format: CompilerInjection(intern("test")),
             span: None,
             allow_internal_unstable: true,
         }
@@ -342,79 +406,128 @@ fn ignored_span(cx: &TestCtxt, sp: Span) -> Span {
     return sp;
 }
 
-#[derive(PartialEq)]
-enum HasTestSignature {
-    Yes,
-    No,
-    NotEvenAFunction,
+/// The source of the synthetic `<test harness>` filemap, which generated
+/// items point into instead of at whatever user code they were built from.
+fn harness_source(is_test_crate: bool, reexport_main: Option<&InternedString>) -> String {
+    let mut src = String::from("pub mod __test {\n");
+    if is_test_crate {
+        src.push_str("    pub use test;\n");
+    } else {
+        src.push_str("    extern crate test;\n");
+    }
+    src.push_str("    pub fn main() {\n");
+    src.push_str("        #![main]\n");
+    src.push_str("        test::test_main_static(TESTS);\n");
+    src.push_str("    }\n");
+    src.push_str("    const TESTS: &'static [self::test::TestDescAndFn] = &[/* ... */];\n");
+    src.push_str("}\n");
+    src.push_str("pub mod __test_reexports { /* ... */ }\n");
+    if let Some(name) = reexport_main {
+        src.push_str(&format!("use __test::main as {};\n", name));
+    }
+    src
 }
 
+/// Craft a span for `snippet` of the `<test harness>` source that, like
+/// `ignored_span`, will be ignored by the stability lint.
+fn harness_span(cx: &TestCtxt, snippet: &str) -> Span {
+    Span { expn_id: cx.harness_expn_id, .. cx.harness.span_of(snippet) }
+}
 
-fn is_test_fn(cx: &TestCtxt, i: &ast::Item) -> bool {
-    let has_test_attr = attr::contains_name(&i.attrs, "test");
+#[derive(Copy, Clone, PartialEq)]
+enum TestKind {
+    Test,
+    Bench,
+}
 
-    fn has_test_signature(i: &ast::Item) -> HasTestSignature {
-        match i.node {
-          ast::ItemKind::Fn(ref decl, _, _, _, ref generics, _) => {
-            let no_output = match decl.output {
-                ast::FunctionRetTy::Default(..) => true,
-                ast::FunctionRetTy::Ty(ref t) if t.node == ast::TyKind::Tup(vec![]) => true,
-                _ => false
-            };
-            if decl.inputs.is_empty()
-                   && no_output
-                   && !generics.is_parameterized() {
-                Yes
-            } else {
-                No
-            }
-          }
-          _ => NotEvenAFunction,
+impl TestKind {
+    fn plural(&self) -> &'static str {
+        match *self {
+            TestKind::Test => "tests",
+            TestKind::Bench => "benches",
         }
     }
+}
 
-    if has_test_attr {
-        let diag = cx.span_diagnostic;
-        match has_test_signature(i) {
-            Yes => {},
-            No => diag.span_err(i.span, "functions used as tests must have signature fn() -> ()"),
-            NotEvenAFunction => diag.span_err(i.span,
-                                              "only functions may be used as tests"),
-        }
+fn test_kind(i: &ast::Item) -> Option<TestKind> {
+    if attr::contains_name(&i.attrs, "test") {
+        Some(TestKind::Test)
+    } else if attr::contains_name(&i.attrs, "bench") {
+        Some(TestKind::Bench)
+    } else {
+        None
     }
-
-    return has_test_attr && has_test_signature(i) == Yes;
 }
 
-fn is_bench_fn(cx: &TestCtxt, i: &ast::Item) -> bool {
-    let has_bench_attr = attr::contains_name(&i.attrs, "bench");
+/// Checks that a `#[test]` or `#[bench]` item is a function the harness can
+/// call: safe, without type parameters, taking no arguments (or just the
+/// `Bencher` for benches) and returning `()`. Reports every problem found,
+/// and returns whether the item can be used.
+fn check_test_signature(cx: &TestCtxt, i: &ast::Item, kind: TestKind) -> bool {
+    let diag = cx.span_diagnostic;
+    let what = kind.plural();
+
+    let (decl, unsafety, generics) = match i.node {
+        ast::ItemKind::Fn(ref decl, unsafety, _, _, ref generics, _) => {
+            (decl, unsafety, generics)
+        }
+        _ => {
+            diag.span_err(i.span, &format!("only functions may be used as {}", what));
+            return false;
+        }
+    };
+
+    let mut ok = true;
 
-    fn has_test_signature(i: &ast::Item) -> bool {
-        match i.node {
-            ast::ItemKind::Fn(ref decl, _, _, _, ref generics, _) => {
-                let input_cnt = decl.inputs.len();
-                let no_output = match decl.output {
-                    ast::FunctionRetTy::Default(..) => true,
-                    ast::FunctionRetTy::Ty(ref t) if t.node == ast::TyKind::Tup(vec![]) => true,
-                    _ => false
-                };
-                let tparm_cnt = generics.ty_params.len();
-                // NB: inadequate check, but we're running
-                // well before resolve, can't get too deep.
-                input_cnt == 1
-                    && no_output && tparm_cnt == 0
-            }
-          _ => false
+    if unsafety == ast::Unsafety::Unsafe {
+        diag.span_err(i.span, &format!("unsafe functions cannot be used for {}", what));
+        ok = false;
+    }
+
+    // Lifetime parameters are fine, the harness never has to name them.
+    let param_span = generics.ty_params.first().map(|p| p.span)
+        .or_else(|| generics.const_params.first().map(|p| p.span));
+    if let Some(span) = param_span {
+        diag.struct_span_err(span, &format!("functions used as {} cannot have type parameters",
+                                            what))
+            .help("the test harness has no way to choose the parameters")
+            .emit();
+        ok = false;
+    }
+
+    let args_span = match (decl.inputs.first(), decl.inputs.last()) {
+        (Some(first), Some(last)) => codemap::mk_sp(first.pat.span.lo, last.ty.span.hi),
+        _ => i.span,
+    };
+    match kind {
+        TestKind::Test if !decl.inputs.is_empty() => {
+            diag.span_err(args_span, "functions used as tests must not take any arguments");
+            ok = false;
+        }
+        TestKind::Bench if decl.inputs.len() != 1 => {
+            diag.struct_span_err(args_span,
+                                 "functions used as benches must take exactly one argument")
+                .help("the argument should be a `&mut test::Bencher`")
+                .emit();
+            ok = false;
         }
+        _ => {}
     }
 
-    if has_bench_attr && !has_test_signature(i) {
-        let diag = cx.span_diagnostic;
-        diag.span_err(i.span, "functions used as benches must have signature \
-                      `fn(&mut Bencher) -> ()`");
+    let bad_output_span = match decl.output {
+        ast::FunctionRetTy::Default(..) => None,
+        ast::FunctionRetTy::Ty(ref t) if t.node == ast::TyKind::Tup(vec![]) => None,
+        ast::FunctionRetTy::Ty(ref t) => Some(t.span),
+        ast::FunctionRetTy::None(span) => Some(span),
+    };
+    if let Some(span) = bad_output_span {
+        diag.struct_span_err(span, &format!("functions used as {} must return `()`", what))
+            .help("report failures by panicking instead")
+            .emit();
+        ok = false;
     }
 
-    return has_bench_attr && has_test_signature(i);
+    ok
 }
 
 fn is_ignored(i: &ast::Item) -> bool {
@@ -452,13 +565,15 @@ mod __test {
 
 fn mk_std(cx: &TestCtxt) -> P<ast::Item> {
     let id_test = token::str_to_ident("test");
-    let (vi, vis, ident) = if cx.is_test_crate {
+    let (vi, vis, ident, sp) = if cx.is_test_crate {
         (ast::ItemKind::Use(
             P(nospan(ast::ViewPathSimple(id_test,
                                          path_node(vec!(id_test)))))),
-         ast::Visibility::Public, token::special_idents::invalid)
+         ast::Visibility::Public, token::special_idents::invalid,
+         harness_span(cx, "pub use test;"))
     } else {
-        (ast::ItemKind::ExternCrate(None), ast::Visibility::Inherited, id_test)
+        (ast::ItemKind::ExternCrate(None), ast::Visibility::Inherited, id_test,
+         harness_span(cx, "extern crate test;"))
     };
     P(ast::Item {
         id: ast::DUMMY_NODE_ID,
@@ -466,19 +581,18 @@ fn mk_std(cx: &TestCtxt) -> P<ast::Item> {
         node: vi,
         attrs: vec![],
         vis: vis,
-        span: DUMMY_SP
+        span: sp
     })
 }
 
 fn mk_main(cx: &mut TestCtxt) -> P<ast::Item> {
-    // Writing this out by hand with 'ignored_span':
+    // Writing this out by hand with 'harness_span':
     //        pub fn main() {
     //            #![main]
-    //            use std::slice::AsSlice;
-    //            test::test_main_static(::std::os::args().as_slice(), TESTS);
+    //            test::test_main_static(TESTS);
     //        }
 
-    let sp = ignored_span(cx, DUMMY_SP);
+    let sp = harness_span(cx, "pub fn main()");
     let ecx = &cx.ext_cx;
 
     // test::test_main_static
@@ -524,7 +638,7 @@ fn mk_test_module(cx: &mut TestCtxt) -> (P<ast::Item>, Option<P<ast::Item>>) {
     let mainfn = mk_main(cx);
 
     let testmod = ast::Mod {
-        inner: DUMMY_SP,
+        inner: harness_span(cx, "{\n"),
         items: vec![import, mainfn, tests],
     };
     let item_ = ast::ItemKind::Mod(testmod);
@@ -536,7 +650,7 @@ fn mk_test_module(cx: &mut TestCtxt) -> (P<ast::Item>, Option<P<ast::Item>>) {
         attrs: vec![],
         node: item_,
         vis: ast::Visibility::Public,
-        span: DUMMY_SP,
+        span: harness_span(cx, "pub mod __test"),
     });
     let reexport = cx.reexport_test_harness_main.as_ref().map(|s| {
         // building `use <ident> = __test::main`
@@ -552,7 +666,7 @@ fn mk_test_module(cx: &mut TestCtxt) -> (P<ast::Item>, Option<P<ast::Item>>) {
             attrs: vec![],
             node: ast::ItemKind::Use(P(use_path)),
             vis: ast::Visibility::Inherited,
-            span: DUMMY_SP
+            span: harness_span(cx, "use __test::main")
         })
     });
 
@@ -583,7 +697,7 @@ fn mk_tests(cx: &TestCtxt) -> P<ast::Item> {
     // FIXME #15962: should be using quote_item, but that stringifies
     // __test_reexports, causing it to be reinterned, losing the
     // gensym information.
-    let sp = DUMMY_SP;
+    let sp = harness_span(cx, "const TESTS");
     let ecx = &cx.ext_cx;
     let struct_type = ecx.ty_path(ecx.path(sp, vec![ecx.ident_of("self"),
                                                     ecx.ident_of("test"),
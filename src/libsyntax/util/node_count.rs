@@ -12,7 +12,7 @@
 
 use visit::*;
 use ast::*;
-use codemap::Span;
+use codemap::{Span, SpanTable};
 
 pub struct NodeCounter {
     pub count: usize,
@@ -162,3 +162,52 @@ impl<'v> Visitor<'v> for NodeCounter {
     }
 
 }
+
+/// Interns the spans of the major AST nodes into a fresh `SpanTable`, to
+/// estimate how much memory the compact span representation would save.
+pub struct SpanCounter {
+    pub table: SpanTable,
+}
+
+impl SpanCounter {
+    pub fn new() -> SpanCounter {
+        SpanCounter {
+            table: SpanTable::new(),
+        }
+    }
+}
+
+impl<'v> Visitor<'v> for SpanCounter {
+    fn visit_ident(&mut self, span: Span, ident: Ident) {
+        self.table.intern(span);
+        walk_ident(self, span, ident);
+    }
+    fn visit_item(&mut self, i: &'v Item) {
+        self.table.intern(i.span);
+        walk_item(self, i)
+    }
+    fn visit_stmt(&mut self, s: &'v Stmt) {
+        self.table.intern(s.span);
+        walk_stmt(self, s)
+    }
+    fn visit_block(&mut self, b: &'v Block) {
+        self.table.intern(b.span);
+        walk_block(self, b)
+    }
+    fn visit_pat(&mut self, p: &'v Pat) {
+        self.table.intern(p.span);
+        walk_pat(self, p)
+    }
+    fn visit_expr(&mut self, ex: &'v Expr) {
+        self.table.intern(ex.span);
+        walk_expr(self, ex)
+    }
+    fn visit_ty(&mut self, t: &'v Ty) {
+        self.table.intern(t.span);
+        walk_ty(self, t)
+    }
+    fn visit_path(&mut self, path: &'v Path, _id: NodeId) {
+        self.table.intern(path.span);
+        walk_path(self, path)
+    }
+}
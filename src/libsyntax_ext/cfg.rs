@@ -21,10 +21,10 @@ use syntax::attr;
 use syntax::parse::token;
 use syntax::config::CfgDiagReal;
 
-pub fn expand_cfg<'cx>(cx: &mut ExtCtxt,
-                       sp: Span,
-                       tts: &[ast::TokenTree])
-                       -> Box<base::MacResult+'static> {
+pub fn expand_cfg(cx: &mut ExtCtxt,
+                  sp: Span,
+                  tts: &[ast::TokenTree])
+                  -> Box<base::MacResult+'static> {
     let mut p = cx.new_parser_from_tts(tts);
     let cfg = panictry!(p.parse_meta_item());
 
@@ -11,7 +11,7 @@
 use deriving::generic::*;
 use deriving::generic::ty::*;
 
-use syntax::ast::{MetaItem, Expr};
+use syntax::ast::{EnumDef, Expr, MetaItem, Variant};
 use syntax::codemap::Span;
 use syntax::ext::base::{ExtCtxt, Annotatable};
 use syntax::ext::build::AstBuilder;
@@ -75,11 +75,60 @@ fn default_substructure(cx: &mut ExtCtxt, trait_span: Span, substr: &Substructur
                 }
             }
         }
-        StaticEnum(..) => {
-            cx.span_err(trait_span, "`Default` cannot be derived for enums, only structs");
-            // let compilation continue
-            cx.expr_usize(trait_span, 0)
+        StaticEnum(enum_def, _) => {
+            match default_variant(cx, trait_span, enum_def) {
+                Some(variant) => {
+                    let path = cx.path(trait_span, vec![substr.type_ident, variant.node.name]);
+                    cx.expr_path(path)
+                }
+                // let compilation continue
+                None => cx.expr_usize(trait_span, 0),
+            }
         }
         _ => cx.span_bug(trait_span, "Non-static method in `derive(Default)`")
     };
 }
+
+/// Finds the variant marked `#[default]`, reporting an error unless there
+/// is exactly one such variant and it has no fields.
+fn default_variant<'a>(cx: &ExtCtxt,
+                       trait_span: Span,
+                       enum_def: &'a EnumDef)
+                       -> Option<&'a Variant> {
+    // Count rather than use `any` so that every `#[default]` gets marked as used.
+    let defaults: Vec<_> = enum_def.variants.iter().filter(|variant| {
+        variant.node.attrs.iter().filter(|a| a.check_name("default")).count() > 0
+    }).collect();
+
+    match defaults.len() {
+        0 => {
+            cx.struct_span_err(trait_span, "no default declared for `#[derive(Default)]` on enum")
+              .help("mark a unit variant with `#[default]` to make it the default")
+              .emit();
+            None
+        }
+        1 => {
+            let variant = defaults[0];
+            if !variant.node.data.is_unit() {
+                cx.struct_span_err(variant.span,
+                                   "the `#[default]` attribute may only be used \
+                                    on unit enum variants")
+                  .span_note(trait_span, "required by `#[derive(Default)]` here")
+                  .emit();
+                return None;
+            }
+            Some(variant)
+        }
+        _ => {
+            let mut err = cx.struct_span_err(trait_span,
+                                             "multiple declared defaults for \
+                                              `#[derive(Default)]` on enum");
+            for variant in &defaults {
+                err.span_note(variant.span, "marked `#[default]` here");
+            }
+            err.help("only one variant can be marked `#[default]`");
+            err.emit();
+            None
+        }
+    }
+}
@@ -23,6 +23,27 @@ use syntax::parse::token;
 
 use std::env;
 
+/// Reads an environment variable for `env!` or `option_env!`, recording it
+/// so that dep-info can list the variables the crate depends on. Returns
+/// `None`, after reporting an error, if the session's I/O policy doesn't
+/// allow `mac` to read `var`.
+fn read_env_var(cx: &ExtCtxt, sp: Span, mac: &str, var: &str)
+                -> Option<Result<String, env::VarError>> {
+    {
+        let io = cx.parse_sess.io.borrow();
+        if !io.may_read_env(var) {
+            cx.struct_span_err(sp, &format!("`{}` may not read environment variable `{}`",
+                                            mac, var))
+              .note(&io.readable_env_note())
+              .emit();
+            return None;
+        }
+    }
+    let value = env::var(var);
+    cx.parse_sess.env_depinfo.borrow_mut().insert(var.to_string(), value.clone().ok());
+    Some(value)
+}
+
 pub fn expand_option_env<'cx>(cx: &'cx mut ExtCtxt, sp: Span, tts: &[ast::TokenTree])
                               -> Box<base::MacResult+'cx> {
     let var = match get_single_str_from_tts(cx, sp, tts, "option_env!") {
@@ -30,7 +51,11 @@ pub fn expand_option_env<'cx>(cx: &'cx mut ExtCtxt, sp: Span, tts: &[ast::TokenT
         Some(v) => v
     };
 
-    let e = match env::var(&var[..]) {
+    let value = match read_env_var(cx, sp, "option_env!", &var) {
+        Some(value) => value,
+        None => return DummyResult::expr(sp),
+    };
+    let e = match value {
       Err(..) => {
           cx.expr_path(cx.path_all(sp,
                                    true,
@@ -95,7 +120,11 @@ pub fn expand_env<'cx>(cx: &'cx mut ExtCtxt, sp: Span, tts: &[ast::TokenTree])
         }
     }
 
-    let e = match env::var(&var[..]) {
+    let value = match read_env_var(cx, sp, "env!", &var) {
+        Some(value) => value,
+        None => return DummyResult::expr(sp),
+    };
+    let e = match value {
         Err(_) => {
             cx.span_err(sp, &msg);
             cx.expr_usize(sp, 0)
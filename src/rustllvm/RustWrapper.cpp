@@ -965,6 +965,32 @@ extern "C" void LLVMWriteDebugLocToString(
 #endif
 }
 
+// Unpacks the line, column, file name and directory of a debug location,
+// returning false if there is no location to unpack.
+extern "C" bool LLVMRustUnpackDebugLoc(
+    LLVMDebugLocRef dl,
+    unsigned *line_out,
+    unsigned *col_out,
+    RustStringRef file_out,
+    RustStringRef dir_out)
+{
+#if LLVM_VERSION_MINOR >= 7
+    const DebugLoc &loc = *unwrap(dl);
+    if (!loc)
+        return false;
+    *line_out = loc.getLine();
+    *col_out = loc.getCol();
+    DIScope *scope = cast<DIScope>(loc.getScope());
+    raw_rust_string_ostream file(file_out);
+    file << scope->getFilename();
+    raw_rust_string_ostream dir(dir_out);
+    dir << scope->getDirectory();
+    return true;
+#else
+    return false;
+#endif
+}
+
 DEFINE_SIMPLE_CONVERSION_FUNCTIONS(SMDiagnostic, LLVMSMDiagnosticRef)
 
 extern "C" void LLVMSetInlineAsmDiagnosticHandler(
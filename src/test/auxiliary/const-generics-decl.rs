@@ -0,0 +1,25 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(const_generics)]
+
+pub struct Buffer<T, const N: usize> {
+    pub data: [T; N],
+}
+
+impl<T: Copy> Buffer<T, 4> {
+    pub fn filled(x: T) -> Buffer<T, 4> {
+        Buffer::<T, 4> { data: [x; 4] }
+    }
+}
+
+pub fn first<T: Copy, const N: usize>(xs: &[T; N]) -> T {
+    xs[0]
+}
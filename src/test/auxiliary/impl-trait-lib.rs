@@ -0,0 +1,28 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(conservative_impl_trait)]
+
+pub fn fizzbuzz(limit: u32) -> impl Iterator<Item=String> {
+    (1..limit + 1).map(|i| match (i % 3, i % 5) {
+        (0, 0) => "FizzBuzz".to_string(),
+        (0, _) => "Fizz".to_string(),
+        (_, 0) => "Buzz".to_string(),
+        _ => i.to_string(),
+    })
+}
+
+pub struct Wrapper<T>(pub Vec<T>);
+
+impl<T: Clone> Wrapper<T> {
+    pub fn items(&self) -> impl Iterator<Item=T> {
+        self.0.clone().into_iter()
+    }
+}
@@ -0,0 +1,55 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+// force-host
+
+#![feature(plugin_registrar, rustc_private)]
+#![feature(box_syntax)]
+
+#[macro_use] extern crate rustc;
+extern crate rustc_front;
+extern crate rustc_plugin;
+
+use rustc::lint::{LateContext, LintContext, LintPass, LateLintPass, LateLintPassObject, LintArray};
+use rustc_plugin::Registry;
+use rustc_front::hir;
+
+declare_lint!(UNPROMOTABLE_BORROW, Warn, "borrow of a temporary that isn't constant");
+
+struct Pass;
+
+impl LintPass for Pass {
+    fn get_lints(&self) -> LintArray {
+        lint_array!(UNPROMOTABLE_BORROW)
+    }
+}
+
+impl LateLintPass for Pass {
+    fn check_expr(&mut self, cx: &LateContext, e: &hir::Expr) {
+        let inner = match e.node {
+            hir::ExprAddrOf(hir::MutImmutable, ref inner) => inner,
+            _ => return,
+        };
+        match inner.node {
+            hir::ExprPath(..) | hir::ExprField(..) | hir::ExprTupField(..) |
+            hir::ExprIndex(..) | hir::ExprUnary(hir::UnDeref, _) => return,
+            _ => {}
+        }
+        if let Some(cause) = cx.tcx.not_const_causes.borrow().get(&inner.id) {
+            cx.span_lint_note(UNPROMOTABLE_BORROW, e.span,
+                              "this borrow's value can't be promoted to a constant",
+                              cause.span, cause.reason.description());
+        }
+    }
+}
+
+#[plugin_registrar]
+pub fn plugin_registrar(reg: &mut Registry) {
+    reg.register_late_lint_pass(box Pass as LateLintPassObject);
+}
@@ -0,0 +1,22 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+pub trait Super {
+    type Output;
+}
+
+pub trait Bar {
+    fn bar(&self, x: &Self);
+}
+
+pub trait Baz: Super {
+    fn baz(&self) -> Self::Output;
+    fn copy(&self) -> Self where Self: Sized;
+}
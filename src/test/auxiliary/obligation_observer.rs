@@ -0,0 +1,63 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// force-host
+
+#![feature(plugin_registrar, rustc_private)]
+#![feature(box_syntax)]
+
+extern crate rustc;
+extern crate rustc_plugin;
+
+use rustc::middle::traits::{FulfillmentError, ObligationObserver, PredicateObligation};
+use rustc::middle::ty::{self, TyCtxt, TypeFoldable};
+use rustc_plugin::Registry;
+
+/// Warns about the obligations of the form `T: Observed` for some
+/// non-generic `T`, so that tests can check the events are delivered.
+struct Observer;
+
+fn observed<'tcx>(tcx: &TyCtxt<'tcx>, obligation: &PredicateObligation<'tcx>) -> bool {
+    match obligation.predicate {
+        ty::Predicate::Trait(ref data) => {
+            tcx.item_name(data.def_id()).as_str() == "Observed" &&
+                !data.skip_binder().self_ty().has_param_types()
+        }
+        _ => false,
+    }
+}
+
+impl<'tcx> ObligationObserver<'tcx> for Observer {
+    fn registered(&mut self, tcx: &TyCtxt<'tcx>, obligation: &PredicateObligation<'tcx>) {
+        if observed(tcx, obligation) {
+            tcx.sess.span_warn(obligation.cause.span,
+                               &format!("registered `{}`", obligation.predicate));
+        }
+    }
+
+    fn fulfilled(&mut self, tcx: &TyCtxt<'tcx>, obligation: &PredicateObligation<'tcx>) {
+        if observed(tcx, obligation) {
+            tcx.sess.span_warn(obligation.cause.span,
+                               &format!("fulfilled `{}`", obligation.predicate));
+        }
+    }
+
+    fn failed(&mut self, tcx: &TyCtxt<'tcx>, error: &FulfillmentError<'tcx>) {
+        if observed(tcx, &error.obligation) {
+            tcx.sess.span_warn(error.obligation.cause.span,
+                               &format!("failed `{}`", error.obligation.predicate));
+        }
+    }
+}
+
+#[plugin_registrar]
+pub fn plugin_registrar(reg: &mut Registry) {
+    reg.register_obligation_observer(box Observer);
+}
@@ -0,0 +1,25 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(specialization)]
+
+pub trait Greet {
+    fn greet(&self) -> String;
+    fn loud(&self) -> bool;
+}
+
+impl<T: ?Sized> Greet for T {
+    default fn greet(&self) -> String { "hello".to_string() }
+    default fn loud(&self) -> bool { false }
+}
+
+impl Greet for str {
+    fn greet(&self) -> String { format!("hello, {}", self) }
+}
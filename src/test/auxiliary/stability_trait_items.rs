@@ -0,0 +1,30 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(staged_api)]
+#![stable(feature = "stable_test", since = "1.0.0")]
+
+#[stable(feature = "stable_test", since = "1.0.0")]
+pub trait WithDefault {
+    #[stable(feature = "stable_test", since = "1.0.0")]
+    fn stable(&self);
+
+    #[unstable(feature = "unstable_test", reason = "not settled", issue = "3")]
+    fn unstable(&self) {}
+}
+
+#[stable(feature = "stable_test", since = "1.0.0")]
+pub trait Required {
+    #[stable(feature = "stable_test", since = "1.0.0")]
+    fn stable(&self);
+
+    #[unstable(feature = "unstable_test", issue = "4")]
+    fn unstable(&self);
+}
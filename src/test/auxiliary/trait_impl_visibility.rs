@@ -0,0 +1,37 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+pub struct S;
+
+// A private trait implemented for a public type.
+trait Hidden {
+    fn hidden(&self) -> u32;
+}
+
+impl Hidden for S {
+    fn hidden(&self) -> u32 { 1 }
+}
+
+pub fn call_hidden(s: &S) -> u32 {
+    s.hidden() + <S as Hidden>::hidden(s)
+}
+
+// A public trait implemented inside a private module.
+pub trait Shown {
+    type Out;
+    fn shown(&self) -> Self::Out;
+}
+
+mod imp {
+    impl super::Shown for super::S {
+        type Out = u32;
+        fn shown(&self) -> u32 { 2 }
+    }
+}
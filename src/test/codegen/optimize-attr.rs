@@ -0,0 +1,27 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -C no-prepopulate-passes -C opt-level=2
+
+#![crate_type = "lib"]
+#![feature(optimize_attribute)]
+
+// CHECK: Function Attrs: {{.*}}optsize
+// CHECK-NEXT: define void @small
+#[no_mangle]
+#[optimize(size)]
+pub fn small() {}
+
+// CHECK: Function Attrs: {{.*}}noinline{{.*}}optnone
+// CHECK-NEXT: define void @unoptimized
+#[no_mangle]
+#[inline(always)]
+#[optimize(none)]
+pub fn unoptimized() {}
@@ -0,0 +1,32 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+// aux-build:lint_const_qualif.rs
+// ignore-stage1
+// compile-flags: -D unpromotable-borrow
+
+// Test that lints can find out which subexpression keeps a value from being
+// promoted to a constant.
+
+#![feature(plugin)]
+#![plugin(lint_const_qualif)]
+
+fn one() -> u8 { 1 }
+
+const TWO: u8 = 2;
+
+fn main() {
+    let _promoted = &(1 + TWO);
+    let _local = &(TWO + one());
+    //~^ ERROR this borrow's value can't be promoted to a constant
+    //~| NOTE only constant functions and constructors can be called
+    let _nested = &[(1, 2), (3, Box::new(4))];
+    //~^ ERROR this borrow's value can't be promoted to a constant
+    //~| NOTE only constant functions and constructors can be called
+}
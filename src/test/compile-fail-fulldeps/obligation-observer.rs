@@ -0,0 +1,34 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// aux-build:obligation_observer.rs
+// ignore-stage1
+
+// Test that plugins are told when obligations are registered, fulfilled and
+// found not to hold.
+
+#![feature(plugin)]
+#![plugin(obligation_observer)]
+
+trait Observed {}
+
+impl Observed for u8 {}
+
+fn observe<T: Observed>(_: T) {}
+
+fn main() {
+    observe(0u8);
+    //~^ WARN registered `u8 : Observed`
+    //~| WARN fulfilled `u8 : Observed`
+    observe(0u16);
+    //~^ WARN registered `u16 : Observed`
+    //~| WARN failed `u16 : Observed`
+    //~| ERROR the trait `Observed` is not implemented for the type `u16`
+}
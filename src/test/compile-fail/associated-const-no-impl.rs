@@ -0,0 +1,33 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that naming an associated const through a trait requires the
+// type to actually implement that trait.
+
+#![feature(associated_consts)]
+
+trait Foo {
+    const X: i32;
+}
+
+trait Bar<T> {
+    const Y: T;
+}
+
+impl Bar<u32> for i32 {
+    const Y: u32 = 1;
+}
+
+fn main() {
+    let _ = <i32 as Foo>::X;
+    //~^ ERROR the trait `Foo` is not implemented for the type `i32`
+    let _ = <i32 as Bar<u64>>::Y;
+    //~^ ERROR the trait `Bar<u64>` is not implemented for the type `i32`
+}
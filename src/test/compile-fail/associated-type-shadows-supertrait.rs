@@ -0,0 +1,41 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that an associated type reusing the name of an associated type from
+// one of the trait's supertraits, directly or transitively, is linted.
+
+#![deny(shadowed_associated_types)]
+
+pub trait Vehicle {
+    type Color;
+}
+
+pub trait Car : Vehicle {
+    type Color; //~ ERROR associated type `Color` is already declared in supertrait `Vehicle`
+    //~^ WARN was previously accepted
+}
+
+pub trait Truck : Vehicle {
+    type Wheels;
+}
+
+pub trait Trailer : Truck {
+    type Wheels; //~ ERROR associated type `Wheels` is already declared in supertrait `Truck`
+    //~^ WARN was previously accepted
+    type Color; //~ ERROR associated type `Color` is already declared in supertrait `Vehicle`
+    //~^ WARN was previously accepted
+}
+
+// Names that don't clash are fine.
+pub trait Bicycle : Vehicle {
+    type Frame;
+}
+
+fn main() { }
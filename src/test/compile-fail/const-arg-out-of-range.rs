@@ -0,0 +1,23 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(const_generics)]
+
+struct Byte<const N: u8>;
+struct Signed<const N: i8>;
+
+fn main() {
+    let _: Byte<255>;
+    let _: Byte<256>; //~ ERROR const argument out of range for `u8`
+    let _: Signed<-128>;
+    let _: Signed<127>;
+    let _: Signed<128>; //~ ERROR const argument out of range for `i8`
+    let _: Signed<-129>; //~ ERROR const argument out of range for `i8`
+}
@@ -18,7 +18,8 @@ const CONSTANT: S = S(0);
 
 enum E {
     V = CONSTANT,
-    //~^ NOTE: for enum discriminant here
+    //~^ NOTE: while evaluating the value of the constant `CONSTANT`
+    //~| NOTE: for enum discriminant here
 }
 
 fn main() {}
@@ -0,0 +1,28 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(const_generics)]
+
+struct Grid<const W: usize, const H: usize> {
+    cells: [[bool; W]; H],
+}
+
+fn len<const N: usize>(xs: &[u8; N]) -> usize { xs.len() }
+
+fn main() {
+    let _: Grid;
+    //~^ ERROR wrong number of const arguments: expected 2, found 0
+    let _: Grid<3>;
+    //~^ ERROR wrong number of const arguments: expected 2, found 1
+    let _: Grid<3, 2, 1>;
+    //~^ ERROR wrong number of const arguments: expected 2, found 3
+    let _ = len::<2, 3>;
+    //~^ ERROR wrong number of const arguments: expected 1, found 2
+}
@@ -0,0 +1,25 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(const_generics, slice_patterns)]
+
+fn first<const N: usize>(a: [u8; N]) -> u8 {
+    match a {
+        [x, ..] => x, //~ ERROR a slice pattern on `[u8; N]` must be a single `..` binding
+    }
+}
+
+fn exact<const N: usize>(a: [u8; N]) -> u8 {
+    match a {
+        [x, y] => x + y, //~ ERROR a slice pattern on `[u8; N]` must be a single `..` binding
+    }
+}
+
+fn main() {}
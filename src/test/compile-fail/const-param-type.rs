@@ -0,0 +1,21 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(const_generics)]
+
+struct Fine<const A: u8, const B: bool>;
+
+struct Char<const C: char>; //~ ERROR the type of a const parameter must be an integer
+
+struct Float<const X: f64>; //~ ERROR the type of a const parameter must be an integer
+
+fn string<const S: &'static str>() {} //~ ERROR the type of a const parameter must be an integer
+
+fn main() {}
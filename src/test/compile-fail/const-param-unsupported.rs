@@ -0,0 +1,26 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(const_generics)]
+
+struct S;
+
+impl<const N: usize> S {}
+//~^ ERROR const parameters are not yet supported on impls
+
+trait Tr<const N: usize> {}
+//~^ ERROR const parameters are not yet supported on traits
+
+impl S {
+    fn method<const N: usize>(&self) {}
+    //~^ ERROR const parameters are not yet supported on methods
+}
+
+fn main() {}
@@ -0,0 +1,22 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that a cycle error notes each step of the cycle: the first at the
+// item it is about, the others at the reference that demanded them.
+
+type A = B;
+//~^ ERROR unsupported cyclic reference
+//~| HELP removing this reference to `B` from `A` would break the cycle
+
+type B = A;
+//~^ NOTE the cycle begins when processing `B`...
+//~| NOTE ...which then requires processing `A`...
+
+fn main() { }
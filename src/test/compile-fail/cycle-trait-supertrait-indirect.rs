@@ -12,14 +12,26 @@
 // a direct participant in the cycle.
 
 trait A: B {
-    //~^ ERROR unsupported cyclic reference
 }
 
+// Converting `A` and then `B` each close the cycle at `C`'s bound; converting
+// `C` closes it at `B`'s bound.
+
 trait B: C {
     //~^ ERROR unsupported cyclic reference
+    //~| NOTE the cycle begins when computing the supertraits of `B`...
+    //~| NOTE ...which then requires computing the supertraits of `C`...
+    //~| NOTE the cycle begins when computing the supertraits of `B`...
+    //~| NOTE ...which then requires computing the supertraits of `C`...
+    //~| HELP removing this reference to `C` from the supertraits of `B`
 }
 
 trait C: B { }
-    //~^ ERROR unsupported cyclic reference
+//~^ ERROR unsupported cyclic reference
+//~| ERROR unsupported cyclic reference
+//~| NOTE the cycle begins when computing the supertraits of `C`...
+//~| NOTE ...which then requires computing the supertraits of `B`...
+//~| HELP removing this reference to `B` from the supertraits of `C`
+//~| HELP removing this reference to `B` from the supertraits of `C`
 
 fn main() { }
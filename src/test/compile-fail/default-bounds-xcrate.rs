@@ -0,0 +1,27 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// aux-build:default_bounds_xcrate.rs
+
+// Test that a `#[default_bound]` trait from another crate is an implicit
+// bound on the type parameters of this one.
+
+extern crate default_bounds_xcrate;
+
+use default_bounds_xcrate::{Movable, Pinned};
+
+fn take<T>(x: T) -> T { x }
+
+fn peek<T: ?Movable>(x: &T) -> &T { x }
+
+fn main() {
+    peek(&Pinned);
+    take(Pinned); //~ ERROR the trait `default_bounds_xcrate::Movable` is not implemented
+}
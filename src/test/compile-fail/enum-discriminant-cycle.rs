@@ -0,0 +1,29 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(const_fn, associated_consts)]
+
+enum E {
+    A = E::X, //~ ERROR recursive constant
+}
+
+impl E {
+    const X: isize = E::A as isize; //~ ERROR recursive constant
+}
+
+const fn b() -> isize {
+    F::B as isize
+}
+
+enum F {
+    B = b(), //~ ERROR recursive constant
+}
+
+fn main() {}
@@ -0,0 +1,29 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that a failed enum discriminant evaluation points at each step
+// between the discriminant and the operation that failed.
+
+#![feature(const_fn)]
+
+const fn double(x: u8) -> u8 { x * 2 }
+//~^ ERROR constant evaluation error: attempted to mul with overflow
+
+const BASE: u8 = 200;
+
+#[repr(u8)]
+enum E {
+    A = double(BASE) + 1,
+    //~^ NOTE while evaluating this call to const fn `double`
+    //~| NOTE while evaluating the left operand of `+`
+    //~| NOTE for enum discriminant here
+}
+
+fn main() {}
@@ -0,0 +1,32 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Associated consts of traits that are not in scope do not give
+// discriminants, even if they are the only ones of that name.
+
+#![feature(associated_consts)]
+
+struct S;
+
+mod hidden {
+    pub trait Hidden {
+        const HIDDEN: isize;
+    }
+
+    impl Hidden for super::S {
+        const HIDDEN: isize = 5;
+    }
+}
+
+enum E {
+    A = S::HIDDEN, //~ ERROR unresolved path in constant expression
+}
+
+fn main() {}
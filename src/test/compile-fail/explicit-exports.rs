@@ -0,0 +1,35 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z explicit-exports
+
+#![crate_type = "dylib"]
+#![feature(export_attribute)]
+
+#[export]
+pub fn exported() {}
+
+#[export]
+pub static EXPORTED: u32 = 0;
+
+pub fn not_exported() {}
+
+#[no_mangle]
+pub extern fn not_listed() {} //~ ERROR not marked `#[export]`
+
+#[export]
+#[no_mangle]
+pub extern fn listed() {}
+
+#[export]
+pub fn generic<T>(_: T) {} //~ ERROR generic functions cannot be marked `#[export]`
+
+#[export]
+pub struct NotAFunction; //~ ERROR only functions and statics can be marked `#[export]`
@@ -0,0 +1,33 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Generic associated types can be declared, but not yet used.
+
+#![feature(generic_associated_types, associated_type_defaults)]
+
+trait Collection {
+    type Iter<'a>;
+    type Member<T>;
+    type Bounded<T>: Clone;
+    //~^ ERROR bounds on generic associated types are not yet supported
+    type Defaulted<T> = Vec<T>;
+    //~^ ERROR defaults for generic associated types are not yet supported
+}
+
+fn iter<C: Collection>(_: C::Iter) {}
+//~^ ERROR projections from the generic associated type `Iter` are not yet supported
+
+fn member<C: Collection>(_: <C as Collection>::Member) {}
+//~^ ERROR projections from the generic associated type `Member` are not yet supported
+
+fn binding<C: Collection<Member = u8>>() {}
+//~^ ERROR projections from the generic associated type `Member` are not yet supported
+
+fn main() {}
@@ -0,0 +1,23 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Type parameters on foreign functions are rejected even when mixed with
+// lifetime parameters, while lifetime parameters alone are accepted.
+
+extern {
+    fn lifetimes_only<'a, 'b>(x: &'a i32, y: &'b i32) -> &'a i32;
+
+    fn mixed<'a, T>(x: &'a T) -> &'a T;
+    //~^ ERROR foreign items may not have type parameters
+    //~| NOTE `mixed` is defined outside of Rust, so it can't be monomorphized
+    //~| HELP declare a separate foreign function for each type you need
+}
+
+fn main() {}
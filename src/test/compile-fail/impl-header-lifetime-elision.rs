@@ -0,0 +1,24 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Lifetimes may be elided in an impl header, but elision is still not
+// permitted in the associated types of the impl.
+
+struct Foo;
+
+trait Pair {
+    type Item;
+}
+
+impl Pair for &Foo {
+    type Item = &Foo; //~ ERROR missing lifetime specifier
+}
+
+fn main() {}
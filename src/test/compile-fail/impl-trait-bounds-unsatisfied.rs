@@ -0,0 +1,35 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that the concrete type behind an `impl Trait` has to satisfy its
+// bounds, and that callers only get to use those bounds.
+
+#![feature(conservative_impl_trait)]
+
+use std::fmt::Debug;
+
+struct NotDebug;
+
+fn not_debug() -> impl Debug {
+    NotDebug //~ ERROR the trait `core::fmt::Debug` is not implemented for the type `NotDebug`
+}
+
+fn number() -> impl Debug {
+    42u32
+}
+
+fn main() {
+    let _: u32 = number();
+    //~^ ERROR mismatched types
+    //~| expected `u32`
+    //~| found `impl core::fmt::Debug`
+    number() + 1;
+    //~^ ERROR binary operation `+` cannot be applied to type `impl core::fmt::Debug`
+}
@@ -0,0 +1,42 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that `impl Trait` is only allowed in the return types of free
+// functions and inherent methods.
+
+#![feature(conservative_impl_trait)]
+
+use std::fmt::Debug;
+
+fn arg(_: impl Debug) {}
+//~^ ERROR `impl Trait` not allowed outside of function and inherent method return types
+
+struct Foo;
+
+trait Bar {
+    fn bar(&self) -> impl Debug;
+    //~^ ERROR `impl Trait` not allowed outside of function and inherent method return types
+}
+
+impl Bar for Foo {
+    fn bar(&self) -> impl Debug { 0 }
+    //~^ ERROR `impl Trait` not allowed outside of function and inherent method return types
+}
+
+type Alias = impl Debug;
+//~^ ERROR `impl Trait` not allowed outside of function and inherent method return types
+
+fn nested() -> fn() -> impl Debug { loop {} }
+//~^ ERROR `impl Trait` not allowed outside of function and inherent method return types
+
+fn main() {
+    let _: impl Debug = 0;
+    //~^ ERROR `impl Trait` not allowed outside of function and inherent method return types
+}
@@ -0,0 +1,27 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that `impl Trait` cannot name the late-bound lifetimes of the
+// function it is returned from, as it only closes over the early-bound
+// parameters.
+
+#![feature(conservative_impl_trait)]
+
+fn elided(x: &u32) -> impl Iterator<Item=&u32> {
+    //~^ ERROR `impl Trait` cannot capture lifetimes that are late-bound to the enclosing function
+    Some(x).into_iter()
+}
+
+fn named<'a>(x: &'a u32) -> impl Iterator<Item=&'a u32> {
+    //~^ ERROR `impl Trait` cannot capture lifetimes that are late-bound to the enclosing function
+    Some(x).into_iter()
+}
+
+fn main() {}
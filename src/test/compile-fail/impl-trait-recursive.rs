@@ -0,0 +1,24 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(conservative_impl_trait)]
+
+use std::fmt::Debug;
+
+fn direct() -> impl Debug { //~ ERROR the concrete type behind this `impl Trait` contains itself
+    direct()
+}
+
+fn nested(n: u32) -> impl Debug {
+//~^ ERROR the concrete type behind this `impl Trait` contains itself
+    if n == 0 { None } else { Some(nested(n - 1)) }
+}
+
+fn main() {}
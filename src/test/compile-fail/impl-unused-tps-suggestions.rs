@@ -0,0 +1,38 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check the fixes suggested for an unconstrained type parameter of an impl.
+
+trait Maker {
+    type Output;
+    fn make(&self) -> Self::Output;
+}
+
+struct Unit;
+
+impl<T> Maker for Unit {
+//~^ ERROR the type parameter `T` is not constrained
+//~| HELP remove the parameter
+//~| HELP or use `T` in the self type or the trait
+    type Output = ();
+    fn make(&self) {}
+}
+
+struct Wrapper<I>(I);
+
+impl<I: Iterator, T> Maker for Wrapper<I> {
+//~^ ERROR the type parameter `T` is not constrained
+//~| HELP or use `T` in the self type or the trait
+//~| HELP or constrain `T` through the bounds of `I`
+    type Output = Option<T>;
+    fn make(&self) -> Option<T> { None }
+}
+
+fn main() {}
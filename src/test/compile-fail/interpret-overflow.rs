@@ -0,0 +1,19 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: --emit=none -Z interpret=main
+
+fn add(a: u8, b: u8) -> u8 {
+    a + b //~ ERROR interpreted program failed: attempted to add with overflow
+}
+
+fn main() {
+    add(200, 100); //~ NOTE inside the call to `add` here
+}
@@ -0,0 +1,24 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: --emit=none -Z interpret=main
+
+// A panic passes its location by reference to a static, which the
+// interpreter must not need to read to report the panic itself.
+
+fn check(x: u32) {
+    assert!(x < 10);
+    //~^ ERROR interpreted program failed: the program panicked: 'assertion failed: x < 10'
+}
+
+fn main() {
+    check(3);
+    check(12); //~ NOTE inside the call to `check` here
+}
@@ -19,6 +19,8 @@ trait HasInventory {
 
 trait TraversesWorld {
     fn attemptTraverse(&self, room: &Room, directionStr: &str) -> Result<&Room, &str> {
+    //~^ NOTE the anonymous lifetime #1 is the lifetime elided in the type of `self`
+    //~| NOTE the anonymous lifetime #2 is the lifetime elided in the type of argument `room`
         let direction = str_to_direction(directionStr);
         let maybe_room = room.direction_to_room.get(&direction);
         //~^ ERROR cannot infer an appropriate lifetime for autoref due to conflicting requirements
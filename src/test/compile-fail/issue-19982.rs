@@ -12,6 +12,9 @@
 
 struct Foo;
 
-impl Fn<(&(),)> for Foo { } //~ ERROR missing lifetime specifier
+// The elided lifetime in the trait reference is an anonymous lifetime
+// parameter of the impl, so only the missing items are reported.
+impl Fn<(&(),)> for Foo { } //~ ERROR not all trait items implemented
+//~| ERROR is not implemented for the type `Foo`
 
 fn main() {}
@@ -17,6 +17,8 @@ fn f() -> &isize {    //~ ERROR missing lifetime specifier
 // Lifetime annotation needed because we have two by-reference parameters.
 fn g(_x: &isize, _y: &isize) -> &isize {    //~ ERROR missing lifetime specifier
 //~^ HELP the signature does not say whether it is borrowed from `_x` or `_y`
+//~| NOTE taken from the only input lifetime, but there are 2 input lifetimes
+//~| NOTE taken from `&self` or `&mut self`, but this function has no such receiver
     panic!()
 }
 
@@ -28,6 +30,8 @@ struct Foo<'a> {
 // and one on the reference.
 fn h(_x: &Foo) -> &isize { //~ ERROR missing lifetime specifier
 //~^ HELP the signature does not say which one of `_x`'s 2 elided lifetimes it is borrowed from
+//~| NOTE taken from the only input lifetime, but there are 2 input lifetimes
+//~| NOTE taken from `&self` or `&mut self`, but this function has no such receiver
     panic!()
 }
 
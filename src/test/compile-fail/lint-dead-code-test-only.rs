@@ -0,0 +1,45 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Helpers that are only used from `#[cfg(test)]` code are not dead code in
+// non-test builds.
+
+#![deny(dead_code)]
+
+fn only_for_tests() -> u32 { 1 }
+
+fn only_for_tests_in_macro() -> u32 { 2 }
+
+struct Fixture;
+
+impl Fixture {
+    fn setup(&self) {}
+}
+
+fn really_unused() {} //~ ERROR function is never used
+
+mod other {
+    // Not the `only_for_tests` the tests use.
+    fn only_for_tests() -> u32 { 3 } //~ ERROR function is never used
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_works() {
+        only_for_tests();
+        assert_eq!(only_for_tests_in_macro(), 2);
+        Fixture.setup();
+    }
+}
+
+fn main() {}
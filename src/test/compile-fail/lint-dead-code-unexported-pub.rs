@@ -0,0 +1,24 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Dead `pub` items name the private item that keeps them from being
+// exported.
+
+#![deny(dead_code)]
+#![crate_type = "lib"]
+
+mod outer {
+    pub mod inner {
+        pub fn hidden() {}
+        //~^ ERROR never used: `hidden`; it is `pub`, but not visible outside the crate as `outer`
+    }
+}
+
+fn unused() {} //~ ERROR function is never used: `unused`
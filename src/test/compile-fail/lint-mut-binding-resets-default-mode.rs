@@ -0,0 +1,21 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(match_default_bindings)]
+#![deny(mut_binding_resets_default_mode)]
+
+fn main() {
+    match &(1, 2) {
+        (mut a, b) => { //~ ERROR `mut a` binds by value although it is matched through a reference
+            a += *b;
+            println!("{}", a);
+        }
+    }
+}
@@ -0,0 +1,57 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![deny(redundant_bounds)]
+#![allow(dead_code)]
+
+trait Super {}
+trait Sub: Super {}
+
+fn duplicate<T: Clone + Clone>(_: T) {}
+//~^ ERROR duplicate bound
+//~| HELP remove this bound
+//~| SUGGESTION fn duplicate<T: Clone>(_: T) {}
+
+fn duplicate_where<T>(_: T) where T: Clone, T: Clone {}
+//~^ ERROR duplicate bound
+//~| HELP remove this bound
+//~| SUGGESTION fn duplicate_where<T>(_: T) where T: Clone {}
+
+fn duplicate_builtin<T: Send + Send>(_: T) {}
+//~^ ERROR duplicate bound
+//~| HELP remove this bound
+//~| SUGGESTION fn duplicate_builtin<T: Send>(_: T) {}
+
+fn implied<T: Ord + PartialOrd>(_: T) {}
+//~^ ERROR is implied by
+//~| HELP remove this bound
+//~| SUGGESTION fn implied<T: Ord>(_: T) {}
+
+fn implied_local<T>(_: T) where T: Super + Sub {}
+//~^ ERROR is implied by
+//~| HELP remove this bound
+//~| SUGGESTION fn implied_local<T>(_: T) where T: Sub {}
+
+struct Methods;
+
+impl Methods {
+    fn implied_method<T: Sub>(_: T) where T: Super, T: Clone {}
+    //~^ ERROR is implied by
+    //~| HELP remove this bound
+    //~| SUGGESTION fn implied_method<T: Sub>(_: T) where T: Clone {}
+}
+
+// Bounds with different parameters are not redundant.
+fn distinct<T: From<u8> + From<u16>>(_: T) {}
+
+// Neither are bounds on different types.
+fn different_types<T: Clone, U: Clone>(_: T, _: U) {}
+
+fn main() {}
@@ -233,7 +233,7 @@ mod cross_crate {
 
     impl Trait for S {
         fn trait_stable(&self) {}
-        fn trait_unstable(&self) {} //~ ERROR use of unstable library feature
+        fn trait_unstable(&self) {} //~ ERROR implementation of unstable trait item
     }
 }
 
@@ -0,0 +1,33 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![deny(type_param_shadowing)]
+#![allow(dead_code)]
+
+struct Foo;
+
+struct Bar<Option>(Option);
+//~^ ERROR type parameter `Option` shadows the imported type `Option` in scope
+
+fn local<Foo>(_: Foo) {} //~ ERROR type parameter `Foo` shadows the type `Foo` in scope
+
+trait Baz<u8> {} //~ ERROR type parameter `u8` shadows the primitive type `u8` in scope
+
+mod inner {
+    // Items of the parent module are not in scope here.
+    fn ok<Foo>(_: Foo) {}
+}
+
+#[allow(type_param_shadowing)]
+fn allowed<String>(_: String) {}
+
+fn unrelated<T>(_: T) {}
+
+fn main() {}
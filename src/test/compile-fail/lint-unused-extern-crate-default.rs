@@ -0,0 +1,28 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// aux-build:two_macros.rs
+// aux-build:lint_unused_extern_crate.rs
+
+// Both lints warn without being asked for.
+
+#![feature(rustc_attrs)]
+
+extern crate two_macros; //~ WARNING unused extern crate
+
+#[macro_use] //~ WARNING no macros from `lint_unused_extern_crate` are used
+extern crate lint_unused_extern_crate;
+
+use lint_unused_extern_crate::foo;
+
+#[rustc_error]
+fn main() { //~ ERROR compilation successful
+    foo();
+}
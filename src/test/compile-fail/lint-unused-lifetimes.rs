@@ -0,0 +1,54 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![deny(unused_lifetimes)]
+#![allow(dead_code)]
+
+fn unused<'a>(x: &u8) -> u8 { *x } //~ ERROR lifetime parameter `'a` is never used
+
+fn used_in_signature<'a>(x: &'a u8) -> &'a u8 { x }
+
+fn used_in_body<'a>(x: &u8) -> u8 {
+    let y: &'a u8 = &0;
+    *x + *y
+}
+
+fn used_as_bound<'a, 'b: 'a>(x: &'b u8) -> &'b u8 { x }
+
+struct Foo;
+
+impl<'a> Foo { //~ ERROR lifetime parameter `'a` is never used
+    fn method<'b>(&self) {} //~ ERROR lifetime parameter `'b` is never used
+
+    fn used_method<'b>(&self, x: &'b u8) -> &'b u8 { x }
+}
+
+struct Bar<'a>(&'a u8);
+
+impl<'a> Bar<'a> {
+    fn get(&self) -> &'a u8 { self.0 }
+}
+
+trait Baz {
+    fn required<'a>(&self); //~ ERROR lifetime parameter `'a` is never used
+
+    fn provided<'a>(&self, x: &'a u8) -> &'a u8 { x }
+}
+
+#[allow(unused_lifetimes)]
+fn allowed<'a>() {}
+
+macro_rules! declare {
+    () => { fn from_macro<'a>() {} }
+}
+
+declare!();
+
+fn main() {}
@@ -0,0 +1,27 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// aux-build:two_macros.rs
+// aux-build:lint_unused_extern_crate.rs
+
+#![deny(unused_macro_imports, unused_extern_crates)]
+
+#[macro_use] // no error, `macro_one!` is used below
+extern crate two_macros;
+
+#[macro_use] //~ ERROR no macros from `other` are used
+extern crate lint_unused_extern_crate as other;
+
+use other::foo;
+
+fn main() {
+    macro_one!();
+    foo();
+}
@@ -0,0 +1,29 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(match_default_bindings)]
+
+fn main() {
+    let opt = Some(String::new());
+    match &opt {
+        Some(s) => s.push('a'), //~ ERROR cannot borrow immutable borrowed content
+        None => {}
+    }
+
+    match &opt {
+        Some(mut s) => {} //~ ERROR cannot move out of borrowed content
+        None => {}
+    }
+
+    let mut pair = (1, 2);
+    let (a, _) = &pair;
+    pair.0 = 3; //~ ERROR cannot assign to `pair.0` because it is borrowed
+    println!("{}", a);
+}
@@ -0,0 +1,35 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-tidy-linelength
+
+// Functions nested in trait impls are named by the impl's header.
+
+#![recursion_limit = "16"]
+
+mod foo {
+    pub trait Wrap {
+        fn wrap<T>(&self, t: T);
+    }
+
+    pub struct Wrapper;
+}
+
+impl foo::Wrap for foo::Wrapper {
+    fn wrap<T>(&self, t: T) {
+    //~^ ERROR reached the recursion limit during monomorphization of `<foo::Wrapper as foo::Wrap>::wrap`
+        self.wrap(Some(t))
+    }
+}
+
+fn main() {
+    use foo::Wrap;
+    foo::Wrapper.wrap(0u8);
+}
@@ -0,0 +1,32 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// aux-build:object_safety_xcrate.rs
+
+// Check that the methods recorded as mentioning `Self` when a trait is
+// collected are read back correctly from another crate: projections out of
+// a supertrait and methods requiring `Self: Sized` are still allowed.
+
+extern crate object_safety_xcrate;
+
+use object_safety_xcrate::{Bar, Baz};
+
+fn make_bar<T:Bar>(t: &T) -> &Bar {
+        //~^ ERROR E0038
+        //~| NOTE method `bar` references the `Self` type in its arguments or return type
+    loop { }
+}
+
+fn make_baz<T:Baz<Output=u32>>(t: &T) -> &Baz<Output=u32> {
+    t
+}
+
+fn main() {
+}
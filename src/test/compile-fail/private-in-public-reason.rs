@@ -0,0 +1,28 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Private-in-public errors explain why the offending interface is visible
+// outside the crate.
+
+#![crate_type = "lib"]
+
+struct Priv;
+
+mod inner {
+    pub fn leak() -> ::Priv { ::Priv }
+    //~^ ERROR private type in public interface
+    //~| NOTE because `inner::leak` is reexported from the crate root
+}
+
+pub use inner::leak;
+
+pub fn direct(_: Priv) {}
+//~^ ERROR private type in public interface
+//~| NOTE visible outside the crate because `direct` is public
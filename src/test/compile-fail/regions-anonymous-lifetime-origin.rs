@@ -0,0 +1,29 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that region errors point at the elided lifetime that introduced
+// an anonymous lifetime.
+
+struct Foo<'a> {
+    x: &'a u8,
+}
+
+fn store<'a>(foo: &mut Foo<'a>, x: &u8) {
+//~^ NOTE the anonymous lifetime #2 defined on the block
+//~| NOTE the lifetime 'a as defined on the block
+//~| NOTE the anonymous lifetime #2 is the lifetime elided in the type of argument `x`
+    foo.x = x;
+    //~^ ERROR mismatched types
+    //~| expected `&'a u8`
+    //~| found `&u8`
+    //~| lifetime mismatch
+}
+
+fn main() {}
@@ -0,0 +1,45 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(specialization)]
+
+// A `default` associated type can't be relied upon in generic code, since a
+// more specialized impl may override it.
+
+trait Foo {
+    type Assoc;
+}
+
+impl<T> Foo for T {
+    default type Assoc = ();
+}
+
+// Nor can the other items of the impl that declares it, as they may be
+// inherited by an impl that overrides it.
+trait Bar {
+    type Out;
+    fn out(&self) -> Self::Out;
+}
+
+impl<T> Bar for T {
+    default type Out = ();
+    default fn out(&self) -> () {}
+    //~^ ERROR method `out` has an incompatible type for trait
+}
+
+fn generic<T>() -> <T as Foo>::Assoc {
+    () //~ ERROR mismatched types
+}
+
+fn concrete() -> <u8 as Foo>::Assoc {
+    ()
+}
+
+fn main() {}
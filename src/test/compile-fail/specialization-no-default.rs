@@ -0,0 +1,31 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(specialization)]
+
+// Only `default` items may be overridden by a more specific impl.
+
+trait Foo {
+    fn foo(&self);
+    fn bar(&self);
+}
+
+impl<T> Foo for T {
+    default fn foo(&self) {}
+    fn bar(&self) {} //~ NOTE parent `impl` is here
+}
+
+impl Foo for u8 {
+    fn foo(&self) {}
+    fn bar(&self) {}
+    //~^ ERROR `bar` specializes an item from a parent `impl`, but that item is not marked `default`
+}
+
+fn main() {}
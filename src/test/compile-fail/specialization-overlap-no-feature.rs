@@ -0,0 +1,22 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Without `#![feature(specialization)]`, a more specific impl still
+// conflicts with a blanket one.
+
+trait Foo {
+    fn foo(&self) {}
+}
+
+impl<T> Foo for T {}
+
+impl Foo for u8 {} //~ ERROR conflicting implementations of trait `Foo` for type `u8`
+
+fn main() {}
@@ -0,0 +1,43 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-tidy-linelength
+// aux-build:stability_trait_items.rs
+
+// Implementing an unstable item of a stable trait needs the item's feature,
+// whether it overrides a default or fills in a required item.
+
+extern crate stability_trait_items;
+use stability_trait_items::{WithDefault, Required};
+
+struct A;
+struct B;
+struct C;
+
+// Leaving out the unstable item is fine when the trait has a default.
+impl WithDefault for A {
+    fn stable(&self) {}
+}
+
+impl WithDefault for B {
+    fn stable(&self) {}
+    fn unstable(&self) {}
+    //~^ ERROR implementation of unstable trait item `stability_trait_items::WithDefault::unstable`: not settled (see issue #3)
+    //~| NOTE remove it from the impl to use the default
+}
+
+impl Required for C {
+    fn stable(&self) {}
+    fn unstable(&self) {}
+    //~^ ERROR implementation of unstable trait item `stability_trait_items::Required::unstable` (see issue #4)
+    //~| NOTE cannot be implemented without the unstable feature
+}
+
+fn main() {}
@@ -0,0 +1,27 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// A `where` clause after a field is reported on its own, and the fields
+// after it are still parsed.
+
+struct Pair<T, U> {
+    first: T where T: Copy,
+    //~^ ERROR `where` clauses are not allowed on struct fields
+    //~| HELP move the bounds into the `where` clause of the struct itself
+    //~| HELP to bound only this field
+    second: U,
+}
+
+struct Wrapper<T>(T where T: Clone, u32);
+//~^ ERROR `where` clauses are not allowed on struct fields
+//~| HELP move the bounds into the `where` clause of the struct itself
+//~| HELP to bound only this field
+
+fn main() {}
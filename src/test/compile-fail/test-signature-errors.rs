@@ -0,0 +1,59 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: --test
+
+// Test that badly-shaped test and bench functions are reported precisely,
+// before the test harness is generated around them.
+
+#![feature(test)]
+
+extern crate test;
+
+#[test]
+fn takes_args(x: u32, y: u32) {}
+//~^ ERROR functions used as tests must not take any arguments
+
+#[test]
+fn generic<T>() {}
+//~^ ERROR functions used as tests cannot have type parameters
+
+#[test]
+fn with_lifetime<'a>() {}
+
+#[test]
+fn returns_value() -> u32 { 0 }
+//~^ ERROR functions used as tests must return `()`
+
+#[test]
+unsafe fn unsafe_test() {}
+//~^ ERROR unsafe functions cannot be used for tests
+
+#[test]
+fn many_problems<T>(x: T) -> T { x }
+//~^ ERROR functions used as tests cannot have type parameters
+//~| ERROR functions used as tests must not take any arguments
+//~| ERROR functions used as tests must return `()`
+
+#[bench]
+fn no_bencher() {}
+//~^ ERROR functions used as benches must take exactly one argument
+
+#[bench]
+fn bench_returns(b: &mut test::Bencher) -> bool { true }
+//~^ ERROR functions used as benches must return `()`
+
+#[bench]
+fn good_bench(b: &mut test::Bencher) {
+    b.iter(|| 1 + 1);
+}
+
+#[test]
+fn good_test() {}
@@ -0,0 +1,47 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(track_caller)]
+
+#[track_caller]
+fn ok() {}
+
+#[track_caller] //~ ERROR E0523
+struct S;
+
+#[track_caller] //~ ERROR requires the Rust ABI, found "C"
+extern "C" fn c_abi() {}
+
+trait Tr {
+    #[track_caller] //~ ERROR E0523
+    fn method(&self);
+}
+
+impl Tr for S {
+    #[track_caller] //~ ERROR E0523
+    fn method(&self) {}
+}
+
+impl S {
+    #[track_caller]
+    fn inherent(&self) {}
+}
+
+extern {
+    #[track_caller] //~ ERROR E0523
+    fn foreign();
+}
+
+fn main() {
+    ok();
+    S.inherent();
+    let f: fn() = ok; //~ ERROR cannot be converted to function pointers
+    let g = ok as fn(); //~ ERROR cannot be converted to function pointers
+}
@@ -0,0 +1,26 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z track-err-types
+
+// When typeck gives up, each error type still around is traced back through
+// the nodes it spread through to where it was introduced.
+
+struct S;
+
+fn main() {
+    let x = S.missing(); //~ ERROR no method named `missing` found
+    //~^ NOTE introduced here, after 1 error(s) had been reported
+    //~| NOTE which it got from here
+    let y = (x, 1);
+    //~^ WARNING an error type spread to here
+    //~| NOTE which it got from here
+    //~| NOTE which it got from here
+}
@@ -0,0 +1,22 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(trait_alias)]
+
+use std::rc::Rc;
+
+trait SendClone = Send + Clone;
+
+fn is_send_clone<T: SendClone>(_: T) {}
+
+fn main() {
+    is_send_clone(0u32);
+    is_send_clone(Rc::new(0u32)); //~ ERROR `core::marker::Send` is not implemented
+}
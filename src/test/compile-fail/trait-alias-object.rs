@@ -0,0 +1,31 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that a trait alias used as an object type must expand to exactly one
+// non-builtin trait, which must not be given `Self` as a type parameter.
+
+#![feature(trait_alias)]
+
+use std::fmt::{Debug, Display};
+
+trait DebugDisplay = Debug + Display;
+trait SendSync = Send + Sync;
+trait SelfEq = PartialEq<Self>;
+
+fn two(_: Box<DebugDisplay>) {}
+//~^ ERROR only the builtin traits can be used as closure or object bounds
+
+fn builtin_only(_: Box<SendSync>) {}
+//~^ ERROR at least one non-builtin trait is required for an object type
+
+fn self_param(_: Box<SelfEq>) {}
+//~^ ERROR the trait `SelfEq` cannot be made into an object
+
+fn main() {}
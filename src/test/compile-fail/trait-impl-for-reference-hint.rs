@@ -0,0 +1,39 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that we point at impls that would apply to a reference to the
+// self type (or to the referent) when a trait bound is not satisfied.
+
+trait Foo {}
+
+struct Bar;
+struct Baz;
+
+impl<'a> Foo for &'a Bar {}
+//~^ NOTE the trait `Foo` is implemented for `&
+
+impl Foo for Baz {}
+//~^ NOTE the trait `Foo` is implemented for `Baz`, but not for `&
+
+struct Wrapper<T>(T);
+
+// Not noted for `Wrapper<Bar>`: the where-clause doesn't hold.
+impl<'a, T: Clone> Foo for &'a Wrapper<T> {}
+
+fn takes_foo<T: Foo>(_: T) {}
+
+fn main() {
+    takes_foo(Bar);
+    //~^ ERROR the trait `Foo` is not implemented for the type `Bar`
+    takes_foo(&Baz);
+    //~^ ERROR the trait `Foo` is not implemented for the type `&
+    takes_foo(Wrapper(Bar));
+    //~^ ERROR the trait `Foo` is not implemented for the type `Wrapper<Bar>`
+}
@@ -0,0 +1,22 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// aux-build:trait_impl_visibility.rs
+
+// The methods of an impl of a private trait stay private to other crates,
+// even though the type they are implemented for is public.
+
+extern crate trait_impl_visibility as other;
+
+use other::S;
+
+fn main() {
+    <S as other::Hidden>::hidden(&S); //~ ERROR trait `Hidden` is private
+}
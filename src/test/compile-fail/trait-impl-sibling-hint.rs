@@ -0,0 +1,51 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that we point at an impl of a sibling of the required trait, one
+// in the same module with the same items, when a trait bound is not
+// satisfied.
+
+use std::fmt;
+
+struct Point;
+
+impl fmt::Debug for Point {
+//~^ NOTE the similar trait `core::fmt::Debug` is implemented for `Point`
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Point")
+    }
+}
+
+mod shapes {
+    pub trait Area { fn area(&self) -> f64; }
+    pub trait Perimeter { fn perimeter(&self) -> f64; }
+    pub trait Size { fn area(&self) -> f64; }
+}
+
+struct Square;
+
+impl shapes::Perimeter for Square {
+    fn perimeter(&self) -> f64 { 4.0 }
+}
+
+impl shapes::Size for Square {
+//~^ NOTE the similar trait `shapes::Size` is implemented for `Square`
+    fn area(&self) -> f64 { 1.0 }
+}
+
+fn show<T: fmt::Display>(_: T) {}
+fn area<T: shapes::Area>(_: T) {}
+
+fn main() {
+    show(Point);
+    //~^ ERROR the trait `core::fmt::Display` is not implemented for the type `Point`
+    area(Square);
+    //~^ ERROR the trait `shapes::Area` is not implemented for the type `Square`
+}
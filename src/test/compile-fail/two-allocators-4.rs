@@ -0,0 +1,25 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// aux-build:allocator1.rs
+// aux-build:allocator2.rs
+// aux-build:uses-allocator2.rs
+// error-pattern: cannot link together two allocators: allocator1 and allocator2
+// error-pattern: `allocator1` is linked statically, as a direct dependency of this crate
+// error-pattern: `allocator2` is linked statically, pulled in by `uses_allocator2 -> allocator2`
+
+// Make sure the error for two allocators says where each of them came from.
+
+extern crate allocator1;
+extern crate uses_allocator2;
+
+fn main() {
+    uses_allocator2::foo();
+}
@@ -0,0 +1,33 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Under `#![feature(type_alias_bounds)]`, the bounds of a type alias must
+// hold wherever it is used.
+
+#![feature(type_alias_bounds)]
+
+trait Float {}
+
+impl Float for f64 {}
+
+type Meters<T: Float> = T;
+
+struct Route {
+    length: Meters<u32>, //~ ERROR the trait `Float` is not implemented for the type `u32`
+}
+
+fn unbounded<T>(_: Meters<T>) {} //~ ERROR the trait `Float` is not implemented for the type `T`
+
+fn bounded<T: Float>(_: Meters<T>) {}
+
+fn main() {
+    let _: Meters<f64> = 1.0;
+    let _: Meters<&str> = "far"; //~ ERROR the trait `Float` is not implemented for the type `&str`
+}
@@ -0,0 +1,28 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(untagged_unions)]
+
+union U {
+    a: u8,
+    b: String,
+    //~^ ERROR union field `b` has type `collections::string::String`, which needs to be dropped
+}
+
+union W<T> {
+    a: T, //~ ERROR union field `a` has type `T`, which needs to be dropped
+}
+
+union C<T: Copy> {
+    a: T, // OK, `T: Copy` is never dropped
+    b: Box<u8>, //~ ERROR union field `b` has type `Box<u8>`, which needs to be dropped
+}
+
+fn main() {}
@@ -0,0 +1,30 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(untagged_unions)]
+
+// The fields of a union overlap, so moving out of one of them moves the
+// whole union.
+
+struct NotCopy;
+
+union U {
+    a: (NotCopy, u8),
+    b: u8,
+}
+
+fn main() {
+    unsafe {
+        let u = U { b: 0 };
+        let a = u.a;
+        let b = u.b; //~ ERROR use of moved value: `u`
+        drop((a, b));
+    }
+}
@@ -0,0 +1,28 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Lints about unreachable code should point at what the user wrote, not at
+// the nodes a `for` loop or `if let` is desugared into.
+
+#![deny(unreachable_code)]
+#![allow(unused_variables)]
+
+fn for_loop() {
+    for _ in { return; vec![1u8] } {} //~ ERROR unreachable expression
+}
+
+fn if_let() {
+    if let Some(x) = { return; Some(1u8) } {} //~ ERROR unreachable expression
+}
+
+fn main() {
+    for_loop();
+    if_let();
+}
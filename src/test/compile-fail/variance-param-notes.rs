@@ -0,0 +1,32 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that `#[rustc_variance]` points at each parameter along with the
+// variance inferred for it.
+
+#![feature(rustc_attrs)]
+
+#[rustc_variance]
+struct Test<'a, 'b: 'a, A: 'a> { //~ ERROR types=[[+];[];[]], regions=[[-, o];[];[]]
+    //~^ NOTE `'a` is contravariant
+    //~| NOTE `'b` is invariant
+    //~| NOTE `A` is covariant
+    x: &'a A,
+    y: &'b mut &'b (),
+}
+
+#[rustc_variance]
+trait Getter<T> { //~ ERROR types=[[o];[o];[]]
+    //~^ NOTE `Self` is invariant
+    //~| NOTE `T` is invariant
+    fn get(&self) -> T;
+}
+
+fn main() {}
@@ -0,0 +1,24 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(variant_where_clauses)]
+
+// The bounds on a variant have to hold wherever it is constructed.
+
+enum Slot<T> {
+    Copied(T) where T: Copy,
+    Owned(T),
+}
+
+fn main() {
+    let _ = Slot::Owned(String::new());
+    let _ = Slot::Copied(String::new());
+    //~^ ERROR the trait `std::marker::Copy` is not implemented for the type `std::string::String`
+}
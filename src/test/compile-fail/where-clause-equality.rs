@@ -0,0 +1,29 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(where_clause_equality)]
+
+trait Shape {
+    type Unit;
+}
+
+impl Shape for u8 {
+    type Unit = u8;
+}
+
+fn wants_u32<S>() where S: Shape, S::Unit == u32 {}
+
+fn no_associated_type<T>() where T == u32 {}
+//~^ ERROR equality constraints must have an associated type on one side
+
+fn main() {
+    wants_u32::<u8>();
+    //~^ ERROR type mismatch resolving `<u8 as Shape>::Unit == u32`
+}
@@ -0,0 +1,53 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![crate_type = "dylib"]
+#![feature(rustc_private)]
+
+#[macro_use]
+extern crate rustc_plugin_abi;
+
+use rustc_plugin_abi::{Item, LintContext, LintDecl, LintPass};
+use rustc_plugin_abi::{MirContext, MirFn, MirPass, Registrar};
+
+struct PanicInLint;
+
+impl LintPass for PanicInLint {
+    fn lints(&self) -> Vec<LintDecl> {
+        vec![]
+    }
+
+    fn check_item(&mut self, _: &LintContext, item: &Item) {
+        if item.name() == "panic_in_lint" {
+            panic!("lint pass panicked");
+        }
+    }
+}
+
+struct PanicInMir;
+
+impl MirPass for PanicInMir {
+    fn name(&self) -> &'static str {
+        "panicky"
+    }
+
+    fn run(&mut self, _: &MirContext, mir: &MirFn) {
+        if mir.path() == "panic_in_mir" {
+            panic!("MIR pass panicked");
+        }
+    }
+}
+
+fn register(reg: &Registrar) {
+    reg.register_lint_pass(PanicInLint);
+    reg.register_mir_pass(PanicInMir);
+}
+
+declare_plugin!(register);
@@ -0,0 +1,54 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![crate_type = "dylib"]
+#![feature(rustc_private)]
+
+#[macro_use]
+extern crate rustc_plugin_abi;
+
+use rustc_plugin_abi::{Item, ItemKind, Level, LintContext, LintDecl, LintPass};
+use rustc_plugin_abi::{MirContext, MirFn, MirPass, Registrar};
+
+struct NoFoo;
+
+impl LintPass for NoFoo {
+    fn lints(&self) -> Vec<LintDecl> {
+        vec![LintDecl::new("no_foo", Level::Warn, "items named `foo`")]
+    }
+
+    fn check_item(&mut self, cx: &LintContext, item: &Item) {
+        if item.kind == ItemKind::Fn && item.name() == "foo" {
+            cx.span_lint(0, item.span, "item is named `foo`");
+        }
+    }
+}
+
+struct CountBlocks;
+
+impl MirPass for CountBlocks {
+    fn name(&self) -> &'static str {
+        "count-blocks"
+    }
+
+    fn run(&mut self, cx: &MirContext, mir: &MirFn) {
+        if mir.path() == "main" {
+            let msg = format!("`main` has {} basic blocks", mir.basic_blocks);
+            cx.span_warn(mir.span, &msg);
+        }
+    }
+}
+
+fn register(reg: &Registrar) {
+    reg.register_lint_pass(NoFoo);
+    reg.register_mir_pass(CountBlocks);
+}
+
+declare_plugin!(register);
@@ -0,0 +1,56 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(rustc_private, box_syntax)]
+
+extern crate driver_harness;
+extern crate rustc;
+extern crate rustc_driver;
+
+use driver_harness::{basic_opts, basic_sess, tmpdir_and_sysroot};
+use rustc::session::config::{build_configuration, Input};
+use rustc_driver::Compilation;
+use rustc_driver::driver::{compile_input, CompileController};
+
+use std::cell::RefCell;
+
+fn main() {
+    let src = r#"
+    fn main() { println!("hello"); }
+    "#;
+
+    let (tmpdir, sysroot) = tmpdir_and_sysroot();
+
+    let (sess, cstore) = basic_sess(basic_opts(sysroot));
+    let cfg = build_configuration(&sess);
+
+    let crates = RefCell::new(Vec::new());
+    let mut control = CompileController::basic();
+    control.after_hir_lowering.callback = box |state| {
+        assert!(state.cstore.is_none());
+    };
+    control.after_analysis.stop = Compilation::Stop;
+    control.after_analysis.callback = box |state| {
+        state.cstore.unwrap().iter_crate_data(|_, data| {
+            crates.borrow_mut().push(data.name());
+        });
+    };
+
+    let result = compile_input(&sess, &cstore,
+                               cfg,
+                               &Input::Str(src.to_string()),
+                               &None,
+                               &Some(tmpdir.join("out")),
+                               None,
+                               &control);
+    assert!(result.is_ok());
+    assert!(crates.borrow().iter().any(|name| name == "std"));
+    assert!(crates.borrow().iter().any(|name| name == "core"));
+}
@@ -0,0 +1,76 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(rustc_private, box_syntax)]
+
+extern crate driver_harness;
+extern crate rustc;
+extern crate rustc_driver;
+
+use driver_harness::{basic_opts, basic_sess, tmpdir_and_sysroot};
+use rustc::middle::analysis_pass::AnalysisPass;
+use rustc::middle::ty::{self, TyCtxt};
+use rustc::mir::mir_map::MirMap;
+use rustc::session::config::{build_configuration, Input};
+use rustc_driver::driver::{compile_input, CompileController, CompileError};
+
+use std::cell::RefCell;
+
+thread_local!(static RAN: RefCell<Vec<&'static str>> = RefCell::new(Vec::new()));
+
+struct CountFns;
+
+impl<'tcx> AnalysisPass<'tcx> for CountFns {
+    fn name(&self) -> &'static str { "count-fns" }
+
+    fn run_pass(&mut self, tcx: &TyCtxt<'tcx>, mir_map: &MirMap<'tcx>,
+                analysis: &ty::CrateAnalysis) {
+        assert_eq!(analysis.name, "rust_out");
+        assert_eq!(mir_map.map.len(), 2);
+        assert!(tcx.sess.err_count() == 0);
+        RAN.with(|ran| ran.borrow_mut().push(self.name()));
+    }
+}
+
+struct Deny;
+
+impl<'tcx> AnalysisPass<'tcx> for Deny {
+    fn name(&self) -> &'static str { "deny" }
+
+    fn run_pass(&mut self, tcx: &TyCtxt<'tcx>, _: &MirMap<'tcx>, _: &ty::CrateAnalysis) {
+        RAN.with(|ran| ran.borrow_mut().push(self.name()));
+        tcx.sess.err("denied by a custom analysis pass");
+    }
+}
+
+fn main() {
+    let src = r#"
+    fn foo(x: u32) -> u32 { x + 1 }
+    fn main() { foo(2); }
+    "#;
+
+    let (tmpdir, sysroot) = tmpdir_and_sysroot();
+
+    let (sess, cstore) = basic_sess(basic_opts(sysroot));
+    sess.analysis_passes.borrow_mut().push_pass(box CountFns);
+    sess.analysis_passes.borrow_mut().push_pass(box Deny);
+    let cfg = build_configuration(&sess);
+
+    let result = compile_input(&sess, &cstore,
+                               cfg,
+                               &Input::Str(src.to_string()),
+                               &None,
+                               &Some(tmpdir.join("out")),
+                               None,
+                               &CompileController::check_only());
+    assert_eq!(result, Err(CompileError::Analysis(1)));
+
+    RAN.with(|ran| assert_eq!(*ran.borrow(), ["count-fns", "deny"]));
+}
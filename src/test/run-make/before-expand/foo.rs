@@ -0,0 +1,58 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(rustc_private, box_syntax)]
+
+extern crate driver_harness;
+extern crate rustc;
+extern crate rustc_driver;
+extern crate syntax;
+
+use driver_harness::{basic_opts, basic_sess, tmpdir_and_sysroot};
+use rustc::session::config::{build_configuration, Input};
+use rustc_driver::driver::{compile_input, CompileController};
+use syntax::attr;
+use syntax::parse::{self, token};
+use syntax::parse::token::InternedString;
+
+fn main() {
+    // `generated` only exists once the driver has added it.
+    let src = r#"
+    fn main() { println!("answer: {}", generated::answer()); }
+    "#;
+
+    let (tmpdir, sysroot) = tmpdir_and_sysroot();
+
+    let (sess, cstore) = basic_sess(basic_opts(sysroot));
+    let cfg = build_configuration(&sess);
+
+    let mut control = CompileController::basic();
+    control.before_expand = box |sess, krate| {
+        let name = attr::mk_name_value_item_str(InternedString::new("crate_name"),
+                                                token::intern_and_get_ident("injected"));
+        krate.attrs.push(attr::mk_attr_inner(attr::mk_attr_id(), name));
+
+        let module = parse::parse_item_from_source_str(
+            "<generated>".to_string(),
+            "mod generated { pub fn answer() -> u32 { 42 } }".to_string(),
+            vec![],
+            &sess.parse_sess);
+        krate.module.items.push(module.ok().unwrap().unwrap());
+    };
+
+    let result = compile_input(&sess, &cstore,
+                               cfg,
+                               &Input::Str(src.to_string()),
+                               &Some(tmpdir.clone()),
+                               &None,
+                               None,
+                               &control);
+    assert!(result.is_ok());
+}
@@ -0,0 +1,29 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+mod a {
+    pub fn f() -> u32 { 1 }
+}
+
+mod b {
+    pub fn f() -> u32 { 2 }
+}
+
+mod c {
+    pub fn f() -> u32 { 3 }
+}
+
+mod d {
+    pub fn f() -> u32 { 4 }
+}
+
+fn main() {
+    assert_eq!(a::f() + b::f() + c::f() + d::f(), 10);
+}
@@ -0,0 +1,70 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(rustc_private)]
+
+extern crate driver_harness;
+extern crate rustc;
+extern crate rustc_driver;
+
+use driver_harness::{basic_opts, basic_sess, tmpdir_and_sysroot};
+use rustc::session::config::{build_configuration, Input, Options, OutputType};
+use rustc_driver::Compilation;
+use rustc_driver::driver::{compile_input, CompileController, CompileError};
+
+use std::path::PathBuf;
+
+fn main() {
+    let (tmpdir, sysroot) = tmpdir_and_sysroot();
+
+    let opts = basic_opts(sysroot);
+    let result = compile(opts.clone(), "fn main() {", &tmpdir.join("out"), true);
+    assert_eq!(result, Err(CompileError::Parse));
+
+    let result = compile(opts.clone(), "fn main() { let x: u32 = \"\"; }",
+                         &tmpdir.join("out"), true);
+    assert_eq!(result, Err(CompileError::Analysis(1)));
+
+    // Stopping early without any errors is not a failure.
+    let result = compile(opts.clone(), "fn main() {}", &tmpdir.join("out"), true);
+    assert_eq!(result, Ok(()));
+
+    // LLVM can't write the object file into a directory that doesn't exist.
+    let mut object_opts = opts.clone();
+    object_opts.output_types.clear();
+    object_opts.output_types.insert(OutputType::Object, None);
+    let result = compile(object_opts, "fn main() {}",
+                         &tmpdir.join("missing").join("out"), false);
+    assert_eq!(result, Err(CompileError::Llvm));
+
+    let mut link_opts = opts.clone();
+    link_opts.cg.linker = Some(tmpdir.join("no-such-linker").display().to_string());
+    let result = compile(link_opts, "fn main() {}", &tmpdir.join("out"), false);
+    assert_eq!(result, Err(CompileError::Link));
+}
+
+fn compile(opts: Options, src: &str, output: &PathBuf, stop_after_analysis: bool)
+           -> Result<(), CompileError> {
+    let (sess, cstore) = basic_sess(opts);
+    let cfg = build_configuration(&sess);
+
+    let mut control = CompileController::basic();
+    if stop_after_analysis {
+        control.after_analysis.stop = Compilation::Stop;
+    }
+
+    compile_input(&sess, &cstore,
+                  cfg,
+                  &Input::Str(src.to_string()),
+                  &None,
+                  &Some(output.clone()),
+                  None,
+                  &control)
+}
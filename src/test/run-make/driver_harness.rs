@@ -0,0 +1,68 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// The session setup shared by the run-make tests that drive the compiler
+// through `compile_input`. Build it with `$(RUSTC) ../driver_harness.rs`
+// before the test program.
+
+#![crate_type = "rlib"]
+#![feature(rustc_private)]
+
+extern crate rustc;
+extern crate rustc_lint;
+extern crate rustc_metadata;
+extern crate syntax;
+
+use rustc::session::{build_session, Session};
+use rustc::session::config::{basic_options, Options, OutputType};
+use rustc_metadata::cstore::CStore;
+use syntax::diagnostics::registry::Registry;
+use syntax::parse::token;
+
+use std::env;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+/// The temporary directory and the sysroot of the compiler under test, for
+/// a program run as `$(call RUN,foo $(TMPDIR) $(RUSTC))`. `$(RUSTC)` starts
+/// with the dynamic linker's environment, so rustc is the third argument.
+pub fn tmpdir_and_sysroot() -> (PathBuf, PathBuf) {
+    let args: Vec<String> = env::args().collect();
+
+    if args.len() < 4 {
+        panic!("expected rustc path");
+    }
+
+    let tmpdir = PathBuf::from(&args[1]);
+
+    let mut sysroot = PathBuf::from(&args[3]);
+    sysroot.pop();
+    sysroot.pop();
+
+    (tmpdir, sysroot)
+}
+
+/// Options for building an executable against `sysroot`.
+pub fn basic_opts(sysroot: PathBuf) -> Options {
+    let mut opts = basic_options();
+    opts.output_types.insert(OutputType::Exe, None);
+    opts.maybe_sysroot = Some(sysroot);
+    opts
+}
+
+/// A session for `opts`, with the builtin lints registered, and its crate
+/// store.
+pub fn basic_sess(opts: Options) -> (Session, Rc<CStore>) {
+    let descriptions = Registry::new(&rustc::DIAGNOSTICS);
+    let cstore = Rc::new(CStore::new(token::get_ident_interner()));
+    let sess = build_session(opts, None, descriptions, cstore.clone());
+    rustc_lint::register_builtins(&mut sess.lint_store.borrow_mut(), Some(&sess));
+    (sess, cstore)
+}
@@ -0,0 +1,33 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+#![crate_type = "lib"]
+
+pub trait Describe {
+    fn describe(&self) -> String;
+}
+
+pub trait Named {}
+
+pub struct Bar;
+
+impl Describe for Bar {
+    fn describe(&self) -> String { "bar".to_string() }
+}
+
+impl<T: Named> Describe for Box<T> {
+    fn describe(&self) -> String { "named".to_string() }
+}
+
+// An upstream trait with upstream impls alongside the local one.
+impl Clone for Bar {
+    fn clone(&self) -> Bar { Bar }
+}
@@ -0,0 +1,59 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(rustc_private, box_syntax)]
+
+extern crate driver_harness;
+extern crate rustc;
+extern crate rustc_driver;
+extern crate syntax;
+
+use driver_harness::{basic_opts, basic_sess, tmpdir_and_sysroot};
+use rustc::session::config::{build_configuration, Input};
+use rustc_driver::driver::{compile_input, CompileController};
+use syntax::parse;
+
+use std::cell::Cell;
+
+fn main() {
+    // Nothing here needs expanding, and std is never injected, so the crate
+    // can be compiled as parsed.
+    let src = r#"
+    #![crate_type = "lib"]
+    pub fn answer() -> u32 { 42 }
+    "#;
+
+    let (tmpdir, sysroot) = tmpdir_and_sysroot();
+
+    let (sess, cstore) = basic_sess(basic_opts(sysroot));
+    let cfg = build_configuration(&sess);
+
+    let krate = parse::parse_crate_from_source_str("<generated>".to_string(),
+                                                   src.to_string(),
+                                                   cfg.clone(),
+                                                   &sess.parse_sess).ok().unwrap();
+
+    let found_answer = Cell::new(false);
+    let mut control = CompileController::check_only();
+    control.after_analysis.callback = box |state| {
+        let krate = state.hir_crate.unwrap();
+        found_answer.set(krate.items.values().any(|item| item.name.as_str() == "answer"));
+    };
+
+    let result = compile_input(&sess, &cstore,
+                               cfg,
+                               &Input::ExpandedCrate(krate),
+                               &Some(tmpdir.join("out")),
+                               &None,
+                               None,
+                               &control);
+    assert!(result.is_ok());
+    assert!(found_answer.get());
+}
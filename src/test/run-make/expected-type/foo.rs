@@ -0,0 +1,19 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+fn takes(x: u32, flags: (u8, bool)) -> u32 {
+    x + flags.0 as u32
+}
+
+fn main() {
+    let n = takes(1, (2, true));
+    let v: Vec<i64> = Vec::with_capacity(n as usize);
+    drop(v);
+}
@@ -0,0 +1,75 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(rustc_private)]
+
+extern crate rustc_driver;
+extern crate syntax;
+
+use rustc_driver::RustcDefaultCalls;
+use syntax::codemap::FileLoader;
+
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Serves source files from memory; none of them exist on disk.
+struct MemoryFileLoader {
+    files: HashMap<PathBuf, String>,
+}
+
+impl FileLoader for MemoryFileLoader {
+    fn file_exists(&self, path: &Path) -> bool {
+        self.files.contains_key(path)
+    }
+
+    fn read_file(&self, path: &Path) -> io::Result<String> {
+        self.files.get(path).cloned().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::NotFound, "no such in-memory file")
+        })
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    if args.len() < 4 {
+        panic!("expected rustc path");
+    }
+
+    let tmpdir = PathBuf::from(&args[1]);
+
+    let mut sysroot = PathBuf::from(&args[3]);
+    sysroot.pop();
+    sysroot.pop();
+
+    let mut files = HashMap::new();
+    files.insert(PathBuf::from("virtual/main.rs"),
+                 "mod greeting; fn main() { greeting::print(); }".to_string());
+    files.insert(PathBuf::from("virtual/greeting/mod.rs"),
+                 "mod message; pub fn print() { println!(\"{}\", message::TEXT); }"
+                     .to_string());
+    files.insert(PathBuf::from("virtual/greeting/message.rs"),
+                 "pub const TEXT: &'static str = \"hello from an in-memory module\";"
+                     .to_string());
+    assert!(!Path::new("virtual/main.rs").exists());
+
+    let rustc_args = vec!["rustc".to_string(),
+                          "virtual/main.rs".to_string(),
+                          "--sysroot".to_string(),
+                          sysroot.to_str().unwrap().to_string(),
+                          "-o".to_string(),
+                          tmpdir.join("virtual").to_str().unwrap().to_string()];
+    let (result, _) =
+        rustc_driver::run_compiler_with_file_loader(&rustc_args,
+                                                    &mut RustcDefaultCalls,
+                                                    Box::new(MemoryFileLoader { files: files }));
+    assert!(result.is_ok());
+}
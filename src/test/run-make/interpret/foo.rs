@@ -0,0 +1,73 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+trait Shape {
+    fn area(&self) -> u32;
+
+    fn double_area(&self) -> u32 {
+        self.area() * 2
+    }
+}
+
+struct Rect {
+    w: u32,
+    h: u32,
+}
+
+impl Shape for Rect {
+    fn area(&self) -> u32 {
+        self.w * self.h
+    }
+}
+
+fn total<T: Shape>(shapes: &[T; 2]) -> u32 {
+    shapes[0].area() + shapes[1].double_area()
+}
+
+enum Op {
+    Add(i32),
+    Neg,
+}
+
+fn apply(ops: &[Op; 3], x: &mut i32) {
+    let mut i = 0;
+    while i < 3 {
+        match ops[i] {
+            Op::Add(n) => *x += n,
+            Op::Neg => *x = -*x,
+        }
+        i += 1;
+    }
+}
+
+fn fib(n: u64) -> u64 {
+    if n < 2 { n } else { fib(n - 1) + fib(n - 2) }
+}
+
+fn fib10() -> u64 {
+    fib(10)
+}
+
+fn main() {
+    let rects = [Rect { w: 3, h: 4 }, Rect { w: 1, h: 2 }];
+    if total(&rects) != 16 {
+        panic!("wrong area");
+    }
+
+    let mut x = 5;
+    apply(&[Op::Add(2), Op::Neg, Op::Add(10)], &mut x);
+    if x != 3 {
+        panic!("wrong result");
+    }
+
+    if 300 as u8 != 44 || -1i8 as u32 != 4294967295 {
+        panic!("wrong casts");
+    }
+}
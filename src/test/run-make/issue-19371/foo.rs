@@ -10,66 +10,51 @@
 
 #![feature(rustc_private)]
 
+extern crate driver_harness;
 extern crate rustc;
 extern crate rustc_driver;
-extern crate rustc_lint;
-extern crate rustc_metadata;
-extern crate syntax;
 
-use rustc::session::{build_session, Session};
-use rustc::session::config::{basic_options, build_configuration, Input, OutputType};
+use driver_harness::{basic_opts, basic_sess, tmpdir_and_sysroot};
+use rustc::session::config::{build_configuration, Input};
 use rustc_driver::driver::{compile_input, CompileController};
-use rustc_metadata::cstore::CStore;
-use syntax::diagnostics::registry::Registry;
-use syntax::parse::token;
 
 use std::path::PathBuf;
-use std::rc::Rc;
 
 fn main() {
     let src = r#"
     fn main() {}
     "#;
 
-    let args: Vec<String> = std::env::args().collect();
-
-    if args.len() < 4 {
-        panic!("expected rustc path");
-    }
-
-    let tmpdir = PathBuf::from(&args[1]);
-
-    let mut sysroot = PathBuf::from(&args[3]);
-    sysroot.pop();
-    sysroot.pop();
+    let (tmpdir, sysroot) = tmpdir_and_sysroot();
 
     compile(src.to_string(), tmpdir.join("out"), sysroot.clone());
 
     compile(src.to_string(), tmpdir.join("out"), sysroot.clone());
-}
 
-fn basic_sess(sysroot: PathBuf) -> (Session, Rc<CStore>) {
-    let mut opts = basic_options();
-    opts.output_types.insert(OutputType::Exe, None);
-    opts.maybe_sysroot = Some(sysroot);
-
-    let descriptions = Registry::new(&rustc::DIAGNOSTICS);
-    let cstore = Rc::new(CStore::new(token::get_ident_interner()));
-    let sess = build_session(opts, None, descriptions, cstore.clone());
-    rustc_lint::register_builtins(&mut sess.lint_store.borrow_mut(), Some(&sess));
-    (sess, cstore)
+    // A different crate, with its own macros and attributes, compiles
+    // cleanly after the others.
+    let src = r#"
+    #[derive(Clone, Debug)]
+    struct Foo { x: u32 }
+    macro_rules! foo { ($e:expr) => (Foo { x: $e }) }
+    #[inline(never)]
+    fn main() { let _ = foo!(1).clone(); }
+    "#;
+    compile(src.to_string(), tmpdir.join("out2"), sysroot.clone());
 }
 
 fn compile(code: String, output: PathBuf, sysroot: PathBuf) {
-    let (sess, cstore) = basic_sess(sysroot);
+    let (sess, cstore) = basic_sess(basic_opts(sysroot));
     let cfg = build_configuration(&sess);
     let control = CompileController::basic();
 
-    compile_input(&sess, &cstore,
+    let result = compile_input(&sess, &cstore,
             cfg,
             &Input::Str(code),
             &None,
             &Some(output),
             None,
             &control);
+    assert!(result.is_ok());
+    assert_eq!(sess.err_count(), 0);
 }
@@ -0,0 +1,28 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[inline(never)]
+fn never_inlined(x: u32) -> u32 {
+    x.wrapping_mul(31)
+}
+
+#[inline(never)]
+fn sum(xs: &[u32]) -> u32 {
+    let mut total = 0u32;
+    for &x in xs {
+        total = total.wrapping_add(x);
+    }
+    total
+}
+
+fn main() {
+    let lens: Vec<u32> = std::env::args().map(|arg| arg.len() as u32).collect();
+    std::process::exit(never_inlined(sum(&lens)) as i32);
+}
@@ -0,0 +1,56 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(rustc_private, box_syntax)]
+
+extern crate driver_harness;
+extern crate rustc;
+extern crate rustc_driver;
+
+use driver_harness::{basic_opts, basic_sess, tmpdir_and_sysroot};
+use rustc::session::config::{build_configuration, Input};
+use rustc_driver::driver::{compile_input, CompileController};
+
+use std::cell::RefCell;
+
+fn main() {
+    let src = r#"
+    fn foo(x: u32) -> u32 { if x > 1 { x } else { 1 } }
+    fn main() { foo(2); }
+    "#;
+
+    let (tmpdir, sysroot) = tmpdir_and_sysroot();
+
+    let (sess, cstore) = basic_sess(basic_opts(sysroot));
+    let cfg = build_configuration(&sess);
+
+    let passes = RefCell::new(Vec::new());
+    let mut control = CompileController::check_only();
+    control.after_mir_pass = box |name, _, mir_map| {
+        assert!(!mir_map.map.is_empty());
+        passes.borrow_mut().push(name.to_string());
+    };
+
+    let result = compile_input(&sess, &cstore,
+                               cfg,
+                               &Input::Str(src.to_string()),
+                               &None,
+                               &Some(tmpdir.join("out")),
+                               None,
+                               &control);
+    assert!(result.is_ok());
+
+    // Each built-in pass is reported once it has run, in order.
+    let passes = passes.borrow();
+    let typeck = passes.iter().position(|p| p == "typeck-mir").unwrap();
+    let erase = passes.iter().position(|p| p == "erase-regions").unwrap();
+    assert!(typeck < erase);
+    assert!(passes.iter().any(|p| p == "simplify-cfg"));
+}
@@ -0,0 +1,63 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(rustc_private, box_syntax)]
+
+extern crate driver_harness;
+extern crate rustc;
+extern crate rustc_driver;
+
+use driver_harness::{basic_opts, basic_sess, tmpdir_and_sysroot};
+use rustc::session::config::{build_configuration, Input, OutputType};
+use rustc_driver::driver::{compile_input, CompileController};
+
+use std::fs::{self, File};
+use std::io::Read;
+
+fn main() {
+    let src = r#"
+    fn main() {}
+    "#;
+
+    let (tmpdir, sysroot) = tmpdir_and_sysroot();
+
+    let mut opts = basic_opts(sysroot);
+    opts.output_types.insert(OutputType::DepInfo, None);
+    let (sess, cstore) = basic_sess(opts);
+    let cfg = build_configuration(&sess);
+
+    let hashed = tmpdir.join("0123abcd");
+    fs::create_dir_all(&hashed).unwrap();
+
+    let mut control = CompileController::check_only();
+    control.output_filenames = box |_, _, mut outputs| {
+        assert_eq!(outputs.out_directory, tmpdir.join("out"));
+        outputs.out_directory = hashed.clone();
+        outputs.extra = "-0123abcd".to_string();
+        outputs
+    };
+
+    let result = compile_input(&sess, &cstore,
+                               cfg,
+                               &Input::Str(src.to_string()),
+                               &Some(tmpdir.join("out")),
+                               &None,
+                               None,
+                               &control);
+    assert!(result.is_ok());
+
+    // The dep-info goes where the callback said, and names the executable
+    // under its new path.
+    let mut deps = String::new();
+    File::open(hashed.join("rust_out-0123abcd.d")).unwrap()
+        .read_to_string(&mut deps).unwrap();
+    assert!(deps.contains("0123abcd"));
+    assert!(!tmpdir.join("out").exists());
+}
@@ -0,0 +1,23 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+trait A { fn a(&self); fn b(&self); fn c(&self); }
+struct S;
+impl A for S {}
+
+struct T { x: u32, y: u32 }
+
+fn main() {
+    let _ = T { z: 1 };
+    let _: u8 = "a";
+    let _: u16 = "b";
+    undefined_a();
+    undefined_b();
+}
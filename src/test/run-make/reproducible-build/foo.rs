@@ -0,0 +1,48 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+#![crate_type = "rlib"]
+
+use std::fmt;
+
+pub trait Shape {
+    fn area(&self) -> f64;
+}
+
+pub struct Square(pub f64);
+pub struct Circle(pub f64);
+
+impl Shape for Square {
+    fn area(&self) -> f64 { self.0 * self.0 }
+}
+
+impl Shape for Circle {
+    fn area(&self) -> f64 { 3.14 * self.0 * self.0 }
+}
+
+impl fmt::Display for Square {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "square({})", self.0)
+    }
+}
+
+pub fn total<S: Shape>(shapes: &[S]) -> f64 {
+    shapes.iter().map(|s| s.area()).fold(0.0, |a, b| a + b)
+}
+
+pub fn describe(s: &Square) -> String {
+    format!("{} with area {}", s, s.area())
+}
+
+// Reexports are collected from a hash map in resolve.
+pub mod shapes {
+    pub use super::{Circle, Shape, Square, describe, total};
+}
@@ -0,0 +1,19 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![crate_type = "lib"]
+
+// Inside the crate's own directory, so always readable.
+pub const DATA: &'static str = include_str!("data.txt");
+
+// Outside of it.
+pub const TOOLS: &'static str = include_str!("../tools.mk");
+
+pub const VAR: &'static str = env!("SANDBOX_IO_TEST_VAR");
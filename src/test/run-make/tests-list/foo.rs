@@ -0,0 +1,35 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(test)]
+
+extern crate test;
+
+pub fn does_not_typeck() -> u32 { "not a number" }
+
+#[test]
+fn plain() {}
+
+#[test]
+#[ignore]
+fn ignored() {}
+
+#[test]
+#[should_panic(expected = "boom")]
+fn panics() { panic!("boom") }
+
+mod inner {
+    #[test]
+    #[should_panic]
+    fn nested() { panic!() }
+
+    #[bench]
+    fn bench(_: &mut ::test::Bencher) {}
+}
@@ -0,0 +1,30 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+mod inner {
+    pub fn generic<T: Clone>(t: &T) -> (T, T) {
+        (t.clone(), t.clone())
+    }
+
+    pub struct Wrapper(pub u32);
+
+    impl Wrapper {
+        pub fn get(&self) -> u32 {
+            self.0
+        }
+    }
+}
+
+fn main() {
+    inner::generic(&1u8);
+    inner::generic(&"foo");
+    inner::generic(&vec![1u32]);
+    inner::Wrapper(1).get();
+}
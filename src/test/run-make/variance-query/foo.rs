@@ -0,0 +1,79 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(rustc_private, box_syntax)]
+
+extern crate driver_harness;
+extern crate rustc;
+extern crate rustc_driver;
+extern crate rustc_front;
+
+use driver_harness::{basic_opts, basic_sess, tmpdir_and_sysroot};
+use rustc::middle::analysis_pass::AnalysisPass;
+use rustc::middle::ty::{self, TyCtxt};
+use rustc::mir::mir_map::MirMap;
+use rustc::session::config::{build_configuration, Input};
+use rustc_driver::driver::{compile_input, CompileController};
+use rustc_front::hir;
+
+use std::cell::RefCell;
+
+thread_local!(static SEEN: RefCell<Vec<String>> = RefCell::new(Vec::new()));
+
+struct Variances;
+
+impl<'tcx> AnalysisPass<'tcx> for Variances {
+    fn name(&self) -> &'static str { "variances" }
+
+    fn run_pass(&mut self, tcx: &TyCtxt<'tcx>, _: &MirMap<'tcx>, _: &ty::CrateAnalysis) {
+        for item in tcx.map.krate().items.values() {
+            match item.node {
+                hir::ItemStruct(..) | hir::ItemTrait(..) => {}
+                _ => continue,
+            }
+            let variances = tcx.item_variances(tcx.map.local_def_id(item.id));
+            SEEN.with(|seen| seen.borrow_mut().push(format!("{}: {:?}", item.name, *variances)));
+        }
+    }
+}
+
+fn main() {
+    let src = r#"
+    use std::cell::Cell;
+    pub struct Getter<'a, T> { f: fn() -> T, r: &'a () }
+    pub struct Setter<T> { f: fn(T) }
+    pub struct Slot<'a> { x: Cell<&'a u8> }
+    pub trait Source<T> { fn next(&self) -> T; }
+    fn main() {}
+    "#;
+
+    let (tmpdir, sysroot) = tmpdir_and_sysroot();
+
+    let (sess, cstore) = basic_sess(basic_opts(sysroot));
+    sess.analysis_passes.borrow_mut().push_pass(box Variances);
+    let cfg = build_configuration(&sess);
+
+    compile_input(&sess, &cstore,
+                  cfg,
+                  &Input::Str(src.to_string()),
+                  &None,
+                  &Some(tmpdir.join("out")),
+                  None,
+                  &CompileController::check_only()).unwrap();
+
+    SEEN.with(|seen| {
+        assert_eq!(*seen.borrow(), [
+            "Getter: ItemVariances(types=[[+];[];[]], regions=[[+];[];[]])",
+            "Setter: ItemVariances(types=[[-];[];[]], regions=[[];[];[]])",
+            "Slot: ItemVariances(types=[[];[];[]], regions=[[o];[];[]])",
+            "Source: ItemVariances(types=[[o];[o];[]], regions=[[];[];[]])",
+        ]);
+    });
+}
@@ -0,0 +1,45 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that the types of associated consts can use the bounds of their impl
+// or trait, e.g. to name an associated type of one of its parameters.
+
+#![feature(associated_consts)]
+
+trait Zero {
+    const ZERO: Self;
+}
+
+impl Zero for u32 {
+    const ZERO: u32 = 0;
+}
+
+trait Origin: Sized {
+    type Coord: Zero;
+    const ORIGIN: (Self::Coord, Self::Coord) =
+        (<Self::Coord as Zero>::ZERO, <Self::Coord as Zero>::ZERO);
+}
+
+struct Plane;
+
+impl Origin for Plane {
+    type Coord = u32;
+}
+
+struct Wrapper<T>(T);
+
+impl<T: Iterator> Wrapper<T> {
+    const NOTHING: Option<T::Item> = None;
+}
+
+fn main() {
+    assert_eq!(Plane::ORIGIN, (0, 0));
+    assert_eq!(Wrapper::<std::vec::IntoIter<u8>>::NOTHING, None);
+}
@@ -0,0 +1,37 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that the values of associated consts are checked with the bounds
+// of their trait or impl in scope.
+
+#![feature(associated_consts)]
+
+trait Sides {
+    const SIDES: usize;
+    const DOUBLE: usize = Self::SIDES * 2;
+}
+
+struct Wrap<T>(T);
+
+impl<T: Sides> Sides for Wrap<T> {
+    const SIDES: usize = 1;
+    const DOUBLE: usize = T::SIDES;
+}
+
+struct Square;
+
+impl Sides for Square {
+    const SIDES: usize = 4;
+}
+
+fn main() {
+    assert_eq!(Square::SIDES, 4);
+    assert_eq!(<Wrap<Square>>::SIDES, 1);
+}
@@ -0,0 +1,82 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that default methods of a generic trait see the associated consts
+// and types of the impl selected by the trait's own type parameters.
+
+#![feature(associated_consts)]
+
+trait Shape<T> {
+    const SIDES: usize;
+    const SCALE: T;
+    type Unit: Default;
+
+    fn sides(&self) -> usize {
+        Self::SIDES
+    }
+
+    fn scale(&self) -> T {
+        Self::SCALE
+    }
+
+    fn sides_with<U>(&self, _: U) -> usize {
+        <Self as Shape<T>>::SIDES * 2
+    }
+
+    fn unit(&self) -> Self::Unit {
+        Default::default()
+    }
+}
+
+struct Square;
+
+impl Shape<u8> for Square {
+    const SIDES: usize = 4;
+    const SCALE: u8 = 2;
+    type Unit = u32;
+}
+
+struct Triangle;
+
+impl Shape<i64> for Triangle {
+    const SIDES: usize = 3;
+    const SCALE: i64 = -1;
+    type Unit = bool;
+}
+
+impl Shape<u8> for Triangle {
+    const SIDES: usize = 30;
+    const SCALE: u8 = 7;
+    type Unit = ();
+}
+
+fn total_sides<T, S: Shape<T>>(s: &S) -> usize {
+    s.sides() + S::SIDES
+}
+
+fn main() {
+    assert_eq!(Square.sides(), 4);
+    assert_eq!(Square.scale(), 2);
+    assert_eq!(Square.sides_with("x"), 8);
+    assert_eq!(Square.unit(), 0);
+
+    assert_eq!(<Triangle as Shape<i64>>::sides(&Triangle), 3);
+    assert_eq!(<Triangle as Shape<i64>>::scale(&Triangle), -1);
+    assert_eq!(<Triangle as Shape<i64>>::sides_with(&Triangle, 1.0), 6);
+    assert_eq!(<Triangle as Shape<i64>>::unit(&Triangle), false);
+
+    assert_eq!(<Triangle as Shape<u8>>::sides(&Triangle), 30);
+    assert_eq!(<Triangle as Shape<u8>>::scale(&Triangle), 7);
+    assert_eq!(<Triangle as Shape<u8>>::sides_with(&Triangle, ()), 60);
+
+    assert_eq!(total_sides::<u8, _>(&Square), 8);
+    assert_eq!(total_sides::<i64, _>(&Triangle), 6);
+    assert_eq!(total_sides::<u8, _>(&Triangle), 60);
+}
@@ -0,0 +1,50 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Associated type defaults can name the trait's other associated types,
+// whichever order they are declared in.
+
+#![feature(associated_type_defaults)]
+
+trait Collection {
+    type Many = Vec<Self::One>;
+    type One;
+    type Pair = (Self::One, Self::One);
+
+    fn one(&self) -> Self::One;
+}
+
+impl Collection for u8 {
+    type One = u8;
+    fn one(&self) -> u8 { *self }
+}
+
+impl Collection for bool {
+    type One = char;
+    type Many = String;
+    fn one(&self) -> char { if *self { 'y' } else { 'n' } }
+}
+
+fn many<T: Collection>(t: &T, n: usize) -> Vec<T::One> {
+    (0..n).map(|_| t.one()).collect()
+}
+
+fn main() {
+    let bytes: <u8 as Collection>::Many = many(&3u8, 2);
+    assert_eq!(bytes, vec![3, 3]);
+
+    let pair: <u8 as Collection>::Pair = (1, 2);
+    assert_eq!(pair, (1u8, 2u8));
+
+    let chars: <bool as Collection>::Many = many(&true, 3).into_iter().collect();
+    assert_eq!(chars, "yyy");
+    let pair: <bool as Collection>::Pair = ('a', 'b');
+    assert_eq!(pair, ('a', 'b'));
+}
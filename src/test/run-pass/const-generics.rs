@@ -0,0 +1,58 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// aux-build:const-generics-decl.rs
+
+// Test that const parameters can be given arguments and used as array
+// lengths, locally and in other crates.
+
+#![feature(const_generics, slice_patterns)]
+
+extern crate const_generics_decl;
+
+use std::mem;
+
+struct Grid<const W: usize, const H: usize> {
+    cells: [[bool; W]; H],
+}
+
+fn count<const N: usize>(cells: &[bool; N]) -> usize {
+    cells.iter().filter(|&&c| c).count()
+}
+
+fn row<const W: usize, const H: usize>(grid: &Grid<{W}, {H}>) -> &[bool; W] {
+    &grid.cells[0]
+}
+
+fn first_ptr<const N: usize>(cells: &[u8; N]) -> *const u8 {
+    cells as *const u8
+}
+
+fn len<const N: usize>(cells: [u8; N]) -> usize {
+    match cells {
+        [rest..] => rest.len(),
+    }
+}
+
+fn main() {
+    let grid = Grid::<3, 2> { cells: [[true, false, true], [false, false, true]] };
+    assert_eq!(count::<3>(&grid.cells[1]), 1);
+    assert_eq!(count::<3>(row::<3, 2>(&grid)), 2);
+    assert_eq!(mem::size_of::<Grid<3, 2>>(), 6);
+    assert_eq!(mem::size_of::<Grid<4, 4>>(), 16);
+
+    let bytes = [5u8, 6, 7];
+    assert_eq!(unsafe { *first_ptr::<3>(&bytes) }, 5);
+    assert_eq!(len::<3>(bytes), 3);
+
+    let buffer = const_generics_decl::Buffer::<u8, 4>::filled(7u8);
+    assert_eq!(buffer.data.len(), 4);
+    assert_eq!(const_generics_decl::first::<u8, 4>(&buffer.data), 7);
+}
@@ -0,0 +1,41 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Traits marked `#[default_bound]` bound every type parameter, like `Sized`,
+// unless the parameter opts out with `?Trait`.
+
+#![feature(default_bounds, optin_builtin_traits)]
+
+#[default_bound]
+trait Movable {}
+
+impl Movable for .. {}
+
+struct Pinned(u32);
+
+impl !Movable for Pinned {}
+
+struct Wrapper<T: ?Movable>(T);
+
+fn peek<T: ?Movable>(x: &T) -> &T { x }
+
+fn take<T>(x: T) -> T { x }
+
+fn both<T: ?Sized + ?Movable>(x: &T) -> &T { peek(x) }
+
+fn main() {
+    let pinned = Pinned(7);
+    assert_eq!(peek(&pinned).0, 7);
+    assert_eq!(both(&pinned).0, 7);
+    assert_eq!(both("str"), "str");
+    assert_eq!(take(5), 5);
+    let wrapper = Wrapper(Pinned(3));
+    assert_eq!((wrapper.0).0, 3);
+}
@@ -0,0 +1,34 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that the default type parameter fallback also applies to type
+// variables created inside closures, and that the default it picks is
+// visible to the rest of the function.
+
+#![feature(default_type_parameter_fallback)]
+
+use std::mem;
+
+fn size<T = u16>() -> usize {
+    mem::size_of::<T>()
+}
+
+fn make<T: Default = u32>() -> T {
+    T::default()
+}
+
+fn main() {
+    let f = || size();
+    assert_eq!(f(), 2);
+
+    let g = || make();
+    let x = g();
+    assert_eq!(mem::size_of_val(&x), 4);
+}
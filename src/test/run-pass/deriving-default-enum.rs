@@ -0,0 +1,31 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(derive_default_enum)]
+
+#[derive(Default, PartialEq, Debug)]
+enum Foo {
+    Alpha(u32),
+    #[default]
+    Beta,
+    Gamma { x: u8 },
+}
+
+#[derive(Default, PartialEq, Debug)]
+enum Generic<T> {
+    #[default]
+    Empty,
+    Full(T),
+}
+
+fn main() {
+    assert_eq!(Foo::default(), Foo::Beta);
+    assert_eq!(Generic::<String>::default(), Generic::Empty);
+}
@@ -0,0 +1,83 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Discriminants can be given by constants, associated constants and calls
+// to const fns, including those of the enum itself, trait defaults, and
+// impls told apart by the type arguments in the path.
+
+#![feature(const_fn, associated_consts)]
+
+const BASE: isize = 10;
+
+const fn double(x: isize) -> isize {
+    x * 2
+}
+
+struct Offsets;
+
+impl Offsets {
+    const SMALL: isize = 100;
+
+    const fn large() -> isize {
+        1000
+    }
+}
+
+trait Code {
+    const CODE: isize;
+}
+
+impl Code for Offsets {
+    const CODE: isize = 7;
+}
+
+trait Defaulted {
+    const DEFAULTED: isize = 3;
+}
+
+impl Defaulted for Offsets {}
+
+struct Wrap<T>(T);
+
+impl Wrap<u8> {
+    const SIZE: isize = 8;
+}
+
+impl Wrap<u16> {
+    const SIZE: isize = 16;
+}
+
+enum E {
+    A = BASE,
+    B = double(BASE),
+    C = Offsets::SMALL,
+    D = Offsets::large(),
+    F = Offsets::CODE,
+    G = E::START + 1,
+    H,
+    J = Offsets::DEFAULTED,
+    K = Wrap::<u16>::SIZE,
+}
+
+impl E {
+    const START: isize = 50;
+}
+
+fn main() {
+    assert_eq!(E::A as isize, 10);
+    assert_eq!(E::B as isize, 20);
+    assert_eq!(E::C as isize, 100);
+    assert_eq!(E::D as isize, 1000);
+    assert_eq!(E::F as isize, 7);
+    assert_eq!(E::G as isize, 51);
+    assert_eq!(E::H as isize, 52);
+    assert_eq!(E::J as isize, 3);
+    assert_eq!(E::K as isize, 16);
+}
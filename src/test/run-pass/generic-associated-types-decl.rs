@@ -0,0 +1,40 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that generic associated types can be declared, with their own
+// lifetimes, type parameters and where clauses, alongside ordinary items.
+
+#![feature(generic_associated_types)]
+
+trait Streaming<T> {
+    type Item<'a>;
+    type Pair<U> where U: Clone;
+    type Both<'a, U: 'a> where T: 'a;
+
+    fn count(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.count() == 0
+    }
+}
+
+trait Plain {
+    type Item;
+    fn get(&self) -> Self::Item;
+}
+
+impl Plain for u8 {
+    type Item = u8;
+    fn get(&self) -> u8 { *self }
+}
+
+fn main() {
+    assert_eq!(3u8.get(), 3);
+}
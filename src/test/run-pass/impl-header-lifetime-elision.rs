@@ -0,0 +1,57 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that lifetimes may be elided in impl headers and in the types
+// of associated constants.
+
+#![feature(associated_consts)]
+
+trait Describe {
+    const KIND: &str;
+    fn describe(&self) -> String;
+}
+
+struct Foo<'a> {
+    name: &'a str,
+}
+
+impl Describe for &Foo {
+    const KIND: &str = "foo";
+    fn describe(&self) -> String {
+        format!("{} {}", Self::KIND, self.name)
+    }
+}
+
+impl Describe for (&str, &str) {
+    const KIND: &str = "pair";
+    fn describe(&self) -> String {
+        format!("{} {} {}", Self::KIND, self.0, self.1)
+    }
+}
+
+impl PartialEq<&str> for Foo {
+    fn eq(&self, other: &&str) -> bool {
+        self.name == *other
+    }
+}
+
+struct Bar;
+
+impl Bar {
+    const GREETING: &str = "hello";
+}
+
+fn main() {
+    let foo = Foo { name: "a" };
+    assert_eq!((&foo).describe(), "foo a");
+    assert_eq!(("b", "c").describe(), "pair b c");
+    assert!(foo == "a");
+    assert_eq!(Bar::GREETING, "hello");
+}
@@ -0,0 +1,27 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// aux-build:impl-trait-lib.rs
+
+// Test that `impl Trait` return types work across crates.
+
+#![feature(conservative_impl_trait)]
+
+extern crate impl_trait_lib;
+
+use impl_trait_lib::{fizzbuzz, Wrapper};
+
+fn main() {
+    let words = fizzbuzz(5).collect::<Vec<_>>();
+    assert_eq!(words, ["1", "2", "Fizz", "4", "Buzz"]);
+
+    let wrapper = Wrapper(vec!['a', 'b']);
+    assert_eq!(wrapper.items().collect::<String>(), "ab");
+}
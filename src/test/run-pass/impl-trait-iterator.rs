@@ -0,0 +1,50 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that functions and inherent methods can return `impl Trait`, and
+// that callers can use the concrete type only through those bounds.
+
+#![feature(conservative_impl_trait)]
+
+use std::fmt::Debug;
+
+fn evens(limit: u32) -> impl Iterator<Item=u32> {
+    (0..limit).filter(|x| x % 2 == 0)
+}
+
+fn boxed_debug<T: Debug + 'static>(x: T) -> impl Debug {
+    Box::new(x)
+}
+
+fn adder(n: u32) -> impl Fn(u32) -> u32 {
+    move |x| x + n
+}
+
+fn nested() -> impl Iterator<Item=impl Debug> {
+    vec![1, 2, 3].into_iter()
+}
+
+struct Counter {
+    count: u32,
+}
+
+impl Counter {
+    fn counts(&self) -> impl Iterator<Item=u32> {
+        0..self.count
+    }
+}
+
+fn main() {
+    assert_eq!(evens(7).collect::<Vec<_>>(), [0, 2, 4, 6]);
+    assert_eq!(format!("{:?}", boxed_debug("hi")), "\"hi\"");
+    assert_eq!(adder(2)(40), 42);
+    assert_eq!(nested().map(|x| format!("{:?}", x)).collect::<Vec<_>>(), ["1", "2", "3"]);
+    assert_eq!(Counter { count: 3 }.counts().sum::<u32>(), 3);
+}
@@ -0,0 +1,109 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(match_default_bindings)]
+
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+enum Shape {
+    Circle(Point, u32),
+    Rect { top_left: Point, bottom_right: Point },
+}
+
+fn radius(shape: &Shape) -> Option<u32> {
+    match shape {
+        Shape::Circle(_, r) => Some(*r),
+        Shape::Rect { .. } => None,
+    }
+}
+
+fn width(shape: &Shape) -> i32 {
+    match shape {
+        Shape::Circle(_, r) => 2 * *r as i32,
+        Shape::Rect { top_left: Point { x: left, .. }, bottom_right: Point { x: right, .. } } => {
+            right - left
+        }
+    }
+}
+
+fn bump(opt: &mut Option<i32>) {
+    if let Some(x) = opt {
+        *x += 1;
+    }
+}
+
+fn first(pair: &(String, String)) -> &str {
+    let (a, _) = pair;
+    a
+}
+
+fn sum(pairs: &[(i32, i32)]) -> i32 {
+    let mut total = 0;
+    for (a, b) in pairs.iter() {
+        total += *a + *b;
+    }
+    total
+}
+
+fn nested(x: &&Option<(u8, &'static str)>) -> u8 {
+    match x {
+        Some((n, _)) => *n,
+        None => 0,
+    }
+}
+
+fn mixed(x: &Option<u8>) -> u8 {
+    match x {
+        &None => 0,
+        Some(n) => *n,
+    }
+}
+
+fn small(x: &i32) -> bool {
+    match x {
+        0 => true,
+        1...5 => true,
+        _ => false,
+    }
+}
+
+fn main() {
+    let circle = Shape::Circle(Point { x: 0, y: 0 }, 3);
+    let rect = Shape::Rect {
+        top_left: Point { x: 1, y: 2 },
+        bottom_right: Point { x: 5, y: 7 },
+    };
+    assert_eq!(radius(&circle), Some(3));
+    assert_eq!(radius(&rect), None);
+    assert_eq!(width(&circle), 6);
+    assert_eq!(width(&rect), 4);
+
+    let mut opt = Some(1);
+    bump(&mut opt);
+    assert_eq!(opt, Some(2));
+
+    let pair = ("a".to_string(), "b".to_string());
+    assert_eq!(first(&pair), "a");
+
+    assert_eq!(sum(&[(1, 2), (3, 4)]), 10);
+    assert_eq!(nested(&&Some((7, "seven"))), 7);
+    assert_eq!(nested(&&None), 0);
+    assert_eq!(mixed(&Some(4)), 4);
+    assert_eq!(mixed(&None), 0);
+    assert!(small(&3));
+    assert!(!small(&6));
+
+    if let Shape::Rect { top_left, .. } = &rect {
+        assert_eq!((top_left.x, top_left.y), (1, 2));
+    }
+}
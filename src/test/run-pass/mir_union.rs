@@ -0,0 +1,76 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(rustc_attrs, untagged_unions)]
+
+union W {
+    a: u8,
+    b: u64,
+}
+
+impl Clone for W {
+    fn clone(&self) -> W { *self }
+}
+impl Copy for W {}
+
+#[repr(C)]
+union Y {
+    f1: u16,
+    f2: [u8; 4],
+}
+
+#[rustc_mir]
+fn make_w(b: u64) -> W {
+    W { b: b }
+}
+
+#[rustc_mir]
+fn read_a(w: W) -> u8 {
+    unsafe { w.a }
+}
+
+#[rustc_mir]
+fn set_a(mut w: W, a: u8) -> W {
+    w.a = a;
+    w
+}
+
+#[rustc_mir]
+fn read_b(w: W) -> u64 {
+    unsafe { w.b }
+}
+
+#[rustc_mir]
+fn match_a(w: W) -> u8 {
+    unsafe {
+        match w {
+            W { a: 0 } => 100,
+            W { a } => a,
+        }
+    }
+}
+
+#[rustc_mir]
+fn y_f1() -> u16 {
+    let y = Y { f2: [1, 2, 3, 4] };
+    unsafe { y.f1 }
+}
+
+fn main() {
+    let w = make_w(0);
+    assert_eq!(read_a(w), 0);
+    assert_eq!(read_b(w), 0);
+    let w = set_a(w, 1);
+    assert_eq!(read_a(w), 1);
+    assert_eq!(read_b(w).to_le(), 1);
+    assert_eq!(match_a(make_w(0)), 100);
+    assert_eq!(match_a(w), 1);
+    assert_eq!(y_f1(), u16::from_le(0x0201));
+}
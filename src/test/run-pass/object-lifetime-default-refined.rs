@@ -0,0 +1,44 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test the refined object lifetime defaults: a `'static` bound wins over
+// any other bound, and in fn bodies an ambiguous default is inferred
+// instead of requiring an explicit bound.
+
+trait Test {
+    fn foo(&self) -> u32 { 22 }
+}
+
+impl Test for u32 {}
+
+struct Ref2<'a,'b:'a,T:'a+'b+?Sized> {
+    r: &'a &'b T
+}
+
+struct Static<'a,T:'a+'static+?Sized> {
+    r: &'a T
+}
+
+// The default for `Static<Test>` is `'static`, so these types are the same.
+fn a<'a>(t: Static<'a,Test>) -> Static<'a,Test+'static> {
+    t
+}
+
+fn b(t: &Test) -> u32 {
+    let r: Ref2<Test> = Ref2 { r: &t };
+    r.r.foo()
+}
+
+fn main() {
+    static X: u32 = 1;
+    let s = Static { r: &X as &Test };
+    assert_eq!(a(s).r.foo(), 22);
+    assert_eq!(b(&X), 22);
+}
@@ -0,0 +1,64 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(self_in_typedefs)]
+
+use std::mem;
+
+enum List<T> {
+    Nil,
+    Cons(T, Box<Self>),
+}
+
+struct Tree<T> {
+    value: T,
+    children: Vec<Self>,
+}
+
+impl<T: Copy> List<T> {
+    fn sum(&self) -> T where T: ::std::ops::Add<Output = T> + Default {
+        match *self {
+            List::Nil => T::default(),
+            List::Cons(x, ref rest) => x + rest.sum(),
+        }
+    }
+}
+
+impl<T> Tree<T> {
+    fn count(&self) -> usize {
+        self.children.iter().fold(1, |n, c| n + c.count())
+    }
+}
+
+struct Node {
+    next: Option<Box<Self>>,
+}
+
+impl Node {
+    fn new() -> Self {
+        // `Self` in a struct nested in an impl names the nested struct.
+        struct Wrapper(Option<Box<Self>>);
+        let _ = Wrapper(Some(Box::new(Wrapper(None))));
+        Node { next: None }
+    }
+}
+
+fn main() {
+    let list = List::Cons(1, Box::new(List::Cons(2, Box::new(List::Nil))));
+    assert_eq!(list.sum(), 3);
+
+    let tree = Tree { value: 0, children: vec![Tree { value: 1, children: vec![] }] };
+    assert_eq!(tree.value, 0);
+    assert_eq!(tree.count(), 2);
+
+    let node = Node { next: Some(Box::new(Node::new())) };
+    assert!(node.next.unwrap().next.is_none());
+    assert_eq!(mem::size_of::<Node>(), mem::size_of::<Box<Node>>());
+}
@@ -0,0 +1,40 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(associated_consts, specialization)]
+
+// A specializing impl inherits the `default const`s it doesn't override from
+// the impl it specializes, not the trait's defaults.
+
+trait Tr {
+    const C: u32 = 1;
+}
+
+impl<T> Tr for T {
+    default const C: u32 = 2;
+}
+
+impl Tr for u8 {}
+
+impl Tr for u16 {
+    const C: u32 = 3;
+}
+
+const INHERITED: u32 = <u8 as Tr>::C;
+
+fn main() {
+    assert_eq!(INHERITED, 2);
+    assert_eq!(<u8 as Tr>::C, 2);
+    assert_eq!(<u16 as Tr>::C, 3);
+    assert_eq!(<char as Tr>::C, 2);
+
+    let array = [0u8; <u8 as Tr>::C as usize];
+    assert_eq!(array.len(), 2);
+}
@@ -0,0 +1,73 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(specialization)]
+
+// Selection picks the most specific impl, and a specializing impl inherits
+// the items it doesn't override from the impls it specializes.
+
+trait Describe {
+    fn name(&self) -> &'static str;
+    fn size(&self) -> usize;
+    fn kind(&self) -> &'static str { "thing" }
+}
+
+impl<T> Describe for T {
+    default fn name(&self) -> &'static str { "anything" }
+    default fn size(&self) -> usize { 0 }
+}
+
+impl<T: Clone> Describe for Vec<T> {
+    default fn name(&self) -> &'static str { "vec" }
+    fn size(&self) -> usize { self.len() }
+}
+
+impl Describe for Vec<u8> {
+    fn name(&self) -> &'static str { "bytes" }
+}
+
+impl Describe for i32 {
+    fn name(&self) -> &'static str { "i32" }
+    fn kind(&self) -> &'static str { "number" }
+}
+
+trait Assoc {
+    type Out;
+    fn tag(&self) -> u8;
+}
+
+impl<T> Assoc for T {
+    default type Out = ();
+    default fn tag(&self) -> u8 { 0 }
+}
+
+impl Assoc for u16 {
+    type Out = u16;
+    fn tag(&self) -> u8 { 1 }
+}
+
+fn describe<T: Describe>(t: &T) -> (&'static str, usize, &'static str) {
+    (t.name(), t.size(), t.kind())
+}
+
+fn main() {
+    assert_eq!(describe(&'a'), ("anything", 0, "thing"));
+    assert_eq!(describe(&1i32), ("i32", 0, "number"));
+    assert_eq!(describe(&vec!['a', 'b']), ("vec", 2, "thing"));
+    assert_eq!(describe(&vec![1u8, 2, 3]), ("bytes", 3, "thing"));
+    assert_eq!(vec![1u8].size(), 1);
+
+    let x: <u16 as Assoc>::Out = 6u16;
+    assert_eq!(x, 6);
+    let y: <char as Assoc>::Out = ();
+    assert_eq!(y, ());
+    assert_eq!(5u16.tag(), 1);
+    assert_eq!('a'.tag(), 0);
+}
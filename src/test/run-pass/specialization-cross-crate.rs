@@ -0,0 +1,34 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// aux-build:specialization_cross_crate.rs
+
+#![feature(specialization)]
+
+extern crate specialization_cross_crate;
+
+use specialization_cross_crate::Greet;
+
+struct Local;
+
+impl Greet for Local {
+    fn greet(&self) -> String { "hi".to_string() }
+    fn loud(&self) -> bool { true }
+}
+
+fn greet<T: Greet + ?Sized>(t: &T) -> (String, bool) {
+    (t.greet(), t.loud())
+}
+
+fn main() {
+    assert_eq!(greet(&0u8), ("hello".to_string(), false));
+    assert_eq!(greet("world"), ("hello, world".to_string(), false));
+    assert_eq!(greet(&Local), ("hi".to_string(), true));
+}
@@ -0,0 +1,68 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(track_caller, core_intrinsics)]
+
+use std::intrinsics::caller_location;
+
+type Location = &'static (&'static str, u32, u32);
+
+#[track_caller]
+fn location() -> Location {
+    unsafe { caller_location() }
+}
+
+#[track_caller]
+fn nested() -> Location {
+    location()
+}
+
+#[track_caller]
+fn generic<T>(_: T) -> Location {
+    location()
+}
+
+struct Foo;
+
+impl Foo {
+    #[track_caller]
+    fn location(&self) -> Location {
+        unsafe { caller_location() }
+    }
+}
+
+// `column!()` is zero-based, while caller locations count columns from one,
+// so each call is lined up under a `column!()` on the line above it.
+fn check(loc: Location, line: u32, column: u32) {
+    let &(file, l, c) = loc;
+    assert!(file.ends_with("track-caller.rs"));
+    assert_eq!((l, c), (line, column + 1));
+}
+
+fn main() {
+    let (column, loc) = (column!(),
+                         location());
+    check(loc, line!() - 1, column);
+
+    let (column, loc) = (column!(),
+                         nested());
+    check(loc, line!() - 1, column);
+
+    let (column, loc) = (column!(),
+                         generic("a"));
+    check(loc, line!() - 1, column);
+
+    let (column, loc) = (column!(),
+                         Foo.location());
+    check(loc, line!() - 1, column);
+
+    let &(_, line, _) = unsafe { caller_location() };
+    assert_eq!(line, line!());
+}
@@ -0,0 +1,33 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(trait_alias)]
+
+use std::fmt::Debug;
+
+trait Show = Debug;
+trait SendShow = Show + Send;
+trait Pair<T> = PartialEq<T> + Clone;
+
+fn show<T: SendShow>(t: T) -> String {
+    format!("{:?}", t)
+}
+
+fn same<T: Pair<u32>>(t: T, u: u32) -> bool {
+    t.clone() == u
+}
+
+fn main() {
+    assert_eq!(show(3), "3");
+    assert!(same(7u32, 7));
+
+    let b: Box<SendShow> = Box::new("hi");
+    assert_eq!(format!("{:?}", b), "\"hi\"");
+}
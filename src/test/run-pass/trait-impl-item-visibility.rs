@@ -0,0 +1,73 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// aux-build:trait_impl_visibility.rs
+
+// The items of a trait impl are as visible as the trait, wherever the impl
+// itself lives.
+
+extern crate trait_impl_visibility as other;
+
+use other::{S, Shown};
+
+mod a {
+    // A private trait with impls in this module...
+    trait Priv {
+        fn get(&self) -> u8;
+    }
+
+    impl Priv for u8 {
+        fn get(&self) -> u8 { *self }
+    }
+
+    pub fn use_priv() -> u8 {
+        3u8.get() + <u8 as Priv>::get(&4) + super::b::use_priv_from_child()
+    }
+
+    pub mod b {
+        // ...and in a child module, which can still see it.
+        impl super::Priv for i8 {
+            fn get(&self) -> u8 { 5 }
+        }
+
+        pub fn use_priv_from_child() -> u8 {
+            use super::Priv;
+            1i8.get()
+        }
+    }
+}
+
+mod c {
+    pub trait Pub {
+        type Assoc;
+        fn get(&self) -> Self::Assoc;
+    }
+
+    // A public trait implemented in a private module.
+    mod imp {
+        impl super::Pub for u16 {
+            type Assoc = u16;
+            fn get(&self) -> u16 { *self * 2 }
+        }
+    }
+}
+
+fn main() {
+    assert_eq!(a::use_priv(), 12);
+
+    use c::Pub;
+    let x: <u16 as Pub>::Assoc = 21u16.get();
+    assert_eq!(x, 42);
+    assert_eq!(<u16 as c::Pub>::get(&1), 2);
+
+    assert_eq!(S.shown(), 2);
+    assert_eq!(<S as Shown>::shown(&S), 2);
+    assert_eq!(other::call_hidden(&S), 2);
+}
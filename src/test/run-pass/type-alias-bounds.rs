@@ -0,0 +1,41 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(type_alias_bounds)]
+
+use std::ops::Add;
+
+trait Float: Copy + Add<Output = Self> {}
+
+impl Float for f32 {}
+impl Float for f64 {}
+
+type Meters<T: Float> = T;
+
+type Pair<T: Float> = (Meters<T>, Meters<T>);
+
+struct Route<T: Float> {
+    legs: Vec<Meters<T>>,
+}
+
+fn total<T: Float>(route: &Route<T>, start: Meters<T>) -> Meters<T> {
+    let mut sum: Meters<T> = start;
+    for &leg in &route.legs {
+        sum = sum + leg;
+    }
+    sum
+}
+
+fn main() {
+    let route = Route { legs: vec![1.5f64, 2.5] };
+    assert_eq!(total(&route, 0.0), 4.0);
+    let pair: Pair<f32> = (1.0, 2.0);
+    assert_eq!(pair.0 + pair.1, 3.0);
+}
@@ -0,0 +1,77 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(untagged_unions)]
+
+use std::mem::{size_of, align_of};
+
+union U {
+    a: u8,
+}
+
+union W {
+    a: u8,
+    b: u64,
+}
+
+impl Clone for W {
+    fn clone(&self) -> W { *self }
+}
+impl Copy for W {}
+
+#[repr(C)]
+union Y {
+    f1: u16,
+    f2: [u8; 4],
+}
+
+union G<T: Copy> {
+    a: T,
+    b: u8,
+}
+
+static S: W = W { a: 3 };
+
+fn main() {
+    assert_eq!(size_of::<U>(), 1);
+    assert_eq!(align_of::<U>(), 1);
+    assert_eq!(size_of::<W>(), 8);
+    assert_eq!(align_of::<W>(), align_of::<u64>());
+    assert_eq!(size_of::<Y>(), 4);
+    assert_eq!(align_of::<Y>(), 2);
+    assert_eq!(size_of::<G<u32>>(), 4);
+    assert_eq!(size_of::<G<()>>(), 1);
+
+    // `union` is only a keyword in item position.
+    let union = 10;
+    assert_eq!(union, 10);
+
+    unsafe {
+        let u = U { a: 10 };
+        assert_eq!(u.a, 10);
+
+        let mut w = W { b: 0 };
+        assert_eq!(w.a, 0);
+        assert_eq!(w.b, 0);
+        w.a = 1;
+        assert_eq!(w.a, 1);
+        assert_eq!(w.b.to_le(), 1);
+
+        let w2 = w;
+        assert_eq!(w2.a, 1);
+        assert_eq!(S.a, 3);
+
+        let y = Y { f2: [1, 2, 3, 4] };
+        assert_eq!(y.f1, u16::from_le(0x0201));
+
+        let g = G { a: 7u32 };
+        assert_eq!(g.a, 7);
+    }
+}
@@ -0,0 +1,29 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// aux-build:union-lib.rs
+
+extern crate union_lib;
+
+use std::mem::size_of;
+use union_lib::U;
+
+fn main() {
+    assert_eq!(size_of::<U>(), 2);
+
+    unsafe {
+        let u = U { b: 0x0101 };
+        assert_eq!(u.a, 1);
+        assert_eq!(u.b(), 0x0101);
+
+        let u = U::new(3);
+        assert_eq!(u.a, 3);
+    }
+}
@@ -0,0 +1,39 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(variant_where_clauses)]
+
+use std::fmt::Debug;
+
+enum Value<T> {
+    Copied(T) where T: Copy,
+    Shown { value: T } where T: Debug + Clone, T: 'static,
+    Nothing,
+}
+
+fn copied<T: Copy>(t: T) -> Value<T> {
+    Value::Copied(t)
+}
+
+fn main() {
+    let v = copied(3);
+    match v {
+        Value::Copied(n) => assert_eq!(n, 3),
+        _ => panic!(),
+    }
+
+    let s = Value::Shown { value: "hi".to_string() };
+    if let Value::Shown { ref value } = s {
+        assert_eq!(format!("{:?}", value), "\"hi\"");
+    }
+
+    let n: Value<u8> = Value::Nothing;
+    assert!(match n { Value::Nothing => true, _ => false });
+}
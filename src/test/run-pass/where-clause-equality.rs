@@ -0,0 +1,48 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test equality constraints in the `where` clauses of functions, traits and
+// impls, with the associated type on either side.
+
+#![feature(where_clause_equality)]
+
+trait Shape {
+    type Unit;
+    fn area(&self) -> Self::Unit;
+}
+
+struct Square(u32);
+
+impl Shape for Square {
+    type Unit = u32;
+    fn area(&self) -> u32 { self.0 * self.0 }
+}
+
+fn total<S>(shapes: &[S]) -> u32 where S: Shape, S::Unit == u32 {
+    shapes.iter().map(|s| s.area()).fold(0, |a, b| a + b)
+}
+
+trait Doubled where Self: Shape, u32 == <Self as Shape>::Unit {
+    fn doubled(&self) -> u32 { self.area() * 2 }
+}
+
+impl Doubled for Square {}
+
+struct Wrapper<S>(S);
+
+impl<S> Wrapper<S> where S: Shape, S::Unit == u32 {
+    fn area_plus_one(&self) -> u32 { self.0.area() + 1 }
+}
+
+fn main() {
+    assert_eq!(total(&[Square(2), Square(3)]), 13);
+    assert_eq!(Square(3).doubled(), 18);
+    assert_eq!(Wrapper(Square(4)).area_plus_one(), 17);
+}